@@ -1,4 +1,4 @@
-use poem::{endpoint::Files, listener::TcpListener, Route, Server};
+use poem::{endpoint::StaticFiles, listener::TcpListener, Route, Server};
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
@@ -9,7 +9,7 @@ async fn main() -> Result<(), std::io::Error> {
 
     let app = Route::new().nest(
         "/",
-        Files::new("./examples/poem/static-files/files").show_files_listing(),
+        StaticFiles::new("./examples/poem/static-files/files").show_files_listing(),
     );
     let server = Server::new(TcpListener::bind("127.0.0.1:3000")).await?;
     server.run(app).await
@@ -11,10 +11,17 @@ mod utils;
 
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, AttributeArgs, FnArg, ItemFn, Member, Meta, NestedMeta, Result};
+use syn::{
+    parse_macro_input, AttributeArgs, Error, FnArg, GenericParam, ItemFn, Member, Meta, NestedMeta,
+    Result,
+};
 
 /// Wrap an asynchronous function as an `Endpoint`.
 ///
+/// Each parameter of the function is extracted from the request by
+/// `FromRequest`, and the return value is converted into the response by
+/// `IntoResponse`.
+///
 /// # Example
 ///
 /// ```ignore
@@ -22,6 +29,17 @@ use syn::{parse_macro_input, AttributeArgs, FnArg, ItemFn, Member, Meta, NestedM
 /// async fn example() {
 /// }
 /// ```
+///
+/// A generic function is wrapped as a generic struct, which can be created
+/// with `Default::default`.
+///
+/// ```ignore
+/// #[handler]
+/// async fn example<T: Send + Sync + 'static>(data: Data<&T>) {
+/// }
+///
+/// let ep = example::<i32>::default();
+/// ```
 #[proc_macro_attribute]
 pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
     let args: AttributeArgs = parse_macro_input!(args as AttributeArgs);
@@ -56,9 +74,29 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
         None
     };
 
+    let mut type_params = Vec::new();
+    for param in &item_fn.sig.generics.params {
+        match param {
+            GenericParam::Type(param) => type_params.push(&param.ident),
+            _ => {
+                return Err(Error::new_spanned(
+                    param,
+                    "Only the type parameters are allowed in the handler.",
+                ))
+            }
+        }
+    }
+    let (impl_generics, type_generics, where_clause) = item_fn.sig.generics.split_for_impl();
+
     let mut extractors = Vec::new();
     let mut args = Vec::new();
     for (idx, input) in item_fn.sig.inputs.clone().into_iter().enumerate() {
+        if let FnArg::Receiver(receiver) = &input {
+            return Err(Error::new_spanned(
+                receiver,
+                "The handler cannot have a `self` receiver.",
+            ));
+        }
         if let FnArg::Typed(pat) = input {
             let ty = &pat.ty;
             let id = quote::format_ident!("p{}", idx);
@@ -72,13 +110,37 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
         }
     }
 
+    let (def_struct, call_fn) = if type_params.is_empty() {
+        (
+            quote! {
+                #(#docs)*
+                #[allow(non_camel_case_types)]
+                #vis struct #ident;
+            },
+            quote!(#ident),
+        )
+    } else {
+        (
+            quote! {
+                #(#docs)*
+                #[allow(non_camel_case_types)]
+                #vis struct #ident #impl_generics (::std::marker::PhantomData<fn() -> (#(#type_params,)*)>) #where_clause;
+
+                impl #impl_generics ::std::default::Default for #ident #type_generics #where_clause {
+                    fn default() -> Self {
+                        Self(::std::marker::PhantomData)
+                    }
+                }
+            },
+            quote!(#ident::<#(#type_params),*>),
+        )
+    };
+
     let expanded = quote! {
-        #(#docs)*
-        #[allow(non_camel_case_types)]
-        #vis struct #ident;
+        #def_struct
 
         #[#crate_name::async_trait]
-        impl #crate_name::Endpoint for #ident {
+        impl #impl_generics #crate_name::Endpoint for #ident #type_generics #where_clause {
             type Output = #crate_name::Response;
 
             #[allow(unused_mut)]
@@ -86,7 +148,7 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
                 let (req, mut body) = req.split();
                 #(#extractors)*
                 #item_fn
-                #crate_name::IntoResponse::into_response(#ident(#(#args),*)#call_await)
+                #crate_name::IntoResponse::into_response(#call_fn(#(#args),*)#call_await)
             }
         }
     };
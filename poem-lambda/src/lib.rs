@@ -10,8 +10,14 @@
 use std::{convert::Infallible, io::ErrorKind, ops::Deref, sync::Arc};
 
 pub use lambda_http::lambda_runtime::Error;
-use lambda_http::{handler, lambda_runtime, Body as LambdaBody, Request as LambdaRequest};
-use poem::{Body, Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, RequestBody};
+use lambda_http::{
+    handler, lambda_runtime, Body as LambdaBody, Request as LambdaRequest, RequestExt,
+    Response as LambdaResponse, StrMap,
+};
+use poem::{
+    http::Uri, Body, Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, RequestBody,
+    Response,
+};
 
 /// The Lambda function execution context.
 ///
@@ -40,6 +46,10 @@ impl Deref for Context {
 
 /// Starts the AWS Lambda runtime.
 ///
+/// The events of API Gateway REST APIs, HTTP APIs and Application Load
+/// Balancers are converted to [`poem::Request`], so the same endpoint, such as
+/// a `Route` or an `OpenApiService`, can run in a container or in Lambda.
+///
 /// # Example
 ///
 /// ```no_run
@@ -66,26 +76,7 @@ pub async fn run(ep: impl IntoEndpoint) -> Result<(), Error> {
                 req.extensions_mut().insert(Context(ctx));
 
                 let resp = ep.call(req).await;
-
-                let (parts, body) = resp.into_parts();
-                let data = body
-                    .into_vec()
-                    .await
-                    .map_err(|_| std::io::Error::new(ErrorKind::Other, "invalid request"))?;
-                let mut lambda_resp = poem::http::Response::new(if data.is_empty() {
-                    LambdaBody::Empty
-                } else {
-                    match String::from_utf8(data) {
-                        Ok(data) => LambdaBody::Text(data),
-                        Err(err) => LambdaBody::Binary(err.into_bytes()),
-                    }
-                });
-                *lambda_resp.status_mut() = parts.status;
-                *lambda_resp.version_mut() = parts.version;
-                *lambda_resp.headers_mut() = parts.headers;
-                *lambda_resp.extensions_mut() = parts.extensions;
-
-                Ok::<_, Error>(lambda_http::IntoResponse::into_response(lambda_resp))
+                to_lambda_response(resp).await
             }
         },
     ))
@@ -93,7 +84,14 @@ pub async fn run(ep: impl IntoEndpoint) -> Result<(), Error> {
 }
 
 fn from_lambda_request(req: LambdaRequest) -> Request {
-    let (parts, lambda_body) = req.into_parts();
+    let query = req.query_string_parameters();
+    let (mut parts, lambda_body) = req.into_parts();
+    if parts.uri.query().is_none() && !query.is_empty() {
+        // API Gateway REST APIs and ALB events pass the query string
+        // separately, so it must be added back for the `Query` extractor.
+        parts.uri = append_query_string(parts.uri, &query);
+    }
+
     let body = match lambda_body {
         LambdaBody::Empty => Body::empty(),
         LambdaBody::Text(data) => Body::from_string(data),
@@ -109,6 +107,43 @@ fn from_lambda_request(req: LambdaRequest) -> Request {
     req
 }
 
+fn append_query_string(uri: Uri, query: &StrMap) -> Uri {
+    let pairs = query
+        .iter()
+        .flat_map(|(name, _)| {
+            query
+                .get_all(name)
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |value| (name, value))
+        })
+        .collect::<Vec<_>>();
+    let query = serde_urlencoded::to_string(pairs).unwrap_or_default();
+    format!("{}?{}", uri, query).parse().unwrap_or(uri)
+}
+
+async fn to_lambda_response(resp: Response) -> Result<LambdaResponse<LambdaBody>, Error> {
+    let (parts, body) = resp.into_parts();
+    let data = body
+        .into_vec()
+        .await
+        .map_err(|_| std::io::Error::new(ErrorKind::Other, "invalid request"))?;
+    let mut lambda_resp = poem::http::Response::new(if data.is_empty() {
+        LambdaBody::Empty
+    } else {
+        match String::from_utf8(data) {
+            Ok(data) => LambdaBody::Text(data),
+            Err(err) => LambdaBody::Binary(err.into_bytes()),
+        }
+    });
+    *lambda_resp.status_mut() = parts.status;
+    *lambda_resp.version_mut() = parts.version;
+    *lambda_resp.headers_mut() = parts.headers;
+    *lambda_resp.extensions_mut() = parts.extensions;
+
+    Ok(lambda_http::IntoResponse::into_response(lambda_resp))
+}
+
 #[poem::async_trait]
 impl<'a> FromRequest<'a> for &'a Context {
     type Error = Infallible;
@@ -121,3 +156,54 @@ impl<'a> FromRequest<'a> for &'a Context {
         Ok(ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use poem::{handler, http::StatusCode, web::Query};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn alb_query_string() {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+        }
+
+        #[handler]
+        fn index(Query(params): Query<Params>, body: String) -> String {
+            format!("{} {}", params.name, body)
+        }
+
+        let lambda_req = lambda_http::request::from_str(
+            r#"{
+                "requestContext": {
+                    "elb": {
+                        "targetGroupArn": "arn:aws:elasticloadbalancing:region:123456789012:targetgroup/my-target-group/6d0ecf831eec9f09"
+                    }
+                },
+                "httpMethod": "GET",
+                "path": "/hello",
+                "queryStringParameters": { "name": "a b" },
+                "multiValueQueryStringParameters": { "name": ["a b"] },
+                "headers": { "host": "example.com" },
+                "multiValueHeaders": { "host": ["example.com"] },
+                "isBase64Encoded": false,
+                "body": "request_body"
+            }"#,
+        )
+        .unwrap();
+
+        let req = from_lambda_request(lambda_req);
+        assert_eq!(req.uri().path(), "/hello");
+        assert_eq!(req.uri().query(), Some("name=a+b"));
+
+        let resp = to_lambda_response(index.call(req).await).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.body(),
+            &LambdaBody::Text("a b request_body".to_string())
+        );
+    }
+}
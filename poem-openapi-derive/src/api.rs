@@ -1,19 +1,24 @@
+use std::collections::{HashMap, HashSet};
+
 use darling::{util::SpannedValue, FromMeta};
 use http::header::HeaderName;
 use indexmap::IndexMap;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, TokenStream, TokenTree};
 use quote::{format_ident, quote};
 use syn::{
-    AttributeArgs, Error, FnArg, ImplItem, ImplItemMethod, ItemImpl, Lit, Meta, NestedMeta, Path,
+    ext::IdentExt, AttributeArgs, Error, FnArg, ImplItem, ImplItemMethod, ItemImpl, Path,
     ReturnType,
 };
 
 use crate::{
-    common_args::{APIMethod, DefaultValue, MaximumValidator, MinimumValidator, ParamIn},
+    common_args::{
+        check_param_style, APIMethod, Auth, DefaultValue, MaximumValidator, MinimumValidator,
+        ParamIn, ParamStyle,
+    },
     error::GeneratorResult,
     utils::{
-        convert_oai_path, get_crate_name, get_summary_and_description, optional_literal,
-        parse_oai_attrs, remove_oai_attrs,
+        convert_oai_path, get_crate_name, get_description, get_summary_and_description,
+        optional_literal, parse_oai_attrs, remove_doc_attrs, remove_oai_attrs, OaiPath,
     },
     validators::HasValidators,
 };
@@ -26,6 +31,8 @@ struct APIArgs {
     prefix_path: Option<SpannedValue<String>>,
     #[darling(default, multiple, rename = "tag")]
     common_tags: Vec<Path>,
+    #[darling(default)]
+    client: Option<SpannedValue<String>>,
 }
 
 #[derive(FromMeta)]
@@ -38,37 +45,16 @@ struct APIOperation {
     tags: Vec<Path>,
     #[darling(default)]
     transform: Option<Ident>,
-}
-
-#[derive(Default)]
-struct Auth {
-    scopes: Vec<Path>,
-}
-
-impl FromMeta for Auth {
-    fn from_meta(item: &Meta) -> darling::Result<Self> {
-        match item {
-            Meta::Path(_) => Ok(Default::default()),
-            Meta::List(ls) => {
-                let mut scopes = Vec::new();
-                for item in &ls.nested {
-                    if let NestedMeta::Lit(Lit::Str(s)) = item {
-                        let path = syn::parse_str::<Path>(&s.value())?;
-                        scopes.push(path);
-                    } else {
-                        return Err(
-                            darling::Error::custom("Incorrect scope definitions.").with_span(item)
-                        );
-                    }
-                }
-                Ok(Self { scopes })
-            }
-            Meta::NameValue(_) => Err(darling::Error::custom(
-                "Incorrect scope definitions. #[oai(auth(\"read\", \"write\"))]",
-            )
-            .with_span(item)),
-        }
-    }
+    #[darling(default)]
+    cache: Option<SpannedValue<String>>,
+    #[darling(default)]
+    cost: Option<u32>,
+    #[darling(default)]
+    sunset: Option<SpannedValue<String>>,
+    #[darling(default)]
+    operation_id: Option<SpannedValue<String>>,
+    #[darling(default)]
+    log_sample: Option<SpannedValue<f64>>,
 }
 
 #[derive(FromMeta, Default)]
@@ -84,6 +70,8 @@ struct APIOperationParam {
     #[darling(default)]
     extract: bool,
     #[darling(default)]
+    flatten: bool,
+    #[darling(default)]
     auth: Option<Auth>,
     #[darling(default)]
     desc: Option<String>,
@@ -91,6 +79,12 @@ struct APIOperationParam {
     deprecated: bool,
     #[darling(default)]
     default: Option<DefaultValue>,
+    #[darling(default)]
+    format: Option<String>,
+    #[darling(default)]
+    style: Option<ParamStyle>,
+    #[darling(default)]
+    explode: Option<bool>,
 
     #[darling(default)]
     multiple_of: Option<SpannedValue<f64>>,
@@ -116,12 +110,17 @@ impl_has_validators!(APIOperationParam);
 
 struct Context {
     add_routes: IndexMap<String, Vec<TokenStream>>,
+    path_templates: IndexMap<String, (String, Vec<APIMethod>)>,
     operations: IndexMap<String, Vec<TokenStream>>,
     param_types: Vec<TokenStream>,
     request_types: Vec<TokenStream>,
     response_types: Vec<TokenStream>,
     tags: Vec<TokenStream>,
     security_schemes: Vec<TokenStream>,
+    extractor_types: Vec<TokenStream>,
+    operation_ids: HashSet<String>,
+    /// The methods of the client, `None` if the client is not generated.
+    client_methods: Option<Vec<TokenStream>>,
 }
 
 pub(crate) fn generate(
@@ -132,20 +131,36 @@ pub(crate) fn generate(
         internal,
         prefix_path,
         common_tags,
+        client,
     } = match APIArgs::from_list(&args) {
         Ok(args) => args,
         Err(err) => return Ok(err.write_errors()),
     };
     let crate_name = get_crate_name(internal);
     let ident = item_impl.self_ty.clone();
+    let client_ident = match &client {
+        Some(name) => match syn::parse_str::<Ident>(name) {
+            Ok(ident) => Some(ident),
+            Err(_) => {
+                return Err(
+                    Error::new(name.span(), "The client name must be an identifier.").into(),
+                )
+            }
+        },
+        None => None,
+    };
     let mut ctx = Context {
         add_routes: Default::default(),
+        path_templates: Default::default(),
         operations: Default::default(),
         param_types: Default::default(),
         request_types: Default::default(),
         response_types: Default::default(),
         tags: Default::default(),
         security_schemes: Default::default(),
+        extractor_types: Default::default(),
+        operation_ids: Default::default(),
+        client_methods: client_ident.as_ref().map(|_| Vec::new()),
     };
 
     for item in &mut item_impl.items {
@@ -178,6 +193,9 @@ pub(crate) fn generate(
         response_types,
         tags,
         security_schemes,
+        extractor_types,
+        client_methods,
+        ..
     } = ctx;
 
     let paths = {
@@ -186,7 +204,7 @@ pub(crate) fn generate(
         for (path, operation) in operations {
             paths.push(quote! {
                 #crate_name::registry::MetaPath {
-                    path: #path,
+                    path: ::std::borrow::Cow::Borrowed(#path),
                     operations: ::std::vec![#(#operation),*],
                 }
             });
@@ -199,7 +217,12 @@ pub(crate) fn generate(
 
         for (path, add_route) in add_routes {
             routes.push(quote! {
-                at(#path, #crate_name::poem::RouteMethod::new()#(.#add_route)*)
+                #[allow(unused_mut)]
+                let mut route_method = ::std::option::Option::<#crate_name::poem::RouteMethod>::None;
+                #(#add_route)*
+                if let ::std::option::Option::Some(route_method) = route_method {
+                    route = route.at(#path, route_method);
+                }
             });
         }
 
@@ -224,18 +247,45 @@ pub(crate) fn generate(
         for ty in security_schemes {
             register_items.push(quote!(<#ty as #crate_name::SecurityScheme>::register(registry);));
         }
+        for ty in extractor_types {
+            register_items.push(quote!(<#ty as #crate_name::ApiExtractor>::register(registry);));
+        }
 
         register_items
     };
 
+    let client = client_ident.map(|client_ident| {
+        let client_methods = client_methods.unwrap_or_default();
+        quote! {
+            /// The typed client of the API, which is generated by `#[OpenApi]`.
+            #[derive(::std::clone::Clone)]
+            pub struct #client_ident {
+                client: #crate_name::client::Client,
+            }
+
+            impl #client_ident {
+                /// Create a client that calls the operations with `client`.
+                pub fn new(client: #crate_name::client::Client) -> Self {
+                    Self { client }
+                }
+
+                #(#client_methods)*
+            }
+        }
+    });
+
     let expanded = quote! {
         #item_impl
 
+        #client
+
         impl #crate_name::OpenApi for #ident {
             fn meta() -> ::std::vec::Vec<#crate_name::registry::MetaApi> {
-                ::std::vec![#crate_name::registry::MetaApi {
-                    paths: ::std::vec![#(#paths),*],
-                }]
+                static META: #crate_name::once_cell::sync::Lazy<#crate_name::registry::MetaApi> =
+                    #crate_name::once_cell::sync::Lazy::new(|| #crate_name::registry::MetaApi {
+                        paths: ::std::vec![#(#paths),*],
+                    });
+                ::std::vec![::std::clone::Clone::clone(&*META)]
             }
 
             fn register(registry: &mut #crate_name::registry::Registry) {
@@ -243,8 +293,15 @@ pub(crate) fn generate(
             }
 
             fn add_routes(self, route: #crate_name::poem::Route) -> #crate_name::poem::Route {
+                #crate_name::OpenApi::add_routes_except(self, route, &::std::default::Default::default())
+            }
+
+            fn add_routes_except(self, route: #crate_name::poem::Route, options: &#crate_name::__private::RouteOptions) -> #crate_name::poem::Route {
                 let api_obj = ::std::sync::Arc::new(self);
-                route #(.#routes)*
+                #[allow(unused_mut)]
+                let mut route = route;
+                #({ #routes })*
+                route
             }
         }
     };
@@ -266,6 +323,11 @@ fn generate_operation(
         deprecated,
         tags,
         transform,
+        cache,
+        cost,
+        sunset,
+        operation_id,
+        log_sample,
     } = args;
     let http_method = method.to_http_method();
     let fn_ident = &item_method.sig.ident;
@@ -274,7 +336,30 @@ fn generate_operation(
     let description = optional_literal(&description);
     let tags = common_tags.iter().chain(&tags);
 
-    let (oai_path, new_path, path_vars) = convert_oai_path(&path, prefix_path)?;
+    if let Some(operation_id) = &operation_id {
+        if !ctx.operation_ids.insert(operation_id.to_string()) {
+            return Err(Error::new(
+                operation_id.span(),
+                format!("The operation id `{}` is already used.", &**operation_id),
+            )
+            .into());
+        }
+    }
+    let operation_id = match &operation_id {
+        Some(operation_id) => {
+            let operation_id = &**operation_id;
+            quote!(::std::option::Option::Some(#operation_id))
+        }
+        None => quote!(::std::option::Option::None),
+    };
+
+    let OaiPath {
+        oai_path,
+        new_path,
+        vars: path_vars,
+        wildcard,
+    } = convert_oai_path(&path, prefix_path)?;
+    check_path_conflicts(ctx, &path, &new_path, method)?;
 
     if item_method.sig.inputs.is_empty() {
         return Err(Error::new_spanned(
@@ -306,11 +391,26 @@ fn generate_operation(
     };
 
     let mut parse_args = Vec::new();
+    let mut parse_payload = None;
     let mut use_args = Vec::new();
     let mut has_request_payload = false;
     let mut request_meta = quote!(::std::option::Option::None);
     let mut params_meta = Vec::new();
     let mut security_requirement = quote!(::std::option::Option::None);
+    let mut extractor_securities = Vec::new();
+    let mut hook_params = Vec::new();
+    let mut hook_principal = quote!(::std::option::Option::None);
+    let mut observe_params = Vec::new();
+    let mut observe_body = None;
+    let mut has_response_context = false;
+    let mut has_response_warnings = false;
+    let mut query_repeated = Vec::new();
+    let is_client = ctx.client_methods.is_some();
+    let mut client_args = Vec::new();
+    let mut client_params = Vec::new();
+    let mut client_path_params = HashMap::new();
+    let mut client_body = None;
+    let mut has_client_cookies = false;
 
     for i in 1..item_method.sig.inputs.len() {
         let arg = &mut item_method.sig.inputs[i];
@@ -321,14 +421,43 @@ fn generate_operation(
             }
         };
         let pname = format_ident!("p{}", i);
+        let client_arg = match &*pat.pat {
+            syn::Pat::Ident(pat) => pat.ident.clone(),
+            _ => pname.clone(),
+        };
         let arg_ty = &pat.ty;
 
         let operation_param = parse_oai_attrs::<APIOperationParam>(&pat.attrs)?;
         remove_oai_attrs(&mut pat.attrs);
+        let arg_description = get_description(&pat.attrs)?;
+        remove_doc_attrs(&mut pat.attrs);
 
         match operation_param {
-            // is poem extractor
-            Some(operation_param) if operation_param.extract => {
+            // is response context
+            None if is_type_named(arg_ty, "ResponseContext") => {
+                parse_args.push(quote! {
+                    let #pname = request.extensions().get::<#crate_name::ResponseContext>().cloned().unwrap_or_default();
+                });
+                use_args.push(pname);
+                has_response_context = true;
+            }
+
+            // is response warnings
+            None if is_type_named(arg_ty, "ResponseWarnings") => {
+                parse_args.push(quote! {
+                    let #pname = #crate_name::ResponseWarnings::new(request.extensions().get::<#crate_name::ResponseContext>().cloned().unwrap_or_default());
+                });
+                use_args.push(pname);
+                has_response_context = true;
+                has_response_warnings = true;
+            }
+
+            // is poem extractor, `Data<&T>` is always an extractor
+            _ if operation_param.as_ref().map_or_else(
+                || is_type_named(arg_ty, "Data"),
+                |operation_param| operation_param.extract,
+            ) =>
+            {
                 parse_args.push(quote! {
                     let #pname = match <#arg_ty as #crate_name::poem::FromRequest>::from_request(&request, &mut body)
                         .await
@@ -343,11 +472,40 @@ fn generate_operation(
                 use_args.push(pname);
             }
 
+            // is a group of parameters and security schemes
+            Some(operation_param) if operation_param.flatten => {
+                if is_client {
+                    return Err(Error::new_spanned(
+                        arg_ty,
+                        "The `flatten` parameters are not supported by the client.",
+                    )
+                    .into());
+                }
+
+                parse_args.push(quote! {
+                    let #pname = match <#arg_ty as #crate_name::ApiExtractor>::from_request(&request, &query).await {
+                        ::std::result::Result::Ok(value) => value,
+                        ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
+                                return ::std::result::Result::Ok(<#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err));
+                            },
+                        ::std::result::Result::Err(err) => return ::std::result::Result::Err(::std::convert::Into::into(err)),
+                    };
+                });
+                use_args.push(pname);
+
+                params_meta.push(quote! {
+                    params.extend(<#arg_ty as #crate_name::ApiExtractor>::params());
+                });
+                extractor_securities
+                    .push(quote!(<#arg_ty as #crate_name::ApiExtractor>::security()));
+                ctx.extractor_types.push(quote!(#arg_ty));
+            }
+
             // is authorization extractor
             Some(operation_param) if operation_param.auth.is_some() => {
                 let auth = operation_param.auth.as_ref().unwrap();
                 parse_args.push(quote! {
-                    let #pname = match <#arg_ty as #crate_name::SecurityScheme>::from_request(&request, &query.0).await {
+                    let #pname = match <#arg_ty as #crate_name::SecurityScheme>::from_request(&request, &query).await {
                         ::std::result::Result::Ok(value) => value,
                         ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
                                 return ::std::result::Result::Ok(<#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err));
@@ -355,6 +513,9 @@ fn generate_operation(
                         ::std::result::Result::Err(err) => return ::std::result::Result::Err(::std::convert::Into::into(err)),
                     };
                 });
+                if is_static_type(arg_ty) {
+                    hook_principal = quote!(::std::option::Option::Some(&#pname as &(dyn ::std::any::Any + ::std::marker::Send + ::std::marker::Sync)));
+                }
                 use_args.push(pname);
 
                 let scopes = &auth.scopes;
@@ -395,6 +556,10 @@ fn generate_operation(
                         ),
                     )
                     .into());
+                } else if let Err(msg) =
+                    check_param_style(param_in, operation_param.style, operation_param.explode)
+                {
+                    return Err(Error::new_spanned(arg, msg).into());
                 } else if param_in == ParamIn::Header
                     && HeaderName::try_from(&param_oai_typename).is_err()
                 {
@@ -419,6 +584,82 @@ fn generate_operation(
                     };
                     quote!(#crate_name::registry::MetaParamIn::#meta_ty)
                 };
+                let is_multiple = quote!(<#arg_ty as #crate_name::types::ParseFromParameterBorrowed<'_>>::IS_MULTIPLE);
+                let get_value = match operation_param.style {
+                    Some(style) if style.is_path_style() => {
+                        let style = style.to_meta(crate_name);
+                        quote!(#crate_name::param::get_path_with_style(#param_oai_typename, #style, &request))
+                    }
+                    Some(style) => {
+                        let explode = style.explode(operation_param.explode);
+                        let style = style.to_meta(crate_name);
+                        let schema = quote!({
+                            static SCHEMA: #crate_name::once_cell::sync::Lazy<#crate_name::param::StyledSchema> =
+                                #crate_name::once_cell::sync::Lazy::new(#crate_name::param::StyledSchema::new::<#arg_ty>);
+                            &*SCHEMA
+                        });
+                        quote!(#crate_name::param::get_query_with_style(&request, #param_oai_typename, #style, #explode, #schema, &query_pairs, &query))
+                    }
+                    None if param_in == ParamIn::Query => {
+                        query_repeated.push(quote!((#param_oai_typename, #is_multiple)));
+                        quote!(#crate_name::param::get_query(#param_oai_typename, #is_multiple, &query_pairs, &query))
+                    }
+                    None => {
+                        quote!(#crate_name::param::get(#param_oai_typename, #meta_in, &request, &query))
+                    }
+                };
+                let is_wildcard =
+                    param_in == ParamIn::Path && wildcard == Some(&*param_oai_typename);
+                let is_query_style =
+                    matches!(operation_param.style, Some(style) if !style.is_path_style());
+                let is_plain_query = param_in == ParamIn::Query && operation_param.style.is_none();
+                if is_client {
+                    let name = &param_oai_typename;
+                    match param_in {
+                        ParamIn::Path => {
+                            client_path_params.insert(
+                                param_oai_typename.clone(),
+                                quote!(#crate_name::client::path_param(&#client_arg, #is_wildcard)),
+                            );
+                        }
+                        ParamIn::Query => client_params.push(quote! {
+                            let __request = #crate_name::client::query_param(__request, #name, &#client_arg);
+                        }),
+                        ParamIn::Header => client_params.push(quote! {
+                            let __request = #crate_name::client::header_param(__request, #name, &#client_arg);
+                        }),
+                        ParamIn::Cookie if operation_param.private || operation_param.signed => {
+                            return Err(Error::new_spanned(
+                                arg_ty,
+                                "The private and signed cookies are not supported by the client.",
+                            )
+                            .into());
+                        }
+                        ParamIn::Cookie => {
+                            client_params.push(quote! {
+                                #crate_name::client::cookie_param(&mut __cookies, #name, &#client_arg);
+                            });
+                            has_client_cookies = true;
+                        }
+                    }
+                    client_args.push(quote!(#client_arg: #arg_ty));
+                }
+                let parse_value = if is_wildcard {
+                    quote!(<#arg_ty as #crate_name::param::ParseFromWildcard>::parse_from_wildcard)
+                } else if is_query_style {
+                    quote!(#crate_name::param::parse_from_styled_value::<#arg_ty>)
+                } else if is_plain_query {
+                    quote!(#crate_name::param::parse_from_query_value)
+                } else {
+                    quote!(#crate_name::types::ParseFromParameterBorrowed::parse_from_parameter_borrowed)
+                };
+                // the styled query parameters are assembled into a JSON value, and
+                // the other query parameters may have several values
+                let as_value = if is_query_style || is_plain_query {
+                    quote!(as_ref)
+                } else {
+                    quote!(as_deref)
+                };
                 let validators_checker = operation_param
                     .validators()
                     .create_param_checker(crate_name, &param_oai_typename)?;
@@ -426,6 +667,9 @@ fn generate_operation(
                     .validators()
                     .create_update_meta(crate_name)?;
 
+                // the parsed parameter may borrow from the raw value, so it is
+                // kept until the operation is completed
+                let pvalue = format_ident!("{}_value", pname);
                 match &operation_param.default {
                     Some(default_value) => {
                         let default_value = match default_value {
@@ -436,12 +680,11 @@ fn generate_operation(
                         };
 
                         parse_args.push(quote! {
+                            let #pvalue = #get_value;
                             let #pname = {
-                                let value = #crate_name::param::get(#param_oai_typename, #meta_in, &request, &query.0);
-                                let value = value.as_deref();
-                                match value {
+                                match #pvalue.#as_value() {
                                     Some(value) => {
-                                        match #crate_name::types::ParseFromParameter::parse_from_parameter(Some(value))
+                                        match #parse_value(Some(value))
                                                 .map_err(|err| #crate_name::ParseRequestError::ParseParam {
                                                     name: #param_oai_typename,
                                                     reason: err.into_message(),
@@ -464,9 +707,9 @@ fn generate_operation(
                     }
                     None => {
                         parse_args.push(quote! {
+                            let #pvalue = #get_value;
                             let #pname = {
-                                let value = #crate_name::param::get(#param_oai_typename, #meta_in, &request, &query.0);
-                                match #crate_name::types::ParseFromParameter::parse_from_parameter(value.as_deref())
+                                match #parse_value(#pvalue.#as_value())
                                         .map_err(|err| #crate_name::ParseRequestError::ParseParam {
                                             name: #param_oai_typename,
                                             reason: err.into_message(),
@@ -496,18 +739,53 @@ fn generate_operation(
                     None => quote!(::std::option::Option::None),
                 };
 
+                if is_static_type(arg_ty) {
+                    hook_params.push(quote!((#param_oai_typename, &#pname as &(dyn ::std::any::Any + ::std::marker::Send + ::std::marker::Sync))));
+                }
+                observe_params.push(quote!((#param_oai_typename, (&#crate_name::__private::Observed(&#pname)).observe_json())));
                 use_args.push(pname);
 
-                let desc = optional_literal(&operation_param.desc);
+                let desc = optional_literal(&operation_param.desc.or(arg_description));
+                let format = optional_literal(&operation_param.format);
                 let deprecated = operation_param.deprecated;
+                let style = match operation_param.style {
+                    Some(style) => {
+                        let style = style.to_meta(crate_name);
+                        quote!(::std::option::Option::Some(#style))
+                    }
+                    None => quote!(::std::option::Option::None),
+                };
+                let explode = match operation_param.style {
+                    Some(style) if !style.is_path_style() => {
+                        let explode = style.explode(operation_param.explode);
+                        quote!(::std::option::Option::Some(#explode))
+                    }
+                    _ => quote!(::std::option::Option::None),
+                };
+                // the repeated query parameters are documented in the `form` style
+                let (style, explode) = if is_plain_query {
+                    (
+                        quote! {
+                            if #is_multiple {
+                                ::std::option::Option::Some(#crate_name::registry::MetaParamStyle::Form)
+                            } else {
+                                ::std::option::Option::None
+                            }
+                        },
+                        quote!(#is_multiple.then(|| true)),
+                    )
+                } else {
+                    (style, explode)
+                };
                 params_meta.push(quote! {
-                    #[allow(unused_mut)]
+                    params.push(#[allow(unused_mut)]
                     #crate_name::registry::MetaOperationParam {
-                        name: #param_oai_typename,
+                        name: ::std::borrow::Cow::Borrowed(#param_oai_typename),
                         schema: {
                             <#arg_ty as #crate_name::types::Type>::schema_ref().merge({
                                 let mut schema = #crate_name::registry::MetaSchema::ANY;
                                 schema.default = #meta_arg_default;
+                                schema.format = #format;
                                 #validators_update_meta
                                 schema
                             })
@@ -516,7 +794,10 @@ fn generate_operation(
                         description: #desc,
                         required: <#arg_ty as #crate_name::types::Type>::IS_REQUIRED,
                         deprecated: #deprecated,
-                    }
+                        style: #style,
+                        explode: #explode,
+                        wildcard: #is_wildcard,
+                    });
                 });
                 ctx.param_types.push(quote!(#arg_ty));
             }
@@ -529,7 +810,10 @@ fn generate_operation(
                     );
                 }
 
-                parse_args.push(quote! {
+                // the request payload is parsed after the other arguments, so the
+                // `Expect: 100-continue` clients will not transmit the body if the
+                // authorization or parameters are invalid.
+                parse_payload = Some(quote! {
                     let #pname = match <#arg_ty as #crate_name::ApiRequest>::from_request(&request, &mut body).await {
                         ::std::result::Result::Ok(value) => value,
                         ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
@@ -538,10 +822,32 @@ fn generate_operation(
                         ::std::result::Result::Err(err) => return ::std::result::Result::Err(::std::convert::Into::into(err)),
                     };
                 });
+                observe_body = Some(quote! {
+                    if let ::std::option::Option::Some(__observed) = __observed.filter(|observed| observed.capture_bodies()) {
+                        #[allow(unused_imports)]
+                        use #crate_name::__private::{ObserveJson as _, ObserveNone as _};
+                        __observed.set_request_body((&#crate_name::__private::Observed(&#pname)).observe_json());
+                    }
+                });
                 use_args.push(pname);
+                if is_client {
+                    client_args.push(quote!(#client_arg: #arg_ty));
+                    client_body = Some(quote! {
+                        let __request = #crate_name::client::ClientRequest::into_request_body(#client_arg, __request);
+                    });
+                }
 
                 has_request_payload = true;
-                request_meta = quote!(::std::option::Option::Some(<#arg_ty as #crate_name::ApiRequest>::meta()));
+                request_meta = match &arg_description {
+                    Some(description) => quote!(::std::option::Option::Some({
+                        let mut request = <#arg_ty as #crate_name::ApiRequest>::meta();
+                        request.description = ::std::option::Option::Some(#description);
+                        request
+                    })),
+                    None => {
+                        quote!(::std::option::Option::Some(<#arg_ty as #crate_name::ApiRequest>::meta()))
+                    }
+                };
                 ctx.request_types.push(quote!(#arg_ty));
             }
         }
@@ -549,48 +855,364 @@ fn generate_operation(
 
     ctx.response_types.push(quote!(#res_ty));
 
+    let cache = match cache {
+        Some(cache) => {
+            if cache.is_empty() || !cache.chars().all(|c| (' '..='~').contains(&c)) {
+                return Err(Error::new(
+                    cache.span(),
+                    "Invalid value of the `Cache-Control` header.",
+                )
+                .into());
+            }
+            let cache = &*cache;
+            Some(quote! {
+                let ep = #crate_name::poem::EndpointExt::after(#crate_name::poem::EndpointExt::map_to_response(ep), |mut resp: #crate_name::poem::Response| async move {
+                    if resp.status().is_success() && !resp.headers().contains_key(#crate_name::poem::http::header::CACHE_CONTROL) {
+                        resp.headers_mut().insert(
+                            #crate_name::poem::http::header::CACHE_CONTROL,
+                            #crate_name::poem::http::HeaderValue::from_static(#cache),
+                        );
+                    }
+                    resp
+                });
+            })
+        }
+        None => None,
+    };
+
+    let cost_meta = match cost {
+        Some(cost) => quote!(::std::option::Option::Some(#cost)),
+        None => quote!(::std::option::Option::None),
+    };
+    let cost = cost.map(|cost| {
+        quote! {
+            let ep = #crate_name::__private::operation_cost(ep, #cost);
+        }
+    });
+
+    let log_sample = match log_sample {
+        Some(rate) if !(0.0..=1.0).contains(&*rate) => {
+            return Err(Error::new(
+                rate.span(),
+                "The `log_sample` attribute must be from 0.0 to 1.0.",
+            )
+            .into());
+        }
+        Some(rate) => {
+            let rate = *rate;
+            Some(quote! {
+                let ep = #crate_name::__private::log_sample(ep, #rate);
+            })
+        }
+        None => None,
+    };
+
+    let (sunset, sunset_meta) = match sunset {
+        Some(sunset) => {
+            if !deprecated {
+                return Err(Error::new(
+                    sunset.span(),
+                    "The `sunset` attribute requires the operation to be `deprecated`.",
+                )
+                .into());
+            }
+            let date = match parse_sunset_date(&sunset) {
+                Some(date) => date,
+                None => {
+                    return Err(Error::new(
+                        sunset.span(),
+                        "Invalid sunset date, the format must be `YYYY-MM-DD`.",
+                    )
+                    .into());
+                }
+            };
+            (
+                Some(quote! {
+                    let ep = #crate_name::__private::sunset(ep, #date);
+                }),
+                Some(quote! {
+                    #crate_name::__private::add_sunset_headers(&mut responses);
+                }),
+            )
+        }
+        None => (None, None),
+    };
+
+    let warnings_meta = has_response_warnings.then(|| {
+        quote! {
+            #crate_name::__private::add_warning_headers(&mut responses);
+        }
+    });
+
+    let response_context = has_response_context.then(|| {
+        quote! {
+            let ep = #crate_name::__private::response_context(ep);
+        }
+    });
+
     let transform = transform.map(|transform| {
         quote! {
             let ep = #transform(ep);
         }
     });
 
+    let mut tag_names = Vec::new();
+    let mut tag_transforms = Vec::new();
+    let mut tag_securities = Vec::new();
+    for tag in tags {
+        ctx.tags.push(quote!(#tag));
+        tag_names.push(quote!(::std::borrow::Cow::Borrowed(#crate_name::Tags::name(&#tag))));
+        tag_transforms.push(quote! {
+            let ep = #crate_name::Tags::transform(&#tag, #crate_name::poem::EndpointExt::boxed(#crate_name::poem::EndpointExt::map_to_response(ep)));
+        });
+        tag_securities.push(quote!(#crate_name::Tags::security(&#tag)));
+    }
+
+    if let Some(client_methods) = &mut ctx.client_methods {
+        // the path variables are replaced with the arguments of the method
+        let mut path_fmt = String::new();
+        let mut path_args = Vec::new();
+        for segment in oai_path.split('/').filter(|segment| !segment.is_empty()) {
+            path_fmt.push('/');
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(var) => match client_path_params.remove(var) {
+                    Some(arg) => {
+                        path_fmt.push_str("{}");
+                        path_args.push(arg);
+                    }
+                    None => {
+                        return Err(Error::new(
+                            path.span(),
+                            format!(
+                                "The client requires the path variable `{}` to be a parameter of the operation.",
+                                var
+                            ),
+                        )
+                        .into());
+                    }
+                },
+                None => path_fmt.push_str(&segment.replace('{', "{{").replace('}', "}}")),
+            }
+        }
+        if path_fmt.is_empty() {
+            path_fmt.push('/');
+        }
+        let request_path = if path_args.is_empty() {
+            quote!(#path_fmt)
+        } else {
+            quote!(&::std::format!(#path_fmt, #(#path_args),*))
+        };
+
+        let docs = item_method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"));
+        let init_cookies =
+            has_client_cookies.then(|| quote!(let mut __cookies = ::std::vec::Vec::new();));
+        let with_cookies = has_client_cookies.then(
+            || quote!(let __request = #crate_name::client::with_cookies(__request, &__cookies);),
+        );
+
+        client_methods.push(quote! {
+            #(#docs)*
+            pub async fn #fn_ident(&self, #(#client_args),*) -> ::std::result::Result<#res_ty, #crate_name::client::ClientError> {
+                let __request = self.client.request(#crate_name::poem::http::Method::#http_method, #request_path);
+                #init_cookies
+                #(#client_params)*
+                #with_cookies
+                #client_body
+                self.client.send::<#res_ty>(__request).await
+            }
+        });
+    }
+
+    let fn_name = fn_ident.unraw().to_string();
     ctx.add_routes.entry(new_path).or_default().push(quote! {
-        method(#crate_name::poem::http::Method::#http_method, {
+        if options.is_operation_enabled(&#crate_name::poem::http::Method::#http_method, #oai_path, ::std::option::Option::Some(#fn_name), #operation_id) {
+            route_method = ::std::option::Option::Some(::std::option::Option::unwrap_or_default(route_method).method(#crate_name::poem::http::Method::#http_method, {
             let api_obj = ::std::clone::Clone::clone(&api_obj);
             let ep = #crate_name::poem::endpoint::make(move |request| {
                 let api_obj = ::std::clone::Clone::clone(&api_obj);
                 async move {
                     let (request, mut body) = request.split();
-                    let query = <#crate_name::poem::web::Query::<::std::collections::HashMap<::std::string::String, ::std::string::String>> as #crate_name::poem::FromRequest>::from_request(&request, &mut body).await.unwrap_or_default();
+                    let query_pairs = #crate_name::param::query_pairs(&request).await;
+                    let query = match #crate_name::param::parse_query_with_repeated(&request, &query_pairs, &[#(#query_repeated),*]) {
+                        ::std::result::Result::Ok(query) => query,
+                        ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
+                            return ::std::result::Result::Ok(<#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err));
+                        },
+                        ::std::result::Result::Err(err) => return ::std::result::Result::Err(<#crate_name::poem::Error as ::std::convert::From<_>>::from(err)),
+                    };
                     #(#parse_args)*
+                    if let ::std::option::Option::Some(hooks) = request.extensions().get::<#crate_name::__private::OperationHooks>() {
+                        hooks.call(&request, &#crate_name::OperationContext::new(
+                            #crate_name::poem::http::Method::#http_method,
+                            #oai_path,
+                            ::std::vec![#(#hook_params),*],
+                            #hook_principal,
+                        )).await?;
+                    }
+                    let __observed = request.extensions().get::<#crate_name::__private::ObservedOperation>();
+                    if let ::std::option::Option::Some(__observed) = __observed {
+                        #[allow(unused_imports)]
+                        use #crate_name::__private::{ObserveJson as _, ObserveNone as _};
+                        __observed.set_params(::std::vec![#(#observe_params),*]);
+                    }
+                    #parse_payload
+                    #observe_body
                     ::std::result::Result::Ok::<_, #crate_name::poem::Error>(api_obj.#fn_ident(#(#use_args),*).await)
                 }
             });
+            #response_context
+            #cache
+            #cost
+            #sunset
             #transform
+            #(#tag_transforms)*
+            let info = ::std::sync::Arc::new(#crate_name::OperationInfo::new(
+                #fn_name,
+                options.operation_id(&#crate_name::poem::http::Method::#http_method, #oai_path, #operation_id),
+                #crate_name::poem::http::Method::#http_method,
+                #oai_path,
+            ));
+            let ep = if options.observed {
+                #crate_name::__private::observe(ep, ::std::clone::Clone::clone(&info))
+            } else {
+                #crate_name::poem::EndpointExt::boxed(#crate_name::poem::EndpointExt::map_to_response(ep))
+            };
+            let ep = #crate_name::__private::operation_info(ep, info);
+            #log_sample
             ep
-        })
+        }));
+        }
     });
 
-    let mut tag_names = Vec::new();
-    for tag in tags {
-        ctx.tags.push(quote!(#tag));
-        tag_names.push(quote!(#crate_name::Tags::name(&#tag)));
-    }
-
     ctx.operations.entry(oai_path).or_default().push(quote! {
         #crate_name::registry::MetaOperation {
+            name: ::std::option::Option::Some(#fn_name),
             tags: ::std::vec![#(#tag_names),*],
+            operation_id: ::std::option::Option::map(#operation_id, ::std::borrow::Cow::Borrowed),
             method: #crate_name::poem::http::Method::#http_method,
-            summary: #summary,
-            description: #description,
-            params: ::std::vec![#(#params_meta),*],
+            summary: ::std::option::Option::map(#summary, ::std::borrow::Cow::Borrowed),
+            description: ::std::option::Option::map(#description, ::std::borrow::Cow::Borrowed),
+            params: {
+                #[allow(unused_mut)]
+                let mut params = ::std::vec::Vec::new();
+                #(#params_meta)*
+                params
+            },
             request: #request_meta,
-            responses: <#res_ty as #crate_name::ApiResponse>::meta(),
+            responses: {
+                #[allow(unused_mut)]
+                let mut responses = <#res_ty as #crate_name::ApiResponse>::meta();
+                #sunset_meta
+                #warnings_meta
+                responses
+            },
             deprecated: #deprecated,
-            security: ::std::vec![::std::iter::FromIterator::from_iter(::std::iter::IntoIterator::into_iter(#security_requirement))],
+            security: {
+                let mut requirement: ::std::collections::HashMap<&'static str, ::std::vec::Vec<&'static str>> = ::std::iter::FromIterator::from_iter(::std::iter::IntoIterator::into_iter(#security_requirement));
+                #(
+                    if let ::std::option::Option::Some((name, scopes)) = #extractor_securities {
+                        requirement.insert(name, scopes);
+                    }
+                )*
+                #(
+                    if let ::std::option::Option::Some(name) = #tag_securities {
+                        requirement.entry(name).or_default();
+                    }
+                )*
+                ::std::vec![requirement]
+            },
+            cost: #cost_meta,
         }
     });
 
     Ok(())
 }
+
+/// Converts a date in the `YYYY-MM-DD` format to the HTTP-date format, which
+/// is used by the `Sunset` header.
+fn parse_sunset_date(date: &str) -> Option<String> {
+    if date.len() != 10 {
+        return None;
+    }
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(date.format("%a, %d %b %Y 00:00:00 GMT").to_string())
+}
+
+/// Returns `false` if the type contains a reference or a lifetime, the values
+/// of such types cannot be passed to the operation hooks.
+fn is_static_type(ty: &syn::Type) -> bool {
+    fn check(tokens: TokenStream) -> bool {
+        tokens.into_iter().all(|token| match token {
+            TokenTree::Punct(punct) => punct.as_char() != '&' && punct.as_char() != '\'',
+            TokenTree::Group(group) => check(group.stream()),
+            _ => true,
+        })
+    }
+    check(quote!(#ty))
+}
+
+fn is_type_named(ty: &syn::Type, name: &str) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == name)
+            .unwrap_or_default(),
+        _ => false,
+    }
+}
+
+/// Checks whether the operation conflicts with the operations added before.
+///
+/// The paths with the same template are matched by the same route, so they
+/// must use the same parameter names, and each method can only be defined
+/// once.
+fn check_path_conflicts(
+    ctx: &mut Context,
+    path: &SpannedValue<String>,
+    new_path: &str,
+    method: APIMethod,
+) -> GeneratorResult<()> {
+    let template = new_path
+        .split('/')
+        .map(|s| match s.chars().next() {
+            Some(':') => ":",
+            Some('*') => "*",
+            _ => s,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    match ctx.path_templates.get_mut(&template) {
+        Some((exists_path, _)) if exists_path != new_path => Err(Error::new(
+            path.span(),
+            format!(
+                "The path `{}` conflicts with `{}`, the path variables must have the same names.",
+                new_path, exists_path
+            ),
+        )
+        .into()),
+        Some((_, methods)) if methods.contains(&method) => Err(Error::new(
+            path.span(),
+            format!(
+                "The operation `{} {}` is already defined.",
+                method.to_http_method(),
+                new_path
+            ),
+        )
+        .into()),
+        Some((_, methods)) => {
+            methods.push(method);
+            Ok(())
+        }
+        None => {
+            ctx.path_templates
+                .insert(template, (new_path.to_string(), vec![method]));
+            Ok(())
+        }
+    }
+}
@@ -2,7 +2,7 @@ use darling::FromMeta;
 use inflector::Inflector;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{Meta, NestedMeta, Path};
+use syn::{Lit, Meta, NestedMeta, Path};
 
 #[derive(Debug, Copy, Clone, FromMeta)]
 pub(crate) enum RenameRule {
@@ -90,7 +90,7 @@ impl FromMeta for PathList {
     }
 }
 
-#[derive(Debug, Copy, Clone, FromMeta)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, FromMeta)]
 #[darling(rename_all = "lowercase")]
 pub(crate) enum APIMethod {
     Get,
@@ -132,6 +132,79 @@ pub(crate) enum ParamIn {
     Cookie,
 }
 
+#[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq)]
+pub(crate) enum ParamStyle {
+    #[darling(rename = "matrix")]
+    Matrix,
+    #[darling(rename = "label")]
+    Label,
+    #[darling(rename = "form")]
+    Form,
+    #[darling(rename = "spaceDelimited")]
+    SpaceDelimited,
+    #[darling(rename = "pipeDelimited")]
+    PipeDelimited,
+    #[darling(rename = "deepObject")]
+    DeepObject,
+}
+
+impl ParamStyle {
+    pub(crate) fn to_meta(self, crate_name: &TokenStream) -> TokenStream {
+        match self {
+            ParamStyle::Matrix => quote!(#crate_name::registry::MetaParamStyle::Matrix),
+            ParamStyle::Label => quote!(#crate_name::registry::MetaParamStyle::Label),
+            ParamStyle::Form => quote!(#crate_name::registry::MetaParamStyle::Form),
+            ParamStyle::SpaceDelimited => {
+                quote!(#crate_name::registry::MetaParamStyle::SpaceDelimited)
+            }
+            ParamStyle::PipeDelimited => {
+                quote!(#crate_name::registry::MetaParamStyle::PipeDelimited)
+            }
+            ParamStyle::DeepObject => quote!(#crate_name::registry::MetaParamStyle::DeepObject),
+        }
+    }
+
+    /// Returns `true` if the style is for the path parameters.
+    pub(crate) fn is_path_style(self) -> bool {
+        matches!(self, ParamStyle::Matrix | ParamStyle::Label)
+    }
+
+    /// Returns whether the parameter is exploded, `explode` is the value of the
+    /// attribute.
+    pub(crate) fn explode(self, explode: Option<bool>) -> bool {
+        match self {
+            ParamStyle::Form | ParamStyle::DeepObject => explode.unwrap_or(true),
+            _ => explode.unwrap_or_default(),
+        }
+    }
+}
+
+/// Checks the `style` and `explode` attributes of a parameter, returns the
+/// error message if they are not allowed.
+pub(crate) fn check_param_style(
+    param_in: ParamIn,
+    style: Option<ParamStyle>,
+    explode: Option<bool>,
+) -> Result<(), &'static str> {
+    match style {
+        Some(style) if style.is_path_style() && param_in != ParamIn::Path => {
+            Err("The `matrix` and `label` styles are only allowed for the path parameters.")
+        }
+        Some(style) if !style.is_path_style() && param_in != ParamIn::Query => Err(
+            "The `form`, `spaceDelimited`, `pipeDelimited` and `deepObject` styles are only \
+             allowed for the query parameters.",
+        ),
+        Some(ParamStyle::DeepObject) if explode == Some(false) => {
+            Err("The `deepObject` style must be exploded.")
+        }
+        Some(style) if style.is_path_style() && explode.is_some() => {
+            Err("The `explode` attribute is only allowed with the query parameter styles.")
+        }
+        None if explode.is_some() => Err("The `explode` attribute requires the `style` attribute."),
+        _ => Ok(()),
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum DefaultValue {
     Default,
@@ -148,6 +221,41 @@ impl FromMeta for DefaultValue {
     }
 }
 
+pub(crate) enum Mask {
+    Last4,
+    All,
+    Function(Path),
+}
+
+impl Mask {
+    pub(crate) fn format(&self) -> &'static str {
+        match self {
+            Mask::Last4 => "masked-last4",
+            Mask::All | Mask::Function(_) => "masked",
+        }
+    }
+
+    pub(crate) fn function(&self, crate_name: &TokenStream) -> TokenStream {
+        match self {
+            Mask::Last4 => quote!(#crate_name::types::mask::last4),
+            Mask::All => quote!(#crate_name::types::mask::all),
+            Mask::Function(path) => quote!(#path),
+        }
+    }
+}
+
+impl FromMeta for Mask {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "last4" => Ok(Mask::Last4),
+            "all" => Ok(Mask::All),
+            _ => Ok(Mask::Function(
+                syn::parse_str(value).map_err(|_| darling::Error::unknown_value(value))?,
+            )),
+        }
+    }
+}
+
 #[derive(FromMeta)]
 pub(crate) struct MaximumValidator {
     pub(crate) value: f64,
@@ -161,3 +269,34 @@ pub(crate) struct MinimumValidator {
     #[darling(default)]
     pub(crate) exclusive: bool,
 }
+
+#[derive(Default)]
+pub(crate) struct Auth {
+    pub(crate) scopes: Vec<Path>,
+}
+
+impl FromMeta for Auth {
+    fn from_meta(item: &Meta) -> darling::Result<Self> {
+        match item {
+            Meta::Path(_) => Ok(Default::default()),
+            Meta::List(ls) => {
+                let mut scopes = Vec::new();
+                for item in &ls.nested {
+                    if let NestedMeta::Lit(Lit::Str(s)) = item {
+                        let path = syn::parse_str::<Path>(&s.value())?;
+                        scopes.push(path);
+                    } else {
+                        return Err(
+                            darling::Error::custom("Incorrect scope definitions.").with_span(item)
+                        );
+                    }
+                }
+                Ok(Self { scopes })
+            }
+            Meta::NameValue(_) => Err(darling::Error::custom(
+                "Incorrect scope definitions. #[oai(auth(\"read\", \"write\"))]",
+            )
+            .with_span(item)),
+        }
+    }
+}
@@ -137,6 +137,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
         #[#crate_name::poem::async_trait]
         impl #crate_name::types::ParseFromMultipartField for #ident {
             async fn parse_from_multipart(field: ::std::option::Option<#crate_name::poem::web::Field>) -> #crate_name::types::ParseResult<Self> {
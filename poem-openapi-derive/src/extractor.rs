@@ -0,0 +1,362 @@
+use darling::{
+    ast::Data,
+    util::{Ignored, SpannedValue},
+    FromDeriveInput, FromField,
+};
+use http::header::HeaderName;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Generics, Type};
+
+use crate::{
+    common_args::{
+        check_param_style, Auth, DefaultValue, MaximumValidator, MinimumValidator, ParamIn,
+        ParamStyle,
+    },
+    error::GeneratorResult,
+    utils::{get_crate_name, get_description, optional_literal},
+    validators::HasValidators,
+};
+
+#[derive(FromField)]
+#[darling(attributes(oai), forward_attrs(doc))]
+struct ExtractorField {
+    ident: Option<Ident>,
+    ty: Type,
+    attrs: Vec<Attribute>,
+
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default, rename = "in")]
+    param_in: Option<ParamIn>,
+    #[darling(default)]
+    private: bool,
+    #[darling(default)]
+    signed: bool,
+    #[darling(default)]
+    auth: Option<Auth>,
+    #[darling(default)]
+    deprecated: bool,
+    #[darling(default)]
+    default: Option<DefaultValue>,
+    #[darling(default)]
+    format: Option<String>,
+    #[darling(default)]
+    style: Option<ParamStyle>,
+    #[darling(default)]
+    explode: Option<bool>,
+
+    #[darling(default)]
+    multiple_of: Option<SpannedValue<f64>>,
+    #[darling(default)]
+    maximum: Option<SpannedValue<MaximumValidator>>,
+    #[darling(default)]
+    minimum: Option<SpannedValue<MinimumValidator>>,
+    #[darling(default)]
+    max_length: Option<SpannedValue<usize>>,
+    #[darling(default)]
+    min_length: Option<SpannedValue<usize>>,
+    #[darling(default)]
+    pattern: Option<SpannedValue<String>>,
+    #[darling(default)]
+    max_items: Option<SpannedValue<usize>>,
+    #[darling(default)]
+    min_items: Option<SpannedValue<usize>>,
+    #[darling(default)]
+    unique_items: bool,
+}
+
+impl_has_validators!(ExtractorField);
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(oai))]
+struct ExtractorArgs {
+    ident: Ident,
+    generics: Generics,
+    data: Data<Ignored, ExtractorField>,
+
+    #[darling(default)]
+    internal: bool,
+}
+
+pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
+    let args: ExtractorArgs = ExtractorArgs::from_derive_input(&args)?;
+    let crate_name = get_crate_name(args.internal);
+    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
+    let ident = &args.ident;
+
+    let s = match &args.data {
+        Data::Struct(s) if s.style.is_struct() => s,
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "ApiExtractor can only be applied to an struct with named fields.",
+            )
+            .into())
+        }
+    };
+
+    let mut fields = Vec::new();
+    let mut parse_fields = Vec::new();
+    let mut params_meta = Vec::new();
+    let mut register_types = Vec::new();
+    let mut security = quote!(::std::option::Option::None);
+    let mut has_auth = false;
+    let mut has_query_pairs = false;
+    // the statics are shared by all the instances of a generic type
+    let generic = !args.generics.params.is_empty();
+
+    for field in &s.fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_ty = &field.ty;
+        fields.push(field_ident);
+
+        // is authorization extractor
+        if let Some(auth) = &field.auth {
+            if has_auth {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "Only one field can be marked as `auth`.",
+                )
+                .into());
+            }
+            has_auth = true;
+
+            parse_fields.push(quote! {
+                let #field_ident = <#field_ty as #crate_name::SecurityScheme>::from_request(request, query).await?;
+            });
+            let scopes = &auth.scopes;
+            security = quote!(::std::option::Option::Some((<#field_ty as #crate_name::SecurityScheme>::NAME, ::std::vec![#(#crate_name::OAuthScopes::name(&#scopes)),*])));
+            register_types
+                .push(quote!(<#field_ty as #crate_name::SecurityScheme>::register(registry);));
+            continue;
+        }
+
+        // is parameter
+        let param_name = field
+            .name
+            .clone()
+            .unwrap_or_else(|| field_ident.unraw().to_string());
+        let param_in = match field.param_in {
+            Some(param_in) => param_in,
+            None => {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    r#"Missing a input type. #[oai(in = "...")]"#,
+                )
+                .into());
+            }
+        };
+        if let Err(msg) = check_param_style(param_in, field.style, field.explode) {
+            return Err(Error::new_spanned(field_ident, msg).into());
+        }
+        if param_in == ParamIn::Header && HeaderName::try_from(&param_name).is_err() {
+            return Err(Error::new_spanned(
+                field_ident,
+                format!(
+                    "The parameter name `{}` is not a valid header name.",
+                    param_name
+                ),
+            )
+            .into());
+        }
+
+        let meta_in = {
+            let meta_ty = match param_in {
+                ParamIn::Path => quote!(Path),
+                ParamIn::Query => quote!(Query),
+                ParamIn::Header => quote!(Header),
+                ParamIn::Cookie if field.private => quote!(CookiePrivate),
+                ParamIn::Cookie if field.signed => quote!(CookieSigned),
+                ParamIn::Cookie => quote!(Cookie),
+            };
+            quote!(#crate_name::registry::MetaParamIn::#meta_ty)
+        };
+        let is_query_style = matches!(field.style, Some(style) if !style.is_path_style());
+        let is_plain_query = param_in == ParamIn::Query && field.style.is_none();
+        let is_multiple =
+            quote!(<#field_ty as #crate_name::types::ParseFromParameter>::IS_MULTIPLE);
+        let get_value = match field.style {
+            Some(style) if style.is_path_style() => {
+                let style = style.to_meta(&crate_name);
+                quote!(#crate_name::param::get_path_with_style(#param_name, #style, request))
+            }
+            Some(style) => {
+                has_query_pairs = true;
+                let explode = style.explode(field.explode);
+                let style = style.to_meta(&crate_name);
+                let schema = if generic {
+                    quote!(&#crate_name::param::StyledSchema::new::<#field_ty>())
+                } else {
+                    quote!({
+                        static SCHEMA: #crate_name::once_cell::sync::Lazy<#crate_name::param::StyledSchema> =
+                            #crate_name::once_cell::sync::Lazy::new(#crate_name::param::StyledSchema::new::<#field_ty>);
+                        &*SCHEMA
+                    })
+                };
+                quote!(#crate_name::param::get_query_with_style(request, #param_name, #style, #explode, #schema, &query_pairs, query))
+            }
+            None if param_in == ParamIn::Query => {
+                has_query_pairs = true;
+                quote!(#crate_name::param::get_query(#param_name, #is_multiple, &query_pairs, query))
+            }
+            None => quote!(#crate_name::param::get(#param_name, #meta_in, request, query)),
+        };
+        // the styled query parameters are assembled into a JSON value, and the
+        // other query parameters may have several values
+        let (parse_fn, as_value) = if is_query_style {
+            (
+                quote!(#crate_name::param::parse_from_styled_value),
+                quote!(as_ref),
+            )
+        } else if is_plain_query {
+            (
+                quote!(#crate_name::param::parse_from_query_value),
+                quote!(as_ref),
+            )
+        } else {
+            (
+                quote!(#crate_name::types::ParseFromParameter::parse_from_parameter),
+                quote!(as_deref),
+            )
+        };
+        let validators_checker = field
+            .validators()
+            .create_param_checker(&crate_name, &param_name)?;
+        let validators_update_meta = field.validators().create_update_meta(&crate_name)?;
+
+        let parse_value = quote! {
+            let value = #parse_fn(value)
+                .map_err(|err| #crate_name::ParseRequestError::ParseParam {
+                    name: #param_name,
+                    reason: err.into_message(),
+                })?;
+            #validators_checker
+            value
+        };
+        match &field.default {
+            Some(default_value) => {
+                let default_value = match default_value {
+                    DefaultValue::Default => {
+                        quote!(<#field_ty as ::std::default::Default>::default())
+                    }
+                    DefaultValue::Function(func_name) => quote!(#func_name()),
+                };
+                parse_fields.push(quote! {
+                    let #field_ident: #field_ty = match #get_value.#as_value() {
+                        ::std::option::Option::Some(value) => {
+                            let value = ::std::option::Option::Some(value);
+                            #parse_value
+                        }
+                        ::std::option::Option::None => #default_value,
+                    };
+                });
+            }
+            None => {
+                parse_fields.push(quote! {
+                    let #field_ident: #field_ty = {
+                        let value = #get_value;
+                        let value = value.#as_value();
+                        #parse_value
+                    };
+                });
+            }
+        }
+
+        let meta_default = match &field.default {
+            Some(DefaultValue::Default) => quote! {
+                ::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&<#field_ty as ::std::default::Default>::default()))
+            },
+            Some(DefaultValue::Function(func_name)) => quote! {
+                ::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&#func_name()))
+            },
+            None => quote!(::std::option::Option::None),
+        };
+        let desc = optional_literal(&get_description(&field.attrs)?);
+        let format = optional_literal(&field.format);
+        let deprecated = field.deprecated;
+        let style = match field.style {
+            Some(style) => {
+                let style = style.to_meta(&crate_name);
+                quote!(::std::option::Option::Some(#style))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        let explode = match field.style {
+            Some(style) if !style.is_path_style() => {
+                let explode = style.explode(field.explode);
+                quote!(::std::option::Option::Some(#explode))
+            }
+            _ => quote!(::std::option::Option::None),
+        };
+        // the repeated query parameters are documented in the `form` style
+        let (style, explode) = if is_plain_query {
+            (
+                quote! {
+                    if #is_multiple {
+                        ::std::option::Option::Some(#crate_name::registry::MetaParamStyle::Form)
+                    } else {
+                        ::std::option::Option::None
+                    }
+                },
+                quote!(#is_multiple.then(|| true)),
+            )
+        } else {
+            (style, explode)
+        };
+        params_meta.push(quote! {
+            #[allow(unused_mut)]
+            #crate_name::registry::MetaOperationParam {
+                name: ::std::borrow::Cow::Borrowed(#param_name),
+                schema: {
+                    <#field_ty as #crate_name::types::Type>::schema_ref().merge({
+                        let mut schema = #crate_name::registry::MetaSchema::ANY;
+                        schema.default = #meta_default;
+                        schema.format = #format;
+                        #validators_update_meta
+                        schema
+                    })
+                },
+                in_type: #meta_in,
+                description: #desc,
+                required: <#field_ty as #crate_name::types::Type>::IS_REQUIRED,
+                deprecated: #deprecated,
+                style: #style,
+                explode: #explode,
+                wildcard: false,
+            }
+        });
+        register_types.push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+    }
+
+    let query_pairs = has_query_pairs
+        .then(|| quote!(let query_pairs = #crate_name::param::query_pairs(request).await;));
+
+    let expanded = quote! {
+        #[#crate_name::poem::async_trait]
+        impl #impl_generics #crate_name::ApiExtractor for #ident #ty_generics #where_clause {
+            fn params() -> ::std::vec::Vec<#crate_name::registry::MetaOperationParam> {
+                ::std::vec![#(#params_meta),*]
+            }
+
+            fn security() -> ::std::option::Option<(&'static str, ::std::vec::Vec<&'static str>)> {
+                #security
+            }
+
+            fn register(registry: &mut #crate_name::registry::Registry) {
+                #(#register_types)*
+            }
+
+            async fn from_request(
+                request: &#crate_name::poem::Request,
+                query: &::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::std::result::Result<Self, #crate_name::ParseRequestError> {
+                #query_pairs
+                #(#parse_fields)*
+                ::std::result::Result::Ok(Self { #(#fields),* })
+            }
+        }
+    };
+
+    Ok(expanded)
+}
@@ -12,6 +12,7 @@ mod api;
 mod common_args;
 mod r#enum;
 mod error;
+mod extractor;
 mod multipart;
 mod oauth_scopes;
 mod object;
@@ -61,6 +62,15 @@ pub fn derive_response(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(ApiExtractor, attributes(oai))]
+pub fn derive_extractor(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as DeriveInput);
+    match extractor::generate(args) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.write_errors().into(),
+    }
+}
+
 #[proc_macro_derive(ApiRequest, attributes(oai))]
 pub fn derive_request(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as DeriveInput);
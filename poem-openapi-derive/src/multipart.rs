@@ -5,7 +5,9 @@ use darling::{
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Generics, Type};
+use syn::{
+    ext::IdentExt, Attribute, DeriveInput, Error, GenericArgument, Generics, PathArguments, Type,
+};
 
 use crate::{
     common_args::{
@@ -26,6 +28,8 @@ struct MultipartField {
     #[darling(default)]
     skip: bool,
     #[darling(default)]
+    extra: bool,
+    #[darling(default)]
     rename: Option<String>,
     #[darling(default)]
     default: Option<DefaultValue>,
@@ -63,6 +67,29 @@ struct MultipartArgs {
     internal: bool,
     #[darling(default)]
     rename_all: Option<RenameRule>,
+    #[darling(default)]
+    deny_unknown_fields: bool,
+    #[darling(default)]
+    ordered: bool,
+}
+
+/// Returns the value type `V` of a `HashMap<String, V>` field.
+fn extra_value_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "HashMap" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.iter().nth(1) {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -88,11 +115,37 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut meta_fields = Vec::new();
     let mut register_fields = Vec::new();
     let mut required_fields = Vec::new();
+    let mut extra_field = None;
 
-    for field in &s.fields {
+    for (index, field) in s.fields.iter().enumerate() {
         let field_ident = field.ident.as_ref().unwrap();
         let field_ty = &field.ty;
 
+        if field.extra {
+            if extra_field.is_some() {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "Only one field can be marked as `extra`.",
+                )
+                .into());
+            }
+            if args.deny_unknown_fields {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "The `extra` field cannot be used with `deny_unknown_fields`.",
+                )
+                .into());
+            }
+            let value_ty = extra_value_type(field_ty).ok_or_else(|| {
+                Error::new_spanned(
+                    field_ty,
+                    "The `extra` field must be a `HashMap<String, T>`.",
+                )
+            })?;
+            extra_field = Some((field_ident, field_ty, value_ty));
+            continue;
+        }
+
         if field.skip {
             skip_fields.push(quote! {
                 let #field_ident: #field_ty = ::std::default::Default::default();
@@ -115,18 +168,43 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         fields.push(field_ident);
 
+        let map_parse_err = quote! {
+            |err: #crate_name::types::ParseError<#field_ty>| {
+                let too_large = err.is_payload_too_large();
+                let client_disconnected = err.is_client_disconnected();
+                let reason = ::std::format!("failed to parse field `{}`: {}", #field_name, err.into_message());
+                if too_large {
+                    #crate_name::ParseRequestError::PayloadTooLarge { reason }
+                } else if client_disconnected {
+                    #crate_name::ParseRequestError::ClientDisconnected { reason }
+                } else {
+                    #crate_name::ParseRequestError::ParseRequestBody { reason }
+                }
+            }
+        };
+
+        let check_order = if args.ordered {
+            quote! {
+                if last_index > #index {
+                    return ::std::result::Result::Err(#crate_name::ParseRequestError::ParseRequestBody {
+                        reason: ::std::format!("field `{}` is out of order", #field_name),
+                    });
+                }
+                last_index = #index;
+            }
+        } else {
+            quote!()
+        };
+
         deserialize_fields.push(quote! {
             if field.name() == ::std::option::Option::Some(#field_name) {
+                #check_order
                 #field_ident = match #field_ident {
                     ::std::option::Option::Some(value) => {
-                        ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field(value, field).await.map_err(|err|
-                            #crate_name::ParseRequestError::ParseRequestBody { reason: ::std::format!("failed to parse field `{}`: {}", #field_name, err.into_message()) }
-                        )?)
+                        ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field_with_context(value, field, &upload_context).await.map_err(#map_parse_err)?)
                     }
                     ::std::option::Option::None => {
-                        ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart(::std::option::Option::Some(field)).await.map_err(|err|
-                            #crate_name::ParseRequestError::ParseRequestBody { reason: ::std::format!("failed to parse field `{}`: {}", #field_name, err.into_message()) }
-                        )?)
+                        ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart_with_context(::std::option::Option::Some(field), &upload_context).await.map_err(#map_parse_err)?)
                     }
                 };
                 continue;
@@ -210,6 +288,69 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         });
     }
 
+    let init_order = if args.ordered {
+        quote!(#[allow(unused_mut, unused_variables)] let mut last_index = 0usize;)
+    } else {
+        quote!()
+    };
+
+    let additional_properties = match &extra_field {
+        Some((_, _, value_ty)) => {
+            register_fields.push(quote! {
+                <#value_ty as #crate_name::types::Type>::register(registry);
+            });
+            quote!(::std::option::Option::Some(::std::boxed::Box::new(
+                #crate_name::registry::MetaAdditionalProperties::Schema(<#value_ty as #crate_name::types::Type>::schema_ref())
+            )))
+        }
+        None if args.deny_unknown_fields => quote!(::std::option::Option::Some(::std::boxed::Box::new(
+            #crate_name::registry::MetaAdditionalProperties::Allowed(false)
+        ))),
+        None => quote!(::std::option::Option::None),
+    };
+
+    let (init_extra, deserialize_unknown, extra_ident) = match extra_field {
+        Some((field_ident, field_ty, value_ty)) => (
+            quote!(let mut #field_ident: #field_ty = ::std::default::Default::default();),
+            quote! {
+                let name = ::std::string::ToString::to_string(field.name().unwrap_or_default());
+                let map_parse_err = |err: #crate_name::types::ParseError<#value_ty>| {
+                    let too_large = err.is_payload_too_large();
+                    let client_disconnected = err.is_client_disconnected();
+                    let reason = ::std::format!("failed to parse field `{}`: {}", name, err.into_message());
+                    if too_large {
+                        #crate_name::ParseRequestError::PayloadTooLarge { reason }
+                    } else if client_disconnected {
+                        #crate_name::ParseRequestError::ClientDisconnected { reason }
+                    } else {
+                        #crate_name::ParseRequestError::ParseRequestBody { reason }
+                    }
+                };
+                let value = match #field_ident.remove(&name) {
+                    ::std::option::Option::Some(value) => {
+                        <#value_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field_with_context(value, field, &upload_context).await.map_err(map_parse_err)?
+                    }
+                    ::std::option::Option::None => {
+                        <#value_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart_with_context(::std::option::Option::Some(field), &upload_context).await.map_err(map_parse_err)?
+                    }
+                };
+                #field_ident.insert(name, value);
+            },
+            Some(field_ident),
+        ),
+        None if args.deny_unknown_fields => (
+            quote!(),
+            quote! {
+                return ::std::result::Result::Err(#crate_name::ParseRequestError::ParseRequestBody {
+                    reason: ::std::format!("unknown field `{}`", field.name().unwrap_or_default()),
+                });
+            },
+            None,
+        ),
+        None => (quote!(), quote!(), None),
+    };
+    let extra_ident = extra_ident.into_iter();
+
     let expanded = quote! {
         impl #impl_generics #crate_name::payload::Payload for #ident #ty_generics #where_clause {
             const CONTENT_TYPE: &'static str = "multipart/form-data";
@@ -223,6 +364,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         fields
                     },
                     properties: ::std::vec![#(#meta_fields),*],
+                    additional_properties: #additional_properties,
                     ..#crate_name::registry::MetaSchema::new("object")
                 };
                 #crate_name::registry::MetaSchemaRef::Inline(Box::new(schema))
@@ -237,18 +379,23 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         impl #impl_generics #crate_name::payload::ParsePayload for #ident #ty_generics #where_clause {
             async fn from_request(request: &#crate_name::poem::Request, body: &mut #crate_name::poem::RequestBody) -> ::std::result::Result<Self, #crate_name::ParseRequestError> {
                 if body.is_some() {
-                    let mut multipart = <#crate_name::poem::web::Multipart as #crate_name::poem::FromRequest>::from_request(request, body).await.map_err(|err| #crate_name::ParseRequestError::ParseRequestBody {
-                        reason: ::std::string::ToString::to_string(::std::convert::Into::<#crate_name::poem::Error>::into(err).reason().unwrap_or_default()),
-                    })?;
+                    let mut multipart = <#crate_name::poem::web::Multipart as #crate_name::poem::FromRequest>::from_request(request, body).await.map_err(#crate_name::__private::parse_multipart_error)?;
+                    let upload_config = match request.data::<#crate_name::types::multipart::UploadConfig>() {
+                        ::std::option::Option::Some(config) => ::std::clone::Clone::clone(config),
+                        ::std::option::Option::None => ::std::default::Default::default(),
+                    };
+                    let upload_context = #crate_name::types::multipart::UploadContext::new(&upload_config);
                     #(#skip_fields)*
                     #(let mut #fields = ::std::option::Option::None;)*
-                    while let ::std::option::Option::Some(field) = multipart.next_field().await.map_err(|err| #crate_name::ParseRequestError::ParseRequestBody {
-                        reason: ::std::string::ToString::to_string(::std::convert::Into::<#crate_name::poem::Error>::into(err).reason().unwrap_or_default()),
-                    })? {
+                    #init_extra
+                    #init_order
+                    while let ::std::option::Option::Some(field) = multipart.next_field().await.map_err(#crate_name::__private::parse_multipart_error)? {
+                        let field = #crate_name::__private::limit_multipart_field(field, &upload_config);
                         #(#deserialize_fields)*
+                        #deserialize_unknown
                     }
                     #(#deserialize_none)*
-                    ::std::result::Result::Ok(Self { #(#fields,)* #(#skip_idents),* })
+                    ::std::result::Result::Ok(Self { #(#fields,)* #(#extra_ident,)* #(#skip_idents),* })
                 } else {
                     ::std::result::Result::Err(#crate_name::ParseRequestError::ParseRequestBody {
                         reason: ::std::convert::Into::into("expect request body"),
@@ -9,8 +9,8 @@ use syn::{ext::IdentExt, Attribute, DeriveInput, Error, GenericParam, Generics,
 
 use crate::{
     common_args::{
-        ConcreteType, DefaultValue, MaximumValidator, MinimumValidator, RenameRule, RenameRuleExt,
-        RenameTarget,
+        ConcreteType, DefaultValue, Mask, MaximumValidator, MinimumValidator, RenameRule,
+        RenameRuleExt, RenameTarget,
     },
     error::GeneratorResult,
     utils::{get_crate_name, get_summary_and_description, optional_literal},
@@ -35,6 +35,12 @@ struct ObjectField {
     write_only: bool,
     #[darling(default)]
     read_only: bool,
+    #[darling(default)]
+    mask: Option<Mask>,
+    #[darling(default)]
+    format: Option<String>,
+    #[darling(default)]
+    lenient: bool,
 
     #[darling(default)]
     multiple_of: Option<SpannedValue<f64>>,
@@ -82,6 +88,8 @@ struct ObjectArgs {
     read_only_all: bool,
     #[darling(default)]
     write_only_all: bool,
+    #[darling(default)]
+    lenient_all: bool,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -103,6 +111,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         .unwrap_or_else(|| RenameTarget::Type.rename(ident.to_string()));
     let (title, description) = get_summary_and_description(&args.attrs)?;
     let mut deserialize_fields = Vec::new();
+    let mut deserialize_fields_bytes = Vec::new();
     let mut serialize_fields = Vec::new();
     let mut register_types = Vec::new();
     let mut fields = Vec::new();
@@ -122,11 +131,24 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         let field_ty = &field.ty;
         let read_only = args.read_only_all || field.read_only;
         let write_only = args.write_only_all || field.write_only;
+        let parse_from_json = if args.lenient_all || field.lenient {
+            quote!(#crate_name::types::parse_from_json_lenient)
+        } else {
+            quote!(#crate_name::types::ParseFromJSON::parse_from_json)
+        };
+        // parses the `raw` value of the property
+        let parse_from_json_bytes = if args.lenient_all || field.lenient {
+            quote!(#crate_name::types::parse_from_json_lenient(#crate_name::serde_json::from_str(raw.get()).unwrap_or_default()))
+        } else {
+            quote!(#crate_name::types::ParseFromJSON::parse_from_json_bytes(raw.get().as_bytes()))
+        };
 
         if field.skip {
-            deserialize_fields.push(quote! {
+            let code = quote! {
                 let #field_ident: #field_ty = ::std::default::Default::default();
-            });
+            };
+            deserialize_fields.push(code.clone());
+            deserialize_fields_bytes.push(code);
             fields.push(field_ident);
             continue;
         }
@@ -154,7 +176,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         fields.push(field_ident);
 
         if read_only {
-            deserialize_fields.push(quote! {
+            let code = quote! {
                 #[allow(non_snake_case)]
                 let #field_ident: #field_ty = {
                     if obj.contains_key(#field_name) {
@@ -162,7 +184,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     }
                     Default::default()
                 };
-            });
+            };
+            deserialize_fields.push(code.clone());
+            deserialize_fields_bytes.push(code);
         } else {
             match &field.default {
                 Some(default_value) => {
@@ -176,26 +200,51 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     deserialize_fields.push(quote! {
                     #[allow(non_snake_case)]
                     let #field_ident: #field_ty = {
-                        match obj.get(#field_name).cloned().unwrap_or_default() {
+                        match obj.remove(#field_name).unwrap_or_default() {
                             #crate_name::serde_json::Value::Null => #default_value,
                             value => {
-                                let value = #crate_name::types::ParseFromJSON::parse_from_json(value).map_err(#crate_name::types::ParseError::propagate)?;
+                                let value = #parse_from_json(value).map_err(#crate_name::types::ParseError::propagate)?;
                                 #validators_checker
                                 value
                             }
                         }
                     };
+                });
+                    deserialize_fields_bytes.push(quote! {
+                    #[allow(non_snake_case)]
+                    let #field_ident: #field_ty = {
+                        match obj.remove(#field_name) {
+                            ::std::option::Option::Some(raw) if raw.get() != "null" => {
+                                let value = #parse_from_json_bytes.map_err(#crate_name::types::ParseError::propagate)?;
+                                #validators_checker
+                                value
+                            }
+                            _ => #default_value,
+                        }
+                    };
                 });
                 }
                 _ => {
                     deserialize_fields.push(quote! {
                     #[allow(non_snake_case)]
                     let #field_ident: #field_ty = {
-                        let value = #crate_name::types::ParseFromJSON::parse_from_json(obj.get(#field_name).cloned().unwrap_or_default())
+                        let value = #parse_from_json(obj.remove(#field_name).unwrap_or_default())
                             .map_err(#crate_name::types::ParseError::propagate)?;
                         #validators_checker
                         value
                     };
+                });
+                    deserialize_fields_bytes.push(quote! {
+                    #[allow(non_snake_case)]
+                    let #field_ident: #field_ty = {
+                        let value = match obj.remove(#field_name) {
+                            ::std::option::Option::Some(raw) => #parse_from_json_bytes,
+                            ::std::option::Option::None => #parse_from_json(#crate_name::serde_json::Value::Null),
+                        }
+                        .map_err(#crate_name::types::ParseError::propagate)?;
+                        #validators_checker
+                        value
+                    };
                 });
                 }
             };
@@ -204,8 +253,16 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         if write_only {
             serialize_fields.push(quote! {});
         } else {
+            let mask = field.mask.as_ref().map(|mask| {
+                let mask_fn = mask.function(&crate_name);
+                quote! {
+                    #crate_name::types::mask::assert_maskable::<#field_ty>();
+                    let value = #crate_name::types::mask::mask_json(value, #mask_fn);
+                }
+            });
             serialize_fields.push(quote! {
                 let value = #crate_name::types::ToJSON::to_json(&self.#field_ident);
+                #mask
                 object.insert(::std::string::ToString::to_string(#field_name), value);
             });
         }
@@ -220,6 +277,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             None => quote!(::std::option::Option::None),
         };
 
+        let field_format = optional_literal(
+            &field
+                .format
+                .as_deref()
+                .or_else(|| field.mask.as_ref().map(Mask::format)),
+        );
+
         register_types.push(quote!(<#field_ty>::register(registry);));
 
         meta_fields.push(quote! {{
@@ -228,6 +292,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 schema.default = #field_meta_default;
                 schema.read_only = #read_only;
                 schema.write_only = #write_only;
+                schema.format = #field_format;
 
                 if let ::std::option::Option::Some(title) = #field_title {
                     schema.title = ::std::option::Option::Some(title);
@@ -280,14 +345,18 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             (
                 quote!(#crate_name::registry::MetaSchemaRef::Inline(Box::new(#meta))),
                 quote! {
-                    #(#register_types)*
+                    registry.register_once::<Self, _>(|registry| {
+                        #(#register_types)*
+                    });
                 },
             )
         } else {
             (
                 quote!(#crate_name::registry::MetaSchemaRef::Reference(#oai_typename)),
                 quote! {
-                    #(#register_types)*
+                    registry.register_once::<Self, _>(|registry| {
+                        #(#register_types)*
+                    });
                     registry.create_schema(#oai_typename, |registry| #meta)
                 },
             )
@@ -319,13 +388,30 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             impl #impl_generics #crate_name::types::ParseFromJSON for #ident #ty_generics #where_clause {
                 fn parse_from_json(value: #crate_name::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
                     match value {
-                        #crate_name::serde_json::Value::Object(obj) => {
+                        #[allow(unused_mut)]
+                        #crate_name::serde_json::Value::Object(mut obj) => {
                             #(#deserialize_fields)*
                             ::std::result::Result::Ok(Self { #(#fields),* })
                         }
                         _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                     }
                 }
+
+                fn parse_from_json_bytes(bytes: &[u8]) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                    // the values of the properties borrow the bytes, and are parsed by the
+                    // types of the fields directly
+                    #[allow(unused_mut, unused_variables)]
+                    let mut obj: #crate_name::types::RawObject =
+                        match #crate_name::serde_json::from_slice(bytes) {
+                            ::std::result::Result::Ok(obj) => obj,
+                            // reports the same errors as `parse_from_json`
+                            ::std::result::Result::Err(_) => return #crate_name::types::ParseFromJSON::parse_from_json(
+                                #crate_name::serde_json::from_slice(bytes).map_err(#crate_name::types::ParseError::custom)?,
+                            ),
+                        };
+                    #(#deserialize_fields_bytes)*
+                    ::std::result::Result::Ok(Self { #(#fields),* })
+                }
             }
 
             impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
@@ -355,13 +441,16 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         code.push(quote! {
             impl #impl_generics #ident #ty_generics #where_clause {
                 fn __internal_register(name: &'static str, registry: &mut #crate_name::registry::Registry) where Self: #crate_name::types::Type {
-                    #(#register_types)*
+                    registry.register_once::<Self, _>(|registry| {
+                        #(#register_types)*
+                    });
                     registry.create_schema(name, |registry| #meta);
                 }
 
                 fn __internal_parse_from_json(value: #crate_name::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> where Self: #crate_name::types::Type {
                     match value {
-                        #crate_name::serde_json::Value::Object(obj) => {
+                        #[allow(unused_mut)]
+                        #crate_name::serde_json::Value::Object(mut obj) => {
                             #(#deserialize_fields)*
                             ::std::result::Result::Ok(Self { #(#fields),* })
                         }
@@ -369,6 +458,22 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     }
                 }
 
+                fn __internal_parse_from_json_bytes(bytes: &[u8]) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> where Self: #crate_name::types::Type {
+                    // the values of the properties borrow the bytes, and are parsed by the
+                    // types of the fields directly
+                    #[allow(unused_mut, unused_variables)]
+                    let mut obj: #crate_name::types::RawObject =
+                        match #crate_name::serde_json::from_slice(bytes) {
+                            ::std::result::Result::Ok(obj) => obj,
+                            // reports the same errors as `parse_from_json`
+                            ::std::result::Result::Err(_) => return Self::__internal_parse_from_json(
+                                #crate_name::serde_json::from_slice(bytes).map_err(#crate_name::types::ParseError::custom)?,
+                            ),
+                        };
+                    #(#deserialize_fields_bytes)*
+                    ::std::result::Result::Ok(Self { #(#fields),* })
+                }
+
                 fn __internal_to_json(&self) -> #crate_name::serde_json::Value where Self: #crate_name::types::Type {
                     let mut object = ::serde_json::Map::new();
                     #(#serialize_fields)*
@@ -409,6 +514,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     fn parse_from_json(value: #crate_name::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
                         Self::__internal_parse_from_json(value)
                     }
+
+                    fn parse_from_json_bytes(bytes: &[u8]) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                        Self::__internal_parse_from_json_bytes(bytes)
+                    }
                 }
 
                 impl #crate_name::types::ToJSON for #concrete_type {
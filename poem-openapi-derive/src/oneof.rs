@@ -117,7 +117,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
 
             fn register(registry: &mut #crate_name::registry::Registry) {
-                #(<#types as #crate_name::types::Type>::register(registry);)*
+                registry.register_once::<Self, _>(|registry| {
+                    #(<#types as #crate_name::types::Type>::register(registry);)*
+                });
             }
 
             fn as_value(&self) -> ::std::option::Option<&Self> {
@@ -1,6 +1,6 @@
 use darling::{
     ast::{Data, Fields},
-    util::Ignored,
+    util::{Ignored, SpannedValue},
     FromDeriveInput, FromVariant,
 };
 use proc_macro2::{Ident, TokenStream};
@@ -17,6 +17,9 @@ use crate::{
 struct RequestItem {
     ident: Ident,
     fields: Fields<Type>,
+
+    #[darling(default)]
+    content_type: Option<SpannedValue<String>>,
 }
 
 #[derive(FromDeriveInput)]
@@ -56,8 +59,28 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             1 => {
                 // Item(payload)
                 let payload_ty = &variant.fields.fields[0];
+                let (payload_content_type, check_content_type) = match &variant.content_type {
+                    Some(content_type) => {
+                        if http::HeaderValue::from_str(content_type).is_err()
+                            || !content_type.contains('/')
+                        {
+                            return Err(
+                                Error::new(content_type.span(), "Invalid content type.").into()
+                            );
+                        }
+                        let content_type = &**content_type;
+                        (
+                            quote!(#content_type),
+                            quote!(#crate_name::__private::check_content_type(content_type, #content_type)),
+                        )
+                    }
+                    None => (
+                        quote!(<#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE),
+                        quote!(<#payload_ty as #crate_name::payload::Payload>::check_content_type(content_type)),
+                    ),
+                };
                 from_requests.push(quote! {
-                    ::std::option::Option::Some(<#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE) => {
+                    ::std::option::Option::Some(content_type) if #check_content_type => {
                         ::std::result::Result::Ok(#ident::#item_ident(
                             <#payload_ty as #crate_name::payload::ParsePayload>::from_request(request, body).await?
                         ))
@@ -65,7 +88,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 });
                 content.push(quote! {
                     #crate_name::registry::MetaMediaType {
-                        content_type: <#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE,
+                        content_type: #payload_content_type,
                         schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
                     }
                 });
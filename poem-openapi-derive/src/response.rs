@@ -1,7 +1,7 @@
 use darling::{
     ast::{Data, Fields},
-    util::Ignored,
-    FromDeriveInput, FromField, FromVariant,
+    util::{Ignored, SpannedValue},
+    FromDeriveInput, FromField, FromMeta, FromVariant,
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote};
@@ -12,6 +12,99 @@ use crate::{
     utils::{get_crate_name, get_description, optional_literal},
 };
 
+#[derive(FromMeta, Copy, Clone)]
+enum CookieSameSite {
+    #[darling(rename = "strict")]
+    Strict,
+    #[darling(rename = "lax")]
+    Lax,
+    #[darling(rename = "none")]
+    None,
+}
+
+#[derive(FromMeta)]
+struct ResponseCookie {
+    name: String,
+    #[darling(default)]
+    path: Option<String>,
+    #[darling(default)]
+    domain: Option<String>,
+    #[darling(default)]
+    max_age: Option<u64>,
+    #[darling(default)]
+    secure: bool,
+    #[darling(default)]
+    http_only: bool,
+    #[darling(default)]
+    same_site: Option<CookieSameSite>,
+}
+
+impl ResponseCookie {
+    /// Returns the attributes of the cookie in the `Set-Cookie` format, such
+    /// as `Max-Age=3600; Secure`.
+    fn describe_attributes(&self) -> String {
+        let mut attrs = Vec::new();
+        if let Some(path) = &self.path {
+            attrs.push(format!("Path={}", path));
+        }
+        if let Some(domain) = &self.domain {
+            attrs.push(format!("Domain={}", domain));
+        }
+        if let Some(max_age) = self.max_age {
+            attrs.push(format!("Max-Age={}", max_age));
+        }
+        if self.secure {
+            attrs.push("Secure".to_string());
+        }
+        if self.http_only {
+            attrs.push("HttpOnly".to_string());
+        }
+        match self.same_site {
+            Some(CookieSameSite::Strict) => attrs.push("SameSite=Strict".to_string()),
+            Some(CookieSameSite::Lax) => attrs.push("SameSite=Lax".to_string()),
+            Some(CookieSameSite::None) => attrs.push("SameSite=None".to_string()),
+            None => {}
+        }
+        attrs.join("; ")
+    }
+}
+
+#[derive(FromMeta)]
+struct LinkParameter {
+    name: String,
+    value: String,
+}
+
+#[derive(FromMeta)]
+struct ResponseLink {
+    name: String,
+    operation_id: String,
+    #[darling(default, multiple, rename = "parameter")]
+    parameters: Vec<LinkParameter>,
+    #[darling(default)]
+    desc: Option<String>,
+}
+
+impl ResponseLink {
+    fn to_meta(&self, crate_name: &TokenStream) -> TokenStream {
+        let name = &self.name;
+        let operation_id = &self.operation_id;
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|LinkParameter { name, value }| quote!((#name, #value)));
+        let description = optional_literal(&self.desc);
+        quote! {
+            #crate_name::registry::MetaLink {
+                name: #name,
+                operation_id: ::std::borrow::Cow::Borrowed(#operation_id),
+                parameters: ::std::vec![#(#parameters),*],
+                description: #description,
+            }
+        }
+    }
+}
+
 #[derive(FromField)]
 #[darling(attributes(oai))]
 struct ResponseField {
@@ -20,6 +113,8 @@ struct ResponseField {
     #[darling(default)]
     header: Option<String>,
     #[darling(default)]
+    cookie: Option<ResponseCookie>,
+    #[darling(default)]
     desc: Option<String>,
 }
 
@@ -32,6 +127,10 @@ struct ResponseItem {
 
     #[darling(default)]
     status: Option<u16>,
+    #[darling(default)]
+    content_type: Option<SpannedValue<String>>,
+    #[darling(default, multiple, rename = "link")]
+    links: Vec<ResponseLink>,
 }
 
 #[derive(FromDeriveInput)]
@@ -45,6 +144,8 @@ struct ResponseArgs {
     internal: bool,
     #[darling(default)]
     bad_request_handler: Option<String>,
+    #[darling(default)]
+    client: bool,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -61,22 +162,108 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     };
 
+    if args.client && !args.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &args.generics,
+            "The `client` attribute does not support the generic response types.",
+        )
+        .into());
+    }
+
     let mut into_responses = Vec::new();
     let mut responses_meta = Vec::new();
     let mut schemas = Vec::new();
+    let mut client_arms = Vec::new();
+    let mut client_default_arm = None;
 
     for variant in e {
         let item_ident = &variant.ident;
         let item_description = get_description(&variant.attrs)?;
         let item_description = optional_literal(&item_description);
         let (values, headers) = parse_fields(&variant.fields);
+        let meta_links = variant
+            .links
+            .iter()
+            .map(|link| link.to_meta(&crate_name))
+            .collect::<Vec<_>>();
 
         let mut match_headers = Vec::new();
         let mut with_headers = Vec::new();
         let mut meta_headers = Vec::new();
+        let mut cookie_descriptions = Vec::new();
+        let mut parse_headers = Vec::new();
 
         for (idx, header) in headers.iter().enumerate() {
             let ident = quote::format_ident!("__p{}", idx);
+
+            if let Some(cookie) = &header.cookie {
+                if header.header.is_some() {
+                    return Err(Error::new_spanned(
+                        &header.ty,
+                        "A field cannot be both a `header` and a `cookie`.",
+                    )
+                    .into());
+                }
+                if cookie.name.is_empty() {
+                    return Err(
+                        Error::new_spanned(&header.ty, "The cookie name cannot be empty.").into(),
+                    );
+                }
+
+                let cookie_name = &cookie.name;
+                let path = cookie
+                    .path
+                    .as_ref()
+                    .map(|path| quote!(cookie.set_path(#path);));
+                let domain = cookie
+                    .domain
+                    .as_ref()
+                    .map(|domain| quote!(cookie.set_domain(#domain);));
+                let max_age = cookie.max_age.map(|max_age| {
+                    quote!(cookie.set_max_age(::std::time::Duration::from_secs(#max_age));)
+                });
+                let secure = cookie.secure.then(|| quote!(cookie.set_secure(true);));
+                let http_only = cookie
+                    .http_only
+                    .then(|| quote!(cookie.set_http_only(true);));
+                let same_site = cookie.same_site.map(|same_site| {
+                    let same_site = match same_site {
+                        CookieSameSite::Strict => quote!(Strict),
+                        CookieSameSite::Lax => quote!(Lax),
+                        CookieSameSite::None => quote!(None),
+                    };
+                    quote!(cookie.set_same_site(#crate_name::poem::web::cookie::SameSite::#same_site);)
+                });
+                with_headers.push(quote! {{
+                    let mut cookie = #crate_name::poem::web::cookie::Cookie::new_with_str(#cookie_name, ::std::string::ToString::to_string(&#ident));
+                    #path
+                    #domain
+                    #max_age
+                    #secure
+                    #http_only
+                    #same_site
+                    if let ::std::result::Result::Ok(value) = #crate_name::poem::http::HeaderValue::from_str(&::std::string::ToString::to_string(&cookie)) {
+                        resp.headers_mut().append(#crate_name::poem::http::header::SET_COOKIE, value);
+                    }
+                }});
+                let cookie_ty = &header.ty;
+                parse_headers.push(quote! {
+                    let #ident = #crate_name::client::response_cookie::<#cookie_ty>(&resp, #cookie_name)?;
+                });
+                match_headers.push(ident);
+
+                let mut description = format!("`{}`", cookie_name);
+                let attrs = cookie.describe_attributes();
+                if !attrs.is_empty() {
+                    description.push_str(&format!(" ({})", attrs));
+                }
+                if let Some(desc) = &header.desc {
+                    description.push_str(&format!(": {}", desc));
+                }
+                cookie_descriptions.push(description);
+                continue;
+            }
+
             let header_name = header.header.as_ref().unwrap().to_uppercase();
             let header_ty = &header.ty;
             let header_desc = optional_literal(&header.desc);
@@ -87,6 +274,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     resp.headers_mut().insert(#header_name, value);
                 }
             }});
+            parse_headers.push(quote! {
+                let #ident = #crate_name::client::response_header::<#header_ty>(&resp, #header_name)?;
+            });
             match_headers.push(ident);
             meta_headers.push(quote! {
                 #crate_name::registry::MetaHeader {
@@ -98,15 +288,52 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             });
         }
 
+        // all the cookies of the response are documented as a single
+        // `Set-Cookie` header, because the header names must be unique
+        if !cookie_descriptions.is_empty() {
+            let description = cookie_descriptions.join("\n");
+            meta_headers.push(quote! {
+                #crate_name::registry::MetaHeader {
+                    name: "Set-Cookie",
+                    description: ::std::option::Option::Some(#description),
+                    required: true,
+                    schema: <::std::string::String as #crate_name::types::Type>::schema_ref(),
+                }
+            });
+        }
+
+        let (content_type, with_content_type) = match &variant.content_type {
+            Some(content_type) => {
+                if http::HeaderValue::from_str(content_type).is_err() {
+                    return Err(Error::new(content_type.span(), "Invalid content type.").into());
+                }
+                let content_type = &**content_type;
+                (
+                    Some(quote!(#content_type)),
+                    Some(quote! {
+                        resp.headers_mut().insert(
+                            #crate_name::poem::http::header::CONTENT_TYPE,
+                            #crate_name::poem::http::HeaderValue::from_static(#content_type),
+                        );
+                    }),
+                )
+            }
+            None => (None, None),
+        };
+
         match values.len() {
             2 => {
                 // #[oai(default)]
                 // Item(StatusCode, payload)
                 let payload_ty = &values[1].ty;
+                let payload_content_type = content_type.clone().unwrap_or_else(
+                    || quote!(<#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE),
+                );
                 into_responses.push(quote! {
                     #ident::#item_ident(status, payload, #(#match_headers),*) => {
                         let mut resp = #crate_name::poem::IntoResponse::into_response(payload);
                         resp.set_status(status);
+                        #with_content_type
                         #(#with_headers)*
                         resp
                     }
@@ -116,23 +343,39 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         description: #item_description,
                         status: ::std::option::Option::None,
                         content: ::std::vec![#crate_name::registry::MetaMediaType {
-                            content_type: <#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE,
+                            content_type: #payload_content_type,
                             schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
                         }],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
                 schemas.push(payload_ty);
+                client_default_arm = Some(quote! {
+                    _ => {
+                        #(#parse_headers)*
+                        let status = resp.status();
+                        ::std::result::Result::Ok(#ident::#item_ident(
+                            status,
+                            <#payload_ty as #crate_name::client::ClientPayload>::from_response_body(resp).await?,
+                            #(#match_headers),*
+                        ))
+                    }
+                });
             }
             1 => {
                 // #[oai(status = 200)]
                 // Item(payload)
                 let payload_ty = &values[0].ty;
+                let payload_content_type = content_type.clone().unwrap_or_else(
+                    || quote!(<#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE),
+                );
                 let status = get_status(variant.ident.span(), variant.status)?;
                 into_responses.push(quote! {
                     #ident::#item_ident(payload, #(#match_headers),*) => {
                         let mut resp = #crate_name::poem::IntoResponse::into_response(payload);
                         resp.set_status(#crate_name::poem::http::StatusCode::from_u16(#status).unwrap());
+                        #with_content_type
                         #(#with_headers)*
                         resp
                     }
@@ -142,13 +385,30 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         description: #item_description,
                         status: ::std::option::Option::Some(#status),
                         content: ::std::vec![#crate_name::registry::MetaMediaType {
-                            content_type: <#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE,
+                            content_type: #payload_content_type,
                             schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
                         }],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
                 schemas.push(payload_ty);
+                client_arms.push(quote! {
+                    #status => {
+                        #(#parse_headers)*
+                        ::std::result::Result::Ok(#ident::#item_ident(
+                            <#payload_ty as #crate_name::client::ClientPayload>::from_response_body(resp).await?,
+                            #(#match_headers),*
+                        ))
+                    }
+                });
+            }
+            0 if content_type.is_some() => {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "The `content_type` attribute requires a payload.",
+                )
+                .into())
             }
             0 if headers.is_empty() => {
                 // #[oai(status = 200)]
@@ -169,8 +429,12 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         status: ::std::option::Option::Some(#status),
                         content: ::std::vec![],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
+                client_arms.push(quote! {
+                    #status => ::std::result::Result::Ok(#ident::#item_ident),
+                });
             }
             0 => {
                 // #[oai(status = 200)]
@@ -191,6 +455,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         status: ::std::option::Option::Some(#status),
                         content: ::std::vec![],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
+                    }
+                });
+                client_arms.push(quote! {
+                    #status => {
+                        #(#parse_headers)*
+                        ::std::result::Result::Ok(#ident::#item_ident(#(#match_headers),*))
                     }
                 });
             }
@@ -222,6 +493,43 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         None => None,
     };
 
+    let meta = quote! {
+        #crate_name::registry::MetaResponses {
+            responses: ::std::vec![#(#responses_meta),*],
+        }
+    };
+    // the statics are shared by all the instances of a generic type
+    let meta = if args.generics.params.is_empty() {
+        quote! {
+            static META: #crate_name::once_cell::sync::Lazy<#crate_name::registry::MetaResponses> =
+                #crate_name::once_cell::sync::Lazy::new(|| #meta);
+            ::std::clone::Clone::clone(&*META)
+        }
+    } else {
+        meta
+    };
+
+    let client = args.client.then(|| {
+        let default_arm = client_default_arm.unwrap_or_else(|| {
+            quote! {
+                _ => ::std::result::Result::Err(#crate_name::client::unexpected_status(resp).await),
+            }
+        });
+        quote! {
+            #[#crate_name::poem::async_trait]
+            impl #crate_name::client::ClientResponse for #ident {
+                async fn from_client_response(
+                    resp: #crate_name::client::reqwest::Response,
+                ) -> ::std::result::Result<Self, #crate_name::client::ClientError> {
+                    match resp.status().as_u16() {
+                        #(#client_arms)*
+                        #default_arm
+                    }
+                }
+            }
+        }
+    });
+
     let expanded = {
         quote! {
             impl #impl_generics #crate_name::poem::IntoResponse for #ident #ty_generics #where_clause {
@@ -236,9 +544,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 #bad_request_handler_const
 
                 fn meta() -> #crate_name::registry::MetaResponses {
-                    #crate_name::registry::MetaResponses {
-                        responses: ::std::vec![#(#responses_meta),*],
-                    }
+                    #meta
                 }
 
                 fn register(registry: &mut #crate_name::registry::Registry) {
@@ -247,6 +553,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
                 #bad_request_handler
             }
+
+            #client
         }
     };
 
@@ -271,7 +579,7 @@ fn parse_fields(fields: &Fields<ResponseField>) -> (Vec<&ResponseField>, Vec<&Re
     let mut headers = Vec::new();
 
     for field in &fields.fields {
-        if field.header.is_some() {
+        if field.header.is_some() || field.cookie.is_some() {
             headers.push(field);
         } else {
             values.push(field);
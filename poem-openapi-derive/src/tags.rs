@@ -5,7 +5,7 @@ use darling::{
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{ext::IdentExt, Attribute, DeriveInput, Error};
+use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Path};
 
 use crate::{
     common_args::{RenameRule, RenameRuleExt, RenameTarget},
@@ -22,6 +22,10 @@ struct TagItem {
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default)]
+    security: Option<Path>,
+    #[darling(default)]
+    transform: Option<Ident>,
 }
 
 #[derive(FromDeriveInput)]
@@ -48,6 +52,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     let mut meta_items = Vec::new();
     let mut to_names = Vec::new();
+    let mut register_security_schemes = Vec::new();
+    let mut to_securities = Vec::new();
+    let mut transforms = Vec::new();
 
     for variant in e {
         if !variant.fields.is_empty() {
@@ -74,12 +81,60 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             description: #description,
         }));
         to_names.push(quote!(Self::#item_ident => #oai_item_name));
+
+        let mut transform = quote!(ep);
+        if let Some(transform_fn) = &variant.transform {
+            transform = quote! {
+                #crate_name::poem::EndpointExt::boxed(#crate_name::poem::EndpointExt::map_to_response(#transform_fn(#transform)))
+            };
+        }
+        match &variant.security {
+            Some(security) => {
+                register_security_schemes
+                    .push(quote!(<#security as #crate_name::SecurityScheme>::register(registry);));
+                to_securities.push(quote!(Self::#item_ident => ::std::option::Option::Some(<#security as #crate_name::SecurityScheme>::NAME)));
+                transform = quote!(#crate_name::__private::check_security::<#security>(#transform));
+            }
+            None => to_securities.push(quote!(Self::#item_ident => ::std::option::Option::None)),
+        }
+        transforms.push(quote!(Self::#item_ident => #transform));
     }
 
+    let security = if !register_security_schemes.is_empty() {
+        Some(quote! {
+            fn security(&self) -> ::std::option::Option<&'static str> {
+                match self {
+                #(#to_securities),*
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let transform = if e
+        .iter()
+        .any(|variant| variant.security.is_some() || variant.transform.is_some())
+    {
+        Some(quote! {
+            fn transform(
+                &self,
+                ep: #crate_name::poem::endpoint::BoxEndpoint<'static, #crate_name::poem::Response>,
+            ) -> #crate_name::poem::endpoint::BoxEndpoint<'static, #crate_name::poem::Response> {
+                match self {
+                #(#transforms),*
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     let expanded = quote! {
         impl #crate_name::Tags for #ident {
             fn register(&self, registry: &mut #crate_name::registry::Registry) {
                 #(registry.create_tag(#meta_items);)*
+                #(#register_security_schemes)*
             }
 
             fn name(&self) -> &'static str {
@@ -87,6 +142,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 #(#to_names),*
                 }
             }
+
+            #security
+
+            #transform
         }
     };
 
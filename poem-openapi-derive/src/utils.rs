@@ -71,6 +71,12 @@ pub(crate) fn optional_literal(s: &Option<impl AsRef<str>>) -> TokenStream {
     }
 }
 
+/// Removes the doc comments, which are not allowed on the function arguments
+/// after the expansion.
+pub(crate) fn remove_doc_attrs(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !attr.path.is_ident("doc"));
+}
+
 pub(crate) fn remove_oai_attrs(attrs: &mut Vec<Attribute>) {
     if let Some((idx, _)) = attrs
         .iter()
@@ -91,10 +97,19 @@ pub(crate) fn parse_oai_attrs<T: FromMeta>(attrs: &[Attribute]) -> GeneratorResu
     Ok(None)
 }
 
+pub(crate) struct OaiPath<'a> {
+    pub(crate) oai_path: String,
+    pub(crate) new_path: String,
+    pub(crate) vars: HashSet<&'a str>,
+    /// The variable that captures the rest of the path, such as `path` in
+    /// `/files/*path`.
+    pub(crate) wildcard: Option<&'a str>,
+}
+
 pub(crate) fn convert_oai_path<'a, 'b: 'a>(
     path: &'a SpannedValue<String>,
     prefix_path: &'b Option<SpannedValue<String>>,
-) -> Result<(String, String, HashSet<&'a str>)> {
+) -> Result<OaiPath<'a>> {
     if !path.starts_with('/') {
         return Err(Error::new(path.span(), "The path must start with '/'."));
     }
@@ -102,12 +117,19 @@ pub(crate) fn convert_oai_path<'a, 'b: 'a>(
     let mut vars = HashSet::new();
     let mut oai_path = String::new();
     let mut new_path = String::new();
+    let mut wildcard = None;
 
     if let Some(prefix_path) = prefix_path {
-        handle_path(prefix_path, &mut vars, &mut oai_path, &mut new_path)?;
+        handle_path(
+            prefix_path,
+            &mut vars,
+            &mut oai_path,
+            &mut new_path,
+            &mut wildcard,
+        )?;
     }
 
-    handle_path(path, &mut vars, &mut oai_path, &mut new_path)?;
+    handle_path(path, &mut vars, &mut oai_path, &mut new_path, &mut wildcard)?;
 
     if oai_path.is_empty() {
         oai_path += "/";
@@ -117,7 +139,12 @@ pub(crate) fn convert_oai_path<'a, 'b: 'a>(
         new_path += "/";
     }
 
-    Ok((oai_path, new_path, vars))
+    Ok(OaiPath {
+        oai_path,
+        new_path,
+        vars,
+        wildcard,
+    })
 }
 
 fn handle_path<'a>(
@@ -125,13 +152,36 @@ fn handle_path<'a>(
     vars: &mut HashSet<&'a str>,
     oai_path: &mut String,
     new_path: &mut String,
+    wildcard: &mut Option<&'a str>,
 ) -> Result<()> {
     for s in path.split('/') {
         if s.is_empty() {
             continue;
         }
 
-        if let Some(var) = s.strip_prefix(':') {
+        if wildcard.is_some() {
+            return Err(Error::new(
+                path.span(),
+                "The wildcard path variable must be the last segment.",
+            ));
+        }
+
+        if let Some(var) = s.strip_prefix('*') {
+            oai_path.push_str("/{");
+            oai_path.push_str(var);
+            oai_path.push('}');
+
+            new_path.push_str("/*");
+            new_path.push_str(var);
+
+            if !vars.insert(var) {
+                return Err(Error::new(
+                    path.span(),
+                    format!("Repeated path variable `{}`.", var),
+                ));
+            }
+            *wildcard = Some(var);
+        } else if let Some(var) = s.strip_prefix(':') {
             oai_path.push_str("/{");
             oai_path.push_str(var);
             oai_path.push('}');
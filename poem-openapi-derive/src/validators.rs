@@ -101,14 +101,16 @@ impl<'a> Validators<'a> {
         let validators = self.create_validators(crate_name)?;
         if !validators.is_empty() {
             Ok(Some(quote! {
-                #(
-                    let validator = #validators;
-                    if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
-                        if !#crate_name::validation::Validator::check(&validator, value) {
-                            return Err(#crate_name::types::ParseError::<Self>::custom(format!("field `{}` verification failed. {}", #field_name, validator)));
+                if !#crate_name::validation::is_skipped() {
+                    #(
+                        let validator = #validators;
+                        if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
+                            if !#crate_name::validation::Validator::check(&validator, value) {
+                                return Err(#crate_name::types::ParseError::<Self>::custom(format!("field `{}` verification failed. {}", #field_name, validator)));
+                            }
                         }
-                    }
-                )*
+                    )*
+                }
             }))
         } else {
             Ok(None)
@@ -123,18 +125,20 @@ impl<'a> Validators<'a> {
         let validators = self.create_validators(crate_name)?;
         if !validators.is_empty() {
             Ok(Some(quote! {
-                #(
-                    let validator = #validators;
-                    if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
-                        if !#crate_name::validation::Validator::check(&validator, value) {
-                            let err = #crate_name::ParseRequestError::ParseParam {
-                                name: #arg_name,
-                                reason: ::std::format!("verification failed. {}", validator),
-                            };
-                            return Err(::std::convert::Into::into(err));
+                if !#crate_name::validation::is_skipped() {
+                    #(
+                        let validator = #validators;
+                        if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
+                            if !#crate_name::validation::Validator::check(&validator, value) {
+                                let err = #crate_name::ParseRequestError::ParseParam {
+                                    name: #arg_name,
+                                    reason: ::std::format!("verification failed. {}", validator),
+                                };
+                                return Err(::std::convert::Into::into(err));
+                            }
                         }
-                    }
-                )*
+                    )*
+                }
             }))
         } else {
             Ok(None)
@@ -149,16 +153,18 @@ impl<'a> Validators<'a> {
         let validators = self.create_validators(crate_name)?;
         if !validators.is_empty() {
             Ok(Some(quote! {
-                #(
-                    let validator = #validators;
-                    if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
-                        if !#crate_name::validation::Validator::check(&validator, value) {
-                            return Err(#crate_name::ParseRequestError::ParseRequestBody {
-                                reason: ::std::format!("field `{}` verification failed. {}", #field_name, validator),
-                            });
+                if !#crate_name::validation::is_skipped() {
+                    #(
+                        let validator = #validators;
+                        if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_value(&value) {
+                            if !#crate_name::validation::Validator::check(&validator, value) {
+                                return Err(#crate_name::ParseRequestError::ParseRequestBody {
+                                    reason: ::std::format!("field `{}` verification failed. {}", #field_name, validator),
+                                });
+                            }
                         }
-                    }
-                )*
+                    )*
+                }
             }))
         } else {
             Ok(None)
@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use poem_openapi::{
+    once_cell::sync::Lazy,
+    registry::MetaSchema,
+    types::{DynamicObject, DynamicObjectDefinition, DynamicObjectSchema, ParseFromJSON},
+    Object,
+};
+use serde_json::json;
+
+static FORM: Lazy<DynamicObjectDefinition> = Lazy::new(|| {
+    DynamicObjectDefinition::new("Form")
+        .property(
+            "name",
+            MetaSchema {
+                pattern: Some("^[a-z]+$".into()),
+                max_length: Some(32),
+                ..MetaSchema::new("string")
+            },
+            true,
+        )
+        .property(
+            "age",
+            MetaSchema {
+                maximum: Some(150.0),
+                ..MetaSchema::new("integer")
+            },
+            false,
+        )
+});
+
+struct Form;
+
+impl DynamicObjectSchema for Form {
+    fn definition() -> &'static DynamicObjectDefinition {
+        &FORM
+    }
+}
+
+/// The schema of the dynamic objects should be compiled only once.
+fn dynamic_object(c: &mut Criterion) {
+    let value = json!({ "name": "sunli", "age": 18 });
+    c.bench_function("dynamic_object", |b| {
+        b.iter(|| DynamicObject::<Form>::parse_from_json(value.clone()).unwrap())
+    });
+}
+
+#[derive(Debug, Object)]
+struct User {
+    id: i64,
+    name: String,
+    email: Option<String>,
+    tags: Vec<String>,
+}
+
+/// Parsing the request body bytes directly should be faster than parsing them
+/// into a `serde_json::Value` first.
+fn objects(c: &mut Criterion) {
+    let users = (0..100)
+        .map(|i| {
+            json!({
+                "id": i,
+                "name": format!("user{}", i),
+                "email": format!("user{}@example.com", i),
+                "tags": ["a", "b", "c"],
+            })
+        })
+        .collect::<Vec<_>>();
+    let data = serde_json::to_vec(&users).unwrap();
+
+    let mut group = c.benchmark_group("objects");
+    group.bench_function("parse_from_json", |b| {
+        b.iter(|| Vec::<User>::parse_from_json(serde_json::from_slice(&data).unwrap()).unwrap())
+    });
+    group.bench_function("parse_from_json_bytes", |b| {
+        b.iter(|| Vec::<User>::parse_from_json_bytes(&data).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dynamic_object, objects);
+criterion_main!(benches);
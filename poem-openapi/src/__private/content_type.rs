@@ -0,0 +1,6 @@
+/// Returns `true` if the essence of the content type of the request equals
+/// the content type specified by the `content_type` attribute, the
+/// parameters such as `charset=utf-8` are ignored.
+pub fn check_content_type(content_type: &str, expected: &str) -> bool {
+    crate::payload::content_type_matches(content_type, expected, None)
+}
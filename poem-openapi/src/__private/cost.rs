@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, IntoResponse, Request, Response};
+
+/// Added to the requests by [`OpenApiService::cost_header`](crate::OpenApiService::cost_header).
+#[derive(Clone, Copy)]
+pub struct CostHeader;
+
+/// Returns an endpoint that adds the `X-Request-Cost` header to the responses
+/// of `ep` if the [`CostHeader`] is enabled.
+pub fn operation_cost<E: Endpoint + 'static>(ep: E, cost: u32) -> BoxEndpoint<'static, Response> {
+    let ep = Arc::new(ep);
+    poem::endpoint::make(move |req: Request| {
+        let ep = ep.clone();
+        async move {
+            let enabled = req.extensions().get::<CostHeader>().is_some();
+            let mut resp = ep.call(req).await.into_response();
+            if enabled {
+                resp.headers_mut().insert("x-request-cost", cost.into());
+            }
+            resp
+        }
+    })
+    .boxed()
+}
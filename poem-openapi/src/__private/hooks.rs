@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use poem::{Request, Result};
+
+use crate::{OperationContext, OperationHook};
+
+#[derive(Clone, Default)]
+pub struct OperationHooks(pub(crate) Vec<Arc<dyn OperationHook>>);
+
+impl OperationHooks {
+    pub async fn call(&self, req: &Request, ctx: &OperationContext<'_>) -> Result<()> {
+        for hook in &self.0 {
+            hook.before_operation(req, ctx).await?;
+        }
+        Ok(())
+    }
+}
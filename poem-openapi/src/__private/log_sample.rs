@@ -0,0 +1,74 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use bytes::Bytes;
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, IntoResponse, Request, Response};
+
+use crate::capture;
+
+/// Returns an endpoint that logs the bodies of the request and the response
+/// for `rate` (from `0.0` to `1.0`) of the requests to `ep`.
+///
+/// The requests are sampled evenly by a counter rather than randomly, so
+/// exactly one of every `1 / rate` requests is logged. The bodies are copied
+/// while they are sent, at most 64KiB of each body is logged, and the streaming
+/// bodies are not logged. The log is written after the response body is sent.
+pub fn log_sample<E: Endpoint + 'static>(ep: E, rate: f64) -> BoxEndpoint<'static, Response> {
+    fn body_text(body: &Option<Bytes>) -> std::borrow::Cow<'_, str> {
+        match body {
+            Some(body) => String::from_utf8_lossy(body),
+            None => "<stream>".into(),
+        }
+    }
+
+    let ep = Arc::new(ep);
+    let counter = Arc::new(AtomicU64::new(0));
+    poem::endpoint::make(move |mut req: Request| {
+        let ep = ep.clone();
+        let counter = counter.clone();
+        async move {
+            let n = counter.fetch_add(1, Ordering::Relaxed) as f64;
+            if ((n + 1.0) * rate).floor() <= (n * rate).floor() {
+                return ep.call(req).await.into_response();
+            }
+
+            let method = req.method().clone();
+            let uri = req.uri().clone();
+            let request_body = if capture::is_streaming(req.headers()) {
+                None
+            } else {
+                let (body, captured) = capture::capture_body(req.take_body());
+                req.set_body(body);
+                Some(captured)
+            };
+
+            let mut resp = ep.call(req).await.into_response();
+            let status = resp.status();
+            let response_body = capture::capture_response_body(&mut resp);
+
+            tokio::spawn(async move {
+                let request_body = match request_body {
+                    Some(body) => Some(body.await),
+                    None => None,
+                };
+                let response_body = match response_body {
+                    Some(body) => Some(body.await),
+                    None => None,
+                };
+                tracing::info!(
+                    target: "poem_openapi::log_sample",
+                    method = %method,
+                    uri = %uri,
+                    status = %status,
+                    request_body = %body_text(&request_body),
+                    response_body = %body_text(&response_body),
+                    "sampled request"
+                );
+            });
+            resp
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,26 @@
+//! The helpers used by the code generated by the macros, which are not part
+//! of the public API.
+
+mod content_type;
+mod cost;
+mod hooks;
+mod log_sample;
+mod multipart;
+mod observe;
+mod operation;
+mod route;
+mod security;
+mod sunset;
+mod warnings;
+
+pub use content_type::check_content_type;
+pub use cost::{operation_cost, CostHeader};
+pub use hooks::OperationHooks;
+pub use log_sample::log_sample;
+pub use multipart::{limit_multipart_field, parse_multipart_error};
+pub use observe::{observe, ApiObservers, ObserveJson, ObserveNone, Observed, ObservedOperation};
+pub use operation::{operation_info, response_context};
+pub use route::{is_operation_enabled, RouteOptions};
+pub use security::check_security;
+pub use sunset::{add_sunset_headers, sunset};
+pub use warnings::add_warning_headers;
@@ -0,0 +1,25 @@
+use poem::{web::Field, Error};
+
+use crate::{types::multipart::UploadConfig, ParseRequestError};
+
+/// Applies [`UploadConfig::max_field_size`] to the multipart field that is not
+/// a file, i.e. has no file name.
+pub fn limit_multipart_field(field: Field, config: &UploadConfig) -> Field {
+    match config.max_field_size {
+        Some(size) if field.file_name().is_none() => field.size_limit(size),
+        _ => field,
+    }
+}
+
+/// Converts the error of parsing the multipart request body to
+/// [`ParseRequestError`], the errors caused by the client disconnecting are
+/// converted to [`ParseRequestError::ClientDisconnected`].
+pub fn parse_multipart_error(err: poem::error::ParseMultipartError) -> ParseRequestError {
+    let client_disconnected = err.is_client_disconnected();
+    let reason = Error::from(err).reason().unwrap_or_default().to_string();
+    if client_disconnected {
+        ParseRequestError::ClientDisconnected { reason }
+    } else {
+        ParseRequestError::ParseRequestBody { reason }
+    }
+}
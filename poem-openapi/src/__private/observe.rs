@@ -0,0 +1,160 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, IntoResponse, Request, Response};
+use serde_json::Value;
+
+use crate::{
+    capture,
+    payload::Json,
+    registry::MetaSchemaRef,
+    types::{mask, ToJSON},
+    ApiObserver, OperationEvent, OperationInfo,
+};
+
+#[derive(Clone, Default)]
+pub struct ApiObservers(pub(crate) Vec<Arc<dyn ApiObserver>>);
+
+#[derive(Default)]
+struct ObservedData {
+    params: Vec<(&'static str, Value)>,
+    request_body: Option<Value>,
+}
+
+/// Added to the requests by [`observe`], the operation stores its parsed
+/// parameters and request body in it.
+#[derive(Clone)]
+pub struct ObservedOperation {
+    capture_bodies: bool,
+    data: Arc<Mutex<ObservedData>>,
+}
+
+impl ObservedOperation {
+    pub fn capture_bodies(&self) -> bool {
+        self.capture_bodies
+    }
+
+    pub fn set_params(&self, params: Vec<(&'static str, Option<Value>)>) {
+        self.data.lock().unwrap().params = params
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value?)))
+            .collect();
+    }
+
+    pub fn set_request_body(&self, body: Option<Value>) {
+        self.data.lock().unwrap().request_body = body;
+    }
+}
+
+/// Wraps the parameters and the payloads of the operations, so that
+/// [`ObserveJson`] is used if the value can be converted to JSON, otherwise
+/// [`ObserveNone`].
+pub struct Observed<'a, T>(pub &'a T);
+
+pub trait ObserveJson {
+    fn observe_json(&self) -> Option<Value>;
+}
+
+impl<T: ToJSON> ObserveJson for Observed<'_, T> {
+    fn observe_json(&self) -> Option<Value> {
+        let value = self.0.to_json();
+        let is_password = |schema: &MetaSchemaRef| match schema {
+            MetaSchemaRef::Inline(schema) => schema.format == Some("password"),
+            MetaSchemaRef::Reference(_) => false,
+        };
+        let schema = T::schema_ref();
+        let items = match &schema {
+            MetaSchemaRef::Inline(schema) => schema.items.as_deref(),
+            MetaSchemaRef::Reference(_) => None,
+        };
+        if is_password(&schema) || items.map(is_password).unwrap_or_default() {
+            Some(mask::mask_json(value, mask::all))
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl<T: ToJSON> ObserveJson for Observed<'_, Json<T>> {
+    fn observe_json(&self) -> Option<Value> {
+        Some(self.0 .0.to_json())
+    }
+}
+
+pub trait ObserveNone {
+    fn observe_json(&self) -> Option<Value>;
+}
+
+impl<T> ObserveNone for &Observed<'_, T> {
+    fn observe_json(&self) -> Option<Value> {
+        None
+    }
+}
+
+/// Returns an endpoint that calls the [`ApiObserver`]s after `ep`, it is only
+/// used if any observer is added to the service, see
+/// [`RouteOptions::observed`](super::RouteOptions::observed).
+pub fn observe<E: Endpoint + 'static>(
+    ep: E,
+    info: Arc<OperationInfo>,
+) -> BoxEndpoint<'static, Response> {
+    let ep = Arc::new(ep);
+    poem::endpoint::make(move |mut req: Request| {
+        let ep = ep.clone();
+        let info = info.clone();
+        async move {
+            let observers = match req.extensions().get::<ApiObservers>() {
+                Some(observers) => observers.clone(),
+                None => return ep.call(req).await.into_response(),
+            };
+
+            let observed = ObservedOperation {
+                capture_bodies: observers
+                    .0
+                    .iter()
+                    .any(|observer| observer.capture_bodies(&info)),
+                data: Default::default(),
+            };
+            req.extensions_mut().insert(observed.clone());
+
+            let start = Instant::now();
+            let mut resp = ep.call(req).await.into_response();
+            let latency = start.elapsed();
+            let status = resp.status();
+            let response_body = if observed.capture_bodies {
+                capture::capture_response_body(&mut resp)
+            } else {
+                None
+            };
+
+            // the observers are called after the response body is sent, so that they
+            // do not delay the response
+            tokio::spawn(async move {
+                let response_body = match response_body {
+                    Some(body) => Some(body.await),
+                    None => None,
+                };
+                let data = std::mem::take(&mut *observed.data.lock().unwrap());
+                let event = OperationEvent {
+                    info: &info,
+                    params: &data.params,
+                    status,
+                    latency,
+                    request_body: data.request_body.as_ref(),
+                    response_body: response_body.as_ref(),
+                };
+                futures_util::future::join_all(
+                    observers
+                        .0
+                        .iter()
+                        .map(|observer| observer.on_operation(&event)),
+                )
+                .await;
+            });
+            resp
+        }
+    })
+    .boxed()
+}
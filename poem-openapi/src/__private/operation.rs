@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, IntoResponse, Request, Response};
+
+use crate::{OperationInfo, ResponseContext};
+
+/// Returns an endpoint that adds the [`OperationInfo`] to the extensions of
+/// the requests before calling `ep`.
+pub fn operation_info<E: Endpoint + 'static>(
+    ep: E,
+    info: Arc<OperationInfo>,
+) -> BoxEndpoint<'static, Response> {
+    let ep = Arc::new(ep);
+    poem::endpoint::make(move |mut req: Request| {
+        let ep = ep.clone();
+        let info = info.clone();
+        async move {
+            req.extensions_mut().insert(info);
+            ep.call(req).await.into_response()
+        }
+    })
+    .boxed()
+}
+
+/// Returns an endpoint that adds a [`ResponseContext`] to the requests, and
+/// applies it to the responses of `ep`.
+pub fn response_context<E: Endpoint + 'static>(ep: E) -> BoxEndpoint<'static, Response> {
+    let ep = Arc::new(ep);
+    poem::endpoint::make(move |mut req: Request| {
+        let ep = ep.clone();
+        async move {
+            let ctx = ResponseContext::default();
+            req.extensions_mut().insert(ctx.clone());
+            let resp = ep.call(req).await.into_response();
+            ctx.apply(resp)
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,65 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use poem::http::Method;
+
+/// The options of adding the routes of the operations, see
+/// [`OpenApi::add_routes_except`](crate::OpenApi::add_routes_except).
+#[derive(Debug, Default)]
+pub struct RouteOptions {
+    /// The names or the operation ids of the operations that are not added.
+    pub disabled: Vec<String>,
+    /// The operation ids generated by the
+    /// [`OperationIdNaming`](crate::OperationIdNaming), by the method and
+    /// the path of the operations.
+    pub operation_ids: HashMap<(Method, String), String>,
+    /// Whether any [`ApiObserver`] is added to the service, the operations
+    /// are only wrapped by [`observe`](super::observe) if it is `true`.
+    pub observed: bool,
+}
+
+impl RouteOptions {
+    /// Returns the operation id of the operation, the `operation_id`
+    /// attribute takes precedence over the generated one.
+    pub fn operation_id(
+        &self,
+        method: &Method,
+        path: &str,
+        operation_id: Option<&'static str>,
+    ) -> Option<Cow<'static, str>> {
+        match operation_id {
+            Some(operation_id) => Some(Cow::Borrowed(operation_id)),
+            None => self
+                .operation_ids
+                .get(&(method.clone(), path.to_string()))
+                .map(|operation_id| Cow::Owned(operation_id.clone())),
+        }
+    }
+
+    /// Returns `false` if the `name` or the operation id of the operation is
+    /// disabled.
+    pub fn is_operation_enabled(
+        &self,
+        method: &Method,
+        path: &str,
+        name: Option<&str>,
+        operation_id: Option<&'static str>,
+    ) -> bool {
+        is_operation_enabled(
+            &self.disabled,
+            name,
+            self.operation_id(method, path, operation_id).as_deref(),
+        )
+    }
+}
+
+/// Returns `false` if the `name` or the `operation_id` of the operation is
+/// in `disabled`.
+pub fn is_operation_enabled(
+    disabled: &[String],
+    name: Option<&str>,
+    operation_id: Option<&str>,
+) -> bool {
+    !disabled
+        .iter()
+        .any(|disabled| Some(disabled.as_str()) == name || Some(disabled.as_str()) == operation_id)
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, Error, Request, Response};
+
+use crate::{param, SecurityScheme};
+
+/// Returns an endpoint that rejects the requests that are not authorized by
+/// the security scheme `S` before calling `ep`.
+pub fn check_security<S: SecurityScheme + Send + 'static>(
+    ep: BoxEndpoint<'static, Response>,
+) -> BoxEndpoint<'static, Response> {
+    let ep = Arc::new(ep);
+    poem::endpoint::make(move |req: Request| {
+        let ep = ep.clone();
+        async move {
+            let query = param::parse_query(&req).await?;
+            S::from_request(&req, &query).await?;
+            Ok::<_, Error>(ep.call(req).await)
+        }
+    })
+    .map_to_response()
+    .boxed()
+}
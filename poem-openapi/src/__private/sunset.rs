@@ -0,0 +1,35 @@
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, Response};
+
+use crate::registry::{MetaHeader, MetaResponses, MetaSchema, MetaSchemaRef};
+
+/// Returns an endpoint that adds the `Deprecation` and `Sunset` headers to the
+/// responses of `ep`.
+pub fn sunset<E: Endpoint + 'static>(ep: E, date: &'static str) -> BoxEndpoint<'static, Response> {
+    ep.map_to_response()
+        .after(move |mut resp: Response| async move {
+            resp.headers_mut()
+                .insert("deprecation", poem::http::HeaderValue::from_static("true"));
+            resp.headers_mut()
+                .insert("sunset", poem::http::HeaderValue::from_static(date));
+            resp
+        })
+        .boxed()
+}
+
+/// Documents the headers added by [`sunset`] in the responses.
+pub fn add_sunset_headers(responses: &mut MetaResponses) {
+    for resp in &mut responses.responses {
+        resp.headers.push(MetaHeader {
+            name: "Deprecation",
+            description: Some("Indicates that the operation is deprecated."),
+            required: true,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+        });
+        resp.headers.push(MetaHeader {
+            name: "Sunset",
+            description: Some("The date when the operation will become unresponsive."),
+            required: true,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+        });
+    }
+}
@@ -0,0 +1,20 @@
+use crate::registry::{MetaHeader, MetaResponses, MetaSchema, MetaSchemaRef};
+
+/// Documents the headers added by [`ResponseWarnings`](crate::ResponseWarnings)
+/// in the responses.
+pub fn add_warning_headers(responses: &mut MetaResponses) {
+    for resp in &mut responses.responses {
+        resp.headers.push(MetaHeader {
+            name: "Warning",
+            description: Some("The warnings about the request, such as a soft deprecation."),
+            required: false,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+        });
+        resp.headers.push(MetaHeader {
+            name: "X-API-Warn",
+            description: Some("The warnings about the request, such as a soft deprecation."),
+            required: false,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+        });
+    }
+}
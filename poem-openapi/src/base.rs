@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
-use mime::Mime;
-use poem::{IntoResponse, Request, RequestBody, Result, Route};
+use poem::{
+    endpoint::{make, BoxEndpoint},
+    http::Method,
+    IntoResponse, Request, RequestBody, Response, Result, Route,
+};
 
 use crate::{
+    __private::RouteOptions,
     payload::{ParsePayload, Payload},
     registry::{
-        MetaApi, MetaMediaType, MetaOAuthScope, MetaRequest, MetaResponse, MetaResponses, Registry,
+        MetaApi, MetaHeader, MetaMediaType, MetaOAuthScope, MetaOperation, MetaOperationParam,
+        MetaPath, MetaRequest, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
     },
     ParseRequestError,
 };
@@ -51,24 +56,12 @@ impl<T: Payload + ParsePayload> ApiRequest for T {
         body: &mut RequestBody,
     ) -> Result<Self, ParseRequestError> {
         match request.content_type() {
-            Some(content_type) => {
-                let mime: Mime = match content_type.parse() {
-                    Ok(mime) => mime,
-                    Err(_) => {
-                        return Err(ParseRequestError::ContentTypeNotSupported {
-                            content_type: content_type.to_string(),
-                        });
-                    }
-                };
-
-                if mime.essence_str() != T::CONTENT_TYPE {
-                    return Err(ParseRequestError::ContentTypeNotSupported {
-                        content_type: content_type.to_string(),
-                    });
-                }
-
+            Some(content_type) if T::check_content_type(content_type) => {
                 <T as ParsePayload>::from_request(request, body).await
             }
+            Some(content_type) => Err(ParseRequestError::ContentTypeNotSupported {
+                content_type: content_type.to_string(),
+            }),
             None => Err(ParseRequestError::ExpectContentType),
         }
     }
@@ -103,20 +96,137 @@ impl ApiResponse for () {
                 status: Some(200),
                 content: vec![],
                 headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+/// The responses of `poem::Error` are not documented.
+impl ApiResponse for poem::Error {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: Vec::new(),
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+/// The redirects of all the statuses supported by `poem::web::Redirect` are
+/// documented, with the `Location` header.
+impl ApiResponse for poem::web::Redirect {
+    fn meta() -> MetaResponses {
+        let redirect = |status, description| MetaResponse {
+            description: Some(description),
+            status: Some(status),
+            content: vec![],
+            headers: vec![MetaHeader {
+                name: "Location",
+                description: Some("The URL to redirect to."),
+                required: true,
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    format: Some("uri"),
+                    ..MetaSchema::new("string")
+                })),
             }],
+            links: vec![],
+        };
+
+        MetaResponses {
+            responses: vec![
+                redirect(301, "Moved Permanently"),
+                redirect(302, "Found"),
+                redirect(303, "See Other"),
+                redirect(307, "Temporary Redirect"),
+                redirect(308, "Permanent Redirect"),
+            ],
         }
     }
 
     fn register(_registry: &mut Registry) {}
 }
 
-impl<T: ApiResponse, E: IntoResponse> ApiResponse for Result<T, E> {
+/// The report is documented as `200 OK` if all the probes passed, and `503
+/// Service Unavailable` otherwise.
+impl ApiResponse for poem::endpoint::HealthReport {
     fn meta() -> MetaResponses {
-        T::meta()
+        let report = |status, description| MetaResponse {
+            description: Some(description),
+            status: Some(status),
+            content: vec![MetaMediaType {
+                content_type: "application/json",
+                schema: MetaSchemaRef::Reference("HealthReport"),
+            }],
+            headers: vec![],
+            links: vec![],
+        };
+
+        MetaResponses {
+            responses: vec![
+                report(200, "All the probes passed."),
+                report(503, "Some of the probes failed."),
+            ],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        let status = || {
+            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                enum_items: vec!["pass".into(), "fail".into()],
+                ..MetaSchema::new("string")
+            }))
+        };
+
+        registry.create_schema("HealthReport", |_| MetaSchema {
+            required: vec!["status", "checks"],
+            properties: vec![
+                ("status", status()),
+                (
+                    "checks",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        description: Some("The results of the probes by name."),
+                        ..MetaSchema::new("object")
+                    })),
+                ),
+            ],
+            ..MetaSchema::new("object")
+        });
+    }
+}
+
+/// The responses of `E` are merged into the responses of `T`, the responses of
+/// `E` with the same status as one of `T` are ignored.
+impl<T: ApiResponse, E: ApiResponse> ApiResponse for Result<T, E> {
+    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER || E::BAD_REQUEST_HANDLER;
+
+    fn meta() -> MetaResponses {
+        let mut meta = T::meta();
+        for resp in E::meta().responses {
+            if meta
+                .responses
+                .iter()
+                .all(|exists| exists.status != resp.status)
+            {
+                meta.responses.push(resp);
+            }
+        }
+        meta
     }
 
     fn register(registry: &mut Registry) {
         T::register(registry);
+        E::register(registry);
+    }
+
+    fn from_parse_request_error(err: ParseRequestError) -> Self {
+        if E::BAD_REQUEST_HANDLER {
+            Err(E::from_parse_request_error(err))
+        } else {
+            Ok(T::from_parse_request_error(err))
+        }
     }
 }
 
@@ -127,6 +237,18 @@ pub trait Tags {
 
     /// Gets the tag name.
     fn name(&self) -> &'static str;
+
+    /// Gets the name of the security scheme required by all operations with
+    /// this tag.
+    fn security(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Applies the middlewares of this tag to the endpoint of an operation
+    /// with this tag.
+    fn transform(&self, ep: BoxEndpoint<'static, Response>) -> BoxEndpoint<'static, Response> {
+        ep
+    }
 }
 
 /// Represents a OpenAPI security scheme.
@@ -145,6 +267,28 @@ pub trait SecurityScheme: Sized {
     ) -> Result<Self, ParseRequestError>;
 }
 
+/// Represents a group of OpenAPI parameters and security schemes that can be
+/// accepted by an operation with `#[oai(flatten)]`.
+#[poem::async_trait]
+pub trait ApiExtractor: Sized {
+    /// Gets metadata of the parameters.
+    fn params() -> Vec<MetaOperationParam>;
+
+    /// Gets the name and scopes of the security scheme, if any.
+    fn security() -> Option<(&'static str, Vec<&'static str>)> {
+        None
+    }
+
+    /// Register the types contained in this extractor to the registry.
+    fn register(registry: &mut Registry);
+
+    /// Parse the extractor from the HTTP request.
+    async fn from_request(
+        request: &Request,
+        query: &HashMap<String, String>,
+    ) -> Result<Self, ParseRequestError>;
+}
+
 /// Represents a OAuth scopes.
 pub trait OAuthScopes {
     /// Gets metadata of this object.
@@ -181,6 +325,30 @@ pub trait OpenApi: Sized {
     /// Adds all API endpoints to the routing object.
     fn add_routes(self, route: Route) -> Route;
 
+    /// Adds the API endpoints to the routing object, except the operations
+    /// whose names or operation ids are disabled by the `options`, see
+    /// [`OpenApiService::disable_operations`](crate::OpenApiService::disable_operations).
+    ///
+    /// The default implementation adds all API endpoints.
+    #[allow(unused_variables)]
+    fn add_routes_except(self, route: Route, options: &RouteOptions) -> Route {
+        self.add_routes(route)
+    }
+
+    /// Gets metadata of this API object, it is the same as [`OpenApi::meta`]
+    /// unless the metadata is only known at runtime, such as for the
+    /// [`ApiBuilder`](crate::ApiBuilder).
+    fn instance_meta(&self) -> Vec<MetaApi> {
+        Self::meta()
+    }
+
+    /// Register the types used by this API object to the registry, it is the
+    /// same as [`OpenApi::register`] unless the types are only known at
+    /// runtime.
+    fn instance_register(&self, registry: &mut Registry) {
+        Self::register(registry);
+    }
+
     /// Combine two API objects into one.
     fn combine<T: OpenApi>(self, other: T) -> CombinedAPI<Self, T> {
         CombinedAPI(self, other)
@@ -205,4 +373,78 @@ impl<A: OpenApi, B: OpenApi> OpenApi for CombinedAPI<A, B> {
     fn add_routes(self, route: Route) -> Route {
         self.1.add_routes(self.0.add_routes(route))
     }
+
+    fn add_routes_except(self, route: Route, options: &RouteOptions) -> Route {
+        self.1
+            .add_routes_except(self.0.add_routes_except(route, options), options)
+    }
+
+    fn instance_meta(&self) -> Vec<MetaApi> {
+        let mut metadata = self.0.instance_meta();
+        metadata.extend(self.1.instance_meta());
+        metadata
+    }
+
+    fn instance_register(&self, registry: &mut Registry) {
+        self.0.instance_register(registry);
+        self.1.instance_register(registry);
+    }
+}
+
+/// Documents the `GET /healthz` and `GET /readyz` operations served by the
+/// [`HealthCheck`](poem::endpoint::HealthCheck).
+impl OpenApi for poem::endpoint::HealthCheck {
+    fn meta() -> Vec<MetaApi> {
+        let operation = |summary: &'static str| MetaOperation {
+            method: Method::GET,
+            name: None,
+            tags: vec![],
+            operation_id: None,
+            summary: Some(summary.into()),
+            description: None,
+            params: vec![],
+            request: None,
+            responses: poem::endpoint::HealthReport::meta(),
+            deprecated: false,
+            security: vec![],
+            cost: None,
+        };
+
+        vec![MetaApi {
+            paths: vec![
+                MetaPath {
+                    path: "/healthz".into(),
+                    operations: vec![operation("Runs the liveness probes")],
+                },
+                MetaPath {
+                    path: "/readyz".into(),
+                    operations: vec![operation("Runs the readiness probes")],
+                },
+            ],
+        }]
+    }
+
+    fn register(registry: &mut Registry) {
+        poem::endpoint::HealthReport::register(registry);
+    }
+
+    fn add_routes(self, route: Route) -> Route {
+        let liveness = self.clone();
+        let readiness = self;
+        route
+            .at(
+                "/healthz",
+                poem::get(make(move |_| {
+                    let health = liveness.clone();
+                    async move { health.check_liveness().await }
+                })),
+            )
+            .at(
+                "/readyz",
+                poem::get(make(move |_| {
+                    let health = readiness.clone();
+                    async move { health.check_readiness().await }
+                })),
+            )
+    }
 }
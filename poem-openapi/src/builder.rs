@@ -0,0 +1,295 @@
+use std::borrow::Cow;
+
+use poem::{
+    endpoint::BoxEndpoint, http::Method, EndpointExt, IntoEndpoint, Response, Route, RouteMethod,
+};
+
+use crate::{
+    __private::{self, RouteOptions},
+    registry::{
+        MetaApi, MetaOperation, MetaOperationParam, MetaParamIn, MetaPath, MetaResponses, Registry,
+    },
+    types::Type,
+    ApiRequest, ApiResponse, OpenApi,
+};
+
+/// Describes an operation added by [`ApiBuilder`].
+///
+/// The builder only describes the operation in the specification, the
+/// endpoint is responsible for parsing the parameters and the request body.
+pub struct OperationBuilder {
+    method: Method,
+    pub(crate) path: Cow<'static, str>,
+    pub(crate) meta: MetaOperation,
+    pub(crate) registers: Vec<fn(&mut Registry)>,
+}
+
+impl OperationBuilder {
+    /// Create an operation with the method and the path, the path variables
+    /// are in the OpenAPI format, such as `/users/{id}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path does not start with `/`.
+    pub fn new(method: Method, path: impl Into<Cow<'static, str>>) -> Self {
+        let path = path.into();
+        assert!(path.starts_with('/'), "the path must start with '/'");
+        Self::new_unchecked(method, path)
+    }
+
+    /// Create an operation of the webhook named `name`, which is added to
+    /// the specification by [`OpenApiService::webhook`](crate::OpenApiService::webhook).
+    ///
+    /// The request body describes the payload delivered to the webhook, and
+    /// the responses are the ones expected from the receiver.
+    pub fn webhook(method: Method, name: impl Into<Cow<'static, str>>) -> Self {
+        Self::new_unchecked(method, name.into())
+    }
+
+    fn new_unchecked(method: Method, path: Cow<'static, str>) -> Self {
+        Self {
+            method: method.clone(),
+            path,
+            meta: MetaOperation {
+                name: None,
+                tags: Vec::new(),
+                operation_id: None,
+                method,
+                summary: None,
+                description: None,
+                params: Vec::new(),
+                request: None,
+                responses: MetaResponses {
+                    responses: Vec::new(),
+                },
+                deprecated: false,
+                security: Vec::new(),
+                cost: None,
+            },
+            registers: Vec::new(),
+        }
+    }
+
+    /// Sets the summary of the operation.
+    #[must_use]
+    pub fn summary(mut self, summary: impl Into<Cow<'static, str>>) -> Self {
+        self.meta.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets the description of the operation.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<Cow<'static, str>>) -> Self {
+        self.meta.description = Some(description.into());
+        self
+    }
+
+    /// Sets the unique id of the operation, which is referenced by the
+    /// links of the responses.
+    #[must_use]
+    pub fn operation_id(mut self, operation_id: impl Into<Cow<'static, str>>) -> Self {
+        self.meta.operation_id = Some(operation_id.into());
+        self
+    }
+
+    /// Appends a tag to the operation.
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<Cow<'static, str>>) -> Self {
+        self.meta.tags.push(tag.into());
+        self
+    }
+
+    /// Marks the operation as deprecated.
+    #[must_use]
+    pub fn deprecated(mut self) -> Self {
+        self.meta.deprecated = true;
+        self
+    }
+
+    /// Sets the cost of the operation, which is emitted as
+    /// `x-operation-cost`.
+    #[must_use]
+    pub fn cost(mut self, cost: u32) -> Self {
+        self.meta.cost = Some(cost);
+        self
+    }
+
+    /// Appends a parameter of type `T`.
+    #[must_use]
+    pub fn param<T: Type>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        in_type: MetaParamIn,
+    ) -> Self {
+        self.meta.params.push(MetaOperationParam {
+            name: name.into(),
+            schema: T::schema_ref(),
+            in_type,
+            description: None,
+            required: T::IS_REQUIRED,
+            deprecated: false,
+            style: None,
+            explode: None,
+            wildcard: false,
+        });
+        self.registers.push(T::register);
+        self
+    }
+
+    /// Sets the request body.
+    #[must_use]
+    pub fn request<T: ApiRequest>(mut self) -> Self {
+        self.meta.request = Some(T::meta());
+        self.registers.push(T::register);
+        self
+    }
+
+    /// Appends the responses of `T`.
+    #[must_use]
+    pub fn response<T: ApiResponse>(mut self) -> Self {
+        self.meta.responses.responses.extend(T::meta().responses);
+        self.registers.push(T::register);
+        self
+    }
+}
+
+struct Operation {
+    builder: OperationBuilder,
+    ep: BoxEndpoint<'static, Response>,
+}
+
+/// A builder for constructing the API at runtime without the macros, such as
+/// for the plugin systems and the dynamically generated APIs.
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::make_sync, http::Method};
+/// use poem_openapi::{
+///     payload::PlainText, registry::MetaParamIn, ApiBuilder, OpenApiService, OperationBuilder,
+/// };
+///
+/// let api = ApiBuilder::new().operation(
+///     OperationBuilder::new(Method::GET, "/users/{id}")
+///         .summary("Get the user")
+///         .param::<i64>("id", MetaParamIn::Path)
+///         .response::<PlainText<String>>(),
+///     make_sync(|req| format!("user {}", req.path_param("id").unwrap_or_default())),
+/// );
+///
+/// let api_service = OpenApiService::new(api).title("Users");
+/// ```
+#[derive(Default)]
+pub struct ApiBuilder {
+    operations: Vec<Operation>,
+}
+
+impl ApiBuilder {
+    /// Create an `ApiBuilder`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends an operation handled by the endpoint.
+    #[must_use]
+    pub fn operation<E>(mut self, operation: OperationBuilder, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.operations.push(Operation {
+            builder: operation,
+            ep: ep.into_endpoint().map_to_response().boxed(),
+        });
+        self
+    }
+}
+
+/// Converts the OpenAPI path to the path of the route, such as `/users/{id}`
+/// to `/users/:id`.
+fn convert_path(path: &str) -> String {
+    path.split('/')
+        .map(
+            |s| match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(var) => format!(":{}", var),
+                None => s.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl OpenApi for ApiBuilder {
+    /// The metadata of the `ApiBuilder` is only known at runtime, see
+    /// [`OpenApi::instance_meta`].
+    fn meta() -> Vec<MetaApi> {
+        Vec::new()
+    }
+
+    fn register(_registry: &mut Registry) {}
+
+    fn add_routes(self, route: Route) -> Route {
+        self.add_routes_except(route, &RouteOptions::default())
+    }
+
+    fn add_routes_except(self, route: Route, options: &RouteOptions) -> Route {
+        let mut routes: Vec<(String, RouteMethod)> = Vec::new();
+        for Operation { builder, ep } in self.operations {
+            let operation_id = builder
+                .meta
+                .operation_id
+                .clone()
+                .or_else(|| options.operation_id(&builder.method, &builder.path, None));
+            if !__private::is_operation_enabled(&options.disabled, None, operation_id.as_deref()) {
+                continue;
+            }
+            let path = convert_path(&builder.path);
+            match routes.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, route_method)) => {
+                    *route_method = std::mem::take(route_method).method(builder.method, ep);
+                }
+                None => routes.push((path, RouteMethod::new().method(builder.method, ep))),
+            }
+        }
+        routes
+            .into_iter()
+            .fold(route, |route, (path, route_method)| {
+                route.at(path, route_method)
+            })
+    }
+
+    fn instance_meta(&self) -> Vec<MetaApi> {
+        let mut paths: Vec<MetaPath> = Vec::new();
+        for Operation { builder, .. } in &self.operations {
+            let meta = builder.meta.clone();
+            match paths.iter_mut().find(|path| path.path == builder.path) {
+                Some(path) => path.operations.push(meta),
+                None => paths.push(MetaPath {
+                    path: builder.path.clone(),
+                    operations: vec![meta],
+                }),
+            }
+        }
+        vec![MetaApi { paths }]
+    }
+
+    fn instance_register(&self, registry: &mut Registry) {
+        for Operation { builder, .. } in &self.operations {
+            for register in &builder.registers {
+                register(registry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_path() {
+        assert_eq!(convert_path("/"), "/");
+        assert_eq!(convert_path("/users/{id}"), "/users/:id");
+        assert_eq!(convert_path("/a/{b}/c/{d}"), "/a/:b/c/:d");
+    }
+}
@@ -0,0 +1,127 @@
+//! Captures the bodies of the requests and the responses for the
+//! [`ApiObserver`](crate::ApiObserver)s and the sampled logs, without reading
+//! them into memory before they are sent.
+
+use std::{
+    future::Future,
+    io::Result as IoResult,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Bytes, BytesMut};
+use poem::{
+    http::{header, HeaderMap, HeaderValue},
+    Body, Response,
+};
+use tokio::{
+    io::{AsyncRead, ReadBuf},
+    sync::oneshot,
+};
+
+/// The maximum size of a captured body, the rest of the body is sent but not
+/// captured.
+pub(crate) const MAX_CAPTURED_BODY_SIZE: usize = 64 * 1024;
+
+/// Returns `true` if the content type is a stream of messages or binary data,
+/// which are never captured.
+pub(crate) fn is_streaming(headers: &HeaderMap) -> bool {
+    let content_type = match headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(content_type) => content_type,
+        None => return false,
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        essence.as_str(),
+        "text/event-stream"
+            | "application/x-ndjson"
+            | "application/json-seq"
+            | "application/octet-stream"
+    ) || essence.starts_with("multipart/")
+}
+
+/// Wraps the `body` to copy the first [`MAX_CAPTURED_BODY_SIZE`] bytes while
+/// it is read, and returns the future of the copied bytes, which is resolved
+/// when the body is read to the end or dropped.
+pub(crate) fn capture_body(body: Body) -> (Body, impl Future<Output = Bytes>) {
+    let (tx, rx) = oneshot::channel();
+    let body = Body::from_async_read(CaptureReader {
+        inner: body.into_async_read(),
+        buf: BytesMut::new(),
+        tx: Some(tx),
+    });
+    (body, async move { rx.await.unwrap_or_default() })
+}
+
+/// Captures the body of the response, see [`capture_body`].
+///
+/// Returns `None` if the response is a stream, see [`is_streaming`].
+pub(crate) fn capture_response_body(resp: &mut Response) -> Option<impl Future<Output = Bytes>> {
+    if is_streaming(resp.headers()) {
+        return None;
+    }
+
+    let body = resp.take_body();
+    // the wrapped body does not know its size
+    if let Some(size) = body.exact_size() {
+        if !resp.headers().contains_key(header::CONTENT_LENGTH) {
+            resp.headers_mut()
+                .insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+        }
+    }
+    let (body, captured) = capture_body(body);
+    resp.set_body(body);
+    Some(captured)
+}
+
+struct CaptureReader<R> {
+    inner: R,
+    buf: BytesMut,
+    tx: Option<oneshot::Sender<Bytes>>,
+}
+
+impl<R> CaptureReader<R> {
+    fn finish(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            let _ = tx.send(self.buf.split().freeze());
+        }
+    }
+}
+
+impl<R> Drop for CaptureReader<R> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CaptureReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = &mut *self;
+        let filled = buf.filled().len();
+        let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+        match &res {
+            Poll::Ready(Ok(())) if buf.filled().len() > filled => {
+                let data = &buf.filled()[filled..];
+                let len = data
+                    .len()
+                    .min(MAX_CAPTURED_BODY_SIZE.saturating_sub(this.buf.len()));
+                this.buf.extend_from_slice(&data[..len]);
+            }
+            Poll::Ready(_) => this.finish(),
+            Poll::Pending => {}
+        }
+        res
+    }
+}
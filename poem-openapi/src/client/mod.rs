@@ -0,0 +1,423 @@
+//! Typed clients generated from the [`OpenApi`](crate::OpenApi) definitions.
+//!
+//! The `#[OpenApi(client = "UserApiClient")]` attribute generates a client
+//! type with an async method for each operation, the arguments and the
+//! return types are the same as the operations, so the services that call
+//! each other stay in sync with the server without generating code from the
+//! specification.
+//!
+//! The parameters are sent as declared, except the parameters in the
+//! `deepObject`, `spaceDelimited` and `pipeDelimited` styles, which are sent
+//! as the repeated query parameters. The Poem extractors, the authorization
+//! extractors and the response contexts are not arguments of the client
+//! methods, the credentials are usually set as the default headers of the
+//! [`reqwest::Client`].
+//!
+//! The `client` feature only supports the `http` URLs, enable the
+//! `client-rustls` feature to call the services over `https`, or enable a TLS
+//! feature of `reqwest` in the application.
+//!
+//! The request payloads must implement [`ClientRequest`], and the response
+//! types must implement [`ClientResponse`], which is implemented by the
+//! response types defined with `#[derive(ApiResponse)]` and the
+//! `#[oai(client)]` attribute.
+//!
+//! # Example
+//!
+//! ```
+//! use poem_openapi::{
+//!     client::Client,
+//!     payload::{Json, PlainText},
+//!     ApiResponse, Object, OpenApi,
+//! };
+//!
+//! #[derive(Object)]
+//! struct User {
+//!     id: i64,
+//!     name: String,
+//! }
+//!
+//! #[derive(ApiResponse)]
+//! #[oai(client)]
+//! enum GetUserResponse {
+//!     #[oai(status = 200)]
+//!     Ok(Json<User>),
+//!     #[oai(status = 404)]
+//!     NotFound,
+//! }
+//!
+//! struct UserApi;
+//!
+//! #[OpenApi(client = "UserApiClient")]
+//! impl UserApi {
+//!     #[oai(path = "/users/:id", method = "get")]
+//!     async fn get(&self, #[oai(name = "id", in = "path")] id: i64) -> GetUserResponse {
+//!         GetUserResponse::NotFound
+//!     }
+//!
+//!     #[oai(path = "/users", method = "post")]
+//!     async fn create(&self, user: Json<User>) -> PlainText<String> {
+//!         PlainText(user.0.name)
+//!     }
+//! }
+//!
+//! # async fn call() -> Result<(), poem_openapi::client::ClientError> {
+//! let client = UserApiClient::new(Client::new("http://users:3000/api"));
+//! match client.get(1).await? {
+//!     GetUserResponse::Ok(Json(user)) => println!("{}", user.name),
+//!     GetUserResponse::NotFound => println!("not found"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use bytes::Bytes;
+use poem::http::{header, Method, StatusCode};
+#[doc(hidden)]
+pub use reqwest;
+use reqwest::{RequestBuilder, Response};
+use serde_json::Value;
+
+use crate::{
+    payload::{Binary, Json, Payload, PlainText},
+    types::{ParseFromJSON, ParseFromParameter, ToJSON},
+};
+
+/// The HTTP client of the services, which is used by the generated clients.
+#[derive(Debug, Clone)]
+pub struct Client {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Client {
+    /// Create a client that sends the requests to `base_url`, e.g.
+    /// `http://users:3000/api`, which is where the service is nested.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Create a client with the configured [`reqwest::Client`], such as the
+    /// client with the default headers or the timeout.
+    pub fn with_client(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Returns the base url of the service.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    #[doc(hidden)]
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+    }
+
+    #[doc(hidden)]
+    pub async fn send<T: ClientResponse>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let resp = request.send().await.map_err(ClientError::Request)?;
+        T::from_client_response(resp).await
+    }
+}
+
+/// This type represents errors that occur when calling the operations with
+/// the generated clients.
+#[derive(Debug)]
+pub enum ClientError {
+    /// Failed to send the request or receive the response.
+    Request(reqwest::Error),
+
+    /// The status code of the response is not defined by the response type.
+    UnexpectedStatus {
+        /// The status code of the response.
+        status: StatusCode,
+
+        /// The body of the response.
+        body: String,
+    },
+
+    /// Failed to parse the response.
+    ParseResponse {
+        /// The reason for the error.
+        reason: String,
+    },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Request(err) => write!(f, "failed to send the request: {}", err),
+            ClientError::UnexpectedStatus { status, body } => {
+                write!(f, "unexpected status `{}`: {}", status, body)
+            }
+            ClientError::ParseResponse { reason } => {
+                write!(f, "failed to parse the response: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// Represents a payload that can be sent as the request body.
+pub trait ClientRequest {
+    /// Sets the body and the content type of the request.
+    fn into_request_body(self, request: RequestBuilder) -> RequestBuilder;
+}
+
+impl<T: ToJSON> ClientRequest for Json<T> {
+    fn into_request_body(self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header(header::CONTENT_TYPE, Self::CONTENT_TYPE)
+            .body(self.0.to_json().to_string())
+    }
+}
+
+impl<T: Into<String> + Send> ClientRequest for PlainText<T> {
+    fn into_request_body(self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header(header::CONTENT_TYPE, Self::CONTENT_TYPE)
+            .body(self.0.into())
+    }
+}
+
+impl<T: Into<Vec<u8>> + Send> ClientRequest for Binary<T> {
+    fn into_request_body(self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header(header::CONTENT_TYPE, Self::CONTENT_TYPE)
+            .body(self.0.into())
+    }
+}
+
+/// Represents a payload that can be parsed from the response body.
+#[poem::async_trait]
+pub trait ClientPayload: Sized {
+    /// Parse the payload from the body of the response, the status code has
+    /// been checked.
+    async fn from_response_body(resp: Response) -> Result<Self, ClientError>;
+}
+
+#[poem::async_trait]
+impl<T: ParseFromJSON> ClientPayload for Json<T> {
+    async fn from_response_body(resp: Response) -> Result<Self, ClientError> {
+        let data = resp.bytes().await.map_err(ClientError::Request)?;
+        let value = T::parse_from_json_bytes(&data).map_err(|err| ClientError::ParseResponse {
+            reason: err.into_message(),
+        })?;
+        Ok(Self(value))
+    }
+}
+
+#[poem::async_trait]
+impl ClientPayload for PlainText<String> {
+    async fn from_response_body(resp: Response) -> Result<Self, ClientError> {
+        Ok(Self(resp.text().await.map_err(ClientError::Request)?))
+    }
+}
+
+#[poem::async_trait]
+impl ClientPayload for Binary<Vec<u8>> {
+    async fn from_response_body(resp: Response) -> Result<Self, ClientError> {
+        Ok(Self(
+            resp.bytes().await.map_err(ClientError::Request)?.to_vec(),
+        ))
+    }
+}
+
+#[poem::async_trait]
+impl ClientPayload for Binary<Bytes> {
+    async fn from_response_body(resp: Response) -> Result<Self, ClientError> {
+        Ok(Self(resp.bytes().await.map_err(ClientError::Request)?))
+    }
+}
+
+/// Represents a response type that can be parsed from the response of the
+/// operation.
+#[poem::async_trait]
+pub trait ClientResponse: Sized {
+    /// Parse from the response.
+    async fn from_client_response(resp: Response) -> Result<Self, ClientError>;
+}
+
+#[poem::async_trait]
+impl<T: ClientPayload + Send> ClientResponse for T {
+    async fn from_client_response(resp: Response) -> Result<Self, ClientError> {
+        if !resp.status().is_success() {
+            return Err(unexpected_status(resp).await);
+        }
+        T::from_response_body(resp).await
+    }
+}
+
+#[poem::async_trait]
+impl ClientResponse for () {
+    async fn from_client_response(resp: Response) -> Result<Self, ClientError> {
+        if !resp.status().is_success() {
+            return Err(unexpected_status(resp).await);
+        }
+        Ok(())
+    }
+}
+
+#[poem::async_trait]
+impl ClientResponse for poem::Error {
+    async fn from_client_response(resp: Response) -> Result<Self, ClientError> {
+        let status = resp.status();
+        let reason = resp.text().await.map_err(ClientError::Request)?;
+        Ok(poem::Error::new(status).with_reason(reason))
+    }
+}
+
+/// The successful responses are parsed as `T`, and the others are parsed as
+/// `E`.
+#[poem::async_trait]
+impl<T: ClientResponse + Send, E: ClientResponse + Send> ClientResponse for Result<T, E> {
+    async fn from_client_response(resp: Response) -> Result<Self, ClientError> {
+        if resp.status().is_success() {
+            T::from_client_response(resp).await.map(Ok)
+        } else {
+            E::from_client_response(resp).await.map(Err)
+        }
+    }
+}
+
+#[doc(hidden)]
+pub async fn unexpected_status(resp: Response) -> ClientError {
+    let status = resp.status();
+    ClientError::UnexpectedStatus {
+        status,
+        body: resp.text().await.unwrap_or_default(),
+    }
+}
+
+/// Converts the value to the values of the parameter, the arrays have a value
+/// for each item, and `null` has no values.
+fn param_values<T: ToJSON>(value: &T) -> Vec<String> {
+    fn to_string(value: Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(s) => Some(s),
+            value => Some(value.to_string()),
+        }
+    }
+
+    match value.to_json() {
+        Value::Array(items) => items.into_iter().filter_map(to_string).collect(),
+        value => to_string(value).into_iter().collect(),
+    }
+}
+
+/// Percent-encodes the characters except the unreserved characters, and `/`
+/// if `keep_slash` is `true`.
+fn percent_encode(s: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+#[doc(hidden)]
+pub fn path_param<T: ToJSON>(value: &T, wildcard: bool) -> String {
+    let values = param_values(value)
+        .iter()
+        .map(|value| percent_encode(value, wildcard))
+        .collect::<Vec<_>>();
+    values.join(if wildcard { "/" } else { "," })
+}
+
+#[doc(hidden)]
+pub fn query_param<T: ToJSON>(request: RequestBuilder, name: &str, value: &T) -> RequestBuilder {
+    let pairs = param_values(value)
+        .into_iter()
+        .map(|value| (name, value))
+        .collect::<Vec<_>>();
+    if pairs.is_empty() {
+        return request;
+    }
+    request.query(&pairs)
+}
+
+#[doc(hidden)]
+pub fn header_param<T: ToJSON>(request: RequestBuilder, name: &str, value: &T) -> RequestBuilder {
+    let values = param_values(value);
+    if values.is_empty() {
+        return request;
+    }
+    request.header(name, values.join(","))
+}
+
+#[doc(hidden)]
+pub fn cookie_param<T: ToJSON>(cookies: &mut Vec<String>, name: &str, value: &T) {
+    let values = param_values(value);
+    if !values.is_empty() {
+        cookies.push(format!("{}={}", name, values.join(",")));
+    }
+}
+
+#[doc(hidden)]
+pub fn with_cookies(request: RequestBuilder, cookies: &[String]) -> RequestBuilder {
+    if cookies.is_empty() {
+        return request;
+    }
+    request.header(header::COOKIE, cookies.join("; "))
+}
+
+#[doc(hidden)]
+pub fn response_header<T: ParseFromParameter>(
+    resp: &Response,
+    name: &'static str,
+) -> Result<T, ClientError> {
+    let value = resp
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok());
+    T::parse_from_parameter(value).map_err(|err| ClientError::ParseResponse {
+        reason: format!("failed to parse header `{}`: {}", name, err.into_message()),
+    })
+}
+
+#[doc(hidden)]
+pub fn response_cookie<T: ParseFromParameter>(
+    resp: &Response,
+    name: &'static str,
+) -> Result<T, ClientError> {
+    let value = resp
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next()?.split_once('='))
+        .find(|(cookie_name, _)| cookie_name.trim() == name)
+        .map(|(_, value)| value.trim());
+    T::parse_from_parameter(value).map_err(|err| ClientError::ParseResponse {
+        reason: format!("failed to parse cookie `{}`: {}", name, err.into_message()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params() {
+        assert_eq!(path_param(&"a b/c", false), "a%20b%2Fc");
+        assert_eq!(path_param(&"a b/c", true), "a%20b/c");
+        assert_eq!(path_param(&vec![1, 2, 3], false), "1,2,3");
+        assert_eq!(path_param(&vec!["a", "b"], true), "a/b");
+        assert_eq!(param_values(&Some(true)), vec!["true"]);
+        assert!(param_values(&Option::<i32>::None).is_empty());
+    }
+}
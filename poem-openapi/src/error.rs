@@ -2,6 +2,7 @@ use poem::{http::StatusCode, Error};
 
 /// This type represents errors that occur when parsing the HTTP request.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ParseRequestError {
     /// Failed to parse a parameter.
     ParseParam {
@@ -18,6 +19,23 @@ pub enum ParseRequestError {
         reason: String,
     },
 
+    /// The client closed the connection or the connection was reset before
+    /// the request body was completely received.
+    ///
+    /// It is converted to the status code `499 Client Closed Request`, so the
+    /// logging and the metrics can distinguish it from the malformed request
+    /// bodies, the response will never be received by the client.
+    ClientDisconnected {
+        /// The reason for the error.
+        reason: String,
+    },
+
+    /// The request body exceeds the size limit.
+    PayloadTooLarge {
+        /// The reason for the error.
+        reason: String,
+    },
+
     /// The `Content-Type` requested by the client is not supported.
     ContentTypeNotSupported {
         /// The `Content-Type` header requested by the client.
@@ -52,6 +70,14 @@ impl From<ParseRequestError> for poem::Error {
                 .with_reason(format!("failed to parse param `{}`: {}", name, reason)),
             ParseRequestError::ParseRequestBody { reason } => Error::new(StatusCode::BAD_REQUEST)
                 .with_reason(format!("failed to parse request body: {}", reason)),
+            ParseRequestError::ClientDisconnected { reason } => {
+                Error::new(StatusCode::from_u16(499).unwrap())
+                    .with_reason(format!("client disconnected: {}", reason))
+            }
+            ParseRequestError::PayloadTooLarge { reason } => {
+                Error::new(StatusCode::PAYLOAD_TOO_LARGE)
+                    .with_reason(format!("payload too large: {}", reason))
+            }
             ParseRequestError::ContentTypeNotSupported { content_type } => {
                 Error::new(StatusCode::METHOD_NOT_ALLOWED).with_reason(format!(
                     "the content type `{}` is not supported.",
@@ -68,3 +94,10 @@ impl From<ParseRequestError> for poem::Error {
         }
     }
 }
+
+/// Returns `true` if the IO error is caused by the data of a multipart field
+/// exceeding the size limit.
+pub(crate) fn is_field_size_exceeded_io_error(err: &std::io::Error) -> bool {
+    err.get_ref()
+        .map_or(false, |err| err.is::<poem::error::FieldSizeExceeded>())
+}
@@ -0,0 +1,392 @@
+#[cfg(feature = "remote")]
+mod remote;
+
+use poem::{endpoint::BoxEndpoint, Endpoint, IntoEndpoint, Response, Route};
+#[cfg(feature = "remote")]
+pub use remote::RemoteService;
+use serde_json::{Map, Value};
+
+#[cfg(feature = "swagger-ui")]
+use crate::ui::create_ui_endpoint;
+use crate::{
+    openapi::SpecEndpoint,
+    registry::{MetaInfo, MetaServer},
+    OpenApi, OpenApiService, OpenApiVersion,
+};
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+struct GatewayService {
+    name: String,
+    prefix: String,
+    spec: Value,
+    ep: BoxEndpoint<'static, Response>,
+}
+
+/// Mounts several independent [`OpenApiService`]s under prefixes, and serves
+/// a merged specification of them.
+///
+/// With the `remote` feature, the services running in other processes can
+/// also be mounted by [`OpenApiGateway::remote_service`], the requests to them
+/// are forwarded to the upstreams.
+///
+/// The schemas and the security schemes of each service are prefixed with the
+/// name of the service, e.g. the `User` schema of the `users` service is named
+/// `users.User` in the merged specification, so the services can define the
+/// types with the same names.
+///
+/// # Example
+///
+/// ```
+/// use poem::Route;
+/// use poem_openapi::{payload::PlainText, OpenApi, OpenApiGateway, OpenApiService};
+///
+/// struct UserApi;
+///
+/// #[OpenApi]
+/// impl UserApi {
+///     #[oai(path = "/", method = "get")]
+///     async fn list(&self) -> PlainText<&'static str> {
+///         PlainText("users")
+///     }
+/// }
+///
+/// struct OrderApi;
+///
+/// #[OpenApi]
+/// impl OrderApi {
+///     #[oai(path = "/", method = "get")]
+///     async fn list(&self) -> PlainText<&'static str> {
+///         PlainText("orders")
+///     }
+/// }
+///
+/// let gateway = OpenApiGateway::new()
+///     .title("Shop")
+///     .service("users", "/users", OpenApiService::new(UserApi))
+///     .service("orders", "/orders", OpenApiService::new(OrderApi));
+/// let spec = gateway.spec_endpoint();
+///
+/// let app = Route::new().nest("/api", gateway).nest("/spec", spec);
+/// ```
+#[derive(Default)]
+pub struct OpenApiGateway {
+    info: Option<MetaInfo>,
+    servers: Vec<MetaServer>,
+    services: Vec<GatewayService>,
+}
+
+impl OpenApiGateway {
+    /// Create an OpenAPI gateway.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the title of the merged specification.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.info.get_or_insert_with(Default::default).title = Some(title.into());
+        self
+    }
+
+    /// Sets the description of the merged specification.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.info.get_or_insert_with(Default::default).description = Some(description.into());
+        self
+    }
+
+    /// Sets the version of the merged specification.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.info.get_or_insert_with(Default::default).version = Some(version.into());
+        self
+    }
+
+    /// Appends a server to the merged specification.
+    #[must_use]
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(MetaServer {
+            url: url.into(),
+            description: None,
+        });
+        self
+    }
+
+    /// Mounts the `service` under `prefix`, the schemas of the service are
+    /// prefixed with `name` in the merged specification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid component name, which only contains
+    /// `a-z`, `A-Z`, `0-9`, `.`, `-` and `_`, or it has been used by another
+    /// service, or a path of the service under `prefix` conflicts with the
+    /// paths of another service.
+    #[must_use]
+    pub fn service<T: OpenApi>(
+        self,
+        name: impl Into<String>,
+        prefix: impl AsRef<str>,
+        service: OpenApiService<T>,
+    ) -> Self {
+        let spec = serde_json::from_str(&service.spec()).unwrap();
+        self.add_service(name.into(), prefix.as_ref(), spec, service.into_endpoint())
+    }
+
+    /// Fetches the specification of the remote `service`, and forwards the
+    /// requests under `prefix` to it.
+    ///
+    /// The schemas of the service are prefixed with `name` in the merged
+    /// specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the specification cannot be fetched from the
+    /// upstream or read from the cache file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is invalid, see [`OpenApiGateway::service`].
+    #[cfg(feature = "remote")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "remote")))]
+    pub async fn remote_service(
+        self,
+        name: impl Into<String>,
+        prefix: impl AsRef<str>,
+        service: RemoteService,
+    ) -> std::io::Result<Self> {
+        let (spec, proxy) = service.fetch().await?;
+        Ok(self.add_service(
+            name.into(),
+            prefix.as_ref(),
+            spec,
+            poem::EndpointExt::boxed(proxy),
+        ))
+    }
+
+    fn add_service(
+        mut self,
+        name: String,
+        prefix: &str,
+        mut spec: Value,
+        ep: BoxEndpoint<'static, Response>,
+    ) -> Self {
+        assert!(
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_'),
+            "invalid service name: `{}`",
+            name
+        );
+        assert!(
+            self.services.iter().all(|service| service.name != name),
+            "duplicate service name: `{}`",
+            name
+        );
+
+        let mut prefix = prefix.trim_end_matches('/').to_string();
+        if !prefix.is_empty() && !prefix.starts_with('/') {
+            prefix.insert(0, '/');
+        }
+
+        for path in spec_paths(&prefix, &spec) {
+            let key = path_key(&path);
+            if let Some(other) = self.services.iter().find(|service| {
+                spec_paths(&service.prefix, &service.spec).any(|path| path_key(&path) == key)
+            }) {
+                panic!(
+                    "the path `{}` of the service `{}` conflicts with the service `{}`",
+                    path, name, other.name
+                );
+            }
+        }
+
+        rename_schema_refs(&mut spec, &name);
+        rename_security_requirements(&mut spec, &name);
+        self.services.push(GatewayService {
+            name,
+            prefix,
+            spec,
+            ep,
+        });
+        self
+    }
+
+    /// Create the Swagger UI endpoint of the merged specification.
+    #[must_use]
+    #[cfg(feature = "swagger-ui")]
+    pub fn swagger_ui(&self) -> impl Endpoint {
+        create_ui_endpoint(self.create_spec_endpoint())
+    }
+
+    /// Create an endpoint to serve the merged specification.
+    pub fn spec_endpoint(&self) -> impl Endpoint {
+        self.create_spec_endpoint()
+    }
+
+    fn create_spec_endpoint(&self) -> SpecEndpoint {
+        SpecEndpoint::new(
+            "application/json",
+            self.spec(),
+            self.info.as_ref().and_then(|info| info.version.clone()),
+        )
+    }
+
+    /// Returns the merged specification.
+    pub fn spec(&self) -> String {
+        let mut tags = Vec::<Value>::new();
+        let mut paths = Map::new();
+        let mut schemas = Map::new();
+        let mut security_schemes = Map::new();
+
+        for service in &self.services {
+            for tag in service.spec["tags"].as_array().into_iter().flatten() {
+                if tags.iter().all(|exists| exists["name"] != tag["name"]) {
+                    tags.push(tag.clone());
+                }
+            }
+
+            for (path, item) in service.spec["paths"].as_object().into_iter().flatten() {
+                paths.insert(merged_path(&service.prefix, path), item.clone());
+            }
+
+            let components = &service.spec["components"];
+            for (name, schema) in components["schemas"].as_object().into_iter().flatten() {
+                schemas.insert(format!("{}.{}", service.name, name), schema.clone());
+            }
+            for (name, scheme) in components["securitySchemes"]
+                .as_object()
+                .into_iter()
+                .flatten()
+            {
+                security_schemes.insert(format!("{}.{}", service.name, name), scheme.clone());
+            }
+        }
+
+        let doc = serde_json::json!({
+            "openapi": OpenApiVersion::V3_0.as_str(),
+            "info": self.info,
+            "servers": self.servers,
+            "tags": tags,
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": security_schemes,
+            },
+        });
+        serde_json::to_string_pretty(&doc).unwrap()
+    }
+}
+
+impl IntoEndpoint for OpenApiGateway {
+    type Endpoint = Route;
+
+    fn into_endpoint(self) -> Self::Endpoint {
+        self.services
+            .into_iter()
+            .fold(Route::new(), |route, service| {
+                route.nest(service.prefix, service.ep)
+            })
+    }
+}
+
+/// Returns the path of an operation of the service nested under `prefix`.
+fn merged_path(prefix: &str, path: &str) -> String {
+    match path {
+        "/" if !prefix.is_empty() => prefix.to_string(),
+        _ => format!("{}{}", prefix, path),
+    }
+}
+
+/// Returns the paths of the specification under `prefix`.
+fn spec_paths<'a>(prefix: &'a str, spec: &'a Value) -> impl Iterator<Item = String> + 'a {
+    spec["paths"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(move |(path, _)| merged_path(prefix, path))
+}
+
+/// Returns the path with the names of the parameters removed, the paths with
+/// the same key match the same requests.
+fn path_key(path: &str) -> String {
+    let mut key = String::with_capacity(path.len());
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                key.push(c);
+            }
+            '}' => {
+                in_param = false;
+                key.push(c);
+            }
+            _ if in_param => {}
+            _ => key.push(c),
+        }
+    }
+    key
+}
+
+/// Prefixes the names of the security schemes in the security requirements
+/// of the operations with `namespace`.
+fn rename_security_requirements(spec: &mut Value, namespace: &str) {
+    let operations = spec
+        .get_mut("paths")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|paths| paths.values_mut())
+        .filter_map(Value::as_object_mut)
+        .flat_map(|item| item.values_mut());
+    for operation in operations {
+        let requirements = operation
+            .get_mut("security")
+            .and_then(Value::as_array_mut)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_object_mut);
+        for requirement in requirements {
+            *requirement = std::mem::take(requirement)
+                .into_iter()
+                .map(|(name, scopes)| (format!("{}.{}", namespace, name), scopes))
+                .collect();
+        }
+    }
+}
+
+/// Prefixes the names of the schemas referenced by `$ref` and the discriminator
+/// mappings with `namespace`.
+fn rename_schema_refs(value: &mut Value, namespace: &str) {
+    let rename = |s: &mut String| {
+        if let Some(name) = s.strip_prefix(SCHEMA_REF_PREFIX) {
+            *s = format!("{}{}.{}", SCHEMA_REF_PREFIX, namespace, name);
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if key == "$ref" => rename(s),
+                    Value::Object(mapping) if key == "mapping" => {
+                        for value in mapping.values_mut() {
+                            if let Value::String(s) = value {
+                                rename(s);
+                            }
+                        }
+                    }
+                    _ => rename_schema_refs(value, namespace),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                rename_schema_refs(value, namespace);
+            }
+        }
+        _ => {}
+    }
+}
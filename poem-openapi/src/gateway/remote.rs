@@ -0,0 +1,162 @@
+use std::{
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use poem::{
+    endpoint::Proxy,
+    error::ReadBodyError,
+    http::{uri::Scheme, Uri},
+    Endpoint, Request,
+};
+use serde_json::Value;
+
+fn remote_error(err: impl ToString) -> IoError {
+    IoError::new(
+        ErrorKind::Other,
+        format!("remote service: {}", err.to_string()),
+    )
+}
+
+/// A service running in another process, whose specification is fetched by
+/// [`OpenApiGateway::remote_service`](crate::OpenApiGateway::remote_service).
+///
+/// The requests are forwarded by [`Proxy`], see it for the forwarded headers
+/// and the timeouts.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::RemoteService;
+///
+/// let service = RemoteService::new("http://users:3000")
+///     .spec_path("/api/spec")
+///     .cache_path("users-spec.json");
+/// ```
+pub struct RemoteService {
+    upstream: String,
+    spec_path: String,
+    cache_path: Option<PathBuf>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+}
+
+impl RemoteService {
+    /// Create a remote service, the requests are forwarded to `upstream`,
+    /// e.g. `http://users:3000`.
+    ///
+    /// Both `http` and `https` are supported.
+    pub fn new(upstream: impl Into<String>) -> Self {
+        Self {
+            upstream: upstream.into().trim_end_matches('/').to_string(),
+            spec_path: "/openapi.json".to_string(),
+            cache_path: None,
+            connect_timeout: None,
+            timeout: None,
+        }
+    }
+
+    /// Sets the path of the specification on the upstream, default is
+    /// `/openapi.json`.
+    #[must_use]
+    pub fn spec_path(self, path: impl Into<String>) -> Self {
+        Self {
+            spec_path: path.into(),
+            ..self
+        }
+    }
+
+    /// Sets the file to cache the specification.
+    ///
+    /// The fetched specification is written to this file, and is used when
+    /// the upstream is unavailable at startup.
+    #[must_use]
+    pub fn cache_path(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_path: Some(path.into()),
+            ..self
+        }
+    }
+
+    /// Sets the timeout of connecting to the upstream, see
+    /// [`Proxy::connect_timeout`].
+    #[must_use]
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        Self {
+            connect_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sets the timeout of waiting for the responses from the upstream, see
+    /// [`Proxy::timeout`].
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Fetches the specification and returns the endpoint to forward the
+    /// requests to the upstream.
+    pub(crate) async fn fetch(self) -> IoResult<(Value, Proxy)> {
+        let upstream = Uri::from_str(&self.upstream).map_err(remote_error)?;
+        let scheme = upstream.scheme();
+        if (scheme != Some(&Scheme::HTTP) && scheme != Some(&Scheme::HTTPS))
+            || upstream.authority().is_none()
+        {
+            return Err(remote_error(format!(
+                "invalid upstream `{}`",
+                self.upstream
+            )));
+        }
+
+        let mut proxy = Proxy::new(&self.upstream);
+        if let Some(timeout) = self.connect_timeout {
+            proxy = proxy.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            proxy = proxy.timeout(timeout);
+        }
+
+        let spec = match fetch_spec(&proxy, &self.spec_path).await {
+            Ok(data) => {
+                if let Some(cache_path) = &self.cache_path {
+                    tokio::fs::write(cache_path, &data).await?;
+                }
+                data
+            }
+            Err(err) => match &self.cache_path {
+                Some(cache_path) => match tokio::fs::read(cache_path).await {
+                    Ok(data) => data,
+                    Err(_) => return Err(err),
+                },
+                None => return Err(err),
+            },
+        };
+
+        let spec = serde_json::from_slice(&spec).map_err(remote_error)?;
+        Ok((spec, proxy))
+    }
+}
+
+async fn fetch_spec(proxy: &Proxy, spec_path: &str) -> IoResult<Vec<u8>> {
+    let req = Request::builder()
+        .uri(Uri::from_str(spec_path).map_err(remote_error)?)
+        .finish();
+    let resp = proxy.call(req).await;
+    if !resp.status().is_success() {
+        return Err(remote_error(format!(
+            "failed to fetch the specification: {}",
+            resp.status()
+        )));
+    }
+    match resp.into_body().into_vec().await {
+        Ok(data) => Ok(data),
+        Err(ReadBodyError::Io(err)) => Err(err),
+        Err(err) => Err(remote_error(format!("{:?}", err))),
+    }
+}
@@ -0,0 +1,366 @@
+use std::{any::Any, borrow::Cow, sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use poem::{
+    http::{Method, StatusCode},
+    middleware::RequestKey,
+    Request, Result,
+};
+use serde_json::Value;
+
+type ParamValue<'a> = &'a (dyn Any + Send + Sync);
+
+/// The parsed parameters and the principal of an operation, which are passed
+/// to the [`OperationHook`].
+pub struct OperationContext<'a> {
+    method: Method,
+    path: &'static str,
+    params: Vec<(&'static str, ParamValue<'a>)>,
+    principal: Option<ParamValue<'a>>,
+}
+
+impl<'a> OperationContext<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        method: Method,
+        path: &'static str,
+        params: Vec<(&'static str, ParamValue<'a>)>,
+        principal: Option<ParamValue<'a>>,
+    ) -> Self {
+        Self {
+            method,
+            path,
+            params,
+            principal,
+        }
+    }
+
+    /// Returns the method of the operation.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the path of the operation, e.g. `/users/{id}`.
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// Returns the value of the parameter, or `None` if the parameter does not
+    /// exist or it is not of type `T`.
+    ///
+    /// The parameters of the types that borrow from the request, such as
+    /// `&str`, are not available.
+    pub fn param<T: Any>(&self, name: &str) -> Option<&T> {
+        self.params
+            .iter()
+            .find(|(param_name, _)| *param_name == name)
+            .and_then(|(_, value)| value.downcast_ref())
+    }
+
+    /// Returns the names of the parameters.
+    pub fn param_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.params.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns the value parsed by the security scheme of the operation, or
+    /// `None` if the operation has no security scheme or it is not of type
+    /// `T`.
+    pub fn principal<T: Any>(&self) -> Option<&T> {
+        self.principal.and_then(|value| value.downcast_ref())
+    }
+}
+
+/// The information of the operation that handles the request.
+///
+/// It is added to the extensions of the request as `Arc<OperationInfo>`
+/// before the middlewares of the operation, such as the `transform` functions
+/// of the operation and its tags, are called, so they can group the requests
+/// by the operation, see [`OperationKey`].
+#[derive(Debug, Clone)]
+pub struct OperationInfo {
+    name: &'static str,
+    operation_id: Option<Cow<'static, str>>,
+    method: Method,
+    path: &'static str,
+}
+
+impl OperationInfo {
+    #[doc(hidden)]
+    pub fn new(
+        name: &'static str,
+        operation_id: Option<Cow<'static, str>>,
+        method: Method,
+        path: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            operation_id,
+            method,
+            path,
+        }
+    }
+
+    /// Returns the name of the method that implements the operation.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the operation id specified by the `operation_id` attribute, or
+    /// generated by the
+    /// [`OpenApiService::operation_id_naming`](crate::OpenApiService::operation_id_naming).
+    pub fn operation_id(&self) -> Option<&str> {
+        self.operation_id.as_deref()
+    }
+
+    /// Returns the method of the operation.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the path of the operation, e.g. `/users/{id}`.
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+}
+
+/// A [`RequestKey`] that groups the requests by the operation, the key is the
+/// operation id if it is specified, otherwise the method and the path, such as
+/// `GET /users/{id}`.
+///
+/// Combine it with other keys to limit the requests of each client for each
+/// operation.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     middleware::{ClientIpKey, RateLimit},
+///     storage::MemoryStorage,
+///     Endpoint, EndpointExt,
+/// };
+/// use poem_openapi::{payload::PlainText, OpenApi, OperationKey};
+///
+/// fn rate_limit(ep: impl Endpoint) -> impl Endpoint {
+///     ep.with(
+///         RateLimit::new(MemoryStorage::new(), 10, Duration::from_secs(60))
+///             .request_key((OperationKey, ClientIpKey)),
+///     )
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/search", method = "get", transform = "rate_limit")]
+///     async fn search(&self) -> PlainText<&'static str> {
+///         PlainText("result")
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct OperationKey;
+
+impl RequestKey for OperationKey {
+    fn request_key(&self, req: &Request) -> Option<String> {
+        let info = req.extensions().get::<Arc<OperationInfo>>()?;
+        Some(match &info.operation_id {
+            Some(operation_id) => operation_id.to_string(),
+            None => format!("{} {}", info.method, info.path),
+        })
+    }
+}
+
+/// Represents a hook that is called before the operations.
+///
+/// The hook is called after the parameters and the security scheme are
+/// parsed, and before the request body is parsed and the operation is called,
+/// so that the checks shared by many operations, such as whether the resource
+/// in the path belongs to the tenant of the principal, can be implemented
+/// once. Use [`OpenApiService::hook`](crate::OpenApiService::hook) to add a
+/// hook to the operations defined by the [`OpenApi`](crate::OpenApi) macro.
+///
+/// # Example
+///
+/// ```
+/// use poem::{http::StatusCode, Error, Request, Result};
+/// use poem_openapi::{
+///     auth::ApiKey, payload::PlainText, OpenApi, OpenApiService, OperationContext,
+///     OperationHook, SecurityScheme,
+/// };
+///
+/// #[derive(SecurityScheme)]
+/// #[oai(type = "api_key", key_name = "X-Tenant", in = "header")]
+/// struct TenantAuth(ApiKey);
+///
+/// struct CheckTenant;
+///
+/// #[poem::async_trait]
+/// impl OperationHook for CheckTenant {
+///     async fn before_operation(&self, _req: &Request, ctx: &OperationContext<'_>) -> Result<()> {
+///         let tenant = ctx.principal::<TenantAuth>();
+///         let tenant_id = ctx.param::<String>("tenant_id");
+///         match (tenant, tenant_id) {
+///             (Some(tenant), Some(tenant_id)) if &tenant.0.key != tenant_id => {
+///                 Err(Error::new(StatusCode::FORBIDDEN))
+///             }
+///             _ => Ok(()),
+///         }
+///     }
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/tenants/:tenant_id/report", method = "get")]
+///     async fn report(
+///         &self,
+///         #[oai(auth)] _auth: TenantAuth,
+///         #[oai(name = "tenant_id", in = "path")] tenant_id: String,
+///     ) -> PlainText<String> {
+///         PlainText(format!("report of {}", tenant_id))
+///     }
+/// }
+///
+/// let api_service = OpenApiService::new(Api).hook(CheckTenant);
+/// ```
+#[poem::async_trait]
+pub trait OperationHook: Send + Sync + 'static {
+    /// Called before the operation, returns an error to reject the request.
+    async fn before_operation(&self, req: &Request, ctx: &OperationContext<'_>) -> Result<()>;
+}
+
+/// The completed operation passed to the [`ApiObserver`].
+pub struct OperationEvent<'a> {
+    pub(crate) info: &'a OperationInfo,
+    pub(crate) params: &'a [(&'static str, Value)],
+    pub(crate) status: StatusCode,
+    pub(crate) latency: Duration,
+    pub(crate) request_body: Option<&'a Value>,
+    pub(crate) response_body: Option<&'a Bytes>,
+}
+
+impl<'a> OperationEvent<'a> {
+    /// Returns the information of the operation.
+    pub fn info(&self) -> &'a OperationInfo {
+        self.info
+    }
+
+    /// Returns the parsed parameters converted to JSON.
+    ///
+    /// The parameters whose types do not implement
+    /// [`ToJSON`](crate::types::ToJSON) are not available, and the values of
+    /// the [`Password`](crate::types::Password) parameters are masked.
+    pub fn params(&self) -> &'a [(&'static str, Value)] {
+        self.params
+    }
+
+    /// Returns the value of the parameter converted to JSON, or `None` if the
+    /// parameter is not available.
+    pub fn param(&self, name: &str) -> Option<&'a Value> {
+        self.params
+            .iter()
+            .find(|(param_name, _)| *param_name == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the status of the response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the time elapsed from when the operation received the request
+    /// to when the response was created.
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Returns the parsed [`Json`](crate::payload::Json) request body converted
+    /// to JSON, so the fields with the `mask` attribute are masked.
+    ///
+    /// It is `None` unless [`ApiObserver::capture_bodies`] returns `true` for
+    /// the operation, or if the request body is not a `Json` payload.
+    pub fn request_body(&self) -> Option<&'a Value> {
+        self.request_body
+    }
+
+    /// Returns the body of the response.
+    ///
+    /// It is `None` unless [`ApiObserver::capture_bodies`] returns `true` for
+    /// the operation. At most 64KiB of the body is captured, and the bodies of
+    /// the streaming responses, such as `text/event-stream`,
+    /// `application/x-ndjson` or `application/octet-stream`, are never
+    /// captured.
+    pub fn response_body(&self) -> Option<&'a Bytes> {
+        self.response_body
+    }
+}
+
+/// Represents an observer of the operations, which is called after each
+/// operation with its parameters, the status and the latency.
+///
+/// The parameters and the request body are captured after they are parsed, so
+/// the audit logging and the analytics can be implemented without wrapping
+/// every operation or parsing the requests again in a middleware. Use
+/// [`OpenApiService::observer`](crate::OpenApiService::observer) to add an
+/// observer to the operations defined by the [`OpenApi`](crate::OpenApi)
+/// macro.
+///
+/// The observers are called concurrently in a spawned task, after the
+/// response body is sent if the bodies are captured, so they do not delay the
+/// responses.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::PlainText, ApiObserver, OpenApi, OpenApiService, OperationEvent,
+/// };
+///
+/// struct AuditLog;
+///
+/// #[poem::async_trait]
+/// impl ApiObserver for AuditLog {
+///     async fn on_operation(&self, event: &OperationEvent<'_>) {
+///         tracing::info!(
+///             operation = event.info().name(),
+///             user = ?event.param("user"),
+///             status = %event.status(),
+///             latency = ?event.latency(),
+///             "operation completed"
+///         );
+///     }
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users/:user", method = "delete")]
+///     async fn delete_user(
+///         &self,
+///         #[oai(name = "user", in = "path")] user: String,
+///     ) -> PlainText<String> {
+///         PlainText(format!("{} deleted", user))
+///     }
+/// }
+///
+/// let api_service = OpenApiService::new(Api).observer(AuditLog);
+/// ```
+#[poem::async_trait]
+pub trait ApiObserver: Send + Sync + 'static {
+    /// Returns `true` if the bodies of the request and the response of the
+    /// operation should be captured, the default is `false`.
+    ///
+    /// The response body is copied while it is sent, see
+    /// [`OperationEvent::response_body`].
+    #[allow(unused_variables)]
+    fn capture_bodies(&self, info: &OperationInfo) -> bool {
+        false
+    }
+
+    /// Called after the operation.
+    async fn on_operation(&self, event: &OperationEvent<'_>);
+}
@@ -28,7 +28,22 @@
 //! | Feature    | Description                      | Default enabled |
 //! | ---------- | -------------------------------- | --------------- |
 //! | chrono     | Integrate with the [`chrono` crate](https://crates.io/crates/chrono). | :x: |
-//! | swagger-ui | Add swagger UI support  | :heavy_check_mark: |
+//! | swagger-ui | Add swagger UI support, the assets are embedded in the binary, so it works offline | :heavy_check_mark: |
+//! | charset    | Transcode the `PlainText` request body according to the `charset` of the `Content-Type` | :x: |
+//! | remote     | Mount the remote services to `OpenApiGateway` | :x: |
+//! | client     | Generate the typed clients of the APIs with the [`reqwest` crate](https://crates.io/crates/reqwest), see the [`client`] module | :x: |
+//! | client-rustls | Enables the `client` feature and supports the `https` URLs with [`rustls`](https://crates.io/crates/rustls) | :x: |
+//! | yaml       | Serve the specification in YAML format | :x: |
+//! | compression | Serve the gzipped specification to the clients that accept it | :x: |
+//! | bigint     | Integrate with the [`num-bigint` crate](https://crates.io/crates/num-bigint), the integers are encoded as strings. | :x: |
+//! | url        | Integrate with the [`url` crate](https://crates.io/crates/url), and add the `Hostname` type, the internationalized domain names are normalized to punycode. | :x: |
+//!
+//! ## WebAssembly
+//!
+//! When compiling for `wasm32`, only the [`types`] module and the `Object`,
+//! `Enum` and `OneOf` macros are available, so the frontend can share the
+//! models and their validators with the backend. Everything that depends on
+//! `Poem` is excluded.
 //!
 //! ## Example
 //!
@@ -88,48 +103,110 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs)]
 
+#[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod __private;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod auth;
+#[cfg(not(target_arch = "wasm32"))]
 mod base;
+#[cfg(not(target_arch = "wasm32"))]
+mod builder;
+#[cfg(not(target_arch = "wasm32"))]
+mod capture;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub mod client;
+#[cfg(not(target_arch = "wasm32"))]
 mod error;
+#[cfg(not(target_arch = "wasm32"))]
+mod gateway;
+#[cfg(not(target_arch = "wasm32"))]
+mod hook;
+#[cfg(not(target_arch = "wasm32"))]
 mod openapi;
 #[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
 pub mod param;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod payload;
 #[doc(hidden)]
 pub mod registry;
+#[cfg(not(target_arch = "wasm32"))]
+mod response_context;
+#[cfg(not(target_arch = "wasm32"))]
+mod server_ext;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod spec_compat;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod test;
 pub mod types;
 #[doc(hidden)]
-#[cfg(feature = "swagger-ui")]
+#[cfg(all(feature = "swagger-ui", not(target_arch = "wasm32")))]
 pub mod ui;
 #[doc(hidden)]
 pub mod validation;
 
-pub use base::{ApiRequest, ApiResponse, CombinedAPI, OAuthScopes, OpenApi, SecurityScheme, Tags};
+#[cfg(not(target_arch = "wasm32"))]
+pub use base::{
+    ApiExtractor, ApiRequest, ApiResponse, CombinedAPI, OAuthScopes, OpenApi, SecurityScheme, Tags,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use builder::{ApiBuilder, OperationBuilder};
+#[cfg(not(target_arch = "wasm32"))]
 pub use error::ParseRequestError;
-pub use openapi::OpenApiService;
+#[cfg(not(target_arch = "wasm32"))]
+pub use gateway::OpenApiGateway;
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+pub use gateway::RemoteService;
+#[cfg(not(target_arch = "wasm32"))]
+pub use hook::{
+    ApiObserver, OperationContext, OperationEvent, OperationHook, OperationInfo, OperationKey,
+};
+#[doc(hidden)]
+pub use once_cell;
+#[cfg(not(target_arch = "wasm32"))]
+pub use openapi::{
+    DuplicateQueryKeys, OpenApiService, OpenApiVersion, OperationIdContext, OperationIdNaming,
+};
 #[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem;
+#[doc = include_str!("docs/extractor.md")]
+#[cfg(not(target_arch = "wasm32"))]
+pub use poem_openapi_derive::ApiExtractor;
 #[doc = include_str!("docs/request.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::ApiRequest;
 #[doc = include_str!("docs/response.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::ApiResponse;
 #[doc = include_str!("docs/enum.md")]
 pub use poem_openapi_derive::Enum;
 #[doc = include_str!("docs/multipart.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::Multipart;
 #[doc = include_str!("docs/oauth_scopes.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::OAuthScopes;
 #[doc = include_str!("docs/object.md")]
 pub use poem_openapi_derive::Object;
 #[doc = include_str!("docs/oneof.md")]
 pub use poem_openapi_derive::OneOf;
 #[doc = include_str!("docs/openapi.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::OpenApi;
 #[doc = include_str!("docs/security_scheme.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::SecurityScheme;
 #[doc = include_str!("docs/tags.md")]
+#[cfg(not(target_arch = "wasm32"))]
 pub use poem_openapi_derive::Tags;
+#[cfg(not(target_arch = "wasm32"))]
+pub use response_context::{ResponseContext, ResponseWarnings};
 #[doc(hidden)]
 pub use serde;
 #[doc(hidden)]
 pub use serde_json;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server_ext::ServerExt;
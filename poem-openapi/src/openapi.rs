@@ -1,23 +1,312 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use bytes::Bytes;
 use poem::{
-    endpoint::{make_sync, BoxEndpoint},
+    endpoint::BoxEndpoint,
+    http::{header, Method, StatusCode},
     web::cookie::CookieKey,
-    Endpoint, EndpointExt, IntoEndpoint, Response, Route,
+    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Route,
 };
+use serde_json::Value;
 
 #[cfg(feature = "swagger-ui")]
 use crate::ui::create_ui_endpoint;
 use crate::{
+    __private::{self, ApiObservers, CostHeader, OperationHooks, RouteOptions},
     poem::middleware::CookieJarManager,
-    registry::{Document, MetaInfo, MetaServer, Registry},
-    OpenApi,
+    registry::{Document, MetaInfo, MetaOperation, MetaPath, MetaResponse, MetaServer, Registry},
+    types::multipart::UploadConfig,
+    validation, ApiObserver, OpenApi, OperationBuilder, OperationHook, Tags,
 };
 
+type SkipValidationFn = Arc<dyn Fn(&Request) -> bool + Send + Sync>;
+type MapSpecFn = Arc<dyn Fn(&mut Value) + Send + Sync>;
+type OperationFilter<'a> = dyn Fn(&str, &MetaOperation) -> bool + 'a;
+
+#[derive(Debug, Copy, Clone)]
+enum SpecFormat {
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+/// How the repeated keys in the query string are handled, see
+/// [`OpenApiService::duplicate_query_keys`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DuplicateQueryKeys {
+    /// The first value is used.
+    FirstWins,
+    /// The last value is used, this is the default.
+    LastWins,
+    /// The request is rejected with `400 Bad Request`.
+    Error,
+}
+
+impl Default for DuplicateQueryKeys {
+    fn default() -> Self {
+        DuplicateQueryKeys::LastWins
+    }
+}
+
+/// The version of the OpenAPI specification of the documents, see
+/// [`OpenApiService::openapi_version`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OpenApiVersion {
+    /// OpenAPI 3.0.3, this is the default.
+    ///
+    /// The documents can be consumed by the tools that don't support 3.1, but
+    /// they can't have the webhooks.
+    V3_0,
+    /// OpenAPI 3.1.0, which supports the webhooks.
+    ///
+    /// The schemas are converted to the JSON Schema dialect of 3.1, such as
+    /// the numeric `exclusiveMaximum` and `exclusiveMinimum`.
+    V3_1,
+}
+
+impl Default for OpenApiVersion {
+    fn default() -> Self {
+        OpenApiVersion::V3_0
+    }
+}
+
+impl OpenApiVersion {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            OpenApiVersion::V3_0 => "3.0.3",
+            OpenApiVersion::V3_1 => "3.1.0",
+        }
+    }
+}
+
+type OperationIdFn = Arc<dyn Fn(&OperationIdContext<'_>) -> Option<String> + Send + Sync>;
+
+/// How the operation ids are generated for the operations without the
+/// `operation_id` attribute, see [`OpenApiService::operation_id_naming`].
+#[derive(Clone)]
+pub enum OperationIdNaming {
+    /// The name of the method in camel case, e.g. `getUser` for `get_user`.
+    CamelCase,
+    /// A template in which `{tag}` is replaced by the first tag of the
+    /// operation and `{method}` by the name of the method, e.g.
+    /// `{tag}_{method}`.
+    ///
+    /// The operations without a tag are not named if the template contains
+    /// `{tag}`.
+    Template(String),
+    /// A function that returns the operation id, or `None` to leave the
+    /// operation unnamed.
+    Custom(OperationIdFn),
+}
+
+impl OperationIdNaming {
+    /// Create a [`OperationIdNaming::Custom`] with a function.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&OperationIdContext<'_>) -> Option<String> + Send + Sync + 'static,
+    {
+        OperationIdNaming::Custom(Arc::new(f))
+    }
+
+    fn operation_id(&self, ctx: &OperationIdContext<'_>) -> Option<String> {
+        match self {
+            OperationIdNaming::CamelCase => ctx.name.map(to_camel_case),
+            OperationIdNaming::Template(template) => {
+                let mut operation_id = template.clone();
+                if template.contains("{tag}") {
+                    operation_id = operation_id.replace("{tag}", ctx.tags.first()?);
+                }
+                if template.contains("{method}") {
+                    operation_id = operation_id.replace("{method}", ctx.name?);
+                }
+                Some(operation_id)
+            }
+            OperationIdNaming::Custom(f) => f(ctx),
+        }
+    }
+}
+
+/// The operation to generate the operation id for, see
+/// [`OperationIdNaming`].
+#[derive(Debug)]
+pub struct OperationIdContext<'a> {
+    /// The name of the method of the operation, or `None` if the operation
+    /// is not defined with `#[OpenApi]`, such as the webhooks.
+    pub name: Option<&'a str>,
+    /// The tags of the operation.
+    pub tags: &'a [Cow<'static, str>],
+    /// The method of the operation.
+    pub method: &'a Method,
+    /// The path of the operation.
+    pub path: &'a str,
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut operation_id = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.trim_start_matches("r#").trim_matches('_').chars() {
+        if c == '_' {
+            upper = !operation_id.is_empty();
+        } else if upper {
+            operation_id.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            operation_id.push(c);
+        }
+    }
+    operation_id
+}
+
+/// Generates the operation ids of the operations without the `operation_id`
+/// attribute, a number is appended to the generated ids that are already
+/// used, such as `list2`, so they are unique.
+///
+/// The links whose `operation_id` is the name of a method whose operation id
+/// is generated are updated to reference the generated id.
+fn resolve_operation_ids<'a>(
+    naming: &OperationIdNaming,
+    apis: impl Iterator<Item = &'a mut Vec<MetaPath>>,
+) -> HashMap<(Method, String), String> {
+    let mut apis = apis.collect::<Vec<_>>();
+    let mut used = apis
+        .iter()
+        .flat_map(|paths| paths.iter())
+        .flat_map(|path| path.operations.iter())
+        .filter_map(|operation| operation.operation_id.as_deref().map(ToString::to_string))
+        .collect::<HashSet<_>>();
+
+    let mut generated = HashMap::new();
+    let mut names = HashMap::new();
+    for paths in apis.iter_mut() {
+        for path in paths.iter_mut() {
+            for operation in &mut path.operations {
+                if operation.operation_id.is_some() {
+                    continue;
+                }
+                let operation_id = naming.operation_id(&OperationIdContext {
+                    name: operation.name,
+                    tags: &operation.tags,
+                    method: &operation.method,
+                    path: &path.path,
+                });
+                if let Some(operation_id) = operation_id {
+                    let mut unique_id = operation_id.clone();
+                    let mut n = 2;
+                    while !used.insert(unique_id.clone()) {
+                        unique_id = format!("{}{}", operation_id, n);
+                        n += 1;
+                    }
+                    if let Some(name) = operation.name {
+                        names.entry(name).or_insert_with(|| unique_id.clone());
+                    }
+                    generated.insert(
+                        (operation.method.clone(), path.path.to_string()),
+                        unique_id.clone(),
+                    );
+                    operation.operation_id = Some(unique_id.into());
+                }
+            }
+        }
+    }
+
+    for paths in apis {
+        for path in paths.iter_mut() {
+            for operation in &mut path.operations {
+                for response in &mut operation.responses.responses {
+                    for link in &mut response.links {
+                        if used.contains(&*link.operation_id) {
+                            continue;
+                        }
+                        if let Some(operation_id) = names.get(&*link.operation_id) {
+                            link.operation_id = operation_id.clone().into();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    generated
+}
+
+/// Returns `true` if the path starts with the path segments of `prefix`.
+fn has_path_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => prefix.is_empty() || rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Returns the names in `disabled` that are neither the name nor the operation
+/// id of an operation, which are usually misspelled.
+fn unknown_operations<'a, 'b>(
+    disabled: &'a [String],
+    apis: impl Iterator<Item = &'b Vec<MetaPath>>,
+) -> Vec<&'a str> {
+    let mut names = HashSet::new();
+    for operation in apis.flatten().flat_map(|path| &path.operations) {
+        names.extend(operation.name);
+        names.extend(operation.operation_id.as_deref());
+    }
+    disabled
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !names.contains(name))
+        .collect()
+}
+
+/// Documents the `413 Payload Too Large` response of the operations that
+/// accept the multipart request bodies.
+fn document_payload_too_large<'a>(apis: impl Iterator<Item = &'a mut Vec<MetaPath>>) {
+    let operations = apis
+        .flatten()
+        .flat_map(|path| &mut path.operations)
+        .filter(|operation| {
+            operation.request.as_ref().map_or(false, |request| {
+                request
+                    .content
+                    .iter()
+                    .any(|media| media.content_type == "multipart/form-data")
+            })
+        });
+    for operation in operations {
+        let responses = &mut operation.responses.responses;
+        if responses.iter().all(|resp| resp.status != Some(413)) {
+            responses.push(MetaResponse {
+                description: Some("The request body exceeds the size limit."),
+                status: Some(413),
+                content: vec![],
+                headers: vec![],
+                links: vec![],
+            });
+        }
+    }
+}
+
 /// An OpenAPI service for Poem.
 pub struct OpenApiService<T> {
     api: T,
     info: Option<MetaInfo>,
     servers: Vec<MetaServer>,
     cookie_key: Option<CookieKey>,
+    skip_validation: Option<SkipValidationFn>,
+    hooks: OperationHooks,
+    observers: ApiObservers,
+    cost_header: bool,
+    duplicate_query_keys: DuplicateQueryKeys,
+    spec_paths: Vec<(String, SpecFormat)>,
+    spec_mappers: Vec<MapSpecFn>,
+    webhooks: Vec<OperationBuilder>,
+    disabled_operations: Vec<String>,
+    operation_id_naming: Option<OperationIdNaming>,
+    openapi_version: OpenApiVersion,
+    upload_config: Option<UploadConfig>,
 }
 
 impl<T> OpenApiService<T> {
@@ -29,6 +318,18 @@ impl<T> OpenApiService<T> {
             info: None,
             servers: Vec::new(),
             cookie_key: None,
+            skip_validation: None,
+            hooks: Default::default(),
+            observers: Default::default(),
+            cost_header: false,
+            duplicate_query_keys: DuplicateQueryKeys::default(),
+            spec_paths: Vec::new(),
+            spec_mappers: Vec::new(),
+            webhooks: Vec::new(),
+            disabled_operations: Vec::new(),
+            operation_id_naming: None,
+            openapi_version: OpenApiVersion::default(),
+            upload_config: None,
         }
     }
 
@@ -94,6 +395,213 @@ impl<T> OpenApiService<T> {
         }
     }
 
+    /// Skips the validators of the parameters and the request body when `f`
+    /// returns `true` for the request.
+    ///
+    /// It is intended for the hot paths between the internal services, `f`
+    /// should only return `true` if the request carries a verified identity of
+    /// a trusted caller. The values are still parsed into the declared types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
+    ///
+    /// /// Inserted by the authentication middleware after verifying the mTLS
+    /// /// certificate of the internal service.
+    /// struct InternalService;
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn index(
+    ///         &self,
+    ///         #[oai(name = "name", in = "query", max_length = 32)] name: String,
+    ///     ) -> PlainText<String> {
+    ///         PlainText(format!("hello, {}!", name))
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api)
+    ///     .skip_validation_if(|req| req.extensions().get::<InternalService>().is_some());
+    /// ```
+    #[must_use]
+    pub fn skip_validation_if<F>(self, f: F) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            skip_validation: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Appends a hook that is called before the operations, see
+    /// [`OperationHook`] for details.
+    ///
+    /// The hooks are called in the order they are added.
+    #[must_use]
+    pub fn hook(mut self, hook: impl OperationHook) -> Self {
+        self.hooks.0.push(Arc::new(hook));
+        self
+    }
+
+    /// Appends an observer that is called after the operations, see
+    /// [`ApiObserver`] for details.
+    ///
+    /// The observers are called in the order they are added.
+    #[must_use]
+    pub fn observer(mut self, observer: impl ApiObserver) -> Self {
+        self.observers.0.push(Arc::new(observer));
+        self
+    }
+
+    /// Adds the `X-Request-Cost` header with the cost of the operation, which
+    /// is specified by `#[oai(cost = N)]`, to the responses.
+    #[must_use]
+    pub fn cost_header(self) -> Self {
+        Self {
+            cost_header: true,
+            ..self
+        }
+    }
+
+    /// Removes the operations with the names, which are the names of the
+    /// methods or the operation ids, from the routes and the specification.
+    ///
+    /// It can be used to retire the operations from the configuration without
+    /// changing the code, the requests to the disabled operations are
+    /// responded with `404 Not Found` or `405 Method Not Allowed`. A warning
+    /// is logged when the service is converted to an endpoint if a name does
+    /// not match any operation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/export", method = "get")]
+    ///     async fn legacy_export(&self) -> PlainText<&'static str> {
+    ///         PlainText("data")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api).disable_operations(&["legacy_export"]);
+    /// assert!(!api_service.spec().contains("/export"));
+    /// ```
+    #[must_use]
+    pub fn disable_operations<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.disabled_operations
+            .extend(names.into_iter().map(|name| name.as_ref().to_string()));
+        self
+    }
+
+    /// Sets how the operation ids are generated for the operations without the
+    /// `operation_id` attribute, so the method names of the generated clients
+    /// follow the same convention.
+    ///
+    /// A number is appended to the generated operation ids that are not
+    /// unique, such as `list2`. The generated ids are also used by
+    /// [`OpenApiService::disable_operations`], the [`OperationInfo`](crate::OperationInfo) and the
+    /// links of the responses that reference the operations by the names of
+    /// the methods.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{
+    ///     payload::PlainText, OpenApi, OpenApiService, OperationIdNaming, Tags,
+    /// };
+    ///
+    /// #[derive(Tags)]
+    /// enum ApiTags {
+    ///     Users,
+    /// }
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/users", method = "get", tag = "ApiTags::Users")]
+    ///     async fn list_users(&self) -> PlainText<&'static str> {
+    ///         PlainText("")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api)
+    ///     .operation_id_naming(OperationIdNaming::Template("{tag}_{method}".to_string()));
+    /// assert!(api_service.spec().contains(r#""operationId": "users_list_users""#));
+    /// ```
+    #[must_use]
+    pub fn operation_id_naming(mut self, naming: OperationIdNaming) -> Self {
+        self.operation_id_naming = Some(naming);
+        self
+    }
+
+    /// Sets how the repeated keys in the query string are handled by the
+    /// parameters and the security schemes, the default is
+    /// [`DuplicateQueryKeys::LastWins`].
+    ///
+    /// It does not apply to the query parameters of the operations that accept
+    /// several values, such as `Vec<T>`, which receive all the values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{DuplicateQueryKeys, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service =
+    ///     OpenApiService::new(Api).duplicate_query_keys(DuplicateQueryKeys::FirstWins);
+    /// ```
+    #[must_use]
+    pub fn duplicate_query_keys(self, policy: DuplicateQueryKeys) -> Self {
+        Self {
+            duplicate_query_keys: policy,
+            ..self
+        }
+    }
+
+    /// Sets the [`UploadConfig`] of the multipart request bodies.
+    ///
+    /// If any of the size limits is set, the operations that accept the
+    /// multipart request bodies document the `413 Payload Too Large` response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{types::multipart::UploadConfig, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service =
+    ///     OpenApiService::new(Api).upload_config(UploadConfig::new().max_file_size(1024 * 1024));
+    /// ```
+    #[must_use]
+    pub fn upload_config(self, config: UploadConfig) -> Self {
+        Self {
+            upload_config: Some(config),
+            ..self
+        }
+    }
+
     /// Create the Swagger UI endpoint.
     #[must_use]
     #[cfg(feature = "swagger-ui")]
@@ -101,7 +609,216 @@ impl<T> OpenApiService<T> {
     where
         T: OpenApi,
     {
-        create_ui_endpoint(&self.spec())
+        create_ui_endpoint(SpecEndpoint::new(
+            "application/json",
+            self.spec(),
+            self.info.as_ref().and_then(|info| info.version.clone()),
+        ))
+    }
+
+    /// Appends a function that modifies the specification before it is
+    /// serialized.
+    ///
+    /// It can add the vendor extensions, remove the internal operations or
+    /// add the tags. The functions are called in the order they are added.
+    ///
+    /// NOTE: The keys of the JSON objects are sorted if any function is added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    ///
+    ///     #[oai(path = "/internal/metrics", method = "get")]
+    ///     async fn metrics(&self) -> PlainText<&'static str> {
+    ///         PlainText("")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api).map_spec(|spec| {
+    ///     spec["info"]["x-logo"] = "https://example.com/logo.png".into();
+    ///     if let Some(paths) = spec["paths"].as_object_mut() {
+    ///         paths.retain(|path, _| !path.starts_with("/internal/"));
+    ///     }
+    /// });
+    ///
+    /// let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    /// assert_eq!(spec["info"]["x-logo"], "https://example.com/logo.png");
+    /// assert!(spec["paths"].get("/internal/metrics").is_none());
+    /// ```
+    #[must_use]
+    pub fn map_spec<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Value) + Send + Sync + 'static,
+    {
+        self.spec_mappers.push(Arc::new(f));
+        self
+    }
+
+    /// Sets the version of the OpenAPI specification of the documents, the
+    /// default is [`OpenApiVersion::V3_0`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{OpenApi, OpenApiService, OpenApiVersion};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {}
+    ///
+    /// let spec: serde_json::Value =
+    ///     serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    /// assert_eq!(spec["openapi"], "3.0.3");
+    ///
+    /// let api_service = OpenApiService::new(Api).openapi_version(OpenApiVersion::V3_1);
+    /// let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    /// assert_eq!(spec["openapi"], "3.1.0");
+    /// ```
+    #[must_use]
+    pub fn openapi_version(mut self, version: OpenApiVersion) -> Self {
+        self.openapi_version = version;
+        self
+    }
+
+    /// Appends a webhook to the `webhooks` section of the specification,
+    /// which documents the requests that the API sends to the endpoints
+    /// registered by the clients.
+    ///
+    /// The types of the webhooks are registered through the same registry as
+    /// the operations.
+    ///
+    /// The webhooks are introduced in OpenAPI 3.1, so the version must be set
+    /// to [`OpenApiVersion::V3_1`], otherwise generating the specification
+    /// panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::http::Method;
+    /// use poem_openapi::{
+    ///     payload::Json, ApiResponse, Object, OpenApi, OpenApiService, OpenApiVersion,
+    ///     OperationBuilder,
+    /// };
+    ///
+    /// #[derive(Object)]
+    /// struct PetCreated {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(ApiResponse)]
+    /// enum WebhookResponse {
+    ///     /// The event is received.
+    ///     #[oai(status = 200)]
+    ///     Ok,
+    /// }
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service = OpenApiService::new(Api)
+    ///     .openapi_version(OpenApiVersion::V3_1)
+    ///     .webhook(
+    ///         OperationBuilder::webhook(Method::POST, "petCreated")
+    ///             .summary("A pet is created")
+    ///             .request::<Json<PetCreated>>()
+    ///             .response::<WebhookResponse>(),
+    ///     );
+    ///
+    /// let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    /// assert_eq!(spec["openapi"], "3.1.0");
+    /// assert_eq!(spec["webhooks"]["petCreated"]["post"]["summary"], "A pet is created");
+    /// ```
+    #[must_use]
+    pub fn webhook(mut self, operation: OperationBuilder) -> Self {
+        self.webhooks.push(operation);
+        self
+    }
+
+    /// Serves the JSON specification at `/openapi.json` of the service, see
+    /// [`OpenApiService::serve_spec_at`].
+    #[must_use]
+    pub fn serve_spec(self) -> Self {
+        self.serve_spec_at("/openapi.json")
+    }
+
+    /// Serves the JSON specification at `path` of the service.
+    ///
+    /// The specification is generated once when the service is converted to
+    /// an endpoint. The responses have the `ETag` header, which is the hash of
+    /// the specification, and the `X-API-Version` header if the version of
+    /// the API is set. The requests with a matching `If-None-Match` header get
+    /// `304 Not Modified`.
+    ///
+    /// If the `compression` feature is enabled, the specification is compressed
+    /// with gzip once, and the compressed one is served to the clients that
+    /// accept it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     http::{header, StatusCode},
+    ///     test::TestClient,
+    /// };
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn index(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api)
+    ///     .version("1.0.0")
+    ///     .serve_spec_at("/spec.json");
+    /// let cli = TestClient::new(api_service);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/spec.json").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_content_type("application/json");
+    /// resp.assert_header("x-api-version", "1.0.0");
+    ///
+    /// let etag = resp.0.headers().get(header::ETAG).cloned().unwrap();
+    /// cli.get("/spec.json")
+    ///     .header(header::IF_NONE_MATCH, etag)
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::NOT_MODIFIED);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn serve_spec_at(mut self, path: impl Into<String>) -> Self {
+        self.spec_paths.push((path.into(), SpecFormat::Json));
+        self
+    }
+
+    /// Serves the YAML specification at `path` of the service, see
+    /// [`OpenApiService::serve_spec_at`].
+    #[must_use]
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn serve_spec_yaml_at(mut self, path: impl Into<String>) -> Self {
+        self.spec_paths.push((path.into(), SpecFormat::Yaml));
+        self
     }
 
     /// Create an endpoint to serve the open api specification.
@@ -109,30 +826,525 @@ impl<T> OpenApiService<T> {
     where
         T: OpenApi,
     {
-        let spec = self.spec();
-        make_sync(move |_| {
-            Response::builder()
-                .content_type("application/json")
-                .body(spec.clone())
-        })
+        self.create_spec_endpoint(SpecFormat::Json)
+    }
+
+    /// Returns the OAS specification file that only contains the operations
+    /// and the webhooks with any of the specified tags.
+    ///
+    /// The schemas that are not referenced by these operations and the unused
+    /// tags are removed, so that a single service can generate the different
+    /// documents for the different audiences. The functions added by
+    /// [`OpenApiService::map_spec`] are called on the filtered document.
+    ///
+    /// NOTE: The keys of the JSON objects are sorted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService, Tags};
+    ///
+    /// #[derive(Tags)]
+    /// enum ApiTags {
+    ///     Public,
+    ///     Internal,
+    /// }
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get", tag = "ApiTags::Public")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    ///
+    ///     #[oai(path = "/metrics", method = "get", tag = "ApiTags::Internal")]
+    ///     async fn metrics(&self) -> PlainText<&'static str> {
+    ///         PlainText("")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api);
+    /// let spec: serde_json::Value =
+    ///     serde_json::from_str(&api_service.spec_for_tags(&[ApiTags::Public])).unwrap();
+    /// assert!(spec["paths"].get("/hello").is_some());
+    /// assert!(spec["paths"].get("/metrics").is_none());
+    /// ```
+    pub fn spec_for_tags<G: Tags>(&self, tags: &[G]) -> String
+    where
+        T: OpenApi,
+    {
+        let names = tags.iter().map(Tags::name).collect::<Vec<_>>();
+        self.filtered_spec(&|_, operation| operation.tags.iter().any(|tag| names.contains(&&**tag)))
+    }
+
+    /// Returns the OAS specification file that only contains the operations
+    /// whose path starts with `prefix`, the webhooks are kept if their names
+    /// start with `prefix`.
+    ///
+    /// The prefix matches the whole path segments, so `/internal` matches
+    /// `/internal` and `/internal/stats`, but not `/internals`.
+    ///
+    /// See [`OpenApiService::spec_for_tags`] for details.
+    pub fn spec_for_path_prefix(&self, prefix: &str) -> String
+    where
+        T: OpenApi,
+    {
+        self.filtered_spec(&|path, _| has_path_prefix(path, prefix))
+    }
+
+    /// Create the Swagger UI endpoint for the specification returned by
+    /// [`OpenApiService::spec_for_tags`].
+    #[must_use]
+    #[cfg(feature = "swagger-ui")]
+    pub fn swagger_ui_for_tags<G: Tags>(&self, tags: &[G]) -> impl Endpoint
+    where
+        T: OpenApi,
+    {
+        create_ui_endpoint(SpecEndpoint::new(
+            "application/json",
+            self.spec_for_tags(tags),
+            self.info.as_ref().and_then(|info| info.version.clone()),
+        ))
+    }
+
+    /// Create the Swagger UI endpoint for the specification returned by
+    /// [`OpenApiService::spec_for_path_prefix`].
+    #[must_use]
+    #[cfg(feature = "swagger-ui")]
+    pub fn swagger_ui_for_path_prefix(&self, prefix: &str) -> impl Endpoint
+    where
+        T: OpenApi,
+    {
+        create_ui_endpoint(SpecEndpoint::new(
+            "application/json",
+            self.spec_for_path_prefix(prefix),
+            self.info.as_ref().and_then(|info| info.version.clone()),
+        ))
     }
 
     /// Returns the OAS specification file.
+    ///
+    /// The document follows the OpenAPI 3.0.3 specification by default, so it
+    /// can be consumed by the tools that don't support 3.1, see
+    /// [`OpenApiService::openapi_version`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are webhooks and the version is not
+    /// [`OpenApiVersion::V3_1`].
     pub fn spec(&self) -> String
+    where
+        T: OpenApi,
+    {
+        match self.mapped_spec() {
+            Some(spec) => serde_json::to_string_pretty(&spec).unwrap(),
+            None => self.with_document(|doc| serde_json::to_string_pretty(doc).unwrap()),
+        }
+    }
+
+    /// Returns the OAS specification file in YAML format.
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn spec_yaml(&self) -> String
+    where
+        T: OpenApi,
+    {
+        match self.mapped_spec() {
+            Some(spec) => serde_yaml::to_string(&spec).unwrap(),
+            None => self.with_document(|doc| serde_yaml::to_string(doc).unwrap()),
+        }
+    }
+
+    /// Returns the specification modified by the functions added by
+    /// [`OpenApiService::map_spec`], without the schemas that are only used by
+    /// the disabled operations, and with the schemas converted to OpenAPI 3.1,
+    /// or `None` if there is nothing to modify.
+    fn mapped_spec(&self) -> Option<Value>
+    where
+        T: OpenApi,
+    {
+        if self.spec_mappers.is_empty()
+            && self.disabled_operations.is_empty()
+            && self.openapi_version == OpenApiVersion::V3_0
+        {
+            return None;
+        }
+
+        let mut spec = self.with_document(|doc| serde_json::to_value(doc).unwrap());
+        if !self.disabled_operations.is_empty() {
+            remove_unused_schemas(&mut spec);
+        }
+        if self.openapi_version == OpenApiVersion::V3_1 {
+            convert_schemas_to_3_1(&mut spec);
+        }
+        for f in &self.spec_mappers {
+            f(&mut spec);
+        }
+        Some(spec)
+    }
+
+    fn with_document<R>(&self, f: impl FnOnce(&Document<'_>) -> R) -> R
+    where
+        T: OpenApi,
+    {
+        self.with_filtered_document(None, f)
+    }
+
+    /// Builds the document that only contains the operations for which
+    /// `filter` returns `true`, the unused tags are also removed.
+    fn with_filtered_document<R>(
+        &self,
+        filter: Option<&OperationFilter<'_>>,
+        f: impl FnOnce(&Document<'_>) -> R,
+    ) -> R
     where
         T: OpenApi,
     {
         let mut registry = Registry::new();
-        let metadata = T::meta();
-        T::register(&mut registry);
+        let mut metadata = self.api.instance_meta();
+        self.api.instance_register(&mut registry);
+        if self
+            .upload_config
+            .as_ref()
+            .map_or(false, UploadConfig::has_limits)
+        {
+            document_payload_too_large(metadata.iter_mut().map(|api| &mut api.paths));
+        }
+
+        let disabled_filter = |path: &str, operation: &MetaOperation| {
+            __private::is_operation_enabled(
+                &self.disabled_operations,
+                operation.name,
+                operation.operation_id.as_deref(),
+            ) && filter.map(|filter| filter(path, operation)).unwrap_or(true)
+        };
+        let filter: Option<&OperationFilter<'_>> = if self.disabled_operations.is_empty() {
+            filter
+        } else {
+            Some(&disabled_filter)
+        };
+
+        assert!(
+            self.webhooks.is_empty() || self.openapi_version == OpenApiVersion::V3_1,
+            "the webhooks require OpenAPI 3.1, set the version with `OpenApiService::openapi_version`"
+        );
+
+        let mut webhooks = self.webhook_paths(&mut registry);
+
+        if let Some(naming) = &self.operation_id_naming {
+            let apis = metadata.iter_mut().map(|api| &mut api.paths);
+            resolve_operation_ids(naming, apis.chain(std::iter::once(&mut webhooks)));
+        }
+
+        if let Some(filter) = filter {
+            let mut used_tags = HashSet::new();
+            let apis = metadata.iter_mut().map(|api| &mut api.paths);
+            for paths in apis.chain(std::iter::once(&mut webhooks)) {
+                for path in paths.iter_mut() {
+                    let path_name = &*path.path;
+                    path.operations
+                        .retain(|operation| filter(path_name, operation));
+                    for operation in &path.operations {
+                        used_tags.extend(operation.tags.iter().cloned());
+                    }
+                }
+                paths.retain(|path| !path.operations.is_empty());
+            }
+            registry.tags.retain(|tag| used_tags.contains(tag.name));
+        }
 
         let doc = Document {
+            version: self.openapi_version,
             info: self.info.as_ref(),
             servers: &self.servers,
             apis: &metadata,
+            webhooks: &webhooks,
             registry: &registry,
         };
-        serde_json::to_string_pretty(&doc).unwrap()
+        f(&doc)
+    }
+
+    fn webhook_paths(&self, registry: &mut Registry) -> Vec<MetaPath> {
+        let mut webhooks: Vec<MetaPath> = Vec::new();
+        for webhook in &self.webhooks {
+            for register in &webhook.registers {
+                register(registry);
+            }
+            let meta = webhook.meta.clone();
+            match webhooks.iter_mut().find(|path| path.path == webhook.path) {
+                Some(path) => path.operations.push(meta),
+                None => webhooks.push(MetaPath {
+                    path: webhook.path.clone(),
+                    operations: vec![meta],
+                }),
+            }
+        }
+        webhooks
+    }
+
+    fn filtered_spec(&self, filter: &OperationFilter<'_>) -> String
+    where
+        T: OpenApi,
+    {
+        let mut spec =
+            self.with_filtered_document(Some(filter), |doc| serde_json::to_value(doc).unwrap());
+        remove_unused_schemas(&mut spec);
+        if self.openapi_version == OpenApiVersion::V3_1 {
+            convert_schemas_to_3_1(&mut spec);
+        }
+        for f in &self.spec_mappers {
+            f(&mut spec);
+        }
+        serde_json::to_string_pretty(&spec).unwrap()
+    }
+
+    fn create_spec_endpoint(&self, format: SpecFormat) -> SpecEndpoint
+    where
+        T: OpenApi,
+    {
+        let (content_type, spec) = match format {
+            SpecFormat::Json => ("application/json", self.spec()),
+            #[cfg(feature = "yaml")]
+            SpecFormat::Yaml => ("application/yaml", self.spec_yaml()),
+        };
+        SpecEndpoint::new(
+            content_type,
+            spec,
+            self.info.as_ref().and_then(|info| info.version.clone()),
+        )
+    }
+}
+
+/// An endpoint that serves the specification generated once.
+pub(crate) struct SpecEndpoint {
+    content_type: &'static str,
+    spec: Bytes,
+    etag: String,
+    version: Option<String>,
+    #[cfg(feature = "compression")]
+    gzip_etag: String,
+    #[cfg(feature = "compression")]
+    gzip_spec: Bytes,
+}
+
+impl SpecEndpoint {
+    /// Create the endpoint, the specification is compressed here if the
+    /// `compression` feature is enabled, so the requests are not delayed.
+    pub(crate) fn new(content_type: &'static str, spec: String, version: Option<String>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        spec.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        Self {
+            content_type,
+            #[cfg(feature = "compression")]
+            gzip_etag: format!("\"{:016x}-gzip\"", hash),
+            #[cfg(feature = "compression")]
+            gzip_spec: gzip(spec.as_bytes()),
+            spec: Bytes::from(spec),
+            etag: format!("\"{:016x}\"", hash),
+            version,
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+fn gzip(data: &[u8]) -> Bytes {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|_| encoder.finish())
+        .map(Bytes::from)
+        .expect("writing to a vec never fails")
+}
+
+#[cfg(feature = "compression")]
+fn accepts_gzip(req: &Request) -> bool {
+    req.headers()
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let name = parts.next().unwrap_or_default();
+            let disabled = parts.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .map(|q| q == 0.0)
+                    .unwrap_or_default()
+            });
+            name.eq_ignore_ascii_case("gzip") && !disabled
+        })
+}
+
+/// Removes the schemas that are not referenced by the paths, directly or
+/// through the other referenced schemas.
+/// Converts the schemas from the OpenAPI 3.0 dialect, in which the meta model
+/// is written, to the JSON Schema dialect of OpenAPI 3.1.
+///
+/// The boolean `exclusiveMaximum` and `exclusiveMinimum` become the numeric
+/// bounds. The examples and the default values are not schemas, so they are
+/// left unchanged.
+fn convert_schemas_to_3_1(spec: &mut Value) {
+    fn convert_bound(map: &mut serde_json::Map<String, Value>, exclusive: &str, bound: &str) {
+        if let Some(Value::Bool(is_exclusive)) = map.get(exclusive) {
+            match (*is_exclusive, map.remove(bound)) {
+                (true, Some(value)) => {
+                    map.insert(exclusive.to_string(), value);
+                }
+                (false, Some(value)) => {
+                    map.remove(exclusive);
+                    map.insert(bound.to_string(), value);
+                }
+                (_, None) => {
+                    map.remove(exclusive);
+                }
+            }
+        }
+    }
+
+    /// `is_map` is `true` for the maps whose keys are names, such as the
+    /// properties of a schema, rather than keywords.
+    fn convert(value: &mut Value, is_map: bool) {
+        match value {
+            Value::Object(map) if is_map => {
+                for value in map.values_mut() {
+                    convert(value, false);
+                }
+            }
+            Value::Object(map) => {
+                convert_bound(map, "exclusiveMaximum", "maximum");
+                convert_bound(map, "exclusiveMinimum", "minimum");
+                for (key, value) in map.iter_mut() {
+                    match key.as_str() {
+                        "default" | "example" | "examples" | "enum" => {}
+                        "properties" | "schemas" | "headers" => convert(value, true),
+                        _ => convert(value, false),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    convert(item, false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    convert(spec, false);
+}
+
+fn remove_unused_schemas(spec: &mut Value) {
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference.clone());
+                }
+                for value in map.values() {
+                    collect_refs(value, refs);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    collect_refs(item, refs);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let schemas = match spec
+        .get_mut("components")
+        .and_then(|components| components.get_mut("schemas"))
+        .and_then(Value::as_object_mut)
+    {
+        Some(schemas) => std::mem::take(schemas),
+        None => return,
+    };
+
+    let mut refs = Vec::new();
+    collect_refs(spec, &mut refs);
+
+    let mut used = HashSet::new();
+    while let Some(reference) = refs.pop() {
+        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+            if used.insert(name.to_string()) {
+                if let Some(schema) = schemas.get(name) {
+                    collect_refs(schema, &mut refs);
+                }
+            }
+        }
+    }
+
+    spec["components"]["schemas"] = Value::Object(
+        schemas
+            .into_iter()
+            .filter(|(name, _)| used.contains(name))
+            .collect(),
+    );
+}
+
+#[poem::async_trait]
+impl Endpoint for SpecEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        #[cfg(feature = "compression")]
+        let gzip_spec = match accepts_gzip(&req) {
+            true => Some(self.gzip_spec.clone()),
+            false => None,
+        };
+        #[cfg(not(feature = "compression"))]
+        let gzip_spec: Option<Bytes> = None;
+
+        #[cfg(feature = "compression")]
+        let etag = match gzip_spec {
+            Some(_) => &self.gzip_etag,
+            None => &self.etag,
+        };
+        #[cfg(not(feature = "compression"))]
+        let etag = &self.etag;
+
+        let mut resp = Response::builder()
+            .header(header::ETAG, etag.as_str())
+            .header(header::CACHE_CONTROL, "no-cache");
+        if cfg!(feature = "compression") {
+            resp = resp.header(header::VARY, "accept-encoding");
+        }
+        if let Some(version) = &self.version {
+            resp = resp.header("x-api-version", version.as_str());
+        }
+
+        let not_modified = req
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .any(|value| value == etag || value == "*")
+            })
+            .unwrap_or_default();
+        if not_modified {
+            return resp.status(StatusCode::NOT_MODIFIED).finish();
+        }
+
+        let resp = resp.content_type(self.content_type);
+        match gzip_spec {
+            Some(data) => resp.header(header::CONTENT_ENCODING, "gzip").body(data),
+            None => resp.body(self.spec.clone()),
+        }
     }
 }
 
@@ -140,17 +1352,113 @@ impl<T: OpenApi> IntoEndpoint for OpenApiService<T> {
     type Endpoint = BoxEndpoint<'static, Response>;
 
     fn into_endpoint(self) -> Self::Endpoint {
-        match self.cookie_key {
-            Some(key) => self
-                .api
-                .add_routes(Route::new())
-                .with(CookieJarManager::with_key(key))
-                .boxed(),
-            None => self
-                .api
-                .add_routes(Route::new())
-                .with(CookieJarManager::new())
+        let spec_endpoints = self
+            .spec_paths
+            .iter()
+            .map(|(path, format)| (path.clone(), self.create_spec_endpoint(*format)))
+            .collect::<Vec<_>>();
+        let mut metadata = self.api.instance_meta();
+        let mut webhooks = self.webhook_paths(&mut Registry::new());
+        let operation_ids = match &self.operation_id_naming {
+            Some(naming) => {
+                let apis = metadata.iter_mut().map(|api| &mut api.paths);
+                resolve_operation_ids(naming, apis.chain(std::iter::once(&mut webhooks)))
+            }
+            None => HashMap::new(),
+        };
+        let apis = metadata.iter().map(|api| &api.paths);
+        let unknown = unknown_operations(
+            &self.disabled_operations,
+            apis.chain(std::iter::once(&webhooks)),
+        );
+        if !unknown.is_empty() {
+            tracing::warn!(
+                operations = ?unknown,
+                "the disabled operations are not found"
+            );
+        }
+        let options = RouteOptions {
+            disabled: self.disabled_operations,
+            operation_ids,
+            observed: !self.observers.0.is_empty(),
+        };
+        let route = spec_endpoints.into_iter().fold(
+            self.api.add_routes_except(Route::new(), &options),
+            |route, (path, ep)| route.at(path, poem::get(ep)),
+        );
+        let ep = match self.cookie_key {
+            Some(key) => route.with(CookieJarManager::with_key(key)).boxed(),
+            None => route.with(CookieJarManager::new()).boxed(),
+        };
+        let ep = if self.hooks.0.is_empty() {
+            ep
+        } else {
+            ep.data(self.hooks).boxed()
+        };
+        let ep = if self.observers.0.is_empty() {
+            ep
+        } else {
+            ep.data(self.observers).boxed()
+        };
+        let ep = if self.cost_header {
+            ep.data(CostHeader).boxed()
+        } else {
+            ep
+        };
+        let ep = if self.duplicate_query_keys != DuplicateQueryKeys::default() {
+            ep.data(self.duplicate_query_keys).boxed()
+        } else {
+            ep
+        };
+        let ep = match self.upload_config {
+            Some(config) => ep.data(config).boxed(),
+            None => ep,
+        };
+
+        match self.skip_validation {
+            Some(skip_validation) => ep
+                .around(move |ep, req| {
+                    let skip = skip_validation(&req);
+                    validation::scope(skip, async move { ep.call(req).await })
+                })
                 .boxed(),
+            None => ep,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use poem::endpoint::make_sync;
+
+    use super::*;
+    use crate::{ApiBuilder, OperationBuilder};
+
+    #[test]
+    fn path_prefix() {
+        assert!(has_path_prefix("/internal", "/internal"));
+        assert!(has_path_prefix("/internal/stats", "/internal"));
+        assert!(has_path_prefix("/internal/stats", "/internal/"));
+        assert!(has_path_prefix("/internal/stats", "/"));
+        assert!(has_path_prefix("/internal/stats", ""));
+        assert!(!has_path_prefix("/internals", "/internal"));
+        assert!(!has_path_prefix("/internal-tools", "/internal/"));
+        assert!(!has_path_prefix("/users", "/internal"));
+    }
+
+    #[test]
+    fn unknown_disabled_operations() {
+        let metadata = ApiBuilder::new()
+            .operation(
+                OperationBuilder::new(Method::GET, "/a").operation_id("get_a"),
+                make_sync(|_| ()),
+            )
+            .operation(OperationBuilder::new(Method::GET, "/b"), make_sync(|_| ()))
+            .instance_meta();
+        let disabled = ["get_a", "get_b", "getA"].map(ToString::to_string);
+        assert_eq!(
+            unknown_operations(&disabled, metadata.iter().map(|api| &api.paths)),
+            vec!["get_b", "getA"]
+        );
+    }
+}
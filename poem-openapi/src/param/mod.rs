@@ -1,6 +1,65 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+};
 
-use crate::{poem::Request, registry::MetaParamIn};
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    poem::{web::Query, FromRequest, Request},
+    registry::{MetaParamIn, MetaParamStyle, MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseFromParameterBorrowed, ParseResult, Type},
+    DuplicateQueryKeys, ParseRequestError,
+};
+
+/// Parses the query string of the request, the repeated keys are handled
+/// according to the [`DuplicateQueryKeys`] of the service.
+pub async fn parse_query(request: &Request) -> Result<HashMap<String, String>, ParseRequestError> {
+    parse_query_with_repeated(request, &query_pairs(request).await, &[])
+}
+
+/// Same as [`parse_query`], but parses the `pairs` returned by
+/// [`query_pairs`], and the keys of the `repeated` pairs whose second item is
+/// `true` can be repeated regardless of the [`DuplicateQueryKeys`], their
+/// values are read by [`get_query`].
+pub fn parse_query_with_repeated(
+    request: &Request,
+    pairs: &[(String, String)],
+    repeated: &[(&str, bool)],
+) -> Result<HashMap<String, String>, ParseRequestError> {
+    let policy = request
+        .extensions()
+        .get::<DuplicateQueryKeys>()
+        .copied()
+        .unwrap_or_default();
+
+    let mut query = HashMap::with_capacity(pairs.len());
+    for (key, value) in pairs {
+        match query.entry(key.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value.clone());
+            }
+            Entry::Occupied(mut entry) => match policy {
+                DuplicateQueryKeys::FirstWins => {}
+                DuplicateQueryKeys::LastWins => {
+                    entry.insert(value.clone());
+                }
+                DuplicateQueryKeys::Error
+                    if repeated
+                        .iter()
+                        .any(|(name, multiple)| *multiple && name == entry.key()) => {}
+                DuplicateQueryKeys::Error => {
+                    return Err(ParseRequestError::Extractor(format!(
+                        "duplicate query key `{}`",
+                        entry.key()
+                    )));
+                }
+            },
+        }
+    }
+    Ok(query)
+}
 
 pub fn get<'a>(
     name: &str,
@@ -38,3 +97,315 @@ pub fn get<'a>(
             .map(Cow::Owned),
     }
 }
+
+/// The value of a query parameter returned by [`get_query`].
+pub enum QueryValue<'a> {
+    Single(&'a str),
+    Multiple(Vec<&'a str>),
+}
+
+/// Gets the value of the query parameter, all the values of the repeated
+/// parameter are taken from the `pairs` if `multiple` is `true`.
+pub fn get_query<'a>(
+    name: &str,
+    multiple: bool,
+    pairs: &'a [(String, String)],
+    query: &'a HashMap<String, String>,
+) -> Option<QueryValue<'a>> {
+    if !multiple {
+        return query.get(name).map(|value| QueryValue::Single(value));
+    }
+    let values = query_values(name, pairs).collect::<Vec<_>>();
+    (!values.is_empty()).then(|| QueryValue::Multiple(values))
+}
+
+fn query_values<'a: 'b, 'b>(
+    name: &'b str,
+    pairs: &'a [(String, String)],
+) -> impl Iterator<Item = &'a str> + 'b {
+    pairs
+        .iter()
+        .filter(move |(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Parses the value returned by [`get_query`].
+pub fn parse_from_query_value<'a, T: ParseFromParameterBorrowed<'a>>(
+    value: Option<&'a QueryValue<'_>>,
+) -> ParseResult<T> {
+    match value {
+        Some(QueryValue::Single(value)) => T::parse_from_parameter_borrowed(Some(*value)),
+        Some(QueryValue::Multiple(values)) => {
+            T::parse_from_parameters_borrowed(values.iter().copied())
+        }
+        None => T::parse_from_parameter_borrowed(None),
+    }
+}
+
+/// Gets the value of the path parameter in the `matrix` (`;id=5`) or `label`
+/// (`.5`) style, returns `None` if the prefix of the style is missing.
+pub fn get_path_with_style<'a>(
+    name: &str,
+    style: MetaParamStyle,
+    request: &'a Request,
+) -> Option<Cow<'a, str>> {
+    let value = request.path_param(name)?;
+    let value = match style {
+        MetaParamStyle::Matrix => value
+            .strip_prefix(';')
+            .and_then(|value| value.strip_prefix(name))
+            .and_then(|value| value.strip_prefix('=')),
+        MetaParamStyle::Label => value.strip_prefix('.'),
+        _ => Some(value),
+    };
+    value.map(Cow::Borrowed)
+}
+
+/// Parses the pairs of the query string, it is done once for each request.
+pub async fn query_pairs(request: &Request) -> Vec<(String, String)> {
+    Query::<Vec<(String, String)>>::from_request(request, &mut Default::default())
+        .await
+        .map(|query| query.0)
+        .unwrap_or_default()
+}
+
+/// The schema of a parameter in the `form`, `spaceDelimited`,
+/// `pipeDelimited` or `deepObject` style, it is created once for each
+/// operation.
+pub struct StyledSchema {
+    registry: Registry,
+    schema_ref: MetaSchemaRef,
+}
+
+impl StyledSchema {
+    /// Create the schema of the parameter type `T`.
+    pub fn new<T: Type>() -> Self {
+        let mut registry = Registry::new();
+        T::register(&mut registry);
+        Self {
+            registry,
+            schema_ref: T::schema_ref(),
+        }
+    }
+}
+
+/// Gets the value of the query parameter in the `form`, `spaceDelimited`,
+/// `pipeDelimited` or `deepObject` style, returns `None` if the parameter is
+/// missing.
+///
+/// The arrays and the objects are assembled according to the `schema` of the
+/// parameter, and the strings are converted to the numbers and the booleans
+/// where the schema expects them, so the value can be parsed with
+/// [`ParseFromJSON`].
+///
+/// The values of the arrays and the objects that are not exploded are split
+/// before they are percent-decoded, so an encoded delimiter such as `%2C` is
+/// a part of the item.
+pub fn get_query_with_style(
+    request: &Request,
+    name: &str,
+    style: MetaParamStyle,
+    explode: bool,
+    schema: &StyledSchema,
+    pairs: &[(String, String)],
+    query: &HashMap<String, String>,
+) -> Option<Value> {
+    let registry = &schema.registry;
+    let schema = resolve_schema(&schema.schema_ref, registry)?;
+
+    let split = || {
+        let value = raw_query_value(request, name)?;
+        let items = match style {
+            MetaParamStyle::SpaceDelimited => value
+                .replace("%20", "+")
+                .split('+')
+                .map(decode_query_component)
+                .collect::<Vec<_>>(),
+            MetaParamStyle::PipeDelimited => value.split('|').map(decode_query_component).collect(),
+            _ => value.split(',').map(decode_query_component).collect(),
+        };
+        Some(items)
+    };
+
+    let value = match (style, schema.ty) {
+        (MetaParamStyle::DeepObject, _) => {
+            let properties = query
+                .iter()
+                .filter_map(|(key, value)| {
+                    let property = key
+                        .strip_prefix(name)?
+                        .strip_prefix('[')?
+                        .strip_suffix(']')?;
+                    Some((property.to_string(), Value::String(value.clone())))
+                })
+                .collect::<Map<_, _>>();
+            if properties.is_empty() {
+                return None;
+            }
+            Value::Object(properties)
+        }
+        (_, "array") if explode => {
+            let items = query_values(name, pairs)
+                .map(|value| Value::String(value.to_string()))
+                .collect::<Vec<_>>();
+            if items.is_empty() {
+                return None;
+            }
+            Value::Array(items)
+        }
+        (_, "array") => Value::Array(split()?.into_iter().map(Value::String).collect()),
+        (_, "object") if explode => {
+            let properties = query
+                .iter()
+                .filter(|(key, _)| find_property(schema, key, registry).is_some())
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .collect::<Map<_, _>>();
+            if properties.is_empty() {
+                return None;
+            }
+            Value::Object(properties)
+        }
+        (_, "object") => {
+            let items = split()?;
+            let mut properties = Map::new();
+            for pair in items.chunks(2) {
+                let value = pair.get(1).cloned().unwrap_or_default();
+                properties.insert(pair[0].clone(), Value::String(value));
+            }
+            Value::Object(properties)
+        }
+        _ => Value::String(query.get(name)?.clone()),
+    };
+
+    Some(coerce_value(value, schema, registry))
+}
+
+/// Returns the raw value of the first query parameter named `name`.
+fn raw_query_value<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (decode_query_component(key) == name).then(|| value)
+    })
+}
+
+fn decode_query_component(value: &str) -> String {
+    let value = value.replace('+', " ");
+    percent_decode_str(&value).decode_utf8_lossy().into_owned()
+}
+
+/// Parses the value returned by [`get_query_with_style`].
+pub fn parse_from_styled_value<T: ParseFromJSON>(value: Option<&Value>) -> ParseResult<T> {
+    match value {
+        Some(value) => T::parse_from_json(value.clone()),
+        None if T::IS_REQUIRED => Err(ParseError::expected_input()),
+        None => T::parse_from_json(Value::Null),
+    }
+}
+
+fn resolve_schema<'a>(schema: &'a MetaSchemaRef, registry: &'a Registry) -> Option<&'a MetaSchema> {
+    match schema {
+        MetaSchemaRef::Inline(schema) => Some(schema),
+        MetaSchemaRef::Reference(name) => registry.schemas.get(name),
+    }
+}
+
+fn find_property<'a>(
+    schema: &'a MetaSchema,
+    name: &str,
+    registry: &'a Registry,
+) -> Option<&'a MetaSchemaRef> {
+    schema
+        .properties
+        .iter()
+        .find(|(property, _)| *property == name)
+        .map(|(_, schema)| schema)
+        .or_else(|| {
+            schema
+                .all_of
+                .iter()
+                .find_map(|schema| find_property(resolve_schema(schema, registry)?, name, registry))
+        })
+}
+
+/// Converts the strings to the types of the schema, the values that can not
+/// be converted are left unchanged, so that the parser reports the error.
+fn coerce_value(value: Value, schema: &MetaSchema, registry: &Registry) -> Value {
+    match value {
+        Value::String(s) => {
+            let converted = match schema.ty {
+                "integer" => s
+                    .parse::<i64>()
+                    .map(Number::from)
+                    .or_else(|_| s.parse::<u64>().map(Number::from))
+                    .ok()
+                    .map(Value::Number),
+                "number" => s
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number),
+                "boolean" => s.parse::<bool>().ok().map(Value::Bool),
+                _ => None,
+            };
+            converted.unwrap_or(Value::String(s))
+        }
+        Value::Array(items) => match schema
+            .items
+            .as_deref()
+            .and_then(|items| resolve_schema(items, registry))
+        {
+            Some(items_schema) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| coerce_value(item, items_schema, registry))
+                    .collect(),
+            ),
+            None => Value::Array(items),
+        },
+        Value::Object(properties) => Value::Object(
+            properties
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match find_property(schema, &key, registry)
+                        .and_then(|schema| resolve_schema(schema, registry))
+                    {
+                        Some(schema) => coerce_value(value, schema, registry),
+                        None => value,
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Represents a type that can be parsed from the rest of the path captured by
+/// a wildcard path variable, such as `path` in `/files/*path`.
+pub trait ParseFromWildcard: Sized {
+    /// Parse from the rest of the path.
+    fn parse_from_wildcard(value: Option<&str>) -> ParseResult<Self>;
+}
+
+impl ParseFromWildcard for String {
+    fn parse_from_wildcard(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(value.to_string()),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+/// The segments of the path, the empty segments are skipped.
+impl ParseFromWildcard for Vec<String> {
+    fn parse_from_wildcard(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(value
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(ToString::to_string)
+                .collect()),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use poem::{http::header, IntoResponse, Response};
+
+use crate::{
+    payload::Payload,
+    registry::{
+        MetaHeader, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
+    },
+    ApiResponse,
+};
+
+/// A binary payload that is downloaded as a file, the `Content-Disposition`
+/// header is set to `attachment`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{payload::Attachment, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/report", method = "get")]
+///     async fn report(&self) -> Attachment<Vec<u8>> {
+///         Attachment::new(b"a,b,c".to_vec())
+///             .filename("report.csv")
+///             .content_type("text/csv")
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Attachment<T> {
+    data: T,
+    filename: Option<String>,
+    content_type: Option<String>,
+}
+
+impl<T> Attachment<T> {
+    /// Create an attachment with the data.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            filename: None,
+            content_type: None,
+        }
+    }
+
+    /// Sets the filename that is suggested to the client.
+    #[must_use]
+    pub fn filename(self, filename: impl Into<String>) -> Self {
+        Self {
+            filename: Some(filename.into()),
+            ..self
+        }
+    }
+
+    /// Sets the `Content-Type`, the default is `application/octet-stream`.
+    #[must_use]
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            ..self
+        }
+    }
+
+    fn content_disposition(&self) -> String {
+        let filename = match &self.filename {
+            Some(filename) => filename,
+            None => return "attachment".to_string(),
+        };
+
+        let mut value = String::from("attachment; filename=\"");
+        for c in filename.chars() {
+            match c {
+                '"' | '\\' => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                ' '..='~' => value.push(c),
+                _ => value.push('_'),
+            }
+        }
+        value.push('"');
+
+        // the non-ASCII filenames are encoded according to RFC 6266
+        if !filename.chars().all(|c| (' '..='~').contains(&c)) {
+            value.push_str("; filename*=UTF-8''");
+            for b in filename.bytes() {
+                if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                    value.push(b as char);
+                } else {
+                    value.push_str(&format!("%{:02X}", b));
+                }
+            }
+        }
+
+        value
+    }
+}
+
+impl<T: Send> Payload for Attachment<T> {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+impl<T: Into<Vec<u8>> + Send> IntoResponse for Attachment<T> {
+    fn into_response(self) -> Response {
+        let content_disposition = self.content_disposition();
+        let data: Vec<u8> = self.data.into();
+        Response::builder()
+            .content_type(self.content_type.as_deref().unwrap_or(Self::CONTENT_TYPE))
+            .header(header::CONTENT_DISPOSITION, content_disposition)
+            .header(header::CONTENT_LENGTH, data.len())
+            .body(data)
+    }
+}
+
+impl<T: Into<Vec<u8>> + Send> ApiResponse for Attachment<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: None,
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![
+                    MetaHeader {
+                        name: "Content-Disposition",
+                        description: Some("Indicates that the content is downloaded as a file, and the suggested filename."),
+                        required: true,
+                        schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                    },
+                    MetaHeader {
+                        name: "Content-Length",
+                        description: Some("The size of the file in bytes."),
+                        required: true,
+                        schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                            format: Some("int64"),
+                            ..MetaSchema::new("integer")
+                        })),
+                    },
+                ],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
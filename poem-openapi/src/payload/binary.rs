@@ -1,8 +1,7 @@
 use poem::{FromRequest, IntoResponse, Request, RequestBody, Response};
 
 use crate::{
-    payload::{ParsePayload, Payload},
-    poem::Error,
+    payload::{read_body_error, ParsePayload, Payload},
     registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
     ApiResponse, ParseRequestError,
 };
@@ -28,16 +27,11 @@ impl ParsePayload for Binary<Vec<u8>> {
         request: &Request,
         body: &mut RequestBody,
     ) -> Result<Self, ParseRequestError> {
-        Ok(Self(<Vec<u8>>::from_request(request, body).await.map_err(
-            |err| {
-                ParseRequestError::ParseRequestBody {
-                    reason: Into::<Error>::into(err)
-                        .reason()
-                        .unwrap_or_default()
-                        .to_string(),
-                }
-            },
-        )?))
+        Ok(Self(
+            <Vec<u8>>::from_request(request, body)
+                .await
+                .map_err(read_body_error)?,
+        ))
     }
 }
 
@@ -60,6 +54,7 @@ impl<T: Into<Vec<u8>> + Send> ApiResponse for Binary<T> {
                     schema: Self::schema_ref(),
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
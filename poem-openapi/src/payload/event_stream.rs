@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use poem::{
+    web::sse::{Event, SSE},
+    IntoResponse, Response,
+};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    types::{ToJSON, Type},
+    ApiResponse,
+};
+
+/// An event stream payload.
+///
+/// Each item in the stream is serialized as JSON and sent as the data of a
+/// [Server-Sent Event](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem_openapi::{payload::EventStream, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/events", method = "get")]
+///     async fn events(&self) -> EventStream<i32> {
+///         EventStream::new(stream::iter(vec![1, 2, 3]))
+///     }
+/// }
+/// ```
+pub struct EventStream<T> {
+    stream: BoxStream<'static, T>,
+    keep_alive: Option<Duration>,
+}
+
+impl<T> EventStream<T> {
+    /// Create an event stream payload using a stream.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+            keep_alive: None,
+        }
+    }
+
+    /// Set the keep alive interval.
+    #[must_use]
+    pub fn keep_alive(self, duration: Duration) -> Self {
+        Self {
+            keep_alive: Some(duration),
+            ..self
+        }
+    }
+}
+
+impl<T: Type> Payload for EventStream<T> {
+    const CONTENT_TYPE: &'static str = "text/event-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(T::schema_ref())),
+            ..MetaSchema::new_with_format("array", "event-stream")
+        }))
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ToJSON + 'static> IntoResponse for EventStream<T> {
+    fn into_response(self) -> Response {
+        let mut sse = SSE::new(
+            self.stream
+                .map(|item| Event::message(item.to_json().to_string())),
+        );
+        if let Some(duration) = self.keep_alive {
+            sse = sse.keep_alive(duration);
+        }
+        sse.into_response()
+    }
+}
+
+impl<T: ToJSON + 'static> ApiResponse for EventStream<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: None,
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
@@ -1,8 +1,8 @@
-use poem::{Error, FromRequest, IntoResponse, Request, RequestBody, Response};
-use serde_json::Value;
+use bytes::Bytes;
+use poem::{FromRequest, IntoResponse, Request, RequestBody, Response};
 
 use crate::{
-    payload::{ParsePayload, Payload},
+    payload::{content_type_matches, read_body_error, ParsePayload, Payload},
     registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
     types::{ParseFromJSON, ToJSON, Type},
     ApiResponse, ParseRequestError,
@@ -23,6 +23,10 @@ impl<T: Type> Payload for Json<T> {
     fn register(registry: &mut Registry) {
         T::register(registry);
     }
+
+    fn check_content_type(content_type: &str) -> bool {
+        content_type_matches(content_type, Self::CONTENT_TYPE, Some("json"))
+    }
 }
 
 #[poem::async_trait]
@@ -31,16 +35,11 @@ impl<T: ParseFromJSON> ParsePayload for Json<T> {
         request: &Request,
         body: &mut RequestBody,
     ) -> Result<Self, ParseRequestError> {
-        let value = poem::web::Json::<Value>::from_request(request, body)
+        let data = Bytes::from_request(request, body)
             .await
-            .map_err(|err| ParseRequestError::ParseRequestBody {
-                reason: Into::<Error>::into(err)
-                    .reason()
-                    .unwrap_or_default()
-                    .to_string(),
-            })?;
+            .map_err(read_body_error)?;
         let value =
-            T::parse_from_json(value.0).map_err(|err| ParseRequestError::ParseRequestBody {
+            T::parse_from_json_bytes(&data).map_err(|err| ParseRequestError::ParseRequestBody {
                 reason: err.into_message(),
             })?;
         Ok(Self(value))
@@ -64,6 +63,7 @@ impl<T: ToJSON> ApiResponse for Json<T> {
                     schema: Self::schema_ref(),
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
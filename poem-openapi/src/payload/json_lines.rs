@@ -0,0 +1,97 @@
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use poem::{Body, IntoResponse, Response};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
+    types::{ToJSON, Type},
+    ApiResponse,
+};
+
+/// A [JSON Lines](https://jsonlines.org/) (NDJSON) payload that is serialized
+/// incrementally from a stream.
+///
+/// Each item is written as a JSON value followed by `\n` while the stream is
+/// consumed, so the clients can process the items as they arrive. The schema
+/// is the schema of an item.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem_openapi::{payload::JsonLines, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/rows", method = "get")]
+///     async fn rows(&self) -> JsonLines<i64> {
+///         JsonLines::new(stream::iter(0..1_000_000))
+///     }
+/// }
+/// ```
+pub struct JsonLines<T> {
+    stream: BoxStream<'static, T>,
+}
+
+impl<T> JsonLines<T> {
+    /// Create a JSON Lines payload using a stream.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+        }
+    }
+}
+
+impl<T: Type> Payload for JsonLines<T> {
+    const CONTENT_TYPE: &'static str = "application/x-ndjson";
+
+    fn schema_ref() -> MetaSchemaRef {
+        T::schema_ref()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ToJSON + 'static> IntoResponse for JsonLines<T> {
+    fn into_response(self) -> Response {
+        let stream = self.stream.map(|item| {
+            let mut data = Vec::with_capacity(64);
+            // serializing a `serde_json::Value` never fails
+            let _ = serde_json::to_writer(&mut data, &item.to_json());
+            data.push(b'\n');
+            Ok::<_, std::io::Error>(Bytes::from(data))
+        });
+
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(Body::from_async_read(tokio_util::io::StreamReader::new(
+                stream,
+            )))
+    }
+}
+
+impl<T: ToJSON + 'static> ApiResponse for JsonLines<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: None,
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
@@ -0,0 +1,107 @@
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use poem::{Body, IntoResponse, Response};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    types::{ToJSON, Type},
+    ApiResponse,
+};
+
+/// A JSON array payload that is serialized incrementally from a stream.
+///
+/// The response body is written as `[`, the items separated by `,`, and `]`
+/// while the stream is consumed, so a huge array can be sent without
+/// collecting all the items into memory. The client receives a regular JSON
+/// array, and the schema is the same as `Json<Vec<T>>`.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem_openapi::{payload::JsonSeq, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/rows", method = "get")]
+///     async fn rows(&self) -> JsonSeq<i64> {
+///         JsonSeq::new(stream::iter(0..1_000_000))
+///     }
+/// }
+/// ```
+pub struct JsonSeq<T> {
+    stream: BoxStream<'static, T>,
+}
+
+impl<T> JsonSeq<T> {
+    /// Create a JSON array payload using a stream.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+        }
+    }
+}
+
+impl<T: Type> Payload for JsonSeq<T> {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(T::schema_ref())),
+            ..MetaSchema::new("array")
+        }))
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ToJSON + 'static> IntoResponse for JsonSeq<T> {
+    fn into_response(self) -> Response {
+        let items = self.stream.enumerate().map(|(idx, item)| {
+            let mut data = Vec::with_capacity(64);
+            if idx > 0 {
+                data.push(b',');
+            }
+            // serializing a `serde_json::Value` never fails
+            let _ = serde_json::to_writer(&mut data, &item.to_json());
+            Ok::<_, std::io::Error>(Bytes::from(data))
+        });
+        let stream = futures_util::stream::once(async { Ok(Bytes::from_static(b"[")) })
+            .chain(items)
+            .chain(futures_util::stream::once(async {
+                Ok(Bytes::from_static(b"]"))
+            }));
+
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(Body::from_async_read(tokio_util::io::StreamReader::new(
+                stream,
+            )))
+    }
+}
+
+impl<T: ToJSON + 'static> ApiResponse for JsonSeq<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: None,
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
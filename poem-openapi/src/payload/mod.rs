@@ -1,13 +1,23 @@
 //! Commonly used payload types.
 
+mod attachment;
 mod binary;
+mod event_stream;
 mod json;
+mod json_lines;
+mod json_seq;
 mod plain_text;
+mod range_binary;
 
+pub use attachment::Attachment;
 pub use binary::Binary;
+pub use event_stream::EventStream;
 pub use json::Json;
+pub use json_lines::JsonLines;
+pub use json_seq::JsonSeq;
 pub use plain_text::PlainText;
-use poem::{Request, RequestBody, Result};
+use poem::{error::ReadBodyError, Error, Request, RequestBody, Result};
+pub use range_binary::{RangeBinary, RangeData, RangeReader};
 
 use crate::{
     registry::{MetaSchemaRef, Registry},
@@ -25,6 +35,61 @@ pub trait Payload: Send {
     /// Register the schema contained in this payload to the registry.
     #[allow(unused_variables)]
     fn register(registry: &mut Registry) {}
+
+    /// Returns `true` if the payload can be parsed from the request with the
+    /// content type.
+    ///
+    /// The default implementation compares the essence of the content type
+    /// (without the parameters) with [`Payload::CONTENT_TYPE`].
+    fn check_content_type(content_type: &str) -> bool {
+        content_type_matches(content_type, Self::CONTENT_TYPE, None)
+    }
+}
+
+/// Compares the essence of the content type with `expected`, case-insensitive
+/// and without allocation. If `suffix` is specified, the subtypes with the
+/// structured syntax suffix are also accepted, e.g. `application/problem+json`
+/// for the suffix `json`.
+pub(crate) fn content_type_matches(
+    content_type: &str,
+    expected: &str,
+    suffix: Option<&str>,
+) -> bool {
+    let essence = match content_type.split_once(';') {
+        Some((essence, _)) => essence,
+        None => content_type,
+    }
+    .trim();
+
+    if essence.eq_ignore_ascii_case(expected) {
+        return true;
+    }
+
+    match (suffix, essence.split_once('/'), expected.split_once('/')) {
+        (Some(suffix), Some((ty, subtype)), Some((expected_ty, _))) => {
+            ty.eq_ignore_ascii_case(expected_ty)
+                && subtype.len() > suffix.len() + 1
+                && subtype.as_bytes()[subtype.len() - suffix.len() - 1] == b'+'
+                && subtype[subtype.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+        }
+        _ => false,
+    }
+}
+
+/// Converts the error of reading the request body to
+/// [`ParseRequestError::ClientDisconnected`] if the client disconnected,
+/// otherwise [`ParseRequestError::ParseRequestBody`].
+pub(crate) fn read_body_error(err: ReadBodyError) -> ParseRequestError {
+    let client_disconnected = err.is_client_disconnected();
+    let reason = Into::<Error>::into(err)
+        .reason()
+        .unwrap_or_default()
+        .to_string();
+    if client_disconnected {
+        ParseRequestError::ClientDisconnected { reason }
+    } else {
+        ParseRequestError::ParseRequestBody { reason }
+    }
 }
 
 /// Represents a payload that can parse from HTTP request.
@@ -49,3 +114,46 @@ impl<T: ParsePayload> ParsePayload for Result<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_matches() {
+        assert!(content_type_matches("text/plain", "text/plain", None));
+        assert!(content_type_matches(
+            "Text/Plain; charset=utf-8",
+            "text/plain",
+            None
+        ));
+        assert!(!content_type_matches("text/html", "text/plain", None));
+        assert!(!content_type_matches("text/plainx", "text/plain", None));
+
+        assert!(content_type_matches(
+            "application/problem+json; charset=utf-8",
+            "application/json",
+            Some("json")
+        ));
+        assert!(!content_type_matches(
+            "application/problem+json",
+            "application/json",
+            None
+        ));
+        assert!(!content_type_matches(
+            "text/x+json",
+            "application/json",
+            Some("json")
+        ));
+        assert!(!content_type_matches(
+            "application/+json",
+            "application/json",
+            Some("json")
+        ));
+        assert!(!content_type_matches(
+            "application/xjson",
+            "application/json",
+            Some("json")
+        ));
+    }
+}
@@ -1,8 +1,7 @@
 use poem::{FromRequest, IntoResponse, Request, RequestBody, Response};
 
 use crate::{
-    payload::{ParsePayload, Payload},
-    poem::Error,
+    payload::{read_body_error, ParsePayload, Payload},
     registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
     types::Type,
     ApiResponse, ParseRequestError,
@@ -26,16 +25,11 @@ impl ParsePayload for PlainText<String> {
         request: &Request,
         body: &mut RequestBody,
     ) -> Result<Self, ParseRequestError> {
-        Ok(Self(String::from_request(request, body).await.map_err(
-            |err| {
-                ParseRequestError::ParseRequestBody {
-                    reason: Into::<Error>::into(err)
-                        .reason()
-                        .unwrap_or_default()
-                        .to_string(),
-                }
-            },
-        )?))
+        Ok(Self(
+            String::from_request(request, body)
+                .await
+                .map_err(read_body_error)?,
+        ))
     }
 }
 
@@ -58,6 +52,7 @@ impl<T: Into<String> + Send> ApiResponse for PlainText<T> {
                     schema: Self::schema_ref(),
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -0,0 +1,342 @@
+use std::{io::SeekFrom, time::SystemTime};
+
+use bytes::Bytes;
+use futures_util::{stream, TryStreamExt};
+use poem::{
+    http::{header, HeaderValue, StatusCode},
+    web::headers::{ETag, Header, HeaderMapExt, IfRange, LastModified},
+    Body, IntoResponse, Response,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use crate::{
+    payload::Payload,
+    registry::{
+        MetaHeader, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
+    },
+    ApiResponse,
+};
+
+/// A binary payload that supports the range requests, for the downloads that
+/// can be resumed or seeked, such as videos.
+///
+/// It is created with the value of the `Range` header of the request. If the
+/// range is satisfiable, the response is `206 Partial Content` with the
+/// requested bytes and the `Content-Range` header, if the range cannot be
+/// satisfied, the response is `416 Range Not Satisfiable`, otherwise the
+/// response is `200 OK` with the whole data. Only a single range is
+/// supported, the requests with multiple ranges receive the whole data.
+///
+/// The data can be in memory, such as `Vec<u8>` or `Bytes`, or be a
+/// [`RangeReader`], which only reads the requested range, such as a file.
+///
+/// If the validators of the data are set by [`RangeBinary::etag`] or
+/// [`RangeBinary::last_modified`], the value of the `If-Range` header passed
+/// to [`RangeBinary::if_range`] is checked, and the whole data is sent if it
+/// has been changed.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::{RangeBinary, RangeReader},
+///     OpenApi,
+/// };
+/// use tokio::fs::File;
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/video", method = "get")]
+///     async fn video(
+///         &self,
+///         #[oai(name = "Range", in = "header")] range: Option<String>,
+///         #[oai(name = "If-Range", in = "header")] if_range: Option<String>,
+///     ) -> poem::Result<RangeBinary<RangeReader<File>>> {
+///         let file = File::open("video.mp4").await?;
+///         let metadata = file.metadata().await?;
+///         Ok(RangeBinary::new(
+///             RangeReader::new(file, metadata.len()),
+///             range.as_deref(),
+///         )
+///         .if_range(if_range.as_deref())
+///         .last_modified(metadata.modified()?)
+///         .content_type("video/mp4"))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RangeBinary<T> {
+    data: T,
+    range: Option<String>,
+    if_range: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<SystemTime>,
+    content_type: Option<String>,
+}
+
+impl<T> RangeBinary<T> {
+    /// Create a binary payload with the data and the value of the `Range`
+    /// header of the request.
+    pub fn new(data: T, range: Option<&str>) -> Self {
+        Self {
+            data,
+            range: range.map(ToString::to_string),
+            if_range: None,
+            etag: None,
+            last_modified: None,
+            content_type: None,
+        }
+    }
+
+    /// Sets the value of the `If-Range` header of the request, the range is
+    /// only sent if it matches the `ETag` or the `Last-Modified` of the data.
+    #[must_use]
+    pub fn if_range(self, if_range: Option<&str>) -> Self {
+        Self {
+            if_range: if_range.map(ToString::to_string),
+            ..self
+        }
+    }
+
+    /// Sets the `ETag` of the data, such as `"abc"`.
+    #[must_use]
+    pub fn etag(self, etag: impl Into<String>) -> Self {
+        Self {
+            etag: Some(etag.into()),
+            ..self
+        }
+    }
+
+    /// Sets the `Last-Modified` time of the data.
+    #[must_use]
+    pub fn last_modified(self, last_modified: SystemTime) -> Self {
+        Self {
+            last_modified: Some(last_modified),
+            ..self
+        }
+    }
+
+    /// Sets the `Content-Type`, the default is `application/octet-stream`.
+    #[must_use]
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            ..self
+        }
+    }
+}
+
+/// The data of [`RangeBinary`].
+pub trait RangeData: Send {
+    /// Returns the size of the data.
+    fn size(&self) -> u64;
+
+    /// Creates the body with the bytes of the data in `start..end`.
+    fn into_body(self, start: u64, end: u64) -> Body;
+}
+
+impl<T: AsRef<[u8]> + Into<Bytes> + Send> RangeData for T {
+    fn size(&self) -> u64 {
+        self.as_ref().len() as u64
+    }
+
+    fn into_body(self, start: u64, end: u64) -> Body {
+        let data: Bytes = self.into();
+        Body::from_bytes(data.slice(start as usize..end as usize))
+    }
+}
+
+/// A reader whose size is known, only the requested range is read from it
+/// by [`RangeBinary`], so the data does not need to be in memory.
+#[derive(Debug)]
+pub struct RangeReader<R> {
+    reader: R,
+    size: u64,
+}
+
+impl<R> RangeReader<R> {
+    /// Create a reader with the size of its data, such as the length of a
+    /// file.
+    pub fn new(reader: R, size: u64) -> Self {
+        Self { reader, size }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send + 'static> RangeData for RangeReader<R> {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn into_body(self, start: u64, end: u64) -> Body {
+        let mut reader = self.reader;
+        let stream = stream::once(async move {
+            reader.seek(SeekFrom::Start(start)).await?;
+            Ok::<_, std::io::Error>(ReaderStream::new(reader.take(end - start)))
+        })
+        .try_flatten();
+        Body::from_async_read(StreamReader::new(Box::pin(stream)))
+    }
+}
+
+/// Parses the value of the `Range` header, returns `Some(None)` if the range
+/// is not satisfiable, and `None` if the range is invalid or has multiple
+/// parts, which are ignored.
+fn parse_range(value: &str, len: u64) -> Option<Option<(u64, u64)>> {
+    let value = value.trim().strip_prefix("bytes=")?;
+    if value.contains(',') {
+        return None;
+    }
+    let (start, end) = value.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        let suffix = end.parse::<u64>().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(None);
+        }
+        (len.saturating_sub(suffix), len - 1)
+    } else {
+        let start = start.parse::<u64>().ok()?;
+        let end = match end {
+            "" => u64::MAX,
+            end => end.parse::<u64>().ok()?,
+        };
+        if start > end {
+            return None;
+        }
+        if start >= len {
+            return Some(None);
+        }
+        (start, end.min(len - 1))
+    };
+    Some(Some(range))
+}
+
+impl<T: Send> Payload for RangeBinary<T> {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+impl<T: RangeData> IntoResponse for RangeBinary<T> {
+    fn into_response(self) -> Response {
+        let len = self.data.size();
+        let etag = self.etag.and_then(|etag| etag.parse::<ETag>().ok());
+        let last_modified = self.last_modified.map(LastModified::from);
+
+        let mut resp = Response::builder()
+            .header(header::ACCEPT_RANGES, "bytes")
+            .content_type(self.content_type.as_deref().unwrap_or(Self::CONTENT_TYPE))
+            .finish();
+        if let Some(etag) = etag.clone() {
+            resp.headers_mut().typed_insert(etag);
+        }
+        if let Some(last_modified) = last_modified {
+            resp.headers_mut().typed_insert(last_modified);
+        }
+
+        let range = self
+            .range
+            .filter(|_| match &self.if_range {
+                Some(if_range) => HeaderValue::from_str(if_range)
+                    .ok()
+                    .and_then(|value| IfRange::decode(&mut std::iter::once(&value)).ok())
+                    .map(|if_range| !if_range.is_modified(etag.as_ref(), last_modified.as_ref()))
+                    .unwrap_or_default(),
+                None => true,
+            })
+            .and_then(|range| parse_range(&range, len));
+        match range {
+            Some(Some((start, end))) => {
+                resp.set_status(StatusCode::PARTIAL_CONTENT);
+                resp.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, len)).unwrap(),
+                );
+                resp.set_body(self.data.into_body(start, end + 1));
+            }
+            Some(None) => {
+                resp.set_status(StatusCode::RANGE_NOT_SATISFIABLE);
+                resp.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&format!("bytes */{}", len)).unwrap(),
+                );
+                resp.headers_mut().remove(header::CONTENT_TYPE);
+            }
+            None => resp.set_body(self.data.into_body(0, len)),
+        }
+        resp
+    }
+}
+
+fn accept_ranges_header() -> MetaHeader {
+    MetaHeader {
+        name: "Accept-Ranges",
+        description: Some("Indicates that the range requests are supported."),
+        required: true,
+        schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+    }
+}
+
+fn content_range_header(description: &'static str) -> MetaHeader {
+    MetaHeader {
+        name: "Content-Range",
+        description: Some(description),
+        required: true,
+        schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+    }
+}
+
+impl<T: RangeData> ApiResponse for RangeBinary<T> {
+    fn meta() -> MetaResponses {
+        let content = || {
+            vec![MetaMediaType {
+                content_type: Self::CONTENT_TYPE,
+                schema: Self::schema_ref(),
+            }]
+        };
+
+        MetaResponses {
+            responses: vec![
+                MetaResponse {
+                    description: Some("The whole data."),
+                    status: Some(200),
+                    content: content(),
+                    headers: vec![accept_ranges_header()],
+                    links: vec![],
+                },
+                MetaResponse {
+                    description: Some("The requested range of the data."),
+                    status: Some(206),
+                    content: content(),
+                    headers: vec![
+                        accept_ranges_header(),
+                        content_range_header(
+                            "The range of the data in the response, such as `bytes 0-99/1000`.",
+                        ),
+                    ],
+                    links: vec![],
+                },
+                MetaResponse {
+                    description: Some("The requested range is not satisfiable."),
+                    status: Some(416),
+                    content: vec![],
+                    headers: vec![content_range_header(
+                        "The size of the data, such as `bytes */1000`.",
+                    )],
+                    links: vec![],
+                },
+            ],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
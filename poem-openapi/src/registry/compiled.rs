@@ -0,0 +1,197 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::registry::{MetaSchema, MetaSchemaRef};
+
+static PATTERNS: Lazy<RwLock<HashMap<String, Option<Arc<Regex>>>>> = Lazy::new(Default::default);
+
+/// Returns the compiled regular expression of the pattern, or `None` if the
+/// pattern is invalid.
+pub(crate) fn compiled_pattern(pattern: &str) -> Option<Arc<Regex>> {
+    if let Some(regex) = PATTERNS.read().unwrap().get(pattern) {
+        return regex.clone();
+    }
+    let regex = Regex::new(pattern).ok().map(Arc::new);
+    PATTERNS
+        .write()
+        .unwrap()
+        .entry(pattern.to_string())
+        .or_insert(regex)
+        .clone()
+}
+
+/// A [`MetaSchema`] whose constraints are prepared for checking the values,
+/// such as the compiled regular expression of the `pattern`.
+#[derive(Debug, Clone)]
+pub(crate) struct CompiledSchema {
+    schema: MetaSchema,
+    pattern: Option<Arc<Regex>>,
+    items: Option<Box<CompiledSchema>>,
+}
+
+impl CompiledSchema {
+    pub(crate) fn new(schema: &MetaSchema) -> Self {
+        let compile_inline = |schema: &MetaSchemaRef| match schema {
+            MetaSchemaRef::Inline(schema) => Some(CompiledSchema::new(schema)),
+            MetaSchemaRef::Reference(_) => None,
+        };
+
+        Self {
+            schema: MetaSchema {
+                properties: Vec::new(),
+                items: None,
+                ..schema.clone()
+            },
+            pattern: schema.pattern.as_deref().and_then(compiled_pattern),
+            items: schema
+                .items
+                .as_deref()
+                .and_then(compile_inline)
+                .map(Box::new),
+        }
+    }
+
+    /// Returns the default value.
+    pub(crate) fn default(&self) -> Option<&Value> {
+        self.schema.default.as_ref()
+    }
+
+    /// Checks the type, the enumeration values and the validators of the
+    /// value, and the items of the arrays. The properties of the objects are
+    /// not checked.
+    pub(crate) fn check(&self, value: &Value) -> Result<(), String> {
+        let schema = &self.schema;
+        let type_matches = match schema.ty {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            _ => true,
+        };
+        if !type_matches {
+            return Err(format!(
+                r#"expected input type "{}", found {}."#,
+                schema.ty, value
+            ));
+        }
+
+        if !schema.enum_items.is_empty() && !schema.enum_items.contains(value) {
+            return Err("expect a valid enumeration value.".to_string());
+        }
+
+        if !crate::validation::is_skipped() {
+            if let Some(validator) = self.failed_validator(value) {
+                return Err(format!("verification failed. {}", validator));
+            }
+        }
+
+        if let (Value::Array(items), Some(items_schema)) = (value, &self.items) {
+            for item in items {
+                items_schema.check(item)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the validator that the value does not satisfy, formatted like
+    /// the validators in the [`validation`](crate::validation) module.
+    fn failed_validator(&self, value: &Value) -> Option<String> {
+        let schema = &self.schema;
+        match value {
+            Value::String(s) => {
+                let len = s.chars().count();
+                if let Some(n) = schema.max_length.filter(|n| len > *n) {
+                    return Some(format!("maxLength({})", n));
+                }
+                if let Some(n) = schema.min_length.filter(|n| len < *n) {
+                    return Some(format!("minLength({})", n));
+                }
+                if let Some(pattern) = &schema.pattern {
+                    // an invalid pattern is never satisfied
+                    if !self.pattern.as_ref().map_or(false, |re| re.is_match(s)) {
+                        return Some(format!("pattern(\"{}\")", pattern));
+                    }
+                }
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                if let Some(maximum) = schema.maximum {
+                    let exclusive = schema.exclusive_maximum.unwrap_or_default();
+                    if n > maximum || (exclusive && n == maximum) {
+                        return Some(format!("maximum({}, exclusive: {})", maximum, exclusive));
+                    }
+                }
+                if let Some(minimum) = schema.minimum {
+                    let exclusive = schema.exclusive_minimum.unwrap_or_default();
+                    if n < minimum || (exclusive && n == minimum) {
+                        return Some(format!("minimum({}, exclusive: {})", minimum, exclusive));
+                    }
+                }
+                if let Some(multiple_of) = schema.multiple_of {
+                    if n % multiple_of != 0.0 {
+                        return Some(format!("multipleOf({})", multiple_of));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(n) = schema.max_items.filter(|n| items.len() > *n) {
+                    return Some(format!("maxItems({})", n));
+                }
+                if let Some(n) = schema.min_items.filter(|n| items.len() < *n) {
+                    return Some(format!("minItems({})", n));
+                }
+                if schema.unique_items == Some(true) {
+                    let mut set = HashSet::new();
+                    if !items.iter().all(|item| set.insert(item.to_string())) {
+                        return Some("uniqueItems()".to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn pattern() {
+        let schema = CompiledSchema::new(&MetaSchema {
+            pattern: Some("^[a-z]+$".into()),
+            ..MetaSchema::new("string")
+        });
+        assert!(schema.check(&json!("abc")).is_ok());
+        assert_eq!(
+            schema.check(&json!("ABC")),
+            Err(r#"verification failed. pattern("^[a-z]+$")"#.to_string())
+        );
+        assert!(Arc::ptr_eq(
+            schema.pattern.as_ref().unwrap(),
+            &compiled_pattern("^[a-z]+$").unwrap()
+        ));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(compiled_pattern("(").is_none());
+        let schema = CompiledSchema::new(&MetaSchema {
+            pattern: Some("(".into()),
+            ..MetaSchema::new("string")
+        });
+        assert!(schema.check(&json!("(")).is_err());
+    }
+}
@@ -1,12 +1,17 @@
+mod compiled;
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
 mod ser;
 
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     collections::{BTreeMap, HashMap, HashSet},
     hash::{Hash, Hasher},
 };
 
-use poem::http::Method;
+pub(crate) use compiled::{compiled_pattern, CompiledSchema};
+use http::Method;
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) use ser::Document;
 use serde::{ser::SerializeMap, Serialize, Serializer};
 use serde_json::Value;
@@ -61,6 +66,8 @@ pub struct MetaSchema {
     pub properties: Vec<(&'static str, MetaSchemaRef)>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub items: Option<Box<MetaSchemaRef>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<Box<MetaAdditionalProperties>>,
     #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_items: Vec<Value>,
     #[serde(skip_serializing_if = "is_false")]
@@ -121,6 +128,7 @@ impl MetaSchema {
         required: vec![],
         properties: vec![],
         items: None,
+        additional_properties: None,
         enum_items: vec![],
         deprecated: false,
         one_of: vec![],
@@ -151,6 +159,7 @@ impl MetaSchema {
             required: vec![],
             properties: vec![],
             items: None,
+            additional_properties: None,
             enum_items: vec![],
             deprecated: false,
             one_of: vec![],
@@ -182,6 +191,7 @@ impl MetaSchema {
             required: vec![],
             properties: vec![],
             items: None,
+            additional_properties: None,
             enum_items: vec![],
             deprecated: false,
             one_of: vec![],
@@ -215,6 +225,7 @@ impl MetaSchema {
             write_only,
             title,
             description,
+            format,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -246,6 +257,7 @@ impl MetaSchema {
             default,
             title,
             description,
+            format,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -262,6 +274,13 @@ impl MetaSchema {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MetaAdditionalProperties {
+    Allowed(bool),
+    Schema(MetaSchemaRef),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MetaSchemaRef {
     Inline(Box<MetaSchema>),
@@ -317,9 +336,26 @@ pub enum MetaParamIn {
     CookieSigned,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MetaParamStyle {
+    /// `;id=5`
+    Matrix,
+    /// `.5`
+    Label,
+    /// `id=3,4,5` or `id=3&id=4&id=5` if exploded
+    Form,
+    /// `id=3%204%205`
+    SpaceDelimited,
+    /// `id=3|4|5`
+    PipeDelimited,
+    /// `filter[name]=x&filter[age]=3`
+    DeepObject,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaOperationParam {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub schema: MetaSchemaRef,
     #[serde(rename = "in")]
     pub in_type: MetaParamIn,
@@ -327,16 +363,22 @@ pub struct MetaOperationParam {
     pub description: Option<&'static str>,
     pub required: bool,
     pub deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<MetaParamStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(rename = "x-wildcard", skip_serializing_if = "is_false")]
+    pub wildcard: bool,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaMediaType {
     #[serde(skip)]
     pub content_type: &'static str,
     pub schema: MetaSchemaRef,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<&'static str>,
@@ -359,12 +401,12 @@ fn serialize_content<S: Serializer>(
     s.end()
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MetaResponses {
     pub responses: Vec<MetaResponse>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaHeader {
     #[serde(skip)]
     pub name: &'static str,
@@ -375,7 +417,7 @@ pub struct MetaHeader {
     pub schema: MetaSchemaRef,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<&'static str>,
@@ -391,6 +433,11 @@ pub struct MetaResponse {
         serialize_with = "serialize_headers"
     )]
     pub headers: Vec<MetaHeader>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_links"
+    )]
+    pub links: Vec<MetaLink>,
 }
 
 fn serialize_headers<S: Serializer>(
@@ -404,16 +451,55 @@ fn serialize_headers<S: Serializer>(
     s.end()
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetaLink {
+    #[serde(skip)]
+    pub name: &'static str,
+    #[serde(rename = "operationId")]
+    pub operation_id: Cow<'static, str>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_link_parameters"
+    )]
+    pub parameters: Vec<(&'static str, &'static str)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'static str>,
+}
+
+fn serialize_links<S: Serializer>(links: &[MetaLink], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for link in links {
+        s.serialize_entry(link.name, link)?;
+    }
+    s.end()
+}
+
+fn serialize_link_parameters<S: Serializer>(
+    parameters: &[(&'static str, &'static str)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for (name, value) in parameters {
+        s.serialize_entry(name, value)?;
+    }
+    s.end()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetaOperation {
     #[serde(skip)]
     pub method: Method,
+    /// The name of the method of the operation defined with `#[OpenApi]`.
+    #[serde(skip)]
+    pub name: Option<&'static str>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub tags: Vec<&'static str>,
+    pub tags: Vec<Cow<'static, str>>,
+    #[serde(rename = "operationId", skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<Cow<'static, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub summary: Option<&'static str>,
+    pub summary: Option<Cow<'static, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
+    pub description: Option<Cow<'static, str>>,
     #[serde(rename = "parameters", skip_serializing_if = "Vec::is_empty")]
     pub params: Vec<MetaOperationParam>,
     #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
@@ -423,11 +509,13 @@ pub struct MetaOperation {
     pub deprecated: bool,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub security: Vec<HashMap<&'static str, Vec<&'static str>>>,
+    #[serde(rename = "x-operation-cost", skip_serializing_if = "Option::is_none")]
+    pub cost: Option<u32>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MetaPath {
-    pub path: &'static str,
+    pub path: Cow<'static, str>,
     pub operations: Vec<MetaOperation>,
 }
 
@@ -548,7 +636,7 @@ pub struct MetaSecurityScheme {
     pub openid_connect_url: Option<&'static str>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MetaApi {
     pub paths: Vec<MetaPath>,
 }
@@ -558,6 +646,7 @@ pub struct Registry {
     pub schemas: HashMap<&'static str, MetaSchema>,
     pub tags: HashSet<MetaTag>,
     pub security_schemes: BTreeMap<&'static str, MetaSecurityScheme>,
+    registered_types: HashSet<&'static str>,
 }
 
 impl Registry {
@@ -578,6 +667,18 @@ impl Registry {
         }
     }
 
+    /// Calls `f` to register the types that `T` depends on, `f` is only
+    /// called the first time for each type, so that the shared types are not
+    /// walked repeatedly.
+    pub fn register_once<T: ?Sized, F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Registry),
+    {
+        if self.registered_types.insert(std::any::type_name::<T>()) {
+            f(self);
+        }
+    }
+
     pub fn create_tag(&mut self, tag: MetaTag) {
         self.tags.insert(tag);
     }
@@ -5,14 +5,15 @@ use serde::{
     Serialize, Serializer,
 };
 
-use crate::registry::{
-    MetaApi, MetaInfo, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef, MetaSecurityScheme,
-    MetaServer, Registry,
+use crate::{
+    registry::{
+        MetaApi, MetaInfo, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef, MetaSecurityScheme,
+        MetaServer, Registry,
+    },
+    OpenApiVersion,
 };
 
-const OPENAPI_VERSION: &str = "3.0.0";
-
-impl<'a> Serialize for MetaSchemaRef {
+impl Serialize for MetaSchemaRef {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
             MetaSchemaRef::Inline(schema) => schema.serialize(serializer),
@@ -32,13 +33,25 @@ impl<'a> Serialize for PathMap<'a> {
         let mut s = serializer.serialize_map(Some(self.0.len()))?;
         for api in self.0 {
             for path in &api.paths {
-                s.serialize_entry(path.path, path)?;
+                s.serialize_entry(&path.path, path)?;
             }
         }
         s.end()
     }
 }
 
+struct WebhookMap<'a>(&'a [MetaPath]);
+
+impl<'a> Serialize for WebhookMap<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_map(Some(self.0.len()))?;
+        for webhook in self.0 {
+            s.serialize_entry(&webhook.path, webhook)?;
+        }
+        s.end()
+    }
+}
+
 impl Serialize for MetaPath {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
@@ -65,9 +78,11 @@ impl Serialize for MetaResponses {
 }
 
 pub(crate) struct Document<'a> {
+    pub(crate) version: OpenApiVersion,
     pub(crate) info: Option<&'a MetaInfo>,
     pub(crate) servers: &'a [MetaServer],
     pub(crate) apis: &'a [MetaApi],
+    pub(crate) webhooks: &'a [MetaPath],
     pub(crate) registry: &'a Registry,
 }
 
@@ -80,13 +95,16 @@ impl<'a> Serialize for Document<'a> {
             security_schemes: &'a BTreeMap<&'static str, MetaSecurityScheme>,
         }
 
-        let mut s = serializer.serialize_struct("OpenAPI", 6)?;
+        let mut s = serializer.serialize_struct("OpenAPI", 7)?;
 
-        s.serialize_field("openapi", OPENAPI_VERSION)?;
+        s.serialize_field("openapi", self.version.as_str())?;
         s.serialize_field("info", &self.info)?;
         s.serialize_field("servers", self.servers)?;
         s.serialize_field("tags", &self.registry.tags)?;
         s.serialize_field("paths", &PathMap(self.apis))?;
+        if !self.webhooks.is_empty() {
+            s.serialize_field("webhooks", &WebhookMap(self.webhooks))?;
+        }
         s.serialize_field(
             "components",
             &Components {
@@ -0,0 +1,178 @@
+use std::sync::{Arc, Mutex};
+
+use poem::{
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    web::cookie::Cookie,
+    Response,
+};
+
+#[derive(Default)]
+struct Inner {
+    status: Option<StatusCode>,
+    headers: HeaderMap,
+}
+
+/// Changes the response of an operation from inside the operation.
+///
+/// The operation that has an argument of this type can override the status
+/// code, and append the headers and cookies of the response without returning
+/// a custom [`ApiResponse`](crate::ApiResponse). The documented responses of
+/// the operation are not changed.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     http::{StatusCode, Uri},
+///     web::cookie::Cookie,
+///     Endpoint, IntoEndpoint, Request,
+/// };
+/// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService, ResponseContext};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/login", method = "post")]
+///     async fn login(&self, ctx: ResponseContext) -> PlainText<&'static str> {
+///         ctx.set_status(StatusCode::CREATED);
+///         ctx.append_header("x-session-version", "2");
+///         ctx.set_cookie(Cookie::new_with_str("session", "abc"));
+///         PlainText("hello")
+///     }
+/// }
+///
+/// let ep = OpenApiService::new(Api).into_endpoint();
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = ep
+///     .call(
+///         Request::builder()
+///             .method(poem::http::Method::POST)
+///             .uri(Uri::from_static("/login"))
+///             .finish(),
+///     )
+///     .await;
+/// assert_eq!(resp.status(), StatusCode::CREATED);
+/// assert_eq!(resp.headers().get("x-session-version").unwrap(), "2");
+/// assert_eq!(resp.headers().get("set-cookie").unwrap(), "session=abc");
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct ResponseContext(Arc<Mutex<Inner>>);
+
+impl ResponseContext {
+    /// Overrides the status code of the response.
+    pub fn set_status(&self, status: StatusCode) {
+        self.0.lock().unwrap().status = Some(status);
+    }
+
+    /// Appends a header to the response.
+    ///
+    /// The header is ignored if the key or the value is invalid.
+    pub fn append_header<K, V>(&self, key: K, value: V)
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        if let (Ok(key), Ok(value)) = (key.try_into(), value.try_into()) {
+            self.0.lock().unwrap().headers.append(key, value);
+        }
+    }
+
+    /// Appends a `Set-Cookie` header to the response.
+    pub fn set_cookie(&self, cookie: Cookie) {
+        self.append_header(header::SET_COOKIE, cookie.to_string());
+    }
+
+    /// Applies the changes to the response.
+    pub(crate) fn apply(&self, mut resp: Response) -> Response {
+        let inner = self.0.lock().unwrap();
+        if let Some(status) = inner.status {
+            resp.set_status(status);
+        }
+        for (key, value) in &inner.headers {
+            resp.headers_mut().append(key, value.clone());
+        }
+        resp
+    }
+}
+
+/// Attaches the warnings to the response of an operation from inside the
+/// operation.
+///
+/// The warnings are sent in the `Warning` header of
+/// [RFC 7234](https://datatracker.ietf.org/doc/html/rfc7234#section-5.5) or
+/// in the custom `X-API-Warn` header, so the clients can be notified of
+/// something like a soft deprecation without changing the response. The
+/// operation that has an argument of this type documents both headers as the
+/// optional headers of all its responses.
+///
+/// # Example
+///
+/// ```
+/// use poem::{http::Uri, Endpoint, IntoEndpoint, Request};
+/// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService, ResponseWarnings};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "get")]
+///     async fn users(&self, warnings: ResponseWarnings) -> PlainText<&'static str> {
+///         warnings.warning(299, "the `page` parameter is deprecated");
+///         warnings.api_warn("use /v2/users instead");
+///         PlainText("hello")
+///     }
+/// }
+///
+/// let ep = OpenApiService::new(Api).into_endpoint();
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = ep
+///     .call(Request::builder().uri(Uri::from_static("/users")).finish())
+///     .await;
+/// assert_eq!(
+///     resp.headers().get("warning").unwrap(),
+///     r#"299 - "the `page` parameter is deprecated""#
+/// );
+/// assert_eq!(
+///     resp.headers().get("x-api-warn").unwrap(),
+///     "use /v2/users instead"
+/// );
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct ResponseWarnings(ResponseContext);
+
+impl ResponseWarnings {
+    #[doc(hidden)]
+    pub fn new(ctx: ResponseContext) -> Self {
+        Self(ctx)
+    }
+
+    /// Appends a `Warning` header with the three digits `code`, such as `299`
+    /// (miscellaneous persistent warning), and the `text`.
+    ///
+    /// The header is ignored if the code is not three digits or the text is
+    /// not a valid header value.
+    pub fn warning(&self, code: u16, text: impl AsRef<str>) {
+        if !(100..=999).contains(&code) {
+            return;
+        }
+        let mut value = format!("{} - \"", code);
+        for c in text.as_ref().chars() {
+            if c == '"' || c == '\\' {
+                value.push('\\');
+            }
+            value.push(c);
+        }
+        value.push('"');
+        self.0.append_header(header::WARNING, value);
+    }
+
+    /// Appends a `X-API-Warn` header with the `text`.
+    ///
+    /// The header is ignored if the text is not a valid header value.
+    pub fn api_warn(&self, text: impl AsRef<str>) {
+        self.0.append_header("x-api-warn", text.as_ref());
+    }
+}
@@ -0,0 +1,149 @@
+use std::{io::Write, path::Path};
+
+use poem::{listener::Acceptor, Server};
+
+use crate::{OpenApi, OpenApiService};
+
+/// The command line argument that dumps the specification.
+const DUMP_ARG: &str = "--dump-openapi";
+
+/// Extension trait for [`Server`] to export the specification.
+pub trait ServerExt: Sized {
+    /// If the command line arguments contain `--dump-openapi <path>`, writes
+    /// the specification of `service` to the path and exits the process,
+    /// otherwise returns the server unchanged.
+    ///
+    /// The specification is written in YAML if the path ends with `.yaml` or
+    /// `.yml` (requires the `yaml` feature), otherwise in JSON. It is written
+    /// to the standard output if the path is `-`.
+    ///
+    /// This lets the binaries export their contracts, e.g. in the CI, without
+    /// handling the command line by themselves.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poem::{listener::TcpListener, Route, Server};
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService, ServerExt};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), std::io::Error> {
+    ///     let api_service = OpenApiService::new(Api);
+    ///     Server::new(TcpListener::bind("127.0.0.1:3000"))
+    ///         .await?
+    ///         .dump_spec_and_exit(&api_service)
+    ///         .run(Route::new().nest("/api", api_service))
+    ///         .await
+    /// }
+    /// ```
+    fn dump_spec_and_exit<T: OpenApi>(self, service: &OpenApiService<T>) -> Self;
+}
+
+impl<A: Acceptor> ServerExt for Server<A> {
+    fn dump_spec_and_exit<T: OpenApi>(self, service: &OpenApiService<T>) -> Self {
+        let path = match dump_path(std::env::args()) {
+            Some(path) => path,
+            None => return self,
+        };
+
+        match write_spec(service, &path) {
+            Ok(()) => std::process::exit(0),
+            Err(err) => {
+                eprintln!("failed to dump the specification to `{}`: {}", path, err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Returns the path of the `--dump-openapi <path>` or `--dump-openapi=<path>`
+/// argument.
+fn dump_path(mut args: impl Iterator<Item = String>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == DUMP_ARG {
+            return args.next();
+        }
+        if let Some(path) = arg
+            .strip_prefix(DUMP_ARG)
+            .and_then(|value| value.strip_prefix('='))
+        {
+            return Some(path.to_string());
+        }
+    }
+    None
+}
+
+fn write_spec<T: OpenApi>(service: &OpenApiService<T>, path: &str) -> std::io::Result<()> {
+    let spec = if is_yaml(path) {
+        #[cfg(feature = "yaml")]
+        {
+            service.spec_yaml()
+        }
+        #[cfg(not(feature = "yaml"))]
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "the `yaml` feature is required to dump the specification in YAML",
+            ));
+        }
+    } else {
+        service.spec()
+    };
+
+    if path == "-" {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(spec.as_bytes())?;
+        stdout.flush()
+    } else {
+        std::fs::write(path, spec)
+    }
+}
+
+fn is_yaml(path: &str) -> bool {
+    matches!(
+        Path::new(path).extension().and_then(|ext| ext.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parse_dump_path() {
+        assert_eq!(dump_path(args(&["app"])), None);
+        assert_eq!(dump_path(args(&["app", "--dump-openapi"])), None);
+        assert_eq!(
+            dump_path(args(&["app", "--dump-openapi", "spec.json"])).as_deref(),
+            Some("spec.json")
+        );
+        assert_eq!(
+            dump_path(args(&["app", "-v", "--dump-openapi=spec.yaml"])).as_deref(),
+            Some("spec.yaml")
+        );
+        assert_eq!(dump_path(args(&["app", "--dump-openapi-x"])), None);
+
+        assert!(is_yaml("spec.yaml"));
+        assert!(is_yaml("dir/spec.yml"));
+        assert!(!is_yaml("spec.json"));
+        assert!(!is_yaml("-"));
+    }
+}
@@ -0,0 +1,597 @@
+//! Checks whether a specification is backward compatible with a previously
+//! published one.
+//!
+//! The published specification is compared with the current specification,
+//! and the changes that may break the existing clients are reported, such as
+//! the removed operations, the narrowed types of the inputs and the new
+//! required fields. The descriptions, the new operations and the other
+//! compatible changes are ignored.
+//!
+//! The inputs (the parameters and the request bodies) are breaking if the
+//! current specification rejects a value that was accepted, and the outputs
+//! (the response bodies) are breaking if the current specification produces a
+//! value that the clients do not expect.
+//!
+//! # Example
+//!
+//! Fail the tests if the API is changed incompatibly:
+//!
+//! ```
+//! use poem_openapi::{payload::PlainText, spec_compat, OpenApi, OpenApiService};
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/hello", method = "get")]
+//!     async fn index(
+//!         &self,
+//!         #[oai(name = "name", in = "query")] name: Option<String>,
+//!     ) -> PlainText<String> {
+//!         PlainText(format!("hello, {}!", name.unwrap_or_default()))
+//!     }
+//! }
+//!
+//! let current = OpenApiService::new(Api).spec();
+//! // e.g. `std::fs::read_to_string("openapi.json")`
+//! let published = current.clone();
+//!
+//! let changes = spec_compat::check(&published, &current).unwrap();
+//! assert!(changes.is_empty(), "breaking changes: {:#?}", changes);
+//! ```
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
+
+use serde_json::{Map, Value};
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// A change that may break the existing clients.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BreakingChange {
+    /// Where the change is, such as `GET /users/{id} response 200
+    /// (application/json).name`.
+    pub location: String,
+
+    /// The kind of the change.
+    pub kind: BreakingChangeKind,
+}
+
+impl Display for BreakingChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.kind)
+    }
+}
+
+/// The kinds of the [`BreakingChange`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum BreakingChangeKind {
+    /// The operation is removed.
+    OperationRemoved,
+
+    /// The parameter is added as required, or the optional parameter becomes
+    /// required.
+    ParameterRequired,
+
+    /// The request body becomes required.
+    RequestBodyRequired,
+
+    /// The content type of the request body or the response is removed.
+    ContentTypeRemoved,
+
+    /// The type is changed.
+    TypeChanged {
+        /// The type in the published specification.
+        old: String,
+
+        /// The type in the current specification.
+        new: String,
+    },
+
+    /// The value of the input enumeration is removed.
+    EnumValueRemoved {
+        /// The removed value.
+        value: String,
+    },
+
+    /// The value of the output enumeration is added.
+    EnumValueAdded {
+        /// The added value.
+        value: String,
+    },
+
+    /// The alternative of the input `oneOf` schema is removed.
+    VariantRemoved {
+        /// The name of the removed schema.
+        name: String,
+    },
+
+    /// The alternative of the output `oneOf` schema is added.
+    VariantAdded {
+        /// The name of the added schema.
+        name: String,
+    },
+
+    /// The validator of the input is added or narrowed, such as a smaller
+    /// `maxLength`.
+    ConstraintNarrowed {
+        /// The name of the validator.
+        constraint: &'static str,
+    },
+
+    /// The field of the input object is added as required, or the optional
+    /// field becomes required.
+    RequiredFieldAdded {
+        /// The name of the field.
+        name: String,
+    },
+
+    /// The required field of the output object is removed or becomes
+    /// optional.
+    RequiredFieldRemoved {
+        /// The name of the field.
+        name: String,
+    },
+}
+
+impl Display for BreakingChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakingChangeKind::OperationRemoved => f.write_str("the operation is removed"),
+            BreakingChangeKind::ParameterRequired => f.write_str("the parameter becomes required"),
+            BreakingChangeKind::RequestBodyRequired => {
+                f.write_str("the request body becomes required")
+            }
+            BreakingChangeKind::ContentTypeRemoved => f.write_str("the content type is removed"),
+            BreakingChangeKind::TypeChanged { old, new } => {
+                write!(f, "the type is changed from `{}` to `{}`", old, new)
+            }
+            BreakingChangeKind::EnumValueRemoved { value } => {
+                write!(f, "the enumeration value `{}` is removed", value)
+            }
+            BreakingChangeKind::EnumValueAdded { value } => {
+                write!(f, "the enumeration value `{}` is added", value)
+            }
+            BreakingChangeKind::VariantRemoved { name } => {
+                write!(f, "the variant `{}` is removed", name)
+            }
+            BreakingChangeKind::VariantAdded { name } => {
+                write!(f, "the variant `{}` is added", name)
+            }
+            BreakingChangeKind::ConstraintNarrowed { constraint } => {
+                write!(f, "the `{}` validator is narrowed", constraint)
+            }
+            BreakingChangeKind::RequiredFieldAdded { name } => {
+                write!(f, "the required field `{}` is added", name)
+            }
+            BreakingChangeKind::RequiredFieldRemoved { name } => {
+                write!(f, "the required field `{}` is removed", name)
+            }
+        }
+    }
+}
+
+/// Parses the published and the current specifications in JSON format, and
+/// returns the breaking changes, see [`compare`].
+pub fn check(published: &str, current: &str) -> serde_json::Result<Vec<BreakingChange>> {
+    Ok(compare(
+        &serde_json::from_str(published)?,
+        &serde_json::from_str(current)?,
+    ))
+}
+
+/// Returns the changes of the current specification that may break the
+/// clients of the published specification.
+pub fn compare(published: &Value, current: &Value) -> Vec<BreakingChange> {
+    let mut ctx = Context {
+        old_spec: published,
+        new_spec: current,
+        visited: HashSet::new(),
+        changes: Vec::new(),
+    };
+
+    for (path, old_item) in published["paths"].as_object().into_iter().flatten() {
+        for (method, old_operation) in old_item.as_object().into_iter().flatten() {
+            let location = format!("{} {}", method.to_uppercase(), path);
+            match current["paths"][path].get(method) {
+                Some(new_operation) => {
+                    ctx.compare_operation(&location, old_operation, new_operation)
+                }
+                None => ctx.add(location, BreakingChangeKind::OperationRemoved),
+            }
+        }
+    }
+
+    ctx.changes
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Direction {
+    Input,
+    Output,
+}
+
+struct Context<'a> {
+    old_spec: &'a Value,
+    new_spec: &'a Value,
+    /// The pairs of the compared schema references, each pair is compared
+    /// once, which also prevents the recursive schemas from being compared
+    /// endlessly.
+    visited: HashSet<(String, String, Direction)>,
+    changes: Vec<BreakingChange>,
+}
+
+impl<'a> Context<'a> {
+    fn add(&mut self, location: impl Into<String>, kind: BreakingChangeKind) {
+        self.changes.push(BreakingChange {
+            location: location.into(),
+            kind,
+        });
+    }
+
+    fn compare_operation(&mut self, location: &str, old: &Value, new: &Value) {
+        for new_param in new["parameters"].as_array().into_iter().flatten() {
+            let param_location = format!(
+                "{} parameter `{}` ({})",
+                location,
+                new_param["name"].as_str().unwrap_or_default(),
+                new_param["in"].as_str().unwrap_or_default()
+            );
+            match find_param(&old["parameters"], &new_param["name"], &new_param["in"]) {
+                Some(old_param) => {
+                    if is_true(&new_param["required"]) && !is_true(&old_param["required"]) {
+                        self.add(&param_location, BreakingChangeKind::ParameterRequired);
+                    }
+                    self.compare_schema(
+                        &param_location,
+                        Direction::Input,
+                        &old_param["schema"],
+                        &new_param["schema"],
+                    );
+                }
+                None if is_true(&new_param["required"]) => {
+                    self.add(param_location, BreakingChangeKind::ParameterRequired)
+                }
+                None => {}
+            }
+        }
+
+        let old_body = &old["requestBody"];
+        let new_body = &new["requestBody"];
+        if !new_body.is_null() {
+            let body_location = format!("{} request body", location);
+            if is_true(&new_body["required"]) && !is_true(&old_body["required"]) {
+                self.add(&body_location, BreakingChangeKind::RequestBodyRequired);
+            }
+            if !old_body.is_null() {
+                self.compare_content(
+                    &body_location,
+                    Direction::Input,
+                    &old_body["content"],
+                    &new_body["content"],
+                );
+            }
+        }
+
+        for (status, old_resp) in old["responses"].as_object().into_iter().flatten() {
+            if let Some(new_resp) = new["responses"].get(status) {
+                self.compare_content(
+                    &format!("{} response {}", location, status),
+                    Direction::Output,
+                    &old_resp["content"],
+                    &new_resp["content"],
+                );
+            }
+        }
+    }
+
+    fn compare_content(&mut self, location: &str, direction: Direction, old: &Value, new: &Value) {
+        for (content_type, old_media) in old.as_object().into_iter().flatten() {
+            let media_location = format!("{} ({})", location, content_type);
+            match new.get(content_type) {
+                Some(new_media) => self.compare_schema(
+                    &media_location,
+                    direction,
+                    &old_media["schema"],
+                    &new_media["schema"],
+                ),
+                None => self.add(media_location, BreakingChangeKind::ContentTypeRemoved),
+            }
+        }
+    }
+
+    fn compare_schema(&mut self, location: &str, direction: Direction, old: &Value, new: &Value) {
+        let (old, old_ref) = resolve(self.old_spec, old);
+        let (new, new_ref) = resolve(self.new_spec, new);
+        if let (Some(old_ref), Some(new_ref)) = (old_ref, new_ref) {
+            if !self
+                .visited
+                .insert((old_ref.to_string(), new_ref.to_string(), direction))
+            {
+                return;
+            }
+        }
+
+        // the schemas with a description or a default value are wrapped in
+        // `allOf`, such as `{"allOf": [{"$ref": "..."}, {"description": "..."}]}`
+        if let (Some(old_all), Some(new_all)) = (old["allOf"].as_array(), new["allOf"].as_array()) {
+            for (old, new) in old_all.iter().zip(new_all) {
+                self.compare_schema(location, direction, old, new);
+            }
+            return;
+        }
+
+        self.compare_variants(location, direction, old, new);
+
+        match (old["type"].as_str(), new["type"].as_str()) {
+            (Some(old_ty), Some(new_ty)) if old_ty != new_ty => {
+                let widened = match direction {
+                    Direction::Input => old_ty == "integer" && new_ty == "number",
+                    Direction::Output => old_ty == "number" && new_ty == "integer",
+                };
+                if !widened {
+                    self.add(
+                        location,
+                        BreakingChangeKind::TypeChanged {
+                            old: old_ty.to_string(),
+                            new: new_ty.to_string(),
+                        },
+                    );
+                    return;
+                }
+            }
+            (None, Some(new_ty)) if direction == Direction::Input && !has_variants(old) => {
+                self.add(
+                    location,
+                    BreakingChangeKind::TypeChanged {
+                        old: "any".to_string(),
+                        new: new_ty.to_string(),
+                    },
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        self.compare_enum(location, direction, old, new);
+        if direction == Direction::Input {
+            self.compare_constraints(location, old, new);
+        }
+        self.compare_properties(location, direction, old, new);
+
+        if old["items"].is_object() && new["items"].is_object() {
+            self.compare_schema(
+                &format!("{}[]", location),
+                direction,
+                &old["items"],
+                &new["items"],
+            );
+        }
+        if old["additionalProperties"].is_object() && new["additionalProperties"].is_object() {
+            self.compare_schema(
+                &format!("{}{{}}", location),
+                direction,
+                &old["additionalProperties"],
+                &new["additionalProperties"],
+            );
+        }
+    }
+
+    fn compare_variants(&mut self, location: &str, direction: Direction, old: &Value, new: &Value) {
+        let variants = |schema: &Value| {
+            ["oneOf", "anyOf"]
+                .iter()
+                .flat_map(|key| schema[*key].as_array().into_iter().flatten())
+                .filter_map(|variant| variant["$ref"].as_str())
+                .map(|name| name.trim_start_matches(SCHEMA_REF_PREFIX).to_string())
+                .collect::<Vec<_>>()
+        };
+        let old_variants = variants(old);
+        let new_variants = variants(new);
+
+        match direction {
+            Direction::Input => {
+                for name in old_variants {
+                    if !new_variants.contains(&name) {
+                        self.add(location, BreakingChangeKind::VariantRemoved { name });
+                    }
+                }
+            }
+            Direction::Output => {
+                for name in new_variants {
+                    if !old_variants.contains(&name) {
+                        self.add(location, BreakingChangeKind::VariantAdded { name });
+                    }
+                }
+            }
+        }
+    }
+
+    fn compare_enum(&mut self, location: &str, direction: Direction, old: &Value, new: &Value) {
+        let (old_items, new_items) = match (old["enum"].as_array(), new["enum"].as_array()) {
+            (Some(old_items), Some(new_items)) => (old_items, new_items),
+            (None, Some(new_items)) if direction == Direction::Input => {
+                // the input is restricted to the enumeration values
+                if !new_items.is_empty() {
+                    self.add(
+                        location,
+                        BreakingChangeKind::ConstraintNarrowed { constraint: "enum" },
+                    );
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        match direction {
+            Direction::Input => {
+                for value in old_items.iter().filter(|value| !new_items.contains(value)) {
+                    self.add(
+                        location,
+                        BreakingChangeKind::EnumValueRemoved {
+                            value: enum_value(value),
+                        },
+                    );
+                }
+            }
+            Direction::Output => {
+                for value in new_items.iter().filter(|value| !old_items.contains(value)) {
+                    self.add(
+                        location,
+                        BreakingChangeKind::EnumValueAdded {
+                            value: enum_value(value),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn compare_constraints(&mut self, location: &str, old: &Value, new: &Value) {
+        // `true` if the new limit is narrower than the old limit
+        let upper = |old: Option<f64>, new: Option<f64>| match (old, new) {
+            (Some(old), Some(new)) => new < old,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        let lower = |old: Option<f64>, new: Option<f64>| match (old, new) {
+            (Some(old), Some(new)) => new > old,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+
+        let mut narrowed = Vec::new();
+        for (constraint, is_upper) in [
+            ("maximum", true),
+            ("minimum", false),
+            ("maxLength", true),
+            ("minLength", false),
+            ("maxItems", true),
+            ("minItems", false),
+        ] {
+            let (old_limit, new_limit) = (old[constraint].as_f64(), new[constraint].as_f64());
+            if (is_upper && upper(old_limit, new_limit))
+                || (!is_upper && lower(old_limit, new_limit))
+            {
+                narrowed.push(constraint);
+            }
+        }
+        if is_true(&new["exclusiveMaximum"]) && !is_true(&old["exclusiveMaximum"]) {
+            narrowed.push("exclusiveMaximum");
+        }
+        if is_true(&new["exclusiveMinimum"]) && !is_true(&old["exclusiveMinimum"]) {
+            narrowed.push("exclusiveMinimum");
+        }
+        for constraint in ["pattern", "multipleOf"] {
+            if !new[constraint].is_null() && new[constraint] != old[constraint] {
+                narrowed.push(constraint);
+            }
+        }
+        if is_true(&new["uniqueItems"]) && !is_true(&old["uniqueItems"]) {
+            narrowed.push("uniqueItems");
+        }
+
+        for constraint in narrowed {
+            self.add(
+                location,
+                BreakingChangeKind::ConstraintNarrowed { constraint },
+            );
+        }
+    }
+
+    fn compare_properties(
+        &mut self,
+        location: &str,
+        direction: Direction,
+        old: &Value,
+        new: &Value,
+    ) {
+        let empty = Map::new();
+        let old_properties = old["properties"].as_object().unwrap_or(&empty);
+        let new_properties = new["properties"].as_object().unwrap_or(&empty);
+        let is_required = |schema: &Value, name: &str| required(schema).contains(&name);
+
+        match direction {
+            Direction::Input => {
+                for name in new_properties.keys() {
+                    if is_required(new, name) && !is_required(old, name) {
+                        self.add(
+                            location,
+                            BreakingChangeKind::RequiredFieldAdded { name: name.clone() },
+                        );
+                    }
+                }
+            }
+            Direction::Output => {
+                for name in old_properties.keys() {
+                    if is_required(old, name)
+                        && (!new_properties.contains_key(name) || !is_required(new, name))
+                    {
+                        self.add(
+                            location,
+                            BreakingChangeKind::RequiredFieldRemoved { name: name.clone() },
+                        );
+                    }
+                }
+            }
+        }
+
+        for (name, old_property) in old_properties {
+            if let Some(new_property) = new_properties.get(name) {
+                self.compare_schema(
+                    &format!("{}.{}", location, name),
+                    direction,
+                    old_property,
+                    new_property,
+                );
+            }
+        }
+    }
+}
+
+/// Returns the schema referenced by `$ref` and the name of the reference.
+fn resolve<'a>(spec: &'a Value, schema: &'a Value) -> (&'a Value, Option<&'a str>) {
+    match schema["$ref"]
+        .as_str()
+        .and_then(|name| name.strip_prefix(SCHEMA_REF_PREFIX))
+    {
+        Some(name) => (&spec["components"]["schemas"][name], Some(name)),
+        None => (schema, None),
+    }
+}
+
+fn find_param<'v>(params: &'v Value, name: &Value, param_in: &Value) -> Option<&'v Value> {
+    params
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|param| &param["name"] == name && &param["in"] == param_in)
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn has_variants(schema: &Value) -> bool {
+    schema["oneOf"].is_array() || schema["anyOf"].is_array()
+}
+
+fn is_true(value: &Value) -> bool {
+    value.as_bool().unwrap_or_default()
+}
+
+fn enum_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
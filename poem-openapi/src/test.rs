@@ -0,0 +1,407 @@
+//! Test utilities to check that the responses conform to the specification.
+
+use poem::{
+    endpoint::BoxEndpoint,
+    http::{header, Method},
+    test::{TestClient, TestResponse},
+    Endpoint, IntoEndpoint, Request, Response,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    payload::{content_type_matches, ParsePayload},
+    registry::Registry,
+    ApiResponse, OpenApi, OpenApiService,
+};
+
+/// An endpoint that checks that the responses of the [`OpenApiService`]
+/// conform to its specification, created by
+/// [`OpenApiService::test_client`].
+///
+/// # Panics
+///
+/// Panics if the response of an operation is not documented in the
+/// specification:
+///
+/// - The status code is not documented, and there is no default response.
+/// - A required header is missing.
+/// - The content type is not documented.
+/// - The JSON body does not match the schema.
+///
+/// The requests that do not match an operation are not checked.
+pub struct SpecCheckEndpoint {
+    inner: BoxEndpoint<'static, Response>,
+    spec: Value,
+}
+
+impl<T: OpenApi> OpenApiService<T> {
+    /// Create a [`TestClient`] that checks that the responses conform to the
+    /// specification, see [`SpecCheckEndpoint`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    /// }
+    ///
+    /// let cli = OpenApiService::new(Api).test_client();
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/hello").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_text("hello").await;
+    /// # });
+    /// ```
+    pub fn test_client(self) -> TestClient<SpecCheckEndpoint> {
+        let spec = serde_json::from_str(&self.spec()).expect("valid specification");
+        TestClient::new(SpecCheckEndpoint {
+            inner: self.into_endpoint(),
+            spec,
+        })
+    }
+}
+
+#[poem::async_trait]
+impl Endpoint for SpecCheckEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let resp = self.inner.call(req).await;
+
+        match find_operation(&self.spec, &method, &path) {
+            Some((template, operation)) => {
+                let name = format!("{} {}", method, template);
+                check_response(&self.spec, &name, operation, resp).await
+            }
+            None => resp,
+        }
+    }
+}
+
+/// Asserts that the response conforms to the response type `T`, and returns
+/// it to check or parse the payload with [`parse_payload`].
+///
+/// # Panics
+///
+/// Panics if the response does not conform to `T`, see
+/// [`SpecCheckEndpoint`].
+///
+/// # Example
+///
+/// ```
+/// use poem::test::TestClient;
+/// use poem_openapi::{
+///     payload::{Json, PlainText},
+///     test::{assert_response_conforms, parse_payload},
+///     ApiResponse, OpenApi, OpenApiService,
+/// };
+///
+/// #[derive(ApiResponse)]
+/// enum CountResponse {
+///     #[oai(status = 200)]
+///     Ok(Json<i32>),
+///     #[oai(status = 404)]
+///     NotFound(PlainText<String>),
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/count", method = "get")]
+///     async fn count(&self) -> CountResponse {
+///         CountResponse::Ok(Json(42))
+///     }
+/// }
+///
+/// let cli = TestClient::new(OpenApiService::new(Api));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = assert_response_conforms::<CountResponse>(cli.get("/count").send().await).await;
+/// resp.assert_status_is_ok();
+/// assert_eq!(parse_payload::<Json<i32>>(resp).await.0, 42);
+/// # });
+/// ```
+pub async fn assert_response_conforms<T: ApiResponse>(resp: TestResponse) -> TestResponse {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    let spec = json!({ "components": { "schemas": registry.schemas } });
+    let operation = json!({ "responses": T::meta() });
+    TestResponse(check_response(&spec, std::any::type_name::<T>(), &operation, resp.0).await)
+}
+
+/// Asserts that the response conforms to the response type `T`, and parses
+/// it as `T` in the same way as the generated clients, see
+/// [`ClientResponse`](crate::client::ClientResponse).
+///
+/// # Panics
+///
+/// Panics if the response does not conform to `T`, see
+/// [`SpecCheckEndpoint`], or it cannot be parsed as `T`.
+///
+/// # Example
+///
+/// ```
+/// use poem::test::TestClient;
+/// use poem_openapi::{
+///     payload::{Json, PlainText},
+///     test::assert_api_response,
+///     ApiResponse, OpenApi, OpenApiService,
+/// };
+///
+/// #[derive(ApiResponse)]
+/// #[oai(client)]
+/// enum CountResponse {
+///     #[oai(status = 200)]
+///     Ok(Json<i32>),
+///     #[oai(status = 404)]
+///     NotFound(PlainText<String>),
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/count", method = "get")]
+///     async fn count(&self) -> CountResponse {
+///         CountResponse::Ok(Json(42))
+///     }
+/// }
+///
+/// let cli = TestClient::new(OpenApiService::new(Api));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = assert_api_response::<CountResponse>(cli.get("/count").send().await).await;
+/// assert!(matches!(resp, CountResponse::Ok(Json(42))));
+/// # });
+/// ```
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub async fn assert_api_response<T>(resp: TestResponse) -> T
+where
+    T: ApiResponse + crate::client::ClientResponse,
+{
+    let name = std::any::type_name::<T>();
+    let (parts, body) = assert_response_conforms::<T>(resp).await.0.into_parts();
+    let mut http_resp = poem::http::Response::new(body.into_vec().await.expect("valid body"));
+    *http_resp.status_mut() = parts.status;
+    *http_resp.version_mut() = parts.version;
+    *http_resp.headers_mut() = parts.headers;
+
+    match T::from_client_response(http_resp.into()).await {
+        Ok(resp) => resp,
+        Err(err) => panic!("{}: the response cannot be parsed: {}", name, err),
+    }
+}
+
+/// Parses the body of the response as the payload `T`, in the same way as
+/// the payload of a request.
+///
+/// # Panics
+///
+/// Panics if the body cannot be parsed as `T`.
+pub async fn parse_payload<T: ParsePayload>(resp: TestResponse) -> T {
+    let (parts, body) = resp.0.into_parts();
+    let mut req = Request::builder();
+    if let Some(content_type) = parts.headers.get(header::CONTENT_TYPE) {
+        req = req.header(header::CONTENT_TYPE, content_type.clone());
+    }
+    let (req, mut body) = req.body(body).split();
+    match T::from_request(&req, &mut body).await {
+        Ok(payload) => payload,
+        Err(err) => panic!("the body cannot be parsed as the payload: {:?}", err),
+    }
+}
+
+/// Returns the path template and the operation that matches the request.
+fn find_operation<'a>(
+    spec: &'a Value,
+    method: &Method,
+    path: &str,
+) -> Option<(&'a str, &'a Value)> {
+    let method = method.as_str().to_lowercase();
+    let segments = path.trim_end_matches('/').split('/').collect::<Vec<_>>();
+    spec["paths"]
+        .as_object()?
+        .iter()
+        .find_map(|(template, item)| {
+            let operation = item.get(&method)?;
+            let template_segments = template
+                .trim_end_matches('/')
+                .split('/')
+                .collect::<Vec<_>>();
+            // the wildcard parameter captures the rest of the path
+            let wildcard = operation["parameters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|param| param["x-wildcard"] == true);
+            let len_matched = if wildcard {
+                template_segments.len() <= segments.len()
+            } else {
+                template_segments.len() == segments.len()
+            };
+            let matched = len_matched
+                && template_segments
+                    .iter()
+                    .zip(&segments)
+                    .all(|(template_segment, segment)| {
+                        (template_segment.starts_with('{') && !segment.is_empty())
+                            || template_segment == segment
+                    });
+            matched.then(|| (template.as_str(), operation))
+        })
+}
+
+async fn check_response(spec: &Value, name: &str, operation: &Value, resp: Response) -> Response {
+    let status = resp.status();
+    let response = operation["responses"]
+        .get(status.as_str())
+        .or_else(|| operation["responses"].get("default"))
+        .unwrap_or_else(|| panic!("{}: the status `{}` is not documented", name, status));
+
+    if let Some(headers) = response["headers"].as_object() {
+        for (header_name, header) in headers {
+            if header["required"].as_bool().unwrap_or_default() {
+                assert!(
+                    resp.headers().contains_key(header_name.as_str()),
+                    "{}: the required header `{}` is missing",
+                    name,
+                    header_name
+                );
+            }
+        }
+    }
+
+    let content = match response["content"].as_object() {
+        Some(content) if !content.is_empty() => content,
+        _ => return resp,
+    };
+    let content_type = resp
+        .content_type()
+        .unwrap_or_else(|| panic!("{}: the content type is missing", name))
+        .to_string();
+    let schema = content
+        .iter()
+        .find(|(expected, _)| content_type_matches(&content_type, expected, None))
+        .map(|(_, media)| &media["schema"])
+        .unwrap_or_else(|| {
+            panic!(
+                "{}: the content type `{}` is not documented",
+                name, content_type
+            )
+        });
+
+    if !content_type_matches(&content_type, "application/json", Some("json")) {
+        return resp;
+    }
+
+    let (parts, body) = resp.into_parts();
+    let data = body.into_vec().await.expect("valid body");
+    let value: Value = serde_json::from_slice(&data)
+        .unwrap_or_else(|err| panic!("{}: the body is not a valid JSON: {}", name, err));
+    if let Err(err) = check_value(spec, schema, &value, "$") {
+        panic!("{}: the body does not match the schema: {}", name, err);
+    }
+    Response::from_parts(parts, data.into())
+}
+
+/// Checks that the JSON value matches the schema, returns the path and the
+/// reason of the first mismatch.
+fn check_value(spec: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        let schema = &spec["components"]["schemas"][name];
+        if schema.is_null() {
+            return Err(format!("{}: unknown schema `{}`", path, reference));
+        }
+        return check_value(spec, schema, value, path);
+    }
+
+    if let Some(all_of) = schema["allOf"].as_array() {
+        for schema in all_of {
+            check_value(spec, schema, value, path)?;
+        }
+    }
+    if let Some(one_of) = schema["oneOf"].as_array() {
+        if !one_of
+            .iter()
+            .any(|schema| check_value(spec, schema, value, path).is_ok())
+        {
+            return Err(format!(
+                "{}: does not match any of the `oneOf` schemas",
+                path
+            ));
+        }
+    }
+    if let Some(items) = schema["enum"].as_array() {
+        if !items.contains(value) {
+            return Err(format!(
+                "{}: `{}` is not one of the enum items",
+                path, value
+            ));
+        }
+    }
+
+    let ty = schema["type"].as_str().unwrap_or_default();
+    let matched = match (ty, value) {
+        ("", _) => true,
+        ("object", Value::Object(_)) => true,
+        ("array", Value::Array(_)) => true,
+        ("string", Value::String(_)) => true,
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64(),
+        ("number", Value::Number(_)) => true,
+        ("boolean", Value::Bool(_)) => true,
+        _ => false,
+    };
+    if !matched {
+        return Err(format!("{}: expected `{}`, found `{}`", path, ty, value));
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(required) = schema["required"].as_array() {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        return Err(format!(
+                            "{}: missing the required property `{}`",
+                            path, name
+                        ));
+                    }
+                }
+            }
+            if let Some(properties) = schema["properties"].as_object() {
+                for (name, property_schema) in properties {
+                    match object.get(name) {
+                        Some(Value::Null) | None => {}
+                        Some(property) => check_value(
+                            spec,
+                            property_schema,
+                            property,
+                            &format!("{}.{}", path, name),
+                        )?,
+                    }
+                }
+            }
+        }
+        Value::Array(items) if !schema["items"].is_null() => {
+            for (idx, item) in items.iter().enumerate() {
+                check_value(spec, &schema["items"], item, &format!("{}[{}]", path, idx))?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -29,6 +29,12 @@ impl<T: DeserializeOwned + Send + Sync> ParseFromJSON for Any<T> {
             serde_json::from_value(value).map_err(ParseError::custom)?,
         ))
     }
+
+    fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self> {
+        Ok(Self(
+            serde_json::from_slice(bytes).map_err(ParseError::custom)?,
+        ))
+    }
 }
 
 impl<T: Serialize + Send + Sync> ToJSON for Any<T> {
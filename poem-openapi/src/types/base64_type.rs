@@ -1,17 +1,37 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
     types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 /// Represents a binary data encoded with base64.
+///
+/// The binary data can be stored in `Vec<u8>` (the default) or any type that
+/// can be converted from `Vec<u8>`, such as `bytes::Bytes`, it is suitable for
+/// the small blobs in the objects.
+///
+/// NOTE: Its type is `string` and the format is `byte`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::types::{Base64, ParseFromJSON, ToJSON};
+///
+/// let value = Base64::<Vec<u8>>::parse_from_json("AQID".into()).unwrap();
+/// assert_eq!(value, Base64(vec![1, 2, 3]));
+/// assert_eq!(value.to_json(), "AQID");
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct Base64(pub Vec<u8>);
+pub struct Base64<T = Vec<u8>>(pub T);
 
-impl Type for Base64 {
+impl<T: AsRef<[u8]> + Send + Sync> Type for Base64<T> {
     fn name() -> Cow<'static, str> {
         "string(bytes)".into()
     }
@@ -19,31 +39,44 @@ impl Type for Base64 {
     impl_value_type!();
 
     fn schema_ref() -> MetaSchemaRef {
-        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("bytes", "string")))
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "byte")))
     }
 }
 
-impl ParseFromJSON for Base64 {
+impl<T: AsRef<[u8]> + From<Vec<u8>> + Send + Sync> ParseFromJSON for Base64<T> {
     fn parse_from_json(value: Value) -> ParseResult<Self> {
         if let Value::String(value) = value {
-            Ok(Self(base64::decode(value)?))
+            Ok(Self(base64::decode(value)?.into()))
         } else {
             Err(ParseError::expected_type(value))
         }
     }
 }
 
-impl ParseFromParameter for Base64 {
+impl<T: AsRef<[u8]> + From<Vec<u8>> + Send + Sync> ParseFromParameter for Base64<T> {
     fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
         match value {
-            Some(value) => Ok(Self(base64::decode(value)?)),
+            Some(value) => Ok(Self(base64::decode(value)?.into())),
             None => Err(ParseError::expected_input()),
         }
     }
 }
 
-impl ToJSON for Base64 {
+#[cfg(not(target_arch = "wasm32"))]
+#[poem::async_trait]
+impl<T: AsRef<[u8]> + From<Vec<u8>> + Send + Sync> ParseFromMultipartField for Base64<T> {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(Self(
+                base64::decode(field.text().await.map_err(ParseError::io)?.trim())?.into(),
+            )),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + Send + Sync> ToJSON for Base64<T> {
     fn to_json(&self) -> Value {
-        Value::String(base64::encode(&self.0))
+        Value::String(base64::encode(self.0.as_ref()))
     }
 }
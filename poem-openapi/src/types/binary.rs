@@ -1,17 +1,27 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::{ParseError, ParseFromMultipartField, ParseResult};
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{ParseError, ParseFromMultipartField, ParseResult, Type},
+    types::Type,
 };
 
 /// Represents a binary data.
+///
+/// The binary data can be stored in `Vec<u8>` (the default) or any type that
+/// can be converted from `Vec<u8>`, such as `bytes::Bytes`.
+///
+/// NOTE: `Vec<u8>` itself is an array of integers, use `Binary<Vec<u8>>` for
+/// the multipart fields, or [`Base64`](crate::types::Base64) for the object
+/// fields.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct Binary(pub Vec<u8>);
+pub struct Binary<T = Vec<u8>>(pub T);
 
-impl Type for Binary {
+impl<T: Send + Sync> Type for Binary<T> {
     fn name() -> Cow<'static, str> {
         "string(binary)".into()
     }
@@ -23,11 +33,12 @@ impl Type for Binary {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
-impl ParseFromMultipartField for Binary {
+impl<T: From<Vec<u8>> + Send + Sync> ParseFromMultipartField for Binary<T> {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
         match field {
-            Some(field) => Ok(Self(field.bytes().await.map_err(ParseError::custom)?)),
+            Some(field) => Ok(Self(field.bytes().await.map_err(ParseError::io)?.into())),
             None => Err(ParseError::expected_input()),
         }
     }
@@ -0,0 +1,188 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+const UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("pb", 1_000_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+    ("pib", 1 << 50),
+];
+
+const BINARY_UNITS: &[(&str, u64)] = &[
+    ("PiB", 1 << 50),
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+];
+
+/// A number of bytes that is parsed from a human-friendly string.
+///
+/// The string is a number followed by an optional unit, such as `1024`,
+/// `10MiB` or `1.5 GB`. The units are case-insensitive, `B`, `KB`, `MB`, `GB`,
+/// `TB` and `PB` are the powers of 1000, `KiB`, `MiB`, `GiB`, `TiB` and `PiB`
+/// are the powers of 1024. An integer is also accepted in JSON.
+///
+/// It is serialized with the largest binary unit that divides the number of
+/// bytes, such as `10MiB` or `1000B`.
+///
+/// NOTE: Its type is `string` and the format is `byte-size`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::types::ByteSize;
+///
+/// assert_eq!("10MiB".parse::<ByteSize>().unwrap(), ByteSize(10 * 1024 * 1024));
+/// assert_eq!("1.5 kb".parse::<ByteSize>().unwrap(), ByteSize(1500));
+/// assert_eq!("1.5KiB".parse::<ByteSize>().unwrap(), ByteSize(1536));
+/// assert_eq!(ByteSize(2048).to_string(), "2KiB");
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Returns the number of bytes.
+    #[inline]
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let idx = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(idx);
+        let unit = unit.trim_start();
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if integer.is_empty()
+            || (number.contains('.') && fraction.is_empty())
+            || !fraction.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(format!("invalid byte size `{}`", s));
+        }
+        let multiplier = match unit {
+            "" => 1,
+            _ => UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                .map(|(_, multiplier)| *multiplier)
+                .ok_or_else(|| format!("unknown unit `{}` of byte size", unit))?,
+        };
+
+        let overflow = || format!("byte size `{}` is too large", s);
+        let bytes = integer
+            .parse::<u64>()
+            .map_err(|_| overflow())?
+            .checked_mul(multiplier)
+            .ok_or_else(overflow)?;
+
+        // the digits beyond the precision of the bytes are truncated
+        let fraction = &fraction[..fraction.len().min(18)];
+        let fraction_bytes = match fraction.parse::<u128>() {
+            Ok(value) => value * u128::from(multiplier) / 10u128.pow(fraction.len() as u32),
+            Err(_) => 0,
+        };
+        let bytes = bytes
+            .checked_add(fraction_bytes as u64)
+            .ok_or_else(overflow)?;
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0 > 0 {
+            for (name, size) in BINARY_UNITS {
+                if self.0 % size == 0 {
+                    return write!(f, "{}{}", self.0 / size, name);
+                }
+            }
+        }
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl Type for ByteSize {
+    fn name() -> Cow<'static, str> {
+        "string(byte-size)".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            description: Some(
+                "A number of bytes with an optional unit, such as `10MiB` or `1.5 GB`. The units \
+                 are case-insensitive, `B`, `KB`, `MB`, `GB`, `TB` and `PB` are the powers of \
+                 1000, `KiB`, `MiB`, `GiB`, `TiB` and `PiB` are the powers of 1024.",
+            ),
+            pattern: Some(r"^\s*[0-9]+(\.[0-9]+)?\s*(([KkMmGgTtPp][Ii]?)?[Bb])?\s*$".to_string()),
+            ..MetaSchema::new_with_format("string", "byte-size")
+        }))
+    }
+}
+
+impl ParseFromJSON for ByteSize {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value {
+            Value::String(value) => Ok(value.parse()?),
+            Value::Number(n) if n.is_u64() => Ok(Self(n.as_u64().unwrap())),
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl ParseFromParameter for ByteSize {
+    fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(value.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[poem::async_trait]
+impl ParseFromMultipartField for ByteSize {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(field.text().await?.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for ByteSize {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    registry::{CompiledSchema, MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+};
+
+/// A property of the [`DynamicObjectDefinition`].
+#[derive(Debug, Clone)]
+struct DynamicProperty {
+    name: String,
+    schema: MetaSchema,
+    compiled: CompiledSchema,
+    required: bool,
+}
+
+/// The definition of a [`DynamicObject`], which can be loaded at runtime.
+///
+/// The schema of each property is a [`MetaSchema`], the `type`, `enum`,
+/// `default` and the validators in it are checked when parsing the object.
+#[derive(Debug, Clone)]
+pub struct DynamicObjectDefinition {
+    name: String,
+    description: Option<String>,
+    properties: Vec<DynamicProperty>,
+}
+
+impl DynamicObjectDefinition {
+    /// Create a definition of the object with the specified schema name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            properties: Vec::new(),
+        }
+    }
+
+    /// Sets the description of the object.
+    #[must_use]
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Appends a property.
+    #[must_use]
+    pub fn property(mut self, name: impl Into<String>, schema: MetaSchema, required: bool) -> Self {
+        self.properties.push(DynamicProperty {
+            name: name.into(),
+            compiled: CompiledSchema::new(&schema),
+            schema,
+            required,
+        });
+        self
+    }
+
+    /// Returns the schema name of the object.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn schema(&'static self) -> MetaSchema {
+        MetaSchema {
+            description: self.description.as_deref(),
+            required: self
+                .properties
+                .iter()
+                .filter(|property| property.required)
+                .map(|property| property.name.as_str())
+                .collect(),
+            properties: self
+                .properties
+                .iter()
+                .map(|property| {
+                    (
+                        property.name.as_str(),
+                        MetaSchemaRef::Inline(Box::new(property.schema.clone())),
+                    )
+                })
+                .collect(),
+            ..MetaSchema::new("object")
+        }
+    }
+}
+
+/// Provides the definition of a [`DynamicObject`].
+///
+/// The definition is usually loaded from the configuration or the database on
+/// startup, and kept in a static variable.
+pub trait DynamicObjectSchema: Send + Sync + 'static {
+    /// Returns the definition of the object.
+    fn definition() -> &'static DynamicObjectDefinition;
+}
+
+/// An object whose properties are defined at runtime.
+///
+/// It is registered as a named schema like the types that derive
+/// [`Object`](crate::Object), and the payloads are validated according to the
+/// [`DynamicObjectDefinition`]. The properties that are not defined are
+/// ignored. Use [`DynamicObject::parse_with`] to parse the objects according
+/// to a definition that is only known at runtime.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     once_cell::sync::OnceCell,
+///     registry::MetaSchema,
+///     types::{DynamicObject, DynamicObjectDefinition, DynamicObjectSchema},
+/// };
+///
+/// static FORM: OnceCell<DynamicObjectDefinition> = OnceCell::new();
+///
+/// struct Form;
+///
+/// impl DynamicObjectSchema for Form {
+///     fn definition() -> &'static DynamicObjectDefinition {
+///         FORM.get().expect("the form is not loaded")
+///     }
+/// }
+///
+/// // loaded from the configuration
+/// FORM.set(
+///     DynamicObjectDefinition::new("Form")
+///         .property("name", MetaSchema::new("string"), true)
+///         .property("age", MetaSchema::new("integer"), false),
+/// )
+/// .unwrap();
+///
+/// type FormObject = DynamicObject<Form>;
+/// ```
+pub struct DynamicObject<T> {
+    value: Map<String, Value>,
+    _mark: PhantomData<T>,
+}
+
+impl<T> Debug for DynamicObject<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynamicObject").field(&self.value).finish()
+    }
+}
+
+impl<T> Clone for DynamicObject<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T> DynamicObject<T> {
+    /// Create a `DynamicObject` without validation.
+    pub fn new(value: Map<String, Value>) -> Self {
+        Self {
+            value,
+            _mark: PhantomData,
+        }
+    }
+
+    /// Returns the value of the property.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.value.get(name)
+    }
+
+    /// Consumes this object and returns the properties.
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.value
+    }
+}
+
+impl<T: DynamicObjectSchema> Type for DynamicObject<T> {
+    fn name() -> Cow<'static, str> {
+        T::definition().name.as_str().into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Reference(T::definition().name.as_str())
+    }
+
+    fn register(registry: &mut Registry) {
+        let definition = T::definition();
+        registry.create_schema(definition.name.as_str(), |_| definition.schema());
+    }
+}
+
+impl<T: DynamicObjectSchema> DynamicObject<T> {
+    /// Parses the object according to the `definition` instead of
+    /// [`DynamicObjectSchema::definition`], such as the definition of a
+    /// tenant loaded for the request.
+    ///
+    /// The object is still documented by the schema of `T`, so the
+    /// `definition` should be compatible with it.
+    pub fn parse_with(definition: &DynamicObjectDefinition, value: Value) -> ParseResult<Self> {
+        let mut input = match value {
+            Value::Object(input) => input,
+            value => return Err(ParseError::expected_type(value)),
+        };
+
+        let mut value = Map::new();
+        for property in &definition.properties {
+            let name = &property.name;
+            match input.remove(name).filter(|value| !value.is_null()) {
+                Some(field_value) => {
+                    if let Err(reason) = property.compiled.check(&field_value) {
+                        return Err(ParseError::custom(format!("field `{}` {}", name, reason)));
+                    }
+                    value.insert(name.clone(), field_value);
+                }
+                None => match property.compiled.default() {
+                    Some(default) => {
+                        value.insert(name.clone(), default.clone());
+                    }
+                    None if property.required => {
+                        return Err(ParseError::custom(format!("field `{}` is required.", name)));
+                    }
+                    None => {}
+                },
+            }
+        }
+
+        Ok(Self::new(value))
+    }
+}
+
+impl<T: DynamicObjectSchema> ParseFromJSON for DynamicObject<T> {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        Self::parse_with(T::definition(), value)
+    }
+}
+
+impl<T: DynamicObjectSchema> ToJSON for DynamicObject<T> {
+    fn to_json(&self) -> Value {
+        Value::Object(self.value.clone())
+    }
+}
@@ -11,6 +11,8 @@ use super::Type;
 #[derive(Debug)]
 pub struct ParseError<T> {
     message: String,
+    payload_too_large: bool,
+    client_disconnected: bool,
     phantom: PhantomData<T>,
 }
 
@@ -24,6 +26,8 @@ impl<T: Type> ParseError<T> {
     fn new(message: String) -> Self {
         Self {
             message,
+            payload_too_large: false,
+            client_disconnected: false,
             phantom: PhantomData,
         }
     }
@@ -56,6 +60,47 @@ impl<T: Type> ParseError<T> {
         Self::custom("not support parsing from multipart")
     }
 
+    /// The input exceeds the size limit.
+    #[must_use]
+    pub fn payload_too_large() -> Self {
+        Self {
+            payload_too_large: true,
+            ..Self::custom("payload too large")
+        }
+    }
+
+    /// The client closed the connection or the connection was reset before the
+    /// input was completely received.
+    #[must_use]
+    pub fn client_disconnected() -> Self {
+        Self {
+            client_disconnected: true,
+            ..Self::custom("client disconnected")
+        }
+    }
+
+    /// Converts the IO error of reading the input, the errors caused by the
+    /// client disconnecting are converted to
+    /// [`ParseError::client_disconnected`], and the errors caused by a
+    /// multipart field exceeding the size limit are converted to
+    /// [`ParseError::payload_too_large`].
+    #[must_use]
+    pub fn io(err: std::io::Error) -> Self {
+        if poem::error::is_disconnected_io_error(&err) {
+            Self {
+                client_disconnected: true,
+                ..Self::custom(err)
+            }
+        } else if crate::error::is_field_size_exceeded_io_error(&err) {
+            Self {
+                payload_too_large: true,
+                ..Self::custom(err)
+            }
+        } else {
+            Self::custom(err)
+        }
+    }
+
     /// A custom error message.
     ///
     /// Any type that implements `Display` is automatically converted to this if
@@ -67,7 +112,7 @@ impl<T: Type> ParseError<T> {
 
     /// Propagate the error message to a different type.
     pub fn propagate<U: Type>(self) -> ParseError<U> {
-        if T::name() != U::name() {
+        let mut err = if T::name() != U::name() {
             ParseError::new(format!(
                 r#"{} (occurred while parsing "{}")"#,
                 self.message,
@@ -75,7 +120,23 @@ impl<T: Type> ParseError<T> {
             ))
         } else {
             ParseError::new(self.message)
-        }
+        };
+        err.payload_too_large = self.payload_too_large;
+        err.client_disconnected = self.client_disconnected;
+        err
+    }
+
+    /// Returns `true` if the input exceeds the size limit.
+    #[inline]
+    pub fn is_payload_too_large(&self) -> bool {
+        self.payload_too_large
+    }
+
+    /// Returns `true` if the client closed the connection or the connection
+    /// was reset before the input was completely received.
+    #[inline]
+    pub fn is_client_disconnected(&self) -> bool {
+        self.client_disconnected
     }
 
     /// Consume this error and convert it into a message.
@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+
+use num_bigint::{BigInt, BigUint};
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+/// Returns `true` if the string only contains the ASCII digits, `num-bigint`
+/// also accepts a leading `+` and the underscores between the digits.
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit())
+}
+
+macro_rules! impl_bigint_type {
+    ($ty:ty, $name:literal, $pattern:literal, $check:expr) => {
+        impl Type for $ty {
+            fn name() -> Cow<'static, str> {
+                $name.into()
+            }
+
+            impl_value_type!();
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    pattern: Some($pattern.to_string()),
+                    ..MetaSchema::new_with_format("string", "bigint")
+                }))
+            }
+        }
+
+        impl ParseFromJSON for $ty {
+            fn parse_from_json(value: Value) -> ParseResult<Self> {
+                match value {
+                    Value::String(value) => Self::parse_from_parameter(Some(&value)),
+                    Value::Number(n) if n.is_i64() || n.is_u64() => {
+                        Self::parse_from_parameter(Some(&n.to_string()))
+                    }
+                    _ => Err(ParseError::expected_type(value)),
+                }
+            }
+        }
+
+        impl ParseFromParameter for $ty {
+            fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+                match value {
+                    Some(value) => {
+                        let check: fn(&str) -> bool = $check;
+                        if !check(value) {
+                            return Err(ParseError::custom(format!("invalid integer `{}`", value)));
+                        }
+                        Ok(value.parse()?)
+                    }
+                    None => Err(ParseError::expected_input()),
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #[poem::async_trait]
+        impl ParseFromMultipartField for $ty {
+            async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+                match field {
+                    Some(field) => Self::parse_from_parameter(Some(&field.text().await?)),
+                    None => Err(ParseError::expected_input()),
+                }
+            }
+        }
+
+        impl ToJSON for $ty {
+            fn to_json(&self) -> Value {
+                Value::String(self.to_string())
+            }
+        }
+    };
+}
+
+impl_bigint_type!(BigInt, "string(bigint)", "^-?[0-9]+$", |value| {
+    is_digits(value.strip_prefix('-').unwrap_or(value))
+});
+impl_bigint_type!(BigUint, "string(biguint)", "^[0-9]+$", is_digits);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const BEYOND_I64: &str = "-123456789012345678901234567890";
+    const BEYOND_U64: &str = "123456789012345678901234567890";
+
+    #[test]
+    fn schema() {
+        let schema = BigInt::schema_ref();
+        let schema = schema.unwrap_inline();
+        assert_eq!(schema.ty, "string");
+        assert_eq!(schema.format, Some("bigint"));
+        let pattern = regex::Regex::new(schema.pattern.as_deref().unwrap()).unwrap();
+        assert!(pattern.is_match(BEYOND_I64));
+        assert!(!pattern.is_match("+1"));
+
+        let schema = BigUint::schema_ref();
+        let schema = schema.unwrap_inline();
+        assert_eq!(schema.ty, "string");
+        assert_eq!(schema.format, Some("bigint"));
+        let pattern = regex::Regex::new(schema.pattern.as_deref().unwrap()).unwrap();
+        assert!(pattern.is_match(BEYOND_U64));
+        assert!(!pattern.is_match("-1"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for value in [BEYOND_I64, BEYOND_U64, "0", "-1"] {
+            let n = BigInt::parse_from_json(json!(value)).unwrap();
+            assert_eq!(n.to_string(), value);
+            assert_eq!(n.to_json(), json!(value));
+        }
+
+        let n = BigUint::parse_from_json(json!(BEYOND_U64)).unwrap();
+        assert_eq!(n.to_json(), json!(BEYOND_U64));
+
+        assert_eq!(
+            BigInt::parse_from_json(json!(i64::MIN)).unwrap().to_json(),
+            json!(i64::MIN.to_string())
+        );
+        assert_eq!(
+            BigUint::parse_from_json(json!(u64::MAX)).unwrap().to_json(),
+            json!(u64::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for value in ["", "+1", "1_000", " 1", "1.0", "1e3", "abc", "--1"] {
+            assert!(BigInt::parse_from_json(json!(value)).is_err(), "{}", value);
+            assert!(BigUint::parse_from_json(json!(value)).is_err(), "{}", value);
+        }
+        assert!(BigUint::parse_from_json(json!("-1")).is_err());
+
+        // the JSON numbers beyond `u64` are parsed as `f64` and lose precision
+        let value: Value = serde_json::from_str(BEYOND_U64).unwrap();
+        assert!(BigInt::parse_from_json(value).is_err());
+        assert!(BigInt::parse_from_json(json!(1.5)).is_err());
+        assert!(BigInt::parse_from_json(json!(null)).is_err());
+    }
+}
@@ -2,13 +2,14 @@ use std::borrow::Cow;
 
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
-    poem::web::Field,
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl Type for bool {
@@ -42,6 +43,7 @@ impl ParseFromParameter for bool {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 impl ParseFromMultipartField for bool {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
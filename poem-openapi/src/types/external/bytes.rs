@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+
+use bytes::Bytes;
+use poem::web::Field;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromMultipartField, ParseResult, Type},
+};
+
+impl Type for Bytes {
+    fn name() -> Cow<'static, str> {
+        "string(binary)".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "binary")))
+    }
+}
+
+#[poem::async_trait]
+impl ParseFromMultipartField for Bytes {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(field.bytes().await.map_err(ParseError::io)?.into()),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
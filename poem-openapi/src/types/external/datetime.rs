@@ -3,13 +3,14 @@ use std::borrow::Cow;
 use chrono::{DateTime, FixedOffset};
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
-    poem::web::Field,
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl Type for DateTime<FixedOffset> {
@@ -43,6 +44,7 @@ impl ParseFromParameter for DateTime<FixedOffset> {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 impl ParseFromMultipartField for DateTime<FixedOffset> {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
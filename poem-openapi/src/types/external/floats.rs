@@ -1,14 +1,14 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field;
 use serde_json::{Number, Value};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 macro_rules! impl_type_for_floats {
@@ -48,6 +48,8 @@ macro_rules! impl_type_for_floats {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
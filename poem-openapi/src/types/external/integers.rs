@@ -1,14 +1,14 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 macro_rules! impl_type_for_integers {
@@ -57,6 +57,8 @@ macro_rules! impl_type_for_integers {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -123,6 +125,8 @@ macro_rules! impl_type_for_unsigneds {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -143,6 +147,79 @@ macro_rules! impl_type_for_unsigneds {
     };
 }
 
+macro_rules! impl_type_for_128bit_integers {
+    ($(($ty:ty, $format:literal, $pattern:literal, $min:literal, $max:literal)),*) => {
+        $(
+        impl Type for $ty {
+            fn name() -> Cow<'static, str> {
+                format!("string({})", $format).into()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    description: Some(concat!(
+                        "An integer from ",
+                        $min,
+                        " to ",
+                        $max,
+                        ", which is encoded as a string."
+                    )),
+                    pattern: Some($pattern.to_string()),
+                    ..MetaSchema::new_with_format("string", $format)
+                }))
+            }
+
+            impl_value_type!();
+        }
+
+        impl ParseFromJSON for $ty {
+            fn parse_from_json(value: Value) -> ParseResult<Self> {
+                match value {
+                    Value::String(value) => Self::parse_from_parameter(Some(&value)),
+                    Value::Number(n) if n.is_i64() || n.is_u64() => {
+                        Self::parse_from_parameter(Some(&n.to_string()))
+                    }
+                    _ => Err(ParseError::expected_type(value)),
+                }
+            }
+        }
+
+        impl ParseFromParameter for $ty {
+            fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+                match value {
+                    Some(value) => value.parse().map_err(|_| {
+                        ParseError::custom(format!(
+                            "Only integers from {} to {} are accepted.",
+                            Self::MIN,
+                            Self::MAX
+                        ))
+                    }),
+                    None => Err(ParseError::expected_input()),
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #[poem::async_trait]
+        impl ParseFromMultipartField for $ty {
+            async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+                match field {
+                    Some(field) => Self::parse_from_parameter(Some(&field.text().await?)),
+                    None => Err(ParseError::expected_input()),
+                }
+            }
+        }
+
+        impl ToJSON for $ty {
+            fn to_json(&self) -> Value {
+                Value::String(self.to_string())
+            }
+        }
+
+        )*
+    };
+}
+
 impl_type_for_integers!((i8, "int8"), (i16, "int16"), (i32, "int32"), (i64, "int64"));
 
 impl_type_for_unsigneds!(
@@ -151,3 +228,20 @@ impl_type_for_unsigneds!(
     (u32, "uint32"),
     (u64, "uint64")
 );
+
+impl_type_for_128bit_integers!(
+    (
+        i128,
+        "int128",
+        "^-?[0-9]+$",
+        "-170141183460469231731687303715884105728",
+        "170141183460469231731687303715884105727"
+    ),
+    (
+        u128,
+        "uint128",
+        "^[0-9]+$",
+        "0",
+        "340282366920938463463374607431768211455"
+    )
+);
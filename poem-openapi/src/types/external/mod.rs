@@ -1,8 +1,16 @@
+#[cfg(feature = "bigint")]
+mod bigint;
 mod bool;
+#[cfg(not(target_arch = "wasm32"))]
+mod bytes;
 #[cfg(feature = "chrono")]
 mod datetime;
 mod floats;
 mod integers;
+mod non_zero;
 mod optional;
+mod smart_ptr;
 mod string;
+#[cfg(feature = "url")]
+mod url;
 mod vec;
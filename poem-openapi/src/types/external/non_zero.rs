@@ -0,0 +1,114 @@
+use std::{
+    borrow::Cow,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU8,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+macro_rules! impl_type_for_non_zero {
+    ($(($ty:ty, $inner:ty, $schema:expr)),*) => {
+        $(
+        impl Type for $ty {
+            fn name() -> Cow<'static, str> {
+                <$inner as Type>::name()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Inline(Box::new($schema))
+            }
+
+            impl_value_type!();
+        }
+
+        impl ParseFromJSON for $ty {
+            fn parse_from_json(value: Value) -> ParseResult<Self> {
+                let value = <$inner>::parse_from_json(value).map_err(ParseError::propagate)?;
+                Self::new(value).ok_or_else(|| ParseError::custom("Only non-zero integers are accepted."))
+            }
+        }
+
+        impl ParseFromParameter for $ty {
+            fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+                let value = <$inner>::parse_from_parameter(value).map_err(ParseError::propagate)?;
+                Self::new(value).ok_or_else(|| ParseError::custom("Only non-zero integers are accepted."))
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #[poem::async_trait]
+        impl ParseFromMultipartField for $ty {
+            async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+                let value = <$inner>::parse_from_multipart(field).await.map_err(ParseError::propagate)?;
+                Self::new(value).ok_or_else(|| ParseError::custom("Only non-zero integers are accepted."))
+            }
+        }
+
+        impl ToJSON for $ty {
+            fn to_json(&self) -> Value {
+                self.get().to_json()
+            }
+        }
+        )*
+    };
+}
+
+fn unsigned(format: &'static str) -> MetaSchema {
+    MetaSchema {
+        minimum: Some(1.0),
+        ..MetaSchema::new_with_format("integer", format)
+    }
+}
+
+fn signed(format: &'static str) -> MetaSchema {
+    MetaSchema {
+        description: Some("A non-zero integer."),
+        ..MetaSchema::new_with_format("integer", format)
+    }
+}
+
+impl_type_for_non_zero!(
+    (NonZeroU8, u8, unsigned("uint8")),
+    (NonZeroU16, u16, unsigned("uint16")),
+    (NonZeroU32, u32, unsigned("uint32")),
+    (NonZeroU64, u64, unsigned("uint64")),
+    (NonZeroI8, i8, signed("int8")),
+    (NonZeroI16, i16, signed("int16")),
+    (NonZeroI32, i32, signed("int32")),
+    (NonZeroI64, i64, signed("int64")),
+    (
+        NonZeroU128,
+        u128,
+        MetaSchema {
+            description: Some(
+                "An integer from 1 to 340282366920938463463374607431768211455, which is encoded \
+                 as a string."
+            ),
+            pattern: Some("^0*[1-9][0-9]*$".into()),
+            ..MetaSchema::new_with_format("string", "uint128")
+        }
+    ),
+    (
+        NonZeroI128,
+        i128,
+        MetaSchema {
+            description: Some(
+                "A non-zero integer from -170141183460469231731687303715884105728 to \
+                 170141183460469231731687303715884105727, which is encoded as a string."
+            ),
+            pattern: Some("^-?0*[1-9][0-9]*$".into()),
+            ..MetaSchema::new_with_format("string", "int128")
+        }
+    )
+);
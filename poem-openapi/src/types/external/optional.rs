@@ -1,12 +1,15 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field as PoemField;
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::{multipart::UploadContext, ParseFromMultipartField};
 use crate::{
     registry::{MetaSchemaRef, Registry},
     types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
+        ParseError, ParseFromJSON, ParseFromParameter, ParseFromParameterBorrowed, ParseResult,
         ToJSON, Type,
     },
 };
@@ -45,9 +48,32 @@ impl<T: ParseFromJSON> ParseFromJSON for Option<T> {
             )),
         }
     }
+
+    fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self> {
+        if matches!(std::str::from_utf8(bytes), Ok(s) if s.trim() == "null") {
+            return Ok(None);
+        }
+        Ok(Some(
+            T::parse_from_json_bytes(bytes).map_err(ParseError::propagate)?,
+        ))
+    }
+}
+
+impl<'a> ParseFromParameterBorrowed<'a> for Option<&'a str> {
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self> {
+        Ok(value)
+    }
+}
+
+impl<'a> ParseFromParameterBorrowed<'a> for Option<Cow<'a, str>> {
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self> {
+        Ok(value.map(Cow::Borrowed))
+    }
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
+    const IS_MULTIPLE: bool = T::IS_MULTIPLE;
+
     fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
         match value {
             Some(value) => T::parse_from_parameter(Some(value))
@@ -56,8 +82,19 @@ impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
             None => Ok(None),
         }
     }
+
+    fn parse_from_parameters<'a, I: IntoIterator<Item = &'a str>>(values: I) -> ParseResult<Self> {
+        let mut values = values.into_iter().peekable();
+        match values.peek() {
+            Some(_) => T::parse_from_parameters(values)
+                .map_err(ParseError::propagate)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Option<T> {
     async fn parse_from_multipart(value: Option<PoemField>) -> ParseResult<Self> {
@@ -69,6 +106,19 @@ impl<T: ParseFromMultipartField> ParseFromMultipartField for Option<T> {
             None => Ok(None),
         }
     }
+
+    async fn parse_from_multipart_with_context(
+        value: Option<PoemField>,
+        context: &UploadContext<'_>,
+    ) -> ParseResult<Self> {
+        match value {
+            Some(value) => T::parse_from_multipart_with_context(Some(value), context)
+                .await
+                .map_err(ParseError::propagate)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
 impl<T: ToJSON> ToJSON for Option<T> {
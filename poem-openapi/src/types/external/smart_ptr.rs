@@ -0,0 +1,107 @@
+use std::{borrow::Cow, sync::Arc};
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field as PoemField;
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::{multipart::UploadContext, ParseFromMultipartField};
+use crate::{
+    registry::{MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+macro_rules! impl_types_for_smart_ptr {
+    ($(($ty:ty, $new:expr, $($bounds:tt)*)),*) => {
+        $(
+        impl<T: Type $($bounds)*> Type for $ty {
+            const IS_REQUIRED: bool = T::IS_REQUIRED;
+
+            type ValueType = T::ValueType;
+
+            fn name() -> Cow<'static, str> {
+                T::name()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                T::schema_ref()
+            }
+
+            fn register(registry: &mut Registry) {
+                T::register(registry);
+            }
+
+            fn as_value(&self) -> Option<&Self::ValueType> {
+                T::as_value(self)
+            }
+        }
+
+        impl<T: ParseFromJSON $($bounds)*> ParseFromJSON for $ty {
+            fn parse_from_json(value: Value) -> ParseResult<Self> {
+                T::parse_from_json(value)
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+
+            fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self> {
+                T::parse_from_json_bytes(bytes)
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+        }
+
+        impl<T: ParseFromParameter $($bounds)*> ParseFromParameter for $ty {
+            const IS_MULTIPLE: bool = T::IS_MULTIPLE;
+
+            fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+                T::parse_from_parameter(value)
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+
+            fn parse_from_parameters<'a, I: IntoIterator<Item = &'a str>>(
+                values: I,
+            ) -> ParseResult<Self> {
+                T::parse_from_parameters(values)
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #[poem::async_trait]
+        impl<T: ParseFromMultipartField $($bounds)*> ParseFromMultipartField for $ty {
+            async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
+                T::parse_from_multipart(field)
+                    .await
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+
+            async fn parse_from_multipart_with_context(
+                field: Option<PoemField>,
+                context: &UploadContext<'_>,
+            ) -> ParseResult<Self> {
+                T::parse_from_multipart_with_context(field, context)
+                    .await
+                    .map($new)
+                    .map_err(ParseError::propagate)
+            }
+        }
+
+        impl<T: ToJSON $($bounds)*> ToJSON for $ty {
+            fn to_json(&self) -> Value {
+                T::to_json(self)
+            }
+        }
+        )*
+    };
+}
+
+// `Rc<T>` is not supported, because the `Type` requires `Send + Sync`, which
+// `Rc<T>` does not implement, use `Arc<T>` instead.
+impl_types_for_smart_ptr!(
+    (Box<T>, Box::new,),
+    (Arc<T>, Arc::new,),
+    (Cow<'static, T>, Cow::Owned, + Clone)
+);
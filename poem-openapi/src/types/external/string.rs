@@ -1,12 +1,15 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
     types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
+        ParseError, ParseFromJSON, ParseFromParameter, ParseFromParameterBorrowed, ParseResult,
         ToJSON, Type,
     },
 };
@@ -31,6 +34,14 @@ impl ParseFromJSON for String {
             Err(ParseError::expected_type(value))
         }
     }
+
+    fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self> {
+        match serde_json::from_slice(bytes) {
+            Ok(value) => Ok(value),
+            // reports the same errors as `parse_from_json`
+            Err(_) => Self::parse_from_json(serde_json::from_slice(bytes)?),
+        }
+    }
 }
 
 impl ParseFromParameter for String {
@@ -42,11 +53,12 @@ impl ParseFromParameter for String {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 impl ParseFromMultipartField for String {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
         match field {
-            Some(field) => Ok(field.text().await.map_err(ParseError::custom)?),
+            Some(field) => Ok(field.text().await.map_err(ParseError::io)?),
             None => Err(ParseError::expected_input()),
         }
     }
@@ -70,8 +82,40 @@ impl<'a> Type for &'a str {
     }
 }
 
+impl<'a> ParseFromParameterBorrowed<'a> for &'a str {
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self> {
+        value.ok_or_else(ParseError::expected_input)
+    }
+}
+
 impl<'a> ToJSON for &'a str {
     fn to_json(&self) -> Value {
         Value::String(self.to_string())
     }
 }
+
+impl<'a> Type for Cow<'a, str> {
+    fn name() -> Cow<'static, str> {
+        "string".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+    }
+}
+
+impl<'a> ParseFromParameterBorrowed<'a> for Cow<'a, str> {
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self> {
+        value
+            .map(Cow::Borrowed)
+            .ok_or_else(ParseError::expected_input)
+    }
+}
+
+impl<'a> ToJSON for Cow<'a, str> {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
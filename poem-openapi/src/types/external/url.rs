@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+use url::Url;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+impl Type for Url {
+    fn name() -> Cow<'static, str> {
+        "string(uri)".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            description: Some(
+                "An absolute URL. The internationalized domain names are converted to punycode \
+                 and the scheme and the host are lowercased.",
+            ),
+            ..MetaSchema::new_with_format("string", "uri")
+        }))
+    }
+}
+
+impl ParseFromJSON for Url {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Ok(Url::parse(&value)?)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl ParseFromParameter for Url {
+    fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(Url::parse(value)?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[poem::async_trait]
+impl ParseFromMultipartField for Url {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(Url::parse(&field.text().await?)?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for Url {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
@@ -1,10 +1,15 @@
 use std::borrow::Cow;
 
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field as PoemField;
+use serde_json::value::RawValue;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::{multipart::UploadContext, ParseFromMultipartField};
 use crate::{
-    poem::web::Field as PoemField,
     registry::{MetaSchema, MetaSchemaRef, Registry},
     serde_json::Value,
-    types::{ParseError, ParseFromJSON, ParseFromMultipartField, ParseResult, ToJSON, Type},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl<T: Type> Type for Vec<T> {
@@ -39,8 +44,55 @@ impl<T: ParseFromJSON> ParseFromJSON for Vec<T> {
             _ => Err(ParseError::expected_type(value)),
         }
     }
+
+    fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self> {
+        // the items borrow the bytes, and are parsed by `T` separately
+        let items: Vec<&RawValue> = match serde_json::from_slice(bytes) {
+            Ok(items) => items,
+            // reports the same errors as `parse_from_json`
+            Err(_) => return Self::parse_from_json(serde_json::from_slice(bytes)?),
+        };
+        let mut res = Vec::with_capacity(items.len());
+        for item in items {
+            res.push(
+                T::parse_from_json_bytes(item.get().as_bytes()).map_err(ParseError::propagate)?,
+            );
+        }
+        Ok(res)
+    }
 }
 
+/// The items of a single value are separated by commas, such as the header
+/// `X-Ids: 1,2`, and the query parameter is repeated for each item, such as
+/// `?id=1&id=2`.
+///
+/// The values of the query parameter are not split, because they have been
+/// percent-decoded, a comma in them is a part of the item, use the `form`
+/// style with `explode = false` for the comma-separated query parameters.
+impl<T: ParseFromParameter> ParseFromParameter for Vec<T> {
+    const IS_MULTIPLE: bool = true;
+
+    fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Self::parse_from_parameters(value.split(',')),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+
+    fn parse_from_parameters<'a, I: IntoIterator<Item = &'a str>>(values: I) -> ParseResult<Self> {
+        let mut values = values.into_iter().peekable();
+        if values.peek().is_none() {
+            return Err(ParseError::expected_input());
+        }
+        let mut res = Vec::new();
+        for item in values.filter(|item| !item.is_empty()) {
+            res.push(T::parse_from_parameter(Some(item)).map_err(ParseError::propagate)?);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Vec<T> {
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
@@ -62,6 +114,33 @@ impl<T: ParseFromMultipartField> ParseFromMultipartField for Vec<T> {
         self.push(item);
         Ok(self)
     }
+
+    async fn parse_from_multipart_with_context(
+        field: Option<PoemField>,
+        context: &UploadContext<'_>,
+    ) -> ParseResult<Self> {
+        match field {
+            Some(field) => {
+                let item = T::parse_from_multipart_with_context(Some(field), context)
+                    .await
+                    .map_err(ParseError::propagate)?;
+                Ok(vec![item])
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn parse_from_repeated_field_with_context(
+        mut self,
+        field: PoemField,
+        context: &UploadContext<'_>,
+    ) -> ParseResult<Self> {
+        let item = T::parse_from_multipart_with_context(Some(field), context)
+            .await
+            .map_err(ParseError::propagate)?;
+        self.push(item);
+        Ok(self)
+    }
 }
 
 impl<T: ToJSON> ToJSON for Vec<T> {
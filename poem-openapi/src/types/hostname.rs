@@ -0,0 +1,181 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+    str::FromStr,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+use url::Host;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+/// A hostname that is normalized when it is parsed, so that the hostnames
+/// submitted by the users compare consistently.
+///
+/// The internationalized domain names are converted to the ASCII form with
+/// punycode and the letters are lowercased, for example `Bücher.Example` is
+/// parsed as `xn--bcher-kva.example`. The IP addresses are rejected, since
+/// they are not hostnames.
+///
+/// The hosts of [`url::Url`] are normalized in the same way.
+///
+/// NOTE: Its type is `string` and the format is `hostname`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::types::Hostname;
+///
+/// let hostname = "Bücher.Example".parse::<Hostname>().unwrap();
+/// assert_eq!(hostname.as_str(), "xn--bcher-kva.example");
+/// assert_eq!(hostname, "xn--bcher-kva.example".parse().unwrap());
+/// assert!("exa mple.com".parse::<Hostname>().is_err());
+/// assert!("127.0.0.1".parse::<Hostname>().is_err());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+pub struct Hostname(String);
+
+impl Hostname {
+    /// Returns the normalized hostname.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Hostname {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromStr for Hostname {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Host::parse(s.trim()) {
+            Ok(Host::Domain(domain)) => Ok(Self(domain)),
+            Ok(_) => Err(format!(
+                "invalid hostname `{}`: IP addresses are not allowed",
+                s
+            )),
+            Err(err) => Err(format!("invalid hostname `{}`: {}", s, err)),
+        }
+    }
+}
+
+impl Display for Hostname {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type for Hostname {
+    fn name() -> Cow<'static, str> {
+        "string(hostname)".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            description: Some(
+                "A hostname. The internationalized domain names are converted to punycode and \
+                 the letters are lowercased.",
+            ),
+            ..MetaSchema::new_with_format("string", "hostname")
+        }))
+    }
+}
+
+impl ParseFromJSON for Hostname {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Ok(value.parse()?)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl ParseFromParameter for Hostname {
+    fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(value.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[poem::async_trait]
+impl ParseFromMultipartField for Hostname {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(field.text().await?.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for Hostname {
+    fn to_json(&self) -> Value {
+        Value::String(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<String, String> {
+        s.parse::<Hostname>().map(|hostname| hostname.0)
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(parse("Example.COM").unwrap(), "example.com");
+        assert_eq!(parse(" example.com ").unwrap(), "example.com");
+        assert_eq!(parse("Bücher.Example").unwrap(), "xn--bcher-kva.example");
+        assert_eq!(
+            parse("xn--bcher-kva.example").unwrap(),
+            "xn--bcher-kva.example"
+        );
+        assert_eq!(parse("BÜCHER.example").unwrap(), "xn--bcher-kva.example");
+        assert_eq!(parse("例え.テスト").unwrap(), "xn--r8jz45g.xn--zckzah");
+        // the fullwidth letters and dots are mapped by IDNA
+        assert_eq!(parse("ｅｘａｍｐｌｅ．com").unwrap(), "example.com");
+    }
+
+    #[test]
+    fn reject() {
+        assert!(parse("").is_err());
+        assert!(parse("exa mple.com").is_err());
+        assert!(parse("127.0.0.1").is_err());
+        assert!(parse("[::1]").is_err());
+        assert!(parse("0x7f.1").is_err());
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            Hostname::parse_from_json(Value::String("Bücher.Example".to_string()))
+                .unwrap()
+                .to_json(),
+            Value::String("xn--bcher-kva.example".to_string())
+        );
+        assert!(Hostname::parse_from_json(Value::String("10.0.0.1".to_string())).is_err());
+        assert!(Hostname::parse_from_json(Value::Bool(true)).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+    str::FromStr,
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use poem::web::Field;
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+const UNITS: &[(&str, u128)] = &[
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// A duration that is parsed from a human-friendly string.
+///
+/// The string is a sequence of integers followed by the units, such as `30s`,
+/// `1h30m` or `1d 12h`. The units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+///
+/// It is serialized with the same format, such as `1h30m`.
+///
+/// NOTE: Its type is `string` and the format is `duration`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem_openapi::types::HumanDuration;
+///
+/// assert_eq!(
+///     "1h30m".parse::<HumanDuration>().unwrap(),
+///     HumanDuration(Duration::from_secs(5400))
+/// );
+/// assert_eq!(
+///     "1s 500ms".parse::<HumanDuration>().unwrap(),
+///     HumanDuration(Duration::from_millis(1500))
+/// );
+/// assert_eq!(HumanDuration(Duration::from_secs(90)).to_string(), "1m30s");
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration `{}`", s);
+        let mut nanos: u128 = 0;
+        let mut rest = s.trim();
+
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+
+        while !rest.is_empty() {
+            let idx = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(idx);
+            let tail = tail.trim_start();
+            let idx = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(idx);
+
+            let number = number.parse::<u128>().map_err(|_| invalid())?;
+            let unit = UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .map(|(_, nanos)| *nanos)
+                .ok_or_else(invalid)?;
+            nanos = number
+                .checked_mul(unit)
+                .and_then(|value| nanos.checked_add(value))
+                .ok_or_else(invalid)?;
+            rest = tail.trim_start();
+        }
+
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| invalid())?;
+        Ok(Self(Duration::new(secs, (nanos % 1_000_000_000) as u32)))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        for (name, unit) in UNITS {
+            if nanos >= *unit {
+                write!(f, "{}{}", nanos / unit, name)?;
+                nanos %= unit;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Type for HumanDuration {
+    fn name() -> Cow<'static, str> {
+        "string(duration)".into()
+    }
+
+    impl_value_type!();
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            description: Some(
+                "A sequence of integers followed by the units, such as `30s`, `1h30m` or `1d \
+                 12h`. The units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.",
+            ),
+            pattern: Some(r"^\s*([0-9]+\s*(d|h|m|s|ms|us|ns)\s*)+$".to_string()),
+            ..MetaSchema::new_with_format("string", "duration")
+        }))
+    }
+}
+
+impl ParseFromJSON for HumanDuration {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Ok(value.parse()?)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl ParseFromParameter for HumanDuration {
+    fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self> {
+        match value {
+            Some(value) => Ok(value.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[poem::async_trait]
+impl ParseFromMultipartField for HumanDuration {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(field.text().await?.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for HumanDuration {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
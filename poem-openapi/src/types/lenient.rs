@@ -0,0 +1,61 @@
+use serde_json::{Number, Value};
+
+use crate::types::{ParseFromJSON, ParseResult};
+
+/// Parses the value, and if it fails, parses it again after converting the
+/// numeric strings to numbers and the floats with zero fraction to integers.
+///
+/// It is used by the object fields with the `lenient` attribute.
+#[doc(hidden)]
+pub fn parse_from_json_lenient<T: ParseFromJSON>(value: Value) -> ParseResult<T> {
+    match T::parse_from_json(value.clone()) {
+        Ok(value) => Ok(value),
+        Err(err) => match normalize_numbers(&value) {
+            Some(value) => T::parse_from_json(value).map_err(|_| err),
+            None => Err(err),
+        },
+    }
+}
+
+/// Returns `None` if there is nothing to convert.
+fn normalize_numbers(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(n) = s.parse::<i64>() {
+                Some(Value::Number(n.into()))
+            } else if let Ok(n) = s.parse::<u64>() {
+                Some(Value::Number(n.into()))
+            } else {
+                s.parse::<f64>().ok().and_then(Number::from_f64).map(|n| {
+                    normalize_numbers(&Value::Number(n.clone())).unwrap_or(Value::Number(n))
+                })
+            }
+        }
+        Value::Number(n) => {
+            let f = n.as_f64().filter(|_| n.is_f64())?;
+            if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+                Some(Value::Number((f as i64).into()))
+            } else if f.fract() == 0.0 && f >= 0.0 && f < u64::MAX as f64 {
+                Some(Value::Number((f as u64).into()))
+            } else {
+                None
+            }
+        }
+        Value::Array(items) => {
+            let mut changed = false;
+            let items = items
+                .iter()
+                .map(|item| match normalize_numbers(item) {
+                    Some(item) => {
+                        changed = true;
+                        item
+                    }
+                    None => item.clone(),
+                })
+                .collect();
+            changed.then(|| Value::Array(items))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,106 @@
+//! The masking functions used by the `#[oai(mask = "...")]` attribute of the
+//! object fields.
+//!
+//! A masking function takes the string value of the field and returns the
+//! masked string, it is only applied when the object is serialized.
+//!
+//! Only the fields that are serialized to strings, or arrays of strings, can
+//! be masked, see [`Maskable`].
+
+use std::{borrow::Cow, sync::Arc};
+
+use serde_json::Value;
+
+use crate::types::Password;
+
+/// The types that are serialized to JSON strings, or arrays of strings, so the
+/// fields of them can be masked.
+///
+/// Masking a field of other types, such as a number, is a compile error
+/// because it would be serialized unmasked. Implement it for the custom types
+/// that are serialized to strings.
+///
+/// ```compile_fail
+/// use poem_openapi::Object;
+///
+/// #[derive(Object)]
+/// struct Obj {
+///     #[oai(mask = "all")]
+///     pin: i32,
+/// }
+/// ```
+pub trait Maskable {}
+
+impl Maskable for String {}
+
+impl Maskable for &str {}
+
+impl Maskable for Cow<'_, str> {}
+
+impl Maskable for Password {}
+
+#[cfg(feature = "url")]
+impl Maskable for crate::types::Hostname {}
+
+#[cfg(feature = "url")]
+impl Maskable for url::Url {}
+
+impl<T: Maskable> Maskable for Option<T> {}
+
+impl<T: Maskable> Maskable for Vec<T> {}
+
+impl<T: Maskable + ?Sized> Maskable for Box<T> {}
+
+impl<T: Maskable + ?Sized> Maskable for Arc<T> {}
+
+#[doc(hidden)]
+pub fn assert_maskable<T: Maskable + ?Sized>() {}
+
+/// Replaces all characters except the last four with `*`, the value with
+/// four or fewer characters is replaced completely.
+///
+/// The format of the masked field is `masked-last4`.
+///
+/// ```
+/// use poem_openapi::types::mask::last4;
+///
+/// assert_eq!(last4("4111111111111111"), "************1111");
+/// assert_eq!(last4("1234"), "****");
+/// ```
+pub fn last4(value: &str) -> String {
+    let len = value.chars().count();
+    if len <= 4 {
+        return all(value);
+    }
+    value
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| if idx < len - 4 { '*' } else { c })
+        .collect()
+}
+
+/// Replaces all characters with `*`.
+///
+/// The format of the masked field is `masked`.
+///
+/// ```
+/// use poem_openapi::types::mask::all;
+///
+/// assert_eq!(all("secret"), "******");
+/// ```
+pub fn all(value: &str) -> String {
+    "*".repeat(value.chars().count())
+}
+
+/// Applies the masking function to the strings in the JSON value, the items
+/// of the arrays are masked separately and other values are not changed.
+#[doc(hidden)]
+pub fn mask_json(value: Value, f: fn(&str) -> String) -> Value {
+    match value {
+        Value::String(value) => Value::String(f(&value)),
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(|item| mask_json(item, f)).collect())
+        }
+        value => value,
+    }
+}
@@ -6,10 +6,20 @@ mod macros;
 mod any;
 mod base64_type;
 mod binary;
+mod byte_size;
+mod dynamic_object;
 mod error;
 mod external;
+#[cfg(feature = "url")]
+mod hostname;
+mod human_duration;
+mod lenient;
 mod password;
+mod raw_object;
 
+pub mod mask;
+
+#[cfg(not(target_arch = "wasm32"))]
 pub mod multipart;
 
 use std::borrow::Cow;
@@ -17,12 +27,24 @@ use std::borrow::Cow;
 pub use any::Any;
 pub use base64_type::Base64;
 pub use binary::Binary;
+pub use byte_size::ByteSize;
+pub use dynamic_object::{DynamicObject, DynamicObjectDefinition, DynamicObjectSchema};
 pub use error::{ParseError, ParseResult};
+#[cfg(feature = "url")]
+pub use hostname::Hostname;
+pub use human_duration::HumanDuration;
+#[doc(hidden)]
+pub use lenient::parse_from_json_lenient;
 pub use password::Password;
+#[cfg(not(target_arch = "wasm32"))]
 use poem::web::Field as PoemField;
+#[doc(hidden)]
+pub use raw_object::RawObject;
 use serde_json::Value;
 
 use crate::registry::{MetaSchemaRef, Registry};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::types::multipart::UploadContext;
 
 /// Represents a OpenAPI type.
 pub trait Type: Send + Sync {
@@ -52,18 +74,91 @@ pub trait ParseFromJSON: Type {
     fn parse_from_json(value: Value) -> ParseResult<Self>
     where
         Self: Sized;
+
+    /// Parse from the bytes of a JSON document, such as the request body.
+    ///
+    /// The default implementation parses the bytes into a
+    /// [`serde_json::Value`] and calls [`ParseFromJSON::parse_from_json`], the
+    /// types that can be deserialized directly override it to avoid the
+    /// intermediate value.
+    fn parse_from_json_bytes(bytes: &[u8]) -> ParseResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_json(serde_json::from_slice(bytes).map_err(ParseError::custom)?)
+    }
 }
 
 /// Represents a type that can parsing from parameter. (header, query, path,
 /// cookie)
 pub trait ParseFromParameter: Type {
+    /// If it is `true`, the query parameter can be repeated, such as
+    /// `?id=1&id=2`, and all the values are passed to
+    /// [`ParseFromParameter::parse_from_parameters`].
+    const IS_MULTIPLE: bool = false;
+
     /// Parse from parameter.
     fn parse_from_parameter(value: Option<&str>) -> ParseResult<Self>
     where
         Self: Sized;
+
+    /// Parse from the values of a repeated query parameter.
+    ///
+    /// The default implementation parses the first value.
+    fn parse_from_parameters<'a, I: IntoIterator<Item = &'a str>>(values: I) -> ParseResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_parameter(values.into_iter().next())
+    }
+}
+
+/// Represents a type that can parsing from parameter, and may borrow from the
+/// parameter value. (header, query, path, cookie)
+///
+/// It is implemented for all the types that implement [`ParseFromParameter`],
+/// and for `&str` and `Cow<str>` that do not allocate a `String` for each
+/// parameter.
+pub trait ParseFromParameterBorrowed<'a>: Type {
+    /// See [`ParseFromParameter::IS_MULTIPLE`].
+    const IS_MULTIPLE: bool = false;
+
+    /// Parse from parameter.
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self>
+    where
+        Self: Sized;
+
+    /// Parse from the values of a repeated query parameter.
+    ///
+    /// The default implementation parses the first value.
+    fn parse_from_parameters_borrowed<I: IntoIterator<Item = &'a str>>(
+        values: I,
+    ) -> ParseResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_parameter_borrowed(values.into_iter().next())
+    }
+}
+
+impl<'a, T: ParseFromParameter> ParseFromParameterBorrowed<'a> for T {
+    const IS_MULTIPLE: bool = T::IS_MULTIPLE;
+
+    #[inline]
+    fn parse_from_parameter_borrowed(value: Option<&'a str>) -> ParseResult<Self> {
+        T::parse_from_parameter(value)
+    }
+
+    #[inline]
+    fn parse_from_parameters_borrowed<I: IntoIterator<Item = &'a str>>(
+        values: I,
+    ) -> ParseResult<Self> {
+        T::parse_from_parameters(values)
+    }
 }
 
 /// Represents a type that can parsing from multipart.
+#[cfg(not(target_arch = "wasm32"))]
 #[poem::async_trait]
 pub trait ParseFromMultipartField: Type {
     /// Parse from multipart field.
@@ -78,6 +173,34 @@ pub trait ParseFromMultipartField: Type {
     {
         Err(ParseError::<Self>::custom("repeated field"))
     }
+
+    /// Parse from multipart field with the [`UploadContext`] of the request.
+    ///
+    /// The default implementation ignores the context.
+    async fn parse_from_multipart_with_context(
+        field: Option<PoemField>,
+        _context: &UploadContext<'_>,
+    ) -> ParseResult<Self>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_multipart(field).await
+    }
+
+    /// Parse from repeated multipart field with the [`UploadContext`] of the
+    /// request.
+    ///
+    /// The default implementation ignores the context.
+    async fn parse_from_repeated_field_with_context(
+        self,
+        field: PoemField,
+        _context: &UploadContext<'_>,
+    ) -> ParseResult<Self>
+    where
+        Self: Sized,
+    {
+        self.parse_from_repeated_field(field).await
+    }
 }
 
 /// Represents a type that can converted to JSON.
@@ -39,7 +39,7 @@ impl<T: ParseFromJSON> ParseFromMultipartField for JsonField<T> {
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
         let value = match field {
             Some(field) => {
-                let data = field.bytes().await.map_err(ParseError::custom)?;
+                let data = field.bytes().await.map_err(ParseError::io)?;
                 serde_json::from_slice(&data).map_err(ParseError::custom)?
             }
             None => Value::Null,
@@ -4,4 +4,4 @@ mod json;
 mod upload;
 
 pub use json::JsonField;
-pub use upload::Upload;
+pub use upload::{Upload, UploadConfig, UploadContext};
@@ -1,12 +1,16 @@
 use std::{
     borrow::Cow,
     fmt::{self, Debug, Formatter},
+    io::Cursor,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 use poem::web::Field as PoemField;
 use tokio::{
     fs::File,
-    io::{AsyncRead, AsyncReadExt, Error as IoError, ErrorKind},
+    io::{
+        AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Error as IoError, ErrorKind, SeekFrom,
+    },
 };
 
 use crate::{
@@ -14,11 +18,158 @@ use crate::{
     types::{ParseError, ParseFromMultipartField, ParseResult, Type},
 };
 
+/// The default size above which an uploaded file is spooled to a temporary
+/// file. (1 MiB)
+const DEFAULT_SPOOL_THRESHOLD: usize = 1024 * 1024;
+
+/// Configuration for parsing [`Upload`] fields.
+///
+/// Attach it to the route with [`poem::EndpointExt::data`], otherwise the
+/// default configuration is used.
+///
+/// If an uploaded file exceeds the [`max_file_size`](UploadConfig::max_file_size),
+/// all uploaded files in the request exceed the
+/// [`max_total_size`](UploadConfig::max_total_size), or a field that is not a
+/// file exceeds the [`max_field_size`](UploadConfig::max_field_size), the
+/// request is rejected with `413 Payload Too Large`.
+///
+/// The fields are parsed as they are received, and the limits are checked
+/// before the data is buffered, so the memory usage is bounded by the limits
+/// and the [`spool_threshold`](UploadConfig::spool_threshold).
+///
+/// # Example
+///
+/// ```
+/// use poem::{EndpointExt, Route};
+/// use poem_openapi::types::multipart::UploadConfig;
+///
+/// let app = Route::new().data(
+///     UploadConfig::new()
+///         .spool_threshold(64 * 1024)
+///         .max_file_size(10 * 1024 * 1024)
+///         .max_total_size(50 * 1024 * 1024)
+///         .max_field_size(64 * 1024),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    spool_threshold: usize,
+    max_file_size: Option<usize>,
+    max_total_size: Option<usize>,
+    pub(crate) max_field_size: Option<usize>,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            spool_threshold: DEFAULT_SPOOL_THRESHOLD,
+            max_file_size: None,
+            max_total_size: None,
+            max_field_size: None,
+        }
+    }
+}
+
+impl UploadConfig {
+    /// Create a `UploadConfig` with the default values.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns `true` if any of the size limits is set.
+    pub(crate) fn has_limits(&self) -> bool {
+        self.max_file_size.is_some()
+            || self.max_total_size.is_some()
+            || self.max_field_size.is_some()
+    }
+
+    /// Files larger than this size are written to a temporary file instead of
+    /// being kept in memory.
+    ///
+    /// Default is `1MiB`.
+    #[must_use]
+    pub fn spool_threshold(self, size: usize) -> Self {
+        Self {
+            spool_threshold: size,
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of a single uploaded file.
+    ///
+    /// Default is unlimited.
+    #[must_use]
+    pub fn max_file_size(self, size: usize) -> Self {
+        Self {
+            max_file_size: Some(size),
+            ..self
+        }
+    }
+
+    /// Sets the maximum total size of all uploaded files in a request.
+    ///
+    /// Default is unlimited.
+    #[must_use]
+    pub fn max_total_size(self, size: usize) -> Self {
+        Self {
+            max_total_size: Some(size),
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of a field that is not a file, such as a text or
+    /// JSON field, which is kept in memory.
+    ///
+    /// Default is unlimited.
+    #[must_use]
+    pub fn max_field_size(self, size: usize) -> Self {
+        Self {
+            max_field_size: Some(size),
+            ..self
+        }
+    }
+}
+
+/// The state of parsing the multipart fields of a request with an
+/// [`UploadConfig`], it counts the size of the uploaded files for
+/// [`UploadConfig::max_total_size`].
+///
+/// A context is created for each request, see
+/// [`ParseFromMultipartField::parse_from_multipart_with_context`].
+pub struct UploadContext<'a> {
+    config: &'a UploadConfig,
+    total_size: AtomicUsize,
+}
+
+impl<'a> UploadContext<'a> {
+    /// Create a `UploadContext` with the configuration.
+    pub fn new(config: &'a UploadConfig) -> Self {
+        Self {
+            config,
+            total_size: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &'a UploadConfig {
+        self.config
+    }
+}
+
+enum UploadData {
+    Memory(Vec<u8>),
+    File(File),
+}
+
 /// A uploaded file for multipart.
+///
+/// Small files are kept in memory, and files larger than
+/// [`UploadConfig::spool_threshold`] are written to a temporary file.
 pub struct Upload {
     file_name: Option<String>,
     content_type: Option<String>,
-    file: File,
+    size: usize,
+    data: UploadData,
 }
 
 impl Debug for Upload {
@@ -30,6 +181,7 @@ impl Debug for Upload {
         if let Some(content_type) = self.content_type() {
             d.field("content_type", &content_type);
         }
+        d.field("size", &self.size);
         d.finish()
     }
 }
@@ -47,28 +199,113 @@ impl Upload {
         self.file_name.as_deref()
     }
 
+    /// Returns the size of the file in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
     /// Consumes this body object to return a [`Vec<u8>`] that contains all
     /// data.
     pub async fn into_vec(self) -> Result<Vec<u8>, IoError> {
-        let mut data = Vec::new();
-        self.into_async_read().read_to_end(&mut data).await?;
-        Ok(data)
+        match self.data {
+            UploadData::Memory(data) => Ok(data),
+            UploadData::File(mut file) => {
+                let mut data = Vec::with_capacity(self.size);
+                file.read_to_end(&mut data).await?;
+                Ok(data)
+            }
+        }
     }
 
     /// Consumes this body object to return a [`String`] that contains all data.
     pub async fn into_string(self) -> Result<String, IoError> {
-        Ok(String::from_utf8(
-            self.into_vec()
-                .await
-                .map_err(|err| IoError::new(ErrorKind::Other, err))?
-                .to_vec(),
-        )
-        .map_err(|err| IoError::new(ErrorKind::Other, err))?)
+        String::from_utf8(self.into_vec().await?).map_err(|err| IoError::new(ErrorKind::Other, err))
+    }
+
+    /// Consumes this body object to return a file that contains all data.
+    ///
+    /// If the data is kept in memory, it will be written to a temporary file
+    /// first.
+    pub async fn into_file(self) -> Result<File, IoError> {
+        match self.data {
+            UploadData::Memory(data) => {
+                let mut file = File::from_std(tempfile::tempfile()?);
+                file.write_all(&data).await?;
+                file.seek(SeekFrom::Start(0)).await?;
+                Ok(file)
+            }
+            UploadData::File(file) => Ok(file),
+        }
     }
 
     /// Consumes this body object to return a reader.
     pub fn into_async_read(self) -> impl AsyncRead + Unpin + Send + 'static {
-        self.file
+        let reader: Box<dyn AsyncRead + Unpin + Send> = match self.data {
+            UploadData::Memory(data) => Box::new(Cursor::new(data)),
+            UploadData::File(file) => Box::new(file),
+        };
+        reader
+    }
+
+    async fn from_field(field: PoemField, context: &UploadContext<'_>) -> ParseResult<Self> {
+        let config = context.config;
+        let content_type = field.content_type().map(ToString::to_string);
+        let file_name = field.file_name().map(ToString::to_string);
+        let reader = field.into_async_read();
+        tokio::pin!(reader);
+
+        let mut size = 0;
+        let mut data = UploadData::Memory(Vec::new());
+        let mut buf = vec![0; 8192];
+
+        loop {
+            let sz = reader.read(&mut buf).await.map_err(ParseError::io)?;
+            if sz == 0 {
+                break;
+            }
+
+            size += sz;
+            if matches!(config.max_file_size, Some(max_file_size) if size > max_file_size) {
+                return Err(ParseError::payload_too_large());
+            }
+            let total_size = context.total_size.fetch_add(sz, Ordering::Relaxed) + sz;
+            if matches!(config.max_total_size, Some(max_total_size) if total_size > max_total_size)
+            {
+                return Err(ParseError::payload_too_large());
+            }
+
+            match &mut data {
+                UploadData::Memory(mem) if size > config.spool_threshold => {
+                    let mut file =
+                        File::from_std(tempfile::tempfile().map_err(ParseError::custom)?);
+                    file.write_all(mem).await.map_err(ParseError::custom)?;
+                    file.write_all(&buf[..sz])
+                        .await
+                        .map_err(ParseError::custom)?;
+                    data = UploadData::File(file);
+                }
+                UploadData::Memory(mem) => mem.extend_from_slice(&buf[..sz]),
+                UploadData::File(file) => file
+                    .write_all(&buf[..sz])
+                    .await
+                    .map_err(ParseError::custom)?,
+            }
+        }
+
+        if let UploadData::File(file) = &mut data {
+            file.flush().await.map_err(ParseError::custom)?;
+            file.seek(SeekFrom::Start(0))
+                .await
+                .map_err(ParseError::custom)?;
+        }
+
+        Ok(Self {
+            file_name,
+            content_type,
+            size,
+            data,
+        })
     }
 }
 
@@ -87,16 +324,16 @@ impl Type for Upload {
 #[poem::async_trait]
 impl ParseFromMultipartField for Upload {
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
+        let config = UploadConfig::default();
+        Self::parse_from_multipart_with_context(field, &UploadContext::new(&config)).await
+    }
+
+    async fn parse_from_multipart_with_context(
+        field: Option<PoemField>,
+        context: &UploadContext<'_>,
+    ) -> ParseResult<Self> {
         match field {
-            Some(field) => {
-                let content_type = field.content_type().map(ToString::to_string);
-                let file_name = field.file_name().map(ToString::to_string);
-                Ok(Self {
-                    content_type,
-                    file_name,
-                    file: field.tempfile().await.map_err(ParseError::custom)?,
-                })
-            }
+            Some(field) => Self::from_field(field, context).await,
             None => Err(ParseError::expected_input()),
         }
     }
@@ -0,0 +1,53 @@
+use std::fmt::{self, Formatter};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::value::RawValue;
+
+/// The properties of a JSON object, the values are not parsed and borrow the
+/// input.
+///
+/// It is used by the generated `ParseFromJSON::parse_from_json_bytes` of the
+/// objects, the objects only have a few properties so they are kept in a
+/// `Vec` rather than a map.
+#[doc(hidden)]
+pub struct RawObject<'a>(Vec<(&'a str, &'a RawValue)>);
+
+impl<'a> RawObject<'a> {
+    /// Returns `true` if the object contains the property.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.0.iter().any(|(key, _)| *key == name)
+    }
+
+    /// Removes the property, the last one wins if the name is duplicated.
+    pub fn remove(&mut self, name: &str) -> Option<&'a RawValue> {
+        let index = self.0.iter().rposition(|(key, _)| *key == name)?;
+        Some(self.0.swap_remove(index).1)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawObject<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawObjectVisitor;
+
+        impl<'de> Visitor<'de> for RawObjectVisitor {
+            type Value = RawObject<'de>;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut properties = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some(property) = map.next_entry()? {
+                    properties.push(property);
+                }
+                Ok(RawObject(properties))
+            }
+        }
+
+        deserializer.deserialize_map(RawObjectVisitor)
+    }
+}
@@ -1,8 +1,10 @@
 use askama::Template;
 use poem::{endpoint::make_sync, web::Html};
 
-use crate::poem::Endpoint;
+use crate::{openapi::SpecEndpoint, poem::Endpoint};
 
+// the assets are embedded in the page, so the UI works without access to the
+// internet
 const SWAGGER_UI_JS: &str = include_str!("swagger-ui-bundle.js");
 const SWAGGER_UI_CSS: &str = include_str!("swagger-ui.css");
 const OAUTH2_REDIRECT_HTML: &str = include_str!("oauth2-redirect.html");
@@ -23,41 +25,36 @@ const OAUTH2_REDIRECT_HTML: &str = include_str!("oauth2-redirect.html");
 
 <div id="ui"></div>
 <script>
-    let spec = {{ spec|safe }};
-    let oauth2RedirectUrl;
-    
-    let query = window.location.href.indexOf("?");
-    if (query > 0) {
-        oauth2RedirectUrl = window.location.href.substring(0, query);
-    } else {
-        oauth2RedirectUrl = window.location.href;
+    // the specification and the OAuth2 redirect page are served next to
+    // this page
+    let baseUrl = window.location.origin + window.location.pathname;
+    if (!baseUrl.endsWith("/")) {
+        baseUrl += "/";
     }
-    
-    if (!oauth2RedirectUrl.endsWith("/")) {
-        oauth2RedirectUrl += "/";
-    }
-    oauth2RedirectUrl += "oauth2-redirect.html";
 
     SwaggerUIBundle({
         dom_id: '#ui',
-        spec: spec,
+        url: baseUrl + "openapi.json",
         filter: false,
-        oauth2RedirectUrl: oauth2RedirectUrl,
+        oauth2RedirectUrl: baseUrl + "oauth2-redirect.html",
+        // the online validator is an external service
+        validatorUrl: null,
     })
 </script>
 
 </body>
 "#
 )]
-struct UITemplate<'a> {
-    spec: &'a str,
+struct UITemplate {
     script: &'static str,
     css: &'static str,
 }
 
-pub(crate) fn create_ui_endpoint(document: &str) -> impl Endpoint {
+/// Create the Swagger UI endpoint, the specification is served by `spec` at
+/// `/openapi.json` of the endpoint, with the `ETag` header and compressed if
+/// the `compression` feature is enabled.
+pub(crate) fn create_ui_endpoint(spec: SpecEndpoint) -> impl Endpoint {
     let index_html = UITemplate {
-        spec: document,
         script: SWAGGER_UI_JS,
         css: SWAGGER_UI_CSS,
     }
@@ -66,6 +63,7 @@ pub(crate) fn create_ui_endpoint(document: &str) -> impl Endpoint {
 
     poem::Route::new()
         .at("/", make_sync(move |_| Html(index_html.clone())))
+        .at("/openapi.json", poem::get(spec))
         .at(
             "/oauth2-redirect.html",
             make_sync(move |_| Html(OAUTH2_REDIRECT_HTML.to_string())),
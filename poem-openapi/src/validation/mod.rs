@@ -22,6 +22,28 @@ pub use unique_items::UniqueItems;
 
 use crate::registry::MetaSchema;
 
+#[cfg(not(target_arch = "wasm32"))]
+tokio::task_local! {
+    static SKIP_VALIDATION: bool;
+}
+
+/// Returns `true` if the validators are skipped for the current request.
+pub fn is_skipped() -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        SKIP_VALIDATION.try_with(|skip| *skip).unwrap_or_default()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        false
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn scope<F: std::future::Future>(skip: bool, fut: F) -> F::Output {
+    SKIP_VALIDATION.scope(skip, fut).await
+}
+
 pub trait Validator<T>: Display {
     fn check(&self, value: &T) -> bool;
 }
@@ -1,8 +1,7 @@
 use derive_more::Display;
-use regex::Regex;
 
 use crate::{
-    registry::MetaSchema,
+    registry::{compiled_pattern, MetaSchema},
     validation::{Validator, ValidatorMeta},
 };
 
@@ -22,7 +21,9 @@ impl Pattern {
 impl<T: AsRef<str>> Validator<T> for Pattern {
     #[inline]
     fn check(&self, value: &T) -> bool {
-        Regex::new(self.pattern).unwrap().is_match(value.as_ref())
+        compiled_pattern(self.pattern)
+            .unwrap()
+            .is_match(value.as_ref())
     }
 }
 
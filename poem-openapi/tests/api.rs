@@ -1,13 +1,15 @@
 use poem::{
+    endpoint::HealthCheck,
     http::{Method, StatusCode, Uri},
     web::Data,
     Endpoint, EndpointExt, IntoEndpoint,
 };
 use poem_openapi::{
-    payload::{Binary, Json, PlainText},
+    payload::{Binary, EventStream, Json, JsonLines, JsonSeq, PlainText, RangeBinary},
     registry::{MetaApi, MetaSchema},
     types::Type,
-    ApiRequest, ApiResponse, OpenApi, OpenApiService, ParseRequestError, Tags,
+    ApiRequest, ApiResponse, CombinedAPI, Object, OpenApi, OpenApiService, OpenApiVersion,
+    ParseRequestError, Tags,
 };
 
 #[tokio::test]
@@ -246,6 +248,57 @@ async fn payload_request() {
     assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
 }
 
+#[tokio::test]
+async fn payload_parsed_after_params() {
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    struct Reader(Arc<AtomicBool>);
+
+    impl AsyncRead for Reader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.0.store(true, Ordering::SeqCst);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(&self, req: Binary<Vec<u8>>, #[oai(name = "code", in = "query")] code: u16) {
+            let _ = (req, code);
+        }
+    }
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let body_read = Arc::new(AtomicBool::new(false));
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/"))
+                .content_type("application/octet-stream")
+                .body(poem::Body::from_async_read(Reader(body_read.clone()))),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(!body_read.load(Ordering::SeqCst));
+}
+
 #[tokio::test]
 async fn response() {
     #[derive(ApiResponse)]
@@ -426,3 +479,1660 @@ async fn poem_extract() {
         .await;
     assert_eq!(resp.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn poem_data() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, data: Data<&i32>) -> PlainText<String> {
+            PlainText(data.0.to_string())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert!(meta.paths[0].operations[0].request.is_none());
+
+    let ep = OpenApiService::new(Api).data(100i32).into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::GET)
+                .uri(Uri::from_static("/"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "100");
+}
+
+#[tokio::test]
+async fn health_check() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self) {}
+    }
+
+    let health = HealthCheck::new()
+        .readiness_check("db", || async { Err::<(), _>("connection refused") })
+        .combine(Api);
+
+    let meta = <CombinedAPI<HealthCheck, Api>>::meta();
+    assert_eq!(meta[0].paths[0].path, "/healthz");
+    assert_eq!(meta[0].paths[1].path, "/readyz");
+    let responses = &meta[0].paths[1].operations[0].responses.responses;
+    assert_eq!(
+        responses
+            .iter()
+            .map(|resp| resp.status.unwrap())
+            .collect::<Vec<_>>(),
+        vec![200, 503]
+    );
+
+    let service = OpenApiService::new(health);
+    let spec: serde_json::Value = serde_json::from_str(&service.spec()).unwrap();
+    assert_eq!(
+        spec["components"]["schemas"]["HealthReport"]["properties"]["status"]["enum"],
+        serde_json::json!(["pass", "fail"])
+    );
+
+    let ep = service.into_endpoint();
+    let call = |uri| {
+        ep.call(
+            poem::Request::builder()
+                .method(Method::GET)
+                .uri(Uri::from_static(uri))
+                .finish(),
+        )
+    };
+    assert_eq!(call("/healthz").await.status(), StatusCode::OK);
+    assert_eq!(
+        call("/readyz").await.status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(call("/").await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn event_stream() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self) -> EventStream<i32> {
+            EventStream::new(futures_util::stream::iter(vec![1, 2, 3]))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let content = &meta.paths[0].operations[0].responses.responses[0].content[0];
+    assert_eq!(content.content_type, "text/event-stream");
+    let schema = content.schema.unwrap_inline();
+    assert_eq!(schema.ty, "array");
+    assert_eq!(schema.format, Some("event-stream"));
+    assert_eq!(schema.items.as_ref().unwrap().unwrap_inline().ty, "integer");
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::GET)
+                .uri(Uri::from_static("/"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.content_type(), Some("text/event-stream"));
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "data: 1\n\ndata: 2\n\ndata: 3\n\n"
+    );
+}
+
+#[tokio::test]
+async fn json_seq() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(name = "n", in = "query")] n: i32) -> JsonSeq<i32> {
+            JsonSeq::new(futures_util::stream::iter(0..n))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let content = &meta.paths[0].operations[0].responses.responses[0].content[0];
+    assert_eq!(content.content_type, "application/json");
+    let schema = content.schema.unwrap_inline();
+    assert_eq!(schema.ty, "array");
+    assert_eq!(schema.items.as_ref().unwrap().unwrap_inline().ty, "integer");
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    for (n, body) in [(0, "[]"), (1, "[0]"), (3, "[0,1,2]")] {
+        let mut resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/?n={}", n).parse().unwrap())
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("application/json"));
+        assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+    }
+}
+
+#[tokio::test]
+async fn json_lines() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(name = "n", in = "query")] n: i32) -> JsonLines<i32> {
+            JsonLines::new(futures_util::stream::iter(0..n))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let content = &meta.paths[0].operations[0].responses.responses[0].content[0];
+    assert_eq!(content.content_type, "application/x-ndjson");
+    assert_eq!(content.schema.unwrap_inline().ty, "integer");
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    for (n, body) in [(0, ""), (1, "0\n"), (3, "0\n1\n2\n")] {
+        let mut resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/?n={}", n).parse().unwrap())
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("application/x-ndjson"));
+        assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+    }
+}
+
+#[tokio::test]
+async fn range_binary() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(name = "Range", in = "header")] range: Option<String>,
+        ) -> RangeBinary<&'static [u8]> {
+            RangeBinary::new(b"0123456789", range.as_deref())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let responses = &meta.paths[0].operations[0].responses.responses;
+    assert_eq!(
+        responses.iter().map(|resp| resp.status).collect::<Vec<_>>(),
+        vec![Some(200), Some(206), Some(416)]
+    );
+    assert_eq!(responses[1].headers[1].name, "Content-Range");
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    for (range, status, content_range, body) in [
+        (None, StatusCode::OK, None, "0123456789"),
+        (
+            Some("bytes=2-4"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 2-4/10"),
+            "234",
+        ),
+        (
+            Some("bytes=7-"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 7-9/10"),
+            "789",
+        ),
+        (
+            Some("bytes=-2"),
+            StatusCode::PARTIAL_CONTENT,
+            Some("bytes 8-9/10"),
+            "89",
+        ),
+        (Some("bytes=0-1,4-5"), StatusCode::OK, None, "0123456789"),
+        (
+            Some("bytes=10-"),
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            Some("bytes */10"),
+            "",
+        ),
+    ] {
+        let mut req = poem::Request::builder().method(Method::GET);
+        if let Some(range) = range {
+            req = req.header("Range", range);
+        }
+        let mut resp = ep.call(req.finish()).await;
+        assert_eq!(resp.status(), status);
+        assert_eq!(
+            resp.headers()
+                .get("Content-Range")
+                .map(|value| value.to_str().unwrap()),
+            content_range
+        );
+        assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+    }
+}
+
+#[tokio::test]
+async fn range_binary_reader() {
+    use std::io::Cursor;
+
+    use poem_openapi::payload::RangeReader;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(name = "Range", in = "header")] range: Option<String>,
+            #[oai(name = "If-Range", in = "header")] if_range: Option<String>,
+        ) -> RangeBinary<RangeReader<Cursor<&'static [u8]>>> {
+            RangeBinary::new(
+                RangeReader::new(Cursor::new(&b"0123456789"[..]), 10),
+                range.as_deref(),
+            )
+            .if_range(if_range.as_deref())
+            .etag("\"v1\"")
+        }
+    }
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    for (range, if_range, status, body) in [
+        (None, None, StatusCode::OK, "0123456789"),
+        (Some("bytes=2-4"), None, StatusCode::PARTIAL_CONTENT, "234"),
+        (Some("bytes=-3"), None, StatusCode::PARTIAL_CONTENT, "789"),
+        (
+            Some("bytes=2-4"),
+            Some("\"v1\""),
+            StatusCode::PARTIAL_CONTENT,
+            "234",
+        ),
+        (
+            Some("bytes=2-4"),
+            Some("\"v2\""),
+            StatusCode::OK,
+            "0123456789",
+        ),
+        (
+            Some("bytes=2-4"),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT"),
+            StatusCode::OK,
+            "0123456789",
+        ),
+    ] {
+        let mut req = poem::Request::builder().method(Method::GET);
+        if let Some(range) = range {
+            req = req.header("Range", range);
+        }
+        if let Some(if_range) = if_range {
+            req = req.header("If-Range", if_range);
+        }
+        let mut resp = ep.call(req.finish()).await;
+        assert_eq!(resp.status(), status);
+        assert_eq!(resp.headers().get("ETag").unwrap(), "\"v1\"");
+        assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+    }
+}
+
+#[tokio::test]
+async fn api_builder() {
+    use poem::endpoint::make_sync;
+    use poem_openapi::{registry::MetaParamIn, ApiBuilder, OperationBuilder};
+
+    #[derive(Object)]
+    struct User {
+        id: i64,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/static", method = "get")]
+        async fn test(&self) {}
+    }
+
+    let builder = ApiBuilder::new()
+        .operation(
+            OperationBuilder::new(Method::GET, "/users/{id}")
+                .summary("get user")
+                .tag("user")
+                .param::<i64>("id", MetaParamIn::Path)
+                .response::<Json<User>>(),
+            make_sync(|req| format!("get {}", req.path_param("id").unwrap())),
+        )
+        .operation(
+            OperationBuilder::new(Method::DELETE, "/users/{id}")
+                .param::<i64>("id", MetaParamIn::Path)
+                .deprecated(),
+            make_sync(|req| format!("delete {}", req.path_param("id").unwrap())),
+        );
+
+    let meta = builder.instance_meta().remove(0);
+    assert_eq!(meta.paths.len(), 1);
+    assert_eq!(meta.paths[0].path, "/users/{id}");
+    assert_eq!(meta.paths[0].operations.len(), 2);
+    assert_eq!(
+        meta.paths[0].operations[0].summary.as_deref(),
+        Some("get user")
+    );
+    assert_eq!(meta.paths[0].operations[0].params[0].name, "id");
+    assert!(meta.paths[0].operations[1].deprecated);
+
+    let service = OpenApiService::new(Api.combine(builder));
+    let spec: serde_json::Value = serde_json::from_str(&service.spec()).unwrap();
+    assert!(spec["paths"]["/static"]["get"].is_object());
+    assert!(spec["paths"]["/users/{id}"]["delete"].is_object());
+    assert!(spec["components"]["schemas"]["User"].is_object());
+
+    let ep = service.into_endpoint();
+    for (method, body) in [(Method::GET, "get 1"), (Method::DELETE, "delete 1")] {
+        let mut resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(method)
+                    .uri(Uri::from_static("/users/1"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+    }
+}
+
+#[tokio::test]
+async fn api_builder_runtime_strings() {
+    use poem::endpoint::make_sync;
+    use poem_openapi::{registry::MetaParamIn, ApiBuilder, OperationBuilder};
+
+    let plugins = ["a", "b"];
+    let builder = plugins.iter().fold(ApiBuilder::new(), |builder, name| {
+        builder.operation(
+            OperationBuilder::new(Method::GET, format!("/plugins/{}", name))
+                .summary(format!("Runs the plugin {}", name))
+                .description(format!("The plugin `{}`.", name))
+                .tag(name.to_string())
+                .operation_id(format!("run_{}", name))
+                .param::<i64>(format!("{}_id", name), MetaParamIn::Query),
+            make_sync(|_| "ok"),
+        )
+    });
+
+    let service = OpenApiService::new(builder);
+    let spec: serde_json::Value = serde_json::from_str(&service.spec()).unwrap();
+    let operation = &spec["paths"]["/plugins/b"]["get"];
+    assert_eq!(operation["summary"], "Runs the plugin b");
+    assert_eq!(operation["description"], "The plugin `b`.");
+    assert_eq!(operation["tags"], serde_json::json!(["b"]));
+    assert_eq!(operation["operationId"], "run_b");
+    assert_eq!(operation["parameters"][0]["name"], "b_id");
+
+    let resp = service
+        .into_endpoint()
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/plugins/a"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn operation_hook() {
+    use poem::{Error, Request};
+    use poem_openapi::{auth::ApiKey, OperationContext, OperationHook, SecurityScheme};
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "api_key", key_name = "X-Tenant", in = "header")]
+    struct TenantAuth(ApiKey);
+
+    struct CheckTenant;
+
+    #[poem::async_trait]
+    impl OperationHook for CheckTenant {
+        async fn before_operation(
+            &self,
+            _req: &Request,
+            ctx: &OperationContext<'_>,
+        ) -> poem::Result<()> {
+            assert_eq!(ctx.method(), Method::POST);
+            assert_eq!(ctx.path(), "/tenants/{tenant}/items/{id}");
+            assert_eq!(ctx.param_names().collect::<Vec<_>>(), vec!["tenant", "id"]);
+            assert!(ctx.param::<String>("id").is_none());
+
+            let tenant = ctx.principal::<TenantAuth>().unwrap();
+            if ctx.param::<String>("tenant") != Some(&tenant.0.key) {
+                return Err(Error::new(StatusCode::FORBIDDEN));
+            }
+            if ctx.param::<i32>("id") == Some(&0) {
+                return Err(Error::new(StatusCode::NOT_FOUND));
+            }
+            Ok(())
+        }
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/tenants/:tenant/items/:id", method = "post")]
+        async fn test(
+            &self,
+            #[oai(auth)] _auth: TenantAuth,
+            #[oai(name = "tenant", in = "path")] tenant: String,
+            #[oai(name = "id", in = "path")] id: i32,
+            body: PlainText<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{}/{}: {}", tenant, id, body.0))
+        }
+    }
+
+    let ep = OpenApiService::new(Api).hook(CheckTenant).into_endpoint();
+    let call = |uri: &'static str| {
+        ep.call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static(uri))
+                .header("X-Tenant", "a")
+                .content_type("text/plain")
+                .body("hello"),
+        )
+    };
+
+    let mut resp = call("/tenants/a/items/1").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "a/1: hello");
+    assert_eq!(
+        call("/tenants/b/items/1").await.status(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        call("/tenants/a/items/0").await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn api_observer() {
+    use poem_openapi::{types::Password, ApiObserver, OperationEvent, OperationInfo};
+    use serde_json::{json, Value};
+    use tokio::sync::mpsc;
+
+    #[derive(Object)]
+    struct Item {
+        name: String,
+        #[oai(mask = "all")]
+        secret: String,
+    }
+
+    type Event = (
+        &'static str,
+        Vec<(&'static str, Value)>,
+        StatusCode,
+        Option<Value>,
+        Option<String>,
+    );
+
+    // the observers are called in spawned tasks, so the events are sent to a
+    // channel to wait for them
+    struct Observer(mpsc::UnboundedSender<Event>);
+
+    #[poem::async_trait]
+    impl ApiObserver for Observer {
+        fn capture_bodies(&self, info: &OperationInfo) -> bool {
+            info.name() == "create"
+        }
+
+        async fn on_operation(&self, event: &OperationEvent<'_>) {
+            let _ = self.0.send((
+                event.info().name(),
+                event.params().to_vec(),
+                event.status(),
+                event.request_body().cloned(),
+                event
+                    .response_body()
+                    .map(|body| String::from_utf8(body.to_vec()).unwrap()),
+            ));
+        }
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/items/:id", method = "get")]
+        async fn get(
+            &self,
+            #[oai(name = "id", in = "path")] id: i32,
+            #[oai(name = "token", in = "query")] _token: Password,
+        ) -> PlainText<String> {
+            PlainText(format!("item {}", id))
+        }
+
+        #[oai(path = "/items", method = "post")]
+        async fn create(
+            &self,
+            #[oai(name = "tags", in = "query")] tags: Vec<String>,
+            item: Json<Item>,
+        ) -> PlainText<String> {
+            PlainText(format!("{} {:?}", item.0.name, tags))
+        }
+    }
+
+    let (tx, mut events) = mpsc::unbounded_channel();
+    let ep = OpenApiService::new(Api)
+        .observer(Observer(tx))
+        .into_endpoint();
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/items/1?token=abc"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        events.recv().await.unwrap(),
+        (
+            "get",
+            vec![("id", json!(1)), ("token", json!("***"))],
+            StatusCode::OK,
+            None,
+            None
+        )
+    );
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/items/x"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        events.recv().await.unwrap(),
+        ("get", vec![], StatusCode::BAD_REQUEST, None, None)
+    );
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/items?tags=a&tags=b"))
+                .content_type("application/json")
+                .body(r#"{"name": "a", "secret": "abc"}"#),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        r#"a ["a", "b"]"#
+    );
+
+    // the response body is captured while it is sent
+    assert_eq!(
+        events.recv().await.unwrap(),
+        (
+            "create",
+            vec![("tags", json!(["a", "b"]))],
+            StatusCode::OK,
+            Some(json!({"name": "a", "secret": "***"})),
+            Some(r#"a ["a", "b"]"#.to_string())
+        )
+    );
+}
+
+#[tokio::test]
+async fn cache_control() {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use poem::{middleware::Cache, storage::MemoryStorage};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn with_cache(ep: impl Endpoint) -> impl Endpoint {
+        ep.with(Cache::new(
+            MemoryStorage::with_capacity(10),
+            Duration::from_secs(1),
+        ))
+    }
+
+    #[derive(ApiResponse)]
+    enum CountResponse {
+        #[oai(status = 200)]
+        Ok(PlainText<String>),
+        #[oai(status = 404)]
+        NotFound,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(
+            path = "/count/:found",
+            method = "get",
+            cache = "public, max-age=60",
+            transform = "with_cache"
+        )]
+        async fn count(&self, #[oai(name = "found", in = "path")] found: bool) -> CountResponse {
+            let n = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+            if found {
+                CountResponse::Ok(PlainText(n.to_string()))
+            } else {
+                CountResponse::NotFound
+            }
+        }
+    }
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let call =
+        |uri: &'static str| ep.call(poem::Request::builder().uri(Uri::from_static(uri)).finish());
+
+    let mut resp = call("/count/true").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("cache-control").unwrap(),
+        "public, max-age=60"
+    );
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "1");
+
+    let mut resp = call("/count/true").await;
+    assert!(resp.headers().contains_key("age"));
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "1");
+
+    let resp = call("/count/false").await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(!resp.headers().contains_key("cache-control"));
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn operation_cost() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/cheap", method = "get")]
+        async fn cheap(&self) -> PlainText<&'static str> {
+            PlainText("cheap")
+        }
+
+        #[oai(path = "/expensive", method = "get", cost = 10)]
+        async fn expensive(&self) -> PlainText<&'static str> {
+            PlainText("expensive")
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(meta.paths[0].operations[0].cost, None);
+    assert_eq!(meta.paths[1].operations[0].cost, Some(10));
+
+    let spec: serde_json::Value = serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    assert!(spec["paths"]["/cheap"]["get"]
+        .get("x-operation-cost")
+        .is_none());
+    assert_eq!(
+        spec["paths"]["/expensive"]["get"]["x-operation-cost"],
+        serde_json::json!(10)
+    );
+
+    let request = |uri: &'static str| poem::Request::builder().uri(Uri::from_static(uri)).finish();
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let resp = ep.call(request("/expensive")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(!resp.headers().contains_key("x-request-cost"));
+
+    let ep = OpenApiService::new(Api).cost_header().into_endpoint();
+    let resp = ep.call(request("/expensive")).await;
+    assert_eq!(resp.headers().get("x-request-cost").unwrap(), "10");
+    let resp = ep.call(request("/cheap")).await;
+    assert!(!resp.headers().contains_key("x-request-cost"));
+}
+
+#[tokio::test]
+async fn sunset() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/old", method = "get", deprecated, sunset = "2025-01-01")]
+        async fn old(&self) -> PlainText<&'static str> {
+            PlainText("old")
+        }
+
+        #[oai(path = "/new", method = "get")]
+        async fn current(&self) -> PlainText<&'static str> {
+            PlainText("new")
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let headers = &meta.paths[0].operations[0].responses.responses[0].headers;
+    assert_eq!(
+        headers.iter().map(|header| header.name).collect::<Vec<_>>(),
+        vec!["Deprecation", "Sunset"]
+    );
+    assert!(meta.paths[1].operations[0].responses.responses[0]
+        .headers
+        .is_empty());
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/old"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("deprecation").unwrap(), "true");
+    assert_eq!(
+        resp.headers().get("sunset").unwrap(),
+        "Wed, 01 Jan 2025 00:00:00 GMT"
+    );
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/new"))
+                .finish(),
+        )
+        .await;
+    assert!(!resp.headers().contains_key("deprecation"));
+    assert!(!resp.headers().contains_key("sunset"));
+}
+
+#[tokio::test]
+async fn response_context() {
+    use poem::web::cookie::Cookie;
+    use poem_openapi::ResponseContext;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post", cache = "max-age=60")]
+        async fn test(
+            &self,
+            ctx: ResponseContext,
+            #[oai(name = "created", in = "query")] created: bool,
+            body: PlainText<String>,
+        ) -> PlainText<String> {
+            if created {
+                ctx.set_status(StatusCode::CREATED);
+            }
+            ctx.append_header("x-value", "1");
+            ctx.append_header("x-value", "2");
+            ctx.set_cookie(Cookie::new_with_str("session", "abc"));
+            PlainText(body.0)
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(operation.params.len(), 1);
+    assert!(operation.request.is_some());
+    assert_eq!(operation.responses.responses[0].status, Some(200));
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/?created=true"))
+                .content_type("text/plain")
+                .body("hello"),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(
+        resp.headers().get_all("x-value").iter().collect::<Vec<_>>(),
+        vec!["1", "2"]
+    );
+    assert_eq!(resp.headers().get("set-cookie").unwrap(), "session=abc");
+    assert_eq!(resp.headers().get("cache-control").unwrap(), "max-age=60");
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "hello");
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/?created=false"))
+                .content_type("text/plain")
+                .body("hello"),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn response_warnings() {
+    use poem_openapi::ResponseWarnings;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            warnings: ResponseWarnings,
+            #[oai(name = "page", in = "query")] page: Option<i32>,
+        ) -> PlainText<String> {
+            if page.is_some() {
+                warnings.warning(299, r#"the "page" parameter is deprecated"#);
+                warnings.api_warn("use `cursor` instead");
+            }
+            warnings.warning(1000, "ignored");
+            PlainText("hello".to_string())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(operation.params.len(), 1);
+    assert!(operation.request.is_none());
+    let headers = &operation.responses.responses[0].headers;
+    assert_eq!(
+        headers
+            .iter()
+            .map(|header| (header.name, header.required))
+            .collect::<Vec<_>>(),
+        vec![("Warning", false), ("X-API-Warn", false)]
+    );
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/?page=1"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get_all("warning").iter().collect::<Vec<_>>(),
+        vec![r#"299 - "the \"page\" parameter is deprecated""#]
+    );
+    assert_eq!(
+        resp.headers().get("x-api-warn").unwrap(),
+        "use `cursor` instead"
+    );
+
+    let resp = ep
+        .call(poem::Request::builder().uri(Uri::from_static("/")).finish())
+        .await;
+    assert!(!resp.headers().contains_key("warning"));
+    assert!(!resp.headers().contains_key("x-api-warn"));
+}
+
+#[tokio::test]
+async fn serve_spec() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn test(&self) -> PlainText<&'static str> {
+            PlainText("hello")
+        }
+    }
+
+    let api_service = OpenApiService::new(Api).serve_spec();
+    let spec = api_service.spec();
+    let ep = api_service.into_endpoint();
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/openapi.json"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert!(resp.headers().get("x-api-version").is_none());
+    let etag = resp.headers().get("etag").cloned().unwrap();
+    assert_eq!(resp.take_body().into_string().await.unwrap(), spec);
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/openapi.json"))
+                .header("if-none-match", etag)
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/openapi.json"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[cfg(feature = "swagger-ui")]
+#[tokio::test]
+async fn swagger_ui_spec() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn test(&self) -> PlainText<&'static str> {
+            PlainText("hello")
+        }
+    }
+
+    let api_service = OpenApiService::new(Api);
+    let spec = api_service.spec();
+    let ep = api_service.swagger_ui().map_to_response();
+
+    let mut resp = ep
+        .call(poem::Request::builder().uri(Uri::from_static("/")).finish())
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = resp.take_body().into_string().await.unwrap();
+    assert!(html.contains(r#"url: baseUrl + "openapi.json""#));
+    assert!(!html.contains("/hello"));
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/openapi.json"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get("etag").is_some());
+    assert_eq!(resp.take_body().into_string().await.unwrap(), spec);
+
+    #[cfg(feature = "compression")]
+    {
+        let resp = ep
+            .call(
+                poem::Request::builder()
+                    .uri(Uri::from_static("/openapi.json"))
+                    .header("accept-encoding", "gzip")
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("content-encoding").unwrap(), "gzip");
+    }
+}
+
+#[tokio::test]
+async fn map_spec() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+
+        #[oai(path = "/internal", method = "get")]
+        async fn internal(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api)
+        .map_spec(|spec| {
+            spec["paths"].as_object_mut().unwrap().remove("/internal");
+        })
+        .map_spec(|spec| spec["x-internal-count"] = 1.into())
+        .serve_spec();
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert!(spec["paths"].get("/hello").is_some());
+    assert!(spec["paths"].get("/internal").is_none());
+    assert_eq!(spec["x-internal-count"], 1);
+
+    let ep = api_service.into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/openapi.json"))
+                .finish(),
+        )
+        .await;
+    let served: serde_json::Value =
+        serde_json::from_str(&resp.take_body().into_string().await.unwrap()).unwrap();
+    assert_eq!(served, spec);
+}
+
+#[tokio::test]
+async fn disable_operations() {
+    #[derive(Object)]
+    struct Report {
+        rows: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/export", method = "get")]
+        async fn legacy_export(&self) -> Json<Report> {
+            Json(Report { rows: 1 })
+        }
+
+        #[oai(path = "/export", method = "post")]
+        async fn export(&self) -> PlainText<&'static str> {
+            PlainText("export")
+        }
+
+        #[oai(path = "/import", method = "post", operation_id = "importData")]
+        async fn import(&self) -> PlainText<&'static str> {
+            PlainText("import")
+        }
+
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) -> PlainText<&'static str> {
+            PlainText("hello")
+        }
+    }
+
+    let api_service =
+        OpenApiService::new(Api).disable_operations(vec!["legacy_export", "importData"]);
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let operations = spec["paths"]["/export"].as_object().unwrap();
+    assert!(!operations.contains_key("get"));
+    assert!(operations.contains_key("post"));
+    assert!(spec["paths"].get("/import").is_none());
+    assert!(spec["paths"].get("/hello").is_some());
+    assert!(spec["components"]["schemas"].get("Report").is_none());
+
+    let ep = api_service.into_endpoint();
+    for (method, uri, status) in [
+        (Method::GET, "/export", StatusCode::METHOD_NOT_ALLOWED),
+        (Method::POST, "/export", StatusCode::OK),
+        (Method::POST, "/import", StatusCode::NOT_FOUND),
+        (Method::GET, "/hello", StatusCode::OK),
+    ] {
+        let resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(method)
+                    .uri(Uri::from_static(uri))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), status, "{}", uri);
+    }
+}
+
+#[test]
+fn spec_for_tags() {
+    #[derive(Tags)]
+    enum MyTags {
+        Public,
+        Internal,
+    }
+
+    #[derive(Object)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Object)]
+    struct User {
+        name: String,
+        address: Address,
+    }
+
+    #[derive(Object)]
+    struct Stats {
+        count: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get", tag = "MyTags::Public")]
+        async fn users(&self) -> Json<User> {
+            todo!()
+        }
+
+        #[oai(path = "/internal/stats", method = "get", tag = "MyTags::Internal")]
+        async fn stats(&self) -> Json<Stats> {
+            todo!()
+        }
+
+        #[oai(path = "/internal/stats", method = "delete", tag = "MyTags::Internal")]
+        async fn reset_stats(&self) {}
+
+        #[oai(path = "/internals", method = "get", tag = "MyTags::Internal")]
+        async fn internals(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api).map_spec(|spec| spec["x-test"] = 1.into());
+
+    let spec: serde_json::Value =
+        serde_json::from_str(&api_service.spec_for_tags(&[MyTags::Public])).unwrap();
+    assert!(spec["paths"].get("/users").is_some());
+    assert!(spec["paths"].get("/internal/stats").is_none());
+    assert_eq!(spec["tags"], serde_json::json!([{ "name": "public" }]));
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    assert!(schemas.contains_key("User"));
+    assert!(schemas.contains_key("Address"));
+    assert!(!schemas.contains_key("Stats"));
+    assert_eq!(spec["x-test"], 1);
+
+    let spec: serde_json::Value =
+        serde_json::from_str(&api_service.spec_for_path_prefix("/internal/")).unwrap();
+    assert!(spec["paths"].get("/users").is_none());
+    assert!(spec["paths"].get("/internals").is_none());
+    let operations = spec["paths"]["/internal/stats"].as_object().unwrap();
+    assert!(operations.contains_key("get"));
+    assert!(operations.contains_key("delete"));
+    assert_eq!(
+        spec["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        vec!["Stats"]
+    );
+}
+
+#[test]
+fn spec_is_openapi_3_0() {
+    fn check_schema(schema: &serde_json::Value) {
+        match schema {
+            serde_json::Value::Object(map) => {
+                if let Some(ty) = map.get("type") {
+                    assert!(ty.is_string(), "type must be a string: {}", ty);
+                    assert_ne!(ty, "null");
+                }
+                map.values().for_each(check_schema);
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(check_schema),
+            _ => {}
+        }
+    }
+
+    #[derive(Object)]
+    struct Obj {
+        a: Option<i32>,
+        b: Vec<Option<String>>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(
+            &self,
+            #[oai(name = "v", in = "query")] _v: Option<i32>,
+            obj: Json<Option<Obj>>,
+        ) -> Json<Option<Obj>> {
+            obj
+        }
+    }
+
+    let spec: serde_json::Value = serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert!(spec.get("webhooks").is_none());
+    check_schema(&spec);
+}
+
+#[test]
+fn webhooks() {
+    use poem_openapi::OperationBuilder;
+
+    #[derive(Object)]
+    struct Pet {
+        id: i64,
+    }
+
+    #[derive(Object)]
+    struct PetCreated {
+        pet: Pet,
+    }
+
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum WebhookResponse {
+        #[oai(status = 200)]
+        Ok,
+    }
+
+    #[derive(Tags)]
+    enum MyTags {
+        Events,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let spec: serde_json::Value = serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert!(spec.get("webhooks").is_none());
+
+    let api_service = OpenApiService::new(Api)
+        .openapi_version(OpenApiVersion::V3_1)
+        .webhook(
+            OperationBuilder::webhook(Method::POST, "petCreated")
+                .tag("events")
+                .request::<Json<PetCreated>>()
+                .response::<WebhookResponse>(),
+        );
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["openapi"], "3.1.0");
+    let operation = &spec["webhooks"]["petCreated"]["post"];
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/PetCreated"
+    );
+    assert!(operation["responses"].get("200").is_some());
+    assert!(spec["components"]["schemas"].get("Pet").is_some());
+
+    let spec: serde_json::Value =
+        serde_json::from_str(&api_service.spec_for_tags(&[MyTags::Events])).unwrap();
+    assert!(spec["paths"].get("/hello").is_none());
+    assert!(spec["webhooks"].get("petCreated").is_some());
+    assert!(spec["components"]["schemas"].get("Pet").is_some());
+}
+
+#[test]
+fn openapi_3_1_schemas() {
+    #[derive(Object)]
+    struct Obj {
+        #[oai(maximum(value = "10", exclusive))]
+        a: i32,
+        #[oai(minimum(value = "1"), maximum(value = "5"))]
+        default: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(
+            &self,
+            #[oai(name = "v", in = "query", minimum(value = "0", exclusive))] _v: i32,
+            obj: Json<Obj>,
+        ) -> Json<Obj> {
+            obj
+        }
+    }
+
+    let spec: serde_json::Value = serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    let properties = &spec["components"]["schemas"]["Obj"]["properties"];
+    assert_eq!(properties["a"]["maximum"], 10.0);
+    assert_eq!(properties["a"]["exclusiveMaximum"], true);
+
+    let spec: serde_json::Value = serde_json::from_str(
+        &OpenApiService::new(Api)
+            .openapi_version(OpenApiVersion::V3_1)
+            .spec(),
+    )
+    .unwrap();
+    assert_eq!(spec["openapi"], "3.1.0");
+    let properties = &spec["components"]["schemas"]["Obj"]["properties"];
+    assert!(properties["a"].get("maximum").is_none());
+    assert_eq!(properties["a"]["exclusiveMaximum"], 10.0);
+    assert_eq!(properties["default"]["minimum"], 1.0);
+    assert_eq!(properties["default"]["maximum"], 5.0);
+    assert!(properties["default"].get("exclusiveMinimum").is_none());
+    let param = &spec["paths"]["/"]["post"]["parameters"][0]["schema"];
+    assert!(param.get("minimum").is_none());
+    assert_eq!(param["exclusiveMinimum"], 0.0);
+}
+
+#[test]
+#[should_panic(expected = "the webhooks require OpenAPI 3.1")]
+fn webhooks_require_openapi_3_1() {
+    use poem_openapi::OperationBuilder;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {}
+
+    OpenApiService::new(Api)
+        .webhook(OperationBuilder::webhook(Method::POST, "petCreated"))
+        .spec();
+}
+
+#[tokio::test]
+async fn log_sample() {
+    use tokio::sync::mpsc;
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    // collects the fields of the sampled logs
+    struct Collector(mpsc::UnboundedSender<Vec<(String, String)>>);
+
+    struct Fields(Vec<(String, String)>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "poem_openapi::log_sample"
+        }
+
+        fn new_span(&self, _span: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(Vec::new());
+            event.record(&mut fields);
+            let _ = self.0.send(fields.0);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/echo", method = "post", log_sample = 0.5)]
+        async fn echo(&self, body: PlainText<String>) -> PlainText<String> {
+            body
+        }
+    }
+
+    // the test runtime has a single thread, so the spawned tasks that write the
+    // logs use the default subscriber of this thread
+    let (tx, mut logs) = mpsc::unbounded_channel();
+    let _guard = tracing::subscriber::set_default(Collector(tx));
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    for i in 0..4 {
+        let mut resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(Method::POST)
+                    .uri(Uri::from_static("/echo"))
+                    .content_type("text/plain")
+                    .body(format!("hello {}", i)),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.take_body().into_string().await.unwrap(),
+            format!("hello {}", i)
+        );
+
+        // one of every two requests is logged
+        if i % 2 == 0 {
+            assert!(logs.try_recv().is_err());
+            continue;
+        }
+        let fields = logs.recv().await.unwrap();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(field("method"), "POST");
+        assert_eq!(field("uri"), "/echo");
+        assert_eq!(field("status"), "200 OK");
+        assert_eq!(field("request_body"), format!("hello {}", i));
+        assert_eq!(field("response_body"), format!("hello {}", i));
+    }
+    assert!(logs.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn operation_key() {
+    use std::time::Duration;
+
+    use poem::{
+        middleware::{HeaderKey, RateLimit},
+        storage::MemoryStorage,
+    };
+    use poem_openapi::OperationKey;
+
+    static STORAGE: once_cell::sync::Lazy<MemoryStorage> =
+        once_cell::sync::Lazy::new(MemoryStorage::new);
+
+    fn rate_limit(ep: impl Endpoint) -> impl Endpoint {
+        ep.with(
+            RateLimit::new(STORAGE.clone(), 1, Duration::from_secs(3600))
+                .request_key((OperationKey, HeaderKey::new("x-client"))),
+        )
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/a", method = "get", transform = "rate_limit")]
+        async fn a(&self) {}
+
+        #[oai(
+            path = "/b",
+            method = "get",
+            operation_id = "getB",
+            transform = "rate_limit"
+        )]
+        async fn b(&self) {}
+    }
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let call = |uri: &'static str, client: &'static str| {
+        ep.call(
+            poem::Request::builder()
+                .uri(Uri::from_static(uri))
+                .header("x-client", client)
+                .finish(),
+        )
+    };
+
+    assert_eq!(call("/a", "1").await.status(), StatusCode::OK);
+    assert_eq!(call("/b", "1").await.status(), StatusCode::OK);
+    assert_eq!(call("/a", "2").await.status(), StatusCode::OK);
+    assert_eq!(
+        call("/a", "1").await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        call("/b", "1").await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[test]
+fn operation_id_naming() {
+    use poem_openapi::OperationIdNaming;
+
+    #[derive(Tags)]
+    enum ApiTags {
+        Users,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get", tag = "ApiTags::Users")]
+        async fn list_users(&self) {}
+
+        #[oai(path = "/users", method = "post", operation_id = "addUser")]
+        async fn create_user(&self) {}
+
+        #[oai(path = "/health", method = "get")]
+        async fn health_check(&self) {}
+    }
+
+    let operation_ids = |naming| {
+        let spec: serde_json::Value =
+            serde_json::from_str(&OpenApiService::new(Api).operation_id_naming(naming).spec())
+                .unwrap();
+        [
+            spec["paths"]["/users"]["get"]["operationId"].clone(),
+            spec["paths"]["/users"]["post"]["operationId"].clone(),
+            spec["paths"]["/health"]["get"]["operationId"].clone(),
+        ]
+    };
+
+    assert_eq!(
+        operation_ids(OperationIdNaming::CamelCase),
+        ["listUsers", "addUser", "healthCheck"]
+    );
+    assert_eq!(
+        operation_ids(OperationIdNaming::Template("{tag}_{method}".to_string())),
+        [
+            serde_json::json!("users_list_users"),
+            "addUser".into(),
+            serde_json::Value::Null
+        ]
+    );
+    assert_eq!(
+        operation_ids(OperationIdNaming::custom(|ctx| {
+            Some(format!("{}:{}", ctx.method, ctx.path))
+        })),
+        ["GET:/users", "addUser", "GET:/health"]
+    );
+}
+
+#[tokio::test]
+async fn operation_id_naming_resolved() {
+    use std::sync::Arc;
+
+    use poem_openapi::{OperationIdNaming, OperationInfo};
+
+    #[derive(ApiResponse)]
+    enum CreateUserResponse {
+        #[oai(status = 201, link(name = "GetUser", operation_id = "get_user"))]
+        Created,
+    }
+
+    struct UsersApi;
+
+    #[OpenApi]
+    impl UsersApi {
+        #[oai(path = "/users", method = "get")]
+        async fn list(&self, info: Data<&Arc<OperationInfo>>) -> PlainText<String> {
+            PlainText(info.operation_id().unwrap_or_default().to_string())
+        }
+
+        #[oai(path = "/users", method = "post")]
+        async fn create_user(&self) -> CreateUserResponse {
+            CreateUserResponse::Created
+        }
+
+        #[oai(path = "/users/:id", method = "get")]
+        async fn get_user(&self, #[oai(name = "id", in = "path")] _id: i32) {}
+    }
+
+    struct OrdersApi;
+
+    #[OpenApi]
+    impl OrdersApi {
+        #[oai(path = "/orders", method = "get")]
+        async fn list(&self, info: Data<&Arc<OperationInfo>>) -> PlainText<String> {
+            PlainText(info.operation_id().unwrap_or_default().to_string())
+        }
+    }
+
+    let service = || {
+        OpenApiService::new(UsersApi.combine(OrdersApi))
+            .operation_id_naming(OperationIdNaming::CamelCase)
+    };
+    let spec: serde_json::Value = serde_json::from_str(&service().spec()).unwrap();
+    assert_eq!(spec["paths"]["/users"]["get"]["operationId"], "list");
+    assert_eq!(spec["paths"]["/orders"]["get"]["operationId"], "list2");
+    assert_eq!(
+        spec["paths"]["/users"]["post"]["responses"]["201"]["links"]["GetUser"]["operationId"],
+        "getUser"
+    );
+
+    let ep = service().into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/orders"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "list2");
+
+    let service = service().disable_operations(["list2"]);
+    let spec: serde_json::Value = serde_json::from_str(&service.spec()).unwrap();
+    assert!(spec["paths"]["/users"]["get"].is_object());
+    assert!(spec["paths"].get("/orders").is_none());
+
+    let ep = service.into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/orders"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
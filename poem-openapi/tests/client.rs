@@ -0,0 +1,148 @@
+#![cfg(feature = "client")]
+
+use poem::{listener::TcpListener, Route, Server};
+use poem_openapi::{
+    client::{Client, ClientError},
+    payload::{Json, PlainText},
+    ApiResponse, Object, OpenApi, OpenApiService,
+};
+
+#[derive(Object, Debug, Eq, PartialEq)]
+struct User {
+    id: i64,
+    name: String,
+}
+
+#[derive(ApiResponse, Debug, Eq, PartialEq)]
+#[oai(client)]
+enum GetUserResponse {
+    #[oai(status = 200)]
+    Ok(Json<User>, #[oai(header = "X-Version")] i32),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+struct Api;
+
+#[OpenApi(prefix_path = "/users", client = "ApiClient")]
+impl Api {
+    /// Get the user.
+    #[oai(path = "/:id", method = "get")]
+    async fn get(&self, #[oai(name = "id", in = "path")] id: i64) -> GetUserResponse {
+        match id {
+            1 => GetUserResponse::Ok(
+                Json(User {
+                    id,
+                    name: "sunli".to_string(),
+                }),
+                3,
+            ),
+            _ => GetUserResponse::NotFound,
+        }
+    }
+
+    #[oai(path = "/", method = "post")]
+    async fn create(
+        &self,
+        #[oai(name = "X-Token", in = "header")] token: String,
+        user: Json<User>,
+    ) -> PlainText<String> {
+        PlainText(format!("{}:{}", token, user.0.name))
+    }
+
+    #[oai(path = "/search/:group/*path", method = "get")]
+    async fn search(
+        &self,
+        #[oai(name = "group", in = "path")] group: String,
+        #[oai(name = "path", in = "path")] path: String,
+        #[oai(name = "id", in = "query")] ids: Vec<i64>,
+        #[oai(name = "lang", in = "cookie")] lang: Option<String>,
+    ) -> Json<String> {
+        Json(format!("{} {} {:?} {:?}", group, path, ids, lang))
+    }
+
+    #[oai(path = "/delete/:id", method = "delete")]
+    async fn delete(&self, #[oai(name = "id", in = "path")] id: i64) -> poem::Result<()> {
+        match id {
+            1 => Ok(()),
+            _ => Err(poem::Error::new(poem::http::StatusCode::FORBIDDEN)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn client() {
+    let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+    let addr = *server.local_addr()[0].as_socket_addr().unwrap();
+    tokio::spawn(server.run(Route::new().nest("/api", OpenApiService::new(Api))));
+
+    let client = ApiClient::new(Client::new(format!("http://{}/api/", addr)));
+
+    assert_eq!(
+        client.get(1).await.unwrap(),
+        GetUserResponse::Ok(
+            Json(User {
+                id: 1,
+                name: "sunli".to_string()
+            }),
+            3
+        )
+    );
+    assert_eq!(client.get(2).await.unwrap(), GetUserResponse::NotFound);
+
+    assert_eq!(
+        client
+            .create(
+                "abc".to_string(),
+                Json(User {
+                    id: 2,
+                    name: "sunli".to_string()
+                })
+            )
+            .await
+            .unwrap()
+            .0,
+        "abc:sunli"
+    );
+
+    assert_eq!(
+        client
+            .search(
+                "a-b".to_string(),
+                "x/y.z".to_string(),
+                vec![1, 2],
+                Some("zh".to_string())
+            )
+            .await
+            .unwrap()
+            .0,
+        r#"a-b x/y.z [1, 2] Some("zh")"#
+    );
+    assert_eq!(
+        client
+            .search("a".to_string(), "b".to_string(), vec![3], None)
+            .await
+            .unwrap()
+            .0,
+        "a b [3] None"
+    );
+
+    assert!(client.delete(1).await.unwrap().is_ok());
+    assert_eq!(
+        client.delete(2).await.unwrap().unwrap_err().status(),
+        poem::http::StatusCode::FORBIDDEN
+    );
+
+    let client = ApiClient::new(Client::new(format!("http://{}/other", addr)));
+    let err = client
+        .create(
+            "abc".to_string(),
+            Json(User {
+                id: 2,
+                name: "sunli".to_string(),
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::UnexpectedStatus { status, .. } if status == 404));
+}
@@ -0,0 +1,236 @@
+use poem::{
+    http::{StatusCode, Uri},
+    Endpoint, IntoEndpoint,
+};
+use poem_openapi::{
+    auth::ApiKey,
+    payload::{Json, PlainText},
+    Object, OpenApi, OpenApiGateway, OpenApiService, SecurityScheme,
+};
+
+mod users {
+    use super::*;
+
+    #[derive(Object)]
+    pub struct Item {
+        pub name: String,
+    }
+
+    pub struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn list(&self) -> Json<Vec<Item>> {
+            Json(vec![Item {
+                name: "sunli".to_string(),
+            }])
+        }
+    }
+}
+
+mod orders {
+    use super::*;
+
+    #[derive(Object)]
+    pub struct Item {
+        pub id: i32,
+    }
+
+    pub struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/:id", method = "get")]
+        async fn get(&self, #[oai(name = "id", in = "path")] id: i32) -> Json<Item> {
+            Json(Item { id })
+        }
+    }
+}
+
+fn gateway() -> OpenApiGateway {
+    OpenApiGateway::new()
+        .title("Shop")
+        .service("users", "/users", OpenApiService::new(users::Api))
+        .service("orders", "/orders/", OpenApiService::new(orders::Api))
+}
+
+#[test]
+fn merged_spec() {
+    let spec: serde_json::Value = serde_json::from_str(&gateway().spec()).unwrap();
+
+    assert_eq!(spec["info"]["title"], "Shop");
+    assert!(spec["paths"]["/users"]["get"].is_object());
+    assert!(spec["paths"]["/orders/{id}"]["get"].is_object());
+
+    let schemas = &spec["components"]["schemas"];
+    assert!(schemas["users.Item"]["properties"]["name"].is_object());
+    assert!(schemas["orders.Item"]["properties"]["id"].is_object());
+    assert!(schemas.get("Item").is_none());
+
+    assert_eq!(
+        spec["paths"]["/users"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]
+            ["items"]["$ref"],
+        "#/components/schemas/users.Item"
+    );
+    assert_eq!(
+        spec["paths"]["/orders/{id}"]["get"]["responses"]["200"]["content"]["application/json"]
+            ["schema"]["$ref"],
+        "#/components/schemas/orders.Item"
+    );
+}
+
+#[tokio::test]
+async fn routes() {
+    let ep = gateway().into_endpoint();
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        r#"[{"name":"sunli"}]"#
+    );
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/orders/1"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), r#"{"id":1}"#);
+}
+
+#[test]
+#[should_panic]
+fn duplicate_service_name() {
+    let _ = OpenApiGateway::new()
+        .service("users", "/a", OpenApiService::new(users::Api))
+        .service("users", "/b", OpenApiService::new(users::Api));
+}
+
+#[cfg(feature = "remote")]
+#[tokio::test]
+async fn remote_service() {
+    use poem::{listener::TcpListener, Route, Server};
+    use poem_openapi::RemoteService;
+
+    let service = OpenApiService::new(orders::Api);
+    let spec = service.spec_endpoint();
+    let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+    let addr = *server.local_addr()[0].as_socket_addr().unwrap();
+    tokio::spawn(server.run(Route::new().at("/api/spec", spec).nest("/api", service)));
+
+    let cache_path = std::env::temp_dir().join(format!("poem-openapi-remote-{}.json", addr.port()));
+    let gateway = OpenApiGateway::new()
+        .service("users", "/users", OpenApiService::new(users::Api))
+        .remote_service(
+            "orders",
+            "/orders",
+            RemoteService::new(format!("http://{}/api", addr))
+                .spec_path("/spec")
+                .cache_path(&cache_path),
+        )
+        .await
+        .unwrap();
+
+    let spec: serde_json::Value = serde_json::from_str(&gateway.spec()).unwrap();
+    assert!(spec["paths"]["/orders/{id}"]["get"].is_object());
+    assert!(spec["components"]["schemas"]["orders.Item"].is_object());
+
+    let ep = gateway.into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/orders/1"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), r#"{"id":1}"#);
+
+    // the cached specification is used if the upstream is unavailable
+    let gateway = OpenApiGateway::new()
+        .remote_service(
+            "orders",
+            "/orders",
+            RemoteService::new("http://127.0.0.1:1").cache_path(&cache_path),
+        )
+        .await
+        .unwrap();
+    let spec: serde_json::Value = serde_json::from_str(&gateway.spec()).unwrap();
+    assert!(spec["paths"]["/orders/{id}"]["get"].is_object());
+
+    std::fs::remove_file(cache_path).unwrap();
+}
+
+mod admin {
+    use super::*;
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "api_key", key_name = "X-API-Key", in = "header")]
+    pub struct Auth(ApiKey);
+
+    pub struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "delete")]
+        async fn delete_user(
+            &self,
+            #[oai(auth)] auth: Auth,
+            #[oai(name = "id", in = "path")] _id: i32,
+        ) -> PlainText<String> {
+            PlainText(auth.0.key)
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "conflicts with the service `orders`")]
+fn conflicting_paths() {
+    mod other {
+        use super::*;
+
+        pub struct Api;
+
+        #[OpenApi]
+        impl Api {
+            #[oai(path = "/orders/:order_id", method = "get")]
+            async fn get(&self, #[oai(name = "order_id", in = "path")] _id: i32) {}
+        }
+    }
+
+    let _ = OpenApiGateway::new()
+        .service("orders", "/orders", OpenApiService::new(orders::Api))
+        .service("other", "/", OpenApiService::new(other::Api));
+}
+
+#[test]
+fn namespaced_security_schemes() {
+    let gateway = OpenApiGateway::new()
+        .service("admin", "/admin", OpenApiService::new(admin::Api))
+        .service("backoffice", "/backoffice", OpenApiService::new(admin::Api));
+    let spec: serde_json::Value = serde_json::from_str(&gateway.spec()).unwrap();
+
+    let schemes = &spec["components"]["securitySchemes"];
+    assert_eq!(schemes["admin.auth"]["name"], "X-API-Key");
+    assert_eq!(schemes["backoffice.auth"]["name"], "X-API-Key");
+    assert!(schemes.get("auth").is_none());
+
+    assert_eq!(
+        spec["paths"]["/admin/users/{id}"]["delete"]["security"],
+        serde_json::json!([{ "admin.auth": [] }])
+    );
+    assert_eq!(
+        spec["paths"]["/backoffice/users/{id}"]["delete"]["security"],
+        serde_json::json!([{ "backoffice.auth": [] }])
+    );
+}
@@ -1,12 +1,13 @@
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 
-use poem::{Request, RequestBody};
+use bytes::Bytes;
+use poem::{Body, Request, RequestBody};
 use poem_openapi::{
     payload::{ParsePayload, Payload},
-    registry::{MetaSchema, MetaSchemaRef},
+    registry::{MetaAdditionalProperties, MetaSchema, MetaSchemaRef},
     types::{
-        multipart::{JsonField, Upload},
-        Binary,
+        multipart::{JsonField, Upload, UploadConfig},
+        Base64, Binary, ToJSON, Type,
     },
     Enum, Multipart, Object, ParseRequestError,
 };
@@ -234,9 +235,193 @@ async fn upload() {
 
     assert_eq!(a.file.file_name(), Some("1.txt"));
     assert_eq!(a.file.content_type(), None);
+    assert_eq!(a.file.size(), 3);
     assert_eq!(a.file.into_vec().await.unwrap(), vec![1, 2, 3]);
 }
 
+#[tokio::test]
+async fn upload_spool_to_file() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        file: Upload,
+    }
+
+    let content = vec![7u8; 100];
+    let data = create_multipart_payload(&[("file", Some("1.txt"), &content)]);
+    let mut request = Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .finish();
+    request.set_data(UploadConfig::new().spool_threshold(10));
+    let a = A::from_request(&request, &mut RequestBody::new(data.into()))
+        .await
+        .unwrap();
+    assert_eq!(a.file.size(), 100);
+    assert_eq!(a.file.into_vec().await.unwrap(), content);
+}
+
+#[tokio::test]
+async fn upload_too_large() {
+    #[derive(Multipart, Debug)]
+    #[allow(dead_code)]
+    struct A {
+        file: Upload,
+    }
+
+    let data = create_multipart_payload(&[("file", Some("1.txt"), &[1, 2, 3, 4, 5])]);
+    let mut request = Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .finish();
+    request.set_data(UploadConfig::new().max_file_size(4));
+    let err = A::from_request(&request, &mut RequestBody::new(data.into()))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ParseRequestError::PayloadTooLarge {
+            reason: r#"failed to parse field `file`: failed to parse "string(binary)": payload too large"#
+                .to_string()
+        }
+    );
+    assert_eq!(
+        poem::Error::from(err).status(),
+        poem::http::StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
+
+#[tokio::test]
+async fn field_too_large() {
+    #[derive(Multipart, Debug)]
+    #[allow(dead_code)]
+    struct A {
+        name: String,
+        file: Upload,
+    }
+
+    let mut request = Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .finish();
+    request.set_data(UploadConfig::new().max_field_size(3));
+
+    // the limit does not apply to the files
+    let data =
+        create_multipart_payload(&[("name", None, b"abc"), ("file", Some("1.txt"), b"abcd")]);
+    let a = A::from_request(&request, &mut RequestBody::new(data.into()))
+        .await
+        .unwrap();
+    assert_eq!(a.name, "abc");
+    assert_eq!(a.file.size(), 4);
+
+    let data = create_multipart_payload(&[("name", None, b"abcd"), ("file", Some("1.txt"), b"a")]);
+    let err = A::from_request(&request, &mut RequestBody::new(data.into()))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ParseRequestError::PayloadTooLarge {
+            reason: r#"failed to parse field `name`: failed to parse "string": field size exceeds the limit of 3 bytes"#
+                .to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn upload_config_of_service() {
+    use poem::{http::StatusCode, Endpoint, IntoEndpoint};
+    use poem_openapi::{OpenApi, OpenApiService};
+
+    #[derive(Multipart)]
+    #[allow(dead_code)]
+    struct A {
+        file: Upload,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/upload", method = "post")]
+        async fn upload(&self, _a: A) {}
+
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let spec = |api_service: OpenApiService<Api>| -> serde_json::Value {
+        serde_json::from_str(&api_service.spec()).unwrap()
+    };
+    let api_service = OpenApiService::new(Api);
+    assert!(spec(api_service)["paths"]["/upload"]["post"]["responses"]["413"].is_null());
+
+    let api_service =
+        OpenApiService::new(Api).upload_config(UploadConfig::new().spool_threshold(1024));
+    assert!(spec(api_service)["paths"]["/upload"]["post"]["responses"]["413"].is_null());
+
+    let api_service =
+        || OpenApiService::new(Api).upload_config(UploadConfig::new().max_file_size(4));
+    let doc = spec(api_service());
+    assert_eq!(
+        doc["paths"]["/upload"]["post"]["responses"]["413"]["description"],
+        "The request body exceeds the size limit."
+    );
+    assert!(doc["paths"]["/hello"]["get"]["responses"]["413"].is_null());
+
+    let ep = api_service().into_endpoint();
+    let data = create_multipart_payload(&[("file", Some("1.txt"), &[1, 2, 3, 4, 5])]);
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(poem::http::Method::POST)
+                .uri(poem::http::Uri::from_static("/upload"))
+                .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+                .body(data),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn multiple_uploads() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        files: Vec<Upload>,
+    }
+
+    let schema_ref = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    let files_schema = schema.properties[0].1.unwrap_inline();
+    assert_eq!(files_schema.ty, "array");
+    let item_schema = files_schema.items.as_ref().unwrap().unwrap_inline();
+    assert_eq!(item_schema.ty, "string");
+    assert_eq!(item_schema.format, Some("binary"));
+
+    let data = create_multipart_payload(&[
+        ("files", Some("1.txt"), &[1, 2, 3]),
+        ("files", Some("2.txt"), &[4, 5, 6]),
+    ]);
+    let mut request = Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .finish();
+    request.set_data(UploadConfig::new().max_file_size(3).max_total_size(6));
+    let a = A::from_request(&request, &mut RequestBody::new(data.clone().into()))
+        .await
+        .unwrap();
+    assert_eq!(a.files.len(), 2);
+    assert_eq!(a.files[0].file_name(), Some("1.txt"));
+    assert_eq!(a.files[1].file_name(), Some("2.txt"));
+
+    // the total size is counted per request
+    let a = A::from_request(&request, &mut RequestBody::new(data.clone().into()))
+        .await
+        .unwrap();
+    assert_eq!(a.files.len(), 2);
+
+    request.set_data(UploadConfig::new().max_file_size(3).max_total_size(5));
+    let err = A::from_request(&request, &mut RequestBody::new(data.into()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ParseRequestError::PayloadTooLarge { .. }));
+}
+
 #[tokio::test]
 async fn validator() {
     #[derive(Multipart, Debug, Eq, PartialEq)]
@@ -498,3 +683,205 @@ fn inline_field() {
         }))
     );
 }
+
+#[tokio::test]
+async fn deny_unknown_fields() {
+    #[derive(Multipart, Debug, Eq, PartialEq)]
+    #[oai(deny_unknown_fields)]
+    struct A {
+        name: String,
+    }
+
+    let data = create_multipart_payload(&[("name", None, b"abc"), ("value", None, b"10")]);
+    let err = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParseRequestError::ParseRequestBody {
+            reason: "unknown field `value`".to_string()
+        }
+    );
+
+    let schema_ref = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(
+        schema.additional_properties,
+        Some(Box::new(MetaAdditionalProperties::Allowed(false)))
+    );
+    assert_eq!(
+        serde_json::to_value(schema).unwrap()["additionalProperties"],
+        serde_json::json!(false)
+    );
+}
+
+#[tokio::test]
+async fn ordered_fields() {
+    #[derive(Multipart, Debug, Eq, PartialEq)]
+    #[oai(ordered)]
+    struct A {
+        name: String,
+        values: Vec<i32>,
+    }
+
+    let data = create_multipart_payload(&[
+        ("name", None, b"abc"),
+        ("values", None, b"1"),
+        ("values", None, b"2"),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        a,
+        A {
+            name: "abc".to_string(),
+            values: vec![1, 2],
+        }
+    );
+
+    let data = create_multipart_payload(&[("values", None, b"1"), ("name", None, b"abc")]);
+    let err = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParseRequestError::ParseRequestBody {
+            reason: "field `name` is out of order".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn extra_fields() {
+    #[derive(Multipart, Debug, Eq, PartialEq)]
+    struct A {
+        name: String,
+        #[oai(extra)]
+        extra: HashMap<String, Vec<String>>,
+    }
+
+    let data = create_multipart_payload(&[
+        ("a", None, b"1"),
+        ("name", None, b"abc"),
+        ("b", None, b"2"),
+        ("a", None, b"3"),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(a.name, "abc");
+    assert_eq!(a.extra.len(), 2);
+    assert_eq!(a.extra["a"], vec!["1".to_string(), "3".to_string()]);
+    assert_eq!(a.extra["b"], vec!["2".to_string()]);
+
+    let schema_ref = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(schema.properties.len(), 1);
+    assert_eq!(
+        schema.additional_properties,
+        Some(Box::new(MetaAdditionalProperties::Schema(
+            <Vec<String>>::schema_ref()
+        )))
+    );
+    assert_eq!(
+        serde_json::to_value(schema).unwrap()["additionalProperties"],
+        serde_json::json!({"type": "array", "items": {"type": "string"}})
+    );
+}
+
+#[tokio::test]
+async fn binary_fields() {
+    #[derive(Multipart, Debug, Eq, PartialEq)]
+    struct A {
+        a: Bytes,
+        b: Binary<Bytes>,
+        c: Base64,
+        d: Base64<Bytes>,
+    }
+
+    let data = create_multipart_payload(&[
+        ("a", None, &[1, 2, 3]),
+        ("b", Some("b.bin"), &[4, 5]),
+        ("c", None, b"AQID"),
+        ("d", None, b"BAU="),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        a,
+        A {
+            a: Bytes::from_static(&[1, 2, 3]),
+            b: Binary(Bytes::from_static(&[4, 5])),
+            c: Base64(vec![1, 2, 3]),
+            d: Base64(Bytes::from_static(&[4, 5])),
+        }
+    );
+    assert_eq!(a.d.to_json(), "BAU=");
+
+    assert_eq!(
+        <Bytes>::schema_ref(),
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "binary")))
+    );
+    assert_eq!(
+        <Base64<Bytes>>::schema_ref(),
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "byte")))
+    );
+}
+
+#[tokio::test]
+async fn client_disconnected() {
+    #[derive(Multipart, Debug, Eq, PartialEq)]
+    struct A {
+        name: String,
+        file: Binary,
+    }
+
+    let stream = futures_util::stream::iter(vec![
+        Ok(Bytes::from_static(
+            b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nabc\r\n\
+              --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"\r\n\r\n123",
+        )),
+        Err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "connection reset",
+        )),
+    ]);
+    let err = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(Body::from_async_read(tokio_util::io::StreamReader::new(
+            stream,
+        ))),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ParseRequestError::ClientDisconnected { .. }));
+}
@@ -425,6 +425,241 @@ fn write_only() {
     );
 }
 
+#[test]
+fn lenient() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        #[oai(lenient)]
+        a: i32,
+        #[oai(lenient)]
+        b: Option<f64>,
+        #[oai(lenient)]
+        c: Vec<u8>,
+        #[oai(lenient)]
+        d: String,
+        e: i32,
+    }
+
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "a": "42",
+            "b": "1.5",
+            "c": [1.0, "2"],
+            "d": "3",
+            "e": 4,
+        }))
+        .unwrap(),
+        Obj {
+            a: 42,
+            b: Some(1.5),
+            c: vec![1, 2],
+            d: "3".to_string(),
+            e: 4,
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "a": 42.0, "c": [], "d": "", "e": 4 }))
+            .unwrap()
+            .a,
+        42
+    );
+    assert!(Obj::parse_from_json(json!({ "a": 42.5, "c": [], "d": "", "e": 4 })).is_err());
+    assert!(Obj::parse_from_json(json!({ "a": "abc", "c": [], "d": "", "e": 4 })).is_err());
+    assert!(Obj::parse_from_json(json!({ "a": 1, "c": [], "d": "", "e": "4" })).is_err());
+
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(lenient_all)]
+    struct Obj2 {
+        a: i32,
+        b: u64,
+    }
+
+    assert_eq!(
+        Obj2::parse_from_json(json!({ "a": "-1", "b": 2.0 })).unwrap(),
+        Obj2 { a: -1, b: 2 }
+    );
+}
+
+#[test]
+fn integers_128bit() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        a: u128,
+        b: i128,
+    }
+
+    let meta = get_meta::<Obj>();
+    let schema = meta.properties[0].1.unwrap_inline();
+    assert_eq!(schema.ty, "string");
+    assert_eq!(schema.format, Some("uint128"));
+    assert!(schema.description.unwrap().contains(&u128::MAX.to_string()));
+    assert_eq!(meta.properties[1].1.unwrap_inline().format, Some("int128"));
+
+    let obj = Obj {
+        a: u128::MAX,
+        b: i128::MIN,
+    };
+    assert_eq!(
+        obj.to_json(),
+        json!({ "a": u128::MAX.to_string(), "b": i128::MIN.to_string() })
+    );
+    assert_eq!(Obj::parse_from_json(obj.to_json()).unwrap(), obj);
+    assert_eq!(
+        Obj::parse_from_json(json!({ "a": 1, "b": -1 })).unwrap(),
+        Obj { a: 1, b: -1 }
+    );
+    assert!(Obj::parse_from_json(json!({ "a": "-1", "b": "1" })).is_err());
+    assert!(Obj::parse_from_json(json!({ "a": 1.5, "b": 1 })).is_err());
+}
+
+#[test]
+fn integers_non_zero() {
+    use std::num::{NonZeroI32, NonZeroU128, NonZeroU64};
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        a: NonZeroU64,
+        b: NonZeroI32,
+        c: NonZeroU128,
+    }
+
+    let meta = get_meta::<Obj>();
+    let schema = meta.properties[0].1.unwrap_inline();
+    assert_eq!(schema.ty, "integer");
+    assert_eq!(schema.format, Some("uint64"));
+    assert_eq!(schema.minimum, Some(1.0));
+    let schema = meta.properties[1].1.unwrap_inline();
+    assert_eq!(schema.format, Some("int32"));
+    assert_eq!(schema.minimum, None);
+    let schema = meta.properties[2].1.unwrap_inline();
+    assert_eq!(schema.ty, "string");
+    assert_eq!(schema.format, Some("uint128"));
+
+    let obj = Obj {
+        a: NonZeroU64::new(1).unwrap(),
+        b: NonZeroI32::new(-1).unwrap(),
+        c: NonZeroU128::new(u128::MAX).unwrap(),
+    };
+    assert_eq!(
+        obj.to_json(),
+        json!({ "a": 1, "b": -1, "c": u128::MAX.to_string() })
+    );
+    assert_eq!(Obj::parse_from_json(obj.to_json()).unwrap(), obj);
+    assert!(Obj::parse_from_json(json!({ "a": 0, "b": 1, "c": "1" })).is_err());
+    assert!(Obj::parse_from_json(json!({ "a": 1, "b": 0, "c": "1" })).is_err());
+    assert!(Obj::parse_from_json(json!({ "a": 1, "b": 1, "c": "0" })).is_err());
+}
+
+#[test]
+fn smart_pointers() {
+    use std::{borrow::Cow, sync::Arc};
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Node {
+        value: Arc<String>,
+        count: Cow<'static, i32>,
+        next: Option<Box<Node>>,
+    }
+
+    let meta = get_meta::<Node>();
+    assert_eq!(meta.properties[0].1.unwrap_inline().ty, "string");
+    assert_eq!(meta.properties[1].1.unwrap_inline().ty, "integer");
+    assert_eq!(meta.properties[2].1.unwrap_reference(), "Node");
+    assert_eq!(meta.required, vec!["value", "count"]);
+
+    let node = Node {
+        value: Arc::new("a".to_string()),
+        count: Cow::Owned(1),
+        next: Some(Box::new(Node {
+            value: Arc::new("b".to_string()),
+            count: Cow::Borrowed(&2),
+            next: None,
+        })),
+    };
+    let value = json!({
+        "value": "a",
+        "count": 1,
+        "next": { "value": "b", "count": 2, "next": null },
+    });
+    assert_eq!(node.to_json(), value);
+    assert_eq!(Node::parse_from_json(value).unwrap(), node);
+}
+
+#[test]
+fn field_format() {
+    #[derive(Object)]
+    struct Obj {
+        #[oai(format = "password")]
+        password: String,
+        #[oai(format = "uuid")]
+        id: Option<String>,
+        #[oai(mask = "last4", format = "card-number")]
+        card_number: String,
+    }
+
+    let meta = get_meta::<Obj>();
+    let schema = meta.properties[0].1.unwrap_inline();
+    assert_eq!(schema.ty, "string");
+    assert_eq!(schema.format, Some("password"));
+    assert_eq!(meta.properties[1].1.unwrap_inline().format, Some("uuid"));
+    assert_eq!(
+        meta.properties[2].1.unwrap_inline().format,
+        Some("card-number")
+    );
+}
+
+#[test]
+fn mask() {
+    fn redact(_: &str) -> String {
+        "REDACTED".to_string()
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        #[oai(mask = "last4")]
+        card_number: String,
+        #[oai(mask = "all")]
+        cvv: Option<String>,
+        #[oai(mask = "redact")]
+        secrets: Vec<String>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().format,
+        Some("masked-last4")
+    );
+    assert_eq!(meta.properties[1].1.unwrap_inline().format, Some("masked"));
+    assert_eq!(meta.properties[2].1.unwrap_inline().format, Some("masked"));
+
+    let obj = Obj {
+        card_number: "4111111111111111".to_string(),
+        cvv: Some("123".to_string()),
+        secrets: vec!["a".to_string(), "b".to_string()],
+    };
+    assert_eq!(
+        obj.to_json(),
+        json!({
+            "cardNumber": "************1111",
+            "cvv": "***",
+            "secrets": ["REDACTED", "REDACTED"],
+        })
+    );
+    assert_eq!(Obj { cvv: None, ..obj }.to_json()["cvv"], json!(null));
+
+    // the values are not masked when parsing
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "cardNumber": "4111111111111111",
+            "cvv": "123",
+            "secrets": ["a"],
+        }))
+        .unwrap()
+        .card_number,
+        "4111111111111111"
+    );
+}
+
 #[test]
 fn inline_fields() {
     #[derive(Object)]
@@ -518,3 +753,209 @@ fn inline() {
     assert_eq!(meta.properties[0].0, "a");
     assert_eq!(meta.properties[0].1.unwrap_inline().ty, "string");
 }
+
+#[test]
+fn self_referential_register() {
+    #[derive(Object)]
+    struct Tree {
+        value: i32,
+        children: Vec<Tree>,
+    }
+
+    #[derive(Object)]
+    struct Forest {
+        a: Tree,
+        b: Option<Tree>,
+    }
+
+    let mut registry = Registry::default();
+    Forest::register(&mut registry);
+
+    let meta_tree = registry.schemas.remove("Tree").unwrap();
+    assert_eq!(
+        meta_tree.properties[1].1.unwrap_inline().items.as_deref(),
+        Some(&MetaSchemaRef::Reference("Tree"))
+    );
+    assert!(registry.schemas.contains_key("Forest"));
+}
+
+#[test]
+fn dynamic_object() {
+    use poem_openapi::{
+        once_cell::sync::Lazy,
+        types::{DynamicObject, DynamicObjectDefinition, DynamicObjectSchema},
+    };
+
+    static DEFINITION: Lazy<DynamicObjectDefinition> = Lazy::new(|| {
+        DynamicObjectDefinition::new("Form")
+            .description("A form")
+            .property(
+                "name",
+                MetaSchema {
+                    max_length: Some(5),
+                    ..MetaSchema::new("string")
+                },
+                true,
+            )
+            .property(
+                "age",
+                MetaSchema {
+                    default: Some(json!(18)),
+                    ..MetaSchema::new("integer")
+                },
+                false,
+            )
+            .property(
+                "tags",
+                MetaSchema {
+                    items: Some(Box::new(MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        enum_items: vec![json!("a"), json!("b")],
+                        ..MetaSchema::new("string")
+                    })))),
+                    ..MetaSchema::new("array")
+                },
+                false,
+            )
+    });
+
+    struct Form;
+
+    impl DynamicObjectSchema for Form {
+        fn definition() -> &'static DynamicObjectDefinition {
+            &DEFINITION
+        }
+    }
+
+    type Obj = DynamicObject<Form>;
+
+    assert_eq!(Obj::name(), "Form");
+    assert_eq!(Obj::schema_ref(), MetaSchemaRef::Reference("Form"));
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.description, Some("A form"));
+    assert_eq!(meta.required, vec!["name"]);
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["name", "age", "tags"]
+    );
+
+    let obj = Obj::parse_from_json(json!({ "name": "abc", "tags": ["a"], "other": 1 })).unwrap();
+    assert_eq!(obj.get("age"), Some(&json!(18)));
+    assert_eq!(
+        obj.to_json(),
+        json!({ "name": "abc", "age": 18, "tags": ["a"] })
+    );
+
+    assert_eq!(
+        Obj::parse_from_json(json!({ "age": 1 }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `name` is required."
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "name": 1 }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `name` expected input type \"string\", found 1."
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "name": "abcdef" }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `name` verification failed. maxLength(5)"
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "name": "abc", "tags": ["c"] }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `tags` expect a valid enumeration value."
+    );
+
+    // a definition loaded at runtime, such as for a tenant
+    let definition = DynamicObjectDefinition::new("Form")
+        .property("name", MetaSchema::new("string"), true)
+        .property(
+            "level",
+            MetaSchema {
+                minimum: Some(1.0),
+                ..MetaSchema::new("integer")
+            },
+            true,
+        );
+    let obj = Obj::parse_with(&definition, json!({ "name": "abcdef", "level": 2 })).unwrap();
+    assert_eq!(obj.to_json(), json!({ "name": "abcdef", "level": 2 }));
+    assert_eq!(
+        Obj::parse_with(&definition, json!({ "name": "abc", "level": 0 }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `level` verification failed. minimum(1, exclusive: false)"
+    );
+    assert_eq!(
+        Obj::parse_with(&definition, json!({ "name": "abc" }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Form\": field `level` is required."
+    );
+}
+
+#[test]
+fn parse_from_json_bytes() {
+    #[derive(Object, Debug, PartialEq)]
+    struct Inner {
+        #[oai(maximum(value = "10"))]
+        value: i32,
+    }
+
+    #[derive(Object, Debug, PartialEq)]
+    struct Obj {
+        a: i32,
+        #[oai(default = "default_b")]
+        b: i32,
+        c: Option<String>,
+        #[oai(lenient)]
+        d: Option<i32>,
+        #[oai(read_only)]
+        e: i32,
+        inner: Vec<Inner>,
+    }
+
+    fn default_b() -> i32 {
+        100
+    }
+
+    assert_eq!(
+        Obj::parse_from_json_bytes(br#"{"a": 1, "c": "abc", "d": "2", "inner": [{"value": 3}]}"#)
+            .unwrap(),
+        Obj {
+            a: 1,
+            b: 100,
+            c: Some("abc".to_string()),
+            d: Some(2),
+            e: 0,
+            inner: vec![Inner { value: 3 }],
+        }
+    );
+
+    // reports the same results as parsing from the `serde_json::Value`
+    for data in [
+        r#"{"a": 1, "b": null, "c": null, "inner": []}"#,
+        r#"{"a": 1, "b": 2, "inner": [{"value": 3}], "other": {"x": [1]}}"#,
+        r#"{"a": "1", "inner": []}"#,
+        r#"{"inner": []}"#,
+        r#"{"a": 1, "e": 1, "inner": []}"#,
+        r#"{"a": 1, "inner": [{"value": 11}]}"#,
+        r#"{"a": 1, "d": "x", "inner": []}"#,
+        r#"{"ab": 1, "a": 1, "inner": []}"#,
+        r#"[1]"#,
+        r#"1"#,
+    ] {
+        let expected = Obj::parse_from_json(serde_json::from_str(data).unwrap())
+            .map_err(|err| err.into_message());
+        let actual = Obj::parse_from_json_bytes(data.as_bytes()).map_err(|err| err.into_message());
+        assert_eq!(actual, expected, "{}", data);
+    }
+
+    assert!(Obj::parse_from_json_bytes(b"{").is_err());
+}
@@ -1,12 +1,14 @@
+use std::time::Duration;
+
 use poem::{
     http::{header, Method, StatusCode, Uri},
     web::cookie::{Cookie, CookieJar, CookieKey},
-    Endpoint, IntoEndpoint, Request,
+    Endpoint, IntoEndpoint, Request, Response,
 };
 use poem_openapi::{
     registry::{MetaApi, MetaParamIn, MetaSchema, MetaSchemaRef},
-    types::Type,
-    OpenApi, OpenApiService,
+    types::{ByteSize, HumanDuration, Type},
+    DuplicateQueryKeys, OpenApi, OpenApiService,
 };
 use serde_json::json;
 
@@ -264,6 +266,120 @@ async fn desc() {
     );
 }
 
+#[tokio::test]
+async fn desc_from_doc_comments() {
+    use poem_openapi::payload::PlainText;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(
+            &self,
+            /// The name of the user.
+            ///
+            /// It is case-insensitive.
+            #[oai(name = "name", in = "query")]
+            _name: String,
+            /// Ignored by the `desc` attribute.
+            #[oai(name = "age", in = "query", desc = "ABC")]
+            _age: i32,
+            /// The content.
+            _body: PlainText<String>,
+        ) {
+            todo!()
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(
+        operation.params[0].description,
+        Some("The name of the user.\n\nIt is case-insensitive.")
+    );
+    assert_eq!(operation.params[1].description, Some("ABC"));
+    assert_eq!(
+        operation.request.as_ref().unwrap().description,
+        Some("The content.")
+    );
+}
+
+#[tokio::test]
+async fn format() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(name = "v", in = "header", format = "password")] _v: String) {
+            todo!()
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0]
+            .schema
+            .unwrap_inline()
+            .format,
+        Some("password")
+    );
+}
+
+#[tokio::test]
+async fn human_friendly_types() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(name = "size", in = "query")] size: ByteSize,
+            #[oai(name = "timeout", in = "query")] timeout: HumanDuration,
+        ) {
+            assert_eq!(size, ByteSize(10 * 1024 * 1024));
+            assert_eq!(*timeout, Duration::from_secs(90));
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let schema = meta.paths[0].operations[0].params[0].schema.unwrap_inline();
+    assert_eq!(schema.format, Some("byte-size"));
+    assert!(schema.pattern.is_some());
+    let schema = meta.paths[0].operations[0].params[1].schema.unwrap_inline();
+    assert_eq!(schema.format, Some("duration"));
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?size=10MiB&timeout=1m30s"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?size=10XB&timeout=1m30s"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?size=10MiB&timeout=90"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn default_opt() {
     struct Api;
@@ -298,3 +414,497 @@ async fn default_opt() {
         .await;
     assert_eq!(resp.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn borrowed_params() {
+    use std::borrow::Cow;
+
+    use poem_openapi::payload::PlainText;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/:name", method = "get")]
+        async fn test(
+            &self,
+            #[oai(name = "name", in = "path")] name: &str,
+            #[oai(name = "v", in = "query")] v: Cow<'_, str>,
+            #[oai(name = "opt", in = "query")] opt: Option<&str>,
+            #[oai(name = "X-Cow", in = "header", max_length = 3)] cow: Option<Cow<'_, str>>,
+        ) -> PlainText<String> {
+            PlainText(format!("{}:{}:{:?}:{:?}", name, v, opt, cow))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].schema,
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+    );
+    assert!(!meta.paths[0].operations[0].params[2].required);
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/abc?v=hello"))
+                .header("X-Cow", "cow")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        r#"abc:hello:None:Some("cow")"#
+    );
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/abc?v=hello"))
+                .header("X-Cow", "long")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn flatten() {
+    use poem_openapi::{auth::ApiKey, payload::PlainText, ApiExtractor, SecurityScheme};
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "api_key", key_name = "X-API-Key", in = "header")]
+    struct MyApiKey(ApiKey);
+
+    #[derive(ApiExtractor)]
+    struct Pagination {
+        /// Page number
+        #[oai(in = "query", minimum(value = "1"))]
+        page: u32,
+        #[oai(name = "page_size", in = "query", default = "default_i32")]
+        size: i32,
+    }
+
+    #[derive(ApiExtractor)]
+    struct Tenant {
+        #[oai(name = "X-Tenant", in = "header")]
+        name: String,
+        #[oai(auth)]
+        key: MyApiKey,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(flatten)] tenant: Tenant,
+            #[oai(name = "q", in = "query")] q: Option<String>,
+            #[oai(flatten)] pagination: Pagination,
+        ) -> PlainText<String> {
+            PlainText(format!(
+                "{}:{}:{:?}:{}:{}",
+                tenant.name, tenant.key.0.key, q, pagination.page, pagination.size
+            ))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(
+        operation
+            .params
+            .iter()
+            .map(|param| &*param.name)
+            .collect::<Vec<_>>(),
+        vec!["X-Tenant", "q", "page", "page_size"]
+    );
+    assert_eq!(operation.params[0].in_type, MetaParamIn::Header);
+    assert_eq!(operation.params[2].description, Some("Page number"));
+    assert!(operation.params[2].required);
+    assert_eq!(
+        operation.params[3].schema.unwrap_inline().default,
+        Some(json!(999))
+    );
+    assert!(operation.security[0].contains_key("my_api_key"));
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?page=2"))
+                .header("X-Tenant", "abc")
+                .header("X-API-Key", "key")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "abc:key:None:2:999"
+    );
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?page=0"))
+                .header("X-Tenant", "abc")
+                .header("X-API-Key", "key")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?page=2"))
+                .header("X-API-Key", "key")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn duplicate_query_keys() {
+    use poem_openapi::payload::PlainText;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(name = "v", in = "query")] v: i32) -> PlainText<String> {
+            PlainText(v.to_string())
+        }
+    }
+
+    async fn call(policy: Option<DuplicateQueryKeys>) -> Response {
+        let api_service = OpenApiService::new(Api);
+        let api_service = match policy {
+            Some(policy) => api_service.duplicate_query_keys(policy),
+            None => api_service,
+        };
+        api_service
+            .into_endpoint()
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/?v=1&v=2"))
+                    .finish(),
+            )
+            .await
+    }
+
+    let mut resp = call(None).await;
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "2");
+
+    let mut resp = call(Some(DuplicateQueryKeys::LastWins)).await;
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "2");
+
+    let mut resp = call(Some(DuplicateQueryKeys::FirstWins)).await;
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "1");
+
+    let resp = call(Some(DuplicateQueryKeys::Error)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn path_style() {
+    use poem_openapi::{payload::PlainText, registry::MetaParamStyle};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get")]
+        async fn matrix(
+            &self,
+            #[oai(name = "id", in = "path", style = "matrix")] id: i32,
+        ) -> PlainText<String> {
+            PlainText(id.to_string())
+        }
+
+        #[oai(path = "/items/:id", method = "get")]
+        async fn label(
+            &self,
+            #[oai(name = "id", in = "path", style = "label")] id: i32,
+        ) -> PlainText<String> {
+            PlainText(id.to_string())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].style,
+        Some(MetaParamStyle::Matrix)
+    );
+    assert_eq!(
+        meta.paths[1].operations[0].params[0].style,
+        Some(MetaParamStyle::Label)
+    );
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/users/;id=5"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "5");
+
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/items/.7"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "7");
+
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/users/5"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn query_style() {
+    use poem_openapi::{payload::PlainText, registry::MetaParamStyle, Object};
+
+    #[derive(Object)]
+    struct Filter {
+        name: String,
+        age: Option<i32>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get")]
+        async fn users(
+            &self,
+            #[oai(name = "filter", in = "query", style = "deepObject")] filter: Filter,
+        ) -> PlainText<String> {
+            PlainText(format!("{}:{:?}", filter.name, filter.age))
+        }
+
+        #[oai(path = "/form", method = "get")]
+        async fn form(
+            &self,
+            #[oai(name = "id", in = "query", style = "form")] ids: Vec<i32>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?}", ids))
+        }
+
+        #[oai(path = "/csv", method = "get")]
+        async fn csv(
+            &self,
+            #[oai(name = "id", in = "query", style = "form", explode = false)] ids: Vec<i32>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?}", ids))
+        }
+
+        #[oai(path = "/pipe", method = "get")]
+        async fn pipe(
+            &self,
+            #[oai(name = "id", in = "query", style = "pipeDelimited")] ids: Option<Vec<u32>>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?}", ids))
+        }
+
+        #[oai(path = "/names", method = "get")]
+        async fn names(
+            &self,
+            #[oai(name = "name", in = "query", style = "form", explode = false)] names: Vec<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?}", names))
+        }
+
+        #[oai(path = "/space", method = "get")]
+        async fn space(
+            &self,
+            #[oai(name = "name", in = "query", style = "spaceDelimited")] names: Vec<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?}", names))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let param = &meta.paths[0].operations[0].params[0];
+    assert_eq!(param.style, Some(MetaParamStyle::DeepObject));
+    assert_eq!(param.explode, Some(true));
+    assert_eq!(
+        serde_json::to_value(param).unwrap()["style"],
+        json!("deepObject")
+    );
+    let param = &meta.paths[2].operations[0].params[0];
+    assert_eq!(param.style, Some(MetaParamStyle::Form));
+    assert_eq!(param.explode, Some(false));
+    let param = &meta.paths[3].operations[0].params[0];
+    assert_eq!(param.style, Some(MetaParamStyle::PipeDelimited));
+    assert_eq!(param.explode, Some(false));
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    for (uri, status, body) in [
+        (
+            "/users?filter[name]=x&filter[age]=3",
+            StatusCode::OK,
+            "x:Some(3)",
+        ),
+        ("/users?filter%5Bname%5D=y", StatusCode::OK, "y:None"),
+        ("/users?filter[age]=3", StatusCode::BAD_REQUEST, ""),
+        (
+            "/users?filter[name]=x&filter[age]=a",
+            StatusCode::BAD_REQUEST,
+            "",
+        ),
+        ("/users", StatusCode::BAD_REQUEST, ""),
+        ("/form?id=3&id=4&id=5", StatusCode::OK, "[3, 4, 5]"),
+        ("/csv?id=3,4,5", StatusCode::OK, "[3, 4, 5]"),
+        ("/pipe?id=3|4|5", StatusCode::OK, "Some([3, 4, 5])"),
+        ("/pipe", StatusCode::OK, "None"),
+        ("/csv?id=3%2C4", StatusCode::BAD_REQUEST, ""),
+        (
+            "/names?name=a%2Cb,c%7Cd",
+            StatusCode::OK,
+            r#"["a,b", "c|d"]"#,
+        ),
+        (
+            "/space?name=a%20b+c%2Bd",
+            StatusCode::OK,
+            r#"["a", "b", "c+d"]"#,
+        ),
+    ] {
+        let mut resp = api
+            .call(Request::builder().uri(Uri::from_static(uri)).finish())
+            .await;
+        assert_eq!(resp.status(), status, "{}", uri);
+        if status == StatusCode::OK {
+            assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+        }
+    }
+}
+
+#[tokio::test]
+async fn multiple_query_values() {
+    use poem_openapi::{payload::PlainText, registry::MetaParamStyle};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(name = "id", in = "query")] ids: Vec<i32>,
+            #[oai(name = "tag", in = "query")] tags: Option<Vec<String>>,
+            #[oai(name = "name", in = "query")] name: Option<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{:?} {:?} {:?}", ids, tags, name))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let params = &meta.paths[0].operations[0].params;
+    assert_eq!(params[0].schema.unwrap_inline().ty, "array");
+    assert!(params[0].required);
+    assert_eq!(params[0].style, Some(MetaParamStyle::Form));
+    assert_eq!(params[0].explode, Some(true));
+    assert!(!params[1].required);
+    assert_eq!(params[1].style, Some(MetaParamStyle::Form));
+    assert_eq!(params[2].style, None);
+    assert_eq!(params[2].explode, None);
+
+    let api = OpenApiService::new(Api)
+        .duplicate_query_keys(DuplicateQueryKeys::Error)
+        .into_endpoint();
+    for (uri, status, body) in [
+        ("/?id=1&id=2", StatusCode::OK, "[1, 2] None None"),
+        ("/?id=1,2&id=3", StatusCode::BAD_REQUEST, ""),
+        (
+            "/?id=1&tag=a&tag=b,c&name=x",
+            StatusCode::OK,
+            r#"[1] Some(["a", "b,c"]) Some("x")"#,
+        ),
+        ("/?id=", StatusCode::OK, "[] None None"),
+        ("/?id=1&id=a", StatusCode::BAD_REQUEST, ""),
+        ("/?tag=a", StatusCode::BAD_REQUEST, ""),
+        ("/?id=1&name=x&name=y", StatusCode::BAD_REQUEST, ""),
+    ] {
+        let mut resp = api
+            .call(Request::builder().uri(Uri::from_static(uri)).finish())
+            .await;
+        assert_eq!(resp.status(), status, "{}", uri);
+        if status == StatusCode::OK {
+            assert_eq!(resp.take_body().into_string().await.unwrap(), body);
+        }
+    }
+}
+
+#[tokio::test]
+async fn wildcard_path() {
+    use poem_openapi::payload::PlainText;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/files/*path", method = "get")]
+        async fn file(&self, #[oai(name = "path", in = "path")] path: String) -> PlainText<String> {
+            PlainText(path)
+        }
+
+        #[oai(path = "/proxy/:service/*path", method = "get")]
+        async fn proxy(
+            &self,
+            #[oai(name = "service", in = "path")] service: String,
+            #[oai(name = "path", in = "path")] path: Vec<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{}:{}", service, path.join(",")))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(meta.paths[0].path, "/files/{path}");
+    assert!(meta.paths[0].operations[0].params[0].wildcard);
+    assert_eq!(meta.paths[1].path, "/proxy/{service}/{path}");
+    assert!(!meta.paths[1].operations[0].params[0].wildcard);
+    assert!(meta.paths[1].operations[0].params[1].wildcard);
+
+    let api = OpenApiService::new(Api).into_endpoint();
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/files/a/b/c.txt"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "a/b/c.txt");
+
+    let mut resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/proxy/users/v1/list"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "users:v1,list"
+    );
+}
@@ -1,8 +1,9 @@
+use poem::{Body, RequestBody};
 use poem_openapi::{
     payload::{Json, PlainText},
     registry::{MetaMediaType, MetaRequest, MetaSchema, MetaSchemaRef},
     types::ParseFromJSON,
-    ApiRequest, Object,
+    ApiRequest, Object, ParseRequestError,
 };
 
 #[derive(Debug, Object, Eq, PartialEq)]
@@ -102,3 +103,160 @@ async fn generic() {
         MyRequest::CreateByJson(Json("hello".to_string()))
     );
 }
+
+#[tokio::test]
+async fn content_type_with_params_and_suffix() {
+    let request = poem::Request::builder()
+        .content_type("application/vnd.api+json; charset=utf-8")
+        .body(r#"{"user": "sunli", "password": "123456"}"#);
+    let (request, mut body) = request.split();
+    assert_eq!(
+        MyRequest::from_request(&request, &mut body).await.unwrap(),
+        MyRequest::CreateByJson(Json(CreateUser {
+            user: "sunli".to_string(),
+            password: "123456".to_string()
+        }))
+    );
+
+    let request = poem::Request::builder()
+        .content_type("Text/Plain; charset=utf-8")
+        .body("abcdef");
+    let (request, mut body) = request.split();
+    assert_eq!(
+        PlainText::<String>::from_request(&request, &mut body)
+            .await
+            .unwrap(),
+        PlainText("abcdef".to_string())
+    );
+
+    let request = poem::Request::builder()
+        .content_type("text/html")
+        .body("abcdef");
+    let (request, mut body) = request.split();
+    assert!(MyRequest::from_request(&request, &mut body).await.is_err());
+}
+
+#[tokio::test]
+async fn content_type_override() {
+    #[derive(Debug, ApiRequest, Eq, PartialEq)]
+    enum MyRequest {
+        #[oai(content_type = "application/vnd.myapp+json")]
+        CreateByVendorJson(Json<CreateUser>),
+        CreateByJson(Json<String>),
+    }
+
+    assert_eq!(
+        MyRequest::meta().content,
+        vec![
+            MetaMediaType {
+                content_type: "application/vnd.myapp+json",
+                schema: MetaSchemaRef::Reference("CreateUser"),
+            },
+            MetaMediaType {
+                content_type: "application/json",
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+            }
+        ]
+    );
+
+    let request = poem::Request::builder()
+        .content_type("Application/Vnd.MyApp+JSON; charset=utf-8")
+        .body(r#"{"user": "sunli", "password": "123456"}"#);
+    let (request, mut body) = request.split();
+    assert_eq!(
+        MyRequest::from_request(&request, &mut body).await.unwrap(),
+        MyRequest::CreateByVendorJson(Json(CreateUser {
+            user: "sunli".to_string(),
+            password: "123456".to_string()
+        }))
+    );
+
+    let request = poem::Request::builder()
+        .content_type("application/json; charset=utf-8")
+        .body(r#""hello""#);
+    let (request, mut body) = request.split();
+    assert_eq!(
+        MyRequest::from_request(&request, &mut body).await.unwrap(),
+        MyRequest::CreateByJson(Json("hello".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn client_disconnected() {
+    fn interrupted_body(kind: std::io::ErrorKind) -> RequestBody {
+        let stream = futures_util::stream::iter(vec![
+            Ok(bytes::Bytes::from_static(b"{\"user\": ")),
+            Err(std::io::Error::new(kind, "interrupted")),
+        ]);
+        RequestBody::new(Body::from_async_read(tokio_util::io::StreamReader::new(
+            stream,
+        )))
+    }
+
+    let request = poem::Request::builder()
+        .content_type("application/json")
+        .finish();
+    let err = Json::<CreateUser>::from_request(
+        &request,
+        &mut interrupted_body(std::io::ErrorKind::ConnectionReset),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ParseRequestError::ClientDisconnected { .. }));
+    assert_eq!(poem::Error::from(err).status().as_u16(), 499);
+
+    let err = Json::<CreateUser>::from_request(
+        &request,
+        &mut interrupted_body(std::io::ErrorKind::Other),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ParseRequestError::ParseRequestBody { .. }));
+
+    let err = Json::<CreateUser>::from_request(&request, &mut RequestBody::new("{".into()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ParseRequestError::ParseRequestBody { .. }));
+}
+
+#[tokio::test]
+async fn json_from_bytes() {
+    type Users = Vec<Option<CreateUser>>;
+
+    async fn parse(data: &'static str) -> Result<Users, ParseRequestError> {
+        let request = poem::Request::builder()
+            .content_type("application/json")
+            .finish();
+        Json::<Users>::from_request(&request, &mut RequestBody::new(data.into()))
+            .await
+            .map(|json| json.0)
+    }
+
+    let data = r#"[{"user": "sunli", "password": "123456"}, null]"#;
+    assert_eq!(
+        parse(data).await.unwrap(),
+        vec![
+            Some(CreateUser {
+                user: "sunli".to_string(),
+                password: "123456".to_string()
+            }),
+            None
+        ]
+    );
+
+    // reports the same errors as parsing from the `serde_json::Value`
+    for data in [r#"[{"user": "sunli"}]"#, r#"{"user": "sunli"}"#, "[1]"] {
+        let reason = Users::parse_from_json(serde_json::from_str(data).unwrap())
+            .unwrap_err()
+            .into_message();
+        match parse(data).await.unwrap_err() {
+            ParseRequestError::ParseRequestBody { reason: err } => assert_eq!(err, reason),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    assert!(matches!(
+        parse("[").await.unwrap_err(),
+        ParseRequestError::ParseRequestBody { .. }
+    ));
+}
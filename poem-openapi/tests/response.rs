@@ -6,9 +6,11 @@ use poem::{
 };
 use poem_openapi::{
     payload::{Json, PlainText},
-    registry::{MetaHeader, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef},
+    registry::{
+        MetaHeader, MetaLink, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef,
+    },
     types::ToJSON,
-    ApiResponse, Object, ParseRequestError,
+    ApiResponse, Object, OpenApi, OpenApiService, ParseRequestError,
 };
 use serde_json::Value;
 
@@ -42,7 +44,8 @@ fn meta() {
                     description: Some("Ok"),
                     status: Some(200),
                     content: vec![],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![]
                 },
                 MetaResponse {
                     description: Some("A\nB\n\nC"),
@@ -51,7 +54,8 @@ fn meta() {
                         content_type: "application/json",
                         schema: MetaSchemaRef::Reference("BadRequestResult")
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![]
                 },
                 MetaResponse {
                     description: None,
@@ -60,7 +64,8 @@ fn meta() {
                         content_type: "text/plain",
                         schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![]
                 }
             ],
         },
@@ -241,7 +246,8 @@ async fn generic() {
                     content_type: "application/json",
                     schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
                 }],
-                headers: vec![]
+                headers: vec![],
+                links: vec![]
             },],
         },
     );
@@ -253,3 +259,321 @@ async fn generic() {
         serde_json::json!("success")
     );
 }
+
+#[tokio::test]
+async fn result() {
+    #[derive(ApiResponse)]
+    pub enum OkResponse {
+        #[oai(status = 200)]
+        Ok(PlainText<String>),
+    }
+
+    #[derive(ApiResponse, Debug)]
+    #[oai(bad_request_handler = "bad_request_handler")]
+    pub enum ErrorResponse {
+        /// Not found
+        #[oai(status = 404)]
+        NotFound,
+        #[oai(status = 200)]
+        Conflicting,
+        #[oai(status = 400)]
+        BadRequest,
+    }
+
+    fn bad_request_handler(_: ParseRequestError) -> ErrorResponse {
+        ErrorResponse::BadRequest
+    }
+
+    let meta = <Result<OkResponse, ErrorResponse>>::meta();
+    assert_eq!(
+        meta.responses
+            .iter()
+            .map(|resp| resp.status)
+            .collect::<Vec<_>>(),
+        vec![Some(200), Some(404), Some(400)]
+    );
+    assert_eq!(meta.responses[1].description, Some("Not found"));
+
+    assert!(<Result<OkResponse, ErrorResponse>>::BAD_REQUEST_HANDLER);
+    assert!(matches!(
+        <Result<OkResponse, ErrorResponse>>::from_parse_request_error(
+            ParseRequestError::ParseRequestBody {
+                reason: "error".to_string(),
+            }
+        ),
+        Err(ErrorResponse::BadRequest)
+    ));
+
+    let resp = Err::<OkResponse, _>(ErrorResponse::NotFound).into_response();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    assert_eq!(<poem::Result<OkResponse>>::meta(), OkResponse::meta());
+    assert!(!<poem::Result<OkResponse>>::BAD_REQUEST_HANDLER);
+}
+
+#[test]
+fn cookies() {
+    #[derive(ApiResponse)]
+    enum LoginResponse {
+        #[oai(status = 200)]
+        Ok(
+            PlainText<String>,
+            #[oai(header = "MY-HEADER1")] i32,
+            #[oai(
+                cookie(
+                    name = "session",
+                    path = "/",
+                    max_age = 3600,
+                    secure,
+                    http_only,
+                    same_site = "lax"
+                ),
+                desc = "The session id"
+            )]
+            String,
+            #[oai(cookie(name = "theme"))] String,
+        ),
+    }
+
+    let meta: MetaResponses = LoginResponse::meta();
+    assert_eq!(
+        meta.responses[0].headers[1],
+        MetaHeader {
+            name: "Set-Cookie",
+            description: Some(
+                "`session` (Path=/; Max-Age=3600; Secure; HttpOnly; SameSite=Lax): The session id\n`theme`"
+            ),
+            required: true,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+        }
+    );
+
+    let resp = LoginResponse::Ok(
+        PlainText("hello".to_string()),
+        88,
+        "abc".to_string(),
+        "dark".to_string(),
+    )
+    .into_response();
+    assert_eq!(
+        resp.headers().get("MY-HEADER1"),
+        Some(&HeaderValue::from_static("88"))
+    );
+    let mut cookies = resp
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|value| value.to_str().unwrap());
+    let session = cookies.next().unwrap();
+    assert!(session.starts_with("session=abc"));
+    for attr in [
+        "HttpOnly",
+        "SameSite=Lax",
+        "Secure",
+        "Path=/",
+        "Max-Age=3600",
+    ] {
+        assert!(session.contains(attr), "missing `{}`", attr);
+    }
+    assert_eq!(cookies.next(), Some("theme=dark"));
+    assert_eq!(cookies.next(), None);
+}
+
+#[tokio::test]
+async fn content_type() {
+    use poem_openapi::payload::Binary;
+
+    #[derive(ApiResponse)]
+    enum DownloadResponse {
+        #[oai(status = 200, content_type = "application/pdf")]
+        Pdf(
+            Binary<Vec<u8>>,
+            #[oai(header = "Content-Disposition")] String,
+        ),
+        #[oai(content_type = "text/csv")]
+        Csv(StatusCode, Binary<Vec<u8>>),
+    }
+
+    let meta: MetaResponses = DownloadResponse::meta();
+    assert_eq!(meta.responses[0].content[0].content_type, "application/pdf");
+    assert_eq!(meta.responses[0].headers[0].name, "CONTENT-DISPOSITION");
+    assert_eq!(meta.responses[1].content[0].content_type, "text/csv");
+
+    let resp = DownloadResponse::Pdf(
+        Binary(vec![1, 2, 3]),
+        "attachment; filename=\"a.pdf\"".to_string(),
+    )
+    .into_response();
+    assert_eq!(resp.content_type(), Some("application/pdf"));
+    assert_eq!(
+        resp.headers().get("content-disposition").unwrap(),
+        "attachment; filename=\"a.pdf\""
+    );
+
+    let resp = DownloadResponse::Csv(StatusCode::CREATED, Binary(b"a,b".to_vec())).into_response();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(resp.content_type(), Some("text/csv"));
+}
+
+#[tokio::test]
+async fn attachment() {
+    use poem_openapi::payload::Attachment;
+
+    let meta = <Attachment<Vec<u8>> as ApiResponse>::meta();
+    assert_eq!(
+        meta.responses[0]
+            .headers
+            .iter()
+            .map(|header| header.name)
+            .collect::<Vec<_>>(),
+        vec!["Content-Disposition", "Content-Length"]
+    );
+
+    let mut resp = Attachment::new(b"abc".to_vec())
+        .filename("a \"b\".csv")
+        .content_type("text/csv")
+        .into_response();
+    assert_eq!(resp.content_type(), Some("text/csv"));
+    assert_eq!(resp.headers().get("content-length").unwrap(), "3");
+    assert_eq!(
+        resp.headers().get("content-disposition").unwrap(),
+        r#"attachment; filename="a \"b\".csv""#
+    );
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "abc");
+
+    let resp = Attachment::new(vec![]).filename("报告.pdf").into_response();
+    assert_eq!(resp.content_type(), Some("application/octet-stream"));
+    assert_eq!(
+        resp.headers().get("content-disposition").unwrap(),
+        "attachment; filename=\"__.pdf\"; filename*=UTF-8''%E6%8A%A5%E5%91%8A.pdf"
+    );
+
+    let resp = Attachment::new(vec![]).into_response();
+    assert_eq!(
+        resp.headers().get("content-disposition").unwrap(),
+        "attachment"
+    );
+}
+
+#[tokio::test]
+async fn links() {
+    #[derive(Object)]
+    struct User {
+        id: i64,
+    }
+
+    #[derive(ApiResponse)]
+    enum CreateUserResponse {
+        /// Created
+        #[oai(
+            status = 201,
+            link(
+                name = "GetUser",
+                operation_id = "getUser",
+                parameter(name = "id", value = "$response.body#/id"),
+                desc = "The `id` can be used to get the user."
+            ),
+            link(name = "ListUsers", operation_id = "listUsers")
+        )]
+        Created(Json<User>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "post", operation_id = "createUser")]
+        async fn create(&self) -> CreateUserResponse {
+            CreateUserResponse::Created(Json(User { id: 1 }))
+        }
+
+        #[oai(path = "/users/:id", method = "get", operation_id = "getUser")]
+        async fn get(&self, #[oai(name = "id", in = "path")] id: i64) -> Json<User> {
+            Json(User { id })
+        }
+
+        #[oai(path = "/users", method = "get", operation_id = "listUsers")]
+        async fn list(&self) -> Json<Vec<User>> {
+            Json(vec![])
+        }
+    }
+
+    let meta = CreateUserResponse::meta();
+    assert_eq!(
+        meta.responses[0].links,
+        vec![
+            MetaLink {
+                name: "GetUser",
+                operation_id: "getUser".into(),
+                parameters: vec![("id", "$response.body#/id")],
+                description: Some("The `id` can be used to get the user."),
+            },
+            MetaLink {
+                name: "ListUsers",
+                operation_id: "listUsers".into(),
+                parameters: vec![],
+                description: None,
+            }
+        ]
+    );
+
+    let spec: serde_json::Value = serde_json::from_str(&OpenApiService::new(Api).spec()).unwrap();
+    assert_eq!(
+        spec["paths"]["/users/{id}"]["get"]["operationId"],
+        "getUser"
+    );
+    assert_eq!(
+        spec["paths"]["/users"]["post"]["responses"]["201"]["links"],
+        serde_json::json!({
+            "GetUser": {
+                "operationId": "getUser",
+                "parameters": { "id": "$response.body#/id" },
+                "description": "The `id` can be used to get the user.",
+            },
+            "ListUsers": { "operationId": "listUsers" },
+        })
+    );
+}
+
+#[tokio::test]
+async fn redirect() {
+    use poem::{http::Uri, web::Redirect, Endpoint, IntoEndpoint};
+
+    let meta = Redirect::meta();
+    assert_eq!(
+        meta.responses
+            .iter()
+            .map(|resp| resp.status)
+            .collect::<Vec<_>>(),
+        vec![Some(301), Some(302), Some(303), Some(307), Some(308)]
+    );
+    assert!(meta
+        .responses
+        .iter()
+        .all(|resp| resp.headers[0].name == "Location" && resp.headers[0].required));
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/login", method = "post")]
+        async fn login(&self) -> Redirect {
+            Redirect::see_other(Uri::from_static("/home"))
+        }
+    }
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(poem::http::Method::POST)
+                .uri(Uri::from_static("/login"))
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers().get("location"),
+        Some(&HeaderValue::from_static("/home"))
+    );
+}
@@ -0,0 +1,188 @@
+use poem_openapi::{
+    payload::Json,
+    spec_compat::{self, BreakingChange, BreakingChangeKind},
+    Enum, Object, OpenApi, OpenApiService,
+};
+
+mod v1 {
+    use super::*;
+
+    #[derive(Enum)]
+    pub enum Status {
+        Active,
+        Disabled,
+    }
+
+    #[derive(Object)]
+    pub struct User {
+        pub id: i64,
+        pub name: String,
+        pub status: Status,
+    }
+
+    #[derive(Object)]
+    pub struct CreateUser {
+        #[oai(max_length = "32")]
+        pub name: String,
+        pub status: Status,
+    }
+
+    pub struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get")]
+        async fn get(&self, #[oai(name = "id", in = "path")] _id: i64) -> Json<User> {
+            todo!()
+        }
+
+        #[oai(path = "/users", method = "post")]
+        async fn create(
+            &self,
+            #[oai(name = "dry_run", in = "query")] _dry_run: Option<bool>,
+            _user: Json<CreateUser>,
+        ) -> Json<i64> {
+            todo!()
+        }
+
+        #[oai(path = "/users/:id", method = "delete")]
+        async fn delete(&self, #[oai(name = "id", in = "path")] _id: i64) {}
+    }
+}
+
+mod v2 {
+    use super::*;
+
+    #[derive(Enum)]
+    pub enum Status {
+        Active,
+        Locked,
+    }
+
+    #[derive(Object)]
+    pub struct User {
+        pub id: i64,
+        pub name: Option<String>,
+        pub status: Status,
+        pub email: String,
+    }
+
+    #[derive(Object)]
+    pub struct CreateUser {
+        #[oai(max_length = "16")]
+        pub name: String,
+        pub status: Status,
+        pub email: String,
+        pub phone: Option<String>,
+    }
+
+    pub struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get")]
+        async fn get(&self, #[oai(name = "id", in = "path")] _id: String) -> Json<User> {
+            todo!()
+        }
+
+        #[oai(path = "/users", method = "post")]
+        async fn create(
+            &self,
+            #[oai(name = "dry_run", in = "query")] _dry_run: bool,
+            _user: Json<CreateUser>,
+        ) -> Json<i64> {
+            todo!()
+        }
+
+        #[oai(path = "/users", method = "get")]
+        async fn list(&self) -> Json<Vec<User>> {
+            todo!()
+        }
+    }
+}
+
+fn change(location: &str, kind: BreakingChangeKind) -> BreakingChange {
+    BreakingChange {
+        location: location.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn compatible() {
+    let spec = OpenApiService::new(v1::Api).spec();
+    assert_eq!(spec_compat::check(&spec, &spec).unwrap(), vec![]);
+
+    // new operations are compatible
+    let spec = OpenApiService::new(v2::Api).spec();
+    let mut value: serde_json::Value = serde_json::from_str(&spec).unwrap();
+    value["paths"]
+        .as_object_mut()
+        .unwrap()
+        .remove("/users")
+        .unwrap();
+    assert_eq!(
+        spec_compat::compare(&value, &serde_json::from_str(&spec).unwrap()),
+        vec![]
+    );
+}
+
+#[test]
+fn breaking_changes() {
+    let published = OpenApiService::new(v1::Api).spec();
+    let current = OpenApiService::new(v2::Api).spec();
+    let mut changes = spec_compat::check(&published, &current).unwrap();
+    changes.sort_by(|a, b| a.location.cmp(&b.location));
+
+    assert_eq!(
+        changes,
+        vec![
+            change("DELETE /users/{id}", BreakingChangeKind::OperationRemoved),
+            change(
+                "GET /users/{id} parameter `id` (path)",
+                BreakingChangeKind::TypeChanged {
+                    old: "integer".to_string(),
+                    new: "string".to_string(),
+                },
+            ),
+            change(
+                "GET /users/{id} response 200 (application/json)",
+                BreakingChangeKind::RequiredFieldRemoved {
+                    name: "name".to_string()
+                },
+            ),
+            change(
+                "GET /users/{id} response 200 (application/json).status",
+                BreakingChangeKind::EnumValueAdded {
+                    value: "LOCKED".to_string()
+                },
+            ),
+            change(
+                "POST /users parameter `dry_run` (query)",
+                BreakingChangeKind::ParameterRequired,
+            ),
+            change(
+                "POST /users request body (application/json)",
+                BreakingChangeKind::RequiredFieldAdded {
+                    name: "email".to_string()
+                },
+            ),
+            change(
+                "POST /users request body (application/json).name",
+                BreakingChangeKind::ConstraintNarrowed {
+                    constraint: "maxLength"
+                },
+            ),
+            change(
+                "POST /users request body (application/json).status",
+                BreakingChangeKind::EnumValueRemoved {
+                    value: "DISABLED".to_string()
+                },
+            ),
+        ]
+    );
+    assert_eq!(
+        changes[0].to_string(),
+        "DELETE /users/{id}: the operation is removed"
+    );
+}
@@ -1,8 +1,15 @@
 use std::collections::HashSet;
 
+use poem::{
+    http::{StatusCode, Uri},
+    middleware::SetHeader,
+    Endpoint, EndpointExt, IntoEndpoint, Request,
+};
 use poem_openapi::{
+    auth::ApiKey,
+    payload::PlainText,
     registry::{MetaTag, Registry},
-    Tags,
+    OpenApi, OpenApiService, SecurityScheme, Tags,
 };
 
 #[tokio::test]
@@ -72,3 +79,76 @@ async fn meta() {
         .collect::<HashSet<_>>()
     );
 }
+
+#[tokio::test]
+async fn security_and_transform() {
+    #[derive(SecurityScheme)]
+    #[oai(type = "api_key", key_name = "X-Admin-Token", in = "header")]
+    #[allow(dead_code)]
+    struct AdminAuth(ApiKey);
+
+    fn admin_middleware(ep: impl Endpoint) -> impl Endpoint {
+        ep.with(SetHeader::new().overriding("X-Admin", "true"))
+    }
+
+    #[derive(Tags)]
+    enum MyTags {
+        User,
+        #[oai(security = "AdminAuth", transform = "admin_middleware")]
+        Admin,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/user", method = "get", tag = "MyTags::User")]
+        async fn user(&self) -> PlainText<&'static str> {
+            PlainText("user")
+        }
+
+        #[oai(path = "/admin", method = "get", tag = "MyTags::Admin")]
+        async fn admin(&self) -> PlainText<&'static str> {
+            PlainText("admin")
+        }
+    }
+
+    assert_eq!(MyTags::User.security(), None);
+    assert_eq!(MyTags::Admin.security(), Some("admin_auth"));
+
+    let meta = Api::meta();
+    assert!(meta[0].paths[0].operations[0].security[0].is_empty());
+    assert_eq!(
+        meta[0].paths[1].operations[0].security[0].get("admin_auth"),
+        Some(&vec![])
+    );
+
+    let mut registry = Registry::new();
+    Api::register(&mut registry);
+    assert!(registry.security_schemes.contains_key("admin_auth"));
+
+    let ep = OpenApiService::new(Api).into_endpoint();
+
+    let resp = ep
+        .call(Request::builder().uri(Uri::from_static("/user")).finish())
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get("X-Admin").is_none());
+
+    let resp = ep
+        .call(Request::builder().uri(Uri::from_static("/admin")).finish())
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let mut resp = ep
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/admin"))
+                .header("X-Admin-Token", "abc")
+                .finish(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("X-Admin").unwrap(), "true");
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "admin");
+}
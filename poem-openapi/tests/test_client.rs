@@ -0,0 +1,181 @@
+use poem::{http::StatusCode, test::TestClient, IntoResponse, Response};
+use poem_openapi::{
+    payload::{Json, PlainText},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, Registry},
+    test::{assert_response_conforms, parse_payload},
+    types::Type,
+    ApiResponse, Object, OpenApi, OpenApiService, ResponseContext,
+};
+
+#[derive(Debug, Object, PartialEq)]
+struct Pet {
+    id: i64,
+    name: String,
+    tags: Vec<String>,
+}
+
+/// A response that documents `Pet`, but returns a different body.
+struct WrongPet;
+
+impl IntoResponse for WrongPet {
+    fn into_response(self) -> Response {
+        poem::web::Json(serde_json::json!({ "id": "1", "name": "dog", "tags": [] })).into_response()
+    }
+}
+
+impl ApiResponse for WrongPet {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: None,
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: "application/json",
+                    schema: Pet::schema_ref(),
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        Pet::register(registry);
+    }
+}
+
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/pets/:id", method = "get")]
+    async fn get_pet(&self, #[oai(name = "id", in = "path")] id: i64) -> Json<Pet> {
+        Json(Pet {
+            id,
+            name: "dog".to_string(),
+            tags: vec!["cute".to_string()],
+        })
+    }
+
+    #[oai(path = "/hello", method = "get")]
+    async fn hello(&self, ctx: ResponseContext) -> PlainText<&'static str> {
+        ctx.set_status(StatusCode::CREATED);
+        PlainText("hello")
+    }
+
+    #[oai(path = "/wrong", method = "get")]
+    async fn wrong(&self) -> WrongPet {
+        WrongPet
+    }
+
+    #[oai(path = "/files/*path", method = "get")]
+    async fn file(&self, #[oai(name = "path", in = "path")] _path: Vec<String>) -> WrongPet {
+        WrongPet
+    }
+}
+
+#[tokio::test]
+async fn conform() {
+    let cli = OpenApiService::new(Api).test_client();
+
+    let resp = cli.get("/pets/1").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_json(&serde_json::json!({ "id": 1, "name": "dog", "tags": ["cute"] }))
+        .await;
+
+    // the requests that do not match an operation are not checked
+    cli.get("/abc")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[should_panic(expected = "GET /hello: the status `201 Created` is not documented")]
+async fn undocumented_status() {
+    let cli = OpenApiService::new(Api).test_client();
+    cli.get("/hello").send().await;
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "GET /wrong: the body does not match the schema: $.id: expected `integer`"
+)]
+async fn schema_mismatch() {
+    let cli = OpenApiService::new(Api).test_client();
+    cli.get("/wrong").send().await;
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "GET /files/{path}: the body does not match the schema: $.id: expected `integer`"
+)]
+async fn wildcard_path() {
+    let cli = OpenApiService::new(Api).test_client();
+    cli.get("/files/a/b").send().await;
+}
+
+#[tokio::test]
+async fn response_conforms() {
+    let cli = TestClient::new(OpenApiService::new(Api));
+
+    let resp = assert_response_conforms::<Json<Pet>>(cli.get("/pets/1").send().await).await;
+    resp.assert_status_is_ok();
+    assert_eq!(
+        parse_payload::<Json<Pet>>(resp).await.0,
+        Pet {
+            id: 1,
+            name: "dog".to_string(),
+            tags: vec!["cute".to_string()],
+        }
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "WrongPet: the body does not match the schema: $.id: expected `integer`")]
+async fn response_mismatch() {
+    let cli = TestClient::new(OpenApiService::new(Api));
+    assert_response_conforms::<WrongPet>(cli.get("/wrong").send().await).await;
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn typed_response() {
+    use poem_openapi::test::assert_api_response;
+
+    let cli = TestClient::new(OpenApiService::new(Api));
+
+    let resp = assert_api_response::<Json<Pet>>(cli.get("/pets/1").send().await).await;
+    assert_eq!(
+        resp.0,
+        Pet {
+            id: 1,
+            name: "dog".to_string(),
+            tags: vec!["cute".to_string()],
+        }
+    );
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn typed_response_variant() {
+    use poem::endpoint::make_sync;
+    use poem_openapi::test::assert_api_response;
+
+    #[derive(ApiResponse)]
+    #[oai(client)]
+    enum GetPetResponse {
+        #[oai(status = 200)]
+        Ok(Json<Pet>),
+        #[oai(status = 404)]
+        NotFound(PlainText<String>),
+    }
+
+    let cli = TestClient::new(make_sync(|_| {
+        PlainText("not found")
+            .with_status(StatusCode::NOT_FOUND)
+            .into_response()
+    }));
+    let resp = assert_api_response::<GetPetResponse>(cli.get("/").send().await).await;
+    assert!(matches!(resp, GetPetResponse::NotFound(PlainText(text)) if text == "not found"));
+}
@@ -1,8 +1,9 @@
 use poem::{
-    http::{StatusCode, Uri},
+    http::{Method, StatusCode, Uri},
     Endpoint, IntoEndpoint, Request,
 };
 use poem_openapi::{
+    payload::Json,
     registry::{MetaApi, MetaSchema},
     types::ParseFromJSON,
     validation,
@@ -309,6 +310,58 @@ async fn param_validator() {
     assert_eq!(resp.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn skip_validation() {
+    #[derive(Object)]
+    struct A {
+        #[oai(max_length = "3")]
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(
+            &self,
+            #[oai(name = "v", in = "query", maximum(value = "100"))] _v: i32,
+            _a: Json<A>,
+        ) {
+        }
+    }
+
+    let api = OpenApiService::new(Api)
+        .skip_validation_if(|req| req.headers().contains_key("x-internal"))
+        .into_endpoint();
+    let request = || {
+        Request::builder()
+            .method(Method::POST)
+            .uri(Uri::from_static("/?v=999"))
+            .content_type("application/json")
+    };
+
+    let resp = api.call(request().body(r#"{"name": "abc"}"#)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = api.call(request().body(r#"{"name": "abcd"}"#)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = api
+        .call(
+            request()
+                .header("x-internal", "1")
+                .body(r#"{"name": "abcd"}"#),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // type conversion is not skipped
+    let resp = api
+        .call(request().header("x-internal", "1").body(r#"{"name": 1}"#))
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
 #[test]
 fn test_option() {
     #[derive(Object, Debug, Eq, PartialEq)]
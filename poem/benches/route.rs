@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use poem::{endpoint::make_sync, http::Uri, Endpoint, Request, Route};
+
+fn create_route(count: usize) -> Route {
+    (0..count).fold(Route::new(), |route, i| {
+        route.at(
+            format!("/api/v1/resources{}/:id/items", i),
+            make_sync(|_| ()),
+        )
+    })
+}
+
+/// The time of matching a path should not grow with the number of routes.
+fn route_matching(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("route_matching");
+
+    for count in [15, 150, 1500] {
+        let route = create_route(count);
+        let uri: Uri = format!("/api/v1/resources{}/1/items", count - 1)
+            .parse()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &uri, |b, uri| {
+            b.to_async(&rt)
+                .iter(|| route.call(Request::builder().uri(uri.clone()).finish()))
+        });
+    }
+
+    group.finish();
+}
+
+fn route_building(c: &mut Criterion) {
+    c.bench_function("route_building_1500", |b| b.iter(|| create_route(1500)));
+}
+
+criterion_group!(benches, route_matching, route_building);
+criterion_main!(benches);
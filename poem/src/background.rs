@@ -0,0 +1,221 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{watch, Notify};
+
+use crate::web::TaskGroup;
+
+struct Inner {
+    shutdown_sender: watch::Sender<bool>,
+    shutdown_receiver: watch::Receiver<bool>,
+    group: TaskGroup,
+    alive_tasks: AtomicUsize,
+    notify: Notify,
+}
+
+/// Decrements the number of the alive tasks when the task completes or is
+/// aborted.
+struct AliveGuard(Arc<Inner>);
+
+impl Drop for AliveGuard {
+    fn drop(&mut self) {
+        if self.0.alive_tasks.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.notify.notify_one();
+        }
+    }
+}
+
+/// A signal that the server is shutting down, which is received by the
+/// background tasks.
+#[derive(Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    /// Returns `true` if the server is shutting down.
+    pub fn is_triggered(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until the server is shutting down.
+    pub async fn wait(&mut self) {
+        while !*self.0.borrow() {
+            if self.0.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// The background tasks of a [`Server`](crate::Server), such as the
+/// periodic jobs or the queue consumers, whose lifetimes are tied to the
+/// server.
+///
+/// When the server initiates the graceful shutdown, the tasks receive the
+/// [`ShutdownSignal`], and the server waits for them to complete after the
+/// connections are closed. If the shutdown timeout elapses, the remaining
+/// tasks are aborted.
+///
+/// The handle is cloned cheaply, and it can be extracted in the handlers
+/// with `Data<&BackgroundTasks>`.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use poem::{handler, listener::TcpListener, web::Data, BackgroundTasks, Server};
+///
+/// #[handler]
+/// fn index(tasks: Data<&BackgroundTasks>) -> &'static str {
+///     tasks.spawn(|_| async move {
+///         // send an email
+///     });
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let server = Server::new(TcpListener::bind("127.0.0.1:3000")).await?;
+/// server
+///     .background_tasks()
+///     .spawn_interval(Duration::from_secs(60), || async move {
+///         // clean up the expired sessions
+///     });
+/// server.run(index).await
+/// # }).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct BackgroundTasks(Arc<Inner>);
+
+impl BackgroundTasks {
+    pub(crate) fn new() -> Self {
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        Self(Arc::new(Inner {
+            shutdown_sender,
+            shutdown_receiver,
+            group: TaskGroup::new(),
+            alive_tasks: AtomicUsize::new(0),
+            notify: Notify::new(),
+        }))
+    }
+
+    /// Spawns a background task, `f` receives the [`ShutdownSignal`] to stop
+    /// the task when the server is shutting down.
+    pub fn spawn<F, Fut>(&self, f: F)
+    where
+        F: FnOnce(ShutdownSignal) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let fut = f(ShutdownSignal(self.0.shutdown_receiver.clone()));
+        self.0.alive_tasks.fetch_add(1, Ordering::SeqCst);
+        let guard = AliveGuard(self.0.clone());
+        self.0.group.spawn(async move {
+            let _guard = guard;
+            fut.await
+        });
+    }
+
+    /// Spawns a background task that calls `f` every `period`, the first call
+    /// is made immediately. The task stops after the current call completes
+    /// when the server is shutting down.
+    pub fn spawn_interval<F, Fut>(&self, period: Duration, mut f: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        self.spawn(move |mut shutdown| async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => f().await,
+                    _ = shutdown.wait() => break,
+                }
+            }
+        });
+    }
+
+    /// Returns the number of the running tasks.
+    pub fn len(&self) -> usize {
+        self.0.alive_tasks.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if there are no running tasks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends the [`ShutdownSignal`] to the tasks.
+    pub(crate) fn shutdown(&self) {
+        let _ = self.0.shutdown_sender.send(true);
+    }
+
+    /// Waits for all the tasks to complete.
+    pub(crate) async fn wait(&self) {
+        while !self.is_empty() {
+            self.0.notify.notified().await;
+        }
+    }
+
+    /// Aborts all the tasks.
+    pub(crate) fn abort_all(&self) {
+        self.0.group.cancel_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown() {
+        let tasks = BackgroundTasks::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tasks.spawn(move |mut shutdown| {
+            assert!(!shutdown.is_triggered());
+            async move {
+                shutdown.wait().await;
+                tx.send("stopped").unwrap();
+            }
+        });
+        assert_eq!(tasks.len(), 1);
+
+        tasks.shutdown();
+        tasks.wait().await;
+        assert!(tasks.is_empty());
+        assert_eq!(rx.recv().await, Some("stopped"));
+    }
+
+    #[tokio::test]
+    async fn interval() {
+        let tasks = BackgroundTasks::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+        tasks.spawn_interval(Duration::from_millis(10), {
+            let counter = counter.clone();
+            move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        tasks.shutdown();
+        tasks.wait().await;
+        assert!(counter.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test]
+    async fn abort() {
+        let tasks = BackgroundTasks::new();
+        tasks.spawn(|_| tokio::time::sleep(Duration::from_secs(60)));
+        tasks.abort_all();
+        tasks.wait().await;
+        assert!(tasks.is_empty());
+    }
+}
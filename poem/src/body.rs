@@ -1,29 +1,45 @@
 use std::{
+    error::Error as StdError,
     fmt::Display,
+    future::Future,
     io::{Error as IoError, ErrorKind},
     pin::Pin,
     task::{Context, Poll},
 };
 
 use bytes::Bytes;
-use futures_util::Stream;
-use hyper::body::HttpBody;
+use futures_util::{Stream, StreamExt};
+use hyper::body::{HttpBody, SizeHint};
+use parking_lot::Mutex;
 use tokio::io::AsyncRead;
 
-use crate::error::ReadBodyError;
+use crate::{error::ReadBodyError, http::HeaderMap};
+
+/// The future of the trailers, it is only polled by `&mut`, the mutex makes
+/// the body `Sync`.
+type TrailersFuture = Mutex<Pin<Box<dyn Future<Output = HeaderMap> + Send>>>;
 
 /// A body object for requests and responses.
 #[derive(Default)]
-pub struct Body(pub(crate) hyper::Body);
+pub struct Body(pub(crate) hyper::Body, Option<TrailersFuture>);
 
 impl From<hyper::Body> for Body {
     fn from(body: hyper::Body) -> Self {
-        Body(body)
+        Body(body, None)
     }
 }
 
 impl From<Body> for hyper::Body {
+    /// NOTE: The trailers of [`Body::from_bytes_stream_with_trailers`] are
+    /// discarded and a warning is logged, because `hyper::Body` cannot carry
+    /// them. The server sends the trailers over HTTP/2 without converting the
+    /// body.
     fn from(body: Body) -> Self {
+        if body.has_trailers() {
+            tracing::warn!(
+                "the trailers of the body are discarded, they are only sent by the server over HTTP/2"
+            );
+        }
         body.0
     }
 }
@@ -31,35 +47,35 @@ impl From<Body> for hyper::Body {
 impl From<&'static [u8]> for Body {
     #[inline]
     fn from(data: &'static [u8]) -> Self {
-        Self(data.into())
+        Self(data.into(), None)
     }
 }
 
 impl From<&'static str> for Body {
     #[inline]
     fn from(data: &'static str) -> Self {
-        Self(data.into())
+        Self(data.into(), None)
     }
 }
 
 impl From<Bytes> for Body {
     #[inline]
     fn from(data: Bytes) -> Self {
-        Self(data.into())
+        Self(data.into(), None)
     }
 }
 
 impl From<Vec<u8>> for Body {
     #[inline]
     fn from(data: Vec<u8>) -> Self {
-        Self(data.into())
+        Self(data.into(), None)
     }
 }
 
 impl From<String> for Body {
     #[inline]
     fn from(data: String) -> Self {
-        Self(data.into())
+        Self(data.into(), None)
     }
 }
 
@@ -92,31 +108,89 @@ impl Body {
     /// Create a body object from reader.
     #[inline]
     pub fn from_async_read(reader: impl AsyncRead + Send + 'static) -> Self {
-        Self(hyper::Body::wrap_stream(tokio_util::io::ReaderStream::new(
-            reader,
-        )))
+        Self(
+            hyper::Body::wrap_stream(tokio_util::io::ReaderStream::new(reader)),
+            None,
+        )
+    }
+
+    /// Create a body object from a bytes stream, and the trailers are sent
+    /// after the stream completes.
+    ///
+    /// The `trailers` future is polled after all the data has been sent, so
+    /// it can be used for checksum-at-end patterns.
+    ///
+    /// NOTE: Trailers are only sent over HTTP/2, they are discarded for
+    /// HTTP/1.1 connections, because hyper 0.14 cannot send them, and the
+    /// `trailers` future is not polled, the server logs a warning if it
+    /// happens. They are also discarded if the body is converted to
+    /// `hyper::Body`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use poem::{
+    ///     http::{HeaderMap, HeaderValue},
+    ///     Body,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let stream = futures_util::stream::iter(vec![Ok::<_, std::io::Error>(Bytes::from("abc"))]);
+    /// let body = Body::from_bytes_stream_with_trailers(stream, async move {
+    ///     let mut trailers = HeaderMap::new();
+    ///     trailers.insert("x-checksum", HeaderValue::from_static("900150983cd24fb0"));
+    ///     trailers
+    /// });
+    /// # });
+    /// ```
+    pub fn from_bytes_stream_with_trailers<S, E, F>(stream: S, trailers: F) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Display + Send + 'static,
+        F: Future<Output = HeaderMap> + Send + 'static,
+    {
+        let stream = stream.map(|res| {
+            res.map_err(|err| {
+                tracing::debug!(error = %err, "failed to read the body stream");
+                IoError::new(ErrorKind::Other, err.to_string())
+            })
+        });
+        Self(
+            hyper::Body::wrap_stream(stream),
+            Some(Mutex::new(Box::pin(trailers))),
+        )
+    }
+
+    /// Returns `true` if the body has the trailers of
+    /// [`Body::from_bytes_stream_with_trailers`].
+    pub(crate) fn has_trailers(&self) -> bool {
+        self.1.is_some()
     }
 
     /// Create an empty body.
     #[inline]
     pub fn empty() -> Self {
-        Self(hyper::Body::empty())
+        Self(hyper::Body::empty(), None)
+    }
+
+    /// Returns the size of the body if it is known exactly, such as the
+    /// bodies created from bytes.
+    pub fn exact_size(&self) -> Option<u64> {
+        HttpBody::size_hint(&self.0).exact()
     }
 
     /// Consumes this body object to return a [`Bytes`] that contains all data.
     pub async fn into_bytes(self) -> Result<Bytes, ReadBodyError> {
-        Ok(hyper::body::to_bytes(self.0)
+        hyper::body::to_bytes(self.0)
             .await
-            .map_err(|err| ReadBodyError::Io(IoError::new(ErrorKind::Other, err)))?)
+            .map_err(|err| ReadBodyError::Io(body_io_error(err)))
     }
 
     /// Consumes this body object to return a [`Vec<u8>`] that contains all
     /// data.
     pub async fn into_vec(self) -> Result<Vec<u8>, ReadBodyError> {
-        Ok(hyper::body::to_bytes(self.0)
-            .await
-            .map_err(|err| ReadBodyError::Io(IoError::new(ErrorKind::Other, err)))?
-            .to_vec())
+        Ok(self.into_bytes().await?.to_vec())
     }
 
     /// Consumes this body object to return a [`String`] that contains all data.
@@ -130,6 +204,43 @@ impl Body {
     }
 }
 
+impl HttpBody for Body {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    #[inline]
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.0).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        match &mut self.1 {
+            Some(trailers) => trailers
+                .get_mut()
+                .as_mut()
+                .poll(cx)
+                .map(|trailers| Ok(Some(trailers))),
+            None => Pin::new(&mut self.0).poll_trailers(cx),
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.1.is_none() && self.0.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        HttpBody::size_hint(&self.0)
+    }
+}
+
 pin_project_lite::pin_project! {
     pub(crate) struct BodyStream<T> {
         #[pin] inner: T,
@@ -146,19 +257,40 @@ impl<T> BodyStream<T> {
 impl<T> Stream for BodyStream<T>
 where
     T: HttpBody,
-    T::Error: Display,
+    T::Error: Into<Box<dyn StdError + Send + Sync>>,
 {
     type Item = Result<T::Data, std::io::Error>;
 
     #[inline]
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.project()
-            .inner
-            .poll_data(cx)
-            .map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))
+        self.project().inner.poll_data(cx).map_err(body_io_error)
     }
 }
 
+/// Converts the error of reading the body to [`IoError`], the kind of the
+/// error is `UnexpectedEof` if the connection was closed before the body was
+/// completely received, or the kind of the underlying IO error, such as
+/// `ConnectionReset`.
+fn body_io_error(err: impl Into<Box<dyn StdError + Send + Sync>>) -> IoError {
+    let err = err.into();
+    let kind = match err.downcast_ref::<hyper::Error>() {
+        Some(err) if err.is_incomplete_message() => ErrorKind::UnexpectedEof,
+        _ => {
+            let mut source: Option<&(dyn StdError + 'static)> = Some(&*err);
+            loop {
+                match source {
+                    Some(err) => match err.downcast_ref::<IoError>() {
+                        Some(err) => break err.kind(),
+                        None => source = err.source(),
+                    },
+                    None => break ErrorKind::Other,
+                }
+            }
+        }
+    };
+    IoError::new(kind, err)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,4 +337,65 @@ mod tests {
         ));
         assert_eq!(body.into_string().await.unwrap(), "abcdefghi");
     }
+
+    #[tokio::test]
+    async fn client_disconnected() {
+        let stream = futures_util::stream::iter(vec![
+            Ok::<_, IoError>(Bytes::from_static(b"abc")),
+            Err(IoError::new(ErrorKind::ConnectionReset, "connection reset")),
+        ]);
+        let err = Body::from_async_read(tokio_util::io::StreamReader::new(stream))
+            .into_vec()
+            .await
+            .unwrap_err();
+        assert!(err.is_client_disconnected());
+
+        let stream = futures_util::stream::iter(vec![
+            Ok::<_, IoError>(Bytes::from_static(b"abc")),
+            Err(IoError::new(ErrorKind::Other, "other")),
+        ]);
+        let err = Body::from_async_read(tokio_util::io::StreamReader::new(stream))
+            .into_vec()
+            .await
+            .unwrap_err();
+        assert!(!err.is_client_disconnected());
+    }
+
+    #[tokio::test]
+    async fn trailers() {
+        fn create() -> Body {
+            let stream = futures_util::stream::iter(vec![
+                Ok::<_, std::io::Error>(Bytes::from_static(b"abc")),
+                Ok(Bytes::from_static(b"def")),
+            ]);
+            Body::from_bytes_stream_with_trailers(stream, async move {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("x-checksum", "abcdef".parse().unwrap());
+                trailers
+            })
+        }
+
+        async fn check(
+            mut body: impl HttpBody<Data = Bytes, Error = hyper::Error> + Unpin,
+            has_trailers: bool,
+        ) {
+            let mut data = Vec::new();
+            while let Some(chunk) = body.data().await {
+                data.extend_from_slice(&chunk.unwrap());
+            }
+            assert_eq!(data, b"abcdef");
+
+            let trailers = body.trailers().await.unwrap();
+            assert_eq!(
+                trailers
+                    .as_ref()
+                    .and_then(|trailers| trailers.get("x-checksum")?.to_str().ok()),
+                has_trailers.then(|| "abcdef")
+            );
+        }
+
+        check(create(), true).await;
+        // the trailers are discarded by the conversion
+        check(hyper::Body::from(create()), false).await;
+    }
 }
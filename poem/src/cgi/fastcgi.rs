@@ -0,0 +1,389 @@
+use std::sync::Arc;
+
+use tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter, Error as IoError, ErrorKind,
+    Result as IoResult,
+};
+
+use crate::{
+    cgi::{build_request, response_head},
+    listener::{Acceptor, Listener},
+    Endpoint, EndpointExt, IntoEndpoint, Response,
+};
+
+const VERSION: u8 = 1;
+
+const BEGIN_REQUEST: u8 = 1;
+const ABORT_REQUEST: u8 = 2;
+const END_REQUEST: u8 = 3;
+const PARAMS: u8 = 4;
+const STDIN: u8 = 5;
+const STDOUT: u8 = 6;
+const GET_VALUES: u8 = 9;
+const GET_VALUES_RESULT: u8 = 10;
+const UNKNOWN_TYPE: u8 = 11;
+
+const ROLE_RESPONDER: u16 = 1;
+const FLAG_KEEP_CONN: u8 = 1;
+
+const REQUEST_COMPLETE: u8 = 0;
+const CANT_MPX_CONN: u8 = 1;
+const UNKNOWN_ROLE: u8 = 3;
+
+/// The maximum length of the content of a record.
+const MAX_CONTENT_LENGTH: usize = 65535;
+
+/// Runs the endpoint as a FastCGI application, accepts the connections from
+/// the web server with the specified listener.
+///
+/// Only the `Responder` role is supported, and the requests on one connection
+/// are processed sequentially.
+///
+/// # Example
+///
+/// ```no_run
+/// use poem::{handler, listener::TcpListener};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), std::io::Error> {
+///     poem::cgi::run_fastcgi(TcpListener::bind("127.0.0.1:9000"), index).await
+/// }
+/// ```
+pub async fn run_fastcgi<E>(listener: impl Listener, ep: E) -> IoResult<()>
+where
+    E: IntoEndpoint,
+    E::Endpoint: 'static,
+{
+    let ep = Arc::new(ep.into_endpoint().map_to_response());
+    let mut acceptor = listener.into_acceptor().await?;
+
+    loop {
+        let (io, _, _) = acceptor.accept().await?;
+        let ep = ep.clone();
+        tokio::spawn(async move {
+            if let Err(err) = serve_connection(io, ep.as_ref()).await {
+                tracing::debug!(error = %err, "fastcgi connection error");
+            }
+        });
+    }
+}
+
+struct Record {
+    ty: u8,
+    request_id: u16,
+    content: Vec<u8>,
+}
+
+async fn read_record<R: AsyncRead + Unpin>(reader: &mut R) -> IoResult<Option<Record>> {
+    let mut header = [0; 8];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    if header[0] != VERSION {
+        return Err(IoError::new(
+            ErrorKind::InvalidData,
+            "unsupported fastcgi version",
+        ));
+    }
+
+    let request_id = u16::from_be_bytes([header[2], header[3]]);
+    let content_length = u16::from_be_bytes([header[4], header[5]]) as usize;
+    let mut content = vec![0; content_length + header[6] as usize];
+    reader.read_exact(&mut content).await?;
+    content.truncate(content_length);
+
+    Ok(Some(Record {
+        ty: header[1],
+        request_id,
+        content,
+    }))
+}
+
+async fn write_record<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    ty: u8,
+    request_id: u16,
+    content: &[u8],
+) -> IoResult<()> {
+    let [id_hi, id_lo] = request_id.to_be_bytes();
+    let [len_hi, len_lo] = (content.len() as u16).to_be_bytes();
+    writer
+        .write_all(&[VERSION, ty, id_hi, id_lo, len_hi, len_lo, 0, 0])
+        .await?;
+    writer.write_all(content).await
+}
+
+async fn write_stdout<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    request_id: u16,
+    data: &[u8],
+) -> IoResult<()> {
+    for chunk in data.chunks(MAX_CONTENT_LENGTH) {
+        write_record(writer, STDOUT, request_id, chunk).await?;
+    }
+    Ok(())
+}
+
+async fn end_request<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    request_id: u16,
+    protocol_status: u8,
+) -> IoResult<()> {
+    write_record(
+        writer,
+        END_REQUEST,
+        request_id,
+        &[0, 0, 0, 0, protocol_status, 0, 0, 0],
+    )
+    .await?;
+    writer.flush().await
+}
+
+fn read_length(data: &mut &[u8]) -> Option<usize> {
+    let first = *data.first()?;
+    if first >> 7 == 0 {
+        *data = &data[1..];
+        Some(first as usize)
+    } else {
+        let bytes = data.get(..4)?;
+        let length = u32::from_be_bytes([bytes[0] & 0x7f, bytes[1], bytes[2], bytes[3]]);
+        *data = &data[4..];
+        Some(length as usize)
+    }
+}
+
+/// Decodes the name-value pairs in the `PARAMS` and `GET_VALUES` records.
+fn decode_pairs(mut data: &[u8]) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    while !data.is_empty() {
+        let (name_length, value_length) = match (read_length(&mut data), read_length(&mut data)) {
+            (Some(name_length), Some(value_length)) => (name_length, value_length),
+            _ => break,
+        };
+        if data.len() < name_length + value_length {
+            break;
+        }
+        let name = String::from_utf8_lossy(&data[..name_length]).into_owned();
+        let value =
+            String::from_utf8_lossy(&data[name_length..name_length + value_length]).into_owned();
+        data = &data[name_length + value_length..];
+        pairs.push((name, value));
+    }
+    pairs
+}
+
+fn encode_pair(buf: &mut Vec<u8>, name: &str, value: &str) {
+    for len in [name.len(), value.len()] {
+        if len < 128 {
+            buf.push(len as u8);
+        } else {
+            buf.extend_from_slice(&(len as u32 | 0x8000_0000).to_be_bytes());
+        }
+    }
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+#[derive(Default)]
+struct PendingRequest {
+    id: u16,
+    keep_conn: bool,
+    params: Vec<u8>,
+    stdin: Vec<u8>,
+    params_done: bool,
+}
+
+async fn serve_connection<T, E>(io: T, ep: &E) -> IoResult<()>
+where
+    T: AsyncRead + AsyncWrite + Send + Unpin,
+    E: Endpoint<Output = Response>,
+{
+    let (mut reader, writer) = tokio::io::split(io);
+    let mut writer = BufWriter::new(writer);
+    let mut pending: Option<PendingRequest> = None;
+
+    while let Some(record) = read_record(&mut reader).await? {
+        match record.ty {
+            GET_VALUES => {
+                let mut content = Vec::new();
+                for (name, _) in decode_pairs(&record.content) {
+                    if name == "FCGI_MPXS_CONNS" {
+                        encode_pair(&mut content, &name, "0");
+                    }
+                }
+                write_record(&mut writer, GET_VALUES_RESULT, 0, &content).await?;
+                writer.flush().await?;
+            }
+            BEGIN_REQUEST if record.content.len() >= 3 => {
+                let role = u16::from_be_bytes([record.content[0], record.content[1]]);
+                if pending.is_some() {
+                    end_request(&mut writer, record.request_id, CANT_MPX_CONN).await?;
+                } else if role != ROLE_RESPONDER {
+                    end_request(&mut writer, record.request_id, UNKNOWN_ROLE).await?;
+                } else {
+                    pending = Some(PendingRequest {
+                        id: record.request_id,
+                        keep_conn: record.content[2] & FLAG_KEEP_CONN != 0,
+                        ..Default::default()
+                    });
+                }
+            }
+            ABORT_REQUEST => {
+                if let Some(req) = pending.take().filter(|req| req.id == record.request_id) {
+                    end_request(&mut writer, req.id, REQUEST_COMPLETE).await?;
+                    if !req.keep_conn {
+                        break;
+                    }
+                }
+            }
+            PARAMS => {
+                if let Some(req) = pending.as_mut().filter(|req| req.id == record.request_id) {
+                    if record.content.is_empty() {
+                        req.params_done = true;
+                    } else {
+                        req.params.extend_from_slice(&record.content);
+                    }
+                }
+            }
+            STDIN => {
+                let req = match pending.as_mut().filter(|req| req.id == record.request_id) {
+                    Some(req) => req,
+                    None => continue,
+                };
+                if !record.content.is_empty() {
+                    req.stdin.extend_from_slice(&record.content);
+                    continue;
+                }
+                if !req.params_done {
+                    return Err(IoError::new(
+                        ErrorKind::InvalidData,
+                        "fastcgi stdin received before params",
+                    ));
+                }
+
+                let req = pending.take().unwrap();
+                let http_req =
+                    build_request(decode_pairs(&req.params), std::io::Cursor::new(req.stdin));
+                let mut resp = ep.call(http_req).await;
+
+                write_stdout(&mut writer, req.id, &response_head(&resp)).await?;
+                let mut body = resp.take_body().into_async_read();
+                let mut buf = vec![0; 8192];
+                loop {
+                    let n = body.read(&mut buf).await?;
+                    if n == 0 {
+                        break;
+                    }
+                    write_stdout(&mut writer, req.id, &buf[..n]).await?;
+                    writer.flush().await?;
+                }
+                write_record(&mut writer, STDOUT, req.id, &[]).await?;
+                end_request(&mut writer, req.id, REQUEST_COMPLETE).await?;
+
+                if !req.keep_conn {
+                    break;
+                }
+            }
+            ty if record.request_id == 0 => {
+                write_record(&mut writer, UNKNOWN_TYPE, 0, &[ty, 0, 0, 0, 0, 0, 0, 0]).await?;
+                writer.flush().await?;
+            }
+            _ => {}
+        }
+    }
+
+    writer.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, http::Method, Request};
+
+    #[handler(internal)]
+    async fn index(req: &Request, body: String) -> String {
+        format!("{} {} {}", req.method(), req.uri(), body)
+    }
+
+    async fn request(buf: &mut Vec<u8>, id: u16, keep_conn: bool, body: &[u8]) {
+        let flags = if keep_conn { FLAG_KEEP_CONN } else { 0 };
+        write_record(buf, BEGIN_REQUEST, id, &[0, 1, flags, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+
+        let mut params = Vec::new();
+        encode_pair(&mut params, "REQUEST_METHOD", "POST");
+        encode_pair(&mut params, "REQUEST_URI", "/a?b=1");
+        encode_pair(&mut params, "QUERY_STRING", "b=1");
+        encode_pair(&mut params, "CONTENT_LENGTH", &body.len().to_string());
+        encode_pair(&mut params, "HTTP_X_LONG", &"x".repeat(200));
+        write_record(buf, PARAMS, id, &params).await.unwrap();
+        write_record(buf, PARAMS, id, &[]).await.unwrap();
+        write_record(buf, STDIN, id, body).await.unwrap();
+        write_record(buf, STDIN, id, &[]).await.unwrap();
+    }
+
+    async fn read_response(reader: &mut &[u8]) -> (u16, String) {
+        let mut stdout = Vec::new();
+        loop {
+            let record = read_record(reader).await.unwrap().unwrap();
+            match record.ty {
+                STDOUT => stdout.extend_from_slice(&record.content),
+                END_REQUEST => {
+                    assert_eq!(record.content[4], REQUEST_COMPLETE);
+                    return (record.request_id, String::from_utf8(stdout).unwrap());
+                }
+                ty => panic!("unexpected record type: {}", ty),
+            }
+        }
+    }
+
+    #[test]
+    fn pairs() {
+        let mut buf = Vec::new();
+        encode_pair(&mut buf, "a", "1");
+        encode_pair(&mut buf, &"b".repeat(200), &"2".repeat(300));
+        assert_eq!(
+            decode_pairs(&buf),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".repeat(200), "2".repeat(300))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn responder() {
+        let mut input = Vec::new();
+        request(&mut input, 1, true, b"hello").await;
+        request(&mut input, 2, false, b"world").await;
+
+        let (client, server) = tokio::io::duplex(1024 * 1024);
+        let ep = index.map_to_response();
+        let (mut client_reader, mut client_writer) = tokio::io::split(client);
+        client_writer.write_all(&input).await.unwrap();
+        serve_connection(server, &ep).await.unwrap();
+
+        let mut output = Vec::new();
+        client_reader.read_to_end(&mut output).await.unwrap();
+        let mut output = &output[..];
+
+        let expected = |body: &str| {
+            format!(
+                "Status: 200 OK\r\ncontent-type: text/plain\r\n\r\n{} /a?b=1 {}",
+                Method::POST,
+                body
+            )
+        };
+        assert_eq!(read_response(&mut output).await, (1, expected("hello")));
+        assert_eq!(read_response(&mut output).await, (2, expected("world")));
+        assert!(output.is_empty());
+    }
+}
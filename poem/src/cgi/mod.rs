@@ -0,0 +1,232 @@
+//! Run the endpoints as CGI or FastCGI applications.
+//!
+//! The same endpoint can be served by an HTTP [`Server`](crate::Server), or
+//! deployed behind a web server or a serverless platform that speaks CGI or
+//! FastCGI, without changing the application code.
+
+mod fastcgi;
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+pub use fastcgi::run_fastcgi;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Result as IoResult};
+
+use crate::{
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, Uri, Version},
+    web::RemoteAddr,
+    Body, Endpoint, EndpointExt, IntoEndpoint, Request, Response,
+};
+
+/// Runs the endpoint as a CGI application.
+///
+/// The request is read from the environment variables and the standard
+/// input, and the response is written to the standard output.
+///
+/// # Example
+///
+/// ```no_run
+/// use poem::handler;
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), std::io::Error> {
+///     poem::cgi::run(index).await
+/// }
+/// ```
+pub async fn run(ep: impl IntoEndpoint) -> IoResult<()> {
+    let ep = ep.into_endpoint().map_to_response();
+    let req = build_request(std::env::vars(), tokio::io::stdin());
+    let resp = ep.call(req).await;
+    let mut stdout = tokio::io::stdout();
+    write_response(resp, &mut stdout).await?;
+    stdout.flush().await
+}
+
+/// Creates a request from the CGI meta-variables and the request body.
+pub(crate) fn build_request(
+    vars: impl IntoIterator<Item = (String, String)>,
+    body: impl AsyncRead + Send + Unpin + 'static,
+) -> Request {
+    let mut method = Method::GET;
+    let mut version = Version::HTTP_11;
+    let mut headers = HeaderMap::new();
+    let mut path_info = None;
+    let mut request_uri = None;
+    let mut query = None;
+    let mut remote_ip = None;
+    let mut remote_port = 0;
+    let mut content_length = None;
+
+    for (name, value) in vars {
+        match name.as_str() {
+            "REQUEST_METHOD" => method = Method::from_str(&value).unwrap_or(Method::GET),
+            "SERVER_PROTOCOL" => {
+                version = match value.as_str() {
+                    "HTTP/0.9" => Version::HTTP_09,
+                    "HTTP/1.0" => Version::HTTP_10,
+                    "HTTP/2" | "HTTP/2.0" => Version::HTTP_2,
+                    _ => Version::HTTP_11,
+                }
+            }
+            "PATH_INFO" if !value.is_empty() => path_info = Some(value),
+            "REQUEST_URI" => request_uri = Some(value),
+            "QUERY_STRING" if !value.is_empty() => query = Some(value),
+            "REMOTE_ADDR" => remote_ip = value.parse::<IpAddr>().ok(),
+            "REMOTE_PORT" => remote_port = value.parse().unwrap_or_default(),
+            "CONTENT_TYPE" if !value.is_empty() => {
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(header::CONTENT_TYPE, value);
+                }
+            }
+            "CONTENT_LENGTH" if !value.is_empty() => {
+                if let Ok(length) = value.parse::<u64>() {
+                    content_length = Some(length);
+                    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+                }
+            }
+            _ => {
+                if let Some(name) = name.strip_prefix("HTTP_") {
+                    let name = name.replace('_', "-").to_ascii_lowercase();
+                    if let (Ok(name), Ok(value)) =
+                        (HeaderName::from_str(&name), HeaderValue::from_str(&value))
+                    {
+                        headers.append(name, value);
+                    }
+                }
+            }
+        }
+    }
+
+    // `PATH_INFO` is relative to the script, `REQUEST_URI` is set by the web
+    // servers that do not split the path, e.g. nginx.
+    let path = path_info
+        .or_else(|| request_uri.map(|uri| uri.split('?').next().unwrap_or_default().to_string()))
+        .unwrap_or_else(|| "/".to_string());
+    let uri = match query {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+
+    let body = match content_length {
+        Some(length) => Body::from_async_read(body.take(length)),
+        None if method == Method::GET || method == Method::HEAD => Body::empty(),
+        None => Body::from_async_read(body),
+    };
+    let mut req = Request::builder()
+        .method(method)
+        .uri(Uri::from_str(&uri).unwrap_or_default())
+        .version(version)
+        .body(body);
+    *req.headers_mut() = headers;
+    if let Some(ip) = remote_ip {
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::new(ip, remote_port).into());
+    }
+    req
+}
+
+/// Serializes the status line and headers of the CGI response.
+fn response_head(resp: &Response) -> Vec<u8> {
+    let mut head = Vec::new();
+    let status = resp.status();
+    head.extend_from_slice(
+        format!(
+            "Status: {} {}\r\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default()
+        )
+        .as_bytes(),
+    );
+    for (name, value) in resp.headers() {
+        head.extend_from_slice(name.as_str().as_bytes());
+        head.extend_from_slice(b": ");
+        head.extend_from_slice(value.as_bytes());
+        head.extend_from_slice(b"\r\n");
+    }
+    head.extend_from_slice(b"\r\n");
+    head
+}
+
+/// Writes the response in the CGI response format.
+pub(crate) async fn write_response<W: AsyncWrite + Unpin>(
+    mut resp: Response,
+    writer: &mut W,
+) -> IoResult<()> {
+    writer.write_all(&response_head(&resp)).await?;
+    tokio::io::copy(&mut resp.take_body().into_async_read(), writer).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, web::Query};
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn request() {
+        let req = build_request(
+            vars(&[
+                ("REQUEST_METHOD", "POST"),
+                ("SERVER_PROTOCOL", "HTTP/1.0"),
+                ("PATH_INFO", "/a/b"),
+                ("QUERY_STRING", "c=1"),
+                ("REMOTE_ADDR", "10.0.0.1"),
+                ("REMOTE_PORT", "1234"),
+                ("CONTENT_TYPE", "text/plain"),
+                ("CONTENT_LENGTH", "5"),
+                ("HTTP_X_CUSTOM_HEADER", "abc"),
+                ("SERVER_NAME", "localhost"),
+            ]),
+            &b"hello world"[..],
+        );
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.version(), Version::HTTP_10);
+        assert_eq!(req.uri(), "/a/b?c=1");
+        assert_eq!(req.content_type(), Some("text/plain"));
+        assert_eq!(req.headers().get("x-custom-header").unwrap(), "abc");
+        assert_eq!(
+            req.remote_addr().as_socket_addr(),
+            Some(&"10.0.0.1:1234".parse().unwrap())
+        );
+        assert_eq!(req.into_body().into_string().await.unwrap(), "hello");
+
+        let req = build_request(vars(&[("REQUEST_URI", "/x/y?z=1")]), &b""[..]);
+        assert_eq!(req.method(), Method::GET);
+        assert_eq!(req.uri(), "/x/y");
+    }
+
+    #[tokio::test]
+    async fn response() {
+        #[derive(serde::Deserialize)]
+        struct Params {
+            name: String,
+        }
+
+        #[handler(internal)]
+        fn index(Query(params): Query<Params>) -> String {
+            format!("hello {}", params.name)
+        }
+
+        let req = build_request(vars(&[("QUERY_STRING", "name=poem")]), &b""[..]);
+        let mut output = Vec::new();
+        write_response(index.call(req).await, &mut output)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Status: 200 OK\r\ncontent-type: text/plain\r\n\r\nhello poem"
+        );
+    }
+}
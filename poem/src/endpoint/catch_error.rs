@@ -0,0 +1,50 @@
+use std::{any::Any, future::Future, marker::PhantomData};
+
+use crate::{error::ErrorSource, Endpoint, IntoResponse, Request, Response};
+
+/// Endpoint for the [`catch_error`](super::EndpointExt::catch_error) method.
+pub struct CatchError<E, F, ErrType> {
+    inner: E,
+    f: F,
+    _mark: PhantomData<fn() -> ErrType>,
+}
+
+impl<E, F, ErrType> CatchError<E, F, ErrType> {
+    #[inline]
+    pub(crate) fn new(inner: E, f: F) -> CatchError<E, F, ErrType> {
+        Self {
+            inner,
+            f,
+            _mark: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<E, F, Fut, ErrType, R> Endpoint for CatchError<E, F, ErrType>
+where
+    E: Endpoint,
+    F: Fn(ErrType) -> Fut + Send + Sync,
+    Fut: Future<Output = R> + Send,
+    ErrType: Any + Send,
+    R: IntoResponse,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let mut resp = self.inner.call(req).await.into_response();
+        let is_caught = matches!(
+            resp.extensions().get::<ErrorSource>(),
+            Some(ErrorSource(source)) if source.is::<ErrType>()
+        );
+        if !is_caught {
+            return resp;
+        }
+
+        let source = resp.extensions_mut().remove::<ErrorSource>().unwrap();
+        match source.0.downcast::<ErrType>() {
+            Ok(err) => (self.f)(*err).await.into_response(),
+            Err(_) => unreachable!(),
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use std::{future::Future, sync::Arc};
+use std::{any::Any, future::Future, sync::Arc};
 
-use super::{After, AndThen, Before, MapErr, MapOk, MapToResponse, MapToResult};
+use super::{After, AndThen, Before, CatchError, MapErr, MapOk, MapToResponse, MapToResult};
 use crate::{
     endpoint::Around,
     middleware::{AddData, AddDataEndpoint},
@@ -455,6 +455,56 @@ pub trait EndpointExt: IntoEndpoint {
     {
         MapErr::new(self.into_endpoint(), f)
     }
+
+    /// Calls `f` to create the response if this endpoint returns an [`Error`]
+    /// converted from an error of type `ErrType`, the other responses are
+    /// returned unchanged.
+    ///
+    /// [`Error`]: crate::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fmt::{self, Display, Formatter};
+    ///
+    /// use poem::{
+    ///     handler, http::StatusCode, Endpoint, EndpointExt, IntoResponse, Request, Result,
+    /// };
+    ///
+    /// #[derive(Debug)]
+    /// struct NotFound(String);
+    ///
+    /// impl Display for NotFound {
+    ///     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "{} not found", self.0)
+    ///     }
+    /// }
+    ///
+    /// #[handler]
+    /// fn index() -> Result<&'static str> {
+    ///     Err(NotFound("user".to_string()).into())
+    /// }
+    ///
+    /// let ep = index.catch_error(|err: NotFound| async move {
+    ///     err.to_string().with_status(StatusCode::NOT_FOUND)
+    /// });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = ep.call(Request::default()).await;
+    /// assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    /// assert_eq!(resp.into_body().into_string().await.unwrap(), "user not found");
+    /// # });
+    /// ```
+    fn catch_error<F, Fut, ErrType, R>(self, f: F) -> CatchError<Self::Endpoint, F, ErrType>
+    where
+        F: Fn(ErrType) -> Fut + Send + Sync,
+        Fut: Future<Output = R> + Send,
+        ErrType: Any + Send,
+        R: IntoResponse,
+        Self: Sized,
+    {
+        CatchError::new(self.into_endpoint(), f)
+    }
 }
 
 impl<T: IntoEndpoint> EndpointExt for T {}
@@ -479,6 +529,18 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_generic_handler() {
+        #[handler(internal)]
+        fn index<T: ToString + Send + Sync + 'static>(data: web::Data<&T>) -> String {
+            data.0.to_string()
+        }
+
+        let ep = index::<i32>::default().data(100);
+        let mut resp = ep.call(Request::default()).await;
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "100");
+    }
+
     #[tokio::test]
     async fn test_before() {
         assert_eq!(
@@ -607,6 +669,45 @@ mod test {
             .unwrap_err();
         assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
     }
+
+    #[tokio::test]
+    async fn test_catch_error() {
+        #[derive(Debug)]
+        struct CustomError(i32);
+
+        impl std::fmt::Display for CustomError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "custom error {}", self.0)
+            }
+        }
+
+        let ep = make_sync(|req| match req.uri().path() {
+            "/custom" => Err(CustomError(1).into()),
+            "/other" => Err(Error::new(StatusCode::BAD_REQUEST)),
+            _ => Ok("ok"),
+        })
+        .catch_error(|err: CustomError| async move {
+            format!("caught {}", err.0).with_status(StatusCode::CONFLICT)
+        });
+
+        let call = |path: &'static str| {
+            ep.call(
+                Request::builder()
+                    .uri(http::Uri::from_static(path))
+                    .finish(),
+            )
+        };
+
+        let resp = call("/").await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "ok");
+
+        let resp = call("/custom").await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "caught 1");
+
+        assert_eq!(call("/other").await.status(), StatusCode::BAD_REQUEST);
+    }
 }
 
 /// Represents a type that can convert into endpoint.
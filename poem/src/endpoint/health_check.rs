@@ -0,0 +1,353 @@
+use std::{collections::BTreeMap, fmt::Display, future::Future, sync::Arc, time::Duration};
+
+use futures_util::future::{join_all, BoxFuture, FutureExt};
+use serde::Serialize;
+
+use crate::{
+    http::{Method, StatusCode},
+    web::Json,
+    Endpoint, IntoResponse, Request, Response,
+};
+
+type Probe = Arc<dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+/// The status of a health check.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The check passed.
+    Pass,
+    /// The check failed.
+    Fail,
+}
+
+/// The result of a probe of the [`HealthCheck`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct HealthCheckResult {
+    /// The status of the probe.
+    pub status: HealthStatus,
+    /// The error of the failed probe, it is only reported if
+    /// [`HealthCheck::expose_errors`] is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The aggregated status of the probes of the [`HealthCheck`].
+///
+/// It is serialized as JSON, such as
+/// `{"status":"fail","checks":{"db":{"status":"fail"}}}`, and the response is `503 Service Unavailable` if any of the probes
+/// failed.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// The aggregated status, it fails if any of the probes failed.
+    pub status: HealthStatus,
+    /// The results of the probes by name.
+    pub checks: BTreeMap<String, HealthCheckResult>,
+}
+
+impl IntoResponse for HealthReport {
+    fn into_response(self) -> Response {
+        let status = match self.status {
+            HealthStatus::Pass => StatusCode::OK,
+            HealthStatus::Fail => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        Json(self).with_status(status).into_response()
+    }
+}
+
+#[derive(Clone, Default)]
+struct Inner {
+    liveness: Vec<(String, Probe)>,
+    readiness: Vec<(String, Probe)>,
+    timeout: Option<Duration>,
+    expose_errors: bool,
+}
+
+/// An endpoint that serves the liveness and readiness probes, such as the
+/// probes of Kubernetes.
+///
+/// It serves `GET /healthz` with the liveness probes and `GET /readyz` with
+/// the readiness probes, the paths are relative to where the endpoint is
+/// nested. The probes run concurrently, and the response is a
+/// [`HealthReport`].
+///
+/// The errors of the failed probes are logged, but are not included in the
+/// responses unless [`HealthCheck::expose_errors`] is enabled, since they may
+/// reveal the details of the internal services.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     endpoint::HealthCheck,
+///     http::{StatusCode, Uri},
+///     Endpoint, Request, Route,
+/// };
+///
+/// let health = HealthCheck::new().readiness_check("db", || async {
+///     // ping the database
+///     Err::<(), _>("connection refused")
+/// });
+/// let app = Route::new().nest("/", health);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = app
+///     .call(Request::builder().uri(Uri::from_static("/healthz")).finish())
+///     .await;
+/// assert_eq!(resp.status(), StatusCode::OK);
+///
+/// let resp = app
+///     .call(Request::builder().uri(Uri::from_static("/readyz")).finish())
+///     .await;
+/// assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct HealthCheck(Arc<Inner>);
+
+impl HealthCheck {
+    /// Create a `HealthCheck` endpoint without any probes, which always
+    /// passes.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a probe that is served by `/healthz`, it should only check
+    /// whether the process is working, the failure usually restarts the
+    /// process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a liveness probe with the same name has been added.
+    #[must_use]
+    pub fn liveness_check<F, Fut, E>(self, name: impl Into<String>, f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.add_probe(false, name.into(), f)
+    }
+
+    /// Adds a probe that is served by `/readyz`, it checks whether the
+    /// dependencies, such as the databases, are available to serve the
+    /// requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a readiness probe with the same name has been added.
+    #[must_use]
+    pub fn readiness_check<F, Fut, E>(self, name: impl Into<String>, f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.add_probe(true, name.into(), f)
+    }
+
+    /// Sets the timeout of each probe, the probes that do not complete in
+    /// time fail.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        let mut inner = self.into_inner();
+        inner.timeout = Some(timeout);
+        Self(Arc::new(inner))
+    }
+
+    /// Sets whether the errors of the failed probes are included in the
+    /// responses, default is `false`.
+    #[must_use]
+    pub fn expose_errors(self, expose_errors: bool) -> Self {
+        let mut inner = self.into_inner();
+        inner.expose_errors = expose_errors;
+        Self(Arc::new(inner))
+    }
+
+    /// Runs the liveness probes.
+    pub async fn check_liveness(&self) -> HealthReport {
+        self.run(&self.0.liveness).await
+    }
+
+    /// Runs the readiness probes.
+    pub async fn check_readiness(&self) -> HealthReport {
+        self.run(&self.0.readiness).await
+    }
+
+    fn into_inner(self) -> Inner {
+        Arc::try_unwrap(self.0).unwrap_or_else(|inner| (*inner).clone())
+    }
+
+    fn add_probe<F, Fut, E>(self, readiness: bool, name: String, f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let probe: Probe =
+            Arc::new(move || f().map(|res| res.map_err(|err| err.to_string())).boxed());
+        let mut inner = self.into_inner();
+        let (kind, probes) = match readiness {
+            true => ("readiness", &mut inner.readiness),
+            false => ("liveness", &mut inner.liveness),
+        };
+        assert!(
+            probes.iter().all(|(exists, _)| *exists != name),
+            "the {} probe `{}` has been added",
+            kind,
+            name
+        );
+        probes.push((name, probe));
+        Self(Arc::new(inner))
+    }
+
+    async fn run(&self, probes: &[(String, Probe)]) -> HealthReport {
+        let timeout = self.0.timeout;
+        let expose_errors = self.0.expose_errors;
+        let results = join_all(probes.iter().map(|(name, probe)| async move {
+            let fut = probe();
+            let res = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, fut)
+                    .await
+                    .unwrap_or_else(|_| Err("timeout".to_string())),
+                None => fut.await,
+            };
+            let result = match res {
+                Ok(()) => HealthCheckResult {
+                    status: HealthStatus::Pass,
+                    error: None,
+                },
+                Err(err) => {
+                    tracing::warn!(probe = %name, error = %err, "health check failed");
+                    HealthCheckResult {
+                        status: HealthStatus::Fail,
+                        error: expose_errors.then(|| err),
+                    }
+                }
+            };
+            (name.clone(), result)
+        }))
+        .await;
+
+        let status = if results
+            .iter()
+            .all(|(_, result)| result.status == HealthStatus::Pass)
+        {
+            HealthStatus::Pass
+        } else {
+            HealthStatus::Fail
+        };
+        HealthReport {
+            status,
+            checks: results.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Endpoint for HealthCheck {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if req.method() != Method::GET {
+            return StatusCode::METHOD_NOT_ALLOWED.into();
+        }
+        match req.uri().path() {
+            "/healthz" => self.check_liveness().await.into_response(),
+            "/readyz" => self.check_readiness().await.into_response(),
+            _ => StatusCode::NOT_FOUND.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, http::Uri, Route};
+
+    async fn get(
+        app: &impl Endpoint<Output = Response>,
+        uri: &'static str,
+    ) -> (StatusCode, serde_json::Value) {
+        let resp = app
+            .call(Request::builder().uri(Uri::from_static(uri)).finish())
+            .await;
+        let status = resp.status();
+        let body = resp.into_body().into_vec().await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn health_check() {
+        #[handler(internal)]
+        fn index() {}
+
+        let health = HealthCheck::new()
+            .liveness_check("worker", || async { Ok::<_, String>(()) })
+            .readiness_check("db", || async { Ok::<_, String>(()) })
+            .readiness_check("queue", || async { Err("connection refused") })
+            .readiness_check("cache", || async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok::<_, String>(())
+            })
+            .timeout(Duration::from_millis(50))
+            .expose_errors(true);
+        let app = Route::new().at("/index", index).nest("/", health);
+
+        assert_eq!(get(&app, "/index").await.0, StatusCode::OK);
+        assert_eq!(
+            get(&app, "/healthz").await,
+            (
+                StatusCode::OK,
+                serde_json::json!({
+                    "status": "pass",
+                    "checks": {
+                        "worker": { "status": "pass" },
+                    },
+                })
+            )
+        );
+        assert_eq!(
+            get(&app, "/readyz").await,
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                serde_json::json!({
+                    "status": "fail",
+                    "checks": {
+                        "db": { "status": "pass" },
+                        "queue": { "status": "fail", "error": "connection refused" },
+                        "cache": { "status": "fail", "error": "timeout" },
+                    },
+                })
+            )
+        );
+        assert_eq!(get(&app, "/other").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn hide_errors() {
+        let health = HealthCheck::new().readiness_check("db", || async { Err("10.0.0.1:5432") });
+
+        assert_eq!(
+            get(&health, "/readyz").await,
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                serde_json::json!({
+                    "status": "fail",
+                    "checks": {
+                        "db": { "status": "fail" },
+                    },
+                })
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the readiness probe `db` has been added")]
+    fn duplicate_name() {
+        let _ = HealthCheck::new()
+            .liveness_check("db", || async { Ok::<_, String>(()) })
+            .readiness_check("db", || async { Ok::<_, String>(()) })
+            .readiness_check("db", || async { Ok::<_, String>(()) });
+    }
+}
@@ -4,16 +4,20 @@ mod after;
 mod and_then;
 mod around;
 mod before;
+mod catch_error;
 #[allow(clippy::module_inception)]
 mod endpoint;
-#[cfg(feature = "staticfiles")]
-mod files;
+mod health_check;
 mod map_err;
 mod map_ok;
 mod map_to_response;
 mod map_to_result;
 #[cfg(feature = "prometheus")]
 mod prometheus_exporter;
+#[cfg(feature = "proxy")]
+mod proxy;
+#[cfg(feature = "staticfiles")]
+mod static_files;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 
@@ -21,14 +25,19 @@ pub use after::After;
 pub use and_then::AndThen;
 pub use around::Around;
 pub use before::Before;
+pub use catch_error::CatchError;
 pub use endpoint::{make, make_sync, BoxEndpoint, Endpoint, EndpointExt, IntoEndpoint};
-#[cfg(feature = "staticfiles")]
-pub use files::Files;
+pub use health_check::{HealthCheck, HealthCheckResult, HealthReport, HealthStatus};
 pub use map_err::MapErr;
 pub use map_ok::MapOk;
 pub use map_to_response::MapToResponse;
 pub use map_to_result::MapToResult;
 #[cfg(feature = "prometheus")]
 pub use prometheus_exporter::PrometheusExporter;
+#[cfg(feature = "proxy")]
+pub use proxy::Proxy;
+#[cfg(feature = "staticfiles")]
+#[allow(deprecated)]
+pub use static_files::{Files, StaticFile, StaticFiles};
 #[cfg(feature = "tower-compat")]
-pub use tower_compat::TowerCompatExt;
+pub use tower_compat::{TowerCompatEndpoint, TowerCompatExt, TowerServiceCompat};
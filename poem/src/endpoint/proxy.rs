@@ -0,0 +1,321 @@
+use std::time::Duration;
+
+use hyper::client::HttpConnector;
+use hyper_rustls::HttpsConnector;
+use tokio_rustls::rustls::ClientConfig;
+
+use crate::{
+    http::{
+        header::{self, HeaderName},
+        uri::{Authority, PathAndQuery, Scheme},
+        HeaderMap, HeaderValue, StatusCode, Uri, Version,
+    },
+    web::ConnectionInfo,
+    Endpoint, Request, Response,
+};
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The headers that are only meaningful for a single connection, which are
+/// not forwarded.
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// An endpoint that forwards the requests to an upstream server, so poem can
+/// front the existing services, e.g. during an incremental migration.
+///
+/// The path and query of the request are appended to the path of the
+/// upstream URL, when the endpoint is nested, the prefix is not forwarded.
+/// The bodies are streamed in both directions, including the trailers, the
+/// `Host` header is set to the upstream server, and the `X-Forwarded-For`,
+/// `X-Forwarded-Host` and `X-Forwarded-Proto` headers are added, the
+/// `X-Forwarded-Proto` is `https` if the request is received over TLS. If the
+/// upstream server cannot be reached, the response is `502 Bad Gateway`, and
+/// if it does not respond in time, the response is `504 Gateway Timeout`.
+///
+/// The `https` upstream servers are verified with the Mozilla root
+/// certificates.
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::Proxy, Route};
+///
+/// // `/legacy/users?id=1` is forwarded to `http://127.0.0.1:8080/api/users?id=1`
+/// let app = Route::new().nest("/legacy", Proxy::new("http://127.0.0.1:8080/api"));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
+pub struct Proxy {
+    scheme: Scheme,
+    authority: Authority,
+    base_path: String,
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
+    timeout: Duration,
+}
+
+fn create_client(connect_timeout: Duration) -> hyper::Client<HttpsConnector<HttpConnector>> {
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+    http.set_connect_timeout(Some(connect_timeout));
+
+    let mut config = ClientConfig::new();
+    config
+        .root_store
+        .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    hyper::Client::builder().build(HttpsConnector::from((http, config)))
+}
+
+impl Proxy {
+    /// Create a `Proxy` endpoint that forwards the requests to the upstream
+    /// URL.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `upstream` is not an absolute `http` or `https` URL.
+    pub fn new(upstream: impl AsRef<str>) -> Self {
+        let upstream: Uri = upstream.as_ref().parse().expect("invalid upstream url");
+        let parts = upstream.into_parts();
+        let scheme = parts.scheme.expect("the upstream url must be absolute");
+        assert!(
+            scheme == Scheme::HTTP || scheme == Scheme::HTTPS,
+            "only the http and https upstreams are supported"
+        );
+
+        Self {
+            scheme,
+            authority: parts.authority.expect("the upstream url must be absolute"),
+            base_path: parts
+                .path_and_query
+                .map(|path| path.path().trim_end_matches('/').to_string())
+                .unwrap_or_default(),
+            client: create_client(DEFAULT_CONNECT_TIMEOUT),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout of connecting to the upstream server, default is 10
+    /// seconds.
+    #[must_use]
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        Self {
+            client: create_client(timeout),
+            ..self
+        }
+    }
+
+    /// Sets the timeout of waiting for the response headers from the upstream
+    /// server, default is 60 seconds.
+    ///
+    /// The body is streamed without a timeout.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    fn upstream_uri(&self, uri: &Uri) -> Option<Uri> {
+        let path = match uri.query() {
+            Some(query) => format!("{}{}?{}", self.base_path, uri.path(), query),
+            None => format!("{}{}", self.base_path, uri.path()),
+        };
+        Uri::builder()
+            .scheme(self.scheme.clone())
+            .authority(self.authority.clone())
+            .path_and_query(path.parse::<PathAndQuery>().ok()?)
+            .build()
+            .ok()
+    }
+}
+
+/// Removes the hop-by-hop headers, including the headers listed in the
+/// `Connection` header.
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let connection_headers = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect::<Vec<_>>();
+    for name in connection_headers {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP_HEADERS {
+        // `TE: trailers` indicates that the client accepts the trailers
+        if *name == "te" && headers.get(header::TE).map(HeaderValue::as_bytes) == Some(b"trailers")
+        {
+            continue;
+        }
+        headers.remove(*name);
+    }
+}
+
+#[async_trait::async_trait]
+impl Endpoint for Proxy {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let uri = match self.upstream_uri(req.uri()) {
+            Some(uri) => uri,
+            None => return StatusCode::BAD_REQUEST.into(),
+        };
+
+        let mut headers = std::mem::take(req.headers_mut());
+        remove_hop_by_hop_headers(&mut headers);
+        if let Some(host) = headers.remove(header::HOST) {
+            headers.insert("x-forwarded-host", host);
+        }
+        if let Ok(host) = HeaderValue::from_str(self.authority.as_str()) {
+            headers.insert(header::HOST, host);
+        }
+        if let Some(addr) = req.remote_addr().as_socket_addr() {
+            let forwarded_for = match headers
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+            {
+                Some(forwarded_for) => format!("{}, {}", forwarded_for, addr.ip()),
+                None => addr.ip().to_string(),
+            };
+            if let Ok(forwarded_for) = HeaderValue::from_str(&forwarded_for) {
+                headers.insert("x-forwarded-for", forwarded_for);
+            }
+        }
+        let tls = req
+            .extensions()
+            .get::<ConnectionInfo>()
+            .map(|info| info.tls)
+            .unwrap_or_default();
+        headers.insert(
+            "x-forwarded-proto",
+            HeaderValue::from_static(if tls { "https" } else { "http" }),
+        );
+
+        let mut upstream_req = hyper::Request::new(req.take_body().into());
+        *upstream_req.method_mut() = req.method().clone();
+        *upstream_req.uri_mut() = uri;
+        *upstream_req.version_mut() = Version::HTTP_11;
+        *upstream_req.headers_mut() = headers;
+
+        match tokio::time::timeout(self.timeout, self.client.request(upstream_req)).await {
+            Ok(Ok(mut resp)) => {
+                remove_hop_by_hop_headers(resp.headers_mut());
+                resp.into()
+            }
+            Ok(Err(err)) => {
+                tracing::warn!(error = %err, upstream = %self.authority, "failed to forward the request");
+                StatusCode::BAD_GATEWAY.into()
+            }
+            Err(_) => {
+                tracing::warn!(upstream = %self.authority, "the upstream server did not respond in time");
+                StatusCode::GATEWAY_TIMEOUT.into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler,
+        listener::{Acceptor, Listener, TcpListener},
+        web::Json,
+        EndpointExt, Route, Server,
+    };
+
+    #[tokio::test]
+    async fn proxy() {
+        #[handler(internal)]
+        async fn echo(req: &Request, body: String) -> Json<serde_json::Value> {
+            let header = |name: &str| {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToString::to_string)
+            };
+            Json(serde_json::json!({
+                "method": req.method().as_str(),
+                "uri": req.uri().to_string(),
+                "host": header("host"),
+                "forwarded_host": header("x-forwarded-host"),
+                "forwarded_proto": header("x-forwarded-proto"),
+                "connection": header("x-custom"),
+                "body": body,
+            }))
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(echo));
+
+        let app = Route::new().nest("/legacy", Proxy::new(format!("http://{}/api/", addr)));
+        let mut req = Request::builder()
+            .method(crate::http::Method::POST)
+            .uri(Uri::from_static("http://example.com/legacy/users?id=1"))
+            .header(header::HOST, "example.com")
+            .header(header::CONNECTION, "x-custom")
+            .header("x-custom", "1")
+            .body("hello");
+        // received over TLS, the scheme of the uri is not trusted
+        req.extensions_mut().insert(ConnectionInfo {
+            tls: true,
+            ..Default::default()
+        });
+        let resp = app.map_to_response().call(req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let value: serde_json::Value =
+            serde_json::from_slice(&resp.into_body().into_vec().await.unwrap()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "method": "POST",
+                "uri": "/api/users?id=1",
+                "host": addr.to_string(),
+                "forwarded_host": "example.com",
+                "forwarded_proto": "https",
+                "connection": null,
+                "body": "hello",
+            })
+        );
+
+        // the upstream is unreachable
+        let resp = Proxy::new("http://127.0.0.1:1")
+            .call(Request::default())
+            .await;
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        #[handler(internal)]
+        async fn slow() {
+            futures_util::future::pending::<()>().await;
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(slow));
+
+        let resp = Proxy::new(format!("http://{}", addr))
+            .timeout(Duration::from_millis(100))
+            .call(Request::default())
+            .await;
+        assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+}
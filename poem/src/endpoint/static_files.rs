@@ -0,0 +1,591 @@
+use std::{
+    ffi::OsStr,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use askama::Template;
+use headers::{ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch, IfRange, LastModified};
+use mime::Mime;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+
+use crate::{
+    http::{header, HeaderValue, Method, StatusCode},
+    Body, Endpoint, Request, Response,
+};
+
+#[derive(Template)]
+#[template(
+    ext = "html",
+    source = r#"
+<html>
+    <head>
+        <title>Index of {{ path }}</title>
+    </head>
+    <body>
+        <h1>Index of /{{ path }}</h1>
+        <ul>
+            {% for file in files %}
+            <li>
+                {% if file.is_dir %} 
+                <a href="{{ file.url }}">{{ file.filename | e }}/</a>
+                {% else %}
+                <a href="{{ file.url }}">{{ file.filename | e }}</a>
+                {% endif %}
+            </li>
+            {% endfor %}
+        </ul>
+    </body>
+    </html>
+"#
+)]
+struct DirectoryTemplate<'a> {
+    path: &'a str,
+    files: Vec<FileRef>,
+}
+
+struct FileRef {
+    url: String,
+    filename: String,
+    is_dir: bool,
+}
+
+/// Static files handling service.
+///
+/// The files are served with the MIME type guessed from the extension, and
+/// support the conditional requests with `ETag` and `Last-Modified`, and the
+/// `Range` requests.
+#[cfg_attr(docsrs, doc(cfg(feature = "staticfiles")))]
+pub struct StaticFiles {
+    path: PathBuf,
+    show_files_listing: bool,
+    index_file: Option<String>,
+    prefer_utf8: bool,
+}
+
+/// Static files handling service.
+#[cfg_attr(docsrs, doc(cfg(feature = "staticfiles")))]
+#[deprecated(note = "use `StaticFiles` instead")]
+pub type Files = StaticFiles;
+
+impl StaticFiles {
+    /// Create new static files service for a specified base directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{endpoint::StaticFiles, Route};
+    ///
+    /// let app = Route::new().nest(
+    ///     "/files",
+    ///     StaticFiles::new("/etc/www")
+    ///         .show_files_listing()
+    ///         .index_file("index.html"),
+    /// );
+    /// ```
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            show_files_listing: false,
+            index_file: None,
+            prefer_utf8: true,
+        }
+    }
+
+    /// Show files listing for directories.
+    ///
+    /// By default show files listing is disabled.
+    pub fn show_files_listing(self) -> Self {
+        Self {
+            show_files_listing: true,
+            ..self
+        }
+    }
+
+    /// Set index file
+    ///
+    /// Shows specific index file for directories instead of showing files
+    /// listing.
+    ///
+    /// If the index file is not found, files listing is shown as a fallback if
+    /// [`StaticFiles::show_files_listing`] is set.
+    pub fn index_file(self, index: impl Into<String>) -> Self {
+        Self {
+            index_file: Some(index.into()),
+            ..self
+        }
+    }
+
+    /// Specifies whether text responses should signal a UTF-8 encoding.
+    ///
+    /// Default is `true`.
+    pub fn prefer_utf8(self, value: bool) -> Self {
+        Self {
+            prefer_utf8: value,
+            ..self
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Endpoint for StaticFiles {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return StatusCode::METHOD_NOT_ALLOWED.into();
+        }
+
+        let path = req
+            .uri()
+            .path()
+            .trim_start_matches('/')
+            .trim_end_matches('/');
+
+        let path = match percent_encoding::percent_decode_str(path).decode_utf8() {
+            Ok(path) => path,
+            Err(_) => return StatusCode::BAD_REQUEST.into(),
+        };
+
+        let mut file_path = self.path.clone();
+        for p in Path::new(&*path) {
+            if p == OsStr::new(".") {
+                continue;
+            } else if p == OsStr::new("..") {
+                file_path.pop();
+            } else {
+                file_path.push(p);
+            }
+        }
+
+        if !file_path.starts_with(&self.path) {
+            return StatusCode::FORBIDDEN.into();
+        }
+
+        if !file_path.exists() {
+            return StatusCode::NOT_FOUND.into();
+        }
+
+        if file_path.is_file() {
+            create_file_response(&req, &file_path, self.prefer_utf8).await
+        } else {
+            if let Some(index_file) = &self.index_file {
+                let index_path = file_path.join(index_file);
+                if index_path.is_file() {
+                    return create_file_response(&req, &index_path, self.prefer_utf8).await;
+                }
+            }
+
+            if self.show_files_listing {
+                let read_dir = match file_path.read_dir() {
+                    Ok(d) => d,
+                    Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into(),
+                };
+                let mut template = DirectoryTemplate {
+                    path: &path,
+                    files: Vec::new(),
+                };
+
+                for res in read_dir {
+                    let entry = match res {
+                        Ok(entry) => entry,
+                        Err(err) => {
+                            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into()
+                        }
+                    };
+
+                    if let Some(filename) = entry.file_name().to_str() {
+                        let mut base_url = req.original_uri().path().to_string();
+                        if !base_url.ends_with('/') {
+                            base_url.push('/');
+                        }
+                        template.files.push(FileRef {
+                            url: format!("{}{}", base_url, filename),
+                            filename: filename.to_string(),
+                            is_dir: entry.path().is_dir(),
+                        });
+                    }
+                }
+
+                let html = match template.render() {
+                    Ok(html) => html,
+                    Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into(),
+                };
+                Response::builder()
+                    .header(header::CONTENT_TYPE, mime::TEXT_HTML_UTF_8.as_ref())
+                    .body(Body::from_string(html))
+            } else {
+                StatusCode::NOT_FOUND.into()
+            }
+        }
+    }
+}
+
+/// Single static file handling service.
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::StaticFile, Route};
+///
+/// let app = Route::new().at("/favicon.ico", StaticFile::new("/etc/www/favicon.ico"));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "staticfiles")))]
+pub struct StaticFile {
+    path: PathBuf,
+    prefer_utf8: bool,
+}
+
+impl StaticFile {
+    /// Create new static file service for a specified file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            prefer_utf8: true,
+        }
+    }
+
+    /// Specifies whether text responses should signal a UTF-8 encoding.
+    ///
+    /// Default is `true`.
+    pub fn prefer_utf8(self, value: bool) -> Self {
+        Self {
+            prefer_utf8: value,
+            ..self
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Endpoint for StaticFile {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return StatusCode::METHOD_NOT_ALLOWED.into();
+        }
+        if !self.path.is_file() {
+            return StatusCode::NOT_FOUND.into();
+        }
+        create_file_response(&req, &self.path, self.prefer_utf8).await
+    }
+}
+
+/// Parses the `Range` header, only a single range is supported, returns
+/// `Some(None)` if the range is not satisfiable.
+fn parse_range(value: &str, len: u64) -> Option<Option<(u64, u64)>> {
+    let value = value.trim().strip_prefix("bytes=")?;
+    if value.contains(',') {
+        return None;
+    }
+    let (start, end) = value.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        let suffix = end.parse::<u64>().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(None);
+        }
+        (len.saturating_sub(suffix), len - 1)
+    } else {
+        let start = start.parse::<u64>().ok()?;
+        let end = match end {
+            "" => u64::MAX,
+            end => end.parse::<u64>().ok()?,
+        };
+        if start > end {
+            return None;
+        }
+        if start >= len {
+            return Some(None);
+        }
+        (start, end.min(len - 1))
+    };
+    Some(Some(range))
+}
+
+async fn create_file_response(req: &Request, path: &Path, prefer_utf8: bool) -> Response {
+    let guess = mime_guess::from_path(path);
+    let mut file = match File::open(path).await {
+        Ok(file) => file,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into(),
+    };
+    let metadata = match file.metadata().await {
+        Ok(metadata) => metadata,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into(),
+    };
+    let len = metadata.len();
+
+    let modified = metadata.modified().ok();
+    let last_modified = modified.map(LastModified::from);
+    let etag = modified
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .and_then(|modified| {
+            format!("W/\"{:x}-{:x}\"", len, modified.as_secs())
+                .parse::<ETag>()
+                .ok()
+        });
+
+    let mut resp = Response::builder()
+        .header(header::ACCEPT_RANGES, "bytes")
+        .finish();
+    if let Some(etag) = etag.clone() {
+        resp.headers_mut().typed_insert(etag);
+    }
+    if let Some(last_modified) = last_modified {
+        resp.headers_mut().typed_insert(last_modified);
+    }
+
+    // conditional requests
+    let not_modified = match (req.headers().typed_get::<IfNoneMatch>(), &etag) {
+        (Some(if_none_match), Some(etag)) => !if_none_match.precondition_passes(etag),
+        (Some(_), None) => false,
+        (None, _) => match (req.headers().typed_get::<IfModifiedSince>(), modified) {
+            (Some(if_modified_since), Some(modified)) => !if_modified_since.is_modified(modified),
+            _ => false,
+        },
+    };
+    if not_modified {
+        resp.set_status(StatusCode::NOT_MODIFIED);
+        return resp;
+    }
+
+    if let Some(mut mime) = guess.first() {
+        if prefer_utf8 {
+            mime = equiv_utf8_text(mime);
+        }
+        if let Ok(header_value) = HeaderValue::from_str(mime.as_ref()) {
+            resp.headers_mut()
+                .insert(header::CONTENT_TYPE, header_value);
+        }
+    }
+
+    // range requests
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|_| match req.headers().typed_get::<IfRange>() {
+            Some(if_range) => !if_range.is_modified(etag.as_ref(), last_modified.as_ref()),
+            None => true,
+        })
+        .and_then(|value| parse_range(value, len));
+    let (start, end) = match range {
+        Some(Some((start, end))) => {
+            resp.set_status(StatusCode::PARTIAL_CONTENT);
+            resp.headers_mut().insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, len)).unwrap(),
+            );
+            (start, end + 1)
+        }
+        Some(None) => {
+            resp.set_status(StatusCode::RANGE_NOT_SATISFIABLE);
+            resp.headers_mut().insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes */{}", len)).unwrap(),
+            );
+            resp.headers_mut().remove(header::CONTENT_TYPE);
+            return resp;
+        }
+        None => (0, len),
+    };
+
+    resp.headers_mut()
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(end - start));
+    if req.method() == Method::HEAD {
+        return resp;
+    }
+
+    if start > 0 {
+        if let Err(err) = file.seek(SeekFrom::Start(start)).await {
+            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into();
+        }
+    }
+    resp.set_body(Body::from_async_read(file.take(end - start)));
+    resp
+}
+
+fn equiv_utf8_text(ct: Mime) -> Mime {
+    if ct == mime::APPLICATION_JAVASCRIPT {
+        return mime::APPLICATION_JAVASCRIPT_UTF_8;
+    }
+
+    if ct == mime::TEXT_HTML {
+        return mime::TEXT_HTML_UTF_8;
+    }
+
+    if ct == mime::TEXT_CSS {
+        return mime::TEXT_CSS_UTF_8;
+    }
+
+    if ct == mime::TEXT_PLAIN {
+        return mime::TEXT_PLAIN_UTF_8;
+    }
+
+    if ct == mime::TEXT_CSV {
+        return mime::TEXT_CSV_UTF_8;
+    }
+
+    if ct == mime::TEXT_TAB_SEPARATED_VALUES {
+        return mime::TEXT_TAB_SEPARATED_VALUES_UTF_8;
+    }
+
+    ct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EndpointExt, Route};
+
+    fn test_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("poem-static-files-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.txt"), "0123456789").unwrap();
+        std::fs::write(dir.join("sub/index.html"), "<html></html>").unwrap();
+        dir
+    }
+
+    async fn get(
+        ep: &impl Endpoint<Output = Response>,
+        uri: &str,
+        headers: &[(&str, &str)],
+    ) -> Response {
+        let mut req = Request::builder().uri(uri.parse().unwrap());
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        ep.call(req.finish()).await
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-4", 10), Some(Some((0, 4))));
+        assert_eq!(parse_range("bytes=5-", 10), Some(Some((5, 9))));
+        assert_eq!(parse_range("bytes=-3", 10), Some(Some((7, 9))));
+        assert_eq!(parse_range("bytes=5-100", 10), Some(Some((5, 9))));
+        assert_eq!(parse_range("bytes=10-", 10), Some(None));
+        assert_eq!(parse_range("bytes=4-2", 10), None);
+        assert_eq!(parse_range("bytes=0-1,3-4", 10), None);
+        assert_eq!(parse_range("items=0-1", 10), None);
+    }
+
+    #[tokio::test]
+    async fn serve_files() {
+        let dir = test_dir();
+        let app = Route::new().nest("/files", StaticFiles::new(&dir).index_file("index.html"));
+
+        let resp = get(&app, "/files/a.txt", &[]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("text/plain; charset=utf-8"));
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "0123456789");
+
+        let resp = get(&app, "/files/sub", &[]).await;
+        assert_eq!(resp.content_type(), Some("text/html; charset=utf-8"));
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "<html></html>"
+        );
+
+        assert_eq!(
+            get(&app, "/files/b.txt", &[]).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            get(&app, "/files/", &[]).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            get(&app, "/files/../a.txt", &[]).await.status(),
+            StatusCode::FORBIDDEN
+        );
+
+        let app = StaticFiles::new(&dir)
+            .show_files_listing()
+            .map_to_response();
+        let resp = get(&app, "/", &[]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp
+            .into_body()
+            .into_string()
+            .await
+            .unwrap()
+            .contains("a.txt"));
+    }
+
+    #[tokio::test]
+    async fn conditional() {
+        let ep = StaticFile::new(test_dir().join("a.txt"));
+
+        let resp = get(&ep, "/", &[]).await;
+        let etag = resp
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let last_modified = resp
+            .headers()
+            .get(header::LAST_MODIFIED)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let resp = get(&ep, "/", &[("if-none-match", &etag)]).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        let resp = get(&ep, "/", &[("if-none-match", "\"abc\"")]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = get(&ep, "/", &[("if-modified-since", &last_modified)]).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn range() {
+        let ep = StaticFile::new(test_dir().join("a.txt"));
+
+        let resp = get(&ep, "/", &[("range", "bytes=2-4")]).await;
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 2-4/10"
+        );
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "234");
+
+        let resp = get(&ep, "/", &[("range", "bytes=20-")]).await;
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */10"
+        );
+
+        let resp = get(&ep, "/", &[("range", "bytes=2-4"), ("if-range", "\"abc\"")]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "0123456789");
+    }
+
+    #[test]
+    fn test_equiv_utf8_text() {
+        assert_eq!(
+            equiv_utf8_text(mime::APPLICATION_JAVASCRIPT),
+            mime::APPLICATION_JAVASCRIPT_UTF_8
+        );
+        assert_eq!(equiv_utf8_text(mime::TEXT_HTML), mime::TEXT_HTML_UTF_8);
+        assert_eq!(equiv_utf8_text(mime::TEXT_CSS), mime::TEXT_CSS_UTF_8);
+        assert_eq!(equiv_utf8_text(mime::TEXT_PLAIN), mime::TEXT_PLAIN_UTF_8);
+        assert_eq!(equiv_utf8_text(mime::TEXT_CSV), mime::TEXT_CSV_UTF_8);
+        assert_eq!(
+            equiv_utf8_text(mime::TEXT_TAB_SEPARATED_VALUES),
+            mime::TEXT_TAB_SEPARATED_VALUES_UTF_8
+        );
+
+        assert_eq!(equiv_utf8_text(mime::TEXT_XML), mime::TEXT_XML);
+        assert_eq!(equiv_utf8_text(mime::IMAGE_PNG), mime::IMAGE_PNG);
+    }
+}
@@ -1,10 +1,20 @@
-use std::{error::Error as StdError, future::Future};
+use std::{
+    convert::Infallible,
+    error::Error as StdError,
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
 use bytes::Bytes;
+use futures_util::{future::BoxFuture, FutureExt};
 use hyper::body::HttpBody;
 use tower::{Service, ServiceExt};
 
-use crate::{body::BodyStream, Endpoint, Request, Response, Result};
+use crate::{
+    web::{LocalAddr, RemoteAddr},
+    Endpoint, IntoEndpoint, IntoResponse, Request, Response, Result,
+};
 
 /// Extension trait for tower service compat.
 #[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
@@ -36,7 +46,7 @@ impl<T> TowerCompatExt for T {}
 
 /// A tower service adapter.
 #[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
-pub struct TowerCompatEndpoint<Svc>(Svc);
+pub struct TowerCompatEndpoint<Svc>(pub(crate) Svc);
 
 #[async_trait::async_trait]
 impl<Svc, ResBody, Err, Fut> Endpoint for TowerCompatEndpoint<Svc>
@@ -63,11 +73,148 @@ where
 
         svc.ready().await?;
 
-        let hyper_req: http::Request<hyper::Body> = req.into();
+        let local_addr = req.local_addr().clone();
+        let remote_addr = req.remote_addr().clone();
+        let mut hyper_req: http::Request<hyper::Body> = req.into();
+        // keeps the addresses if the request is converted back by `TowerServiceCompat`
+        hyper_req.extensions_mut().insert(local_addr);
+        hyper_req.extensions_mut().insert(remote_addr);
         let hyper_resp = svc.call(hyper_req.map(Into::into)).await?;
 
-        Ok(hyper_resp
-            .map(|body| hyper::Body::wrap_stream(BodyStream::new(body)))
-            .into())
+        Ok(hyper_resp.map(into_hyper_body).into())
+    }
+}
+
+/// A poem endpoint to tower service adapter, so the endpoint can be used by
+/// the libraries built on tower, such as `hyper` and `tonic`.
+///
+/// The addresses of the connection are taken from the [`LocalAddr`] and
+/// [`RemoteAddr`] extensions of the request if they exist.
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::TowerServiceCompat, handler, Route};
+/// use tower::ServiceExt;
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let svc = TowerServiceCompat::new(Route::new().at("/", index));
+/// let resp = svc
+///     .oneshot(http::Request::new(hyper::Body::empty()))
+///     .await
+///     .unwrap();
+/// let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+/// assert_eq!(body, "hello");
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
+pub struct TowerServiceCompat<E>(Arc<E>);
+
+impl<E> Clone for TowerServiceCompat<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E: Endpoint> TowerServiceCompat<E> {
+    /// Create a tower service from the endpoint.
+    pub fn new(ep: impl IntoEndpoint<Endpoint = E>) -> Self {
+        Self(Arc::new(ep.into_endpoint()))
+    }
+}
+
+impl<E: Endpoint + 'static> Service<http::Request<hyper::Body>> for TowerServiceCompat<E> {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: http::Request<hyper::Body>) -> Self::Future {
+        let ep = self.0.clone();
+        let local_addr = req
+            .extensions_mut()
+            .remove::<LocalAddr>()
+            .unwrap_or_default();
+        let remote_addr = req
+            .extensions_mut()
+            .remove::<RemoteAddr>()
+            .unwrap_or_default();
+        async move {
+            let req = Request::from((req, local_addr, remote_addr));
+            Ok(ep.call(req).await.into_response().into())
+        }
+        .boxed()
+    }
+}
+
+/// Converts the response body of the tower service to [`hyper::Body`], the
+/// trailers are also forwarded, which gRPC services rely on.
+fn into_hyper_body<B>(body: B) -> hyper::Body
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Into<Bytes> + Send + 'static,
+    B::Error: StdError + Send + Sync + 'static,
+{
+    let (mut sender, hyper_body) = hyper::Body::channel();
+
+    tokio::spawn(async move {
+        futures_util::pin_mut!(body);
+        while let Some(res) = body.data().await {
+            match res {
+                Ok(data) => {
+                    if sender.send_data(data.into()).await.is_err() {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    tracing::debug!(error = %err, "failed to read the response body");
+                    sender.abort();
+                    return;
+                }
+            }
+        }
+        match body.trailers().await {
+            Ok(Some(trailers)) => {
+                let _ = sender.send_trailers(trailers).await;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                tracing::debug!(error = %err, "failed to read the response trailers");
+                sender.abort();
+            }
+        }
+    });
+
+    hyper_body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, http::StatusCode};
+
+    #[tokio::test]
+    async fn round_trip() {
+        let svc = TowerServiceCompat::new(make_sync(|req| req.uri().path().to_string()));
+        let ep = svc.compat();
+
+        let mut resp = ep
+            .call(
+                Request::builder()
+                    .uri(http::Uri::from_static("/a"))
+                    .finish(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "/a");
     }
 }
@@ -1,7 +1,8 @@
 //! Some common error types.
 
 use std::{
-    fmt::{Debug, Display},
+    any::Any,
+    fmt::{self, Debug, Display, Formatter},
     string::FromUtf8Error,
 };
 
@@ -21,26 +22,48 @@ macro_rules! define_http_error {
 }
 
 /// General response error.
-#[derive(Debug)]
+///
+/// The error converted from another error type keeps the original error, which
+/// can be retrieved by [`Error::downcast_ref`] or [`Error::downcast`].
 pub struct Error {
     status: StatusCode,
     reason: Option<String>,
+    source: Option<Box<dyn Any + Send + Sync>>,
 }
 
-impl<T: Display> From<T> for Error {
+impl Debug for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("status", &self.status)
+            .field("reason", &self.reason)
+            .finish()
+    }
+}
+
+impl<T: Display + Send + Sync + 'static> From<T> for Error {
     #[inline]
     fn from(err: T) -> Self {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
             reason: Some(err.to_string()),
+            source: Some(Box::new(err)),
         }
     }
 }
 
+/// The original error of the [`Error`] that is converted to a response, it is
+/// kept in the extensions of the response for
+/// [`EndpointExt::catch_error`](crate::EndpointExt::catch_error) and
+/// [`Route::catch_error`](crate::Route::catch_error).
+pub(crate) struct ErrorSource(pub(crate) Box<dyn Any + Send + Sync>);
+
 impl IntoResponse for Error {
-    #[inline]
     fn into_response(self) -> Response {
-        self.as_response()
+        let mut resp = self.as_response();
+        if let Some(source) = self.source {
+            resp.extensions_mut().insert(ErrorSource(source));
+        }
+        resp
     }
 }
 
@@ -51,6 +74,7 @@ impl Error {
         Self {
             status,
             reason: None,
+            source: None,
         }
     }
 
@@ -75,6 +99,33 @@ impl Error {
         self.reason.as_deref()
     }
 
+    /// Returns `true` if this error is converted from an error of type `T`.
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// Returns a reference to the original error if it is of type `T`.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Takes the original error if it is of type `T`, otherwise returns this
+    /// error.
+    pub fn downcast<T: Any>(self) -> Result<T, Error> {
+        match self.source {
+            Some(source) => match source.downcast() {
+                Ok(err) => Ok(*err),
+                Err(source) => Err(Self {
+                    source: Some(source),
+                    ..self
+                }),
+            },
+            None => Err(self),
+        }
+    }
+
     /// Creates full response for this error.
     #[inline]
     pub fn as_response(&self) -> Response {
@@ -197,6 +248,9 @@ macro_rules! define_simple_errors {
 define_simple_errors!(
     /// Only the endpoints under the router can get the path parameters, otherwise this error will occur.
     (ErrorInvalidPathParams, BAD_REQUEST, "invalid path params");
+
+    /// The client did not provide a certificate.
+    (ClientCertRequired, UNAUTHORIZED, "client certificate required");
 );
 
 /// A possible error value when reading the body.
@@ -208,10 +262,40 @@ pub enum ReadBodyError {
     /// Body is not a valid utf8 string.
     Utf8(FromUtf8Error),
 
+    /// The charset specified in the `Content-Type` header is not supported.
+    UnsupportedCharset(String),
+
+    /// Body is not a valid string in the specified charset.
+    InvalidCharset(String),
+
     /// Io error.
     Io(std::io::Error),
 }
 
+impl ReadBodyError {
+    /// Returns `true` if the client closed the connection or the connection
+    /// was reset before the body was completely received.
+    pub fn is_client_disconnected(&self) -> bool {
+        match self {
+            ReadBodyError::Io(err) => is_disconnected_io_error(err),
+            _ => false,
+        }
+    }
+}
+
+/// Returns `true` if the IO error is caused by the client closing the
+/// connection or the connection being reset.
+#[doc(hidden)]
+pub fn is_disconnected_io_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::UnexpectedEof
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::BrokenPipe
+    )
+}
+
 impl From<FromUtf8Error> for ReadBodyError {
     fn from(err: FromUtf8Error) -> Self {
         Self::Utf8(err)
@@ -233,6 +317,12 @@ impl From<ReadBodyError> for Error {
             ReadBodyError::Utf8(err) => {
                 Error::new(StatusCode::BAD_REQUEST).with_reason(format!("parse utf8: {}", err))
             }
+            ReadBodyError::UnsupportedCharset(charset) => {
+                Error::new(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+                    .with_reason(format!("unsupported charset `{}`", charset))
+            }
+            ReadBodyError::InvalidCharset(charset) => Error::new(StatusCode::BAD_REQUEST)
+                .with_reason(format!("body is not a valid `{}` string", charset)),
             ReadBodyError::Io(err) => {
                 Error::new(StatusCode::BAD_REQUEST).with_reason(format!("io: {}", err))
             }
@@ -285,6 +375,18 @@ impl IntoResponse for ParseCookieError {
     }
 }
 
+/// A possible error value when loading the localized messages.
+#[cfg(feature = "i18n")]
+#[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+#[derive(Debug)]
+pub enum I18NError {
+    /// The locale is not a valid language identifier.
+    InvalidLocale(String),
+
+    /// The Fluent resource is invalid.
+    Fluent(Vec<fluent::FluentError>),
+}
+
 /// A possible error value when extracts data from request fails.
 #[derive(Debug)]
 pub struct GetDataError(pub &'static str);
@@ -393,6 +495,22 @@ impl IntoResponse for ParseJsonError {
     }
 }
 
+/// A possible error value when parsing path params.
+#[derive(Debug)]
+pub struct ParsePathError(pub String);
+
+impl From<ParsePathError> for Error {
+    fn from(err: ParsePathError) -> Self {
+        Error::new(StatusCode::BAD_REQUEST).with_reason(format!("invalid path params: {}", err.0))
+    }
+}
+
+impl IntoResponse for ParsePathError {
+    fn into_response(self) -> Response {
+        Into::<Error>::into(self).as_response()
+    }
+}
+
 /// A possible error value when parsing query.
 #[derive(Debug)]
 pub struct ParseQueryError(pub serde_urlencoded::de::Error);
@@ -433,6 +551,57 @@ pub enum ParseMultipartError {
     Multipart(multer::Error),
 }
 
+#[cfg(feature = "multipart")]
+impl ParseMultipartError {
+    /// Returns `true` if the client closed the connection or the connection
+    /// was reset before the body was completely received.
+    pub fn is_client_disconnected(&self) -> bool {
+        match self {
+            ParseMultipartError::ReadBody(err) => err.is_client_disconnected(),
+            ParseMultipartError::Multipart(err) => multer_io_error(err)
+                .map(is_disconnected_io_error)
+                .unwrap_or_default(),
+            _ => false,
+        }
+    }
+}
+
+/// The data of a multipart field exceeds the limit set by
+/// [`Field::size_limit`](crate::web::Field::size_limit).
+///
+/// It is wrapped in the IO error of reading the field.
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FieldSizeExceeded {
+    /// The size limit of the field.
+    pub limit: usize,
+}
+
+#[cfg(feature = "multipart")]
+impl Display for FieldSizeExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "field size exceeds the limit of {} bytes", self.limit)
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl std::error::Error for FieldSizeExceeded {}
+
+/// Returns the IO error of reading the body that causes the multipart error.
+#[cfg(feature = "multipart")]
+pub(crate) fn multer_io_error(err: &multer::Error) -> Option<&std::io::Error> {
+    match err {
+        multer::Error::StreamReadFailed(err) => match err.downcast_ref::<std::io::Error>() {
+            Some(err) => Some(err),
+            None => err
+                .downcast_ref::<multer::Error>()
+                .and_then(multer_io_error),
+        },
+        _ => None,
+    }
+}
+
 #[cfg(feature = "multipart")]
 impl From<ReadBodyError> for ParseMultipartError {
     fn from(err: ReadBodyError) -> Self {
@@ -574,6 +743,34 @@ impl IntoResponse for UpgradeError {
     }
 }
 
+/// A possible error value when adding the routes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RouteError {
+    /// The path is invalid, has been added, or conflicts with the existing
+    /// paths.
+    InvalidPath(String),
+
+    /// The nest path contains wildcards.
+    WildcardInNestPath(String),
+}
+
+impl Display for RouteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::InvalidPath(path) => write!(
+                f,
+                "invalid path or the path conflicts with the existing routes: `{}`",
+                path
+            ),
+            RouteError::WildcardInNestPath(path) => {
+                write!(f, "wildcards are not allowed in the nest path: `{}`", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -585,6 +782,18 @@ mod tests {
         assert_eq!(err.reason(), Some("a"));
     }
 
+    #[test]
+    fn downcast() {
+        let err: Error = std::io::Error::new(std::io::ErrorKind::Other, "a").into();
+        assert!(err.is::<std::io::Error>());
+        assert!(err.downcast_ref::<String>().is_none());
+        let err = err.downcast::<String>().unwrap_err();
+        assert_eq!(err.reason(), Some("a"));
+        assert_eq!(err.downcast::<std::io::Error>().unwrap().to_string(), "a");
+
+        assert!(!Error::new(StatusCode::BAD_REQUEST).is::<std::io::Error>());
+    }
+
     #[test]
     fn extractor_err_into_error() {
         let err: Error = ReadBodyError::BodyHasBeenTaken.into();
@@ -40,14 +40,23 @@
 //! |multipart         | Support for Multipart          |
 //! |sse               | Support Server-Sent Events (SSE)       |
 //! |tls               | Support for HTTP server over TLS   |
+//! |acme              | Support for automatic certificate management with ACME (Let's Encrypt) |
 //! |tempfile          | Support for [`tempfile`](https://crates.io/crates/tempfile) |
 //! |tower-compat      | Adapters for `tower::Layer` and `tower::Service`. |
+//! |cgi               | Run the endpoints as CGI or FastCGI applications |
+//! |grpc-web          | Support for translating gRPC-Web requests to gRPC services |
 //! |session           | Support for CookieSession    |
+//! |csrf              | Support for Cross-Site Request Forgery (CSRF) protection |
+//! |redis-storage     | Support for the redis storage back-end |
 //! |redis-session     | Support for RedisSession     |
 //! |opentelemetry     | Support for opentelemetry    |
 //! |prometheus        | Support for Prometheus       |
 //! |template          | Support for [`askama`](https://crates.io/crates/askama)       |
+//! |tera              | Support for [`tera`](https://crates.io/crates/tera) templates with hot reload in debug builds |
 //! |staticfiles       | Support for serve static files       |
+//! |charset           | Transcode the request body according to the `charset` of the `Content-Type` |
+//! |proxy             | Support for forwarding the requests to an upstream server |
+//! |i18n              | Support for the localized messages in the [`Fluent`](https://projectfluent.org/) format |
 
 #![doc(html_favicon_url = "https://poem.rs/assets/favicon.ico")]
 #![doc(html_logo_url = "https://poem.rs/assets/logo.png")]
@@ -56,6 +65,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs)]
 
+#[cfg(feature = "cgi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cgi")))]
+pub mod cgi;
 pub mod endpoint;
 pub mod error;
 pub mod listener;
@@ -63,12 +75,15 @@ pub mod middleware;
 #[cfg(feature = "session")]
 #[cfg_attr(docsrs, doc(cfg(feature = "session")))]
 pub mod session;
+pub mod storage;
+pub mod test;
 pub mod web;
 
 #[doc(inline)]
 pub use http;
 
 mod addr;
+mod background;
 mod body;
 mod request;
 mod response;
@@ -77,6 +92,7 @@ mod server;
 
 pub use addr::Addr;
 pub use async_trait::async_trait;
+pub use background::{BackgroundTasks, ShutdownSignal};
 pub use body::Body;
 pub use endpoint::{Endpoint, EndpointExt, IntoEndpoint};
 pub use error::{Error, Result};
@@ -85,7 +101,8 @@ pub use poem_derive::handler;
 pub use request::{OnUpgrade, Request, RequestBuilder, RequestParts, Upgraded};
 pub use response::{Response, ResponseBuilder, ResponseParts};
 pub use route::{
-    connect, delete, get, head, options, patch, post, put, trace, Route, RouteDomain, RouteMethod,
+    connect, delete, get, head, options, patch, post, put, trace, DynamicRoute, Route, RouteDomain,
+    RouteMethod,
 };
-pub use server::Server;
+pub use server::{ServeBackend, Server};
 pub use web::{FromRequest, IntoResponse, RequestBody};
@@ -0,0 +1,117 @@
+//! Creates and parses the certificates used by ACME.
+
+use std::{
+    collections::BTreeSet,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rcgen::{Certificate, CertificateParams, CustomExtension, DistinguishedName, DnType};
+use x509_parser::extensions::GeneralName;
+
+fn cert_error(err: impl ToString) -> IoError {
+    IoError::new(ErrorKind::Other, format!("acme: {}", err.to_string()))
+}
+
+/// A generated certificate and its PKCS #8 private key.
+pub(crate) struct GeneratedCert {
+    pub(crate) der: Vec<u8>,
+    pub(crate) key: Vec<u8>,
+}
+
+/// Creates a DER-encoded certificate signing request for the domains, and
+/// returns it with the private key of the certificate.
+pub(crate) fn create_csr(domains: &[String]) -> IoResult<GeneratedCert> {
+    let mut params = CertificateParams::new(domains.to_vec());
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, domains[0].clone());
+    let cert = Certificate::from_params(params).map_err(cert_error)?;
+    Ok(GeneratedCert {
+        der: cert.serialize_request_der().map_err(cert_error)?,
+        key: cert.serialize_private_key_der(),
+    })
+}
+
+/// Creates a DER-encoded self-signed certificate for the `tls-alpn-01`
+/// challenge.
+///
+/// Reference: <https://datatracker.ietf.org/doc/html/rfc8737#section-3>
+pub(crate) fn create_tls_alpn01_cert(
+    domain: &str,
+    key_authorization_digest: &[u8],
+) -> IoResult<GeneratedCert> {
+    let mut params = CertificateParams::new(vec![domain.to_string()]);
+    params.custom_extensions = vec![CustomExtension::new_acme_identifier(
+        key_authorization_digest,
+    )];
+    let cert = Certificate::from_params(params).map_err(cert_error)?;
+    Ok(GeneratedCert {
+        der: cert.serialize_der().map_err(cert_error)?,
+        key: cert.serialize_private_key_der(),
+    })
+}
+
+/// The fields of a certificate that are checked before using it.
+pub(crate) struct CertInfo {
+    pub(crate) not_after: SystemTime,
+    pub(crate) domains: BTreeSet<String>,
+}
+
+/// Parses the expiration time and the DNS names of a DER-encoded
+/// certificate.
+pub(crate) fn parse_cert(der: &[u8]) -> IoResult<CertInfo> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).map_err(cert_error)?;
+    let not_after = u64::try_from(cert.validity().not_after.timestamp())
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .map_err(|_| cert_error("invalid expiration time"))?;
+    let domains = cert
+        .subject_alternative_name()
+        .map_err(cert_error)?
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(CertInfo { not_after, domains })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tls_alpn01_cert() {
+        let cert = create_tls_alpn01_cert("example.com", &[0; 32]).unwrap();
+        let info = parse_cert(&cert.der).unwrap();
+        assert!(info.not_after > SystemTime::now());
+        assert_eq!(
+            info.domains.into_iter().collect::<Vec<_>>(),
+            vec!["example.com".to_string()]
+        );
+
+        // the key can be used by rustls
+        let key = tokio_rustls::rustls::PrivateKey(cert.key);
+        assert!(tokio_rustls::rustls::sign::any_ecdsa_type(&key).is_ok());
+    }
+
+    #[test]
+    fn csr() {
+        let csr = create_csr(&["example.com".to_string(), "www.example.com".to_string()]).unwrap();
+        let csr = rcgen::CertificateSigningRequest::from_der(&csr.der).unwrap();
+        assert_eq!(
+            csr.params.subject_alt_names,
+            vec![
+                rcgen::SanType::DnsName("example.com".to_string()),
+                rcgen::SanType::DnsName("www.example.com".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::{
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, Method, Request, StatusCode, Uri};
+use ring::{
+    digest::{digest, SHA256},
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+};
+use tokio_rustls::{rustls::ClientConfig, TlsConnector};
+
+use crate::listener::acme::protocol::{
+    Directory, Identifier, NewAccountRequest, NewOrderRequest, Order, Problem,
+};
+
+const JOSE_JSON: &str = "application/jose+json";
+const MAX_BAD_NONCE_RETRIES: usize = 3;
+
+fn base64url(data: impl AsRef<[u8]>) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn http_error(err: impl ToString) -> IoError {
+    IoError::new(ErrorKind::Other, format!("acme: {}", err.to_string()))
+}
+
+pub(crate) struct Response {
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Bytes,
+}
+
+impl Response {
+    fn location(&self) -> Option<String> {
+        self.headers
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+    }
+
+    fn problem(&self) -> Problem {
+        serde_json::from_slice(&self.body).unwrap_or(Problem {
+            ty: None,
+            detail: Some(String::from_utf8_lossy(&self.body).into_owned()),
+        })
+    }
+
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> IoResult<T> {
+        serde_json::from_slice(&self.body).map_err(http_error)
+    }
+}
+
+/// A minimal ACME client.
+///
+/// Reference: <https://datatracker.ietf.org/doc/html/rfc8555>
+pub(crate) struct AcmeClient {
+    tls_connector: TlsConnector,
+    directory: Directory,
+    key_pair: EcdsaKeyPair,
+    jwk: Value,
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl AcmeClient {
+    /// Connect to the ACME directory and create or find the account of the
+    /// `account_key`.
+    pub(crate) async fn new(
+        directory_url: &str,
+        root_certs: &[u8],
+        account_key: &[u8],
+        contacts: &[String],
+        terms_of_service_agreed: bool,
+    ) -> IoResult<Self> {
+        let mut config = ClientConfig::new();
+        config
+            .root_store
+            .add_pem_file(&mut &*root_certs)
+            .map_err(|_| IoError::new(ErrorKind::Other, "failed to parse root certificates"))?;
+        config.set_protocols(&["http/1.1".into()]);
+        let tls_connector = TlsConnector::from(Arc::new(config));
+
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, account_key)
+            .map_err(|_| IoError::new(ErrorKind::Other, "invalid acme account key"))?;
+        let public_key = key_pair.public_key().as_ref();
+        let jwk = serde_json::json!({
+            "crv": "P-256",
+            "kty": "EC",
+            "x": base64url(&public_key[1..33]),
+            "y": base64url(&public_key[33..]),
+        });
+
+        let resp = send_request(
+            &tls_connector,
+            Method::GET,
+            directory_url,
+            None,
+            Bytes::new(),
+        )
+        .await?;
+        if !resp.status.is_success() {
+            return Err(http_error(format!(
+                "failed to get the directory: {}",
+                resp.status
+            )));
+        }
+
+        let directory: Directory = resp.json()?;
+        if let Some(terms_of_service) = &directory.meta.terms_of_service {
+            if !terms_of_service_agreed {
+                return Err(http_error(format!(
+                    "the terms of service `{}` must be agreed with `AutoCertBuilder::terms_of_service_agreed`",
+                    terms_of_service
+                )));
+            }
+        }
+
+        let mut client = Self {
+            tls_connector,
+            directory,
+            key_pair,
+            jwk,
+            kid: None,
+            nonce: None,
+        };
+
+        let resp = client
+            .post(
+                &client.directory.new_account.clone(),
+                Some(&NewAccountRequest {
+                    only_return_existing: false,
+                    terms_of_service_agreed,
+                    contact: contacts,
+                }),
+            )
+            .await?;
+        client.kid = Some(
+            resp.location()
+                .ok_or_else(|| http_error("the account url is missing"))?,
+        );
+        Ok(client)
+    }
+
+    /// Returns the key authorization of the challenge token.
+    ///
+    /// Reference: <https://datatracker.ietf.org/doc/html/rfc8555#section-8.1>
+    pub(crate) fn key_authorization(&self, token: &str) -> String {
+        // the members of the JWK are in lexicographic order
+        let thumbprint = digest(&SHA256, self.jwk.to_string().as_bytes());
+        format!("{}.{}", token, base64url(thumbprint))
+    }
+
+    /// Create a new order for the domains, and returns the order url and
+    /// object.
+    pub(crate) async fn new_order(&mut self, domains: &[String]) -> IoResult<(String, Order)> {
+        let resp = self
+            .post(
+                &self.directory.new_order.clone(),
+                Some(&NewOrderRequest {
+                    identifiers: domains
+                        .iter()
+                        .map(|domain| Identifier {
+                            ty: "dns".to_string(),
+                            value: domain.clone(),
+                        })
+                        .collect(),
+                }),
+            )
+            .await?;
+        let order_url = resp
+            .location()
+            .ok_or_else(|| http_error("the order url is missing"))?;
+        Ok((order_url, resp.json()?))
+    }
+
+    /// Sends a `POST` request with a JWS body, or a `POST-as-GET` request if
+    /// the `payload` is `None`.
+    pub(crate) async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<&impl Serialize>,
+    ) -> IoResult<Response> {
+        let payload = match payload {
+            Some(payload) => base64url(serde_json::to_vec(payload).map_err(http_error)?),
+            None => String::new(),
+        };
+
+        let mut retries = 0;
+        loop {
+            let nonce = match self.nonce.take() {
+                Some(nonce) => nonce,
+                None => self.new_nonce().await?,
+            };
+            let body = self.jws(url, nonce, &payload)?;
+            let resp = send_request(
+                &self.tls_connector,
+                Method::POST,
+                url,
+                Some(JOSE_JSON),
+                body.into(),
+            )
+            .await?;
+
+            self.nonce = resp
+                .headers
+                .get("replay-nonce")
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string);
+
+            if resp.status.is_success() {
+                return Ok(resp);
+            }
+
+            let problem = resp.problem();
+            if problem.ty.as_deref() == Some("urn:ietf:params:acme:error:badNonce")
+                && retries < MAX_BAD_NONCE_RETRIES
+            {
+                retries += 1;
+                continue;
+            }
+            return Err(http_error(format!(
+                "request to `{}` failed with status {}: {}",
+                url,
+                resp.status,
+                problem.detail.unwrap_or_default()
+            )));
+        }
+    }
+
+    async fn new_nonce(&self) -> IoResult<String> {
+        let resp = send_request(
+            &self.tls_connector,
+            Method::HEAD,
+            &self.directory.new_nonce,
+            None,
+            Bytes::new(),
+        )
+        .await?;
+        resp.headers
+            .get("replay-nonce")
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+            .ok_or_else(|| http_error("failed to get a new nonce"))
+    }
+
+    fn jws(&self, url: &str, nonce: String, payload: &str) -> IoResult<String> {
+        let mut protected = serde_json::json!({
+            "alg": "ES256",
+            "nonce": nonce,
+            "url": url,
+        });
+        match &self.kid {
+            Some(kid) => protected["kid"] = kid.clone().into(),
+            None => protected["jwk"] = self.jwk.clone(),
+        }
+        let protected = base64url(protected.to_string());
+        let signature = self
+            .key_pair
+            .sign(
+                &SystemRandom::new(),
+                format!("{}.{}", protected, payload).as_bytes(),
+            )
+            .map_err(|_| http_error("failed to sign the request"))?;
+        Ok(serde_json::json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": base64url(signature),
+        })
+        .to_string())
+    }
+}
+
+async fn send_request(
+    tls_connector: &TlsConnector,
+    method: Method,
+    url: &str,
+    content_type: Option<&str>,
+    body: Bytes,
+) -> IoResult<Response> {
+    let uri: Uri = url.parse().map_err(http_error)?;
+    let https = match uri.scheme_str() {
+        Some("https") => true,
+        Some("http") => false,
+        _ => return Err(http_error(format!("unsupported url `{}`", url))),
+    };
+    let host = uri
+        .host()
+        .ok_or_else(|| http_error(format!("invalid url `{}`", url)))?
+        .to_string();
+    let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"))
+        .header(header::HOST, uri.authority().unwrap().as_str())
+        .header(header::USER_AGENT, "poem");
+    if let Some(content_type) = content_type {
+        builder = builder.header(header::CONTENT_TYPE, content_type);
+    }
+    let req = builder.body(hyper::Body::from(body)).map_err(http_error)?;
+
+    let stream = tokio::time::timeout(
+        Duration::from_secs(30),
+        TcpStream::connect((host.as_str(), port)),
+    )
+    .await
+    .map_err(http_error)??;
+    if https {
+        let domain = webpki::DNSNameRef::try_from_ascii_str(&host).map_err(http_error)?;
+        let stream = tls_connector.connect(domain, stream).await?;
+        send_request_with_io(stream, req).await
+    } else {
+        send_request_with_io(stream, req).await
+    }
+}
+
+async fn send_request_with_io(
+    io: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    req: Request<hyper::Body>,
+) -> IoResult<Response> {
+    let (mut sender, conn) = hyper::client::conn::handshake(io)
+        .await
+        .map_err(http_error)?;
+    tokio::spawn(async move {
+        let _ = conn.await;
+    });
+    let resp = sender.send_request(req).await.map_err(http_error)?;
+    let (parts, body) = resp.into_parts();
+    let body = hyper::body::to_bytes(body).await.map_err(http_error)?;
+    Ok(Response {
+        status: parts.status,
+        headers: parts.headers,
+        body,
+    })
+}
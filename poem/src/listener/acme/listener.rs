@@ -0,0 +1,727 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    path::Path,
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
+
+use parking_lot::RwLock;
+use ring::{
+    digest::{digest, SHA256},
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+};
+use tokio::io::AsyncWriteExt;
+use tokio_rustls::rustls::{
+    internal::pemfile,
+    sign::{any_ecdsa_type, any_supported_type, CertifiedKey},
+    Certificate, ClientHello, NoClientAuth, PrivateKey, ResolvesServerCert, ServerConfig,
+};
+
+use crate::listener::{
+    acme::{
+        cert::{self, GeneratedCert},
+        client::AcmeClient,
+        protocol::{Authorization, FinalizeRequest, Order, Status},
+        AutoCert, ChallengeType,
+    },
+    Listener, TlsAcceptor,
+};
+
+/// The ALPN protocol name of the `tls-alpn-01` challenge.
+pub(crate) const ACME_TLS_ALPN_NAME: &[u8] = b"acme-tls/1";
+
+/// Renew the certificate 30 days before it expires.
+const RENEW_BEFORE: Duration = Duration::from_secs(60 * 60 * 24 * 30);
+
+/// Check the certificate at least once a day.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Retry after 5 minutes if it fails to obtain the certificate.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
+/// The interval and number of attempts to poll the status of the
+/// authorizations and order.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_ATTEMPTS: usize = 30;
+
+fn acme_error(err: impl ToString) -> IoError {
+    IoError::new(ErrorKind::Other, format!("acme: {}", err.to_string()))
+}
+
+#[derive(Default)]
+pub(crate) struct ResolveServerCert {
+    cert: RwLock<Option<(CertifiedKey, SystemTime)>>,
+    acme_keys: RwLock<HashMap<String, CertifiedKey>>,
+}
+
+impl ResolveServerCert {
+    fn expires_at(&self) -> Option<SystemTime> {
+        self.cert.read().as_ref().map(|(_, expires_at)| *expires_at)
+    }
+
+    /// Sets the certificate, it must be issued for exactly the `domains`, so
+    /// that a cached certificate is not used after the domains are changed.
+    fn set_cert(&self, cert_pem: &[u8], key_pem: &[u8], domains: &[String]) -> IoResult<()> {
+        let certs = pemfile::certs(&mut &*cert_pem)
+            .map_err(|_| acme_error("failed to parse the certificates"))?;
+        let info = cert::parse_cert(
+            &certs
+                .first()
+                .ok_or_else(|| acme_error("failed to parse the certificates"))?
+                .0,
+        )?;
+        if !info
+            .domains
+            .iter()
+            .eq(domains.iter().collect::<BTreeSet<_>>())
+        {
+            return Err(acme_error(format!(
+                "the certificate is issued for {:?} rather than {:?}",
+                info.domains, domains
+            )));
+        }
+        let key = pemfile::pkcs8_private_keys(&mut &*key_pem)
+            .ok()
+            .and_then(|mut keys| keys.pop())
+            .ok_or_else(|| acme_error("failed to parse the private key"))?;
+        let key = any_supported_type(&key).map_err(|_| acme_error("invalid private key"))?;
+        *self.cert.write() = Some((CertifiedKey::new(certs, Arc::new(key)), info.not_after));
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for ResolveServerCert {
+    fn resolve(&self, client_hello: ClientHello) -> Option<CertifiedKey> {
+        let is_acme_challenge = client_hello
+            .alpn()
+            .map(|protocols| protocols.contains(&ACME_TLS_ALPN_NAME))
+            .unwrap_or_default();
+        if is_acme_challenge {
+            let server_name: &str = client_hello.server_name()?.into();
+            return self.acme_keys.read().get(server_name).cloned();
+        }
+        self.cert.read().as_ref().map(|(cert, _)| cert.clone())
+    }
+}
+
+/// A wrapper around an underlying listener which obtains and renews the
+/// certificates automatically.
+///
+/// NOTE: You cannot create it directly and should use the
+/// [`acme`](crate::listener::Listener::acme) method to create it, because it
+/// needs to wrap a underlying listener.
+#[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+pub struct AutoCertListener<T> {
+    inner: T,
+    auto_cert: AutoCert,
+}
+
+impl<T> AutoCertListener<T> {
+    pub(crate) fn new(inner: T, auto_cert: AutoCert) -> Self {
+        Self { inner, auto_cert }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Listener> Listener for AutoCertListener<T> {
+    type Acceptor = TlsAcceptor<T::Acceptor>;
+
+    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
+        let resolver = Arc::new(ResolveServerCert::default());
+
+        if let Some(cache_path) = &self.auto_cert.cache_path {
+            if let Ok((cert_pem, key_pem)) = read_cached_cert(cache_path).await {
+                if let Err(err) = resolver.set_cert(&cert_pem, &key_pem, &self.auto_cert.domains) {
+                    tracing::warn!(error = %err, "failed to load the cached certificate");
+                }
+            }
+        }
+
+        let mut server_config = ServerConfig::new(NoClientAuth::new());
+        server_config.cert_resolver = resolver.clone();
+        let mut protocols = vec!["h2".into(), "http/1.1".into()];
+        if self.auto_cert.challenge_type == ChallengeType::TlsAlpn01 {
+            protocols.push(ACME_TLS_ALPN_NAME.to_vec());
+        }
+        server_config.set_protocols(&protocols);
+
+        let acceptor =
+            TlsAcceptor::from_server_config(self.inner.into_acceptor().await?, server_config);
+        tokio::spawn(renew_cert(Arc::downgrade(&resolver), self.auto_cert));
+        Ok(acceptor)
+    }
+}
+
+async fn renew_cert(resolver: Weak<ResolveServerCert>, auto_cert: AutoCert) {
+    loop {
+        let resolver = match resolver.upgrade() {
+            Some(resolver) => resolver,
+            None => return,
+        };
+
+        let renew_at = resolver
+            .expires_at()
+            .map(|expires_at| expires_at - RENEW_BEFORE);
+        let wait = match renew_at.map(|renew_at| renew_at.duration_since(SystemTime::now())) {
+            Some(Ok(wait)) => wait.min(CHECK_INTERVAL),
+            _ => {
+                tracing::info!(domains = ?auto_cert.domains, "obtain the certificate");
+                match issue_cert(&auto_cert, &resolver).await {
+                    Ok(()) => {
+                        tracing::info!(domains = ?auto_cert.domains, "certificate obtained");
+                        continue;
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to obtain the certificate");
+                        RETRY_INTERVAL
+                    }
+                }
+            }
+        };
+
+        drop(resolver);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+fn generate_key() -> IoResult<Vec<u8>> {
+    EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &SystemRandom::new())
+        .map(|pkcs8| pkcs8.as_ref().to_vec())
+        .map_err(|_| acme_error("failed to generate the private key"))
+}
+
+fn pem_encode(label: &str, data: &[u8]) -> String {
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in base64::encode(data).as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+async fn read_cached_cert(cache_path: &Path) -> IoResult<(Vec<u8>, Vec<u8>)> {
+    Ok((
+        tokio::fs::read(cache_path.join("cert.pem")).await?,
+        tokio::fs::read(cache_path.join("key.pem")).await?,
+    ))
+}
+
+/// Writes a file in the cache directory, which is only readable by the owner
+/// on unix because it may contain a private key.
+///
+/// The data is written to a temporary file which is then renamed, so that a
+/// partially written file is never loaded.
+async fn write_cache_file(path: &Path, data: &[u8]) -> IoResult<()> {
+    let tmp_path = path.with_extension("tmp");
+    // the mode only applies to the new files
+    let _ = tokio::fs::remove_file(&tmp_path).await;
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&tmp_path).await?;
+    file.write_all(data).await?;
+    file.sync_all().await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
+async fn load_account_key(auto_cert: &AutoCert) -> IoResult<Vec<u8>> {
+    let cache_path = match &auto_cert.cache_path {
+        Some(cache_path) => cache_path,
+        None => return generate_key(),
+    };
+    let path = cache_path.join("account.pem");
+    if let Ok(data) = tokio::fs::read(&path).await {
+        if let Some(key) = pemfile::pkcs8_private_keys(&mut data.as_slice())
+            .ok()
+            .and_then(|mut keys| keys.pop())
+        {
+            return Ok(key.0);
+        }
+    }
+
+    let key = generate_key()?;
+    tokio::fs::create_dir_all(cache_path).await?;
+    write_cache_file(&path, pem_encode("PRIVATE KEY", &key).as_bytes()).await?;
+    Ok(key)
+}
+
+async fn issue_cert(auto_cert: &AutoCert, resolver: &ResolveServerCert) -> IoResult<()> {
+    let account_key = load_account_key(auto_cert).await?;
+    let mut client = AcmeClient::new(
+        &auto_cert.directory_url,
+        &auto_cert.root_certs,
+        &account_key,
+        &auto_cert.contacts,
+        auto_cert.terms_of_service_agreed,
+    )
+    .await?;
+    let (order_url, order) = client.new_order(&auto_cert.domains).await?;
+
+    for auth_url in &order.authorizations {
+        let res = authorize(&mut client, auth_url, auto_cert, resolver).await;
+
+        // the challenge keys are no longer needed
+        auto_cert.http01_keys.write().clear();
+        resolver.acme_keys.write().clear();
+        res?;
+    }
+
+    let order = poll_order(&mut client, &order_url, Status::Ready).await?;
+    let GeneratedCert { der: csr, key } = cert::create_csr(&auto_cert.domains)?;
+    client
+        .post(
+            &order.finalize,
+            Some(&FinalizeRequest {
+                csr: base64::encode_config(csr, base64::URL_SAFE_NO_PAD),
+            }),
+        )
+        .await?;
+
+    let order = poll_order(&mut client, &order_url, Status::Valid).await?;
+    let cert_url = order
+        .certificate
+        .ok_or_else(|| acme_error("the certificate url is missing"))?;
+    let cert_pem = client.post(&cert_url, None::<&()>).await?.body;
+    let key_pem = pem_encode("PRIVATE KEY", &key);
+    resolver.set_cert(&cert_pem, key_pem.as_bytes(), &auto_cert.domains)?;
+
+    if let Some(cache_path) = &auto_cert.cache_path {
+        tokio::fs::create_dir_all(cache_path).await?;
+        write_cache_file(&cache_path.join("cert.pem"), &cert_pem).await?;
+        write_cache_file(&cache_path.join("key.pem"), key_pem.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+async fn authorize(
+    client: &mut AcmeClient,
+    auth_url: &str,
+    auto_cert: &AutoCert,
+    resolver: &ResolveServerCert,
+) -> IoResult<()> {
+    let auth: Authorization = client.post(auth_url, None::<&()>).await?.json()?;
+    if auth.status == Status::Valid {
+        return Ok(());
+    }
+
+    let challenge = auth
+        .challenges
+        .iter()
+        .find(|challenge| challenge.ty == auto_cert.challenge_type.as_str())
+        .ok_or_else(|| {
+            acme_error(format!(
+                "the challenge `{}` is not supported for `{}`",
+                auto_cert.challenge_type.as_str(),
+                auth.identifier.value
+            ))
+        })?;
+    let key_authorization = client.key_authorization(&challenge.token);
+
+    match auto_cert.challenge_type {
+        ChallengeType::Http01 => {
+            auto_cert
+                .http01_keys
+                .write()
+                .insert(challenge.token.clone(), key_authorization);
+        }
+        ChallengeType::TlsAlpn01 => {
+            let GeneratedCert { der: cert, key } = cert::create_tls_alpn01_cert(
+                &auth.identifier.value,
+                digest(&SHA256, key_authorization.as_bytes()).as_ref(),
+            )?;
+            let key =
+                any_ecdsa_type(&PrivateKey(key)).map_err(|_| acme_error("invalid private key"))?;
+            resolver.acme_keys.write().insert(
+                auth.identifier.value.clone(),
+                CertifiedKey::new(vec![Certificate(cert)], Arc::new(key)),
+            );
+        }
+    }
+
+    // tell the server that the challenge is ready
+    client
+        .post(&challenge.url, Some(&serde_json::json!({})))
+        .await?;
+
+    for _ in 0..POLL_ATTEMPTS {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let auth: Authorization = client.post(auth_url, None::<&()>).await?.json()?;
+        match auth.status {
+            Status::Pending => continue,
+            Status::Valid => return Ok(()),
+            status => {
+                let detail = auth
+                    .challenges
+                    .into_iter()
+                    .find_map(|challenge| challenge.error.and_then(|err| err.detail))
+                    .unwrap_or_default();
+                return Err(acme_error(format!(
+                    "the authorization of `{}` is {:?}: {}",
+                    auth.identifier.value, status, detail
+                )));
+            }
+        }
+    }
+
+    Err(acme_error(format!(
+        "the authorization of `{}` timed out",
+        auth.identifier.value
+    )))
+}
+
+async fn poll_order(client: &mut AcmeClient, order_url: &str, expected: Status) -> IoResult<Order> {
+    for _ in 0..POLL_ATTEMPTS {
+        let order: Order = client.post(order_url, None::<&()>).await?.json()?;
+        if order.status == expected {
+            return Ok(order);
+        }
+        match order.status {
+            Status::Pending | Status::Ready | Status::Processing => {
+                tokio::time::sleep(POLL_INTERVAL).await
+            }
+            status => {
+                return Err(acme_error(format!(
+                    "the order is {:?}: {}",
+                    status,
+                    order.error.and_then(|err| err.detail).unwrap_or_default()
+                )))
+            }
+        }
+    }
+    Err(acme_error("the order timed out"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rcgen::{BasicConstraints, CertificateParams, CertificateSigningRequest, IsCa};
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+    use tokio_rustls::{rustls::ClientConfig, webpki::DNSNameRef, TlsConnector};
+
+    use super::*;
+    use crate::{
+        handler,
+        http::{Method, StatusCode},
+        listener::{acme::AutoCert, Acceptor, TcpAcceptor, TcpListener},
+        web::Json,
+        Endpoint, IntoResponse, Request, Response, Route, Server,
+    };
+
+    /// An in-process ACME server which issues the certificates for the
+    /// `http-01` challenges.
+    struct MockAcme {
+        base_url: String,
+        http01_addr: String,
+        ca: rcgen::Certificate,
+        state: parking_lot::Mutex<MockState>,
+    }
+
+    #[derive(Default)]
+    struct MockState {
+        thumbprint: String,
+        domains: Vec<String>,
+        authorized: bool,
+        cert: Option<String>,
+    }
+
+    const TOKEN: &str = "mock-token";
+
+    fn base64url_decode(value: &Value) -> Vec<u8> {
+        base64::decode_config(value.as_str().unwrap(), base64::URL_SAFE_NO_PAD).unwrap()
+    }
+
+    impl MockAcme {
+        fn order(&self, state: &MockState) -> Value {
+            let status = match (&state.cert, state.authorized) {
+                (Some(_), _) => "valid",
+                (None, true) => "ready",
+                (None, false) => "pending",
+            };
+            json!({
+                "status": status,
+                "authorizations": [format!("{}/authz", self.base_url)],
+                "finalize": format!("{}/finalize", self.base_url),
+                "certificate": state.cert.as_ref().map(|_| format!("{}/cert", self.base_url)),
+            })
+        }
+
+        async fn validate_http01(&self, thumbprint: &str) -> bool {
+            let resp = hyper::Client::new()
+                .get(
+                    format!(
+                        "http://{}/.well-known/acme-challenge/{}",
+                        self.http01_addr, TOKEN
+                    )
+                    .parse()
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+            body == format!("{}.{}", TOKEN, thumbprint)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Endpoint for MockAcme {
+        type Output = Response;
+
+        async fn call(&self, mut req: Request) -> Self::Output {
+            let path = req.uri().path().to_string();
+            let resp = if req.method() == Method::GET && path == "/directory" {
+                Json(json!({
+                    "newNonce": format!("{}/nonce", self.base_url),
+                    "newAccount": format!("{}/account", self.base_url),
+                    "newOrder": format!("{}/order", self.base_url),
+                    "meta": { "termsOfService": format!("{}/terms", self.base_url) },
+                }))
+                .into_response()
+            } else if req.method() == Method::HEAD {
+                Response::default()
+            } else {
+                let jws: Value =
+                    serde_json::from_slice(&req.take_body().into_vec().await.unwrap()).unwrap();
+                let protected: Value =
+                    serde_json::from_slice(&base64url_decode(&jws["protected"])).unwrap();
+                assert_eq!(protected["url"], format!("{}{}", self.base_url, path));
+                let payload = base64url_decode(&jws["payload"]);
+                let payload: Value = if payload.is_empty() {
+                    Value::Null
+                } else {
+                    serde_json::from_slice(&payload).unwrap()
+                };
+
+                let thumbprint = self.state.lock().thumbprint.clone();
+                match path.as_str() {
+                    "/account" => {
+                        assert_eq!(payload["termsOfServiceAgreed"], true);
+                        let jwk = &protected["jwk"];
+                        let jwk = json!({
+                            "crv": jwk["crv"],
+                            "kty": jwk["kty"],
+                            "x": jwk["x"],
+                            "y": jwk["y"],
+                        });
+                        self.state.lock().thumbprint = base64::encode_config(
+                            digest(&SHA256, jwk.to_string().as_bytes()),
+                            base64::URL_SAFE_NO_PAD,
+                        );
+                        Json(json!({ "status": "valid" }))
+                            .with_status(StatusCode::CREATED)
+                            .with_header("location", format!("{}/account/1", self.base_url))
+                            .into_response()
+                    }
+                    "/order" => {
+                        let mut state = self.state.lock();
+                        state.domains = payload["identifiers"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|identifier| identifier["value"].as_str().unwrap().to_string())
+                            .collect();
+                        Json(self.order(&state))
+                            .with_status(StatusCode::CREATED)
+                            .with_header("location", format!("{}/order/1", self.base_url))
+                            .into_response()
+                    }
+                    "/order/1" => Json(self.order(&self.state.lock())).into_response(),
+                    "/authz" => {
+                        let state = self.state.lock();
+                        Json(json!({
+                            "identifier": { "type": "dns", "value": state.domains[0] },
+                            "status": if state.authorized { "valid" } else { "pending" },
+                            "challenges": [{
+                                "type": "http-01",
+                                "url": format!("{}/challenge", self.base_url),
+                                "token": TOKEN,
+                            }],
+                        }))
+                        .into_response()
+                    }
+                    "/challenge" => {
+                        let authorized = self.validate_http01(&thumbprint).await;
+                        self.state.lock().authorized = authorized;
+                        Json(json!({})).into_response()
+                    }
+                    "/finalize" => {
+                        let csr =
+                            CertificateSigningRequest::from_der(&base64url_decode(&payload["csr"]))
+                                .unwrap();
+                        let mut state = self.state.lock();
+                        state.cert = Some(csr.serialize_pem_with_signer(&self.ca).unwrap());
+                        Json(self.order(&state)).into_response()
+                    }
+                    "/cert" => self.state.lock().cert.clone().unwrap().into_response(),
+                    _ => StatusCode::NOT_FOUND.into_response(),
+                }
+            };
+            resp.with_header("replay-nonce", "nonce").into_response()
+        }
+    }
+
+    /// Starts the mock ACME server, and returns it with the acceptor that
+    /// the `http-01` challenges are sent to.
+    async fn start_mock_acme() -> (Arc<MockAcme>, TcpAcceptor) {
+        let acme_acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let http01_acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+
+        let mut ca_params = CertificateParams::new(Vec::<String>::new());
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let mock = Arc::new(MockAcme {
+            base_url: format!(
+                "http://{}",
+                acme_acceptor.local_addr()[0].as_socket_addr().unwrap()
+            ),
+            http01_addr: http01_acceptor.local_addr()[0]
+                .as_socket_addr()
+                .unwrap()
+                .to_string(),
+            ca: rcgen::Certificate::from_params(ca_params).unwrap(),
+            state: Default::default(),
+        });
+        tokio::spawn(Server::new_with_acceptor(acme_acceptor).run(mock.clone()));
+        (mock, http01_acceptor)
+    }
+
+    #[tokio::test]
+    async fn terms_of_service() {
+        let (mock, _) = start_mock_acme().await;
+        let err = AcmeClient::new(
+            &format!("{}/directory", mock.base_url),
+            &[],
+            &generate_key().unwrap(),
+            &[],
+            false,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("terms of service"), "{}", err);
+    }
+
+    #[test]
+    fn cert_domains_changed() {
+        let cert = rcgen::generate_simple_self_signed(vec!["example.com".to_string()]).unwrap();
+        let cert_pem = cert.serialize_pem().unwrap();
+        let key_pem = cert.serialize_private_key_pem();
+        let resolver = ResolveServerCert::default();
+
+        assert!(resolver
+            .set_cert(
+                cert_pem.as_bytes(),
+                key_pem.as_bytes(),
+                &["www.example.com".to_string()]
+            )
+            .is_err());
+        assert!(resolver.expires_at().is_none());
+
+        resolver
+            .set_cert(
+                cert_pem.as_bytes(),
+                key_pem.as_bytes(),
+                &["example.com".to_string()],
+            )
+            .unwrap();
+        assert!(resolver.expires_at().is_some());
+    }
+
+    #[tokio::test]
+    async fn cache_file() {
+        let dir = std::env::temp_dir().join(format!("poem-acme-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("key.pem");
+
+        write_cache_file(&path, b"abc").await.unwrap();
+        write_cache_file(&path, b"def").await.unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"def");
+        assert!(!dir.join("key.tmp").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = tokio::fs::metadata(&path)
+                .await
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn obtain_cert() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let (mock, http01_acceptor) = start_mock_acme().await;
+        let auto_cert = AutoCert::builder()
+            .directory_url(format!("{}/directory", mock.base_url))
+            .domain("localhost")
+            .challenge_type(ChallengeType::Http01)
+            .terms_of_service_agreed(true)
+            .build()
+            .unwrap();
+        tokio::spawn(
+            Server::new_with_acceptor(http01_acceptor)
+                .run(Route::new().nest("/.well-known/acme-challenge", auto_cert.http01_endpoint())),
+        );
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .acme(auto_cert)
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(index));
+
+        // the certificate issued by the mock ACME server is served
+        let mut config = ClientConfig::new();
+        config
+            .root_store
+            .add(&Certificate(mock.ca.serialize_der().unwrap()))
+            .unwrap();
+        config.set_protocols(&["http/1.1".into()]);
+        let connector = TlsConnector::from(Arc::new(config));
+        let mut stream = tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                let stream = TcpStream::connect(addr).await.unwrap();
+                let domain = DNSNameRef::try_from_ascii_str("localhost").unwrap();
+                match connector.connect(domain, stream).await {
+                    Ok(stream) => break stream,
+                    Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut data = String::new();
+        stream.read_to_string(&mut data).await.unwrap();
+        assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(data.ends_with("hello"));
+        assert_eq!(mock.state.lock().domains, vec!["localhost".to_string()]);
+    }
+}
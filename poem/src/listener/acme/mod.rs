@@ -0,0 +1,332 @@
+//! Automatic certificate management with the ACME protocol, such as
+//! [Let's Encrypt](https://letsencrypt.org/).
+
+mod cert;
+mod client;
+mod listener;
+mod protocol;
+
+use std::{
+    collections::HashMap,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    path::PathBuf,
+    sync::Arc,
+};
+
+pub use listener::AutoCertListener;
+pub(crate) use listener::ACME_TLS_ALPN_NAME;
+use parking_lot::RwLock;
+
+use crate::{http::StatusCode, Endpoint, Request, Response};
+
+/// The directory url of the Let's Encrypt production environment.
+pub const LETS_ENCRYPT_PRODUCTION: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// The directory url of the Let's Encrypt staging environment.
+pub const LETS_ENCRYPT_STAGING: &str = "https://acme-staging-v02.api.letsencrypt.org/directory";
+
+/// The root certificates used to connect to the Let's Encrypt servers.
+const DEFAULT_ROOT_CERTS: &[u8] = include_bytes!("isrg_root.pem");
+
+/// The type of the challenge used to validate the domains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChallengeType {
+    /// The `http-01` challenge, the validation request is sent to
+    /// `http://<domain>/.well-known/acme-challenge/<token>`, see
+    /// [`AutoCert::http01_endpoint`].
+    Http01,
+
+    /// The `tls-alpn-01` challenge, the validation is performed by the TLS
+    /// listener on port `443`.
+    TlsAlpn01,
+}
+
+impl ChallengeType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ChallengeType::Http01 => "http-01",
+            ChallengeType::TlsAlpn01 => "tls-alpn-01",
+        }
+    }
+}
+
+type Http01Keys = Arc<RwLock<HashMap<String, String>>>;
+
+/// Automatic certificate management.
+///
+/// The certificates are obtained from the ACME server when the listener is
+/// started, and renewed 30 days before they expire. If a cache path is
+/// specified, the account key and the certificates are stored in it, so they
+/// can be reused after restarting.
+///
+/// # Example
+///
+/// ```no_run
+/// use poem::{
+///     get, handler,
+///     listener::{acme::AutoCert, Listener, TcpListener},
+///     Route, Server,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let auto_cert = AutoCert::builder()
+///     .domain("example.com")
+///     .contact("mailto:admin@example.com")
+///     .terms_of_service_agreed(true)
+///     .cache_path("/var/cache/acme")
+///     .build()?;
+///
+/// let app = Route::new().at("/", get(index));
+/// Server::new(TcpListener::bind("0.0.0.0:443").acme(auto_cert))
+///     .await?
+///     .run(app)
+///     .await
+/// # });
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+pub struct AutoCert {
+    directory_url: String,
+    domains: Vec<String>,
+    contacts: Vec<String>,
+    cache_path: Option<PathBuf>,
+    challenge_type: ChallengeType,
+    root_certs: Vec<u8>,
+    terms_of_service_agreed: bool,
+    http01_keys: Http01Keys,
+}
+
+impl AutoCert {
+    /// Create an [`AutoCertBuilder`].
+    pub fn builder() -> AutoCertBuilder {
+        AutoCertBuilder::new()
+    }
+
+    /// Returns an endpoint that responds to the `http-01` challenges.
+    ///
+    /// It must be served on port `80` at the path
+    /// `/.well-known/acme-challenge`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poem::{
+    ///     listener::{
+    ///         acme::{AutoCert, ChallengeType},
+    ///         Listener, TcpListener,
+    ///     },
+    ///     Route, Server,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let auto_cert = AutoCert::builder()
+    ///     .domain("example.com")
+    ///     .terms_of_service_agreed(true)
+    ///     .challenge_type(ChallengeType::Http01)
+    ///     .build()?;
+    ///
+    /// let challenge_app = Route::new().nest("/.well-known/acme-challenge", auto_cert.http01_endpoint());
+    /// tokio::spawn(async move {
+    ///     Server::new(TcpListener::bind("0.0.0.0:80"))
+    ///         .await?
+    ///         .run(challenge_app)
+    ///         .await
+    /// });
+    ///
+    /// Server::new(TcpListener::bind("0.0.0.0:443").acme(auto_cert))
+    ///     .await?
+    ///     .run(Route::new())
+    ///     .await
+    /// # });
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn http01_endpoint(&self) -> Http01Endpoint {
+        Http01Endpoint {
+            keys: self.http01_keys.clone(),
+        }
+    }
+}
+
+/// A builder for [`AutoCert`].
+#[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+pub struct AutoCertBuilder {
+    directory_url: String,
+    domains: Vec<String>,
+    contacts: Vec<String>,
+    cache_path: Option<PathBuf>,
+    challenge_type: ChallengeType,
+    root_certs: Vec<u8>,
+    terms_of_service_agreed: bool,
+}
+
+impl AutoCertBuilder {
+    fn new() -> Self {
+        Self {
+            directory_url: LETS_ENCRYPT_PRODUCTION.to_string(),
+            domains: Vec::new(),
+            contacts: Vec::new(),
+            cache_path: None,
+            challenge_type: ChallengeType::TlsAlpn01,
+            root_certs: DEFAULT_ROOT_CERTS.to_vec(),
+            terms_of_service_agreed: false,
+        }
+    }
+
+    /// Sets the directory url of the ACME server.
+    ///
+    /// Default is [`LETS_ENCRYPT_PRODUCTION`].
+    #[must_use]
+    pub fn directory_url(mut self, directory_url: impl Into<String>) -> Self {
+        self.directory_url = directory_url.into();
+        self
+    }
+
+    /// Adds a domain to the certificate.
+    ///
+    /// You can call this function multiple times to add more domains.
+    #[must_use]
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domains.push(domain.into());
+        self
+    }
+
+    /// Adds a contact url of the account, for example
+    /// `mailto:admin@example.com`.
+    #[must_use]
+    pub fn contact(mut self, contact: impl Into<String>) -> Self {
+        self.contacts.push(contact.into());
+        self
+    }
+
+    /// Sets the directory used to cache the account key and the
+    /// certificates.
+    #[must_use]
+    pub fn cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(path.into());
+        self
+    }
+
+    /// Sets the type of the challenge.
+    ///
+    /// Default is [`ChallengeType::TlsAlpn01`].
+    #[must_use]
+    pub fn challenge_type(mut self, challenge_type: ChallengeType) -> Self {
+        self.challenge_type = challenge_type;
+        self
+    }
+
+    /// Sets the PEM-encoded root certificates used to connect to the ACME
+    /// server.
+    ///
+    /// Default is the root certificates of Let's Encrypt.
+    #[must_use]
+    pub fn root_certs(mut self, root_certs: impl Into<Vec<u8>>) -> Self {
+        self.root_certs = root_certs.into();
+        self
+    }
+
+    /// Indicates that you have read and agreed to the terms of service of
+    /// the ACME server, such as the
+    /// [subscriber agreement](https://letsencrypt.org/repository/) of Let's
+    /// Encrypt.
+    ///
+    /// The ACME servers refuse to create the account unless the terms of
+    /// service are agreed, so the certificates cannot be obtained without
+    /// it. Default is `false`.
+    #[must_use]
+    pub fn terms_of_service_agreed(mut self, agreed: bool) -> Self {
+        self.terms_of_service_agreed = agreed;
+        self
+    }
+
+    /// Consumes this builder and returns an [`AutoCert`].
+    pub fn build(self) -> IoResult<AutoCert> {
+        if self.domains.is_empty() {
+            return Err(IoError::new(
+                ErrorKind::Other,
+                "at least one domain is required",
+            ));
+        }
+
+        Ok(AutoCert {
+            directory_url: self.directory_url,
+            domains: self.domains,
+            contacts: self.contacts,
+            cache_path: self.cache_path,
+            challenge_type: self.challenge_type,
+            root_certs: self.root_certs,
+            terms_of_service_agreed: self.terms_of_service_agreed,
+            http01_keys: Default::default(),
+        })
+    }
+}
+
+/// An endpoint that responds to the `http-01` challenges.
+///
+/// NOTE: You cannot create it directly and should use the
+/// [`AutoCert::http01_endpoint`] method to create it.
+#[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+pub struct Http01Endpoint {
+    keys: Http01Keys,
+}
+
+#[async_trait::async_trait]
+impl Endpoint for Http01Endpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let token = req.uri().path().rsplit('/').next().unwrap_or_default();
+        match self.keys.read().get(token) {
+            Some(key_authorization) => Response::builder()
+                .content_type("application/octet-stream")
+                .body(key_authorization.clone()),
+            None => StatusCode::NOT_FOUND.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::Uri;
+
+    #[test]
+    fn build() {
+        assert!(AutoCert::builder().build().is_err());
+        assert!(AutoCert::builder().domain("example.com").build().is_ok());
+    }
+
+    #[tokio::test]
+    async fn http01_endpoint() {
+        let auto_cert = AutoCert::builder().domain("example.com").build().unwrap();
+        let ep = auto_cert.http01_endpoint();
+        auto_cert
+            .http01_keys
+            .write()
+            .insert("abc".to_string(), "abc.def".to_string());
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/.well-known/acme-challenge/abc"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "abc.def");
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/.well-known/acme-challenge/def"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
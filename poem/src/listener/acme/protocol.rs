@@ -0,0 +1,92 @@
+//! The ACME protocol objects.
+//!
+//! Reference: <https://datatracker.ietf.org/doc/html/rfc8555#section-7.1>
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Directory {
+    pub(crate) new_nonce: String,
+    pub(crate) new_account: String,
+    pub(crate) new_order: String,
+    #[serde(default)]
+    pub(crate) meta: DirectoryMeta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DirectoryMeta {
+    pub(crate) terms_of_service: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NewAccountRequest<'a> {
+    pub(crate) only_return_existing: bool,
+    pub(crate) terms_of_service_agreed: bool,
+    pub(crate) contact: &'a [String],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Identifier {
+    #[serde(rename = "type")]
+    pub(crate) ty: String,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct NewOrderRequest {
+    pub(crate) identifiers: Vec<Identifier>,
+}
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Pending,
+    Ready,
+    Processing,
+    Valid,
+    Invalid,
+    Revoked,
+    Deactivated,
+    Expired,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Problem {
+    #[serde(rename = "type")]
+    pub(crate) ty: Option<String>,
+    pub(crate) detail: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Order {
+    pub(crate) status: Status,
+    pub(crate) authorizations: Vec<String>,
+    pub(crate) finalize: String,
+    pub(crate) certificate: Option<String>,
+    pub(crate) error: Option<Problem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Authorization {
+    pub(crate) identifier: Identifier,
+    pub(crate) status: Status,
+    pub(crate) challenges: Vec<Challenge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Challenge {
+    #[serde(rename = "type")]
+    pub(crate) ty: String,
+    pub(crate) url: String,
+    #[serde(default)]
+    pub(crate) token: String,
+    pub(crate) error: Option<Problem>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FinalizeRequest {
+    pub(crate) csr: String,
+}
@@ -7,7 +7,7 @@ use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, Result as IoResult};
 
 use crate::{
     listener::{Acceptor, Listener},
-    web::{LocalAddr, RemoteAddr},
+    web::{ClientCert, ConnectionInfo, LocalAddr, RemoteAddr},
 };
 
 /// Listener for the [`Listener::combine`](crate::listener::Listener::combine)
@@ -59,6 +59,25 @@ impl<A: Acceptor, B: Acceptor> Acceptor for Combined<A, B> {
             }
         }
     }
+
+    fn client_cert(&self, io: &Self::Io) -> Option<ClientCert> {
+        match io {
+            CombinedStream::A(io) => self.a.client_cert(io),
+            CombinedStream::B(io) => self.b.client_cert(io),
+        }
+    }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        match io {
+            CombinedStream::A(io) => self.a.connection_info(io),
+            CombinedStream::B(io) => self.b.connection_info(io),
+        }
+    }
+
+    fn set_http2(&mut self, enabled: bool) {
+        self.a.set_http2(enabled);
+        self.b.set_http2(enabled);
+    }
 }
 
 /// A IO stream for CombinedAcceptor.
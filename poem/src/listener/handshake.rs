@@ -0,0 +1,53 @@
+use std::{any::Any, future::Future, time::Duration};
+
+use tokio::{
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+
+type AnyResult = Box<dyn Any + Send>;
+
+/// Runs the handshakes of the accepted connections (e.g. the TLS handshake)
+/// in separate tasks, so a client that never completes the handshake does not
+/// delay the other connections.
+///
+/// The results are type erased, so that the acceptors that use it do not need
+/// to bound their type parameters on the struct.
+pub(crate) struct Handshakes {
+    tx: UnboundedSender<AnyResult>,
+    rx: UnboundedReceiver<AnyResult>,
+}
+
+impl Handshakes {
+    pub(crate) fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self { tx, rx }
+    }
+
+    /// Spawns a task for the handshake, it fails with [`ErrorKind::TimedOut`]
+    /// if it does not complete within the `timeout`.
+    pub(crate) fn spawn<T, F>(&self, timeout: Duration, timeout_msg: &'static str, fut: F)
+    where
+        T: Send + 'static,
+        F: Future<Output = IoResult<T>> + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let res = match tokio::time::timeout(timeout, fut).await {
+                Ok(res) => res,
+                Err(_) => Err(IoError::new(ErrorKind::TimedOut, timeout_msg)),
+            };
+            let _ = tx.send(Box::new(res));
+        });
+    }
+
+    /// Waits for the next completed handshake.
+    ///
+    /// This function is cancel safe.
+    pub(crate) async fn next<T: 'static>(&mut self) -> IoResult<T> {
+        // the sender is owned by `self`, so the channel is never closed
+        let res = self.rx.recv().await.expect("the channel is never closed");
+        *res.downcast::<IoResult<T>>()
+            .expect("all handshakes of an acceptor have the same output type")
+    }
+}
@@ -1,6 +1,11 @@
 //! Commonly used listeners.
 
+#[cfg(feature = "acme")]
+#[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+pub mod acme;
 mod combined;
+mod handshake;
+mod proxy_protocol;
 mod tcp;
 #[cfg(feature = "tls")]
 mod tls;
@@ -14,14 +19,15 @@ use std::{
 };
 
 pub use combined::{Combined, CombinedStream};
+pub use proxy_protocol::{ProxyProtocolAcceptor, ProxyProtocolListener, ProxyProtocolStream};
 pub use tcp::{TcpAcceptor, TcpListener};
 #[cfg(feature = "tls")]
-pub use tls::{TlsAcceptor, TlsConfig, TlsListener};
+pub use tls::{IntoTlsConfigStream, TlsAcceptor, TlsConfig, TlsListener};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, Result as IoResult};
 #[cfg(unix)]
 pub use unix::{UnixAcceptor, UnixListener};
 
-use crate::web::{LocalAddr, RemoteAddr};
+use crate::web::{ClientCert, ConnectionInfo, LocalAddr, RemoteAddr};
 
 /// Represents a acceptor type.
 #[async_trait::async_trait]
@@ -38,6 +44,29 @@ pub trait Acceptor: Send + Sync {
     /// established, the corresponding IO stream and the remote peer’s
     /// address will be returned.
     async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr)>;
+
+    /// Returns the certificate chain presented by the client of the
+    /// connection.
+    ///
+    /// The default implementation returns `None`.
+    fn client_cert(&self, _io: &Self::Io) -> Option<ClientCert> {
+        None
+    }
+
+    /// Returns the information of the connection, such as the TLS server
+    /// name.
+    ///
+    /// The default implementation returns an empty [`ConnectionInfo`].
+    fn connection_info(&self, _io: &Self::Io) -> ConnectionInfo {
+        ConnectionInfo::default()
+    }
+
+    /// Sets whether HTTP/2 is served on the accepted connections, it is
+    /// called by [`Server::http2`](crate::Server::http2).
+    ///
+    /// The TLS acceptors don't offer HTTP/2 by ALPN if it is disabled. The
+    /// default implementation does nothing.
+    fn set_http2(&mut self, _enabled: bool) {}
 }
 
 /// An owned dynamically typed Acceptor for use in cases where you can’t
@@ -63,6 +92,15 @@ pub trait AcceptorExt: Acceptor {
         Box::new(WrappedAcceptor(self))
     }
 
+    /// Consume this acceptor and return a new acceptor that parses the PROXY
+    /// protocol header, see [`ProxyProtocolListener`].
+    fn proxy_protocol(self) -> ProxyProtocolAcceptor<Self>
+    where
+        Self: Sized,
+    {
+        ProxyProtocolAcceptor::new(self)
+    }
+
     /// Consume this acceptor and return a new TLS acceptor.
     #[cfg(feature = "tls")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
@@ -87,7 +125,9 @@ pub trait Listener: Send {
 
     /// Combine two listeners.
     ///
-    /// You can call this function multiple times to combine more listeners.
+    /// You can call this function multiple times to combine more listeners,
+    /// the connections accepted by all of them are served by the same
+    /// endpoint.
     ///
     /// # Example
     ///
@@ -104,15 +144,42 @@ pub trait Listener: Send {
         Combined::new(self, other)
     }
 
+    /// Consume this listener and return a new listener that parses the PROXY
+    /// protocol header sent by the load balancer, see
+    /// [`ProxyProtocolListener`].
+    #[must_use]
+    fn proxy_protocol(self) -> ProxyProtocolListener<Self>
+    where
+        Self: Sized,
+    {
+        ProxyProtocolListener::new(self)
+    }
+
     /// Consume this listener and return a new TLS listener.
+    ///
+    /// The `config_stream` can be a [`TlsConfig`] or a stream of
+    /// [`TlsConfig`], see [`IntoTlsConfigStream`] for reloading the
+    /// certificates at runtime.
     #[cfg(feature = "tls")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
     #[must_use]
-    fn tls(self, config: TlsConfig) -> TlsListener<Self>
+    fn tls<S: IntoTlsConfigStream>(self, config_stream: S) -> TlsListener<Self, S>
+    where
+        Self: Sized,
+    {
+        TlsListener::new(self, config_stream)
+    }
+
+    /// Consume this listener and return a new TLS listener that obtains the
+    /// certificates from the ACME server automatically.
+    #[cfg(feature = "acme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "acme")))]
+    #[must_use]
+    fn acme(self, auto_cert: acme::AutoCert) -> acme::AutoCertListener<Self>
     where
         Self: Sized,
     {
-        TlsListener::new(self, config)
+        acme::AutoCertListener::new(self, auto_cert)
     }
 }
 
@@ -127,20 +194,40 @@ impl<T: Acceptor + ?Sized> Acceptor for Box<T> {
     async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr)> {
         self.as_mut().accept().await
     }
+
+    fn client_cert(&self, io: &Self::Io) -> Option<ClientCert> {
+        self.as_ref().client_cert(io)
+    }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        self.as_ref().connection_info(io)
+    }
+
+    fn set_http2(&mut self, enabled: bool) {
+        self.as_mut().set_http2(enabled)
+    }
 }
 
 /// An IO type for BoxAcceptor.
 pub struct BoxIo {
     reader: Box<dyn AsyncRead + Send + Unpin + 'static>,
     writer: Box<dyn AsyncWrite + Send + Unpin + 'static>,
+    client_cert: Option<ClientCert>,
+    connection_info: ConnectionInfo,
 }
 
 impl BoxIo {
-    fn new(io: impl AsyncRead + AsyncWrite + Send + Unpin + 'static) -> Self {
+    fn new(
+        io: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+        client_cert: Option<ClientCert>,
+        connection_info: ConnectionInfo,
+    ) -> Self {
         let (reader, writer) = tokio::io::split(io);
         Self {
             reader: Box::new(reader),
             writer: Box::new(writer),
+            client_cert,
+            connection_info,
         }
     }
 }
@@ -188,10 +275,26 @@ impl<T: Acceptor> Acceptor for WrappedAcceptor<T> {
     }
 
     async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr)> {
-        self.0
-            .accept()
-            .await
-            .map(|(io, local_addr, remote_addr)| (BoxIo::new(io), local_addr, remote_addr))
+        let (io, local_addr, remote_addr) = self.0.accept().await?;
+        let client_cert = self.0.client_cert(&io);
+        let connection_info = self.0.connection_info(&io);
+        Ok((
+            BoxIo::new(io, client_cert, connection_info),
+            local_addr,
+            remote_addr,
+        ))
+    }
+
+    fn client_cert(&self, io: &Self::Io) -> Option<ClientCert> {
+        io.client_cert.clone()
+    }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        io.connection_info.clone()
+    }
+
+    fn set_http2(&mut self, enabled: bool) {
+        self.0.set_http2(enabled)
     }
 }
 
@@ -241,4 +344,42 @@ mod tests {
 
         let _ = a.combine(b);
     }
+
+    #[tokio::test]
+    async fn combined_server() {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpStream,
+        };
+
+        use crate::{handler, Server};
+
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .combine(TcpListener::bind("127.0.0.1:0"))
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addrs = acceptor.local_addr();
+        assert_eq!(local_addrs.len(), 2);
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(index));
+
+        for local_addr in local_addrs {
+            let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+                .await
+                .unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut data = String::new();
+            stream.read_to_string(&mut data).await.unwrap();
+            assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+            assert!(data.ends_with("hello"));
+        }
+    }
 }
@@ -0,0 +1,422 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::{Buf, Bytes, BytesMut};
+use tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncWrite, Error as IoError, ErrorKind, ReadBuf, Result as IoResult,
+};
+
+use crate::{
+    listener::{handshake::Handshakes, Acceptor, Listener},
+    web::{ClientCert, ConnectionInfo, LocalAddr, RemoteAddr},
+};
+
+/// The signature of the PROXY protocol version 2.
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The length of the fixed part of the PROXY protocol version 2 header.
+const V2_HEADER_LENGTH: usize = 16;
+
+/// The maximum length of the PROXY protocol version 1 header.
+const V1_MAX_LENGTH: usize = 107;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A wrapper listener that parses the
+/// [PROXY protocol](https://www.haproxy.org/download/2.4/doc/proxy-protocol.txt)
+/// header sent by the load balancer.
+///
+/// Both the version 1 (text) and version 2 (binary) headers are supported, the
+/// source address in the header is used as the [`RemoteAddr`] of the
+/// requests. Connections without a valid header are rejected, so the inner
+/// listener must only be reachable by the load balancer.
+///
+/// The headers are read in a separate task for each connection, so a client
+/// that sends the header slowly does not delay the other connections.
+///
+/// # Example
+///
+/// ```
+/// use poem::listener::{Listener, TcpListener};
+///
+/// let listener = TcpListener::bind("0.0.0.0:3000").proxy_protocol();
+/// ```
+pub struct ProxyProtocolListener<T> {
+    inner: T,
+    timeout: Duration,
+}
+
+impl<T> ProxyProtocolListener<T> {
+    pub(crate) fn new(inner: T) -> Self {
+        Self {
+            inner,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout for reading the PROXY protocol header, default is `5`
+    /// seconds.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Listener> Listener for ProxyProtocolListener<T> {
+    type Acceptor = ProxyProtocolAcceptor<T::Acceptor>;
+
+    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
+        let mut acceptor = ProxyProtocolAcceptor::new(self.inner.into_acceptor().await?);
+        acceptor.timeout = self.timeout;
+        Ok(acceptor)
+    }
+}
+
+/// A acceptor that parses the PROXY protocol header, see
+/// [`ProxyProtocolListener`].
+pub struct ProxyProtocolAcceptor<T> {
+    inner: T,
+    timeout: Duration,
+    handshakes: Handshakes,
+}
+
+impl<T> ProxyProtocolAcceptor<T> {
+    pub(crate) fn new(inner: T) -> Self {
+        Self {
+            inner,
+            timeout: DEFAULT_TIMEOUT,
+            handshakes: Handshakes::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Acceptor> Acceptor for ProxyProtocolAcceptor<T> {
+    type Io = ProxyProtocolStream<T::Io>;
+
+    fn local_addr(&self) -> Vec<LocalAddr> {
+        self.inner.local_addr()
+    }
+
+    async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr)> {
+        loop {
+            tokio::select! {
+                res = self.inner.accept() => {
+                    let (stream, local_addr, remote_addr) = res?;
+                    self.handshakes.spawn(
+                        self.timeout,
+                        "timeout reading proxy protocol header",
+                        async move {
+                            let (stream, source) = read_header(stream).await?;
+                            let remote_addr = match source {
+                                Some(addr) => RemoteAddr(addr.into()),
+                                None => remote_addr,
+                            };
+                            Ok((stream, local_addr, remote_addr))
+                        },
+                    );
+                }
+                res = self.handshakes.next() => return res,
+            }
+        }
+    }
+
+    fn client_cert(&self, io: &Self::Io) -> Option<ClientCert> {
+        self.inner.client_cert(&io.inner)
+    }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        self.inner.connection_info(&io.inner)
+    }
+
+    fn set_http2(&mut self, enabled: bool) {
+        self.inner.set_http2(enabled)
+    }
+}
+
+/// A IO stream for [`ProxyProtocolAcceptor`], it returns the data received
+/// after the PROXY protocol header.
+pub struct ProxyProtocolStream<T> {
+    inner: T,
+    buf: Bytes,
+}
+
+impl<T> ProxyProtocolStream<T> {
+    /// Returns a reference to the inner stream.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for ProxyProtocolStream<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = &mut *self;
+        if !this.buf.is_empty() {
+            let len = this.buf.len().min(buf.remaining());
+            buf.put_slice(&this.buf[..len]);
+            this.buf.advance(len);
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for ProxyProtocolStream<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+fn invalid_header() -> IoError {
+    IoError::new(ErrorKind::InvalidData, "invalid proxy protocol header")
+}
+
+/// Reads the PROXY protocol header, and returns the stream and the source
+/// address. The address is `None` if the header does not contain it, e.g.
+/// health checks of the load balancer.
+async fn read_header<T: AsyncRead + Unpin>(
+    mut stream: T,
+) -> IoResult<(ProxyProtocolStream<T>, Option<SocketAddr>)> {
+    let mut buf = BytesMut::with_capacity(512);
+    loop {
+        if let Some((len, addr)) = parse_header(&buf)? {
+            buf.advance(len);
+            return Ok((
+                ProxyProtocolStream {
+                    inner: stream,
+                    buf: buf.freeze(),
+                },
+                addr,
+            ));
+        }
+        if stream.read_buf(&mut buf).await? == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+    }
+}
+
+/// Parses the PROXY protocol header at the beginning of `buf`, and returns the
+/// length of the header and the source address, or `None` if more data is
+/// required.
+fn parse_header(buf: &[u8]) -> IoResult<Option<(usize, Option<SocketAddr>)>> {
+    // the shortest v1 header `PROXY UNKNOWN\r\n` is longer than the v2 signature
+    if buf.len() < V2_SIGNATURE.len() {
+        return Ok(None);
+    }
+
+    if buf.starts_with(V2_SIGNATURE) {
+        if buf.len() < V2_HEADER_LENGTH {
+            return Ok(None);
+        }
+        let len = V2_HEADER_LENGTH + u16::from_be_bytes([buf[14], buf[15]]) as usize;
+        if buf.len() < len {
+            return Ok(None);
+        }
+        let addr = parse_v2(buf[12], buf[13], &buf[V2_HEADER_LENGTH..len])?;
+        Ok(Some((len, addr)))
+    } else if buf.starts_with(b"PROXY ") {
+        let data = &buf[..buf.len().min(V1_MAX_LENGTH)];
+        match data.windows(2).position(|w| w == b"\r\n") {
+            Some(pos) => Ok(Some((pos + 2, parse_v1(&buf[..pos])?))),
+            None if data.len() >= V1_MAX_LENGTH => Err(invalid_header()),
+            None => Ok(None),
+        }
+    } else {
+        Err(invalid_header())
+    }
+}
+
+fn parse_v1(line: &[u8]) -> IoResult<Option<SocketAddr>> {
+    let line = std::str::from_utf8(line).map_err(|_| invalid_header())?;
+    let mut parts = line.split(' ').skip(1);
+
+    match parts.next() {
+        Some("TCP4") | Some("TCP6") => {}
+        Some("UNKNOWN") => return Ok(None),
+        _ => return Err(invalid_header()),
+    }
+
+    let mut next = || parts.next().ok_or_else(invalid_header);
+    let src_ip: IpAddr = next()?.parse().map_err(|_| invalid_header())?;
+    let _dst_ip: IpAddr = next()?.parse().map_err(|_| invalid_header())?;
+    let src_port: u16 = next()?.parse().map_err(|_| invalid_header())?;
+    let _dst_port: u16 = next()?.parse().map_err(|_| invalid_header())?;
+    Ok(Some(SocketAddr::new(src_ip, src_port)))
+}
+
+fn parse_v2(ver_cmd: u8, family: u8, data: &[u8]) -> IoResult<Option<SocketAddr>> {
+    if ver_cmd >> 4 != 2 {
+        return Err(invalid_header());
+    }
+
+    match ver_cmd & 0x0f {
+        // LOCAL
+        0 => return Ok(None),
+        // PROXY
+        1 => {}
+        _ => return Err(invalid_header()),
+    }
+
+    match family >> 4 {
+        // AF_INET
+        1 if data.len() >= 12 => {
+            let ip = Ipv4Addr::new(data[0], data[1], data[2], data[3]);
+            let port = u16::from_be_bytes([data[8], data[9]]);
+            Ok(Some(SocketAddr::new(ip.into(), port)))
+        }
+        // AF_INET6
+        2 if data.len() >= 36 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&data[..16]);
+            let port = u16::from_be_bytes([data[32], data[33]]);
+            Ok(Some(SocketAddr::new(Ipv6Addr::from(octets).into(), port)))
+        }
+        // AF_UNSPEC, AF_UNIX
+        0 | 3 => Ok(None),
+        _ => Err(invalid_header()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+    use super::*;
+    use crate::listener::TcpListener;
+
+    async fn accept(header: &'static [u8]) -> IoResult<RemoteAddr> {
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .proxy_protocol()
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = acceptor.local_addr().remove(0);
+
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+                .await
+                .unwrap();
+            stream.write_all(header).await.unwrap();
+            stream.write_i32(10).await.unwrap();
+        });
+
+        let (mut stream, _, remote_addr) = acceptor.accept().await?;
+        assert_eq!(stream.read_i32().await.unwrap(), 10);
+        Ok(remote_addr)
+    }
+
+    #[tokio::test]
+    async fn v1() {
+        let remote_addr = accept(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            remote_addr.as_socket_addr(),
+            Some(&"192.168.0.1:56324".parse().unwrap())
+        );
+
+        let remote_addr = accept(b"PROXY TCP6 ::1 ::2 56324 443\r\n").await.unwrap();
+        assert_eq!(
+            remote_addr.as_socket_addr(),
+            Some(&"[::1]:56324".parse().unwrap())
+        );
+
+        let remote_addr = accept(b"PROXY UNKNOWN\r\n").await.unwrap();
+        assert!(remote_addr.as_socket_addr().unwrap().ip().is_loopback());
+    }
+
+    #[tokio::test]
+    async fn v2() {
+        let remote_addr = accept(
+            b"\r\n\r\n\0\r\nQUIT\n\x21\x11\x00\x0c\xc0\xa8\x00\x01\xc0\xa8\x00\x0b\xdc\x04\x01\xbb",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            remote_addr.as_socket_addr(),
+            Some(&"192.168.0.1:56324".parse().unwrap())
+        );
+
+        let remote_addr = accept(b"\r\n\r\n\0\r\nQUIT\n\x20\x00\x00\x00")
+            .await
+            .unwrap();
+        assert!(remote_addr.as_socket_addr().unwrap().ip().is_loopback());
+    }
+
+    #[tokio::test]
+    async fn invalid() {
+        assert!(accept(b"GET / HTTP/1.1\r\n\r\n").await.is_err());
+        assert!(accept(b"PROXY TCP4 abc 192.168.0.11 56324 443\r\n")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn slow_client() {
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .proxy_protocol()
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = *acceptor.local_addr().remove(0).as_socket_addr().unwrap();
+
+        // the header is sent in two parts, and the connection that never sends
+        // the header does not block it
+        let _idle = TcpStream::connect(local_addr).await.unwrap();
+        let mut stream = TcpStream::connect(local_addr).await.unwrap();
+        stream.write_all(b"PROXY TCP4 192.168.0.1 ").await.unwrap();
+        tokio::spawn(async move {
+            stream
+                .write_all(b"192.168.0.11 56324 443\r\nhello")
+                .await
+                .unwrap();
+            stream.write_i32(10).await.unwrap();
+        });
+
+        let (mut stream, _, remote_addr) = acceptor.accept().await.unwrap();
+        assert_eq!(
+            remote_addr.as_socket_addr(),
+            Some(&"192.168.0.1:56324".parse().unwrap())
+        );
+        let mut data = [0; 5];
+        stream.read_exact(&mut data).await.unwrap();
+        assert_eq!(&data, b"hello");
+        assert_eq!(stream.read_i32().await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .proxy_protocol()
+            .timeout(Duration::from_millis(100))
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = *acceptor.local_addr().remove(0).as_socket_addr().unwrap();
+
+        let _idle = TcpStream::connect(local_addr).await.unwrap();
+        let err = acceptor.accept().await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+}
@@ -1,19 +1,27 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use futures_util::{
+    stream::{self, BoxStream, Once},
+    Stream, StreamExt,
+};
+use parking_lot::Mutex;
 use tokio::io::{Error as IoError, ErrorKind, Result as IoResult};
 use tokio_rustls::{
     rustls::{
         AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, NoClientAuth,
-        RootCertStore, ServerConfig,
+        RootCertStore, ServerConfig, Session,
     },
     server::TlsStream,
 };
 
 use crate::{
-    listener::{Acceptor, Listener},
-    web::{LocalAddr, RemoteAddr},
+    listener::{handshake::Handshakes, Acceptor, Listener},
+    web::{ClientCert, ConnectionInfo, LocalAddr, RemoteAddr},
 };
 
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
 #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
 enum TlsClientAuth {
     Off,
@@ -22,12 +30,14 @@ enum TlsClientAuth {
 }
 
 /// TLS Config.
+#[derive(Clone)]
 #[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
 pub struct TlsConfig {
     cert: Vec<u8>,
     key: Vec<u8>,
     client_auth: TlsClientAuth,
     ocsp_resp: Vec<u8>,
+    http2: bool,
 }
 
 impl Default for TlsConfig {
@@ -44,6 +54,7 @@ impl TlsConfig {
             key: Vec::new(),
             client_auth: TlsClientAuth::Off,
             ocsp_resp: Vec::new(),
+            http2: true,
         }
     }
 
@@ -66,6 +77,9 @@ impl TlsConfig {
     }
 
     /// Sets the trust anchor for required client authentication.
+    ///
+    /// The verified certificate chain of the client can be extracted with
+    /// [`ClientCert`].
     pub fn client_auth_required(mut self, trust_anchor: impl Into<Vec<u8>>) -> Self {
         self.client_auth = TlsClientAuth::Required(trust_anchor.into());
         self
@@ -76,56 +90,30 @@ impl TlsConfig {
         self.ocsp_resp = ocsp_resp.into();
         self
     }
-}
-
-/// A wrapper around an underlying listener which implements the TLS or SSL
-/// protocol.
-///
-/// NOTE: You cannot create it directly and should use the
-/// [`tls`](crate::listener::Listener::tls) method to create it, because it
-/// needs to wrap a underlying listener.
-#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
-pub struct TlsListener<T> {
-    config: TlsConfig,
-    inner: T,
-}
-
-impl<T: Listener> TlsListener<T> {
-    pub(crate) fn new(inner: T, config: TlsConfig) -> Self {
-        Self { config, inner }
-    }
-}
-
-#[async_trait::async_trait]
-impl<T: Listener> Listener for TlsListener<T> {
-    type Acceptor = TlsAcceptor<T::Acceptor>;
 
-    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
-        TlsAcceptor::new(self.inner.into_acceptor().await?, self.config)
+    /// Sets whether HTTP/2 is offered by ALPN, the default is `true`.
+    ///
+    /// It is also not offered if HTTP/2 is disabled with
+    /// [`Server::http2`](crate::Server::http2), so this is only needed to
+    /// disable HTTP/2 for some of the listeners.
+    pub fn http2(mut self, enabled: bool) -> Self {
+        self.http2 = enabled;
+        self
     }
-}
 
-/// A TLS or SSL protocol acceptor.
-#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
-pub struct TlsAcceptor<T> {
-    acceptor: tokio_rustls::TlsAcceptor,
-    inner: T,
-}
-
-impl<T> TlsAcceptor<T> {
-    pub(crate) fn new(inner: T, config: TlsConfig) -> IoResult<Self> {
-        let cert = tokio_rustls::rustls::internal::pemfile::certs(&mut config.cert.as_slice())
+    fn create_server_config(self) -> IoResult<ServerConfig> {
+        let cert = tokio_rustls::rustls::internal::pemfile::certs(&mut self.cert.as_slice())
             .map_err(|_| IoError::new(ErrorKind::Other, "failed to parse tls certificates"))?;
         let key = {
             let mut pkcs8 = tokio_rustls::rustls::internal::pemfile::pkcs8_private_keys(
-                &mut config.key.as_slice(),
+                &mut self.key.as_slice(),
             )
             .map_err(|_| IoError::new(ErrorKind::Other, "failed to parse tls private keys"))?;
             if !pkcs8.is_empty() {
                 pkcs8.remove(0)
             } else {
                 let mut rsa = tokio_rustls::rustls::internal::pemfile::rsa_private_keys(
-                    &mut config.key.as_slice(),
+                    &mut self.key.as_slice(),
                 )
                 .map_err(|_| IoError::new(ErrorKind::Other, "failed to parse tls private keys"))?;
 
@@ -152,7 +140,7 @@ impl<T> TlsAcceptor<T> {
             }
         }
 
-        let client_auth = match config.client_auth {
+        let client_auth = match self.client_auth {
             TlsClientAuth::Off => NoClientAuth::new(),
             TlsClientAuth::Optional(trust_anchor) => {
                 AllowAnyAnonymousOrAuthenticatedClient::new(read_trust_anchor(&trust_anchor)?)
@@ -164,15 +152,171 @@ impl<T> TlsAcceptor<T> {
 
         let mut server_config = ServerConfig::new(client_auth);
         server_config
-            .set_single_cert_with_ocsp_and_sct(cert, key, config.ocsp_resp, Vec::new())
+            .set_single_cert_with_ocsp_and_sct(cert, key, self.ocsp_resp, Vec::new())
             .map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
-        server_config.set_protocols(&["h2".into(), "http/1.1".into()]);
+        if self.http2 {
+            server_config.set_protocols(&["h2".into(), "http/1.1".into()]);
+        } else {
+            server_config.set_protocols(&["http/1.1".into()]);
+        }
+        Ok(server_config)
+    }
+}
+
+/// Represents a type that can convert into tls config stream.
+///
+/// It is implemented for [`TlsConfig`] and any stream of [`TlsConfig`]. When
+/// the stream yields a new config, it is used for subsequent connections, so
+/// certificates can be reloaded without restarting the server.
+///
+/// # Example
+///
+/// ```
+/// use poem::listener::{Listener, TcpListener, TlsConfig};
+/// use tokio::sync::watch;
+///
+/// # let (cert, key) = ("", "");
+/// let (tx, rx) = watch::channel(TlsConfig::new().cert(cert).key(key));
+/// let config_stream = futures_util::stream::unfold((rx, true), |(mut rx, first)| async move {
+///     if !first {
+///         // wait for the next config sent by `tx.send(...)`
+///         rx.changed().await.ok()?;
+///     }
+///     let config = rx.borrow().clone();
+///     Some((config, (rx, false)))
+/// });
+///
+/// # drop(tx);
+/// let listener = TcpListener::bind("127.0.0.1:3000").tls(config_stream);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub trait IntoTlsConfigStream: Send + 'static {
+    /// Represents a tls config stream type.
+    type Stream: Stream<Item = TlsConfig> + Send + 'static;
+
+    /// Consume this object and return a tls config stream.
+    fn into_stream(self) -> IoResult<Self::Stream>;
+}
+
+impl IntoTlsConfigStream for TlsConfig {
+    type Stream = Once<futures_util::future::Ready<TlsConfig>>;
+
+    fn into_stream(self) -> IoResult<Self::Stream> {
+        Ok(stream::once(futures_util::future::ready(self)))
+    }
+}
+
+impl<T> IntoTlsConfigStream for T
+where
+    T: Stream<Item = TlsConfig> + Send + 'static,
+{
+    type Stream = T;
+
+    fn into_stream(self) -> IoResult<Self::Stream> {
+        Ok(self)
+    }
+}
+
+/// A wrapper around an underlying listener which implements the TLS or SSL
+/// protocol.
+///
+/// NOTE: You cannot create it directly and should use the
+/// [`tls`](crate::listener::Listener::tls) method to create it, because it
+/// needs to wrap a underlying listener.
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub struct TlsListener<T, S = TlsConfig> {
+    config_stream: S,
+    inner: T,
+    handshake_timeout: Duration,
+}
 
-        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server_config));
-        Ok(TlsAcceptor { acceptor, inner })
+impl<T: Listener, S: IntoTlsConfigStream> TlsListener<T, S> {
+    pub(crate) fn new(inner: T, config_stream: S) -> Self {
+        Self {
+            config_stream,
+            inner,
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+        }
+    }
+
+    /// Sets the timeout for the TLS handshake, default is `10` seconds.
+    #[must_use]
+    pub fn handshake_timeout(self, handshake_timeout: Duration) -> Self {
+        Self {
+            handshake_timeout,
+            ..self
+        }
     }
 }
 
+#[async_trait::async_trait]
+impl<T: Listener, S: IntoTlsConfigStream> Listener for TlsListener<T, S> {
+    type Acceptor = TlsAcceptor<T::Acceptor>;
+
+    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
+        let mut config_stream = self.config_stream.into_stream()?.boxed();
+        let config = config_stream
+            .next()
+            .await
+            .ok_or_else(|| IoError::new(ErrorKind::Other, "tls config stream is empty"))?;
+        let mut acceptor = TlsAcceptor::new(self.inner.into_acceptor().await?, config)?;
+        acceptor.config_stream = Some(Mutex::new(config_stream));
+        acceptor.handshake_timeout = self.handshake_timeout;
+        Ok(acceptor)
+    }
+}
+
+/// A TLS or SSL protocol acceptor.
+///
+/// The handshakes are done in a separate task for each connection, so a
+/// client that never completes the handshake does not delay the other
+/// connections.
+#[cfg_attr(docsrs, doc(cfg(feature = "tls")))]
+pub struct TlsAcceptor<T> {
+    acceptor: tokio_rustls::TlsAcceptor,
+    server_config: Arc<ServerConfig>,
+    http2: bool,
+    config_stream: Option<Mutex<BoxStream<'static, TlsConfig>>>,
+    handshake_timeout: Duration,
+    handshakes: Handshakes,
+    inner: T,
+}
+
+impl<T: Acceptor> TlsAcceptor<T> {
+    pub(crate) fn new(inner: T, config: TlsConfig) -> IoResult<Self> {
+        Ok(Self::from_server_config(
+            inner,
+            config.create_server_config()?,
+        ))
+    }
+
+    pub(crate) fn from_server_config(inner: T, server_config: ServerConfig) -> Self {
+        let server_config = Arc::new(server_config);
+        TlsAcceptor {
+            acceptor: tokio_rustls::TlsAcceptor::from(server_config.clone()),
+            server_config,
+            http2: true,
+            config_stream: None,
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            handshakes: Handshakes::new(),
+            inner,
+        }
+    }
+}
+
+/// Creates the acceptor for the `server_config`, which doesn't offer HTTP/2 by
+/// ALPN if `http2` is disabled.
+fn create_acceptor(server_config: &Arc<ServerConfig>, http2: bool) -> tokio_rustls::TlsAcceptor {
+    if http2 {
+        return tokio_rustls::TlsAcceptor::from(server_config.clone());
+    }
+    let mut server_config = ServerConfig::clone(server_config);
+    server_config
+        .alpn_protocols
+        .retain(|protocol| protocol.as_slice() != b"h2");
+    tokio_rustls::TlsAcceptor::from(Arc::new(server_config))
+}
+
 #[async_trait::async_trait]
 impl<T: Acceptor> Acceptor for TlsAcceptor<T> {
     type Io = TlsStream<T::Io>;
@@ -182,9 +326,83 @@ impl<T: Acceptor> Acceptor for TlsAcceptor<T> {
     }
 
     async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr)> {
-        let (stream, local_addr, remote_addr) = self.inner.accept().await?;
-        let stream = self.acceptor.accept(stream).await?;
-        Ok((stream, local_addr, remote_addr))
+        loop {
+            let Self {
+                acceptor,
+                server_config: current_server_config,
+                http2,
+                config_stream,
+                handshake_timeout,
+                handshakes,
+                inner,
+            } = &mut *self;
+
+            let next_config = async {
+                match config_stream {
+                    Some(config_stream) => config_stream.get_mut().next().await,
+                    None => futures_util::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                config = next_config => match config {
+                    Some(config) => match config.create_server_config() {
+                        Ok(server_config) => {
+                            tracing::info!("tls config reloaded");
+                            *current_server_config = Arc::new(server_config);
+                            *acceptor = create_acceptor(current_server_config, *http2);
+                        }
+                        Err(err) => tracing::error!(error = %err, "failed to reload tls config"),
+                    },
+                    None => *config_stream = None,
+                },
+                res = inner.accept() => {
+                    let (stream, local_addr, remote_addr) = res?;
+                    let accept = acceptor.accept(stream);
+                    handshakes.spawn(*handshake_timeout, "timeout during tls handshake", async move {
+                        Ok((accept.await?, local_addr, remote_addr))
+                    });
+                }
+                res = handshakes.next::<(Self::Io, LocalAddr, RemoteAddr)>() => {
+                    let (stream, local_addr, remote_addr) = res?;
+
+                    // the connection is only used for the `tls-alpn-01` challenge
+                    #[cfg(feature = "acme")]
+                    if stream.get_ref().1.get_alpn_protocol()
+                        == Some(crate::listener::acme::ACME_TLS_ALPN_NAME)
+                    {
+                        continue;
+                    }
+
+                    return Ok((stream, local_addr, remote_addr));
+                }
+            }
+        }
+    }
+
+    fn client_cert(&self, io: &Self::Io) -> Option<ClientCert> {
+        let (inner_io, session) = io.get_ref();
+        match session.get_peer_certificates() {
+            Some(certs) if !certs.is_empty() => Some(ClientCert::new(
+                certs.into_iter().map(|cert| cert.0).collect(),
+            )),
+            _ => self.inner.client_cert(inner_io),
+        }
+    }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        let (inner_io, session) = io.get_ref();
+        let mut info = self.inner.connection_info(inner_io);
+        info.server_name = session.get_sni_hostname().map(ToString::to_string);
+        info.alpn_protocol = session.get_alpn_protocol().map(<[u8]>::to_vec);
+        info.tls = true;
+        info
+    }
+
+    fn set_http2(&mut self, enabled: bool) {
+        self.inner.set_http2(enabled);
+        self.http2 = enabled;
+        self.acceptor = create_acceptor(&self.server_config, enabled);
     }
 }
 
@@ -371,6 +589,162 @@ B1Y0rlLoKG62pnkeXp1O4I57gnClatWRg5qw11a8V8e3jvDKIYM=
         });
 
         let (mut stream, _, _) = acceptor.accept().await.unwrap();
+        let info = acceptor.connection_info(&stream);
+        assert_eq!(info.server_name.as_deref(), Some("testserver.com"));
+        assert_eq!(info.alpn_protocol, None);
         assert_eq!(stream.read_i32().await.unwrap(), 10);
     }
+
+    /// Generates a self-signed certificate and the private key for `name`.
+    fn generate_cert(name: &str) -> (String, String) {
+        let cert = rcgen::generate_simple_self_signed(vec![name.to_string()]).unwrap();
+        (
+            cert.serialize_pem().unwrap(),
+            cert.serialize_private_key_pem(),
+        )
+    }
+
+    /// Connects to `addr` and verifies the certificate of `name` with the
+    /// trusted `cert`.
+    async fn connect(
+        addr: &LocalAddr,
+        cert: &str,
+        name: &str,
+        alpn_protocols: &[&[u8]],
+    ) -> IoResult<tokio_rustls::client::TlsStream<TcpStream>> {
+        let mut config = ClientConfig::new();
+        config
+            .root_store
+            .add_pem_file(&mut cert.as_bytes())
+            .unwrap();
+        config.alpn_protocols = alpn_protocols
+            .iter()
+            .map(|protocol| protocol.to_vec())
+            .collect();
+
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+        let domain = webpki::DNSNameRef::try_from_ascii_str(name).unwrap();
+        let stream = TcpStream::connect(*addr.as_socket_addr().unwrap()).await?;
+        connector.connect(domain, stream).await
+    }
+
+    #[tokio::test]
+    async fn http2() {
+        let (cert, key) = generate_cert("localhost");
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .tls(TlsConfig::new().key(key).cert(cert.clone()))
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = acceptor.local_addr().pop().unwrap();
+
+        for (http2, protocol) in [(true, &b"h2"[..]), (false, &b"http/1.1"[..])] {
+            acceptor.set_http2(http2);
+            let client = connect(&local_addr, &cert, "localhost", &[b"h2", b"http/1.1"]);
+            let (client, server) = tokio::join!(client, acceptor.accept());
+            assert_eq!(
+                client.unwrap().get_ref().1.get_alpn_protocol(),
+                Some(protocol)
+            );
+            let (stream, _, _) = server.unwrap();
+            assert_eq!(
+                acceptor.connection_info(&stream).alpn_protocol.as_deref(),
+                Some(protocol)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_client() {
+        let (cert, key) = generate_cert("localhost");
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .tls(TlsConfig::new().key(key).cert(cert.clone()))
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = acceptor.local_addr().pop().unwrap();
+
+        // the connection that never sends the client hello does not block the
+        // other connections
+        let _idle = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+            .await
+            .unwrap();
+        let client = async {
+            let mut stream = connect(&local_addr, &cert, "localhost", &[]).await.unwrap();
+            stream.write_i32(10).await.unwrap();
+            stream
+        };
+        let (_client, server) = tokio::join!(client, acceptor.accept());
+        let (mut stream, _, _) = server.unwrap();
+        assert_eq!(stream.read_i32().await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn handshake_timeout() {
+        let (cert, key) = generate_cert("localhost");
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .tls(TlsConfig::new().key(key).cert(cert))
+            .handshake_timeout(Duration::from_millis(100))
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = acceptor.local_addr().pop().unwrap();
+
+        let _idle = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+            .await
+            .unwrap();
+        let err = acceptor.accept().await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn empty_config_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").tls(futures_util::stream::empty());
+        assert!(listener.into_acceptor().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reload_config() {
+        let (old_cert, old_key) = generate_cert("localhost");
+        let (new_cert, new_key) = generate_cert("localhost");
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tx.send(TlsConfig::new().key(old_key).cert(old_cert.clone()))
+            .unwrap();
+        let config_stream = futures_util::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|config| (config, rx))
+        });
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .tls(config_stream)
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = acceptor.local_addr().pop().unwrap();
+
+        let client = connect(&local_addr, &old_cert, "localhost", &[]);
+        let (client, server) = tokio::join!(client, acceptor.accept());
+        assert!(client.is_ok());
+        assert!(server.is_ok());
+
+        // an invalid config is ignored, and the listener keeps the previous one.
+        tx.send(TlsConfig::new()).unwrap();
+        tx.send(TlsConfig::new().key(new_key).cert(new_cert.clone()))
+            .unwrap();
+        drop(tx);
+
+        let res = tokio::time::timeout(Duration::from_millis(100), acceptor.accept()).await;
+        assert!(res.is_err());
+        assert!(acceptor.config_stream.is_none());
+
+        // the new certificate is served
+        let client = connect(&local_addr, &new_cert, "localhost", &[]);
+        let (client, server) = tokio::join!(client, acceptor.accept());
+        assert!(client.is_ok());
+        assert!(server.is_ok());
+
+        let client = connect(&local_addr, &old_cert, "localhost", &[]);
+        let (client, server) = tokio::join!(client, acceptor.accept());
+        assert!(client.is_err());
+        assert!(server.is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use std::{io::Result, path::Path};
+use std::{fs::Permissions, io::Result, path::Path};
 
 use tokio::{
     io::Result as IoResult,
@@ -7,19 +7,43 @@ use tokio::{
 
 use crate::{
     listener::{Acceptor, Listener},
-    web::{LocalAddr, RemoteAddr},
+    web::{ConnectionInfo, LocalAddr, PeerCred, RemoteAddr},
 };
 
 /// A Unix domain socket listener.
 #[cfg_attr(docsrs, doc(cfg(unix)))]
 pub struct UnixListener<T> {
     path: T,
+    permissions: Option<Permissions>,
 }
 
 impl<T> UnixListener<T> {
     /// Binds to the provided address, and returns a [`UnixListener<T>`].
     pub fn bind(path: T) -> Self {
-        Self { path }
+        Self {
+            path,
+            permissions: None,
+        }
+    }
+
+    /// Sets the permissions of the socket file after it is created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+    ///
+    /// use poem::listener::UnixListener;
+    ///
+    /// let listener = UnixListener::bind("/tmp/poem.sock")
+    ///     .with_permissions(Permissions::from_mode(0o660));
+    /// ```
+    #[must_use]
+    pub fn with_permissions(self, permissions: Permissions) -> Self {
+        Self {
+            permissions: Some(permissions),
+            ..self
+        }
     }
 }
 
@@ -28,7 +52,10 @@ impl<T: AsRef<Path> + Send> Listener for UnixListener<T> {
     type Acceptor = UnixAcceptor;
 
     async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
-        let listener = TokioUnixListener::bind(self.path)?;
+        let listener = TokioUnixListener::bind(&self.path)?;
+        if let Some(permissions) = self.permissions {
+            std::fs::set_permissions(&self.path, permissions)?;
+        }
         let local_addr = listener
             .local_addr()
             .map(|addr| LocalAddr(addr.into()))
@@ -61,6 +88,17 @@ impl Acceptor for UnixAcceptor {
         let (stream, addr) = self.listener.accept().await?;
         Ok((stream, self.local_addr.clone(), RemoteAddr(addr.into())))
     }
+
+    fn connection_info(&self, io: &Self::Io) -> ConnectionInfo {
+        ConnectionInfo {
+            peer_cred: io.peer_cred().ok().map(|cred| PeerCred {
+                uid: cred.uid(),
+                gid: cred.gid(),
+                pid: cred.pid(),
+            }),
+            ..Default::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -83,10 +121,26 @@ mod tests {
         });
 
         let (mut stream, _, _) = acceptor.accept().await.unwrap();
+        assert!(acceptor.connection_info(&stream).peer_cred.is_some());
         assert_eq!(stream.read_i32().await.unwrap(), 10);
 
         tokio::time::sleep(Duration::from_secs(1)).await;
         drop(acceptor);
         std::fs::remove_file("test-socket").unwrap();
     }
+
+    #[tokio::test]
+    async fn permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let listener = UnixListener::bind("test-socket-permissions")
+            .with_permissions(Permissions::from_mode(0o600));
+        let acceptor = listener.into_acceptor().await.unwrap();
+
+        let metadata = std::fs::metadata("test-socket-permissions").unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+        drop(acceptor);
+        std::fs::remove_file("test-socket-permissions").unwrap();
+    }
 }
@@ -0,0 +1,253 @@
+use std::{
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    http::{header, Method, StatusCode, Version},
+    web::{MatchedPath, RealIp},
+    Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+type Formatter = Arc<dyn Fn(&AccessLogRecord<'_>) -> String + Send + Sync>;
+
+/// A record of the [`AccessLog`] middleware.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct AccessLogRecord<'a> {
+    /// The time when the request is received.
+    pub time: SystemTime,
+    /// The IP address of the client.
+    pub remote_ip: Option<IpAddr>,
+    /// The method of the request.
+    pub method: &'a Method,
+    /// The path and query of the request.
+    pub uri: &'a str,
+    /// The path pattern of the route that matches the request.
+    pub matched_path: Option<&'a str>,
+    /// The HTTP version of the request.
+    pub version: Version,
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The size of the response body, it is only known if the response has
+    /// the `Content-Length` header.
+    pub bytes: Option<u64>,
+    /// The time taken to produce the response.
+    pub latency: Duration,
+}
+
+/// The format of the access log.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AccessLogFormat {
+    /// The [Common Log Format](https://httpd.apache.org/docs/current/logs.html#common).
+    Common,
+    /// A JSON object per request.
+    Json,
+}
+
+/// Middleware for writing an access log record for each request.
+///
+/// The records are emitted as `tracing` events at the `INFO` level, in the
+/// [`AccessLogFormat::Common`] format by default.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::{AccessLog, AccessLogFormat},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .with(AccessLog::new().format(AccessLogFormat::Json));
+/// ```
+pub struct AccessLog {
+    formatter: Formatter,
+}
+
+impl Default for AccessLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccessLog {
+    /// Create `AccessLog` middleware.
+    pub fn new() -> Self {
+        Self {
+            formatter: Arc::new(format_common),
+        }
+    }
+
+    /// Sets the format of the records.
+    #[must_use]
+    pub fn format(self, format: AccessLogFormat) -> Self {
+        match format {
+            AccessLogFormat::Common => self.custom_format(format_common),
+            AccessLogFormat::Json => self.custom_format(format_json),
+        }
+    }
+
+    /// Sets the function that formats the records.
+    #[must_use]
+    pub fn custom_format<F>(self, f: F) -> Self
+    where
+        F: Fn(&AccessLogRecord<'_>) -> String + Send + Sync + 'static,
+    {
+        Self {
+            formatter: Arc::new(f),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for AccessLog {
+    type Output = AccessLogEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        AccessLogEndpoint {
+            inner: ep,
+            formatter: self.formatter.clone(),
+        }
+    }
+}
+
+/// Endpoint for AccessLog middleware.
+pub struct AccessLogEndpoint<E> {
+    inner: E,
+    formatter: Formatter,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for AccessLogEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let time = SystemTime::now();
+        let start = Instant::now();
+        let remote_ip = match req.extensions().get::<RealIp>() {
+            Some(RealIp(ip)) => *ip,
+            None => req.remote_addr().as_socket_addr().map(|addr| addr.ip()),
+        };
+        let method = req.method().clone();
+        let uri = req
+            .uri()
+            .path_and_query()
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| req.uri().path().to_string());
+        let version = req.version();
+
+        let resp = self.inner.call(req).await.into_response();
+
+        let record = AccessLogRecord {
+            time,
+            remote_ip,
+            method: &method,
+            uri: &uri,
+            matched_path: resp.extensions().get::<MatchedPath>().map(|path| &**path),
+            version,
+            status: resp.status(),
+            bytes: resp
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok()),
+            latency: start.elapsed(),
+        };
+        tracing::info!(target: module_path!(), "{}", (self.formatter)(&record));
+        resp
+    }
+}
+
+fn format_common(record: &AccessLogRecord<'_>) -> String {
+    format!(
+        "{} - - [{}] \"{} {} {:?}\" {} {}",
+        record
+            .remote_ip
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        format_clf_time(record.time),
+        record.method,
+        record.uri,
+        record.version,
+        record.status.as_u16(),
+        record
+            .bytes
+            .map(|bytes| bytes.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+    )
+}
+
+fn format_json(record: &AccessLogRecord<'_>) -> String {
+    serde_json::json!({
+        "time": format_clf_time(record.time),
+        "remote_ip": record.remote_ip.map(|ip| ip.to_string()),
+        "method": record.method.as_str(),
+        "uri": record.uri,
+        "matched_path": record.matched_path,
+        "version": format!("{:?}", record.version),
+        "status": record.status.as_u16(),
+        "bytes": record.bytes,
+        "latency_ms": record.latency.as_secs_f64() * 1000.0,
+    })
+    .to_string()
+}
+
+/// Formats the time as `10/Oct/2000:13:55:36 +0000`.
+fn format_clf_time(time: SystemTime) -> String {
+    // rearranges the HTTP-date `Tue, 10 Oct 2000 13:55:36 GMT`
+    let date = httpdate::fmt_http_date(time);
+    let parts = date.split(' ').collect::<Vec<_>>();
+    format!("{}/{}/{}:{} +0000", parts[1], parts[2], parts[3], parts[4])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::UNIX_EPOCH};
+
+    use super::*;
+
+    fn record() -> AccessLogRecord<'static> {
+        AccessLogRecord {
+            time: UNIX_EPOCH + Duration::from_secs(971186136),
+            remote_ip: Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            method: &Method::GET,
+            uri: "/users/1?a=1",
+            matched_path: Some("/users/:id"),
+            version: Version::HTTP_11,
+            status: StatusCode::OK,
+            bytes: Some(2326),
+            latency: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn common() {
+        assert_eq!(
+            format_common(&record()),
+            r#"127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /users/1?a=1 HTTP/1.1" 200 2326"#
+        );
+        assert_eq!(format_clf_time(UNIX_EPOCH), "01/Jan/1970:00:00:00 +0000");
+        assert_eq!(
+            format_clf_time(UNIX_EPOCH + Duration::from_secs(951782400)),
+            "29/Feb/2000:00:00:00 +0000"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let value: serde_json::Value = serde_json::from_str(&format_json(&record())).unwrap();
+        assert_eq!(value["method"], "GET");
+        assert_eq!(value["matched_path"], "/users/:id");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["bytes"], 2326);
+        assert_eq!(value["latency_ms"], 5.0);
+    }
+}
@@ -0,0 +1,587 @@
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyper::body::HttpBody;
+
+use crate::{
+    http::{
+        header::{self, HeaderName},
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    storage::KvStorage,
+    Body, Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+/// Middleware for caching the responses of the `GET` requests.
+///
+/// The responses are keyed by the host, the original URI and the values of the
+/// request headers listed in the `Vary` header of the response or specified by
+/// [`Cache::vary`], and kept in a [`KvStorage`], use the
+/// [`MemoryStorage::with_capacity`](crate::storage::MemoryStorage::with_capacity)
+/// for an in-memory LRU cache, or the `RedisStorage` to share the cache
+/// between multiple instances.
+///
+/// Only the `200 OK` responses with a body of known size are cached. The
+/// `Cache-Control` directives are honored:
+///
+/// - The requests with `no-store` are not cached, the requests with `no-cache`
+///   or `max-age=0` bypass the cache but update it.
+/// - The responses with `no-store`, `no-cache` or `private` are not cached,
+///   `s-maxage` or `max-age` overrides the default TTL.
+///
+/// The requests with the `Authorization` header, and the responses with the
+/// `Set-Cookie` or `Vary: *` header are never cached.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{handler, middleware::Cache, storage::MemoryStorage, EndpointExt, Route};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new().at("/", index).with(
+///     Cache::new(MemoryStorage::with_capacity(1000), Duration::from_secs(60))
+///         .vary("accept-encoding"),
+/// );
+/// ```
+pub struct Cache<S> {
+    storage: Arc<S>,
+    ttl: Duration,
+    vary: Vec<HeaderName>,
+    max_body_size: usize,
+}
+
+impl<S: KvStorage> Cache<S> {
+    /// Create `Cache` middleware that keeps the responses for `ttl` by
+    /// default.
+    pub fn new(storage: S, ttl: Duration) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            ttl,
+            vary: Vec::new(),
+            max_body_size: 1024 * 1024,
+        }
+    }
+
+    /// Appends a request header that the responses vary on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `name` is not a valid header name.
+    #[must_use]
+    pub fn vary(mut self, name: impl AsRef<str>) -> Self {
+        self.vary
+            .push(name.as_ref().parse().expect("illegal header name"));
+        self
+    }
+
+    /// Sets the maximum size of the response bodies that are cached, default
+    /// is `1MiB`.
+    #[must_use]
+    pub fn max_body_size(self, size: usize) -> Self {
+        Self {
+            max_body_size: size,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint, S: KvStorage + 'static> Middleware<E> for Cache<S> {
+    type Output = CacheEndpoint<E, S>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CacheEndpoint {
+            inner: ep,
+            storage: self.storage.clone(),
+            ttl: self.ttl,
+            vary: self.vary.clone(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+/// Endpoint for Cache middleware.
+pub struct CacheEndpoint<E, S> {
+    inner: E,
+    storage: Arc<S>,
+    ttl: Duration,
+    vary: Vec<HeaderName>,
+    max_body_size: usize,
+}
+
+#[derive(Default)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut cache_control = Self::default();
+        for value in headers.get_all(header::CACHE_CONTROL) {
+            let value = match value.to_str() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            for directive in value.split(',') {
+                let (name, arg) = match directive.split_once('=') {
+                    Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                    None => (directive.trim(), None),
+                };
+                let seconds = || arg.and_then(|arg| arg.parse().ok());
+                match name.to_ascii_lowercase().as_str() {
+                    "no-store" => cache_control.no_store = true,
+                    "no-cache" => cache_control.no_cache = true,
+                    "private" => cache_control.private = true,
+                    "max-age" => cache_control.max_age = seconds(),
+                    "s-maxage" => cache_control.s_maxage = seconds(),
+                    _ => {}
+                }
+            }
+        }
+        cache_control
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Encodes the response as the stored time, the status, the headers and the
+/// body.
+fn encode_response(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(body.len() + 256);
+    data.extend_from_slice(&now_secs().to_be_bytes());
+    data.extend_from_slice(&status.as_u16().to_be_bytes());
+    data.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+    for (name, value) in headers {
+        data.extend_from_slice(&(name.as_str().len() as u32).to_be_bytes());
+        data.extend_from_slice(name.as_str().as_bytes());
+        data.extend_from_slice(&(value.as_bytes().len() as u32).to_be_bytes());
+        data.extend_from_slice(value.as_bytes());
+    }
+    data.extend_from_slice(body);
+    data
+}
+
+fn decode_response(data: &[u8]) -> Option<Response> {
+    fn take<'a>(data: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        if data.len() < len {
+            return None;
+        }
+        let (value, rest) = data.split_at(len);
+        *data = rest;
+        Some(value)
+    }
+
+    fn take_u32(data: &mut &[u8]) -> Option<usize> {
+        Some(u32::from_be_bytes(take(data, 4)?.try_into().ok()?) as usize)
+    }
+
+    let mut data = data;
+    let stored_at = u64::from_be_bytes(take(&mut data, 8)?.try_into().ok()?);
+    let status =
+        StatusCode::from_u16(u16::from_be_bytes(take(&mut data, 2)?.try_into().ok()?)).ok()?;
+
+    let mut resp = Response::builder().status(status).finish();
+    for _ in 0..take_u32(&mut data)? {
+        let len = take_u32(&mut data)?;
+        let name = HeaderName::from_bytes(take(&mut data, len)?).ok()?;
+        let len = take_u32(&mut data)?;
+        let value = HeaderValue::from_bytes(take(&mut data, len)?).ok()?;
+        resp.headers_mut().append(name, value);
+    }
+    resp.headers_mut().insert(
+        header::AGE,
+        HeaderValue::from(now_secs().saturating_sub(stored_at)),
+    );
+    resp.set_body(data.to_vec());
+    Some(resp)
+}
+
+/// Returns the names of the headers listed in the `Vary` header, or `None` for
+/// `Vary: *`.
+fn vary_names(headers: &HeaderMap) -> Option<BTreeSet<String>> {
+    let mut names = BTreeSet::new();
+    for value in headers.get_all(header::VARY) {
+        for name in String::from_utf8_lossy(value.as_bytes()).split(',') {
+            match name.trim() {
+                "" => {}
+                "*" => return None,
+                name => {
+                    names.insert(name.to_ascii_lowercase());
+                }
+            }
+        }
+    }
+    Some(names)
+}
+
+/// Returns the key of the names of the headers that the response varies on.
+fn vary_key(base_key: &str) -> String {
+    format!("poem-cache-vary:{}", base_key)
+}
+
+/// Returns the key of the response that varies on the `names` headers.
+fn cache_key(base_key: &str, names: &BTreeSet<String>, headers: &HeaderMap) -> String {
+    let mut key = format!("poem-cache:{}", base_key);
+    for name in names {
+        key.push('\n');
+        key.push_str(name);
+        key.push(':');
+        for value in headers.get_all(name.as_str()) {
+            key.push_str(&String::from_utf8_lossy(value.as_bytes()));
+            key.push(',');
+        }
+    }
+    key
+}
+
+impl<E, S: KvStorage> CacheEndpoint<E, S> {
+    /// Returns the key of the host and the URI.
+    fn base_key(&self, req: &Request) -> String {
+        let host = req
+            .headers()
+            .get(header::HOST)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .or_else(|| req.original_uri().authority().map(ToString::to_string))
+            .unwrap_or_default();
+        let uri = req.original_uri();
+        format!(
+            "{}{}",
+            host,
+            uri.path_and_query().map(|p| p.as_str()).unwrap_or("/")
+        )
+    }
+
+    /// Returns the names of the configured vary headers and the headers in the
+    /// `names`.
+    fn vary_with(&self, names: impl IntoIterator<Item = String>) -> BTreeSet<String> {
+        self.vary
+            .iter()
+            .map(|name| name.as_str().to_string())
+            .chain(names)
+            .collect()
+    }
+
+    async fn get_cached(&self, base_key: &str, headers: &HeaderMap) -> Option<Response> {
+        let res = async {
+            let names = match self.storage.get(&vary_key(base_key)).await? {
+                Some(names) => names,
+                None => return Ok(None),
+            };
+            let names = self.vary_with(
+                String::from_utf8_lossy(&names)
+                    .split(',')
+                    .filter(|name| !name.is_empty())
+                    .map(ToString::to_string),
+            );
+            self.storage
+                .get(&cache_key(base_key, &names, headers))
+                .await
+        };
+        match res.await {
+            Ok(data) => data.and_then(|data| decode_response(&data)),
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to read the response cache");
+                None
+            }
+        }
+    }
+
+    async fn set_cached(
+        &self,
+        base_key: &str,
+        headers: &HeaderMap,
+        resp_vary: BTreeSet<String>,
+        data: &[u8],
+        ttl: Duration,
+    ) {
+        let res = async {
+            let names = resp_vary.iter().cloned().collect::<Vec<_>>().join(",");
+            self.storage
+                .set(&vary_key(base_key), names.as_bytes(), Some(ttl))
+                .await?;
+            let key = cache_key(base_key, &self.vary_with(resp_vary), headers);
+            self.storage.set(&key, data, Some(ttl)).await
+        };
+        if let Err(err) = res.await {
+            tracing::warn!(error = ?err, "failed to update the response cache");
+        }
+    }
+
+    /// Returns the TTL of the response, or `None` if it cannot be cached.
+    fn response_ttl(&self, resp: &Response) -> Option<Duration> {
+        if resp.status() != StatusCode::OK || resp.headers().contains_key(header::SET_COOKIE) {
+            return None;
+        }
+        let cache_control = CacheControl::from_headers(resp.headers());
+        if cache_control.no_store || cache_control.no_cache || cache_control.private {
+            return None;
+        }
+        let ttl = match cache_control.s_maxage.or(cache_control.max_age) {
+            Some(seconds) => Duration::from_secs(seconds),
+            None => self.ttl,
+        };
+        if ttl.is_zero() {
+            return None;
+        }
+        Some(ttl)
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint, S: KvStorage + 'static> Endpoint for CacheEndpoint<E, S> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if req.method() != Method::GET || req.headers().contains_key(header::AUTHORIZATION) {
+            return self.inner.call(req).await.into_response();
+        }
+
+        let cache_control = CacheControl::from_headers(req.headers());
+        if cache_control.no_store {
+            return self.inner.call(req).await.into_response();
+        }
+
+        let base_key = self.base_key(&req);
+        let headers = req.headers().clone();
+        if !cache_control.no_cache && cache_control.max_age != Some(0) {
+            if let Some(resp) = self.get_cached(&base_key, &headers).await {
+                return resp;
+            }
+        }
+
+        let mut resp = self.inner.call(req).await.into_response();
+        let (ttl, resp_vary) = match (self.response_ttl(&resp), vary_names(resp.headers())) {
+            (Some(ttl), Some(resp_vary)) => (ttl, resp_vary),
+            _ => return resp,
+        };
+
+        // the streaming bodies are not cached
+        let body = resp.take_body();
+        if !matches!(body.0.size_hint().exact(), Some(size) if size <= self.max_body_size as u64) {
+            resp.set_body(body);
+            return resp;
+        }
+        let body = match body.into_bytes().await {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to read the response body");
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .finish();
+            }
+        };
+        let data = encode_response(resp.status(), resp.headers(), &body);
+        self.set_cached(&base_key, &headers, resp_vary, &data, ttl)
+            .await;
+        resp.set_body(Body::from_bytes(body));
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{endpoint::make_sync, storage::MemoryStorage, EndpointExt};
+
+    #[tokio::test]
+    async fn cache() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let ep = make_sync({
+            let counter = counter.clone();
+            move |req| {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let mut resp = Response::builder().header("x-value", "a").body(format!(
+                    "{}:{}",
+                    req.uri().path(),
+                    n
+                ));
+                if req.uri().path() == "/private" {
+                    resp.headers_mut()
+                        .insert(header::CACHE_CONTROL, HeaderValue::from_static("private"));
+                }
+                resp
+            }
+        })
+        .with(Cache::new(MemoryStorage::new(), Duration::from_secs(60)).vary("accept-language"));
+        let get = |path: &'static str| Request::builder().uri(http::Uri::from_static(path));
+        let call = |req: Request| {
+            let ep = &ep;
+            async move {
+                let mut resp = ep.call(req).await;
+                (
+                    resp.headers().contains_key(header::AGE),
+                    resp.take_body().into_string().await.unwrap(),
+                )
+            }
+        };
+
+        assert_eq!(call(get("/a").finish()).await, (false, "/a:0".to_string()));
+        let resp = ep.call(get("/a").finish()).await;
+        assert_eq!(resp.headers().get("x-value").unwrap(), "a");
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "/a:0");
+
+        // vary headers
+        assert_eq!(
+            call(get("/a").header("accept-language", "fr").finish()).await,
+            (false, "/a:1".to_string())
+        );
+        assert_eq!(
+            call(get("/a").header("accept-language", "fr").finish()).await,
+            (true, "/a:1".to_string())
+        );
+
+        // request directives
+        assert_eq!(
+            call(get("/a").header("cache-control", "no-store").finish()).await,
+            (false, "/a:2".to_string())
+        );
+        assert_eq!(call(get("/a").finish()).await, (true, "/a:0".to_string()));
+        assert_eq!(
+            call(get("/a").header("cache-control", "no-cache").finish()).await,
+            (false, "/a:3".to_string())
+        );
+        assert_eq!(call(get("/a").finish()).await, (true, "/a:3".to_string()));
+
+        // response directives and methods
+        assert_eq!(
+            call(get("/private").finish()).await,
+            (false, "/private:4".to_string())
+        );
+        assert_eq!(
+            call(get("/private").finish()).await,
+            (false, "/private:5".to_string())
+        );
+        assert_eq!(
+            call(get("/a").method(Method::POST).finish()).await,
+            (false, "/a:6".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn max_age() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let ep = make_sync({
+            let counter = counter.clone();
+            move |_| {
+                Response::builder()
+                    .header(header::CACHE_CONTROL, "public, max-age=1")
+                    .body(counter.fetch_add(1, Ordering::SeqCst).to_string())
+            }
+        })
+        .with(Cache::new(MemoryStorage::new(), Duration::from_secs(60)));
+
+        let call = || async {
+            ep.call(Request::default())
+                .await
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+        };
+        assert_eq!(call().await, "0");
+        assert_eq!(call().await, "0");
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert_eq!(call().await, "1");
+    }
+
+    #[tokio::test]
+    async fn response_vary() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let ep = make_sync({
+            let counter = counter.clone();
+            move |req| {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let mut resp = Response::builder()
+                    .header(header::VARY, "Accept-Encoding")
+                    .body(n.to_string());
+                if req.uri().path() == "/any" {
+                    resp.headers_mut()
+                        .insert(header::VARY, HeaderValue::from_static("*"));
+                }
+                resp
+            }
+        })
+        .with(Cache::new(MemoryStorage::new(), Duration::from_secs(60)));
+        let call = |host: &'static str, path: &'static str, encoding: &'static str| {
+            let ep = &ep;
+            async move {
+                ep.call(
+                    Request::builder()
+                        .uri(http::Uri::from_static(path))
+                        .header(header::HOST, host)
+                        .header(header::ACCEPT_ENCODING, encoding)
+                        .finish(),
+                )
+                .await
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+            }
+        };
+
+        assert_eq!(call("a.com", "/", "gzip").await, "0");
+        assert_eq!(call("a.com", "/", "gzip").await, "0");
+        assert_eq!(call("a.com", "/", "br").await, "1");
+        assert_eq!(call("a.com", "/", "br").await, "1");
+        assert_eq!(call("a.com", "/", "gzip").await, "0");
+
+        // the host is a part of the key
+        assert_eq!(call("b.com", "/", "gzip").await, "2");
+        assert_eq!(call("b.com", "/", "gzip").await, "2");
+
+        // `Vary: *`
+        assert_eq!(call("a.com", "/any", "gzip").await, "3");
+        assert_eq!(call("a.com", "/any", "gzip").await, "4");
+    }
+
+    #[tokio::test]
+    async fn original_uri() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let storage = MemoryStorage::new();
+        let ep = || {
+            let counter = counter.clone();
+            make_sync(move |_| counter.fetch_add(1, Ordering::SeqCst).to_string())
+                .with(Cache::new(storage.clone(), Duration::from_secs(60)))
+        };
+        let app = crate::Route::new().nest("/a", ep()).nest("/b", ep());
+        let call = |path: &'static str| {
+            let app = &app;
+            async move {
+                app.call(
+                    Request::builder()
+                        .uri(http::Uri::from_static(path))
+                        .finish(),
+                )
+                .await
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+            }
+        };
+
+        assert_eq!(call("/a/x").await, "0");
+        assert_eq!(call("/b/x").await, "1");
+        assert_eq!(call("/a/x").await, "0");
+    }
+}
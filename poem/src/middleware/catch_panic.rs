@@ -0,0 +1,143 @@
+use std::{any::Any, panic::AssertUnwindSafe, sync::Arc};
+
+use futures_util::FutureExt;
+
+use crate::{http::StatusCode, Endpoint, IntoResponse, Middleware, Request, Response};
+
+type PanicHandler = Arc<dyn Fn(Box<dyn Any + Send>) -> Response + Send + Sync>;
+
+/// Middleware for catching the panics in the endpoints, and converting them
+/// into responses.
+///
+/// By default, the panic message is logged and the
+/// `500 Internal Server Error` status code is returned, use
+/// [`CatchPanic::with_handler`] to customize it.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::CatchPanic, EndpointExt, IntoResponse, Route,
+/// };
+///
+/// #[handler]
+/// fn index() {
+///     panic!("oops");
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .with(CatchPanic::new().with_handler(|_| {
+///         "something went wrong"
+///             .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+///     }));
+/// ```
+pub struct CatchPanic {
+    handler: PanicHandler,
+}
+
+impl Default for CatchPanic {
+    fn default() -> Self {
+        Self {
+            handler: Arc::new(|err| {
+                tracing::error!(
+                    message = panic_message(&*err).unwrap_or("unknown"),
+                    "the endpoint panicked"
+                );
+                StatusCode::INTERNAL_SERVER_ERROR.into()
+            }),
+        }
+    }
+}
+
+impl CatchPanic {
+    /// Create `CatchPanic` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the handler that converts the panic payload into a response.
+    #[must_use]
+    pub fn with_handler<F, R>(self, f: F) -> Self
+    where
+        F: Fn(Box<dyn Any + Send>) -> R + Send + Sync + 'static,
+        R: IntoResponse,
+    {
+        Self {
+            handler: Arc::new(move |err| f(err).into_response()),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for CatchPanic {
+    type Output = CatchPanicEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CatchPanicEndpoint {
+            inner: ep,
+            handler: self.handler.clone(),
+        }
+    }
+}
+
+/// Endpoint for CatchPanic middleware.
+pub struct CatchPanicEndpoint<E> {
+    inner: E,
+    handler: PanicHandler,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for CatchPanicEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        match AssertUnwindSafe(self.inner.call(req)).catch_unwind().await {
+            Ok(output) => output.into_response(),
+            Err(err) => (self.handler)(err),
+        }
+    }
+}
+
+fn panic_message(err: &(dyn Any + Send)) -> Option<&str> {
+    match err.downcast_ref::<&'static str>() {
+        Some(msg) => Some(msg),
+        None => err.downcast_ref::<String>().map(String::as_str),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, http::Uri, EndpointExt};
+
+    #[tokio::test]
+    async fn catch_panic() {
+        let index = |req: Request| {
+            if req.uri().path() == "/panic" {
+                panic!("oops");
+            }
+            "hello"
+        };
+
+        let resp = make_sync(index)
+            .with(CatchPanic::new())
+            .call(Request::builder().uri(Uri::from_static("/panic")).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let ep = make_sync(index).with(CatchPanic::new().with_handler(|err| {
+            panic_message(&*err)
+                .unwrap_or_default()
+                .to_string()
+                .with_status(StatusCode::SERVICE_UNAVAILABLE)
+        }));
+        let mut resp = ep
+            .call(Request::builder().uri(Uri::from_static("/panic")).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "oops");
+
+        let resp = ep.call(Request::default()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -1,21 +1,54 @@
 use std::{cmp::Reverse, str::FromStr};
 
+use hyper::body::HttpBody;
 use typed_headers::{AcceptEncoding, ContentCoding, HeaderMapExt};
 
 use crate::{
-    http::header,
+    http::{header, HeaderValue},
     web::{Compress, CompressionAlgo},
     Body, Endpoint, IntoResponse, Middleware, Request, Response,
 };
 
+/// The default minimum size of the response body to compress.
+const DEFAULT_MIN_SIZE: u64 = 1024;
+
 /// Middleware for decompress request body and compress response body.
 ///
 /// It selects the decompression algorithm according to the request
 /// `Content-Encoding` header, and selects the compression algorithm according
 /// to the request `Accept-Encoding` header.
+///
+/// The response body is not compressed if it is smaller than the
+/// [`Compression::min_size`], if it already has the `Content-Encoding`
+/// header, or if its content type is already compressed, such as images,
+/// videos and archives.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, middleware::Compression, EndpointExt};
+///
+/// #[handler]
+/// fn index() -> String {
+///     "hello".repeat(1000)
+/// }
+///
+/// let app = index.with(Compression::new().min_size(256));
+/// ```
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
-#[derive(Default)]
-pub struct Compression;
+pub struct Compression {
+    min_size: u64,
+    decompress_request: bool,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            min_size: DEFAULT_MIN_SIZE,
+            decompress_request: true,
+        }
+    }
+}
 
 impl Compression {
     /// Creates a new `Compression` middleware.
@@ -23,13 +56,36 @@ impl Compression {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Sets the minimum size of the response body to compress, default is
+    /// `1024` bytes.
+    ///
+    /// The body whose size is unknown, such as streams, is always compressed.
+    #[must_use]
+    pub fn min_size(self, min_size: u64) -> Self {
+        Self { min_size, ..self }
+    }
+
+    /// Specifies whether to decompress the request body according to the
+    /// `Content-Encoding` header, default is `true`.
+    #[must_use]
+    pub fn decompress_request(self, enable: bool) -> Self {
+        Self {
+            decompress_request: enable,
+            ..self
+        }
+    }
 }
 
 impl<E: Endpoint> Middleware<E> for Compression {
     type Output = CompressionEndpoint<E>;
 
     fn transform(&self, ep: E) -> Self::Output {
-        CompressionEndpoint { ep }
+        CompressionEndpoint {
+            ep,
+            min_size: self.min_size,
+            decompress_request: self.decompress_request,
+        }
     }
 }
 
@@ -37,6 +93,51 @@ impl<E: Endpoint> Middleware<E> for Compression {
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
 pub struct CompressionEndpoint<E: Endpoint> {
     ep: E,
+    min_size: u64,
+    decompress_request: bool,
+}
+
+/// Returns `true` if the content type is already compressed.
+fn is_compressed_content_type(content_type: &str) -> bool {
+    let mime = match content_type.parse::<mime::Mime>() {
+        Ok(mime) => mime,
+        Err(_) => return false,
+    };
+
+    match (mime.type_(), mime.subtype().as_str()) {
+        (mime::IMAGE, subtype) => subtype != "svg",
+        (mime::AUDIO, _) | (mime::VIDEO, _) => true,
+        // server-sent events must be flushed immediately
+        (mime::TEXT, "event-stream") => true,
+        (mime::APPLICATION, subtype) => matches!(
+            subtype,
+            "zip"
+                | "gzip"
+                | "x-gzip"
+                | "zstd"
+                | "x-bzip2"
+                | "x-xz"
+                | "x-7z-compressed"
+                | "pdf"
+                | "wasm"
+        ),
+        _ => false,
+    }
+}
+
+fn negotiate(req: &Request) -> Option<CompressionAlgo> {
+    let mut encoding = req.headers().typed_get::<AcceptEncoding>().ok()??;
+    encoding.0.sort_by_key(|item| Reverse(item.quality));
+    encoding
+        .0
+        .iter()
+        .take_while(|item| item.quality.as_u16() > 0)
+        .find_map(|item| match item.item {
+            ContentCoding::BROTLI => Some(CompressionAlgo::BR),
+            ContentCoding::DEFLATE => Some(CompressionAlgo::DEFLATE),
+            ContentCoding::STAR | ContentCoding::GZIP => Some(CompressionAlgo::GZIP),
+            _ => None,
+        })
 }
 
 #[async_trait::async_trait]
@@ -45,35 +146,53 @@ impl<E: Endpoint> Endpoint for CompressionEndpoint<E> {
 
     async fn call(&self, mut req: Request) -> Self::Output {
         // decompress request body
-        if let Some(algo) = req
-            .headers()
-            .get(header::CONTENT_ENCODING)
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| CompressionAlgo::from_str(value).ok())
-        {
-            let new_body = algo.decompress(req.take_body().into_async_read());
-            req.set_body(Body::from_async_read(new_body));
+        if self.decompress_request {
+            if let Some(algo) = req
+                .headers()
+                .get(header::CONTENT_ENCODING)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| CompressionAlgo::from_str(value).ok())
+            {
+                let new_body = algo.decompress(req.take_body().into_async_read());
+                req.set_body(Body::from_async_read(new_body));
+                req.headers_mut().remove(header::CONTENT_ENCODING);
+                req.headers_mut().remove(header::CONTENT_LENGTH);
+            }
         }
 
         // negotiate content-encoding
-        let mut compress_algo = None;
-
-        if let Ok(Some(mut encoding)) = req.headers().typed_get::<AcceptEncoding>() {
-            encoding.0.sort_by_key(|item| Reverse(item.quality));
-            if let Some(item) = encoding.0.get(0) {
-                compress_algo = match item.item {
-                    ContentCoding::BROTLI => Some(CompressionAlgo::BR),
-                    ContentCoding::DEFLATE => Some(CompressionAlgo::DEFLATE),
-                    ContentCoding::STAR | ContentCoding::GZIP => Some(CompressionAlgo::GZIP),
-                    _ => None,
-                }
-            }
+        let compress_algo = negotiate(&req);
+        let mut resp = self.ep.call(req).await.into_response();
+        resp.headers_mut()
+            .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+        let algo = match compress_algo {
+            Some(algo) => algo,
+            None => return resp,
+        };
+
+        if resp.headers().contains_key(header::CONTENT_ENCODING)
+            || resp
+                .content_type()
+                .map(is_compressed_content_type)
+                .unwrap_or_default()
+        {
+            return resp;
         }
 
-        match compress_algo {
-            Some(algo) => Compress::new(self.ep.call(req).await, algo).into_response(),
-            None => self.ep.call(req).await.into_response(),
+        let body = resp.take_body();
+        let too_small = body
+            .0
+            .size_hint()
+            .exact()
+            .map(|size| size < self.min_size)
+            .unwrap_or_default();
+        resp.set_body(body);
+        if too_small {
+            return resp;
         }
+
+        Compress::new(resp, algo).into_response()
     }
 }
 
@@ -93,7 +212,7 @@ mod tests {
     }
 
     async fn test_algo(algo: CompressionAlgo) {
-        let ep = index.with(Compression);
+        let ep = index.with(Compression::new().min_size(0));
         let mut resp = ep
             .call(
                 Request::builder()
@@ -125,7 +244,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_negotiate() {
-        let ep = index.with(Compression);
+        let ep = index.with(Compression::new().min_size(0));
         let mut resp = ep
             .call(
                 Request::builder()
@@ -149,7 +268,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_star() {
-        let ep = index.with(Compression);
+        let ep = index.with(Compression::new().min_size(0));
         let mut resp = ep
             .call(
                 Request::builder()
@@ -170,4 +289,58 @@ mod tests {
         reader.read_to_end(&mut data).await.unwrap();
         assert_eq!(data, DATA_REV.as_bytes());
     }
+
+    #[test]
+    fn test_compressed_content_type() {
+        assert!(is_compressed_content_type("image/png"));
+        assert!(is_compressed_content_type("video/mp4"));
+        assert!(is_compressed_content_type("application/zip"));
+        assert!(is_compressed_content_type("text/event-stream"));
+        assert!(!is_compressed_content_type("image/svg+xml"));
+        assert!(!is_compressed_content_type("text/plain; charset=utf-8"));
+        assert!(!is_compressed_content_type("application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_skip() {
+        #[handler(internal)]
+        fn png() -> Response {
+            Response::builder().content_type("image/png").body(DATA)
+        }
+
+        let resp = index
+            .with(Compression::new())
+            .call(
+                Request::builder()
+                    .header("Accept-Encoding", "gzip")
+                    .body(DATA),
+            )
+            .await;
+        assert!(resp.headers().get("Content-Encoding").is_none());
+        assert_eq!(resp.headers().get("Vary").unwrap(), "accept-encoding");
+        assert_eq!(resp.into_body().into_string().await.unwrap(), DATA_REV);
+
+        let resp = png
+            .with(Compression::new().min_size(0))
+            .call(
+                Request::builder()
+                    .header("Accept-Encoding", "gzip")
+                    .finish(),
+            )
+            .await;
+        assert!(resp.headers().get("Content-Encoding").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_no_decompress_request() {
+        let ep = index.with(Compression::new().decompress_request(false));
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "gzip")
+                    .body(DATA),
+            )
+            .await;
+        assert_eq!(resp.into_body().into_string().await.unwrap(), DATA_REV);
+    }
 }
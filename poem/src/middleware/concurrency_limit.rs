@@ -0,0 +1,212 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::priority_limit::Limiter;
+use crate::{http::StatusCode, Endpoint, Error, Middleware, Request, Result};
+
+/// Middleware for limiting the number of the requests processed concurrently.
+///
+/// When the limit is reached, the new requests wait in a queue until the
+/// others are completed. The queue is unbounded by default, use
+/// [`ConcurrencyLimit::max_queue`] to reject the requests with
+/// `503 Service Unavailable` when it is full, and combine it with the
+/// [`Timeout`](crate::middleware::Timeout) middleware to bound the waiting
+/// time.
+///
+/// The limit is shared by all the endpoints created by the same
+/// `ConcurrencyLimit`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     handler,
+///     middleware::{ConcurrencyLimit, Timeout},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .with(ConcurrencyLimit::new(100).max_queue(1000))
+///     .with(Timeout::new(Duration::from_secs(30)));
+/// ```
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    max_concurrency: usize,
+    max_queue: usize,
+    limiter: Arc<Limiter>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyLimit {
+    /// Create `ConcurrencyLimit` middleware with the maximum number of
+    /// concurrent requests.
+    pub fn new(max_concurrency: usize) -> Self {
+        Self {
+            max_concurrency,
+            max_queue: usize::MAX,
+            limiter: Default::default(),
+            queued: Default::default(),
+        }
+    }
+
+    /// Sets the maximum number of the requests waiting in the queue, the
+    /// requests exceeding it are rejected with `503 Service Unavailable`.
+    ///
+    /// Use `0` to reject the requests immediately when the limit is reached.
+    #[must_use]
+    pub fn max_queue(self, max_queue: usize) -> Self {
+        Self { max_queue, ..self }
+    }
+
+    /// Returns the number of the requests that can be processed immediately.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.max_concurrency
+            .saturating_sub(self.limiter.in_flight())
+    }
+
+    /// Returns the number of the requests waiting in the queue.
+    #[inline]
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ConcurrencyLimit {
+    type Output = ConcurrencyLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ConcurrencyLimitEndpoint {
+            inner: ep,
+            limit: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for ConcurrencyLimit middleware.
+pub struct ConcurrencyLimitEndpoint<E> {
+    inner: E,
+    limit: ConcurrencyLimit,
+}
+
+struct QueueGuard(Arc<AtomicUsize>);
+
+impl Drop for QueueGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for ConcurrencyLimitEndpoint<E> {
+    type Output = Result<E::Output>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let limit = &self.limit;
+        let _permit = match limit.limiter.try_acquire(limit.max_concurrency) {
+            Some(permit) => permit,
+            None => {
+                let max_queue = limit.max_queue;
+                if limit
+                    .queued
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                        if n < max_queue {
+                            Some(n + 1)
+                        } else {
+                            None
+                        }
+                    })
+                    .is_err()
+                {
+                    tracing::debug!("request rejected, the queue is full");
+                    return Err(Error::new(StatusCode::SERVICE_UNAVAILABLE));
+                }
+                let _guard = QueueGuard(limit.queued.clone());
+                limit.limiter.acquire(limit.max_concurrency).await
+            }
+        };
+        Ok(self.inner.call(req).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{endpoint::make, EndpointExt, IntoResponse};
+
+    #[tokio::test]
+    async fn concurrency_limit() {
+        let limit = ConcurrencyLimit::new(1).max_queue(1);
+        let (started_tx, started_rx) = oneshot::channel();
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let channels = Arc::new(std::sync::Mutex::new(Some((started_tx, release_rx))));
+        let ep = Arc::new(
+            make(move |_| {
+                let channels = channels.lock().unwrap().take();
+                async move {
+                    if let Some((started_tx, release_rx)) = channels {
+                        started_tx.send(()).unwrap();
+                        release_rx.await.unwrap();
+                    }
+                }
+            })
+            .with(limit.clone()),
+        );
+
+        let first = tokio::spawn({
+            let ep = ep.clone();
+            async move { ep.call(Request::default()).await.into_response() }
+        });
+        started_rx.await.unwrap();
+        assert_eq!(limit.available(), 0);
+
+        let second = ep.call(Request::default());
+        futures_util::pin_mut!(second);
+        assert!(futures_util::poll!(&mut second).is_pending());
+        assert_eq!(limit.queued(), 1);
+
+        assert_eq!(
+            ep.call(Request::default()).await.into_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        release_tx.send(()).unwrap();
+        assert_eq!(first.await.unwrap().status(), StatusCode::OK);
+        assert_eq!(second.await.into_response().status(), StatusCode::OK);
+        assert_eq!(limit.queued(), 0);
+        assert_eq!(limit.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn cancel_queued() {
+        let limit = ConcurrencyLimit::new(1);
+        let ep = make(|_| async {}).with(limit.clone());
+        let permit = limit.limiter.try_acquire(1).unwrap();
+
+        let mut queued = ep.call(Request::default());
+        assert!(futures_util::poll!(&mut queued).is_pending());
+        assert_eq!(limit.queued(), 1);
+
+        let next = ep.call(Request::default());
+        futures_util::pin_mut!(next);
+        assert!(futures_util::poll!(&mut next).is_pending());
+
+        drop(permit);
+        drop(queued);
+        assert_eq!(limit.queued(), 1);
+        assert_eq!(next.await.into_response().status(), StatusCode::OK);
+        assert_eq!(limit.queued(), 0);
+    }
+}
@@ -63,6 +63,12 @@ impl<E: Endpoint> Endpoint for CookieJarManagerEndpoint<E> {
             cookie_jar.append_delta_to_headers(resp.headers_mut());
             resp
         } else {
+            // the outer `CookieJarManager` may not have a key
+            if let (Some(cookie_jar), Some(key)) = (&mut req.state_mut().cookie_jar, &self.key) {
+                if cookie_jar.key.is_none() {
+                    cookie_jar.key = Some(key.clone());
+                }
+            }
             self.inner.call(req).await.into_response()
         }
     }
@@ -124,4 +130,39 @@ mod tests {
             .await;
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_nested_cookie_jar_manager_with_key() {
+        #[handler(internal)]
+        async fn index(cookie_jar: &CookieJar) {
+            cookie_jar.signed().add(Cookie::new_with_str("value", "88"));
+        }
+
+        let key = CookieKey::generate();
+        let ep = index
+            .with(CookieJarManager::with_key(key.clone()))
+            .with(CookieJarManager::new());
+        let resp = ep.call(Request::default()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let cookie_jar = CookieJar::default();
+        cookie_jar.add(
+            Cookie::parse(
+                resp.headers()
+                    .get(crate::http::header::SET_COOKIE)
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            cookie_jar
+                .signed_with_key(&key)
+                .get("value")
+                .unwrap()
+                .value_str(),
+            "88"
+        );
+    }
 }
@@ -19,15 +19,29 @@ use crate::{
 
 /// Middleware for CORS
 ///
+/// The origins can be allowed by exact values with [`Cors::allow_origin`], or
+/// by a predicate with [`Cors::allow_origins_fn`], any origin is allowed if
+/// neither of them is specified.
+///
+/// The preflight requests are answered by this middleware without calling
+/// the inner endpoint, so apply it to the whole [`Route`](crate::Route)
+/// instead of the individual endpoints, then the routes do not need to
+/// handle the `OPTIONS` method.
+///
 /// # Example
 ///
 /// ```
-/// use poem::{http::Method, middleware::Cors};
+/// use poem::{handler, http::Method, middleware::Cors, post, EndpointExt, Route};
+///
+/// #[handler]
+/// fn create_user() {}
 ///
 /// let cors = Cors::new()
+///     .allow_origin("https://example.com")
 ///     .allow_method(Method::GET)
 ///     .allow_method(Method::POST)
 ///     .allow_credentials(false);
+/// let app = Route::new().at("/users", post(create_user)).with(cors);
 /// ```
 #[derive(Default)]
 pub struct Cors {
@@ -244,11 +258,19 @@ impl<E> CorsEndpoint<E> {
         )
     }
 
-    fn build_preflight_response(&self, origin: &HeaderValue) -> Response {
+    fn build_preflight_response(
+        &self,
+        origin: &HeaderValue,
+        request_headers: Option<&HeaderValue>,
+    ) -> Response {
         let mut builder = Response::builder()
             .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin)
             .typed_header(self.expose_headers_header.clone())
-            .header(header::ACCESS_CONTROL_MAX_AGE, self.max_age);
+            .header(header::ACCESS_CONTROL_MAX_AGE, self.max_age)
+            .header(
+                header::VARY,
+                "Origin, Access-Control-Request-Method, Access-Control-Request-Headers",
+            );
 
         if self.allow_methods.is_empty() {
             builder = builder.typed_header(
@@ -272,7 +294,14 @@ impl<E> CorsEndpoint<E> {
         }
 
         if self.allow_headers.is_empty() {
-            builder = builder.header(header::ACCESS_CONTROL_ALLOW_HEADERS, "*");
+            // the wildcard is not supported for the requests with credentials,
+            // so reflect the requested headers
+            match request_headers.filter(|_| self.allow_credentials) {
+                Some(request_headers) => {
+                    builder = builder.header(header::ACCESS_CONTROL_ALLOW_HEADERS, request_headers)
+                }
+                None => builder = builder.header(header::ACCESS_CONTROL_ALLOW_HEADERS, "*"),
+            }
         } else {
             builder = builder.typed_header(self.allow_headers_header.clone());
         }
@@ -303,7 +332,13 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
             return Err(Error::new(StatusCode::UNAUTHORIZED));
         }
 
-        if req.method() == Method::OPTIONS {
+        // a preflight request is an `OPTIONS` request with the
+        // `Access-Control-Request-Method` header
+        if req.method() == Method::OPTIONS
+            && req
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
             let allow_method = req
                 .headers()
                 .get(header::ACCESS_CONTROL_REQUEST_METHOD)
@@ -320,33 +355,29 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
                 return Err(Error::new(StatusCode::UNAUTHORIZED));
             }
 
-            let allow_headers = {
-                let mut allow_headers = true;
-                if !self.allow_headers.is_empty() {
-                    if let Some(request_header) =
-                        req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS)
-                    {
-                        allow_headers = false;
-                        if let Ok(s) = request_header.to_str() {
-                            for header in s.split(',') {
-                                if let Ok(header) = HeaderName::from_str(header.trim()) {
-                                    if self.allow_headers.contains(&header) {
-                                        allow_headers = true;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                allow_headers
+            let request_headers = req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS);
+            let allow_headers = match request_headers {
+                Some(request_headers) if !self.allow_headers.is_empty() => request_headers
+                    .to_str()
+                    .map(|s| {
+                        s.split(',')
+                            .map(str::trim)
+                            .filter(|header| !header.is_empty())
+                            .all(|header| {
+                                HeaderName::from_str(header)
+                                    .map(|header| self.allow_headers.contains(&header))
+                                    .unwrap_or_default()
+                            })
+                    })
+                    .unwrap_or_default(),
+                _ => true,
             };
 
             if !allow_headers {
                 return Err(Error::new(StatusCode::UNAUTHORIZED));
             }
 
-            return Ok(self.build_preflight_response(&origin));
+            return Ok(self.build_preflight_response(&origin, request_headers));
         }
 
         let mut resp = self.inner.call(req).await.into_response();
@@ -368,7 +399,7 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
 
         if vary_header {
             resp.headers_mut()
-                .insert(header::VARY, HeaderValue::from_static("Origin"));
+                .append(header::VARY, HeaderValue::from_static("Origin"));
         }
 
         Ok(resp)
@@ -378,7 +409,7 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{endpoint::make_sync, EndpointExt};
+    use crate::{endpoint::make_sync, http::Uri, EndpointExt};
 
     const ALLOW_ORIGIN: &str = "https://example.com";
     const ALLOW_HEADER: &str = "X-Token";
@@ -729,6 +760,89 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn preflight_across_route() {
+        use crate::{handler, post, Route};
+
+        #[handler(internal)]
+        fn create() {}
+
+        let ep = Route::new()
+            .at("/users", post(create))
+            .with(cors())
+            .map_to_response();
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(Uri::from_static("/users"))
+                    .header(header::ORIGIN, ALLOW_ORIGIN)
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            ALLOW_ORIGIN
+        );
+
+        // not a preflight request, passed to the route
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(Uri::from_static("/users"))
+                    .header(header::ORIGIN, ALLOW_ORIGIN)
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn preflight_all_headers_allowed() {
+        let ep = make_sync(|_| "hello").with(cors()).map_to_response();
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .header(header::ORIGIN, ALLOW_ORIGIN)
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                    .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "X-Token, X-ABC")
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn reflect_request_headers_with_credentials() {
+        let ep = make_sync(|_| "hello")
+            .with(Cors::new().allow_credentials(true))
+            .map_to_response();
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .header(header::ORIGIN, ALLOW_ORIGIN)
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                    .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "x-token, x-abc")
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap(),
+            "x-token, x-abc"
+        );
+    }
+
     #[tokio::test]
     async fn no_cors_requests() {
         let ep = make_sync(|_| "hello").with(Cors::new().allow_origin(ALLOW_ORIGIN));
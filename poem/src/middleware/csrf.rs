@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+
+use crate::{
+    http::header::HeaderName,
+    middleware::{CookieJarManager, CookieJarManagerEndpoint},
+    web::{
+        cookie::{Cookie, SameSite},
+        CsrfToken, CsrfVerifier,
+    },
+    Endpoint, Middleware, Request,
+};
+
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Clone)]
+struct CsrfConfig {
+    cookie_name: String,
+    header_name: HeaderName,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+/// Middleware for Cross-Site Request Forgery (CSRF) protection.
+///
+/// It uses the double-submit pattern: a random token is stored in a cookie,
+/// and the client must submit the same token again in a form field or the
+/// `X-CSRF-Token` header. Use the [`CsrfToken`] extractor to render the token
+/// into the page, and the [`CsrfVerifier`] extractor to verify the submitted
+/// token.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     http::StatusCode,
+///     middleware::Csrf,
+///     web::{CsrfToken, CsrfVerifier, Form, Html},
+///     EndpointExt, Error, Result, Route,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Params {
+///     csrf_token: String,
+///     name: String,
+/// }
+///
+/// #[handler]
+/// fn form(token: &CsrfToken) -> Html<String> {
+///     Html(format!(
+///         r#"<form method="post">
+///             <input type="hidden" name="csrf_token" value="{}" />
+///             <input name="name" />
+///         </form>"#,
+///         token.0
+///     ))
+/// }
+///
+/// #[handler]
+/// fn submit(verifier: &CsrfVerifier, Form(params): Form<Params>) -> Result<String> {
+///     if !verifier.is_valid(&params.csrf_token) {
+///         return Err(Error::new(StatusCode::FORBIDDEN));
+///     }
+///     Ok(format!("hello, {}", params.name))
+/// }
+///
+/// let app = Route::new()
+///     .at("/", get(form).post(submit))
+///     .with(Csrf::new());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "csrf")))]
+pub struct Csrf {
+    config: CsrfConfig,
+}
+
+impl Default for Csrf {
+    fn default() -> Self {
+        Self {
+            config: CsrfConfig {
+                cookie_name: "poem-csrf-token".to_string(),
+                header_name: HeaderName::from_static("x-csrf-token"),
+                secure: true,
+                http_only: true,
+                same_site: Some(SameSite::Strict),
+            },
+        }
+    }
+}
+
+impl Csrf {
+    /// Create `Csrf` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the name of the cookie that stores the token, default is
+    /// `poem-csrf-token`.
+    #[must_use]
+    pub fn cookie_name(mut self, value: impl Into<String>) -> Self {
+        self.config.cookie_name = value.into();
+        self
+    }
+
+    /// Sets the name of the header that [`CsrfVerifier::is_header_valid`]
+    /// checks, default is `X-CSRF-Token`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `value` is not a valid header name.
+    #[must_use]
+    pub fn header_name(mut self, value: impl AsRef<str>) -> Self {
+        self.config.header_name = value.as_ref().parse().expect("illegal header name");
+        self
+    }
+
+    /// Sets the `Secure` attribute of the cookie, default is `true`.
+    #[must_use]
+    pub fn secure(mut self, value: bool) -> Self {
+        self.config.secure = value;
+        self
+    }
+
+    /// Sets the `HttpOnly` attribute of the cookie, default is `true`.
+    ///
+    /// Set it to `false` if the client-side scripts need to read the token
+    /// from the cookie and put it into the header.
+    #[must_use]
+    pub fn http_only(mut self, value: bool) -> Self {
+        self.config.http_only = value;
+        self
+    }
+
+    /// Sets the `SameSite` attribute of the cookie, default is
+    /// `SameSite::Strict`.
+    #[must_use]
+    pub fn same_site(mut self, value: impl Into<Option<SameSite>>) -> Self {
+        self.config.same_site = value.into();
+        self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Csrf {
+    type Output = CookieJarManagerEndpoint<CsrfEndpoint<E>>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CookieJarManager::new().transform(CsrfEndpoint {
+            inner: ep,
+            config: Arc::new(self.config.clone()),
+        })
+    }
+}
+
+/// Endpoint for `Csrf` middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "csrf")))]
+pub struct CsrfEndpoint<E> {
+    inner: E,
+    config: Arc<CsrfConfig>,
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.len() == TOKEN_LENGTH && token.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn generate_token() -> String {
+    OsRng
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for CsrfEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let cookie_jar = req.cookie().clone();
+        let token = match cookie_jar
+            .get(&self.config.cookie_name)
+            .map(|cookie| cookie.value_str().to_string())
+            .filter(|token| is_valid_token(token))
+        {
+            Some(token) => token,
+            None => {
+                let token = generate_token();
+                let mut cookie = Cookie::new_with_str(self.config.cookie_name.clone(), &token);
+                cookie.set_path("/");
+                cookie.set_secure(self.config.secure);
+                cookie.set_http_only(self.config.http_only);
+                if let Some(same_site) = self.config.same_site {
+                    cookie.set_same_site(same_site);
+                }
+                cookie_jar.add(cookie);
+                token
+            }
+        };
+
+        let header_token = req
+            .headers()
+            .get(&self.config.header_name)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+
+        req.extensions_mut().insert(CsrfToken(token.clone()));
+        req.extensions_mut()
+            .insert(CsrfVerifier::new(token, header_token));
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler,
+        http::{header, Method, StatusCode},
+        EndpointExt, IntoResponse,
+    };
+
+    #[handler(internal)]
+    fn index(token: &CsrfToken, verifier: &CsrfVerifier, body: String) -> impl IntoResponse {
+        if body.is_empty() {
+            return token.0.clone().into_response();
+        }
+        if verifier.is_valid(&body) || verifier.is_header_valid() {
+            "ok".into_response()
+        } else {
+            StatusCode::FORBIDDEN.into_response()
+        }
+    }
+
+    fn get_cookie(resp: &crate::Response) -> Option<String> {
+        resp.headers()
+            .get(header::SET_COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Cookie::parse(value).ok())
+            .map(|cookie| cookie.value_str().to_string())
+    }
+
+    #[tokio::test]
+    async fn csrf() {
+        let ep = index.with(Csrf::new());
+
+        let resp = ep.call(Request::default()).await;
+        let cookie = get_cookie(&resp).unwrap();
+        assert!(is_valid_token(&cookie));
+        assert_eq!(resp.into_body().into_string().await.unwrap(), cookie);
+
+        // reuses the token in the cookie
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header(header::COOKIE, format!("poem-csrf-token={}", cookie))
+                    .finish(),
+            )
+            .await;
+        assert!(get_cookie(&resp).is_none());
+        assert_eq!(resp.into_body().into_string().await.unwrap(), cookie);
+
+        // form field
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .header(header::COOKIE, format!("poem-csrf-token={}", cookie))
+                    .body(cookie.clone()),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // header
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .header(header::COOKIE, format!("poem-csrf-token={}", cookie))
+                    .header("X-CSRF-Token", cookie.clone())
+                    .body("x"),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // without the cookie
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .header("X-CSRF-Token", cookie.clone())
+                    .body(cookie.clone()),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // mismatched token
+        let resp = ep
+            .call(
+                Request::builder()
+                    .method(Method::POST)
+                    .header(header::COOKIE, format!("poem-csrf-token={}", cookie))
+                    .body(generate_token()),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use crate::{
+    http::{header, StatusCode},
+    web::headers::HeaderMapExt,
+    Endpoint, Error, Middleware, Request, Result,
+};
+
+type CheckFn = Arc<dyn Fn(&Request) -> bool + Send + Sync + 'static>;
+
+/// Middleware for control the behavior of the `Expect: 100-continue` requests.
+///
+/// The `100 Continue` response is sent when the request body is read for the
+/// first time, so if the endpoint returns a response without reading the
+/// body, the client will not transmit it. This middleware can refuse the
+/// requests before the body is transmitted:
+///
+/// - [`ExpectContinue::reject`] refuses all these requests with
+///   `417 Expectation Failed`, the client will resend the request without the
+///   `Expect` header.
+/// - [`ExpectContinue::max_length`] refuses the requests whose
+///   `Content-Length` exceeds the limit with `413 Payload Too Large`.
+/// - [`ExpectContinue::check`] refuses the requests that do not pass the
+///   check with `417 Expectation Failed`, for example, the requests without
+///   credentials.
+///
+/// The requests with any other expectation are refused with
+/// `417 Expectation Failed`. Requests without the `Expect` header are passed
+/// to the inner endpoint unchanged.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::header, middleware::ExpectContinue, EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn upload(data: Vec<u8>) -> String {
+///     format!("{} bytes", data.len())
+/// }
+///
+/// let app = Route::new().at(
+///     "/upload",
+///     upload.with(
+///         ExpectContinue::new()
+///             .max_length(1024 * 1024)
+///             .check(|req| req.headers().contains_key(header::AUTHORIZATION)),
+///     ),
+/// );
+/// ```
+#[derive(Default)]
+pub struct ExpectContinue {
+    reject: bool,
+    max_length: Option<u64>,
+    check: Option<CheckFn>,
+}
+
+impl ExpectContinue {
+    /// Create `ExpectContinue` middleware.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuses all the requests that expect `100 Continue`.
+    #[must_use]
+    pub fn reject(self) -> Self {
+        Self {
+            reject: true,
+            ..self
+        }
+    }
+
+    /// Sets the maximum `Content-Length` of the requests that expect
+    /// `100 Continue`.
+    #[must_use]
+    pub fn max_length(self, max_length: u64) -> Self {
+        Self {
+            max_length: Some(max_length),
+            ..self
+        }
+    }
+
+    /// Sets a function to check whether to accept the request that expects
+    /// `100 Continue`, it is called before the request body is transmitted.
+    #[must_use]
+    pub fn check(self, f: impl Fn(&Request) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            check: Some(Arc::new(f)),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ExpectContinue {
+    type Output = ExpectContinueEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ExpectContinueEndpoint {
+            inner: ep,
+            reject: self.reject,
+            max_length: self.max_length,
+            check: self.check.clone(),
+        }
+    }
+}
+
+/// Endpoint for ExpectContinue middleware.
+pub struct ExpectContinueEndpoint<E> {
+    inner: E,
+    reject: bool,
+    max_length: Option<u64>,
+    check: Option<CheckFn>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for ExpectContinueEndpoint<E> {
+    type Output = Result<E::Output>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let expect = match req.headers().get(header::EXPECT) {
+            Some(expect) => expect,
+            None => return Ok(self.inner.call(req).await),
+        };
+
+        if !expect.as_bytes().eq_ignore_ascii_case(b"100-continue") || self.reject {
+            return Err(Error::new(StatusCode::EXPECTATION_FAILED));
+        }
+
+        if let Some(max_length) = self.max_length {
+            if let Some(content_length) = req.headers().typed_get::<headers::ContentLength>() {
+                if content_length.0 > max_length {
+                    return Err(Error::new(StatusCode::PAYLOAD_TOO_LARGE));
+                }
+            }
+        }
+
+        if let Some(check) = &self.check {
+            if !check(&req) {
+                return Err(Error::new(StatusCode::EXPECTATION_FAILED));
+            }
+        }
+
+        Ok(self.inner.call(req).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::{make_sync, EndpointExt},
+        IntoResponse,
+    };
+
+    async fn call(ep: &impl Endpoint<Output = Result<()>>, req: Request) -> StatusCode {
+        ep.call(req).await.into_response().status()
+    }
+
+    #[tokio::test]
+    async fn expect_continue() {
+        let ep = make_sync(|_| ()).with(
+            ExpectContinue::new()
+                .max_length(5)
+                .check(|req| req.headers().contains_key(header::AUTHORIZATION)),
+        );
+
+        assert_eq!(call(&ep, Request::default()).await, StatusCode::OK);
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::EXPECT, "100-continue")
+                    .header(header::AUTHORIZATION, "Bearer abc")
+                    .header(header::CONTENT_LENGTH, 5)
+                    .finish()
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::EXPECT, "100-continue")
+                    .header(header::AUTHORIZATION, "Bearer abc")
+                    .header(header::CONTENT_LENGTH, 6)
+                    .finish()
+            )
+            .await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::EXPECT, "100-continue")
+                    .header(header::CONTENT_LENGTH, 5)
+                    .finish()
+            )
+            .await,
+            StatusCode::EXPECTATION_FAILED
+        );
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::EXPECT, "something")
+                    .header(header::AUTHORIZATION, "Bearer abc")
+                    .finish()
+            )
+            .await,
+            StatusCode::EXPECTATION_FAILED
+        );
+    }
+
+    #[tokio::test]
+    async fn reject() {
+        let ep = make_sync(|_| ()).with(ExpectContinue::new().reject());
+
+        assert_eq!(call(&ep, Request::default()).await, StatusCode::OK);
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::EXPECT, "100-continue")
+                    .finish()
+            )
+            .await,
+            StatusCode::EXPECTATION_FAILED
+        );
+    }
+}
@@ -0,0 +1,528 @@
+use std::{net::IpAddr, str::FromStr};
+
+use crate::{
+    http::{
+        header::{self, HeaderName},
+        uri::{Authority, Scheme},
+        HeaderValue, Uri,
+    },
+    web::RealIp,
+    Endpoint, Middleware, Request,
+};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// An IP network, such as `10.0.0.0/8`.
+#[derive(Debug, Copy, Clone)]
+struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl FromStr for IpNet {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (
+                addr.parse::<IpAddr>().map_err(|_| ())?,
+                Some(prefix.parse::<u8>().map_err(|_| ())?),
+            ),
+            None => (s.parse::<IpAddr>().map_err(|_| ())?, None),
+        };
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max_prefix);
+        if prefix > max_prefix {
+            return Err(());
+        }
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl IpNet {
+    fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The headers used by the [`ForwardedHeaders`] middleware.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ForwardedHeadersKind {
+    /// The [`Forwarded`](https://datatracker.ietf.org/doc/html/rfc7239) header.
+    Forwarded,
+    /// The `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host`
+    /// headers.
+    XForwarded,
+}
+
+impl Default for ForwardedHeadersKind {
+    fn default() -> Self {
+        ForwardedHeadersKind::XForwarded
+    }
+}
+
+/// The values of the `Forwarded` or `X-Forwarded-*` headers.
+#[derive(Debug, Default)]
+struct Forwarding {
+    /// The addresses of the hops, the last one is appended by the nearest
+    /// proxy.
+    ips: Vec<Option<IpAddr>>,
+    /// The scheme set by the nearest proxy.
+    proto: Option<String>,
+    /// The host set by the nearest proxy.
+    host: Option<String>,
+}
+
+/// Middleware for derive the client IP, scheme and host from the `Forwarded`
+/// or `X-Forwarded-*` headers set by the trusted proxies.
+///
+/// The headers are only used if the remote peer is a trusted proxy, the
+/// client IP is the last address in the headers that is not a trusted proxy,
+/// it can be extracted with [`RealIp`]. The scheme and host of the request
+/// URI and the `Host` header are replaced by the values set by the nearest
+/// proxy, so the URLs generated from the request point to the public address.
+///
+/// Only the headers selected by [`ForwardedHeaders::kind`] are read, the
+/// `X-Forwarded-*` headers by default, so that a client cannot forge the
+/// other family of headers that the proxies pass through. If an address in
+/// the headers cannot be parsed, the headers are ignored and the remote
+/// address is used.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, middleware::ForwardedHeaders, web::RealIp, EndpointExt};
+///
+/// #[handler]
+/// fn index(RealIp(ip): RealIp) -> String {
+///     format!("{:?}", ip)
+/// }
+///
+/// let app = index.with(
+///     ForwardedHeaders::new()
+///         .trusted_proxy("127.0.0.1")
+///         .trusted_proxy("10.0.0.0/8"),
+/// );
+/// ```
+#[derive(Default)]
+pub struct ForwardedHeaders {
+    kind: ForwardedHeadersKind,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl ForwardedHeaders {
+    /// Creates a new `ForwardedHeaders` middleware.
+    #[must_use]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets which headers the trusted proxies use, default is
+    /// [`ForwardedHeadersKind::XForwarded`].
+    #[must_use]
+    pub fn kind(self, kind: ForwardedHeadersKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// Add a trusted proxy, it can be an IP address such as `10.0.0.1`, or a
+    /// network such as `10.0.0.0/8`.
+    ///
+    /// NOTE: If no trusted proxy is added, the headers are ignored.
+    #[must_use]
+    pub fn trusted_proxy(mut self, proxy: &str) -> Self {
+        let net = match proxy.parse::<IpNet>() {
+            Ok(net) => net,
+            Err(_) => panic!("illegal proxy address"),
+        };
+        self.trusted_proxies.push(net);
+        self
+    }
+
+    /// Add many trusted proxies.
+    #[must_use]
+    pub fn trusted_proxies<'a>(self, proxies: impl IntoIterator<Item = &'a str>) -> Self {
+        proxies
+            .into_iter()
+            .fold(self, |mw, proxy| mw.trusted_proxy(proxy))
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ForwardedHeaders {
+    type Output = ForwardedHeadersEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ForwardedHeadersEndpoint {
+            inner: ep,
+            kind: self.kind,
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+}
+
+/// Endpoint for ForwardedHeaders middleware.
+pub struct ForwardedHeadersEndpoint<E> {
+    inner: E,
+    kind: ForwardedHeadersKind,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl<E> ForwardedHeadersEndpoint<E> {
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    /// Returns the client address.
+    ///
+    /// Returns `None` if the address of a hop before it cannot be parsed, such
+    /// as `unknown` or an obfuscated identifier, so that the headers are
+    /// ignored and the remote address is used.
+    fn client_ip(&self, forwarding: &Forwarding) -> Option<IpAddr> {
+        let mut client = None;
+        for ip in forwarding.ips.iter().rev() {
+            let ip = (*ip)?;
+            client = Some(ip);
+            if !self.is_trusted(&ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for ForwardedHeadersEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let peer_ip = req.remote_addr().as_socket_addr().map(|addr| addr.ip());
+
+        if matches!(peer_ip, Some(ip) if self.is_trusted(&ip)) {
+            let forwarding = match self.kind {
+                ForwardedHeadersKind::Forwarded => parse_forwarded(&req),
+                ForwardedHeadersKind::XForwarded => parse_x_forwarded(&req),
+            };
+            if let Some(ip) = self.client_ip(&forwarding) {
+                apply_forwarding(&mut req, ip, forwarding);
+            }
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+fn apply_forwarding(req: &mut Request, ip: IpAddr, forwarding: Forwarding) {
+    req.extensions_mut().insert(RealIp(Some(ip)));
+
+    if let Some(host) = forwarding
+        .host
+        .as_deref()
+        .and_then(|host| HeaderValue::from_str(host).ok())
+    {
+        req.headers_mut().insert(header::HOST, host);
+    }
+
+    let scheme = forwarding
+        .proto
+        .as_deref()
+        .and_then(|proto| proto.parse::<Scheme>().ok());
+    if let Some(uri) = rewrite_uri(req, scheme) {
+        *req.uri_mut() = uri;
+    }
+}
+
+fn rewrite_uri(req: &Request, scheme: Option<Scheme>) -> Option<Uri> {
+    let scheme = scheme.or_else(|| req.uri().scheme().cloned())?;
+    let authority = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| host.parse::<Authority>().ok())
+        .or_else(|| req.uri().authority().cloned())?;
+    let mut parts = req.uri().clone().into_parts();
+    parts.scheme = Some(scheme);
+    parts.authority = Some(authority);
+    if parts.path_and_query.is_none() {
+        parts.path_and_query = Some("/".parse().unwrap());
+    }
+    Uri::from_parts(parts).ok()
+}
+
+fn parse_forwarded(req: &Request) -> Forwarding {
+    let mut forwarding = Forwarding::default();
+
+    for value in req.headers().get_all(header::FORWARDED) {
+        let value = match value.to_str() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        for element in value.split(',') {
+            // only the scheme and host of the element appended by the nearest
+            // proxy are used, the previous ones may be sent by the client
+            let mut ip = None;
+            forwarding.proto = None;
+            forwarding.host = None;
+            for pair in element.split(';') {
+                let (name, value) = match pair.split_once('=') {
+                    Some((name, value)) => (name.trim(), value.trim().trim_matches('"')),
+                    None => continue,
+                };
+                if name.eq_ignore_ascii_case("for") {
+                    ip = parse_node(value);
+                } else if name.eq_ignore_ascii_case("proto") {
+                    forwarding.proto = Some(value.to_string());
+                } else if name.eq_ignore_ascii_case("host") {
+                    forwarding.host = Some(value.to_string());
+                }
+            }
+            forwarding.ips.push(ip);
+        }
+    }
+
+    forwarding
+}
+
+fn parse_x_forwarded(req: &Request) -> Forwarding {
+    let values = |name: &HeaderName| {
+        req.headers()
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // only the scheme and host appended by the nearest proxy are used, the
+    // previous ones may be sent by the client
+    Forwarding {
+        ips: values(&X_FORWARDED_FOR)
+            .iter()
+            .map(|ip| parse_node(ip))
+            .collect(),
+        proto: values(&X_FORWARDED_PROTO).pop(),
+        host: values(&X_FORWARDED_HOST).pop(),
+    }
+}
+
+/// Parses the node in the `Forwarded` or `X-Forwarded-For` headers, such as
+/// `192.0.2.43`, `192.0.2.43:47011`, `[2001:db8:cafe::17]:47011`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse() {
+        return Some(ip);
+    }
+    if let Some(node) = node.strip_prefix('[') {
+        return node.split(']').next()?.parse().ok();
+    }
+    node.split(':').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler,
+        web::{RealIp, RemoteAddr},
+        Addr, EndpointExt,
+    };
+
+    #[handler(internal)]
+    fn index(req: &Request, RealIp(ip): RealIp) -> String {
+        format!(
+            "{} {} {}",
+            ip.map(|ip| ip.to_string()).unwrap_or_default(),
+            req.uri(),
+            req.headers()
+                .get(header::HOST)
+                .and_then(|host| host.to_str().ok())
+                .unwrap_or_default()
+        )
+    }
+
+    async fn call(peer: &str, headers: &[(&'static str, &'static str)]) -> String {
+        call_with(ForwardedHeadersKind::XForwarded, peer, headers).await
+    }
+
+    async fn call_with(
+        kind: ForwardedHeadersKind,
+        peer: &str,
+        headers: &[(&'static str, &'static str)],
+    ) -> String {
+        let ep = index.with(ForwardedHeaders::new().kind(kind).trusted_proxies([
+            "127.0.0.1",
+            "10.0.0.0/8",
+            "fd00::/8",
+        ]));
+        let mut req = Request::builder().uri(Uri::from_static("/a?b=1"));
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        let mut req = req.finish();
+        req.state_mut().remote_addr = RemoteAddr(Addr::SocketAddr(peer.parse().unwrap()));
+        ep.call(req).await.into_body().into_string().await.unwrap()
+    }
+
+    #[test]
+    fn ip_net() {
+        let net = "10.0.0.0/8".parse::<IpNet>().unwrap();
+        assert!(net.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!net.contains(&"11.1.2.3".parse().unwrap()));
+        assert!(!net.contains(&"::1".parse().unwrap()));
+
+        let net = "0.0.0.0/0".parse::<IpNet>().unwrap();
+        assert!(net.contains(&"11.1.2.3".parse().unwrap()));
+
+        let net = "fd00::/8".parse::<IpNet>().unwrap();
+        assert!(net.contains(&"fd12::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("abc".parse::<IpNet>().is_err());
+    }
+
+    #[tokio::test]
+    async fn untrusted_peer() {
+        assert_eq!(
+            call("1.1.1.1:1234", &[("x-forwarded-for", "2.2.2.2")]).await,
+            "1.1.1.1 /a?b=1 "
+        );
+    }
+
+    #[tokio::test]
+    async fn x_forwarded() {
+        assert_eq!(
+            call(
+                "127.0.0.1:1234",
+                &[
+                    ("x-forwarded-for", "9.9.9.9, 2.2.2.2, 10.0.0.1"),
+                    ("x-forwarded-proto", "https"),
+                    ("x-forwarded-host", "example.com"),
+                ]
+            )
+            .await,
+            "2.2.2.2 https://example.com/a?b=1 example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn forwarded() {
+        assert_eq!(
+            call_with(
+                ForwardedHeadersKind::Forwarded,
+                "127.0.0.1:1234",
+                &[
+                    (
+                        "forwarded",
+                        "for=\"[2001:db8:cafe::17]:4711\", for=10.0.0.1;proto=https;host=example.com"
+                    ),
+                    ("x-forwarded-for", "2.2.2.2"),
+                ]
+            )
+            .await,
+            "2001:db8:cafe::17 https://example.com/a?b=1 example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn spoofed_header_kind() {
+        assert_eq!(
+            call(
+                "127.0.0.1:1234",
+                &[
+                    ("forwarded", "for=6.6.6.6;host=evil.com"),
+                    ("x-forwarded-for", "2.2.2.2"),
+                ]
+            )
+            .await,
+            "2.2.2.2 /a?b=1 "
+        );
+        assert_eq!(
+            call_with(
+                ForwardedHeadersKind::Forwarded,
+                "127.0.0.1:1234",
+                &[
+                    ("forwarded", "for=2.2.2.2"),
+                    ("x-forwarded-for", "6.6.6.6"),
+                    ("x-forwarded-host", "evil.com"),
+                ]
+            )
+            .await,
+            "2.2.2.2 /a?b=1 "
+        );
+    }
+
+    #[tokio::test]
+    async fn spoofed_host_and_proto() {
+        assert_eq!(
+            call(
+                "127.0.0.1:1234",
+                &[
+                    ("x-forwarded-for", "6.6.6.6, 2.2.2.2"),
+                    ("x-forwarded-proto", "http, https"),
+                    ("x-forwarded-host", "evil.com, example.com"),
+                ]
+            )
+            .await,
+            "2.2.2.2 https://example.com/a?b=1 example.com"
+        );
+        assert_eq!(
+            call_with(
+                ForwardedHeadersKind::Forwarded,
+                "127.0.0.1:1234",
+                &[(
+                    "forwarded",
+                    "for=6.6.6.6;proto=http;host=evil.com, for=2.2.2.2"
+                )]
+            )
+            .await,
+            "2.2.2.2 /a?b=1 "
+        );
+    }
+
+    #[tokio::test]
+    async fn unparseable_hop() {
+        assert_eq!(
+            call(
+                "127.0.0.1:1234",
+                &[
+                    ("x-forwarded-for", "2.2.2.2, unknown"),
+                    ("x-forwarded-host", "example.com"),
+                ]
+            )
+            .await,
+            "127.0.0.1 /a?b=1 "
+        );
+        assert_eq!(
+            call_with(
+                ForwardedHeadersKind::Forwarded,
+                "127.0.0.1:1234",
+                &[("forwarded", "for=_hidden;proto=https;host=example.com")]
+            )
+            .await,
+            "127.0.0.1 /a?b=1 "
+        );
+    }
+
+    #[tokio::test]
+    async fn all_trusted() {
+        assert_eq!(
+            call(
+                "127.0.0.1:1234",
+                &[("x-forwarded-for", "10.0.0.2, 10.0.0.1")]
+            )
+            .await,
+            "10.0.0.2 /a?b=1 "
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use std::io::{Error as IoError, ErrorKind};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::StreamExt;
+use hyper::body::HttpBody;
+
+use crate::{
+    body::BodyStream,
+    http::{header, HeaderMap, HeaderValue, Version},
+    Body, Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+const GRPC: &str = "application/grpc";
+const GRPC_WEB: &str = "application/grpc-web";
+const GRPC_WEB_TEXT: &str = "application/grpc-web-text";
+
+/// The flag of the frame that contains the trailers.
+const TRAILERS_FLAG: u8 = 0x80;
+
+/// Middleware for translate [gRPC-Web](https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-WEB.md)
+/// requests into gRPC requests.
+///
+/// Browsers can't access the HTTP/2 trailers, so gRPC-Web sends the trailers
+/// at the end of the response body. This middleware converts the
+/// `application/grpc-web(-text)` requests into `application/grpc` requests,
+/// calls the inner endpoint, and appends the trailers returned by the inner
+/// endpoint to the response body. Requests of any other content type are
+/// passed to the inner endpoint unchanged.
+///
+/// It is usually used with a `tonic` service converted by
+/// [`TowerCompatExt::compat`](crate::endpoint::TowerCompatExt::compat), and
+/// [`Cors`](crate::middleware::Cors) if the service is accessed from another
+/// origin.
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::{endpoint::TowerCompatExt, middleware::GrpcWeb, EndpointExt, Route};
+///
+/// let greeter = GreeterServer::new(MyGreeter::default());
+/// let app = Route::new().nest_no_strip(
+///     "/helloworld.Greeter",
+///     greeter.compat().with(GrpcWeb::new()),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "grpc-web")))]
+#[derive(Default)]
+pub struct GrpcWeb;
+
+impl GrpcWeb {
+    /// Creates a new `GrpcWeb` middleware.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for GrpcWeb {
+    type Output = GrpcWebEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        GrpcWebEndpoint { ep }
+    }
+}
+
+/// Endpoint for GrpcWeb middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "grpc-web")))]
+pub struct GrpcWebEndpoint<E> {
+    ep: E,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for GrpcWebEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let (text, suffix) = match req.content_type() {
+            Some(content_type) => match parse_content_type(content_type) {
+                Some((text, suffix)) => (text, suffix.to_string()),
+                None => return self.ep.call(req).await.into_response(),
+            },
+            None => return self.ep.call(req).await.into_response(),
+        };
+
+        // convert to gRPC request
+        let headers = req.headers_mut();
+        headers.remove(header::CONTENT_LENGTH);
+        if let Ok(value) = HeaderValue::from_str(&format!("{}{}", GRPC, suffix)) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        headers.insert(header::TE, HeaderValue::from_static("trailers"));
+        req.set_version(Version::HTTP_2);
+        if text {
+            let body = req.take_body();
+            req.set_body(decode_text_body(body));
+        }
+
+        let mut resp = self.ep.call(req).await.into_response();
+
+        // convert to gRPC-Web response
+        let grpc_suffix = match resp
+            .content_type()
+            .and_then(|value| value.strip_prefix(GRPC))
+        {
+            Some(suffix) if !suffix.starts_with('-') => suffix.to_string(),
+            _ => return resp,
+        };
+        let headers = resp.headers_mut();
+        headers.remove(header::CONTENT_LENGTH);
+        let content_type = if text { GRPC_WEB_TEXT } else { GRPC_WEB };
+        if let Ok(value) = HeaderValue::from_str(&format!("{}{}", content_type, grpc_suffix)) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        let body = resp.take_body();
+        resp.set_body(encode_body(body, text));
+        resp
+    }
+}
+
+/// Returns whether the request is `grpc-web-text` and the suffix of the
+/// content type (e.g. `+proto`).
+fn parse_content_type(content_type: &str) -> Option<(bool, &str)> {
+    let (text, suffix) = match content_type.strip_prefix(GRPC_WEB_TEXT) {
+        Some(suffix) => (true, suffix),
+        None => (false, content_type.strip_prefix(GRPC_WEB)?),
+    };
+    if suffix.is_empty() || suffix.starts_with('+') || suffix.starts_with(';') {
+        Some((text, suffix))
+    } else {
+        None
+    }
+}
+
+fn decode_text_body(body: Body) -> Body {
+    let stream = futures_util::stream::unfold(
+        (BodyStream::new(body.0), Vec::new()),
+        |(mut stream, mut buf)| async move {
+            loop {
+                match stream.next().await {
+                    Some(Ok(data)) => {
+                        buf.extend(data.iter().filter(|c| !c.is_ascii_whitespace()));
+                        let len = buf.len() / 4 * 4;
+                        if len == 0 {
+                            continue;
+                        }
+                        let res = decode_base64(&buf[..len]);
+                        buf.drain(..len);
+                        return Some((res, (stream, buf)));
+                    }
+                    Some(Err(err)) => return Some((Err(err), (stream, buf))),
+                    None if buf.is_empty() => return None,
+                    None => {
+                        buf.clear();
+                        let err = IoError::new(ErrorKind::InvalidData, "incomplete base64 body");
+                        return Some((Err(err), (stream, buf)));
+                    }
+                }
+            }
+        },
+    );
+    Body::from(hyper::Body::wrap_stream(stream))
+}
+
+fn decode_base64(data: &[u8]) -> Result<Bytes, IoError> {
+    // each message may be encoded separately, so the padding can appear in the
+    // middle of the data.
+    let mut buf = Vec::with_capacity(data.len() / 4 * 3);
+    let mut start = 0;
+    for (idx, chunk) in data.chunks(4).enumerate() {
+        if chunk.ends_with(b"=") {
+            let end = (idx + 1) * 4;
+            base64::decode_config_buf(&data[start..end], base64::STANDARD, &mut buf)
+                .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+            start = end;
+        }
+    }
+    base64::decode_config_buf(&data[start..], base64::STANDARD, &mut buf)
+        .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+    Ok(buf.into())
+}
+
+fn encode_body(body: Body, text: bool) -> Body {
+    let stream = futures_util::stream::unfold(Some(body), move |body| async move {
+        let mut body = body?;
+        let (res, body) = match body.data().await {
+            Some(Ok(data)) => (Ok(data), Some(body)),
+            Some(Err(err)) => (Err(IoError::new(ErrorKind::Other, err)), None),
+            None => match body.trailers().await {
+                Ok(Some(trailers)) => (Ok(encode_trailers(&trailers)), None),
+                Ok(None) => return None,
+                Err(err) => (Err(IoError::new(ErrorKind::Other, err)), None),
+            },
+        };
+        let res = res.map(|data| {
+            if text {
+                Bytes::from(base64::encode(&data))
+            } else {
+                data
+            }
+        });
+        Some((res, body))
+    });
+    Body::from(hyper::Body::wrap_stream(stream))
+}
+
+fn encode_trailers(trailers: &HeaderMap) -> Bytes {
+    let mut data = Vec::new();
+    for (name, value) in trailers {
+        data.extend_from_slice(name.as_str().as_bytes());
+        data.push(b':');
+        data.extend_from_slice(value.as_bytes());
+        data.extend_from_slice(b"\r\n");
+    }
+
+    let mut frame = BytesMut::with_capacity(data.len() + 5);
+    frame.put_u8(TRAILERS_FLAG);
+    frame.put_u32(data.len() as u32);
+    frame.extend_from_slice(&data);
+    frame.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, http::StatusCode, EndpointExt};
+
+    #[handler(internal)]
+    async fn echo(req: &Request, body: Body) -> Response {
+        assert_eq!(req.content_type(), Some("application/grpc+proto"));
+        assert_eq!(
+            req.headers().get(header::TE),
+            Some(&HeaderValue::from_static("trailers"))
+        );
+
+        let data = body.into_bytes().await.unwrap();
+        let stream = futures_util::stream::iter(vec![Ok::<_, IoError>(data)]);
+        Response::builder()
+            .content_type("application/grpc+proto")
+            .body(Body::from_bytes_stream_with_trailers(stream, async move {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("grpc-status", HeaderValue::from_static("0"));
+                trailers
+            }))
+    }
+
+    fn trailers_frame() -> Vec<u8> {
+        let mut data = vec![0x80, 0, 0, 0, 15];
+        data.extend_from_slice(b"grpc-status:0\r\n");
+        data
+    }
+
+    #[tokio::test]
+    async fn binary() {
+        let ep = echo.with(GrpcWeb::new());
+        let mut resp = ep
+            .call(
+                Request::builder()
+                    .content_type("application/grpc-web+proto")
+                    .body(vec![0, 0, 0, 0, 3, 1, 2, 3]),
+            )
+            .await;
+        assert_eq!(resp.content_type(), Some("application/grpc-web+proto"));
+
+        let mut expected = vec![0, 0, 0, 0, 3, 1, 2, 3];
+        expected.extend(trailers_frame());
+        assert_eq!(resp.take_body().into_vec().await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn text() {
+        let ep = echo.with(GrpcWeb::new());
+        let mut resp = ep
+            .call(
+                Request::builder()
+                    .content_type("application/grpc-web-text+proto")
+                    .body(base64::encode([0, 0, 0, 0, 3, 1, 2, 3])),
+            )
+            .await;
+        assert_eq!(resp.content_type(), Some("application/grpc-web-text+proto"));
+
+        let data = resp.take_body().into_vec().await.unwrap();
+        let mut expected = vec![0, 0, 0, 0, 3, 1, 2, 3];
+        expected.extend(trailers_frame());
+        assert_eq!(decode_base64(&data).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn not_grpc_web() {
+        #[handler(internal)]
+        async fn index() -> &'static str {
+            "hello"
+        }
+
+        let ep = index.with(GrpcWeb::new());
+        let mut resp = ep
+            .call(Request::builder().content_type("text/plain").finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "hello");
+    }
+
+    #[test]
+    fn decode_padded_messages() {
+        let mut data = base64::encode(b"a").into_bytes();
+        data.extend(base64::encode(b"bcd").into_bytes());
+        assert_eq!(decode_base64(&data).unwrap(), Bytes::from_static(b"abcd"));
+    }
+}
@@ -1,37 +1,71 @@
 //! Commonly used middleware.
 
+mod access_log;
 mod add_data;
+mod cache;
+mod catch_panic;
 #[cfg(feature = "compression")]
 mod compression;
+mod concurrency_limit;
 #[cfg(feature = "cookie")]
 mod cookie_jar_manager;
 mod cors;
+#[cfg(feature = "csrf")]
+mod csrf;
+mod expect_continue;
+mod forwarded_headers;
+#[cfg(feature = "grpc-web")]
+mod grpc_web;
 mod normalize_path;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_metrics;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_tracing;
+mod priority_limit;
+mod rate_limit;
+mod request_id;
+mod request_key;
 mod set_header;
 mod size_limit;
+#[cfg(feature = "tera")]
+mod tera_templating;
+mod timeout;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 mod tracing_mw;
 
+pub use access_log::{AccessLog, AccessLogEndpoint, AccessLogFormat, AccessLogRecord};
 pub use add_data::{AddData, AddDataEndpoint};
+pub use cache::{Cache, CacheEndpoint};
+pub use catch_panic::{CatchPanic, CatchPanicEndpoint};
 #[cfg(feature = "compression")]
 pub use compression::{Compression, CompressionEndpoint};
+pub use concurrency_limit::{ConcurrencyLimit, ConcurrencyLimitEndpoint};
 #[cfg(feature = "cookie")]
 pub use cookie_jar_manager::{CookieJarManager, CookieJarManagerEndpoint};
 pub use cors::{Cors, CorsEndpoint};
+#[cfg(feature = "csrf")]
+pub use csrf::{Csrf, CsrfEndpoint};
+pub use expect_continue::{ExpectContinue, ExpectContinueEndpoint};
+pub use forwarded_headers::{ForwardedHeaders, ForwardedHeadersEndpoint, ForwardedHeadersKind};
+#[cfg(feature = "grpc-web")]
+pub use grpc_web::{GrpcWeb, GrpcWebEndpoint};
 pub use normalize_path::{NormalizePath, NormalizePathEndpoint, TrailingSlash};
 #[cfg(feature = "opentelemetry")]
 pub use opentelemetry_metrics::{OpenTelemetryMetrics, OpenTelemetryMetricsEndpoint};
 #[cfg(feature = "opentelemetry")]
 pub use opentelemetry_tracing::{OpenTelemetryTracing, OpenTelemetryTracingEndpoint};
+pub use priority_limit::{Priority, PriorityGroup, PriorityLimit, PriorityLimitEndpoint};
+pub use rate_limit::{RateLimit, RateLimitEndpoint};
+pub use request_id::{RequestId, RequestIdEndpoint};
+pub use request_key::{ClientIpKey, ExtensionKey, HeaderKey, RequestKey};
 pub use set_header::{SetHeader, SetHeaderEndpoint};
 pub use size_limit::{SizeLimit, SizeLimitEndpoint};
+#[cfg(feature = "tera")]
+pub use tera_templating::{TeraTemplating, TeraTemplatingEndpoint};
+pub use timeout::{Timeout, TimeoutEndpoint};
 #[cfg(feature = "tower-compat")]
-pub use tower_compat::TowerLayerCompatExt;
+pub use tower_compat::{TowerLayerCompat, TowerLayerCompatExt};
 pub use tracing_mw::{Tracing, TracingEndpoint};
 
 #[cfg(feature = "tracing")]
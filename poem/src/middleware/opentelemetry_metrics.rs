@@ -92,7 +92,7 @@ impl<E: Endpoint> Endpoint for OpenTelemetryMetricsEndpoint<E> {
         }
         self.request_count.add(1, &labels);
         self.duration
-            .record(elapsed.as_secs_f64() / 1000.0, &labels);
+            .record(elapsed.as_secs_f64() * 1000.0, &labels);
 
         resp
     }
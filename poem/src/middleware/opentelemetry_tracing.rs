@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use libopentelemetry::{
     global,
-    trace::{FutureExt, Span, SpanKind, TraceContextExt, Tracer},
+    trace::{FutureExt, Span, SpanKind, StatusCode, TraceContextExt, Tracer},
     Context,
 };
 use opentelemetry_http::HeaderExtractor;
@@ -86,6 +86,9 @@ where
             let span = cx.span();
             span.add_event("request.completed".to_string(), vec![]);
             span.set_attribute(trace::HTTP_STATUS_CODE.i64(resp.status().as_u16() as i64));
+            if resp.status().is_server_error() {
+                span.set_status(StatusCode::Error, resp.status().to_string());
+            }
             if let Some(content_length) = resp.headers().typed_get::<headers::ContentLength>() {
                 span.set_attribute(
                     trace::HTTP_RESPONSE_CONTENT_LENGTH.i64(content_length.0 as i64),
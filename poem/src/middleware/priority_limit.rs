@@ -0,0 +1,294 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+use crate::{http::StatusCode, Endpoint, Error, Middleware, Request, Result};
+
+/// The priority class of the requests.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Priority {
+    /// Batch or background requests, shed first.
+    Low,
+    /// Ordinary requests.
+    Normal,
+    /// Important requests, such as payments.
+    High,
+    /// Requests that must keep working, such as health checks.
+    Critical,
+}
+
+impl Priority {
+    fn index(self) -> usize {
+        match self {
+            Priority::Low => 0,
+            Priority::Normal => 1,
+            Priority::High => 2,
+            Priority::Critical => 3,
+        }
+    }
+}
+
+/// A group of routes that share the concurrency limit, the requests are
+/// admitted according to their [`Priority`].
+///
+/// Each priority class can only use a part of the capacity, by default the
+/// requests of [`Priority::Low`] are admitted while less than `50%` of the
+/// capacity is used, `75%` for [`Priority::Normal`], `90%` for
+/// [`Priority::High`] and `100%` for [`Priority::Critical`]. So when the
+/// server is saturated, the low-priority requests are shed first with
+/// `503 Service Unavailable`, and the capacity left is reserved for the
+/// high-priority requests.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::{Priority, PriorityGroup},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn health() {}
+///
+/// #[handler]
+/// fn pay() {}
+///
+/// #[handler]
+/// fn report() {}
+///
+/// let group = PriorityGroup::new(1000).threshold(Priority::Low, 0.3);
+/// let app = Route::new()
+///     .at("/health", health.with(group.priority(Priority::Critical)))
+///     .at("/pay", pay.with(group.priority(Priority::High)))
+///     .at("/report", report.with(group.priority(Priority::Low)));
+/// ```
+#[derive(Clone)]
+pub struct PriorityGroup {
+    max_concurrency: usize,
+    limits: [usize; 4],
+    limiter: Arc<Limiter>,
+}
+
+impl PriorityGroup {
+    /// Create a `PriorityGroup` with the maximum number of concurrent
+    /// requests.
+    pub fn new(max_concurrency: usize) -> Self {
+        let mut group = Self {
+            max_concurrency,
+            limits: [0; 4],
+            limiter: Default::default(),
+        };
+        for (priority, ratio) in [
+            (Priority::Low, 0.5),
+            (Priority::Normal, 0.75),
+            (Priority::High, 0.9),
+            (Priority::Critical, 1.0),
+        ] {
+            group = group.threshold(priority, ratio);
+        }
+        group
+    }
+
+    /// Sets the ratio of the capacity that can be used by the requests of
+    /// the specified priority, the value is clamped to `0.0..=1.0`.
+    ///
+    /// The middlewares created by [`PriorityGroup::priority`] before calling
+    /// this method are not affected.
+    #[must_use]
+    pub fn threshold(mut self, priority: Priority, ratio: f64) -> Self {
+        self.limits[priority.index()] =
+            (self.max_concurrency as f64 * ratio.clamp(0.0, 1.0)) as usize;
+        self
+    }
+
+    /// Returns the number of the requests being processed.
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.limiter.in_flight()
+    }
+
+    /// Create a middleware that admits the requests with the specified
+    /// priority.
+    pub fn priority(&self, priority: Priority) -> PriorityLimit {
+        PriorityLimit {
+            group: self.clone(),
+            priority,
+        }
+    }
+
+    fn try_acquire(&self, priority: Priority) -> Option<Permit> {
+        self.limiter.try_acquire(self.limits[priority.index()])
+    }
+}
+
+/// Counts the requests being processed, shared by [`PriorityGroup`] and
+/// [`ConcurrencyLimit`](crate::middleware::ConcurrencyLimit).
+#[derive(Default)]
+pub(super) struct Limiter {
+    in_flight: AtomicUsize,
+    released: Notify,
+}
+
+impl Limiter {
+    pub(super) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Admits the request if less than `limit` requests are being processed.
+    pub(super) fn try_acquire(self: &Arc<Self>, limit: usize) -> Option<Permit> {
+        self.in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                if n < limit {
+                    Some(n + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+            .map(|_| Permit(self.clone()))
+    }
+
+    /// Waits until the request is admitted.
+    pub(super) async fn acquire(self: &Arc<Self>, limit: usize) -> Permit {
+        loop {
+            let released = self.released.notified();
+            if let Some(permit) = self.try_acquire(limit) {
+                return permit;
+            }
+            released.await;
+        }
+    }
+}
+
+pub(super) struct Permit(Arc<Limiter>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.0.released.notify_one();
+    }
+}
+
+/// Middleware for admit the requests according to the priority, see
+/// [`PriorityGroup`].
+pub struct PriorityLimit {
+    group: PriorityGroup,
+    priority: Priority,
+}
+
+impl<E: Endpoint> Middleware<E> for PriorityLimit {
+    type Output = PriorityLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        PriorityLimitEndpoint {
+            inner: ep,
+            group: self.group.clone(),
+            priority: self.priority,
+        }
+    }
+}
+
+/// Endpoint for PriorityLimit middleware.
+pub struct PriorityLimitEndpoint<E> {
+    inner: E,
+    group: PriorityGroup,
+    priority: Priority,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for PriorityLimitEndpoint<E> {
+    type Output = Result<E::Output>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let _permit = match self.group.try_acquire(self.priority) {
+            Some(permit) => permit,
+            None => {
+                tracing::debug!(priority = ?self.priority, "request shed");
+                return Err(Error::new(StatusCode::SERVICE_UNAVAILABLE));
+            }
+        };
+        Ok(self.inner.call(req).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::{make_sync, EndpointExt},
+        IntoResponse,
+    };
+
+    #[test]
+    fn admission() {
+        let group = PriorityGroup::new(10);
+
+        let low = (0..5)
+            .map(|_| group.try_acquire(Priority::Low).unwrap())
+            .collect::<Vec<_>>();
+        assert!(group.try_acquire(Priority::Low).is_none());
+
+        let normal = (0..2)
+            .map(|_| group.try_acquire(Priority::Normal).unwrap())
+            .collect::<Vec<_>>();
+        assert!(group.try_acquire(Priority::Normal).is_none());
+
+        let high = (0..2)
+            .map(|_| group.try_acquire(Priority::High).unwrap())
+            .collect::<Vec<_>>();
+        assert!(group.try_acquire(Priority::High).is_none());
+
+        let critical = group.try_acquire(Priority::Critical).unwrap();
+        assert!(group.try_acquire(Priority::Critical).is_none());
+        assert_eq!(group.in_flight(), 10);
+
+        drop((low, normal, high, critical));
+        assert_eq!(group.in_flight(), 0);
+        assert!(group.try_acquire(Priority::Low).is_some());
+    }
+
+    #[test]
+    fn threshold() {
+        let group = PriorityGroup::new(10).threshold(Priority::Low, 0.0);
+        assert!(group.try_acquire(Priority::Low).is_none());
+        assert!(group.try_acquire(Priority::Normal).is_some());
+    }
+
+    #[tokio::test]
+    async fn shed() {
+        let group = PriorityGroup::new(2);
+        let ep_low = make_sync(|_| ()).with(group.priority(Priority::Low));
+        let ep_critical = make_sync(|_| ()).with(group.priority(Priority::Critical));
+
+        let permit = group.try_acquire(Priority::Low).unwrap();
+        assert_eq!(
+            ep_low
+                .call(Request::default())
+                .await
+                .into_response()
+                .status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            ep_critical
+                .call(Request::default())
+                .await
+                .into_response()
+                .status(),
+            StatusCode::OK
+        );
+        drop(permit);
+        assert_eq!(
+            ep_low
+                .call(Request::default())
+                .await
+                .into_response()
+                .status(),
+            StatusCode::OK
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    http::{header, StatusCode},
+    middleware::{ClientIpKey, HeaderKey, RequestKey},
+    storage::CounterStorage,
+    Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+/// Middleware for limiting the request rate.
+///
+/// The requests are grouped by a [`RequestKey`], which is the IP address of
+/// the client by default. The rate is limited with the generic cell rate
+/// algorithm (GCRA), which allows a burst of `limit` requests and then one
+/// request in every `period / limit`, and the requests exceeding the limit are
+/// rejected with `429 Too Many Requests` and the `Retry-After` header.
+///
+/// The state is kept in a [`CounterStorage`], which checks and updates it
+/// atomically, use the [`MemoryStorage`](crate::storage::MemoryStorage) for a
+/// single instance, or the `RedisStorage` to share the limits between
+/// multiple instances. If the storage fails, the requests are allowed by
+/// default, see [`RateLimit::fail_open`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     handler, middleware::RateLimit, storage::MemoryStorage, EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// // at most 100 requests per minute for each client
+/// let app = Route::new().at("/", index).with(RateLimit::new(
+///     MemoryStorage::new(),
+///     100,
+///     Duration::from_secs(60),
+/// ));
+/// ```
+pub struct RateLimit<S> {
+    storage: Arc<S>,
+    limit: u64,
+    period: Duration,
+    key: Arc<dyn RequestKey>,
+    fail_open: bool,
+}
+
+impl<S: CounterStorage> RateLimit<S> {
+    /// Create `RateLimit` middleware that allows at most `limit` requests in
+    /// every `period` for each key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `period` is less than 1 millisecond.
+    pub fn new(storage: S, limit: u64, period: Duration) -> Self {
+        assert!(
+            period.as_millis() > 0,
+            "the period must be at least 1 millisecond"
+        );
+        Self {
+            storage: Arc::new(storage),
+            limit,
+            period,
+            key: Arc::new(ClientIpKey),
+            fail_open: true,
+        }
+    }
+
+    /// Groups the requests by the value of the header, the requests without
+    /// the header are not limited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `name` is not a valid header name.
+    #[must_use]
+    pub fn key_by_header(self, name: impl AsRef<str>) -> Self {
+        self.request_key(HeaderKey::new(name))
+    }
+
+    /// Groups the requests by the key returned by `f`, the requests that `f`
+    /// returns `None` are not limited.
+    #[must_use]
+    pub fn key_by<F>(self, f: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        self.request_key(f)
+    }
+
+    /// Groups the requests by the [`RequestKey`], the requests that the key
+    /// is `None` are not limited.
+    #[must_use]
+    pub fn request_key(self, key: impl RequestKey) -> Self {
+        Self {
+            key: Arc::new(key),
+            ..self
+        }
+    }
+
+    /// Sets whether the requests are allowed if the storage fails, default
+    /// is `true`.
+    ///
+    /// If it is `false`, the requests are rejected with
+    /// `503 Service Unavailable` when the storage fails.
+    #[must_use]
+    pub fn fail_open(self, fail_open: bool) -> Self {
+        Self { fail_open, ..self }
+    }
+}
+
+impl<E: Endpoint, S: CounterStorage + 'static> Middleware<E> for RateLimit<S> {
+    type Output = RateLimitEndpoint<E, S>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RateLimitEndpoint {
+            inner: ep,
+            storage: self.storage.clone(),
+            limit: self.limit,
+            period: self.period,
+            key: self.key.clone(),
+            fail_open: self.fail_open,
+        }
+    }
+}
+
+/// Endpoint for RateLimit middleware.
+pub struct RateLimitEndpoint<E, S> {
+    inner: E,
+    storage: Arc<S>,
+    limit: u64,
+    period: Duration,
+    key: Arc<dyn RequestKey>,
+    fail_open: bool,
+}
+
+impl<E, S: CounterStorage> RateLimitEndpoint<E, S> {
+    /// Returns the seconds to wait if the request is rejected.
+    async fn check(&self, key: &str) -> crate::Result<Option<u64>> {
+        let wait = self
+            .storage
+            .throttle(&format!("poem-ratelimit:{}", key), self.limit, self.period)
+            .await?;
+        Ok(wait.map(|wait| (wait.as_secs_f64().ceil() as u64).max(1)))
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint, S: CounterStorage + 'static> Endpoint for RateLimitEndpoint<E, S> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        if let Some(key) = self.key.request_key(&req) {
+            match self.check(&key).await {
+                Ok(Some(retry_after)) => {
+                    return Response::builder()
+                        .status(StatusCode::TOO_MANY_REQUESTS)
+                        .header(header::RETRY_AFTER, retry_after)
+                        .finish();
+                }
+                Ok(None) => {}
+                Err(err) if self.fail_open => {
+                    tracing::warn!(error = ?err, "failed to check the rate limit");
+                }
+                Err(err) => {
+                    tracing::error!(error = ?err, "failed to check the rate limit");
+                    return StatusCode::SERVICE_UNAVAILABLE.into_response();
+                }
+            }
+        }
+
+        self.inner.call(req).await.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, storage::MemoryStorage, EndpointExt, Result};
+
+    #[tokio::test]
+    async fn rate_limit() {
+        let ep = make_sync(|_| "hello").with(
+            RateLimit::new(MemoryStorage::new(), 2, Duration::from_secs(3600))
+                .key_by_header("x-client"),
+        );
+        let request = |client: &str| Request::builder().header("x-client", client).finish();
+
+        assert_eq!(ep.call(request("a")).await.status(), StatusCode::OK);
+        assert_eq!(ep.call(request("a")).await.status(), StatusCode::OK);
+
+        let resp = ep.call(request("a")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = resp
+            .headers()
+            .get(header::RETRY_AFTER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=3600).contains(&retry_after));
+
+        assert_eq!(ep.call(request("b")).await.status(), StatusCode::OK);
+        assert_eq!(ep.call(Request::default()).await.status(), StatusCode::OK);
+    }
+
+    struct FailingStorage;
+
+    #[async_trait::async_trait]
+    impl CounterStorage for FailingStorage {
+        async fn increment(&self, _key: &str, _delta: i64, _expires: Duration) -> Result<i64> {
+            Err(crate::error::InternalServerError(std::io::Error::from(
+                std::io::ErrorKind::ConnectionRefused,
+            )))
+        }
+
+        async fn throttle(
+            &self,
+            _key: &str,
+            _limit: u64,
+            _period: Duration,
+        ) -> Result<Option<Duration>> {
+            Err(crate::error::InternalServerError(std::io::Error::from(
+                std::io::ErrorKind::ConnectionRefused,
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn storage_failure() {
+        let limit = || {
+            RateLimit::new(FailingStorage, 2, Duration::from_secs(3600)).key_by_header("x-client")
+        };
+        let request = || Request::builder().header("x-client", "a").finish();
+
+        let ep = make_sync(|_| "hello").with(limit());
+        assert_eq!(ep.call(request()).await.status(), StatusCode::OK);
+
+        let ep = make_sync(|_| "hello").with(limit().fail_open(false));
+        assert_eq!(
+            ep.call(request()).await.status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    http::{header::HeaderName, HeaderValue},
+    web::RequestIdValue,
+    Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+type Generator = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// Middleware for assigning an ID to each request.
+///
+/// The ID is read from the `X-Request-Id` header, or generated if the header
+/// is missing. It is stored in the request extensions, and can be extracted
+/// with [`RequestIdValue`], it is also recorded in the span of the
+/// [`Tracing`](crate::middleware::Tracing) middleware applied inside this
+/// middleware. The header is added to the request and the response, so the
+/// ID can be used to correlate the logs across services.
+///
+/// The generated IDs are unique, but they are not unpredictable, use
+/// [`RequestId::generator`] if the IDs must not be guessed.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::{RequestId, Tracing},
+///     web::RequestIdValue,
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index(id: &RequestIdValue) -> String {
+///     id.to_string()
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .with(Tracing)
+///     .with(RequestId::new());
+/// ```
+pub struct RequestId {
+    header_name: HeaderName,
+    use_incoming: bool,
+    generator: Generator,
+}
+
+impl Default for RequestId {
+    fn default() -> Self {
+        Self {
+            header_name: X_REQUEST_ID,
+            use_incoming: true,
+            generator: Arc::new(generate_id),
+        }
+    }
+}
+
+impl RequestId {
+    /// Create `RequestId` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the name of the header, default is `X-Request-Id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `name` is not a valid header name.
+    #[must_use]
+    pub fn header_name(self, name: impl AsRef<str>) -> Self {
+        Self {
+            header_name: name.as_ref().parse().expect("illegal header name"),
+            ..self
+        }
+    }
+
+    /// Sets whether to use the ID in the request header, default is `true`.
+    ///
+    /// Set it to `false` if the clients are not trusted to choose the IDs.
+    #[must_use]
+    pub fn use_incoming(self, enable: bool) -> Self {
+        Self {
+            use_incoming: enable,
+            ..self
+        }
+    }
+
+    /// Sets the function to generate the IDs.
+    #[must_use]
+    pub fn generator(self, f: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Self {
+            generator: Arc::new(f),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestId {
+    type Output = RequestIdEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestIdEndpoint {
+            inner: ep,
+            header_name: self.header_name.clone(),
+            use_incoming: self.use_incoming,
+            generator: self.generator.clone(),
+        }
+    }
+}
+
+/// Endpoint for RequestId middleware.
+pub struct RequestIdEndpoint<E> {
+    inner: E,
+    header_name: HeaderName,
+    use_incoming: bool,
+    generator: Generator,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for RequestIdEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let incoming = req
+            .headers()
+            .get(&self.header_name)
+            .filter(|_| self.use_incoming)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string);
+        let id = match incoming {
+            Some(id) => id,
+            None => {
+                let id = (self.generator)();
+                if let Ok(value) = HeaderValue::from_str(&id) {
+                    req.headers_mut().insert(self.header_name.clone(), value);
+                }
+                id
+            }
+        };
+        req.extensions_mut().insert(RequestIdValue(id.clone()));
+
+        let mut resp = self.inner.call(req).await.into_response();
+        if let Ok(value) = HeaderValue::from_str(&id) {
+            resp.headers_mut().insert(self.header_name.clone(), value);
+        }
+        resp
+    }
+}
+
+fn generate_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    // every `RandomState` has different random keys
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    let a = hasher.finish();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    let b = hasher.finish();
+    format!("{:016x}{:016x}", a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, EndpointExt};
+
+    #[tokio::test]
+    async fn request_id() {
+        let ep = make_sync(|req| {
+            let id = req.extensions().get::<RequestIdValue>().unwrap();
+            assert_eq!(
+                req.headers().get("x-request-id").unwrap().to_str().unwrap(),
+                id.0
+            );
+            id.to_string()
+        })
+        .with(RequestId::new());
+
+        let mut resp = ep
+            .call(Request::builder().header("X-Request-Id", "abc").finish())
+            .await;
+        assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc");
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "abc");
+
+        let mut resp = ep.call(Request::default()).await;
+        let id = resp
+            .headers()
+            .get("x-request-id")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(id.len(), 32);
+        assert_eq!(resp.take_body().into_string().await.unwrap(), id);
+        assert_ne!(generate_id(), generate_id());
+    }
+
+    #[tokio::test]
+    async fn custom() {
+        let ep = make_sync(|req| {
+            req.extensions()
+                .get::<RequestIdValue>()
+                .unwrap()
+                .to_string()
+        })
+        .with(
+            RequestId::new()
+                .header_name("x-trace")
+                .use_incoming(false)
+                .generator(|| "generated".to_string()),
+        );
+
+        let resp = ep
+            .call(Request::builder().header("x-trace", "abc").finish())
+            .await;
+        assert_eq!(resp.headers().get("x-trace").unwrap(), "generated");
+    }
+}
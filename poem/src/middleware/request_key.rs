@@ -0,0 +1,160 @@
+use std::{any::Any, fmt::Display, marker::PhantomData};
+
+use crate::{http::header::HeaderName, web::RealIp, Request};
+
+/// Derives the key that groups the requests, which is shared by the
+/// middlewares that keep the states per client, such as [`RateLimit`].
+///
+/// It is implemented for the closures `Fn(&Request) -> Option<String>`, and
+/// for the tuples of keys, whose parts are joined with `:`, so a deployment
+/// can implement its keying logic once and use it everywhere.
+///
+/// [`RateLimit`]: crate::middleware::RateLimit
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     middleware::{ClientIpKey, HeaderKey, RateLimit},
+///     storage::MemoryStorage,
+/// };
+///
+/// // limits the requests for each API key from each client
+/// let rate_limit = RateLimit::new(MemoryStorage::new(), 100, Duration::from_secs(60))
+///     .request_key((HeaderKey::new("x-api-key"), ClientIpKey));
+/// ```
+pub trait RequestKey: Send + Sync + 'static {
+    /// Returns the key of the request, or `None` if the request is not
+    /// grouped, such as when the header of the key is missing.
+    fn request_key(&self, req: &Request) -> Option<String>;
+}
+
+impl<F> RequestKey for F
+where
+    F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+{
+    fn request_key(&self, req: &Request) -> Option<String> {
+        self(req)
+    }
+}
+
+/// Keys the requests by the IP address of the client, which is the
+/// [`RealIp`] if it is available, otherwise the remote address.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ClientIpKey;
+
+impl RequestKey for ClientIpKey {
+    fn request_key(&self, req: &Request) -> Option<String> {
+        let ip = match req.extensions().get::<RealIp>() {
+            Some(RealIp(ip)) => *ip,
+            None => req.remote_addr().as_socket_addr().map(|addr| addr.ip()),
+        };
+        ip.map(|ip| ip.to_string())
+    }
+}
+
+/// Keys the requests by the value of a header, such as the API key.
+#[derive(Debug, Clone)]
+pub struct HeaderKey(HeaderName);
+
+impl HeaderKey {
+    /// Create `HeaderKey` with the name of the header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `name` is not a valid header name.
+    pub fn new(name: impl AsRef<str>) -> Self {
+        Self(name.as_ref().parse().expect("illegal header name"))
+    }
+}
+
+impl RequestKey for HeaderKey {
+    fn request_key(&self, req: &Request) -> Option<String> {
+        req.headers()
+            .get(&self.0)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+    }
+}
+
+/// Keys the requests by a value of type `T` in the extensions of the request,
+/// such as the principal inserted by an authentication middleware.
+pub struct ExtensionKey<T>(PhantomData<fn() -> T>);
+
+impl<T> ExtensionKey<T> {
+    /// Create `ExtensionKey`.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for ExtensionKey<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Display + Any + Send + Sync> RequestKey for ExtensionKey<T> {
+    fn request_key(&self, req: &Request) -> Option<String> {
+        req.extensions().get::<T>().map(ToString::to_string)
+    }
+}
+
+macro_rules! impl_request_key_for_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($name: RequestKey),*> RequestKey for ($($name,)*) {
+            fn request_key(&self, req: &Request) -> Option<String> {
+                let ($($name,)*) = self;
+                Some([$($name.request_key(req)?),*].join(":"))
+            }
+        }
+    };
+}
+
+impl_request_key_for_tuple!(A, B);
+impl_request_key_for_tuple!(A, B, C);
+impl_request_key_for_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        let mut req = Request::builder().header("x-api-key", "abc").finish();
+        req.extensions_mut()
+            .insert(RealIp(Some([1, 2, 3, 4].into())));
+        req.extensions_mut().insert(42u32);
+
+        assert_eq!(ClientIpKey.request_key(&req).as_deref(), Some("1.2.3.4"));
+        assert_eq!(
+            HeaderKey::new("x-api-key").request_key(&req).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            ExtensionKey::<u32>::new().request_key(&req).as_deref(),
+            Some("42")
+        );
+        assert_eq!(
+            (
+                HeaderKey::new("x-api-key"),
+                ClientIpKey,
+                ExtensionKey::<u32>::new()
+            )
+                .request_key(&req)
+                .as_deref(),
+            Some("abc:1.2.3.4:42")
+        );
+        assert_eq!(
+            (ClientIpKey, HeaderKey::new("x-other")).request_key(&req),
+            None
+        );
+        assert_eq!(
+            (|_: &Request| Some("custom".to_string())).request_key(&req),
+            Some("custom".to_string())
+        );
+    }
+}
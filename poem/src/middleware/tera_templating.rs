@@ -0,0 +1,256 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::SystemTime};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    http::StatusCode,
+    web::tera::{Tera, TeraTemplate},
+    Endpoint, IntoResponse, Middleware, Request, Response,
+};
+
+/// The modification time of the template files and the directories that
+/// contain them.
+type Files = BTreeMap<PathBuf, Option<SystemTime>>;
+
+fn files(tera: &Tera) -> Files {
+    let modified = |path: &PathBuf| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let mut files = Files::new();
+    for path in tera.templates.values().filter_map(|t| t.path.as_ref()) {
+        let path = PathBuf::from(path);
+        if let Some(dir) = path.parent() {
+            let dir = dir.to_path_buf();
+            let time = modified(&dir);
+            files.insert(dir, time);
+        }
+        let time = modified(&path);
+        files.insert(path, time);
+    }
+    files
+}
+
+/// Middleware for rendering the [`TeraTemplate`] responses.
+///
+/// In debug builds, the templates are reloaded from the disk when the template
+/// files or the directories that contain them are modified, see
+/// [`TeraTemplating::auto_reload`].
+///
+/// If a template cannot be rendered, the error is logged and the response is
+/// `500 Internal Server Error`.
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub struct TeraTemplating {
+    tera: Arc<RwLock<Tera>>,
+    files: Arc<Mutex<Files>>,
+    auto_reload: bool,
+}
+
+impl From<Tera> for TeraTemplating {
+    fn from(tera: Tera) -> Self {
+        Self {
+            files: Arc::new(Mutex::new(files(&tera))),
+            tera: Arc::new(RwLock::new(tera)),
+            auto_reload: cfg!(debug_assertions),
+        }
+    }
+}
+
+impl TeraTemplating {
+    /// Loads the templates that match the glob, such as `templates/**/*`.
+    pub fn from_glob(glob: &str) -> libtera::Result<Self> {
+        Ok(Tera::new(glob)?.into())
+    }
+
+    /// Sets whether to reload the templates when they are modified, the
+    /// default is `true` in debug builds.
+    ///
+    /// Only the templates loaded by [`TeraTemplating::from_glob`] or added
+    /// from files can be reloaded.
+    #[must_use]
+    pub fn auto_reload(self, auto_reload: bool) -> Self {
+        Self {
+            auto_reload,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TeraTemplating {
+    type Output = TeraTemplatingEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TeraTemplatingEndpoint {
+            inner: ep,
+            tera: self.tera.clone(),
+            files: self.files.clone(),
+            auto_reload: self.auto_reload,
+        }
+    }
+}
+
+/// Endpoint for `TeraTemplating` middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub struct TeraTemplatingEndpoint<E> {
+    inner: E,
+    tera: Arc<RwLock<Tera>>,
+    files: Arc<Mutex<Files>>,
+    auto_reload: bool,
+}
+
+impl<E> TeraTemplatingEndpoint<E> {
+    fn reload_if_modified(&self) {
+        let current = files(&self.tera.read());
+        let mut files = self.files.lock();
+        if *files == current {
+            return;
+        }
+
+        let mut tera = self.tera.write();
+        if let Err(err) = tera.full_reload() {
+            tracing::error!(error = ?err, "failed to reload the templates");
+        }
+        *files = self::files(&tera);
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for TeraTemplatingEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        let mut resp = self.inner.call(req).await.into_response();
+        let template = match resp.extensions_mut().remove::<TeraTemplate>() {
+            Some(template) => template,
+            None => return resp,
+        };
+
+        if self.auto_reload {
+            self.reload_if_modified();
+        }
+
+        match self.tera.read().render(&template.name, &template.context) {
+            Ok(body) => {
+                if resp.status() == StatusCode::INTERNAL_SERVER_ERROR {
+                    resp.set_status(StatusCode::OK);
+                }
+                resp.set_body(body);
+                resp
+            }
+            Err(err) => {
+                tracing::error!(
+                    template = %template.name,
+                    error = ?err,
+                    "failed to render the template"
+                );
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        get, handler,
+        http::Uri,
+        web::tera::{Context, Tera},
+        EndpointExt, Route,
+    };
+
+    #[tokio::test]
+    async fn render() {
+        #[handler(internal)]
+        fn index() -> (StatusCode, TeraTemplate) {
+            let mut context = Context::new();
+            context.insert("name", "poem");
+            (
+                StatusCode::CREATED,
+                TeraTemplate::render("hello.html", context),
+            )
+        }
+
+        #[handler(internal)]
+        fn missing() -> TeraTemplate {
+            TeraTemplate::render("missing.html", Context::new())
+        }
+
+        let mut tera = Tera::default();
+        tera.add_raw_template("hello.html", "<h1>Hello {{ name }}</h1>")
+            .unwrap();
+        let app = Route::new()
+            .at("/", get(index))
+            .at("/missing", get(missing))
+            .with(TeraTemplating::from(tera).auto_reload(false));
+
+        let mut resp = app.call(Request::default()).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.content_type(), Some("text/html"));
+        assert_eq!(
+            resp.take_body().into_string().await.unwrap(),
+            "<h1>Hello poem</h1>"
+        );
+
+        let mut resp = app
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/missing"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(resp.take_body().into_string().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn without_middleware() {
+        #[handler(internal)]
+        fn index() -> TeraTemplate {
+            TeraTemplate::render("hello.html", Context::new())
+        }
+
+        let resp = index.call(Request::default()).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn reload() {
+        #[handler(internal)]
+        fn index() -> TeraTemplate {
+            TeraTemplate::render("hello.html", Context::new())
+        }
+
+        let dir = std::env::temp_dir().join(format!("poem-tera-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hello.html");
+        std::fs::write(&path, "a").unwrap();
+
+        let templating = TeraTemplating::from_glob(&format!("{}/*", dir.display()))
+            .unwrap()
+            .auto_reload(true);
+        let files = templating.files.clone();
+        let ep = index.with(templating);
+        let render = || async {
+            ep.call(Request::default())
+                .await
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+        };
+        assert_eq!(render().await, "a");
+
+        // not reloaded if the files are not modified
+        std::fs::write(&path, "b").unwrap();
+        let current = super::files(&ep.tera.read());
+        *files.lock() = current;
+        assert_eq!(render().await, "a");
+
+        files.lock().clear();
+        assert_eq!(render().await, "b");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use crate::{http::StatusCode, Endpoint, Error, Middleware, Request, Result};
+
+/// Middleware for aborting the requests that take longer than a duration.
+///
+/// If the inner endpoint does not complete in time, it is dropped and the
+/// `503 Service Unavailable` status code is returned, use
+/// [`Timeout::status`] to return another status code such as
+/// `408 Request Timeout`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{handler, http::StatusCode, middleware::Timeout, EndpointExt, Route};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .with(Timeout::new(Duration::from_secs(30)).status(StatusCode::REQUEST_TIMEOUT));
+/// ```
+pub struct Timeout {
+    duration: Duration,
+    status: StatusCode,
+}
+
+impl Timeout {
+    /// Create `Timeout` middleware.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            status: StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Sets the status code returned when the request times out, default is
+    /// `503 Service Unavailable`.
+    #[must_use]
+    pub fn status(self, status: StatusCode) -> Self {
+        Self { status, ..self }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Timeout {
+    type Output = TimeoutEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TimeoutEndpoint {
+            inner: ep,
+            duration: self.duration,
+            status: self.status,
+        }
+    }
+}
+
+/// Endpoint for Timeout middleware.
+pub struct TimeoutEndpoint<E> {
+    inner: E,
+    duration: Duration,
+    status: StatusCode,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for TimeoutEndpoint<E> {
+    type Output = Result<E::Output>;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        match tokio::time::timeout(self.duration, self.inner.call(req)).await {
+            Ok(output) => Ok(output),
+            Err(_) => {
+                tracing::debug!(timeout = ?self.duration, "request timed out");
+                Err(Error::new(self.status))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make, http::Uri, EndpointExt, IntoResponse};
+
+    #[tokio::test]
+    async fn timeout() {
+        let ep = make(|req| async move {
+            if req.uri().query() == Some("pending") {
+                futures_util::future::pending::<()>().await;
+            }
+        })
+        .with(Timeout::new(Duration::from_millis(10)).status(StatusCode::REQUEST_TIMEOUT));
+
+        assert_eq!(
+            ep.call(Request::builder().uri(Uri::from_static("/")).finish())
+                .await
+                .into_response()
+                .status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            ep.call(Request::builder().uri(Uri::from_static("/?pending")).finish())
+                .await
+                .into_response()
+                .status(),
+            StatusCode::REQUEST_TIMEOUT
+        );
+    }
+}
@@ -1,13 +1,20 @@
 use std::{
     convert::Infallible,
+    error::Error as StdError,
+    future::Future,
     sync::Arc,
     task::{Context, Poll},
 };
 
+use bytes::Bytes;
 use futures_util::{future::BoxFuture, FutureExt};
+use hyper::body::HttpBody;
 use tower::{buffer::Buffer, Layer, Service, ServiceExt};
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Result};
+use crate::{
+    endpoint::{TowerCompatEndpoint, TowerServiceCompat},
+    Endpoint, IntoResponse, Middleware, Request, Result,
+};
 
 /// Extension trait for tower layer compat.
 #[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
@@ -87,3 +94,67 @@ where
         Ok(res)
     }
 }
+
+/// A poem middleware to tower layer adapter, so the middleware can be applied
+/// to the tower services.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     endpoint::TowerServiceCompat,
+///     handler,
+///     middleware::{SetHeader, TowerLayerCompat},
+/// };
+/// use tower::{Layer, ServiceExt};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let svc = TowerLayerCompat::new(SetHeader::new().appending("x-version", "1"))
+///     .layer(TowerServiceCompat::new(index));
+/// let resp = svc
+///     .oneshot(http::Request::new(hyper::Body::empty()))
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.headers().get("x-version").unwrap(), "1");
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tower-compat")))]
+pub struct TowerLayerCompat<M>(M);
+
+impl<M> TowerLayerCompat<M> {
+    /// Create a tower layer from the middleware.
+    pub fn new(middleware: M) -> Self {
+        Self(middleware)
+    }
+}
+
+impl<M, Svc, ResBody, Err, Fut> Layer<Svc> for TowerLayerCompat<M>
+where
+    M: Middleware<TowerCompatEndpoint<Svc>>,
+    M::Output: 'static,
+    ResBody: HttpBody + Send + 'static,
+    ResBody::Data: Into<Bytes> + Send + 'static,
+    ResBody::Error: StdError + Send + Sync + 'static,
+    Err: StdError + Send + Sync + 'static,
+    Svc: Service<
+            http::Request<hyper::Body>,
+            Response = hyper::Response<ResBody>,
+            Error = Err,
+            Future = Fut,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+    Fut: Future<Output = Result<hyper::Response<ResBody>, Err>> + Send + 'static,
+{
+    type Service = TowerServiceCompat<M::Output>;
+
+    fn layer(&self, svc: Svc) -> Self::Service {
+        TowerServiceCompat::new(self.0.transform(TowerCompatEndpoint(svc)))
+    }
+}
@@ -2,7 +2,7 @@ use std::time::SystemTime;
 
 use tracing::{Instrument, Level};
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response};
+use crate::{web::RequestIdValue, Endpoint, IntoResponse, Middleware, Request, Response};
 
 /// Middleware for [`tracing`](https://crates.io/crates/tracing).
 #[derive(Default)]
@@ -34,7 +34,11 @@ impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
             version = ?req.version(),
             method = %req.method(),
             path = %req.uri(),
+            request_id = tracing::field::Empty,
         );
+        if let Some(id) = req.extensions().get::<RequestIdValue>() {
+            span.record("request_id", id.0.as_str());
+        }
 
         async move {
             let now = SystemTime::now();
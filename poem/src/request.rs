@@ -108,7 +108,7 @@ impl From<(http::Request<hyper::Body>, LocalAddr, RemoteAddr)> for Request {
             version: parts.version,
             headers: parts.headers,
             extensions: parts.extensions,
-            body: Body(body),
+            body: body.into(),
             state: RequestState {
                 local_addr,
                 remote_addr,
@@ -473,12 +473,15 @@ impl RequestBuilder {
     pub fn body(self, body: impl Into<Body>) -> Request {
         Request {
             method: self.method,
-            uri: self.uri,
+            uri: self.uri.clone(),
             version: self.version,
             headers: self.headers,
             extensions: self.extensions,
             body: body.into(),
-            state: Default::default(),
+            state: RequestState {
+                original_uri: self.uri,
+                ..Default::default()
+            },
         }
     }
 
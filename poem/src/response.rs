@@ -83,9 +83,9 @@ impl<T: Into<Body>> From<(StatusCode, T)> for Response {
     }
 }
 
-impl From<Response> for hyper::Response<hyper::Body> {
+impl From<Response> for hyper::Response<Body> {
     fn from(resp: Response) -> Self {
-        let mut hyper_resp = hyper::Response::new(resp.body.into());
+        let mut hyper_resp = hyper::Response::new(resp.body);
         *hyper_resp.status_mut() = resp.status;
         *hyper_resp.version_mut() = resp.version;
         *hyper_resp.headers_mut() = resp.headers;
@@ -94,6 +94,12 @@ impl From<Response> for hyper::Response<hyper::Body> {
     }
 }
 
+impl From<Response> for hyper::Response<hyper::Body> {
+    fn from(resp: Response) -> Self {
+        hyper::Response::<Body>::from(resp).map(Into::into)
+    }
+}
+
 impl From<hyper::Response<hyper::Body>> for Response {
     fn from(hyper_resp: hyper::Response<hyper::Body>) -> Self {
         let (parts, body) = hyper_resp.into_parts();
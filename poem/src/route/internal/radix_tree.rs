@@ -214,10 +214,9 @@ impl<T> Node<T> {
 
     fn insert_param_child(&mut self, segments: Vec<Segment<'_>>, name: &[u8], data: T) -> bool {
         let child = match &mut self.param_child {
-            Some(child) => {
-                child.name = name.to_vec();
-                child
-            }
+            // renaming the parameter would break the paths already added
+            Some(child) if child.name != name => return false,
+            Some(child) => child,
             None => {
                 self.param_child = Some(Box::new(Node {
                     node_type: NodeType::Param,
@@ -260,11 +259,12 @@ impl<T> Node<T> {
         data: T,
     ) -> bool {
         let child = match &mut self.regex_child {
-            Some(child) => {
-                child.name = name.unwrap_or_default().to_vec();
-                child.re = Some(re);
-                child
+            Some(child)
+                if child.name != name.unwrap_or_default() || child.re.as_ref() != Some(&re) =>
+            {
+                return false
             }
+            Some(child) => child,
             None => {
                 self.regex_child = Some(Box::new(Node {
                     node_type: NodeType::Regex,
@@ -907,6 +907,12 @@ mod tests {
         assert!(!tree.add("/a/b/*p2", 2));
         assert!(tree.add("/k/h/<\\d>+", 1));
         assert!(!tree.add("/k/h/:name<\\d>+", 2));
+        assert!(tree.add("/m/:id", 1));
+        assert!(!tree.add("/m/:name/n", 2));
+        assert!(tree.add("/m/:id/n", 2));
+        assert!(tree.add("/r/:id<\\d+>", 1));
+        assert!(!tree.add("/r/:id<\\w+>/n", 2));
+        assert!(tree.add("/r/:id<\\d+>/n", 2));
     }
 
     fn create_url_params<I, K, V>(values: I) -> PathParams
@@ -3,6 +3,7 @@
 mod internal;
 mod router;
 mod router_domain;
+mod router_dynamic;
 mod router_method;
 
 pub(crate) use internal::radix_tree::PathParams;
@@ -11,6 +12,8 @@ pub use router::Route;
 #[allow(unreachable_pub)]
 pub use router_domain::RouteDomain;
 #[allow(unreachable_pub)]
+pub use router_dynamic::DynamicRoute;
+#[allow(unreachable_pub)]
 pub use router_method::{
     connect, delete, get, head, options, patch, post, put, trace, RouteMethod,
 };
@@ -1,19 +1,31 @@
-use std::{str::FromStr, sync::Arc};
+use std::{any::Any, future::Future, str::FromStr, sync::Arc};
 
+use futures_util::{future::BoxFuture, FutureExt};
 use http::StatusCode;
-use regex::Regex;
 
 use crate::{
     endpoint::BoxEndpoint,
+    error::{ErrorSource, RouteError},
     http::{uri::PathAndQuery, Uri},
     route::internal::radix_tree::RadixTree,
+    web::MatchedPath,
     Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response,
 };
 
+type AnyError = Box<dyn Any + Send + Sync>;
+
+/// A handler registered by [`Route::catch_error`].
+struct ErrorHandler {
+    is: fn(&(dyn Any + Send + Sync)) -> bool,
+    handle: Box<dyn Fn(AnyError) -> BoxFuture<'static, Response> + Send + Sync>,
+}
+
 /// Routing object
 #[derive(Default)]
 pub struct Route {
     tree: RadixTree<BoxEndpoint<'static, Response>>,
+    error_handlers: Vec<ErrorHandler>,
+    fallback: Option<BoxEndpoint<'static, Response>>,
 }
 
 impl Route {
@@ -27,6 +39,11 @@ impl Route {
     /// You can match the full path or wildcard path, and use the
     /// [`Path`](crate::web::Path) extractor to get the path parameters.
     ///
+    /// If the path is invalid, has been added, or conflicts with the existing
+    /// paths, e.g. `/users/:id` and `/users/:name/posts` use different names
+    /// for the same parameter, the endpoint is ignored and a warning is
+    /// logged, use [`Route::try_at`] to handle the error.
+    ///
     /// # Example
     ///
     /// ```
@@ -105,15 +122,51 @@ impl Route {
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        self.tree.add(
-            &normalize_path(path.as_ref()),
-            Box::new(ep.into_endpoint().map_to_response()),
-        );
+        if let Err(err) = self.add(path.as_ref(), ep) {
+            tracing::warn!(error = %err, "the endpoint is ignored");
+        }
         self
     }
 
+    /// Add an [Endpoint] to the specified path, see [`Route::at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid, has been added, or conflicts
+    /// with the existing paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{error::RouteError, handler, Route};
+    ///
+    /// #[handler]
+    /// fn index() {}
+    ///
+    /// let app = Route::new().try_at("/users/:id", index).unwrap();
+    /// assert_eq!(
+    ///     app.try_at("/users/:name/posts", index).err(),
+    ///     Some(RouteError::InvalidPath("/users/:name/posts".to_string()))
+    /// );
+    /// ```
+    pub fn try_at<E>(mut self, path: impl AsRef<str>, ep: E) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.add(path.as_ref(), ep)?;
+        Ok(self)
+    }
+
     /// Nest a `Endpoint` to the specified path and strip the prefix.
     ///
+    /// If the path conflicts with the existing paths, the endpoint is ignored
+    /// and a warning is logged, use [`Route::try_nest`] to handle the error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path contains wildcards.
+    ///
     /// # Example
     ///
     /// ```
@@ -143,16 +196,41 @@ impl Route {
     /// # });
     /// ```
     #[must_use]
-    pub fn nest<E>(self, path: impl AsRef<str>, ep: E) -> Self
+    pub fn nest<E>(mut self, path: impl AsRef<str>, ep: E) -> Self
     where
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        self.internal_nest(&normalize_path(path.as_ref()), ep, true)
+        self.nest_or_warn(path.as_ref(), ep, true);
+        self
+    }
+
+    /// Nest a `Endpoint` to the specified path and strip the prefix, see
+    /// [`Route::nest`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path contains wildcards or conflicts with the
+    /// existing paths.
+    pub fn try_nest<E>(mut self, path: impl AsRef<str>, ep: E) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.internal_nest(&normalize_path(path.as_ref()), ep, true)?;
+        Ok(self)
     }
 
     /// Nest a `Endpoint` to the specified path, but do not strip the prefix.
     ///
+    /// If the path conflicts with the existing paths, the endpoint is ignored
+    /// and a warning is logged, use [`Route::try_nest_no_strip`] to handle the
+    /// error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path contains wildcards.
+    ///
     /// # Example
     ///
     /// ```
@@ -182,15 +260,182 @@ impl Route {
     /// # });
     /// ```
     #[must_use]
-    pub fn nest_no_strip<E>(self, path: impl AsRef<str>, ep: E) -> Self
+    pub fn nest_no_strip<E>(mut self, path: impl AsRef<str>, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.nest_or_warn(path.as_ref(), ep, false);
+        self
+    }
+
+    /// Nest a `Endpoint` to the specified path, but do not strip the prefix,
+    /// see [`Route::nest_no_strip`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path contains wildcards or conflicts with the
+    /// existing paths.
+    pub fn try_nest_no_strip<E>(mut self, path: impl AsRef<str>, ep: E) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.internal_nest(&normalize_path(path.as_ref()), ep, false)?;
+        Ok(self)
+    }
+
+    /// Converts the errors of type `ErrType` returned by the endpoints of this
+    /// route, including the nested ones, into responses with `f`.
+    ///
+    /// Unlike [`EndpointExt::catch_error`](crate::EndpointExt::catch_error),
+    /// multiple handlers for different error types can be registered on the
+    /// same route, so that an application can map each of its error types to a
+    /// consistent response in one place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fmt::{self, Display, Formatter};
+    ///
+    /// use poem::{
+    ///     handler,
+    ///     http::{StatusCode, Uri},
+    ///     web::Json,
+    ///     Endpoint, IntoResponse, Request, Result, Route,
+    /// };
+    ///
+    /// #[derive(Debug)]
+    /// struct UserNotFound(u32);
+    ///
+    /// impl Display for UserNotFound {
+    ///     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "user {} not found", self.0)
+    ///     }
+    /// }
+    ///
+    /// #[handler]
+    /// fn user() -> Result<String> {
+    ///     Err(UserNotFound(1).into())
+    /// }
+    ///
+    /// let app = Route::new()
+    ///     .at("/user", user)
+    ///     .catch_error(|err: UserNotFound| async move {
+    ///         Json(serde_json::json!({ "error": err.to_string() }))
+    ///             .with_status(StatusCode::NOT_FOUND)
+    ///     });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = app
+    ///     .call(Request::builder().uri(Uri::from_static("/user")).finish())
+    ///     .await;
+    /// assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    /// assert_eq!(
+    ///     resp.into_body().into_string().await.unwrap(),
+    ///     r#"{"error":"user 1 not found"}"#
+    /// );
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn catch_error<ErrType, F, Fut, R>(mut self, f: F) -> Self
+    where
+        ErrType: Any + Send,
+        F: Fn(ErrType) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        R: IntoResponse + 'static,
+    {
+        self.error_handlers.push(ErrorHandler {
+            is: |err| err.is::<ErrType>(),
+            handle: Box::new(move |err| match err.downcast::<ErrType>() {
+                Ok(err) => f(*err).map(IntoResponse::into_response).boxed(),
+                Err(_) => unreachable!(),
+            }),
+        });
+        self
+    }
+
+    /// Sets the endpoint to call when no path of this route matches the
+    /// request, instead of returning `404 Not Found`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     handler,
+    ///     http::{StatusCode, Uri},
+    ///     Endpoint, Request, Route,
+    /// };
+    ///
+    /// #[handler]
+    /// fn api() -> &'static str {
+    ///     "api"
+    /// }
+    ///
+    /// #[handler]
+    /// fn index() -> &'static str {
+    ///     "index.html"
+    /// }
+    ///
+    /// // single-page application
+    /// let app = Route::new().at("/api", api).fallback(index);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = app
+    ///     .call(
+    ///         Request::builder()
+    ///             .uri(Uri::from_static("/users/1"))
+    ///             .finish(),
+    ///     )
+    ///     .await;
+    /// assert_eq!(resp.status(), StatusCode::OK);
+    /// assert_eq!(resp.into_body().into_string().await.unwrap(), "index.html");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn fallback<E>(mut self, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.fallback = Some(Box::new(ep.into_endpoint().map_to_response()));
+        self
+    }
+
+    /// Adds the endpoint, the route is unchanged if it returns an error.
+    fn add<E>(&mut self, path: &str, ep: E) -> Result<(), RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        let path = normalize_path(path);
+        let ep = WithMatchedPath {
+            inner: ep.into_endpoint(),
+            pattern: path.as_str().into(),
+        };
+        if self.tree.add(&path, Box::new(ep)) {
+            Ok(())
+        } else {
+            Err(RouteError::InvalidPath(path))
+        }
+    }
+
+    /// Nests the endpoint, the wildcards in the path are a programming error,
+    /// but the conflicts are only logged for compatibility.
+    fn nest_or_warn<E>(&mut self, path: &str, ep: E, strip: bool)
     where
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        self.internal_nest(&normalize_path(path.as_ref()), ep, false)
+        match self.internal_nest(&normalize_path(path), ep, strip) {
+            Ok(()) => {}
+            Err(err @ RouteError::WildcardInNestPath(_)) => panic!("{}", err),
+            Err(err) => tracing::warn!(error = %err, "the endpoint is ignored"),
+        }
     }
 
-    fn internal_nest<E>(mut self, path: &str, ep: E, strip: bool) -> Self
+    /// Nests the endpoint, the route is unchanged if it returns an error.
+    fn internal_nest<E>(&mut self, path: &str, ep: E, strip: bool) -> Result<(), RouteError>
     where
         E: IntoEndpoint,
         E::Endpoint: 'static,
@@ -205,6 +450,7 @@ impl Route {
             inner: T,
             root: bool,
             prefix_len: usize,
+            prefix: Option<Arc<str>>,
         }
 
         #[async_trait::async_trait]
@@ -231,37 +477,53 @@ impl Route {
                     Uri::from_parts(uri_parts).unwrap()
                 };
                 *req.uri_mut() = new_uri;
+                if let Some(prefix) = &self.prefix {
+                    let prefix = match req.extensions().get::<MatchedPath>() {
+                        Some(MatchedPath(parent)) => format!("{}{}", parent, prefix).into(),
+                        None => prefix.clone(),
+                    };
+                    req.extensions_mut().insert(MatchedPath(prefix));
+                }
                 self.inner.call(req).await.into_response()
             }
         }
 
-        assert!(
-            path.find('*').is_none(),
-            "wildcards are not allowed in the nest path."
-        );
+        if path.contains('*') {
+            return Err(RouteError::WildcardInNestPath(path));
+        }
 
         let prefix_len = match strip {
             false => 0,
             true => path.len() - 1,
         };
-        self.tree.add(
+        // the patterns of the routes nested without stripping the prefix
+        // already contain it
+        let prefix: Option<Arc<str>> = match strip {
+            false => None,
+            true => Some(path[..path.len() - 1].into()),
+        };
+        if !self.tree.add(
             &format!("{}*--poem-rest", path),
             Box::new(Nest {
                 inner: ep.clone(),
                 root: false,
                 prefix_len,
+                prefix: prefix.clone(),
             }),
-        );
+        ) {
+            return Err(RouteError::InvalidPath(path));
+        }
         self.tree.add(
             &path[..path.len() - 1],
             Box::new(Nest {
                 inner: ep,
                 root: true,
                 prefix_len,
+                prefix,
             }),
         );
 
-        self
+        Ok(())
     }
 }
 
@@ -270,24 +532,70 @@ impl Endpoint for Route {
     type Output = Response;
 
     async fn call(&self, mut req: Request) -> Self::Output {
-        match self.tree.matches(req.uri().path()) {
+        let mut resp = match self.tree.matches(req.uri().path()) {
             Some(matches) => {
                 req.state_mut().match_params.extend(matches.params);
                 matches.data.call(req).await
             }
-            None => StatusCode::NOT_FOUND.into(),
+            None => match &self.fallback {
+                Some(fallback) => fallback.call(req).await,
+                None => StatusCode::NOT_FOUND.into(),
+            },
+        };
+
+        let handler = resp
+            .extensions()
+            .get::<ErrorSource>()
+            .and_then(|ErrorSource(source)| {
+                self.error_handlers
+                    .iter()
+                    .find(|handler| (handler.is)(source.as_ref()))
+            });
+        match handler {
+            Some(handler) => {
+                let source = resp.extensions_mut().remove::<ErrorSource>().unwrap();
+                (handler.handle)(source.0).await
+            }
+            None => resp,
         }
     }
 }
 
-fn normalize_path(path: &str) -> String {
-    let re = Regex::new("//+").unwrap();
-    let mut path = re.replace_all(path, "/").to_string();
-    if !path.starts_with('/') {
-        path.insert(0, '/');
+/// Sets the [`MatchedPath`] of the request and the response.
+struct WithMatchedPath<E> {
+    inner: E,
+    pattern: Arc<str>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for WithMatchedPath<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Self::Output {
+        let matched_path = match req.extensions().get::<MatchedPath>() {
+            Some(MatchedPath(prefix)) => MatchedPath(format!("{}{}", prefix, self.pattern).into()),
+            None => MatchedPath(self.pattern.clone()),
+        };
+        req.extensions_mut().insert(matched_path.clone());
+        let mut resp = self.inner.call(req).await.into_response();
+        resp.extensions_mut().insert(matched_path);
+        resp
     }
+}
 
-    path
+pub(super) fn normalize_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len() + 1);
+    if !path.starts_with('/') {
+        normalized.push('/');
+    }
+    for ch in path.chars() {
+        // merges the repeated slashes
+        if ch == '/' && normalized.ends_with('/') {
+            continue;
+        }
+        normalized.push(ch);
+    }
+    normalized
 }
 
 #[cfg(test)]
@@ -302,6 +610,8 @@ mod tests {
         assert_eq!(normalize_path("/a/b/c"), "/a/b/c");
         assert_eq!(normalize_path("/a///b//c"), "/a/b/c");
         assert_eq!(normalize_path("a/b/c"), "/a/b/c");
+        assert_eq!(normalize_path(""), "/");
+        assert_eq!(normalize_path("//a/"), "/a/");
     }
 
     #[handler(internal)]
@@ -400,4 +710,196 @@ mod tests {
         assert_eq!(get(&r, "/a").await, "/");
         assert_eq!(get(&r, "/a?a=1").await, "/?a=1");
     }
+
+    #[test]
+    fn duplicate_path() {
+        assert_eq!(
+            Route::new().at("/a", h).try_at("/a", h).err(),
+            Some(RouteError::InvalidPath("/a".to_string()))
+        );
+        assert_eq!(
+            Route::new().nest("/a", h).try_nest("/a", h).err(),
+            Some(RouteError::InvalidPath("/a/".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn conflicting_param_names() {
+        #[handler(internal)]
+        fn a() -> &'static str {
+            "a"
+        }
+
+        #[handler(internal)]
+        fn b() -> &'static str {
+            "b"
+        }
+
+        assert!(Route::new()
+            .at("/a/:id", h)
+            .try_at("/a/:name/b", h)
+            .is_err());
+
+        // the conflicting endpoint is ignored
+        let r = Route::new()
+            .at("/a/:id", a)
+            .at("/a/:name/b", b)
+            .at("/a/:id/c", b);
+        assert_eq!(get(&r, "/a/1").await, "a");
+        assert_eq!(get(&r, "/a/1/c").await, "b");
+        let resp = r
+            .call(Request::builder().uri(Uri::from_static("/a/1/b")).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    #[should_panic(expected = "wildcards are not allowed in the nest path")]
+    fn wildcard_nest_path() {
+        let _ = Route::new().nest("/a/*", h);
+    }
+
+    #[tokio::test]
+    async fn matched_path() {
+        let ep = || make_sync(|req| req.extensions().get::<MatchedPath>().unwrap().to_string());
+        let r = Route::new()
+            .at("/a/:id", ep())
+            .nest(
+                "/b",
+                Route::new()
+                    .at("/c/:id", ep())
+                    .nest("/d", Route::new().at("/*path", ep())),
+            )
+            .nest_no_strip("/e", Route::new().at("/e/:id", ep()));
+
+        assert_eq!(get(&r, "/a/1").await, "/a/:id");
+        assert_eq!(get(&r, "/b/c/1").await, "/b/c/:id");
+        assert_eq!(get(&r, "/b/d/x/y").await, "/b/d/*path");
+        assert_eq!(get(&r, "/e/1").await, "/e/:id");
+
+        let resp = r
+            .call(Request::builder().uri(Uri::from_static("/b/c/1")).finish())
+            .await;
+        assert_eq!(
+            resp.extensions().get::<MatchedPath>(),
+            Some(&MatchedPath("/b/c/:id".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn catch_error() {
+        #[derive(Debug)]
+        struct NotFound;
+
+        impl std::fmt::Display for NotFound {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "not found")
+            }
+        }
+
+        #[derive(Debug)]
+        struct Forbidden;
+
+        impl std::fmt::Display for Forbidden {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "forbidden")
+            }
+        }
+
+        let r = Route::new()
+            .at(
+                "/a",
+                make_sync(|_| Err::<(), crate::Error>(NotFound.into())),
+            )
+            .nest(
+                "/inner",
+                Route::new().at(
+                    "/b",
+                    make_sync(|_| Err::<(), crate::Error>(Forbidden.into())),
+                ),
+            )
+            .at(
+                "/c",
+                make_sync(|_| Err::<(), crate::Error>(crate::Error::new(StatusCode::CONFLICT))),
+            )
+            .catch_error(|err: NotFound| async move {
+                format!("caught: {}", err).with_status(StatusCode::NOT_FOUND)
+            })
+            .catch_error(|err: Forbidden| async move {
+                format!("caught: {}", err).with_status(StatusCode::FORBIDDEN)
+            });
+
+        let call =
+            |path: &'static str| r.call(Request::builder().uri(Uri::from_static(path)).finish());
+
+        let mut resp = call("/a").await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.take_body().into_string().await.unwrap(),
+            "caught: not found"
+        );
+
+        let mut resp = call("/inner/b").await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            resp.take_body().into_string().await.unwrap(),
+            "caught: forbidden"
+        );
+
+        assert_eq!(call("/c").await.status(), StatusCode::CONFLICT);
+        assert_eq!(call("/d").await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn fallback() {
+        let r = Route::new()
+            .at("/a", h)
+            .at("/files/*path", h)
+            .at("/users/:id<\\d+>", h)
+            .nest("/inner", Route::new().at("/b", h))
+            .fallback(make_sync(|_| "fallback"));
+
+        assert_eq!(get(&r, "/a").await, "/a");
+        assert_eq!(get(&r, "/files/x/y.txt").await, "/files/x/y.txt");
+        assert_eq!(get(&r, "/users/1").await, "/users/1");
+        assert_eq!(get(&r, "/users/abc").await, "fallback");
+        assert_eq!(get(&r, "/b").await, "fallback");
+
+        let resp = r
+            .call(Request::builder().uri(Uri::from_static("/c")).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // the nested route returns `404 Not Found` by itself
+        let resp = r
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/inner/c"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn many_routes() {
+        let r = (0..1500).fold(Route::new(), |route, i| {
+            route.at(
+                format!("/api/v1/resources{}/:id/items", i),
+                make_sync(move |req| format!("{} {}", i, req.path_param("id").unwrap())),
+            )
+        });
+
+        assert_eq!(get(&r, "/api/v1/resources0/1/items").await, "0 1");
+        assert_eq!(get(&r, "/api/v1/resources749/2/items").await, "749 2");
+        assert_eq!(get(&r, "/api/v1/resources1499/3/items").await, "1499 3");
+        let resp = r
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/api/v1/resources1500/1/items"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
 }
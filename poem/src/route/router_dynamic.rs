@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    error::RouteError, route::router::normalize_path, Endpoint, EndpointExt, IntoEndpoint, Request,
+    Response, Route,
+};
+
+type SharedEndpoint = Arc<dyn Endpoint<Output = Response>>;
+
+#[derive(Clone)]
+struct Entry {
+    path: String,
+    nest: bool,
+    ep: SharedEndpoint,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// The registered endpoints, the lock also serializes the updates.
+    entries: Mutex<Vec<Entry>>,
+    route: RwLock<Arc<Route>>,
+}
+
+/// Routing object whose endpoints can be registered and removed while the
+/// server is running, e.g. for plugins.
+///
+/// Each update builds a new routing table and swaps it in atomically, the
+/// requests that have been routed continue to use the previous table. The
+/// `DynamicRoute` can be cloned cheaply, and all the clones share the same
+/// routing table.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::{StatusCode, Uri},
+///     DynamicRoute, Endpoint, Request,
+/// };
+///
+/// #[handler]
+/// fn hello() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = DynamicRoute::new();
+/// let plugins = app.clone();
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let req = || Request::builder().uri(Uri::from_static("/hello")).finish();
+/// assert_eq!(app.call(req()).await.status(), StatusCode::NOT_FOUND);
+///
+/// plugins.at("/hello", hello).unwrap();
+/// assert_eq!(app.call(req()).await.status(), StatusCode::OK);
+///
+/// plugins.remove("/hello");
+/// assert_eq!(app.call(req()).await.status(), StatusCode::NOT_FOUND);
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct DynamicRoute {
+    inner: Arc<Inner>,
+}
+
+impl DynamicRoute {
+    /// Create a new dynamic routing object without any endpoints.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an [`Endpoint`] to the specified path, replaces the endpoint that
+    /// has been added to the same path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid or conflicts with the existing
+    /// paths, see [`Route::at`], the routing table is unchanged in this case.
+    pub fn at<E>(&self, path: impl AsRef<str>, ep: E) -> Result<(), RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.add(path.as_ref(), false, ep)
+    }
+
+    /// Nest an [`Endpoint`] to the specified path and strip the prefix,
+    /// replaces the endpoint that has been nested to the same path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path conflicts with the existing paths, see
+    /// [`Route::nest`], the routing table is unchanged in this case.
+    pub fn nest<E>(&self, path: impl AsRef<str>, ep: E) -> Result<(), RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.add(path.as_ref(), true, ep)
+    }
+
+    /// Removes the endpoint that has been added or nested to the specified
+    /// path, returns `false` if there is no such endpoint.
+    pub fn remove(&self, path: impl AsRef<str>) -> bool {
+        let path = normalize_path(path.as_ref());
+        let mut entries = self.inner.entries.lock();
+        let len = entries.len();
+        entries.retain(|entry| entry.path != path);
+        if entries.len() == len {
+            return false;
+        }
+        // the remaining entries have been added without conflicts
+        let route = build_route(&entries).expect("the remaining routes are valid");
+        *self.inner.route.write() = Arc::new(route);
+        true
+    }
+
+    /// Returns the paths of the registered endpoints.
+    pub fn paths(&self) -> Vec<String> {
+        self.inner
+            .entries
+            .lock()
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    fn add<E>(&self, path: &str, nest: bool, ep: E) -> Result<(), RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        let entry = Entry {
+            path: normalize_path(path),
+            nest,
+            ep: Arc::new(ep.into_endpoint().map_to_response()),
+        };
+
+        let mut entries = self.inner.entries.lock();
+        let mut new_entries = entries
+            .iter()
+            .filter(|item| item.path != entry.path || item.nest != entry.nest)
+            .cloned()
+            .collect::<Vec<_>>();
+        new_entries.push(entry);
+
+        // builds the routing table before updating the entries, so an error
+        // leaves them unchanged
+        let route = build_route(&new_entries)?;
+        *entries = new_entries;
+        *self.inner.route.write() = Arc::new(route);
+        Ok(())
+    }
+}
+
+fn build_route(entries: &[Entry]) -> Result<Route, RouteError> {
+    entries.iter().try_fold(Route::new(), |route, entry| {
+        if entry.nest {
+            route.try_nest(&entry.path, entry.ep.clone())
+        } else {
+            route.try_at(&entry.path, entry.ep.clone())
+        }
+    })
+}
+
+#[async_trait::async_trait]
+impl Endpoint for DynamicRoute {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Self::Output {
+        // the lock must not be held across the `await`
+        let route = self.inner.route.read().clone();
+        route.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{StatusCode, Uri};
+
+    use super::*;
+    use crate::{handler, web::Path};
+
+    async fn get(app: &DynamicRoute, uri: &'static str) -> (StatusCode, String) {
+        let resp = app
+            .call(Request::builder().uri(Uri::from_static(uri)).finish())
+            .await;
+        (resp.status(), resp.into_body().into_string().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn dynamic_route() {
+        #[handler(internal)]
+        fn a() -> &'static str {
+            "a"
+        }
+
+        #[handler(internal)]
+        fn b(Path(id): Path<i32>) -> String {
+            format!("b{}", id)
+        }
+
+        #[handler(internal)]
+        fn c() -> &'static str {
+            "c"
+        }
+
+        let app = DynamicRoute::new();
+        assert_eq!(get(&app, "/a").await.0, StatusCode::NOT_FOUND);
+
+        app.at("/a", a).unwrap();
+        app.nest("/api", Route::new().at("/b/:id", b)).unwrap();
+        assert_eq!(get(&app, "/a").await, (StatusCode::OK, "a".to_string()));
+        assert_eq!(
+            get(&app, "/api/b/1").await,
+            (StatusCode::OK, "b1".to_string())
+        );
+        assert_eq!(app.paths(), vec!["/a", "/api"]);
+
+        // replaces the endpoint
+        app.at("a", c).unwrap();
+        assert_eq!(get(&app, "/a").await, (StatusCode::OK, "c".to_string()));
+        assert_eq!(app.paths(), vec!["/api", "/a"]);
+
+        assert!(app.remove("/a"));
+        assert!(!app.remove("/a"));
+        assert_eq!(get(&app, "/a").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&app, "/api/b/2").await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn invalid_path_keeps_routes() {
+        #[handler(internal)]
+        fn a() {}
+
+        let app = DynamicRoute::new();
+        app.at("/users/:id", a).unwrap();
+
+        assert_eq!(
+            app.at("/users/:name/posts", a),
+            Err(RouteError::InvalidPath("/users/:name/posts".to_string()))
+        );
+        assert!(app.nest("/api/*", a).is_err());
+        assert_eq!(app.paths(), vec!["/users/:id"]);
+        assert_eq!(get(&app, "/users/1").await.0, StatusCode::OK);
+    }
+}
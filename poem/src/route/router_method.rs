@@ -1,10 +1,14 @@
 use crate::{
     endpoint::BoxEndpoint,
-    http::{Method, StatusCode},
+    http::{header, Method, StatusCode},
     Endpoint, EndpointExt, IntoEndpoint, Request, Response,
 };
 
 /// Routing object for HTTP methods
+///
+/// The requests with a method that is not registered get the
+/// `405 Method Not Allowed` response, whose `Allow` header lists the
+/// registered methods.
 #[derive(Default)]
 pub struct RouteMethod {
     methods: Vec<(Method, BoxEndpoint<'static, Response>)>,
@@ -143,6 +147,20 @@ impl RouteMethod {
     {
         self.method(Method::TRACE, ep)
     }
+
+    /// Returns the value of the `Allow` header.
+    fn allowed_methods(&self) -> String {
+        let mut methods: Vec<&str> = Vec::new();
+        for (method, _) in &self.methods {
+            if !methods.contains(&method.as_str()) {
+                methods.push(method.as_str());
+            }
+        }
+        if methods.contains(&"GET") && !methods.contains(&"HEAD") {
+            methods.push("HEAD");
+        }
+        methods.join(", ")
+    }
 }
 
 #[async_trait::async_trait]
@@ -164,7 +182,10 @@ impl Endpoint for RouteMethod {
                     resp.set_body(());
                     return resp;
                 }
-                StatusCode::NOT_FOUND.into()
+                Response::builder()
+                    .status(StatusCode::METHOD_NOT_ALLOWED)
+                    .header(header::ALLOW, self.allowed_methods())
+                    .finish()
             }
         }
     }
@@ -326,4 +347,29 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
         assert!(resp.into_body().into_vec().await.unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn method_not_allowed() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let route = get(index).post(index).put(index);
+        let resp = route
+            .call(Request::builder().method(Method::DELETE).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            resp.headers().get(header::ALLOW).unwrap(),
+            "GET, POST, PUT, HEAD"
+        );
+
+        let route = post(index);
+        let resp = route
+            .call(Request::builder().method(Method::HEAD).finish())
+            .await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "POST");
+    }
 }
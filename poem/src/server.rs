@@ -10,32 +10,174 @@ use std::{
 use hyper::server::conn::Http;
 use tokio::{
     io::{AsyncRead, AsyncWrite, Result as IoResult},
-    sync::Notify,
+    sync::{watch, Notify},
     time::Duration,
 };
 
 use crate::{
+    http::Version,
     listener::{Acceptor, Listener},
-    web::{LocalAddr, RemoteAddr},
-    Endpoint, EndpointExt, IntoEndpoint, Response,
+    web::{ClientCert, ConnectionInfo, LocalAddr, RemoteAddr},
+    BackgroundTasks, Body, Endpoint, EndpointExt, IntoEndpoint, Request, Response,
 };
 
+/// A backend that drives the endpoint, such as an HTTP server.
+///
+/// [`Server`] is the default backend, it accepts the connections with a
+/// [`Listener`] and serves them with `hyper`. Implement this trait to embed
+/// the endpoints in other runtimes, e.g. serverless or edge hosts, which
+/// deliver the requests by their own event loops.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use poem::{
+///     handler, http::StatusCode, Endpoint, EndpointExt, Request, Response, ServeBackend,
+/// };
+/// use tokio::sync::{mpsc, oneshot};
+///
+/// /// A backend that receives the requests from a channel.
+/// struct ChannelBackend(mpsc::Receiver<(Request, oneshot::Sender<Response>)>);
+///
+/// #[poem::async_trait]
+/// impl ServeBackend for ChannelBackend {
+///     async fn serve(
+///         mut self,
+///         ep: Arc<dyn Endpoint<Output = Response>>,
+///     ) -> std::io::Result<()> {
+///         while let Some((req, reply)) = self.0.recv().await {
+///             let _ = reply.send(ep.call(req).await);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (tx, rx) = mpsc::channel(16);
+/// tokio::spawn(ChannelBackend(rx).serve(Arc::new(index.map_to_response())));
+///
+/// let (reply_tx, reply_rx) = oneshot::channel();
+/// tx.send((Request::default(), reply_tx)).await.unwrap();
+/// assert_eq!(reply_rx.await.unwrap().status(), StatusCode::OK);
+/// # });
+/// ```
+#[async_trait::async_trait]
+pub trait ServeBackend: Send {
+    /// Serves the endpoint until the backend stops.
+    async fn serve(self, ep: Arc<dyn Endpoint<Output = Response>>) -> IoResult<()>;
+}
+
 /// An HTTP Server.
+///
+/// It is the default [`ServeBackend`] based on `hyper`. It serves HTTP/1 and
+/// HTTP/2, the HTTP/2 connections are accepted with prior knowledge (h2c) or
+/// negotiated by ALPN with TLS.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use poem::{handler, listener::TcpListener, Server};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// Server::new(TcpListener::bind("127.0.0.1:3000"))
+///     .await?
+///     .http1_header_read_timeout(Duration::from_secs(10))
+///     .http2_max_concurrent_streams(100)
+///     .http2_keep_alive_interval(Duration::from_secs(30))
+///     .run(index)
+///     .await
+/// # }).unwrap();
+/// ```
 pub struct Server<T> {
     acceptor: T,
+    http: Http,
+    background_tasks: BackgroundTasks,
 }
 
 impl<T: Acceptor> Server<T> {
     /// Use the specified listener to create an HTTP server.
     pub async fn new<K: Listener<Acceptor = T>>(listener: K) -> IoResult<Server<T>> {
-        Ok(Self {
-            acceptor: listener.into_acceptor().await?,
-        })
+        Ok(Self::new_with_acceptor(listener.into_acceptor().await?))
     }
 
     /// Use the specified acceptor to create an HTTP server.
     pub fn new_with_acceptor(acceptor: T) -> Self {
-        Self { acceptor }
+        Self {
+            acceptor,
+            http: Http::new(),
+            background_tasks: BackgroundTasks::new(),
+        }
+    }
+
+    /// Sets whether HTTP/2 is served, the default is `true`.
+    ///
+    /// If it is disabled, only HTTP/1 is served, and the TLS listeners don't
+    /// offer HTTP/2 by ALPN, see [`Acceptor::set_http2`].
+    #[must_use]
+    pub fn http2(mut self, enabled: bool) -> Self {
+        self.http.http1_only(!enabled);
+        self.acceptor.set_http2(enabled);
+        self
+    }
+
+    /// Sets whether the HTTP/1 connections are kept alive, the default is
+    /// `true`.
+    #[must_use]
+    pub fn http1_keep_alive(mut self, enabled: bool) -> Self {
+        self.http.http1_keep_alive(enabled);
+        self
+    }
+
+    /// Sets the timeout for reading the headers of the HTTP/1 requests, the
+    /// connection is closed if the headers are not received in time.
+    ///
+    /// The default is no timeout.
+    #[must_use]
+    pub fn http1_header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.http.http1_header_read_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum number of the concurrent streams of each HTTP/2
+    /// connection, the default is no limit.
+    #[must_use]
+    pub fn http2_max_concurrent_streams(mut self, max: u32) -> Self {
+        self.http.http2_max_concurrent_streams(max);
+        self
+    }
+
+    /// Sets the interval of the HTTP/2 keep-alive pings, the default is no
+    /// pings.
+    #[must_use]
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http.http2_keep_alive_interval(interval);
+        self
+    }
+
+    /// Sets the timeout for receiving the acknowledgement of the HTTP/2
+    /// keep-alive pings, the connection is closed if the acknowledgement is not
+    /// received in time.
+    ///
+    /// It only applies if the interval is set with
+    /// [`Server::http2_keep_alive_interval`]. The default is 20 seconds.
+    #[must_use]
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.http.http2_keep_alive_timeout(timeout);
+        self
     }
 
     /// Returns the local address that this server is bound to.
@@ -43,6 +185,15 @@ impl<T: Acceptor> Server<T> {
         self.acceptor.local_addr()
     }
 
+    /// Returns the [`BackgroundTasks`] of this server, which are shut down
+    /// with the server.
+    ///
+    /// The handle is also added to the requests, so the handlers can extract
+    /// it with `Data<&BackgroundTasks>`.
+    pub fn background_tasks(&self) -> BackgroundTasks {
+        self.background_tasks.clone()
+    }
+
     /// Run this server.
     pub async fn run<E>(self, ep: E) -> IoResult<()>
     where
@@ -54,6 +205,38 @@ impl<T: Acceptor> Server<T> {
     }
 
     /// Run this server and a signal to initiate graceful shutdown.
+    ///
+    /// When the `signal` completes, the server stops accepting new
+    /// connections, closes the idle connections, and waits for the in-flight
+    /// requests and the [`BackgroundTasks`] to complete. If the `timeout` is
+    /// specified, the remaining connections and tasks are aborted after it
+    /// elapses.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use poem::{handler, listener::TcpListener, Server};
+    ///
+    /// #[handler]
+    /// fn index() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// Server::new(TcpListener::bind("127.0.0.1:3000"))
+    ///     .await?
+    ///     .run_with_graceful_shutdown(
+    ///         index,
+    ///         async move {
+    ///             let _ = tokio::signal::ctrl_c().await;
+    ///         },
+    ///         Some(Duration::from_secs(5)),
+    ///     )
+    ///     .await
+    /// # }).unwrap();
+    /// ```
     pub async fn run_with_graceful_shutdown<E>(
         self,
         ep: E,
@@ -64,12 +247,18 @@ impl<T: Acceptor> Server<T> {
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        let ep = ep.into_endpoint();
+        let Server {
+            mut acceptor,
+            http,
+            background_tasks,
+        } = self;
+        let ep = ep.into_endpoint().data(background_tasks.clone());
         let ep = Arc::new(ep.map_to_response());
-        let Server { mut acceptor } = self;
+        let mut deadline = None;
         let alive_connections = Arc::new(AtomicUsize::new(0));
         let notify = Arc::new(Notify::new());
         let timeout_notify = Arc::new(Notify::new());
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
 
         tokio::pin!(signal);
 
@@ -92,28 +281,35 @@ impl<T: Acceptor> Server<T> {
                             tokio::time::sleep(timeout).await;
                             timeout_notify.notify_waiters();
                         });
+                        deadline = Some(tokio::time::Instant::now() + timeout);
                     } else {
                         tracing::info!("initiate graceful shutdown");
                     }
+                    let _ = shutdown_sender.send(true);
+                    background_tasks.shutdown();
                     break;
                 },
                 res = acceptor.accept() => {
                     if let Ok((socket, local_addr, remote_addr)) = res {
+                        let client_cert = acceptor.client_cert(&socket);
+                        let connection_info = acceptor.connection_info(&socket);
                         let ep = ep.clone();
+                        let http = http.clone();
                         let alive_connections = alive_connections.clone();
                         let notify = notify.clone();
                         let timeout_notify = timeout_notify.clone();
+                        let shutdown_receiver = shutdown_receiver.clone();
 
+                        alive_connections.fetch_add(1, Ordering::SeqCst);
                         tokio::spawn(async move {
-                            alive_connections.fetch_add(1, Ordering::SeqCst);
-
+                            let conn = serve_connection(http, socket, local_addr, remote_addr, client_cert, connection_info, ep, shutdown_receiver);
                             if timeout.is_some() {
                                 tokio::select! {
-                                    _ = serve_connection(socket, local_addr, remote_addr, ep) => {}
+                                    _ = conn => {}
                                     _ = timeout_notify.notified() => {}
                                 }
                             } else {
-                                serve_connection(socket, local_addr, remote_addr, ep).await;
+                                conn.await;
                             }
 
                             if alive_connections.fetch_sub(1, Ordering::SeqCst) == 1 {
@@ -131,31 +327,276 @@ impl<T: Acceptor> Server<T> {
             notify.notified().await;
         }
 
+        if !background_tasks.is_empty() {
+            tracing::info!("wait for all background tasks to complete.");
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, background_tasks.wait())
+                        .await
+                        .is_err()
+                    {
+                        background_tasks.abort_all();
+                    }
+                }
+                None => background_tasks.wait().await,
+            }
+        }
+
         tracing::info!("server stopped");
         Ok(())
     }
 }
 
+#[async_trait::async_trait]
+impl<T: Acceptor> ServeBackend for Server<T> {
+    async fn serve(self, ep: Arc<dyn Endpoint<Output = Response>>) -> IoResult<()> {
+        self.run(ep).await
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn serve_connection(
+    http: Http,
     socket: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
     local_addr: LocalAddr,
     remote_addr: RemoteAddr,
+    client_cert: Option<ClientCert>,
+    connection_info: ConnectionInfo,
     ep: Arc<dyn Endpoint<Output = Response>>,
+    mut shutdown: watch::Receiver<bool>,
 ) {
-    let service = hyper::service::service_fn({
-        move |req: hyper::Request<hyper::Body>| {
-            let ep = ep.clone();
-            let local_addr = local_addr.clone();
-            let remote_addr = remote_addr.clone();
-            async move {
-                let resp = ep.call((req, local_addr, remote_addr).into()).await.into();
-                Ok::<_, Infallible>(resp)
+    let service = hyper::service::service_fn(move |req: hyper::Request<hyper::Body>| {
+        let ep = ep.clone();
+        let local_addr = local_addr.clone();
+        let remote_addr = remote_addr.clone();
+        let client_cert = client_cert.clone();
+        let connection_info = connection_info.clone();
+        async move {
+            let mut req: Request = (req, local_addr, remote_addr).into();
+            if let Some(client_cert) = client_cert {
+                req.extensions_mut().insert(client_cert);
+            }
+            req.extensions_mut().insert(connection_info);
+            let version = req.version();
+            let resp: hyper::Response<Body> = ep.call(req).await.into();
+            if version < Version::HTTP_2 && resp.body().has_trailers() {
+                tracing::warn!(
+                    ?version,
+                    "the trailers of the response are discarded, they are only sent over HTTP/2"
+                );
             }
+            Ok::<_, Infallible>(resp)
         }
     });
 
-    let conn = Http::new()
-        .serve_connection(socket, service)
-        .with_upgrades();
+    let conn = http.serve_connection(socket, service).with_upgrades();
+    tokio::pin!(conn);
+
+    tokio::select! {
+        _ = &mut conn => return,
+        _ = shutdown.changed() => {}
+    }
+
+    // finish the in-flight requests, and close the connection
+    conn.as_mut().graceful_shutdown();
     let _ = conn.await;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error as IoError;
+
+    use hyper::body::HttpBody;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::*;
+    use crate::{
+        handler,
+        listener::{TcpAcceptor, TcpListener},
+        web::Data,
+    };
+
+    #[tokio::test]
+    async fn graceful_shutdown() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+        let local_addr = server.local_addr().remove(0);
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(server.run_with_graceful_shutdown(
+            index,
+            async move {
+                let _ = rx.await;
+            },
+            None,
+        ));
+
+        // keep an idle keep-alive connection
+        let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200 OK\r\n"));
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        // the idle connection is closed
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn trailers() {
+        #[handler(internal)]
+        fn index() -> Body {
+            let stream = futures_util::stream::iter(vec![Ok::<_, IoError>(
+                bytes::Bytes::from_static(b"hello"),
+            )]);
+            Body::from_bytes_stream_with_trailers(stream, async move {
+                let mut trailers = crate::http::HeaderMap::new();
+                trailers.insert("x-checksum", "abcdef".parse().unwrap());
+                trailers
+            })
+        }
+
+        let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+        let local_addr = server.local_addr().remove(0);
+        tokio::spawn(server.run(index));
+
+        let client = hyper::Client::builder()
+            .http2_only(true)
+            .build_http::<hyper::Body>();
+        let uri = format!("http://{}/", local_addr.as_socket_addr().unwrap());
+        let mut resp = client.get(uri.parse().unwrap()).await.unwrap();
+        assert_eq!(resp.version(), Version::HTTP_2);
+        let body = resp.body_mut();
+        assert_eq!(body.data().await.unwrap().unwrap(), "hello");
+        assert!(body.data().await.is_none());
+        let trailers = body.trailers().await.unwrap().unwrap();
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abcdef");
+    }
+
+    #[tokio::test]
+    async fn background_tasks() {
+        #[handler(internal)]
+        fn index(tasks: Data<&BackgroundTasks>) {
+            // never stops, aborted after the timeout
+            tasks.spawn(|_| futures_util::future::pending());
+        }
+
+        let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+        let local_addr = server.local_addr().remove(0);
+        let (stopped_tx, stopped_rx) = tokio::sync::oneshot::channel();
+        server.background_tasks().spawn(|mut shutdown| async move {
+            shutdown.wait().await;
+            let _ = stopped_tx.send(());
+        });
+        let tasks = server.background_tasks();
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(server.run_with_graceful_shutdown(
+            index,
+            async move {
+                let _ = rx.await;
+            },
+            Some(Duration::from_millis(100)),
+        ));
+
+        let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200 OK\r\n"));
+        assert_eq!(tasks.len(), 2);
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        stopped_rx.await.unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn http2() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        async fn send_preface(server: Server<TcpAcceptor>) -> Vec<u8> {
+            let local_addr = server.local_addr().remove(0);
+            tokio::spawn(server.run(index));
+
+            let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+                .await
+                .unwrap();
+            stream
+                .write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")
+                .await
+                .unwrap();
+            let mut buf = vec![0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            buf.truncate(n);
+            buf
+        }
+
+        // the server responds with the `SETTINGS` frame
+        let server = Server::new(TcpListener::bind("127.0.0.1:0")).await.unwrap();
+        assert_eq!(send_preface(server).await.get(3), Some(&0x4));
+
+        let server = Server::new(TcpListener::bind("127.0.0.1:0"))
+            .await
+            .unwrap()
+            .http2(false);
+        assert!(!send_preface(server).await.starts_with(&[0, 0]));
+    }
+
+    #[tokio::test]
+    async fn http1_header_read_timeout() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let server = Server::new(TcpListener::bind("127.0.0.1:0"))
+            .await
+            .unwrap()
+            .http1_header_read_timeout(Duration::from_millis(100));
+        let local_addr = server.local_addr().remove(0);
+        tokio::spawn(server.run(index));
+
+        let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+            .await
+            .unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+        let mut buf = [0; 1024];
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        assert!(!buf[..n].starts_with(b"HTTP/1.1 200 OK\r\n"));
+    }
+}
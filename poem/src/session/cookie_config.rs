@@ -129,6 +129,16 @@ impl CookieConfig {
 
     /// Set the cookie value to `CookieJar`.
     pub fn set_cookie_value(&self, cookie_jar: &CookieJar, value: &str) {
+        self.set_cookie_value_with_ttl(cookie_jar, value, self.max_age);
+    }
+
+    /// Set the cookie value to `CookieJar` with the specified `MaxAge`.
+    pub(crate) fn set_cookie_value_with_ttl(
+        &self,
+        cookie_jar: &CookieJar,
+        value: &str,
+        ttl: Option<Duration>,
+    ) {
         let mut cookie = Cookie::new_with_str(self.name.clone(), value);
 
         cookie.set_path(self.path.clone());
@@ -140,8 +150,8 @@ impl CookieConfig {
         cookie.set_secure(self.secure);
         cookie.set_http_only(self.http_only);
 
-        if let Some(max_age) = &self.max_age {
-            cookie.set_max_age(*max_age);
+        if let Some(max_age) = ttl {
+            cookie.set_max_age(max_age);
         }
 
         if let Some(same_site) = &self.same_site {
@@ -58,9 +58,10 @@ impl<E: Endpoint> Endpoint for CookieSessionEndpoint<E> {
 
         match session.status() {
             SessionStatus::Changed | SessionStatus::Renewed => {
-                self.config.set_cookie_value(
+                self.config.set_cookie_value_with_ttl(
                     &cookie_jar,
-                    &serde_json::to_string(&session.entries()).unwrap_or_default(),
+                    &serde_json::to_string(&session.stored_entries()).unwrap_or_default(),
+                    session.ttl().or_else(|| self.config.ttl()),
                 );
             }
             SessionStatus::Purged => {
@@ -111,4 +112,61 @@ mod tests {
         client.call(&app, 5).await;
         client.assert_cookies(vec![]);
     }
+
+    #[tokio::test]
+    async fn session_ttl() {
+        use std::time::Duration;
+
+        use crate::{
+            handler,
+            http::{header, Uri},
+        };
+
+        #[handler(internal)]
+        fn remember(session: &Session) {
+            session.set_ttl(Duration::from_secs(60));
+        }
+
+        #[handler(internal)]
+        fn set(session: &Session) {
+            assert_eq!(session.ttl(), Some(Duration::from_secs(60)));
+            session.set("a", 1);
+        }
+
+        let app = Route::new()
+            .at("/remember", remember)
+            .at("/set", set)
+            .with(CookieSession::new(CookieConfig::default()));
+
+        let resp = app
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/remember"))
+                    .finish(),
+            )
+            .await;
+        let cookie = resp
+            .headers()
+            .get(header::SET_COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap()
+            .to_string();
+        assert!(cookie.contains("Max-Age=60"));
+
+        let value = cookie.split(';').next().unwrap().to_string();
+        let resp = app
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/set"))
+                    .header(header::COOKIE, value)
+                    .finish(),
+            )
+            .await;
+        let cookie = resp
+            .headers()
+            .get(header::SET_COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap();
+        assert!(cookie.contains("Max-Age=60"));
+    }
 }
@@ -11,10 +11,10 @@ mod session_storage;
 #[cfg(test)]
 pub(crate) mod test_harness;
 
+#[cfg(feature = "redis-session")]
+pub use crate::storage::RedisStorage;
 pub use cookie_config::{CookieConfig, CookieSecurity};
 pub use cookie_session::{CookieSession, CookieSessionEndpoint};
-#[cfg(feature = "redis-session")]
-pub use redis_storage::RedisStorage;
 pub use server_session::{ServerSession, ServerSessionEndpoint};
 pub use session::{Session, SessionStatus};
 pub use session_storage::SessionStorage;
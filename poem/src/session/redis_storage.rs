@@ -2,20 +2,10 @@ use std::{collections::BTreeMap, time::Duration};
 
 use redis::{aio::ConnectionLike, AsyncCommands, Cmd};
 
-use crate::{error::InternalServerError, session::session_storage::SessionStorage, Result};
-
-/// A session storage using redis.
-#[cfg_attr(docsrs, doc(cfg(feature = "redis-session")))]
-pub struct RedisStorage<T> {
-    connection: T,
-}
-
-impl<T> RedisStorage<T> {
-    /// Create a `RedisStorage`.
-    pub fn new(connection: T) -> Self {
-        Self { connection }
-    }
-}
+use crate::{
+    error::InternalServerError, session::session_storage::SessionStorage, storage::RedisStorage,
+    Result,
+};
 
 #[async_trait::async_trait]
 impl<T: ConnectionLike + Clone + Sync + Send> SessionStorage for RedisStorage<T> {
@@ -46,7 +36,7 @@ impl<T: ConnectionLike + Clone + Sync + Send> SessionStorage for RedisStorage<T>
             Some(expires) => Cmd::set_ex(&session_id, value, expires.as_secs() as usize),
             None => Cmd::set(&session_id, value),
         };
-        cmd.query_async(&mut self.connection.clone())
+        cmd.query_async::<_, ()>(&mut self.connection.clone())
             .await
             .map_err(InternalServerError)?;
         Ok(())
@@ -54,7 +44,7 @@ impl<T: ConnectionLike + Clone + Sync + Send> SessionStorage for RedisStorage<T>
 
     async fn remove_session(&self, session_id: &str) -> Result<()> {
         Cmd::del(session_id)
-            .query_async(&mut self.connection.clone())
+            .query_async::<_, ()>(&mut self.connection.clone())
             .await
             .map_err(InternalServerError)?;
         Ok(())
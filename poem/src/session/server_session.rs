@@ -75,19 +75,26 @@ where
 
         req.extensions_mut().insert(session.clone());
         let resp = self.inner.call(req).await;
+        let ttl = session.ttl().or_else(|| self.config.ttl());
 
         match session.status() {
             SessionStatus::Changed => match session_id {
                 Some(session_id) => {
+                    if session.ttl().is_some() {
+                        // refresh the expiration of the cookie
+                        self.config
+                            .set_cookie_value_with_ttl(&cookie_jar, &session_id, ttl);
+                    }
                     self.storage
-                        .update_session(&session_id, &session.entries(), self.config.ttl())
+                        .update_session(&session_id, &session.stored_entries(), ttl)
                         .await?;
                 }
                 None => {
                     let session_id = generate_session_id();
-                    self.config.set_cookie_value(&cookie_jar, &session_id);
+                    self.config
+                        .set_cookie_value_with_ttl(&cookie_jar, &session_id, ttl);
                     self.storage
-                        .update_session(&session_id, &session.entries(), self.config.ttl())
+                        .update_session(&session_id, &session.stored_entries(), ttl)
                         .await?;
                 }
             },
@@ -97,9 +104,10 @@ where
                 }
 
                 let session_id = generate_session_id();
-                self.config.set_cookie_value(&cookie_jar, &session_id);
+                self.config
+                    .set_cookie_value_with_ttl(&cookie_jar, &session_id, ttl);
                 self.storage
-                    .update_session(&session_id, &session.entries(), self.config.ttl())
+                    .update_session(&session_id, &session.stored_entries(), ttl)
                     .await?;
             }
             SessionStatus::Purged => {
@@ -3,6 +3,7 @@ use std::{
     convert::Infallible,
     fmt::{self, Debug, Formatter},
     sync::Arc,
+    time::Duration,
 };
 
 use parking_lot::RwLock;
@@ -26,9 +27,13 @@ pub enum SessionStatus {
     Unchanged,
 }
 
+/// The reserved entry to store the TTL(time-to-live) of the session.
+const TTL_KEY: &str = "__poem_session_ttl";
+
 struct SessionInner {
     status: SessionStatus,
     entries: BTreeMap<String, String>,
+    ttl: Option<Duration>,
 }
 
 /// Session
@@ -43,6 +48,7 @@ impl Debug for Session {
         f.debug_struct("Session")
             .field("status", &inner.status)
             .field("entries", &inner.entries)
+            .field("ttl", &inner.ttl)
             .finish()
     }
 }
@@ -57,11 +63,16 @@ impl Session {
     /// Creates a new session instance.
     ///
     /// The default status is [`SessionStatus::Unchanged`].
-    pub(crate) fn new(entries: BTreeMap<String, String>) -> Self {
+    pub(crate) fn new(mut entries: BTreeMap<String, String>) -> Self {
+        let ttl = entries
+            .remove(TTL_KEY)
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(Duration::from_secs);
         Self {
             inner: Arc::new(RwLock::new(SessionInner {
                 status: SessionStatus::Unchanged,
                 entries,
+                ttl,
             })),
         }
     }
@@ -109,6 +120,36 @@ impl Session {
         inner.entries.clone()
     }
 
+    /// Sets the TTL(time-to-live) of this session, it overrides the
+    /// [`CookieConfig::max_age`](crate::session::CookieConfig::max_age) for
+    /// this session and is kept until the session is purged.
+    pub fn set_ttl(&self, ttl: Duration) {
+        let mut inner = self.inner.write();
+        if inner.status != SessionStatus::Purged {
+            inner.ttl = Some(ttl);
+            if inner.status == SessionStatus::Unchanged {
+                inner.status = SessionStatus::Changed;
+            }
+        }
+    }
+
+    /// Returns the TTL(time-to-live) of this session set by
+    /// [`Session::set_ttl`].
+    pub fn ttl(&self) -> Option<Duration> {
+        let inner = self.inner.read();
+        inner.ttl
+    }
+
+    /// Returns the entries to be saved, including the TTL of this session.
+    pub(crate) fn stored_entries(&self) -> BTreeMap<String, String> {
+        let inner = self.inner.read();
+        let mut entries = inner.entries.clone();
+        if let Some(ttl) = inner.ttl {
+            entries.insert(TTL_KEY.to_string(), ttl.as_secs().to_string());
+        }
+        entries
+    }
+
     /// Clear the session.
     pub fn clear(&self) {
         let mut inner = self.inner.write();
@@ -129,6 +170,7 @@ impl Session {
         let mut inner = self.inner.write();
         if inner.status != SessionStatus::Purged {
             inner.entries.clear();
+            inner.ttl = None;
             inner.status = SessionStatus::Purged;
         }
     }
@@ -145,9 +187,9 @@ impl<'a> FromRequest<'a> for &'a Session {
     type Error = Infallible;
 
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
-        Ok(req
-            .extensions()
-            .get::<Session>()
-            .expect("To use the `Session` extractor, the `CookieSession` middleware is required."))
+        Ok(req.extensions().get::<Session>().expect(
+            "To use the `Session` extractor, the `CookieSession` or `ServerSession` middleware \
+                 is required.",
+        ))
     }
 }
@@ -0,0 +1,397 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    storage::{CounterStorage, KvStorage},
+    Result,
+};
+
+/// The number of writes between the removals of the expired entries.
+const PURGE_INTERVAL: usize = 1024;
+
+struct Entry<T> {
+    value: T,
+    expires_at: Option<Instant>,
+    used_at: u64,
+}
+
+impl<T> Entry<T> {
+    fn new(value: T, expires: Option<Duration>) -> Self {
+        Self {
+            value,
+            expires_at: expires.map(|expires| Instant::now() + expires),
+            used_at: 0,
+        }
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+struct Table<T> {
+    entries: HashMap<String, Entry<T>>,
+    writes: usize,
+    capacity: Option<usize>,
+    /// The keys ordered by the last access, it is only maintained if the
+    /// capacity is limited.
+    lru: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            writes: 0,
+            capacity: None,
+            lru: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl<T> Table<T> {
+    fn get_mut(&mut self, key: &str) -> Option<&mut Entry<T>> {
+        let now = Instant::now();
+        if self.entries.get(key)?.is_expired(now) {
+            self.remove(key);
+            return None;
+        }
+        let entry = self.entries.get_mut(key)?;
+        if self.capacity.is_some() {
+            self.lru.remove(&entry.used_at);
+            self.clock += 1;
+            entry.used_at = self.clock;
+            self.lru.insert(self.clock, key.to_string());
+        }
+        Some(entry)
+    }
+
+    fn insert(&mut self, key: &str, mut entry: Entry<T>) {
+        self.writes += 1;
+        if self.writes >= PURGE_INTERVAL {
+            self.writes = 0;
+            let now = Instant::now();
+            let lru = &mut self.lru;
+            self.entries.retain(|_, entry| {
+                let expired = entry.is_expired(now);
+                if expired {
+                    lru.remove(&entry.used_at);
+                }
+                !expired
+            });
+        }
+
+        if self.capacity.is_some() {
+            self.clock += 1;
+            entry.used_at = self.clock;
+            self.lru.insert(self.clock, key.to_string());
+        }
+        if let Some(old) = self.entries.insert(key.to_string(), entry) {
+            self.lru.remove(&old.used_at);
+        }
+
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() > capacity {
+                let used_at = match self.lru.keys().next() {
+                    Some(used_at) => *used_at,
+                    None => break,
+                };
+                if let Some(key) = self.lru.remove(&used_at) {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.used_at);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    values: Mutex<Table<Vec<u8>>>,
+    counters: Mutex<Table<i64>>,
+}
+
+/// A storage that keeps the data in memory, cloning the storage shares the
+/// same data.
+///
+/// The data is lost when the process exits, and it is not shared between the
+/// processes, use it for development or single instance deployments.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    inner: Arc<Inner>,
+}
+
+impl MemoryStorage {
+    /// Create a `MemoryStorage`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a `MemoryStorage` that keeps at most `capacity` values, the
+    /// least recently used values are removed when it is full.
+    ///
+    /// The counters are not limited.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let storage = Self::new();
+        storage.inner.values.lock().capacity = Some(capacity);
+        storage
+    }
+}
+
+#[async_trait::async_trait]
+impl KvStorage for MemoryStorage {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .inner
+            .values
+            .lock()
+            .get_mut(key)
+            .map(|entry| entry.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<()> {
+        self.inner
+            .values
+            .lock()
+            .insert(key, Entry::new(value.to_vec(), expires));
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<bool> {
+        let mut values = self.inner.values.lock();
+        if values.get_mut(key).is_some() {
+            return Ok(false);
+        }
+        values.insert(key, Entry::new(value.to_vec(), expires));
+        Ok(true)
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.inner.values.lock().remove(key);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CounterStorage for MemoryStorage {
+    async fn increment(&self, key: &str, delta: i64, expires: Duration) -> Result<i64> {
+        let mut counters = self.inner.counters.lock();
+        if let Some(entry) = counters.get_mut(key) {
+            entry.value += delta;
+            return Ok(entry.value);
+        }
+        counters.insert(key, Entry::new(delta, Some(expires)));
+        Ok(delta)
+    }
+
+    async fn throttle(&self, key: &str, limit: u64, period: Duration) -> Result<Option<Duration>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as i64;
+        let mut counters = self.inner.counters.lock();
+        let tat = counters.get_mut(key).map(|entry| entry.value);
+        match gcra(tat, now, limit, period) {
+            Ok(new_tat) => {
+                let expires = Duration::from_micros((new_tat - now) as u64);
+                counters.insert(key, Entry::new(new_tat, Some(expires)));
+                Ok(None)
+            }
+            Err(wait) => Ok(Some(wait)),
+        }
+    }
+}
+
+/// The state of the GCRA is the theoretical arrival time (TAT) of the next
+/// request in microseconds since the unix epoch, returns the new TAT if the
+/// request at `now` is allowed, otherwise the time to wait.
+fn gcra(
+    tat: Option<i64>,
+    now: i64,
+    limit: u64,
+    period: Duration,
+) -> std::result::Result<i64, Duration> {
+    let period = period.as_micros() as i64;
+    if limit == 0 {
+        return Err(Duration::from_micros(period as u64));
+    }
+    let interval = period / limit as i64;
+    let new_tat = tat.unwrap_or(now).max(now) + interval;
+    if new_tat - now > period {
+        return Err(Duration::from_micros((new_tat - now - period) as u64));
+    }
+    Ok(new_tat)
+}
+
+#[cfg(feature = "session")]
+#[async_trait::async_trait]
+impl crate::session::SessionStorage for MemoryStorage {
+    async fn load_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<std::collections::BTreeMap<String, String>>> {
+        Ok(self
+            .get(session_id)
+            .await?
+            .and_then(|data| serde_json::from_slice(&data).ok()))
+    }
+
+    async fn update_session(
+        &self,
+        session_id: &str,
+        entries: &std::collections::BTreeMap<String, String>,
+        expires: Option<Duration>,
+    ) -> Result<()> {
+        let value = serde_json::to_vec(entries).unwrap_or_default();
+        self.set(session_id, &value, expires).await
+    }
+
+    async fn remove_session(&self, session_id: &str) -> Result<()> {
+        self.remove(session_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn kv() {
+        let storage = MemoryStorage::new();
+        assert_eq!(storage.get("a").await.unwrap(), None);
+
+        storage.set("a", b"1", None).await.unwrap();
+        assert_eq!(storage.get("a").await.unwrap(), Some(b"1".to_vec()));
+
+        assert!(!storage.set_nx("a", b"2", None).await.unwrap());
+        assert!(storage.set_nx("b", b"2", None).await.unwrap());
+        assert_eq!(storage.get("b").await.unwrap(), Some(b"2".to_vec()));
+
+        storage.remove("a").await.unwrap();
+        assert_eq!(storage.get("a").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn expires() {
+        let storage = MemoryStorage::new();
+        storage
+            .set("a", b"1", Some(Duration::from_millis(50)))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .increment("c", 2, Duration::from_millis(50))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            storage
+                .increment("c", 3, Duration::from_millis(50))
+                .await
+                .unwrap(),
+            5
+        );
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(storage.get("a").await.unwrap(), None);
+        assert!(storage.set_nx("a", b"2", None).await.unwrap());
+        assert_eq!(
+            storage
+                .increment("c", 1, Duration::from_millis(50))
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn throttle() {
+        let storage = MemoryStorage::new();
+        let period = Duration::from_secs(3600);
+        assert_eq!(storage.throttle("a", 2, period).await.unwrap(), None);
+        assert_eq!(storage.throttle("a", 2, period).await.unwrap(), None);
+
+        let wait = storage.throttle("a", 2, period).await.unwrap().unwrap();
+        assert!(wait > Duration::from_secs(1790) && wait <= Duration::from_secs(1800));
+        // the rejected requests are not recorded
+        assert!(storage.throttle("a", 2, period).await.unwrap().unwrap() <= wait);
+
+        assert_eq!(storage.throttle("b", 2, period).await.unwrap(), None);
+        assert!(storage.throttle("c", 0, period).await.unwrap().is_some());
+    }
+
+    #[test]
+    fn gcra_spacing() {
+        let period = Duration::from_secs(1);
+        // 10 requests per second, the burst is used up at once
+        let mut tat = None;
+        for _ in 0..10 {
+            tat = Some(gcra(tat, 0, 10, period).unwrap());
+        }
+        assert_eq!(gcra(tat, 0, 10, period), Err(Duration::from_millis(100)));
+        // a request is allowed again after one interval
+        assert!(gcra(tat, 100_000, 10, period).is_ok());
+    }
+
+    #[tokio::test]
+    async fn lru() {
+        let storage = MemoryStorage::with_capacity(2);
+        storage.set("a", b"1", None).await.unwrap();
+        storage.set("b", b"2", None).await.unwrap();
+        assert!(storage.get("a").await.unwrap().is_some());
+
+        storage.set("c", b"3", None).await.unwrap();
+        assert_eq!(storage.get("b").await.unwrap(), None);
+        assert_eq!(storage.get("a").await.unwrap(), Some(b"1".to_vec()));
+        assert_eq!(storage.get("c").await.unwrap(), Some(b"3".to_vec()));
+
+        storage.set("c", b"4", None).await.unwrap();
+        storage.remove("a").await.unwrap();
+        storage.set("d", b"5", None).await.unwrap();
+        assert_eq!(storage.get("c").await.unwrap(), Some(b"4".to_vec()));
+        assert_eq!(storage.get("d").await.unwrap(), Some(b"5".to_vec()));
+    }
+
+    #[cfg(feature = "session")]
+    #[tokio::test]
+    async fn memory_session() {
+        use crate::{
+            session::{
+                test_harness::{index, TestClient},
+                CookieConfig, ServerSession,
+            },
+            EndpointExt, Route,
+        };
+
+        let app = Route::new().at("/:action", index).with(ServerSession::new(
+            CookieConfig::default(),
+            MemoryStorage::new(),
+        ));
+        let mut client = TestClient::default();
+
+        client.call(&app, 0).await;
+        client.assert_cookies(vec![]);
+
+        client.call(&app, 1).await;
+        client.call(&app, 2).await;
+        client.call(&app, 7).await;
+        client.call(&app, 6).await;
+        client.call(&app, 3).await;
+        client.call(&app, 4).await;
+        client.call(&app, 5).await;
+        client.assert_cookies(vec![]);
+    }
+}
@@ -0,0 +1,72 @@
+//! Storage back-ends shared by the middlewares.
+//!
+//! The traits in this module abstract the storages used by the sessions, rate
+//! limiting, idempotency and caching middlewares, so all of them can share one
+//! back-end, such as one redis connection pool.
+//!
+//! # Example
+//!
+//! ```
+//! use poem::storage::{KvStorage, MemoryStorage};
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let storage = MemoryStorage::new();
+//! storage.set("a", b"1", None).await.unwrap();
+//! assert_eq!(storage.get("a").await.unwrap().as_deref(), Some(&b"1"[..]));
+//! # });
+//! ```
+
+mod memory_storage;
+#[cfg(feature = "redis-storage")]
+mod redis_storage;
+
+use std::time::Duration;
+
+pub use memory_storage::MemoryStorage;
+#[cfg(feature = "redis-storage")]
+pub use redis_storage::RedisStorage;
+
+#[cfg(feature = "session")]
+#[cfg_attr(docsrs, doc(cfg(feature = "session")))]
+pub use crate::session::SessionStorage;
+use crate::Result;
+
+/// Represents a key-value storage, used by the caching and idempotency
+/// middlewares.
+#[async_trait::async_trait]
+pub trait KvStorage: Send + Sync {
+    /// Returns the value of the key, or `None` if the key does not exist or
+    /// has expired.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Sets the value of the key, the key is removed after `expires` if it is
+    /// specified.
+    async fn set(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<()>;
+
+    /// Sets the value of the key only if the key does not exist, returns
+    /// `true` if the value is set.
+    async fn set_nx(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<bool>;
+
+    /// Removes the key.
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// Represents a storage of the counters, used by the rate limiting
+/// middlewares.
+#[async_trait::async_trait]
+pub trait CounterStorage: Send + Sync {
+    /// Adds `delta` to the counter and returns the new value.
+    ///
+    /// If the counter does not exist, it is created with the value `0` and
+    /// removed after `expires`.
+    async fn increment(&self, key: &str, delta: i64, expires: Duration) -> Result<i64>;
+
+    /// Checks a request against the limit of `limit` requests in every
+    /// `period` with the generic cell rate algorithm (GCRA), and records it
+    /// if it is allowed. Returns `None` if the request is allowed, otherwise
+    /// the time to wait before the next request is allowed.
+    ///
+    /// The check and the update must be atomic, so the concurrent requests
+    /// sharing the storage cannot exceed the limit.
+    async fn throttle(&self, key: &str, limit: u64, period: Duration) -> Result<Option<Duration>>;
+}
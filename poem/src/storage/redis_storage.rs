@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use redis::{aio::ConnectionLike, AsyncCommands, Cmd, Script};
+
+use crate::{
+    error::InternalServerError,
+    storage::{CounterStorage, KvStorage},
+    Result,
+};
+
+/// Increments the counter, and sets the expiration if the counter has none,
+/// such as a counter that is created or whose value returns to `delta`.
+const INCREMENT_SCRIPT: &str = r#"
+local value = redis.call('INCRBY', KEYS[1], ARGV[1])
+if redis.call('PTTL', KEYS[1]) < 0 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return value
+"#;
+
+/// The generic cell rate algorithm, the key is the theoretical arrival time
+/// of the next request in microseconds, and the time of the redis server is
+/// used so the instances sharing the storage agree on it. Returns `-1` if the
+/// request is allowed, otherwise the microseconds to wait.
+const THROTTLE_SCRIPT: &str = r#"
+redis.replicate_commands()
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local interval = tonumber(ARGV[1])
+local period = tonumber(ARGV[2])
+local tat = tonumber(redis.call('GET', KEYS[1]) or now)
+if tat < now then
+    tat = now
+end
+local new_tat = tat + interval
+if new_tat - now > period then
+    return new_tat - now - period
+end
+redis.call('SET', KEYS[1], string.format('%.0f', new_tat), 'PX', math.ceil((new_tat - now) / 1000))
+return -1
+"#;
+
+/// A storage using redis.
+///
+/// The connection is cloned for each command, so a
+/// [`ConnectionManager`](redis::aio::ConnectionManager) can be shared by all
+/// the middlewares using this storage.
+#[cfg_attr(docsrs, doc(cfg(feature = "redis-storage")))]
+#[derive(Clone)]
+pub struct RedisStorage<T> {
+    pub(crate) connection: T,
+}
+
+impl<T> RedisStorage<T> {
+    /// Create a `RedisStorage`.
+    pub fn new(connection: T) -> Self {
+        Self { connection }
+    }
+}
+
+fn set_cmd(key: &str, value: &[u8], expires: Option<Duration>) -> Cmd {
+    let mut cmd = redis::cmd("SET");
+    cmd.arg(key).arg(value);
+    if let Some(expires) = expires {
+        cmd.arg("PX").arg(expires.as_millis() as u64);
+    }
+    cmd
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionLike + Clone + Sync + Send> KvStorage for RedisStorage<T> {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .connection
+            .clone()
+            .get(key)
+            .await
+            .map_err(InternalServerError)?)
+    }
+
+    async fn set(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<()> {
+        set_cmd(key, value, expires)
+            .query_async::<_, ()>(&mut self.connection.clone())
+            .await
+            .map_err(InternalServerError)?;
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &[u8], expires: Option<Duration>) -> Result<bool> {
+        let res: Option<String> = set_cmd(key, value, expires)
+            .arg("NX")
+            .query_async(&mut self.connection.clone())
+            .await
+            .map_err(InternalServerError)?;
+        Ok(res.is_some())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        Cmd::del(key)
+            .query_async::<_, ()>(&mut self.connection.clone())
+            .await
+            .map_err(InternalServerError)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: ConnectionLike + Clone + Sync + Send> CounterStorage for RedisStorage<T> {
+    async fn increment(&self, key: &str, delta: i64, expires: Duration) -> Result<i64> {
+        Ok(Script::new(INCREMENT_SCRIPT)
+            .key(key)
+            .arg(delta)
+            .arg(expires.as_millis() as u64)
+            .invoke_async(&mut self.connection.clone())
+            .await
+            .map_err(InternalServerError)?)
+    }
+
+    async fn throttle(&self, key: &str, limit: u64, period: Duration) -> Result<Option<Duration>> {
+        if limit == 0 {
+            return Ok(Some(period));
+        }
+        let period = period.as_micros() as u64;
+        let wait: i64 = Script::new(THROTTLE_SCRIPT)
+            .key(key)
+            .arg(period / limit)
+            .arg(period)
+            .invoke_async(&mut self.connection.clone())
+            .await
+            .map_err(InternalServerError)?;
+        Ok((wait >= 0).then(|| Duration::from_micros(wait as u64)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redis::{aio::ConnectionManager, Client};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn redis_storage() {
+        let client = Client::open("redis://127.0.0.1/").unwrap();
+        let storage = RedisStorage::new(ConnectionManager::new(client).await.unwrap());
+
+        storage.remove("poem-test-a").await.unwrap();
+        assert!(storage
+            .set_nx("poem-test-a", b"1", Some(Duration::from_secs(10)))
+            .await
+            .unwrap());
+        assert!(!storage.set_nx("poem-test-a", b"2", None).await.unwrap());
+        assert_eq!(
+            storage.get("poem-test-a").await.unwrap(),
+            Some(b"1".to_vec())
+        );
+        storage.remove("poem-test-a").await.unwrap();
+        assert_eq!(storage.get("poem-test-a").await.unwrap(), None);
+
+        storage.remove("poem-test-c").await.unwrap();
+        let expires = Duration::from_secs(10);
+        assert_eq!(
+            storage.increment("poem-test-c", 2, expires).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            storage.increment("poem-test-c", 3, expires).await.unwrap(),
+            5
+        );
+        storage.remove("poem-test-c").await.unwrap();
+
+        // the counter without an expiration gets one
+        storage.set("poem-test-d", b"1", None).await.unwrap();
+        assert_eq!(
+            storage.increment("poem-test-d", 1, expires).await.unwrap(),
+            2
+        );
+        let ttl: i64 = redis::cmd("PTTL")
+            .arg("poem-test-d")
+            .query_async(&mut storage.connection.clone())
+            .await
+            .unwrap();
+        assert!(ttl > 0 && ttl <= expires.as_millis() as i64);
+        storage.remove("poem-test-d").await.unwrap();
+
+        storage.remove("poem-test-e").await.unwrap();
+        let period = Duration::from_secs(3600);
+        assert_eq!(
+            storage.throttle("poem-test-e", 2, period).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.throttle("poem-test-e", 2, period).await.unwrap(),
+            None
+        );
+        let wait = storage
+            .throttle("poem-test-e", 2, period)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(wait > Duration::from_secs(1790) && wait <= Duration::from_secs(1800));
+        storage.remove("poem-test-e").await.unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+use std::convert::TryInto;
+
+use crate::{
+    http::{header::HeaderName, HeaderMap, HeaderValue, Method},
+    test::TestRequestBuilder,
+    Endpoint, IntoEndpoint,
+};
+
+/// A client that calls an endpoint directly, without binding a socket.
+///
+/// # Example
+///
+/// ```
+/// use poem::{get, handler, test::TestClient, web::Path, Route};
+///
+/// #[handler]
+/// fn hello(Path(name): Path<String>) -> String {
+///     format!("hello: {}", name)
+/// }
+///
+/// let app = Route::new().at("/hello/:name", get(hello));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/hello/poem").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello: poem").await;
+/// # });
+/// ```
+pub struct TestClient<E> {
+    pub(crate) ep: E,
+    pub(crate) default_headers: HeaderMap,
+}
+
+impl<E: Endpoint> TestClient<E> {
+    /// Create a client for the endpoint.
+    pub fn new(ep: impl IntoEndpoint<Endpoint = E>) -> Self {
+        Self {
+            ep: ep.into_endpoint(),
+            default_headers: HeaderMap::new(),
+        }
+    }
+
+    /// Sets a header that is sent with all the requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key or the value is invalid.
+    #[must_use]
+    pub fn default_header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header name"));
+        let value = value
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header value"));
+        self.default_headers.append(key, value);
+        self
+    }
+
+    /// Create a request with the method and the uri, such as `/users?page=1`.
+    pub fn request(&self, method: Method, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        TestRequestBuilder::new(self, method, uri.into())
+    }
+
+    /// Create a `GET` request.
+    pub fn get(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::GET, uri)
+    }
+
+    /// Create a `POST` request.
+    pub fn post(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::POST, uri)
+    }
+
+    /// Create a `PUT` request.
+    pub fn put(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::PUT, uri)
+    }
+
+    /// Create a `DELETE` request.
+    pub fn delete(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::DELETE, uri)
+    }
+
+    /// Create a `PATCH` request.
+    pub fn patch(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::PATCH, uri)
+    }
+
+    /// Create a `HEAD` request.
+    pub fn head(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::HEAD, uri)
+    }
+
+    /// Create an `OPTIONS` request.
+    pub fn options(&self, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
+        self.request(Method::OPTIONS, uri)
+    }
+}
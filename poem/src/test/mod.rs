@@ -0,0 +1,103 @@
+//! Test utilities to test the endpoints without binding a socket.
+
+mod client;
+mod request_builder;
+mod response;
+
+pub use client::TestClient;
+pub use request_builder::TestRequestBuilder;
+pub use response::TestResponse;
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        get, handler,
+        http::{HeaderMap, StatusCode},
+        web::{Json, Query},
+        EndpointExt, Route,
+    };
+
+    #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+    struct Item {
+        name: String,
+    }
+
+    #[handler(internal)]
+    fn echo_query(Query(item): Query<Item>, headers: &HeaderMap) -> String {
+        format!(
+            "{}:{}",
+            item.name,
+            headers
+                .get("x-value")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+        )
+    }
+
+    #[handler(internal)]
+    fn echo_json(Json(item): Json<Item>) -> Json<Item> {
+        Json(item)
+    }
+
+    fn app() -> impl crate::Endpoint {
+        Route::new()
+            .at("/query", get(echo_query))
+            .at("/json", get(echo_json).post(echo_json))
+            .map_to_response()
+    }
+
+    #[tokio::test]
+    async fn query_and_headers() {
+        let cli = TestClient::new(app()).default_header("X-Value", "a");
+
+        let resp = cli.get("/query").query("name", &"sunli").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("sunli:a").await;
+
+        let resp = cli
+            .get("/query?name=poem")
+            .header("X-Value", "b")
+            .send()
+            .await;
+        resp.assert_text("poem:b").await;
+
+        cli.get("/missing")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn json() {
+        let cli = TestClient::new(app());
+
+        let resp = cli
+            .post("/json")
+            .body_json(&Item {
+                name: "sunli".to_string(),
+            })
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("application/json");
+        resp.assert_json(json!({ "name": "sunli" })).await;
+
+        let item: Item = cli
+            .post("/json")
+            .body_json(&json!({ "name": "poem" }))
+            .send()
+            .await
+            .json()
+            .await;
+        assert_eq!(
+            item,
+            Item {
+                name: "poem".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::convert::TryInto;
+
+use serde::Serialize;
+
+use crate::{
+    http::{header, header::HeaderName, HeaderMap, HeaderValue, Method, Uri},
+    test::{TestClient, TestResponse},
+    Body, Endpoint, IntoResponse, Request,
+};
+
+/// A request of the [`TestClient`].
+pub struct TestRequestBuilder<'a, E> {
+    cli: &'a TestClient<E>,
+    method: Method,
+    uri: String,
+    query: Vec<(String, String)>,
+    headers: HeaderMap,
+    body: Body,
+}
+
+impl<'a, E: Endpoint> TestRequestBuilder<'a, E> {
+    pub(crate) fn new(cli: &'a TestClient<E>, method: Method, uri: String) -> Self {
+        Self {
+            cli,
+            method,
+            uri,
+            query: Vec::new(),
+            headers: cli.default_headers.clone(),
+            body: Body::empty(),
+        }
+    }
+
+    /// Appends a query parameter to the uri.
+    #[must_use]
+    pub fn query(mut self, name: impl Into<String>, value: &impl ToString) -> Self {
+        self.query.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Sets a header, the default header with the same name is replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key or the value is invalid.
+    #[must_use]
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header name"));
+        let value = value
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header value"));
+        self.headers.insert(key, value);
+        self
+    }
+
+    /// Sets the `Content-Type` header.
+    #[must_use]
+    pub fn content_type(self, content_type: &str) -> Self {
+        self.header(header::CONTENT_TYPE, content_type)
+    }
+
+    /// Sets the body.
+    #[must_use]
+    pub fn body(self, body: impl Into<Body>) -> Self {
+        Self {
+            body: body.into(),
+            ..self
+        }
+    }
+
+    /// Sets the JSON body, and the `Content-Type` is `application/json`.
+    #[must_use]
+    pub fn body_json(self, body: &impl Serialize) -> Self {
+        self.content_type("application/json")
+            .body(serde_json::to_string(body).expect("valid json"))
+    }
+
+    /// Sets the form body, and the `Content-Type` is
+    /// `application/x-www-form-urlencoded`.
+    #[must_use]
+    pub fn body_form(self, body: &impl Serialize) -> Self {
+        self.content_type("application/x-www-form-urlencoded")
+            .body(serde_urlencoded::to_string(body).expect("valid form"))
+    }
+
+    /// Sends the request to the endpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the uri is invalid.
+    pub async fn send(self) -> TestResponse {
+        let mut uri = self.uri;
+        if !self.query.is_empty() {
+            uri.push(if uri.contains('?') { '&' } else { '?' });
+            uri.push_str(&serde_urlencoded::to_string(&self.query).expect("valid query"));
+        }
+        let uri: Uri = uri.parse().expect("valid uri");
+
+        let mut req = Request::builder()
+            .method(self.method)
+            .uri(uri)
+            .body(self.body);
+        *req.headers_mut() = self.headers;
+        TestResponse(self.cli.ep.call(req).await.into_response())
+    }
+}
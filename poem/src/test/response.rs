@@ -0,0 +1,87 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    http::{header::HeaderName, HeaderValue, StatusCode},
+    Response,
+};
+
+/// A response of the [`TestClient`](crate::test::TestClient) with assertion
+/// helpers.
+pub struct TestResponse(pub Response);
+
+impl TestResponse {
+    /// Asserts that the status code is equal to `status`.
+    #[track_caller]
+    pub fn assert_status(&self, status: StatusCode) {
+        assert_eq!(self.0.status(), status);
+    }
+
+    /// Asserts that the status code is `200 OK`.
+    #[track_caller]
+    pub fn assert_status_is_ok(&self) {
+        self.assert_status(StatusCode::OK);
+    }
+
+    /// Asserts that the header is equal to `value`.
+    #[track_caller]
+    pub fn assert_header<K, V>(&self, key: K, value: V)
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header name"));
+        let value = value
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header value"));
+        assert_eq!(self.0.headers().get(&key), Some(&value), "header `{}`", key);
+    }
+
+    /// Asserts that the header does not exist.
+    #[track_caller]
+    pub fn assert_header_is_not_exist(&self, key: impl TryInto<HeaderName>) {
+        let key = key
+            .try_into()
+            .unwrap_or_else(|_| panic!("invalid header name"));
+        assert!(
+            !self.0.headers().contains_key(&key),
+            "header `{}` exists",
+            key
+        );
+    }
+
+    /// Asserts that the `Content-Type` is equal to `content_type`.
+    #[track_caller]
+    pub fn assert_content_type(&self, content_type: &str) {
+        assert_eq!(self.0.content_type(), Some(content_type));
+    }
+
+    /// Asserts that the body is equal to `text`.
+    pub async fn assert_text(self, text: impl AsRef<str>) {
+        assert_eq!(
+            self.0.into_body().into_string().await.expect("valid body"),
+            text.as_ref()
+        );
+    }
+
+    /// Asserts that the body is equal to `bytes`.
+    pub async fn assert_bytes(self, bytes: impl AsRef<[u8]>) {
+        assert_eq!(
+            self.0.into_body().into_vec().await.expect("valid body"),
+            bytes.as_ref()
+        );
+    }
+
+    /// Asserts that the body is a JSON that is equal to `json`.
+    pub async fn assert_json(self, json: impl Serialize) {
+        let expected = serde_json::to_value(json).expect("valid json");
+        assert_eq!(self.json::<serde_json::Value>().await, expected);
+    }
+
+    /// Deserializes the body as JSON.
+    pub async fn json<T: DeserializeOwned>(self) -> T {
+        let data = self.0.into_body().into_vec().await.expect("valid body");
+        serde_json::from_slice(&data).expect("valid json")
+    }
+}
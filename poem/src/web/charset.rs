@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "charset")]
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::{error::ReadBodyError, Request};
+
+/// Returns the `charset` parameter of the `Content-Type` header.
+#[cfg(feature = "charset")]
+fn charset(req: &Request) -> Option<String> {
+    let mime: mime::Mime = req.content_type()?.parse().ok()?;
+    mime.get_param(mime::CHARSET)
+        .map(|charset| charset.as_str().to_string())
+}
+
+/// Decodes the body data into a UTF-8 string.
+///
+/// If the `charset` feature is enabled, the data is transcoded according to the
+/// `charset` parameter of the `Content-Type` header.
+#[cfg_attr(not(feature = "charset"), allow(unused_variables))]
+pub(crate) fn decode_body(req: &Request, data: Vec<u8>) -> Result<String, ReadBodyError> {
+    #[cfg(feature = "charset")]
+    if let Some(charset) = charset(req) {
+        let encoding = Encoding::for_label(charset.as_bytes())
+            .ok_or_else(|| ReadBodyError::UnsupportedCharset(charset.clone()))?;
+        if encoding != UTF_8 {
+            return encoding
+                .decode_without_bom_handling_and_without_replacement(&data)
+                .map(Cow::into_owned)
+                .ok_or(ReadBodyError::InvalidCharset(charset));
+        }
+    }
+
+    String::from_utf8(data).map_err(ReadBodyError::Utf8)
+}
+
+/// Decodes the `application/x-www-form-urlencoded` body data, and the
+/// percent-encoded bytes are transcoded according to the `charset` parameter of
+/// the `Content-Type` header.
+#[cfg(feature = "charset")]
+pub(crate) fn decode_form<'a>(
+    req: &Request,
+    data: &'a [u8],
+) -> Result<Cow<'a, [u8]>, ReadBodyError> {
+    let charset = match charset(req) {
+        Some(charset) => charset,
+        None => return Ok(Cow::Borrowed(data)),
+    };
+    let encoding = Encoding::for_label(charset.as_bytes())
+        .ok_or_else(|| ReadBodyError::UnsupportedCharset(charset.clone()))?;
+    if encoding == UTF_8 {
+        return Ok(Cow::Borrowed(data));
+    }
+
+    let decode = |s: &[u8]| {
+        let s = s
+            .iter()
+            .map(|c| if *c == b'+' { b' ' } else { *c })
+            .collect::<Vec<_>>();
+        let s = percent_encoding::percent_decode(&s).collect::<Vec<_>>();
+        encoding
+            .decode_without_bom_handling_and_without_replacement(&s)
+            .map(Cow::into_owned)
+            .ok_or_else(|| ReadBodyError::InvalidCharset(charset.clone()))
+    };
+
+    let mut pairs = Vec::new();
+    for pair in data.split(|c| *c == b'&').filter(|s| !s.is_empty()) {
+        let mut it = pair.splitn(2, |c| *c == b'=');
+        let name = decode(it.next().unwrap_or_default())?;
+        let value = decode(it.next().unwrap_or_default())?;
+        pairs.push((name, value));
+    }
+
+    Ok(Cow::Owned(
+        serde_urlencoded::to_string(pairs)
+            .unwrap_or_default()
+            .into_bytes(),
+    ))
+}
+
+#[cfg(not(feature = "charset"))]
+#[inline]
+pub(crate) fn decode_form<'a>(
+    _req: &Request,
+    data: &'a [u8],
+) -> Result<Cow<'a, [u8]>, ReadBodyError> {
+    Ok(Cow::Borrowed(data))
+}
+
+#[cfg(all(test, feature = "charset"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_latin1() {
+        let req = Request::builder()
+            .content_type("text/plain; charset=iso-8859-1")
+            .finish();
+        assert_eq!(decode_body(&req, b"caf\xe9".to_vec()).unwrap(), "café");
+
+        let req = Request::builder()
+            .content_type("text/plain; charset=utf-8")
+            .finish();
+        assert_eq!(
+            decode_body(&req, "café".as_bytes().to_vec()).unwrap(),
+            "café"
+        );
+
+        let req = Request::builder()
+            .content_type("text/plain; charset=abc")
+            .finish();
+        assert!(matches!(
+            decode_body(&req, b"abc".to_vec()),
+            Err(ReadBodyError::UnsupportedCharset(charset)) if charset == "abc"
+        ));
+    }
+
+    #[test]
+    fn decode_latin1_form() {
+        let req = Request::builder()
+            .content_type("application/x-www-form-urlencoded; charset=iso-8859-1")
+            .finish();
+        let data = decode_form(&req, b"name=caf%E9&value=a+b").unwrap();
+        assert_eq!(
+            serde_urlencoded::from_bytes::<Vec<(String, String)>>(&data).unwrap(),
+            vec![
+                ("name".to_string(), "café".to_string()),
+                ("value".to_string(), "a b".to_string())
+            ]
+        );
+    }
+}
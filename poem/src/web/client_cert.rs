@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use crate::{error::ClientCertRequired, FromRequest, Request, RequestBody, Result};
+
+/// The certificate chain presented by the client.
+///
+/// It is only available when the server is listening with a TLS listener that
+/// enables client authentication, see
+/// [`TlsConfig::client_auth_required`](crate::listener::TlsConfig::client_auth_required).
+///
+/// Use `Option<&ClientCert>` if the client authentication is optional.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::ClientCert};
+///
+/// #[handler]
+/// fn index(cert: &ClientCert) -> String {
+///     format!("{} certificates", cert.chain().len())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientCert(Arc<Vec<Vec<u8>>>);
+
+impl ClientCert {
+    /// Create a client certificate chain from the DER-encoded certificates.
+    pub fn new(chain: Vec<Vec<u8>>) -> Self {
+        Self(Arc::new(chain))
+    }
+
+    /// Returns the DER-encoded certificate chain, the first one is the
+    /// end-entity certificate.
+    #[inline]
+    pub fn chain(&self) -> &[Vec<u8>] {
+        &self.0
+    }
+
+    /// Returns the DER-encoded end-entity certificate.
+    #[inline]
+    pub fn leaf(&self) -> Option<&[u8]> {
+        self.0.first().map(Vec::as_slice)
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a ClientCert {
+    type Error = ClientCertRequired;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        req.extensions()
+            .get::<ClientCert>()
+            .ok_or(ClientCertRequired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn extract_client_cert() {
+        let mut req = Request::default();
+        assert!(<&ClientCert>::from_request(&req, &mut Default::default())
+            .await
+            .is_err());
+
+        req.extensions_mut()
+            .insert(ClientCert::new(vec![vec![1, 2], vec![3]]));
+        let cert = <&ClientCert>::from_request(&req, &mut Default::default())
+            .await
+            .unwrap();
+        assert_eq!(cert.chain().len(), 2);
+        assert_eq!(cert.leaf(), Some(&[1u8, 2][..]));
+    }
+}
@@ -111,7 +111,7 @@ pub struct Compress<T> {
 }
 
 impl<T> Compress<T> {
-    /// /// Create a compressed response using the specified algorithm.
+    /// Create a compressed response using the specified algorithm.
     pub fn new(inner: T, algo: CompressionAlgo) -> Self {
         Self { inner, algo }
     }
@@ -122,6 +122,7 @@ impl<T: IntoResponse> IntoResponse for Compress<T> {
         let mut resp = self.inner.into_response();
         let body = resp.take_body();
 
+        resp.headers_mut().remove(header::CONTENT_LENGTH);
         resp.headers_mut().append(
             header::CONTENT_ENCODING,
             HeaderValue::from_static(self.algo.as_str()),
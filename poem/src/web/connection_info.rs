@@ -0,0 +1,92 @@
+use std::convert::Infallible;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+static EMPTY: ConnectionInfo = ConnectionInfo {
+    server_name: None,
+    alpn_protocol: None,
+    peer_cred: None,
+    tls: false,
+};
+
+/// The credentials of the peer process of a Unix domain socket connection.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PeerCred {
+    /// The user id of the peer process.
+    pub uid: u32,
+
+    /// The group id of the peer process.
+    pub gid: u32,
+
+    /// The process id of the peer process, if it is supported by the
+    /// platform.
+    pub pid: Option<i32>,
+}
+
+/// The information of the connection that the request was received on,
+/// provided by the listener.
+///
+/// The fields that are not supported by the listener are `None`, e.g. the
+/// TLS fields of a plain TCP connection.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::ConnectionInfo};
+///
+/// #[handler]
+/// fn index(info: &ConnectionInfo) -> String {
+///     format!("server name: {:?}", info.server_name)
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ConnectionInfo {
+    /// The server name requested by the client with the TLS SNI extension.
+    pub server_name: Option<String>,
+
+    /// The protocol negotiated with the TLS ALPN extension, such as `h2`.
+    pub alpn_protocol: Option<Vec<u8>>,
+
+    /// The credentials of the peer process of a Unix domain socket
+    /// connection.
+    pub peer_cred: Option<PeerCred>,
+
+    /// Whether the connection is secured by TLS.
+    pub tls: bool,
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a ConnectionInfo {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req.extensions().get::<ConnectionInfo>().unwrap_or(&EMPTY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn extract_connection_info() {
+        let mut req = Request::default();
+        let info = <&ConnectionInfo>::from_request(&req, &mut Default::default())
+            .await
+            .unwrap();
+        assert_eq!(info, &ConnectionInfo::default());
+
+        req.extensions_mut().insert(ConnectionInfo {
+            server_name: Some("example.com".to_string()),
+            alpn_protocol: Some(b"h2".to_vec()),
+            peer_cred: None,
+            tls: true,
+        });
+        let info = <&ConnectionInfo>::from_request(&req, &mut Default::default())
+            .await
+            .unwrap();
+        assert_eq!(info.server_name.as_deref(), Some("example.com"));
+        assert_eq!(info.alpn_protocol.as_deref(), Some(&b"h2"[..]));
+    }
+}
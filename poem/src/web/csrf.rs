@@ -0,0 +1,71 @@
+use std::convert::Infallible;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The CSRF token of the current client, it should be submitted with the form
+/// or the header of the unsafe requests.
+///
+/// _Requires the [`Csrf`](crate::middleware::Csrf) middleware._
+#[cfg_attr(docsrs, doc(cfg(feature = "csrf")))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CsrfToken(pub String);
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a CsrfToken {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req
+            .extensions()
+            .get::<CsrfToken>()
+            .expect("To use the `CsrfToken` extractor, the `Csrf` middleware is required."))
+    }
+}
+
+/// Verifies the CSRF token submitted by the client.
+///
+/// _Requires the [`Csrf`](crate::middleware::Csrf) middleware._
+#[cfg_attr(docsrs, doc(cfg(feature = "csrf")))]
+pub struct CsrfVerifier {
+    token: String,
+    header_token: Option<String>,
+}
+
+impl CsrfVerifier {
+    pub(crate) fn new(token: String, header_token: Option<String>) -> Self {
+        Self {
+            token,
+            header_token,
+        }
+    }
+
+    /// Returns `true` if the `token` matches the token in the cookie.
+    pub fn is_valid(&self, token: &str) -> bool {
+        constant_time_eq(self.token.as_bytes(), token.as_bytes())
+    }
+
+    /// Returns `true` if the token in the CSRF header matches the token in the
+    /// cookie.
+    pub fn is_header_valid(&self) -> bool {
+        self.header_token
+            .as_deref()
+            .map(|token| self.is_valid(token))
+            .unwrap_or_default()
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a CsrfVerifier {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req
+            .extensions()
+            .get::<CsrfVerifier>()
+            .expect("To use the `CsrfVerifier` extractor, the `Csrf` middleware is required."))
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// An in-process publish/subscribe channel, usually shared by the handlers
+/// with [`AddData`](crate::middleware::AddData).
+///
+/// Every subscriber receives a clone of each event published after it
+/// subscribes. A subscriber that falls behind by more than the capacity of the
+/// bus skips the oldest events.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::StreamExt;
+/// use poem::{
+///     get, handler,
+///     middleware::AddData,
+///     web::{Data, EventBus},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn update(bus: Data<&EventBus<String>>) {
+///     bus.publish("changed".to_string());
+/// }
+///
+/// #[handler]
+/// async fn wait(bus: Data<&EventBus<String>>) -> String {
+///     bus.subscribe().next().await.unwrap_or_default()
+/// }
+///
+/// let app = Route::new()
+///     .at("/update", get(update))
+///     .at("/wait", get(wait))
+///     .with(AddData::new(EventBus::<String>::new(32)));
+/// ```
+///
+/// Use [`EventBus::sse`] or [`EventBus::websocket`] to forward the events to
+/// the connected clients.
+pub struct EventBus<T> {
+    sender: broadcast::Sender<T>,
+}
+
+impl<T> Clone for EventBus<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> Debug for EventBus<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.sender.receiver_count())
+            .finish()
+    }
+}
+
+impl<T: Clone + Send + 'static> EventBus<T> {
+    /// Create an `EventBus` that buffers at most `capacity` events for each
+    /// subscriber.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `capacity` is `0`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Publishes an event to all the subscribers, and returns the number of
+    /// the subscribers that will receive it.
+    pub fn publish(&self, event: T) -> usize {
+        self.sender.send(event).unwrap_or_default()
+    }
+
+    /// Returns the number of the subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Subscribes to the events published after this call.
+    pub fn subscribe(&self) -> EventStream<T> {
+        let receiver = self.sender.subscribe();
+        EventStream(
+            futures_util::stream::unfold(receiver, |mut receiver| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) => return Some((event, receiver)),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            })
+            .boxed(),
+        )
+    }
+
+    /// Create an [`SSE`](crate::web::sse::SSE) response that sends the
+    /// events to the client, `f` converts each event to the
+    /// [`Event`](crate::web::sse::Event).
+    #[cfg(feature = "sse")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+    pub fn sse<F>(&self, f: F) -> crate::web::sse::SSE
+    where
+        F: Fn(T) -> crate::web::sse::Event + Send + 'static,
+    {
+        crate::web::sse::SSE::new(self.subscribe().map(f))
+    }
+
+    /// Upgrades the [`WebSocket`](crate::web::websocket::WebSocket) and sends
+    /// the events to the client until it closes the connection, `f` converts
+    /// each event to the [`Message`](crate::web::websocket::Message).
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub fn websocket<F>(
+        &self,
+        ws: crate::web::websocket::WebSocket,
+        f: F,
+    ) -> impl crate::IntoResponse
+    where
+        T: Sync,
+        F: Fn(T) -> crate::web::websocket::Message + Send + Sync + 'static,
+    {
+        use futures_util::SinkExt;
+
+        use crate::web::websocket::Message;
+
+        let bus = self.clone();
+        ws.on_upgrade(move |socket| async move {
+            let (mut sink, mut stream) = socket.split();
+            let mut events = bus.subscribe();
+
+            loop {
+                tokio::select! {
+                    event = events.next() => match event {
+                        Some(event) => {
+                            if sink.send(f(event)).await.is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    },
+                    msg = stream.next() => match msg {
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                        _ => {}
+                    },
+                }
+            }
+        })
+    }
+}
+
+/// A stream of the events published to the [`EventBus`].
+pub struct EventStream<T>(BoxStream<'static, T>);
+
+impl<T> Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn publish_subscribe() {
+        let bus = EventBus::new(8);
+        assert_eq!(bus.publish(1), 0);
+
+        let mut a = bus.subscribe();
+        let mut b = bus.clone().subscribe();
+        assert_eq!(bus.subscriber_count(), 2);
+        assert_eq!(bus.publish(2), 2);
+        assert_eq!(bus.publish(3), 2);
+
+        assert_eq!(a.next().await, Some(2));
+        assert_eq!(a.next().await, Some(3));
+        assert_eq!(b.next().await, Some(2));
+
+        drop(a);
+        assert_eq!(bus.subscriber_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn lagged() {
+        let bus = EventBus::new(2);
+        let mut stream = bus.subscribe();
+        for i in 0..4 {
+            bus.publish(i);
+        }
+        assert_eq!(stream.next().await, Some(2));
+        assert_eq!(stream.next().await, Some(3));
+
+        drop(bus);
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[cfg(feature = "sse")]
+    #[tokio::test]
+    async fn sse() {
+        use crate::{web::sse::Event, IntoResponse};
+
+        let bus = EventBus::new(8);
+        let resp = bus.sse(Event::message).into_response();
+        bus.publish("a");
+        bus.publish("b");
+        drop(bus);
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "data: a\n\ndata: b\n\n"
+        );
+    }
+}
@@ -1,14 +1,12 @@
 use std::ops::{Deref, DerefMut};
 
+use mime::Mime;
 use serde::de::DeserializeOwned;
 
 use crate::{
     error::ParseFormError,
-    http::{
-        header::{self, HeaderValue},
-        Method,
-    },
-    web::RequestBody,
+    http::Method,
+    web::{charset::decode_form, RequestBody},
     FromRequest, Request, Result,
 };
 
@@ -92,21 +90,18 @@ impl<'a, T: DeserializeOwned> FromRequest<'a> for Form<T> {
         if req.method() == Method::GET {
             Ok(serde_urlencoded::from_str(req.uri().query().unwrap_or_default()).map(Self)?)
         } else {
-            let content_type = req.headers().get(header::CONTENT_TYPE);
-            if content_type
-                != Some(&HeaderValue::from_static(
-                    "application/x-www-form-urlencoded",
-                ))
+            let content_type = req
+                .content_type()
+                .ok_or(ParseFormError::ContentTypeRequired)?;
+            if !matches!(content_type.parse::<Mime>(), Ok(mime) if mime.essence_str() == mime::APPLICATION_WWW_FORM_URLENCODED.essence_str())
             {
-                return match content_type.and_then(|value| value.to_str().ok()) {
-                    Some(ty) => Err(ParseFormError::InvalidContentType(ty.to_string())),
-                    None => Err(ParseFormError::ContentTypeRequired),
-                };
+                return Err(ParseFormError::InvalidContentType(content_type.to_string()));
             }
 
-            Ok(Self(serde_urlencoded::from_bytes(
-                &body.take()?.into_bytes().await?,
-            )?))
+            let data = body.take()?.into_bytes().await?;
+            Ok(Self(serde_urlencoded::from_bytes(&decode_form(
+                req, &data,
+            )?)?))
         }
     }
 }
@@ -118,7 +113,7 @@ mod tests {
     use super::*;
     use crate::{
         handler,
-        http::{StatusCode, Uri},
+        http::{header, StatusCode, Uri},
         Endpoint,
     };
 
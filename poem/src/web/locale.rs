@@ -0,0 +1,408 @@
+use std::convert::Infallible;
+#[cfg(feature = "i18n")]
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "i18n")]
+use fluent::{concurrent::FluentBundle, FluentArgs, FluentError, FluentResource};
+
+#[cfg(feature = "i18n")]
+use crate::error::I18NError;
+use crate::{error::GetDataError, http::header, FromRequest, Request, RequestBody, Result};
+
+/// The languages accepted by the client, parsed from the `Accept-Language`
+/// header and ranked by the quality values, the most preferred first.
+///
+/// The languages with a quality value of `0` are not acceptable and are
+/// excluded. It is empty if the header is missing.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::AcceptLanguage};
+///
+/// #[handler]
+/// fn index(accept_language: AcceptLanguage) -> String {
+///     accept_language.0.join(",")
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AcceptLanguage(pub Vec<String>);
+
+impl AcceptLanguage {
+    fn parse(value: &str) -> Self {
+        let mut languages = value
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let language = parts.next()?.trim();
+                if language.is_empty() {
+                    return None;
+                }
+                let mut quality = 1.0;
+                for param in parts {
+                    if let Some(value) = param.trim().strip_prefix("q=") {
+                        quality = value.trim().parse::<f32>().ok()?;
+                    }
+                }
+                (quality > 0.0).then(|| (language.to_string(), quality))
+            })
+            .collect::<Vec<_>>();
+        // the sort is stable, so the languages with the same quality keep the
+        // order in the header
+        languages.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        Self(
+            languages
+                .into_iter()
+                .map(|(language, _)| language)
+                .collect(),
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for AcceptLanguage {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req
+            .headers()
+            .get_all(header::ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(AcceptLanguage::parse)
+            .fold(AcceptLanguage::default(), |mut a, b| {
+                a.0.extend(b.0);
+                a
+            }))
+    }
+}
+
+/// The locales supported by the application, which are negotiated with the
+/// `Accept-Language` header by the [`Locale`] extractor.
+///
+/// The first locale is the default locale, which is used if none of the
+/// locales is accepted by the client. Add it to the application with
+/// [`EndpointExt::data`](crate::EndpointExt::data).
+///
+/// With the `i18n` feature, the messages in the
+/// [Fluent](https://projectfluent.org/) format can be loaded with
+/// [`Locales::add_ftl`].
+#[derive(Clone)]
+pub struct Locales {
+    locales: Vec<String>,
+    #[cfg(feature = "i18n")]
+    resources: HashMap<String, Vec<Arc<FluentResource>>>,
+    #[cfg(feature = "i18n")]
+    bundles: HashMap<String, Arc<FluentBundle<Arc<FluentResource>>>>,
+}
+
+impl Locales {
+    /// Create the supported locales, the first one is the default locale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `locales` is empty.
+    pub fn new(locales: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let locales = locales.into_iter().map(Into::into).collect::<Vec<_>>();
+        assert!(!locales.is_empty(), "at least one locale is required");
+        Self {
+            locales,
+            #[cfg(feature = "i18n")]
+            resources: HashMap::new(),
+            #[cfg(feature = "i18n")]
+            bundles: HashMap::new(),
+        }
+    }
+
+    /// Loads the messages of `locale` from a Fluent (`.ftl`) resource, the
+    /// locale is added to the supported locales if it is not in the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::web::Locales;
+    ///
+    /// let locales = Locales::new(["en-US", "zh-CN"])
+    ///     .add_ftl("en-US", "hello = Hello, { $name }!")
+    ///     .unwrap()
+    ///     .add_ftl("zh-CN", "hello = 你好，{ $name }！")
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "i18n")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+    pub fn add_ftl(
+        mut self,
+        locale: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Result<Self, I18NError> {
+        let locale = locale.into();
+        let language_id = locale
+            .parse()
+            .map_err(|_| I18NError::InvalidLocale(locale.clone()))?;
+        let resource = FluentResource::try_new(source.into()).map_err(|(_, errors)| {
+            I18NError::Fluent(errors.into_iter().map(FluentError::ParserError).collect())
+        })?;
+
+        // the bundles cannot be cloned, so the bundle of the locale is rebuilt
+        // with all of its resources
+        let mut resources = self.resources.get(&locale).cloned().unwrap_or_default();
+        resources.push(Arc::new(resource));
+        let mut bundle = FluentBundle::new_concurrent(vec![language_id]);
+        // the Unicode isolation marks are not wanted in the responses
+        bundle.set_use_isolating(false);
+        for resource in &resources {
+            bundle
+                .add_resource(resource.clone())
+                .map_err(I18NError::Fluent)?;
+        }
+
+        if !self.locales.contains(&locale) {
+            self.locales.push(locale.clone());
+        }
+        self.resources.insert(locale.clone(), resources);
+        self.bundles.insert(locale, Arc::new(bundle));
+        Ok(self)
+    }
+
+    /// Returns the supported locales, the first one is the default locale.
+    #[inline]
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    /// Returns the default locale.
+    #[inline]
+    pub fn default_locale(&self) -> &str {
+        &self.locales[0]
+    }
+
+    /// Returns the supported locale that best matches the languages accepted
+    /// by the client, or the default locale if none of them matches.
+    ///
+    /// A language matches a locale with the same tag, or else a locale with
+    /// the same primary language, e.g. `en-GB` matches `en-US`. The tags are
+    /// compared case-insensitively.
+    pub fn negotiate(&self, accept_language: &AcceptLanguage) -> &str {
+        for language in &accept_language.0 {
+            if language == "*" {
+                break;
+            }
+            if let Some(locale) = self
+                .locales
+                .iter()
+                .find(|locale| locale.eq_ignore_ascii_case(language))
+            {
+                return locale;
+            }
+            if let Some(locale) = self.locales.iter().find(|locale| {
+                primary_language(locale).eq_ignore_ascii_case(primary_language(language))
+            }) {
+                return locale;
+            }
+        }
+        self.default_locale()
+    }
+
+    #[cfg(feature = "i18n")]
+    fn text(&self, locale: &str, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        std::iter::once(locale)
+            .chain(std::iter::once(self.default_locale()))
+            .filter_map(|locale| self.bundles.get(locale))
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(
+                    bundle
+                        .format_pattern(pattern, args, &mut errors)
+                        .into_owned(),
+                )
+            })
+    }
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or(tag)
+}
+
+/// An extractor that negotiates the locale of the request with the
+/// `Accept-Language` header, against the [`Locales`] added to the
+/// application.
+///
+/// # Errors
+///
+/// - [`GetDataError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     http::header,
+///     web::{Locale, Locales},
+///     Endpoint, EndpointExt, Request, Route,
+/// };
+///
+/// #[handler]
+/// fn index(locale: Locale<'_>) -> String {
+///     locale.name().to_string()
+/// }
+///
+/// let app = Route::new()
+///     .at("/", get(index))
+///     .data(Locales::new(["en-US", "zh-CN"]));
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = app
+///     .call(
+///         Request::builder()
+///             .header(header::ACCEPT_LANGUAGE, "zh;q=0.9, fr")
+///             .finish(),
+///     )
+///     .await;
+/// assert_eq!(resp.into_body().into_string().await.unwrap(), "zh-CN");
+/// # });
+/// ```
+pub struct Locale<'a> {
+    name: &'a str,
+    #[cfg(feature = "i18n")]
+    locales: &'a Locales,
+}
+
+impl<'a> Locale<'a> {
+    /// Returns the name of the negotiated locale.
+    #[inline]
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the message `id` translated to the locale, falls back to the
+    /// default locale if the message is missing. Returns `None` if the message
+    /// is missing in both locales.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     handler,
+    ///     web::{fluent::fluent_args, Locale},
+    /// };
+    ///
+    /// #[handler]
+    /// fn index(locale: Locale<'_>) -> String {
+    ///     locale
+    ///         .text_with_args("hello", &fluent_args!["name" => "poem"])
+    ///         .unwrap_or_default()
+    /// }
+    /// ```
+    #[cfg(feature = "i18n")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+    pub fn text(&self, id: &str) -> Option<String> {
+        self.locales.text(self.name, id, None)
+    }
+
+    /// Returns the message `id` translated to the locale with the arguments,
+    /// see [`Locale::text`].
+    #[cfg(feature = "i18n")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+    pub fn text_with_args(&self, id: &str, args: &FluentArgs) -> Option<String> {
+        self.locales.text(self.name, id, Some(args))
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for Locale<'a> {
+    type Error = GetDataError;
+
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self, Self::Error> {
+        let locales = req
+            .extensions()
+            .get::<Locales>()
+            .ok_or_else(|| GetDataError(std::any::type_name::<Locales>()))?;
+        let accept_language = AcceptLanguage::from_request(req, body).await.unwrap();
+        Ok(Locale {
+            name: locales.negotiate(&accept_language),
+            #[cfg(feature = "i18n")]
+            locales,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accept_language() {
+        assert_eq!(
+            AcceptLanguage::parse("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5").0,
+            vec!["fr-CH", "fr", "en", "de", "*"]
+        );
+        assert_eq!(
+            AcceptLanguage::parse("en;q=0.5, zh-CN, ja;q=0, de;q=0.5").0,
+            vec!["zh-CN", "en", "de"]
+        );
+        assert_eq!(AcceptLanguage::parse("en;q=abc, , de").0, vec!["de"]);
+    }
+
+    #[tokio::test]
+    async fn negotiate_locale() {
+        let locales = Locales::new(["en-US", "zh-CN", "fr"]);
+        let negotiate = |value: &str| locales.negotiate(&AcceptLanguage::parse(value)).to_string();
+
+        assert_eq!(negotiate(""), "en-US");
+        assert_eq!(negotiate("zh-cn"), "zh-CN");
+        assert_eq!(negotiate("de, fr-CH;q=0.8"), "fr");
+        assert_eq!(negotiate("en-GB, zh-CN;q=0.9"), "en-US");
+        assert_eq!(negotiate("de, *;q=0.5, zh;q=0.1"), "en-US");
+
+        let mut req = Request::builder()
+            .header(header::ACCEPT_LANGUAGE, "ja, zh;q=0.9")
+            .finish();
+        assert!(Locale::from_request(&req, &mut Default::default())
+            .await
+            .is_err());
+
+        req.extensions_mut().insert(locales);
+        let locale = Locale::from_request(&req, &mut Default::default())
+            .await
+            .unwrap();
+        assert_eq!(locale.name(), "zh-CN");
+    }
+
+    #[cfg(feature = "i18n")]
+    #[tokio::test]
+    async fn translate() {
+        use fluent::fluent_args;
+
+        let locales = Locales::new(["en-US"])
+            .add_ftl("en-US", "hello = Hello, { $name }!\nbye = Bye!")
+            .unwrap()
+            .add_ftl("zh-CN", "hello = 你好，{ $name }！")
+            .unwrap();
+        assert_eq!(locales.locales(), &["en-US", "zh-CN"]);
+        assert!(matches!(
+            Locales::new(["en-US"]).add_ftl("en-US", "hello = {"),
+            Err(I18NError::Fluent(_))
+        ));
+        assert!(matches!(
+            Locales::new(["en-US"]).add_ftl("!", "hello = Hello"),
+            Err(I18NError::InvalidLocale(_))
+        ));
+
+        let mut req = Request::builder()
+            .header(header::ACCEPT_LANGUAGE, "zh-CN")
+            .finish();
+        req.extensions_mut().insert(locales);
+        let locale = Locale::from_request(&req, &mut Default::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            locale
+                .text_with_args("hello", &fluent_args!["name" => "poem"])
+                .as_deref(),
+            Some("你好，poem！")
+        );
+        // falls back to the default locale
+        assert_eq!(locale.text("bye").as_deref(), Some("Bye!"));
+        assert_eq!(locale.text("missing"), None);
+    }
+}
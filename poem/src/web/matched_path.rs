@@ -0,0 +1,45 @@
+use std::{convert::Infallible, ops::Deref, sync::Arc};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The path pattern of the route that matches the request, such as
+/// `/users/:id`.
+///
+/// It is set by [`Route`](crate::Route), the patterns of the nested routes are
+/// joined with the prefixes. It is also added to the extensions of the
+/// response, so the middlewares applied outside the routes can read it.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::MatchedPath, Route};
+///
+/// #[handler]
+/// fn index(path: &MatchedPath) -> String {
+///     path.to_string()
+/// }
+///
+/// let app = Route::new().at("/users/:id", index);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchedPath(pub Arc<str>);
+
+impl Deref for MatchedPath {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a MatchedPath {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req
+            .extensions()
+            .get::<MatchedPath>()
+            .expect("To use the `MatchedPath` extractor, the endpoint must be added to a `Route`."))
+    }
+}
@@ -1,26 +1,43 @@
 //! Commonly used as the type of extractor or response.
 
 mod addr;
+mod charset;
+mod client_cert;
 #[cfg(feature = "compression")]
 mod compress;
+mod connection_info;
 #[cfg(feature = "cookie")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 pub mod cookie;
+#[cfg(feature = "csrf")]
+mod csrf;
 mod data;
+mod event_bus;
 mod form;
 mod json;
+mod locale;
+mod matched_path;
 #[cfg(feature = "multipart")]
 mod multipart;
 mod path;
 mod query;
+mod real_ip;
 mod redirect;
+mod request_id;
 #[cfg(feature = "sse")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
 pub mod sse;
+mod task_group;
 #[cfg(feature = "tempfile")]
 mod tempfile;
 #[cfg(feature = "template")]
 mod template;
+#[cfg(feature = "tera")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub mod tera;
+#[cfg(feature = "i18n")]
+#[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+pub use fluent;
 #[doc(inline)]
 pub use headers;
 mod typed_header;
@@ -32,16 +49,26 @@ use std::convert::Infallible;
 
 pub use addr::{LocalAddr, RemoteAddr};
 use bytes::Bytes;
+pub use client_cert::ClientCert;
 #[cfg(feature = "compression")]
 pub use compress::{Compress, CompressionAlgo};
+pub use connection_info::{ConnectionInfo, PeerCred};
+#[cfg(feature = "csrf")]
+pub use csrf::{CsrfToken, CsrfVerifier};
 pub use data::Data;
+pub use event_bus::{EventBus, EventStream};
 pub use form::Form;
 pub use json::Json;
+pub use locale::{AcceptLanguage, Locale, Locales};
+pub use matched_path::MatchedPath;
 #[cfg(feature = "multipart")]
 pub use multipart::{Field, Multipart};
 pub use path::Path;
 pub use query::Query;
+pub use real_ip::RealIp;
 pub use redirect::Redirect;
+pub use request_id::RequestIdValue;
+pub use task_group::TaskGroup;
 #[cfg(feature = "template")]
 pub use template::{HtmlTemplate, Template};
 pub use typed_header::TypedHeader;
@@ -105,10 +132,37 @@ impl RequestBody {
 ///
 ///    Extracts the remote peer's address [`RemoteAddr`] from request.
 ///
+/// - **RealIp**
+///
+///    Extracts the client IP address [`RealIp`] from request.
+///
 /// - **LocalAddr**
 ///
 ///    Extracts the local server's address [`LocalAddr`] from request.
 ///
+/// - **&ClientCert**
+///
+///    Extracts the client certificate chain [`ClientCert`] from request.
+///
+/// - **&ConnectionInfo**
+///
+///    Extracts the information of the connection [`ConnectionInfo`], such as
+/// the TLS server name, from request.
+///
+/// - **AcceptLanguage**
+///
+///    Extracts the languages accepted by the client [`AcceptLanguage`] from
+/// request.
+///
+/// - **Locale**
+///
+///    Negotiates the [`Locale`] of the request against the supported
+/// [`Locales`].
+///
+/// - **TaskGroup**
+///
+///    Creates a [`TaskGroup`] to spawn the subtasks scoped to the request.
+///
 /// - **Method**
 ///
 ///    Extracts the [`Method`] from the incoming request.
@@ -179,6 +233,29 @@ impl RequestBody {
 ///
 ///    _Requires `CookieSession` or `RedisSession` middleware._
 ///
+/// - **&MatchedPath**
+///
+///    Extracts the path pattern [`MatchedPath`] of the route that matches
+///    the request.
+///
+/// - **&CsrfToken**
+///
+///    Extracts the [`CsrfToken`] from the incoming request.
+///
+///    _Requires `Csrf` middleware._
+///
+/// - **&CsrfVerifier**
+///
+///    Extracts the [`CsrfVerifier`] from the incoming request.
+///
+///    _Requires `Csrf` middleware._
+///
+/// - **&RequestIdValue**
+///
+///    Extracts the [`RequestIdValue`] from the incoming request.
+///
+///    _Requires `RequestId` middleware._
+///
 /// - **Body**
 ///
 ///    Extracts the [`Body`] from the incoming request.
@@ -622,6 +699,49 @@ impl<T: IntoResponse> IntoResponse for (HeaderMap, T) {
     }
 }
 
+/// The headers that cannot be converted into `HeaderName` and `HeaderValue`
+/// are ignored, like [`IntoResponse::with_header`].
+impl<K, V, T, const N: usize> IntoResponse for ([(K, V); N], T)
+where
+    K: TryInto<HeaderName> + Send,
+    V: TryInto<HeaderValue> + Send,
+    T: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut resp = self.1.into_response();
+        append_headers(resp.headers_mut(), self.0);
+        resp
+    }
+}
+
+/// The headers that cannot be converted into `HeaderName` and `HeaderValue`
+/// are ignored, like [`IntoResponse::with_header`].
+impl<K, V, T, const N: usize> IntoResponse for (StatusCode, [(K, V); N], T)
+where
+    K: TryInto<HeaderName> + Send,
+    V: TryInto<HeaderValue> + Send,
+    T: IntoResponse,
+{
+    fn into_response(self) -> Response {
+        let mut resp = self.2.into_response();
+        resp.set_status(self.0);
+        append_headers(resp.headers_mut(), self.1);
+        resp
+    }
+}
+
+fn append_headers<K, V, const N: usize>(headers: &mut HeaderMap, items: [(K, V); N])
+where
+    K: TryInto<HeaderName>,
+    V: TryInto<HeaderValue>,
+{
+    for (key, value) in items {
+        if let (Ok(key), Ok(value)) = (key.try_into(), value.try_into()) {
+            headers.append(key, value);
+        }
+    }
+}
+
 impl<T, E> IntoResponse for std::result::Result<T, E>
 where
     T: IntoResponse,
@@ -705,9 +825,9 @@ impl<'a> FromRequest<'a> for Body {
 impl<'a> FromRequest<'a> for String {
     type Error = ReadBodyError;
 
-    async fn from_request(_req: &'a Request, body: &mut RequestBody) -> Result<Self, Self::Error> {
-        let data = body.take()?.into_bytes().await?;
-        String::from_utf8(data.to_vec()).map_err(ReadBodyError::Utf8)
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self, Self::Error> {
+        let data = body.take()?.into_vec().await?;
+        charset::decode_body(req, data)
     }
 }
 
@@ -738,6 +858,15 @@ impl<'a> FromRequest<'a> for &'a RemoteAddr {
     }
 }
 
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a LocalAddr {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(&req.state().local_addr)
+    }
+}
+
 #[async_trait::async_trait]
 impl<'a, T: FromRequest<'a>> FromRequest<'a> for Option<T> {
     type Error = T::Error;
@@ -836,6 +965,34 @@ mod tests {
         );
         assert_eq!(resp.into_body().into_string().await.unwrap(), "abc");
 
+        // ([(K, V); N], T)
+        let resp = ([("Value1", "123"), ("Value2", "456")], "abc").into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("Value1"),
+            Some(&HeaderValue::from_static("123"))
+        );
+        assert_eq!(
+            resp.headers().get("Value2"),
+            Some(&HeaderValue::from_static("456"))
+        );
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "abc");
+
+        // (StatusCode, [(K, V); N], T)
+        let resp = (
+            StatusCode::CREATED,
+            [("Location", "/users/1"), ("Invalid\n", "abc")],
+            "abc",
+        )
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(
+            resp.headers().get("Location"),
+            Some(&HeaderValue::from_static("/users/1"))
+        );
+        assert_eq!(resp.headers().len(), 2);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "abc");
+
         // Result<T, E>
         let resp = Ok::<_, Error>("abc").into_response();
         assert_eq!(resp.status(), StatusCode::OK);
@@ -933,6 +1090,7 @@ mod tests {
                 .uri(Uri::from_static("http://example.com/a/b"))
                 .body("abc");
             req.state_mut().remote_addr = RemoteAddr(Addr::custom("test", "example"));
+            req.state_mut().local_addr = LocalAddr(Addr::custom("test", "server"));
             req
         }
 
@@ -968,6 +1126,12 @@ mod tests {
             &RemoteAddr(Addr::custom("test", "example"))
         );
 
+        // &LocalAddr
+        assert_eq!(
+            <&LocalAddr>::from_request(&req, &mut body).await.unwrap(),
+            &LocalAddr(Addr::custom("test", "server"))
+        );
+
         // &Method
         assert_eq!(
             <Method>::from_request(&req, &mut body).await.unwrap(),
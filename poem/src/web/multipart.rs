@@ -11,29 +11,49 @@ use tokio::io::{AsyncRead, AsyncReadExt};
 #[cfg(feature = "tempfile")]
 use tokio::io::{AsyncSeekExt, SeekFrom};
 
-use crate::{error::ParseMultipartError, http::header, FromRequest, Request, RequestBody, Result};
+use crate::{
+    error::{multer_io_error, FieldSizeExceeded, ParseMultipartError},
+    http::header,
+    FromRequest, Request, RequestBody, Result,
+};
 
 /// A single field in a multipart stream.
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
-pub struct Field(multer::Field<'static>);
+pub struct Field {
+    inner: multer::Field<'static>,
+    size_limit: Option<usize>,
+}
 
 impl Field {
     /// Get the content type of the field.
     #[inline]
     pub fn content_type(&self) -> Option<&str> {
-        self.0.content_type().map(|mime| mime.essence_str())
+        self.inner.content_type().map(|mime| mime.essence_str())
     }
 
     /// The file name found in the `Content-Disposition` header.
     #[inline]
     pub fn file_name(&self) -> Option<&str> {
-        self.0.file_name()
+        self.inner.file_name()
     }
 
     /// The name found in the `Content-Disposition` header.
     #[inline]
     pub fn name(&self) -> Option<&str> {
-        self.0.name()
+        self.inner.name()
+    }
+
+    /// Limits the size of the data of the field, reading more than `size`
+    /// bytes fails with an IO error that wraps [`FieldSizeExceeded`].
+    ///
+    /// The data is checked as it is received, so it is never buffered beyond
+    /// the limit.
+    #[must_use]
+    pub fn size_limit(self, size: usize) -> Self {
+        Self {
+            size_limit: Some(size),
+            ..self
+        }
     }
 
     /// Get the full data of the field as bytes.
@@ -72,9 +92,26 @@ impl Field {
 
     /// Consume this field to return a reader.
     pub fn into_async_read(self) -> impl AsyncRead + Send {
+        let size_limit = self.size_limit;
+        let mut size = 0;
         tokio_util::io::StreamReader::new(
-            self.0
-                .map_err(|err| std::io::Error::new(ErrorKind::Other, err.to_string())),
+            self.inner
+                .map_err(|err| {
+                    // keeps the kind of the IO error, such as `ConnectionReset`
+                    let kind = multer_io_error(&err)
+                        .map(IoError::kind)
+                        .unwrap_or(ErrorKind::Other);
+                    IoError::new(kind, err.to_string())
+                })
+                .and_then(move |chunk| {
+                    size += chunk.len();
+                    futures_util::future::ready(match size_limit {
+                        Some(limit) if size > limit => {
+                            Err(IoError::new(ErrorKind::Other, FieldSizeExceeded { limit }))
+                        }
+                        _ => Ok(chunk),
+                    })
+                }),
         )
     }
 }
@@ -136,7 +173,10 @@ impl Multipart {
     /// Yields the next [`Field`] if available.
     pub async fn next_field(&mut self) -> Result<Option<Field>, ParseMultipartError> {
         match self.inner.next_field().await? {
-            Some(field) => Ok(Some(Field(field))),
+            Some(field) => Ok(Some(Field {
+                inner: field,
+                size_limit: None,
+            })),
             None => Ok(None),
         }
     }
@@ -196,4 +236,31 @@ mod tests {
             .await;
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn field_size_limit() {
+        #[handler(internal)]
+        async fn index(mut multipart: Multipart) {
+            let field = multipart.next_field().await.unwrap().unwrap();
+            assert_eq!(field.size_limit(4).text().await.unwrap(), "abcd");
+
+            let field = multipart.next_field().await.unwrap().unwrap();
+            let err = field.size_limit(4).bytes().await.unwrap_err();
+            assert_eq!(
+                err.get_ref()
+                    .and_then(|err| err.downcast_ref::<FieldSizeExceeded>()),
+                Some(&FieldSizeExceeded { limit: 4 })
+            );
+        }
+
+        let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nabcde\r\n--X-BOUNDARY--\r\n";
+        let resp = index
+            .call(
+                Request::builder()
+                    .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+                    .body(data),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
@@ -4,7 +4,7 @@ use std::ops::{Deref, DerefMut};
 
 use serde::de::DeserializeOwned;
 
-use crate::{error::ErrorInvalidPathParams, FromRequest, Request, RequestBody, Result};
+use crate::{error::ParsePathError, FromRequest, Request, RequestBody, Result};
 
 /// An extractor that will get captures from the URL and parse them using
 /// `serde`.
@@ -122,11 +122,58 @@ impl<T> DerefMut for Path<T> {
 
 #[async_trait::async_trait]
 impl<'a, T: DeserializeOwned> FromRequest<'a> for Path<T> {
-    type Error = ErrorInvalidPathParams;
+    type Error = ParsePathError;
 
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
         T::deserialize(de::PathDeserializer::new(&req.state().match_params))
-            .map_err(|_| ErrorInvalidPathParams)
+            .map_err(|err| ParsePathError(err.0))
             .map(Path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{handler, http::Uri, Endpoint, Route};
+
+    #[tokio::test]
+    async fn invalid_path_params() {
+        #[derive(Deserialize)]
+        struct Params {
+            #[allow(dead_code)]
+            id: i32,
+        }
+
+        #[handler(internal)]
+        async fn index(_params: Path<Params>) {}
+
+        let app = Route::new().at("/users/:id", index);
+
+        let resp = app
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/users/1"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let mut resp = app
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/users/abc"))
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(resp
+            .take_body()
+            .into_string()
+            .await
+            .unwrap()
+            .starts_with("invalid path params:"));
+    }
+}
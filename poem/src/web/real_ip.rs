@@ -0,0 +1,71 @@
+use std::{convert::Infallible, net::IpAddr};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The IP address of the client.
+///
+/// It is the address derived from the `Forwarded` or `X-Forwarded-For` headers
+/// by the [`ForwardedHeaders`](crate::middleware::ForwardedHeaders)
+/// middleware, or the IP address of the remote peer if the middleware is not
+/// used. It is `None` if the remote peer is not connected through the
+/// internet socket, e.g. Unix domain socket.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::RealIp};
+///
+/// #[handler]
+/// fn index(RealIp(ip): RealIp) -> String {
+///     format!("{:?}", ip)
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RealIp(pub Option<IpAddr>);
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for RealIp {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(match req.extensions().get::<RealIp>() {
+            Some(real_ip) => *real_ip,
+            None => RealIp(req.remote_addr().as_socket_addr().map(|addr| addr.ip())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{web::RemoteAddr, Addr};
+
+    #[tokio::test]
+    async fn real_ip() {
+        let mut req = Request::default();
+        assert_eq!(
+            RealIp::from_request(&req, &mut Default::default())
+                .await
+                .unwrap(),
+            RealIp(None)
+        );
+
+        req.state_mut().remote_addr =
+            RemoteAddr(Addr::SocketAddr("10.0.0.1:1234".parse().unwrap()));
+        assert_eq!(
+            RealIp::from_request(&req, &mut Default::default())
+                .await
+                .unwrap(),
+            RealIp(Some("10.0.0.1".parse().unwrap()))
+        );
+
+        req.extensions_mut()
+            .insert(RealIp(Some("1.2.3.4".parse().unwrap())));
+        assert_eq!(
+            RealIp::from_request(&req, &mut Default::default())
+                .await
+                .unwrap(),
+            RealIp(Some("1.2.3.4".parse().unwrap()))
+        );
+    }
+}
@@ -53,6 +53,15 @@ impl Redirect {
         }
     }
 
+    /// A simple `302` redirect to a different location, the method of the
+    /// request may be changed to `GET`.
+    pub fn found(uri: Uri) -> Self {
+        Self {
+            status: StatusCode::FOUND,
+            uri,
+        }
+    }
+
     /// A simple `303` redirect to a different location.
     pub fn see_other(uri: Uri) -> Self {
         Self {
@@ -100,6 +109,7 @@ mod tests {
 
     test_redirect!(permanent, PERMANENT_REDIRECT);
     test_redirect!(moved_permanent, MOVED_PERMANENTLY);
+    test_redirect!(found, FOUND);
     test_redirect!(see_other, SEE_OTHER);
     test_redirect!(temporary, TEMPORARY_REDIRECT);
 }
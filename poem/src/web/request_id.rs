@@ -0,0 +1,41 @@
+use std::{
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The ID of the current request, it is read from the request header or
+/// generated if the header is missing.
+///
+/// _Requires the [`RequestId`](crate::middleware::RequestId) middleware._
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::RequestIdValue};
+///
+/// #[handler]
+/// fn index(id: &RequestIdValue) -> String {
+///     format!("request: {}", id)
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RequestIdValue(pub String);
+
+impl Display for RequestIdValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a RequestIdValue {
+    type Error = Infallible;
+
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(req.extensions().get::<RequestIdValue>().expect(
+            "To use the `RequestIdValue` extractor, the `RequestId` middleware is required.",
+        ))
+    }
+}
@@ -0,0 +1,248 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{Event, SSE};
+
+struct State {
+    client_buffer: usize,
+    history_size: usize,
+    clients: Vec<mpsc::Sender<Event>>,
+    history: VecDeque<(String, Event)>,
+    next_id: u64,
+}
+
+/// Sends the events to many connected SSE clients.
+///
+/// Each client has a buffer of pending events, a client that falls behind
+/// and fills up its buffer is disconnected, so that it does not slow down the
+/// other clients. The latest events are kept in a ring buffer, and they are
+/// replayed to the reconnecting client according to the `Last-Event-ID`
+/// header.
+///
+/// The message events without an id are assigned an increasing numeric id.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     middleware::AddData,
+///     web::{
+///         sse::{Broadcaster, Event, SSE},
+///         Data,
+///     },
+///     EndpointExt, Request, Route,
+/// };
+///
+/// #[handler]
+/// fn events(req: &Request, broadcaster: Data<&Broadcaster>) -> SSE {
+///     broadcaster.subscribe(req.headers().get("Last-Event-ID").and_then(|value| value.to_str().ok()))
+/// }
+///
+/// #[handler]
+/// fn publish(broadcaster: Data<&Broadcaster>, body: String) {
+///     broadcaster.send(Event::message(body));
+/// }
+///
+/// let app = Route::new()
+///     .at("/events", get(events).post(publish))
+///     .with(AddData::new(Broadcaster::new().history(100)));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+#[derive(Clone)]
+pub struct Broadcaster {
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for Broadcaster {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                client_buffer: 64,
+                history_size: 0,
+                clients: Vec::new(),
+                history: VecDeque::new(),
+                next_id: 1,
+            })),
+        }
+    }
+}
+
+impl Broadcaster {
+    /// Create a `Broadcaster`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of pending events for each client, default is
+    /// `64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `size` is `0`.
+    #[must_use]
+    pub fn client_buffer(self, size: usize) -> Self {
+        assert!(size > 0, "the client buffer size must be greater than 0");
+        self.state.lock().client_buffer = size;
+        self
+    }
+
+    /// Sets the number of the latest events that are kept for replay, default
+    /// is `0`.
+    #[must_use]
+    pub fn history(self, size: usize) -> Self {
+        let mut state = self.state.lock();
+        state.history_size = size;
+        while state.history.len() > size {
+            state.history.pop_front();
+        }
+        drop(state);
+        self
+    }
+
+    /// Returns the number of the connected clients.
+    pub fn client_count(&self) -> usize {
+        let mut state = self.state.lock();
+        state.clients.retain(|client| !client.is_closed());
+        state.clients.len()
+    }
+
+    /// Sends an event to all the connected clients, and returns the number of
+    /// the clients that receive it.
+    pub fn send(&self, event: Event) -> usize {
+        let mut state = self.state.lock();
+
+        let event = match event {
+            Event::Message { id, event, data } if id.is_empty() => {
+                let id = state.next_id.to_string();
+                state.next_id += 1;
+                Event::Message { id, event, data }
+            }
+            event => event,
+        };
+
+        if state.history_size > 0 {
+            if let Event::Message { id, .. } = &event {
+                if state.history.len() == state.history_size {
+                    state.history.pop_front();
+                }
+                state.history.push_back((id.clone(), event.clone()));
+            }
+        }
+
+        state
+            .clients
+            .retain(|client| match client.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    tracing::debug!("disconnect the lagging SSE client");
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            });
+        state.clients.len()
+    }
+
+    /// Subscribes to the events, and returns an SSE response that sends them
+    /// to the client.
+    ///
+    /// If `last_event_id` is specified, the events after it in the history are
+    /// sent first. If it is no longer in the history, the entire history is
+    /// sent.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> SSE {
+        let mut state = self.state.lock();
+        let (tx, rx) = mpsc::channel(state.client_buffer);
+
+        let replay = match last_event_id {
+            Some(last_event_id) => {
+                let skip = state
+                    .history
+                    .iter()
+                    .position(|(id, _)| id == last_event_id)
+                    .map(|pos| pos + 1)
+                    .unwrap_or_default();
+                state
+                    .history
+                    .iter()
+                    .skip(skip)
+                    .map(|(_, event)| event.clone())
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        state.clients.push(tx);
+        drop(state);
+
+        SSE::new(futures_util::stream::iter(replay).chain(ReceiverStream::new(rx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoResponse;
+
+    async fn read_events(sse: SSE) -> String {
+        sse.into_response().into_body().into_string().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        let broadcaster = Broadcaster::new();
+        let a = broadcaster.subscribe(None);
+        let b = broadcaster.subscribe(None);
+        assert_eq!(broadcaster.client_count(), 2);
+
+        assert_eq!(broadcaster.send(Event::message("a")), 2);
+        assert_eq!(broadcaster.send(Event::message("b").id("x")), 2);
+        drop(broadcaster);
+
+        assert_eq!(read_events(a).await, "id: 1\ndata: a\n\nid: x\ndata: b\n\n");
+        assert_eq!(read_events(b).await, "id: 1\ndata: a\n\nid: x\ndata: b\n\n");
+    }
+
+    #[tokio::test]
+    async fn evict_lagging_client() {
+        let broadcaster = Broadcaster::new().client_buffer(2);
+        let client = broadcaster.subscribe(None);
+
+        assert_eq!(broadcaster.send(Event::message("a")), 1);
+        assert_eq!(broadcaster.send(Event::message("b")), 1);
+        assert_eq!(broadcaster.send(Event::message("c")), 0);
+        assert_eq!(broadcaster.client_count(), 0);
+
+        assert_eq!(
+            read_events(client).await,
+            "id: 1\ndata: a\n\nid: 2\ndata: b\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        let broadcaster = Broadcaster::new().history(2);
+        for data in ["a", "b", "c"] {
+            broadcaster.send(Event::message(data));
+        }
+
+        let from_2 = broadcaster.subscribe(Some("2"));
+        let from_unknown = broadcaster.subscribe(Some("1"));
+        let latest = broadcaster.subscribe(None);
+        broadcaster.send(Event::message("d"));
+        drop(broadcaster);
+
+        assert_eq!(
+            read_events(from_2).await,
+            "id: 3\ndata: c\n\nid: 4\ndata: d\n\n"
+        );
+        assert_eq!(
+            read_events(from_unknown).await,
+            "id: 2\ndata: b\n\nid: 3\ndata: c\n\nid: 4\ndata: d\n\n"
+        );
+        assert_eq!(read_events(latest).await, "id: 4\ndata: d\n\n");
+    }
+}
@@ -35,8 +35,10 @@ impl Display for Event {
                 if !event.is_empty() && event != "message" {
                     writeln!(f, "event: {}", &event)?;
                 }
-                for line in data.lines() {
-                    writeln!(f, "data: {}", line)?;
+                // empty lines are kept, so that the empty and multiline data
+                // are received unchanged
+                for line in data.split('\n') {
+                    writeln!(f, "data: {}", line.strip_suffix('\r').unwrap_or(line))?;
                 }
                 writeln!(f)?;
                 Ok(())
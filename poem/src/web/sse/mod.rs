@@ -1,8 +1,10 @@
 //! Server-Sent Events (SSE) types.
 
+mod broadcaster;
 mod event;
 mod response;
 
+pub use broadcaster::Broadcaster;
 pub use event::Event;
 pub use response::SSE;
 
@@ -53,6 +55,22 @@ data: 5
         );
     }
 
+    #[tokio::test]
+    async fn retry() {
+        let sse = SSE::new(futures_util::stream::iter(vec![
+            Event::message(""),
+            Event::message("a\r\nb\n"),
+        ]))
+        .retry(Duration::from_secs(3));
+        let resp = sse.into_response();
+
+        assert_eq!(resp.headers().get("cache-control").unwrap(), "no-cache");
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "retry: 3000\n\ndata: \n\ndata: a\ndata: b\ndata: \n\n"
+        );
+    }
+
     #[tokio::test]
     async fn keep_alive() {
         let sse = SSE::new(futures_util::stream::pending()).keep_alive(Duration::from_secs(1));
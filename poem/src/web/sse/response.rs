@@ -3,7 +3,7 @@ use futures_util::{stream::BoxStream, Stream, StreamExt};
 use tokio::time::Duration;
 
 use super::Event;
-use crate::{Body, IntoResponse, Response};
+use crate::{http::header, Body, IntoResponse, Response};
 
 /// An SSE response.
 ///
@@ -39,6 +39,7 @@ use crate::{Body, IntoResponse, Response};
 pub struct SSE {
     stream: BoxStream<'static, Event>,
     keep_alive: Option<Duration>,
+    retry: Option<Duration>,
 }
 
 impl SSE {
@@ -47,10 +48,12 @@ impl SSE {
         Self {
             stream: stream.boxed(),
             keep_alive: None,
+            retry: None,
         }
     }
 
-    /// Set the keep alive interval.
+    /// Set the keep alive interval, a comment is sent to the client at this
+    /// interval to keep the connection open.
     #[must_use]
     pub fn keep_alive(self, duration: Duration) -> Self {
         Self {
@@ -58,12 +61,25 @@ impl SSE {
             ..self
         }
     }
+
+    /// Set the reconnection time of the client, it is sent before the other
+    /// events.
+    #[must_use]
+    pub fn retry(self, duration: Duration) -> Self {
+        Self {
+            retry: Some(duration),
+            ..self
+        }
+    }
 }
 
 impl IntoResponse for SSE {
     fn into_response(self) -> Response {
-        let mut stream = self
-            .stream
+        let retry = self
+            .retry
+            .map(|duration| Event::retry(duration.as_millis() as u64));
+        let mut stream = futures_util::stream::iter(retry)
+            .chain(self.stream)
             .map(|event| Ok::<_, std::io::Error>(Bytes::from(event.to_string())))
             .boxed();
         if let Some(duration) = self.keep_alive {
@@ -81,6 +97,7 @@ impl IntoResponse for SSE {
 
         Response::builder()
             .content_type("text/event-stream")
+            .header(header::CACHE_CONTROL, "no-cache")
             .body(Body::from_async_read(tokio_util::io::StreamReader::new(
                 stream,
             )))
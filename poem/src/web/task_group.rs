@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+use futures_util::future::{AbortHandle, Abortable};
+use parking_lot::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+#[derive(Default)]
+struct Inner {
+    next_id: AtomicUsize,
+    tasks: Mutex<HashMap<usize, AbortHandle>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        for (_, handle) in self.tasks.get_mut().drain() {
+            handle.abort();
+        }
+    }
+}
+
+/// A group of subtasks scoped to the request.
+///
+/// The subtasks spawned by [`TaskGroup::spawn`] are cancelled when all the
+/// clones of the group are dropped, that is, when the handler completes, or
+/// the client disconnects and the handler is cancelled. If the handler
+/// returns a streaming response, move a clone of the group into the stream,
+/// so the subtasks are cancelled when the stream is dropped.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{handler, web::TaskGroup};
+///
+/// #[handler]
+/// async fn index(group: TaskGroup) -> String {
+///     // this task is cancelled when the request completes
+///     group.spawn(async move {
+///         loop {
+///             tokio::time::sleep(Duration::from_secs(1)).await;
+///             println!("tick");
+///         }
+///     });
+///
+///     let res = group.spawn(async move { 1 + 1 });
+///     format!("{:?}", res.await.unwrap())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct TaskGroup(Arc<Inner>);
+
+impl TaskGroup {
+    /// Create a new `TaskGroup`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a subtask in this group.
+    ///
+    /// The output of the returned [`JoinHandle`] is `None` if the subtask is
+    /// cancelled.
+    pub fn spawn<F>(&self, fut: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (handle, registration) = AbortHandle::new_pair();
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        self.0.tasks.lock().insert(id, handle);
+
+        let group = Arc::downgrade(&self.0);
+        let task = Abortable::new(fut, registration);
+        tokio::spawn(async move {
+            let res = task.await.ok();
+            remove_task(&group, id);
+            res
+        })
+    }
+
+    /// Cancels all the subtasks in this group.
+    pub fn cancel_all(&self) {
+        for (_, handle) in self.0.tasks.lock().drain() {
+            handle.abort();
+        }
+    }
+
+    /// Returns the number of the running subtasks.
+    pub fn len(&self) -> usize {
+        self.0.tasks.lock().len()
+    }
+
+    /// Returns `true` if there are no running subtasks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn remove_task(group: &Weak<Inner>, id: usize) {
+    if let Some(group) = group.upgrade() {
+        group.tasks.lock().remove(&id);
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for TaskGroup {
+    type Error = Infallible;
+
+    async fn from_request(_req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
+        Ok(TaskGroup::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn spawn() {
+        let group = TaskGroup::new();
+        assert_eq!(group.spawn(async { 1 + 1 }).await.unwrap(), Some(2));
+        assert!(group.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_on_drop() {
+        let group = TaskGroup::new();
+        let handle = group.spawn(tokio::time::sleep(Duration::from_secs(60)));
+        assert_eq!(group.len(), 1);
+        drop(group);
+        assert_eq!(handle.await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn cancel_all() {
+        let group = TaskGroup::new();
+        let handle = group.spawn(tokio::time::sleep(Duration::from_secs(60)));
+        group.cancel_all();
+        assert!(group.is_empty());
+        assert_eq!(handle.await.unwrap(), None);
+    }
+}
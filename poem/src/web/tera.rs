@@ -0,0 +1,67 @@
+//! Template rendering with [`tera`](https://crates.io/crates/tera).
+
+pub use libtera::{Context, Tera};
+
+use crate::{http::StatusCode, IntoResponse, Response};
+
+/// A template response that is rendered by the
+/// [`TeraTemplating`](crate::middleware::TeraTemplating) middleware, with
+/// content-type "text/html".
+///
+/// The status code and headers of the response are kept, if the template
+/// cannot be rendered, the response is `500 Internal Server Error`.
+///
+/// The response is also `500 Internal Server Error` if the middleware is not
+/// used, so a missing middleware does not go unnoticed.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     middleware::TeraTemplating,
+///     web::tera::{Context, Tera, TeraTemplate},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn hello() -> TeraTemplate {
+///     let mut context = Context::new();
+///     context.insert("name", "poem");
+///     TeraTemplate::render("hello.html", context)
+/// }
+///
+/// let mut tera = Tera::default();
+/// tera.add_raw_template("hello.html", "<h1>Hello {{ name }}</h1>")
+///     .unwrap();
+///
+/// let app = Route::new()
+///     .at("/", get(hello))
+///     .with(TeraTemplating::from(tera));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub struct TeraTemplate {
+    pub(crate) name: String,
+    pub(crate) context: Context,
+}
+
+impl TeraTemplate {
+    /// Create a response that renders the template `name` with the `context`.
+    pub fn render(name: impl Into<String>, context: Context) -> Self {
+        Self {
+            name: name.into(),
+            context,
+        }
+    }
+}
+
+impl IntoResponse for TeraTemplate {
+    fn into_response(self) -> Response {
+        // the middleware sets the status code to `200 OK` after rendering
+        Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .content_type("text/html")
+            .extension(self)
+            .finish()
+    }
+}
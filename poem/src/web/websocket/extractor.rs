@@ -6,12 +6,23 @@ use super::{utils::sign, WebSocketStream};
 use crate::{
     error::WebSocketError,
     http::{
-        header::{self, HeaderValue},
+        header::{self, HeaderName, HeaderValue},
         Method, StatusCode,
     },
     Body, FromRequest, IntoResponse, OnUpgrade, Request, RequestBody, Response, Result,
 };
 
+/// Returns `true` if the comma-separated header value contains the specified
+/// token. (case-insensitive)
+fn header_contains(req: &Request, name: HeaderName, token: &str) -> bool {
+    req.headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case(token))
+}
+
 /// An extractor that can accept websocket connections.
 pub struct WebSocket {
     key: HeaderValue,
@@ -26,8 +37,8 @@ impl<'a> FromRequest<'a> for WebSocket {
 
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self, Self::Error> {
         if req.method() != Method::GET
-            || req.headers().get(header::CONNECTION) != Some(&HeaderValue::from_static("Upgrade"))
-            || req.headers().get(header::UPGRADE) != Some(&HeaderValue::from_static("websocket"))
+            || !header_contains(req, header::CONNECTION, "upgrade")
+            || !header_contains(req, header::UPGRADE, "websocket")
             || req.headers().get(header::SEC_WEBSOCKET_VERSION)
                 != Some(&HeaderValue::from_static("13"))
         {
@@ -164,3 +175,24 @@ where
         resp
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_header_tokens() {
+        let req = Request::builder()
+            .header(header::CONNECTION, "keep-alive, Upgrade")
+            .header(header::UPGRADE, "WebSocket")
+            .finish();
+        assert!(header_contains(&req, header::CONNECTION, "upgrade"));
+        assert!(header_contains(&req, header::UPGRADE, "websocket"));
+
+        let req = Request::builder()
+            .header(header::CONNECTION, "keep-alive")
+            .finish();
+        assert!(!header_contains(&req, header::CONNECTION, "upgrade"));
+        assert!(!header_contains(&req, header::UPGRADE, "websocket"));
+    }
+}
@@ -106,6 +106,7 @@ impl From<CloseCode> for u16 {
 }
 
 /// An enum representing the various forms of a WebSocket message.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Message {
     /// A text WebSocket message
     Text(String),
@@ -21,12 +21,53 @@
 //!
 //! let app = Route::new().at("/", get(index));
 //! ```
+//!
+//! # Routing
+//!
+//! A websocket endpoint is an ordinary `GET` endpoint, so it can be mounted in
+//! the same [`Route`](crate::Route) tree as the rest of the API, and
+//! middlewares (authentication, tracing, etc.) are applied to the upgrade
+//! request. The extractor returns `400 Bad Request` if the request is not a
+//! valid websocket upgrade request, so a path can also serve plain HTTP
+//! requests by using `Option<WebSocket>`.
+//!
+//! ```
+//! use futures_util::{SinkExt, StreamExt};
+//! use poem::{
+//!     get, handler,
+//!     web::websocket::{Message, WebSocket},
+//!     IntoResponse, Response, Route,
+//! };
+//!
+//! #[handler]
+//! async fn users() -> &'static str {
+//!     "users"
+//! }
+//!
+//! #[handler]
+//! async fn events(ws: Option<WebSocket>) -> Response {
+//!     match ws {
+//!         Some(ws) => ws
+//!             .on_upgrade(|mut socket| async move {
+//!                 let _ = socket.send(Message::Text("hello".to_string())).await;
+//!             })
+//!             .into_response(),
+//!         None => "connect with a websocket client".into_response(),
+//!     }
+//! }
+//!
+//! let app = Route::new()
+//!     .at("/api/users", get(users))
+//!     .at("/api/events", get(events));
+//! ```
 
 mod extractor;
 mod message;
+mod rooms;
 mod stream;
 mod utils;
 
 pub use extractor::WebSocket;
 pub use message::{CloseCode, Message};
+pub use rooms::{RoomMember, Rooms};
 pub use stream::WebSocketStream;
@@ -0,0 +1,353 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::Hash,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use super::Message;
+
+struct Inner<K> {
+    rooms: Mutex<HashMap<K, HashMap<u64, mpsc::Sender<Message>>>>,
+    next_id: AtomicU64,
+    capacity: usize,
+    buffer: usize,
+}
+
+impl<K: Eq + Hash> Inner<K> {
+    fn broadcast<Q>(&self, room: &Q, msg: Message, except: Option<u64>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut rooms = self.rooms.lock();
+        let members = match rooms.get_mut(room) {
+            Some(members) => members,
+            None => return 0,
+        };
+        let mut count = 0;
+        members.retain(|id, sender| {
+            if Some(*id) == except {
+                return true;
+            }
+            match sender.try_send(msg.clone()) {
+                Ok(()) => {
+                    count += 1;
+                    true
+                }
+                Err(TrySendError::Full(_)) => {
+                    tracing::debug!("remove the lagging websocket member from the room");
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            }
+        });
+        if members.is_empty() {
+            rooms.remove(room);
+        }
+        count
+    }
+}
+
+/// Groups the websocket connections into rooms, and broadcasts the messages to
+/// the members of a room.
+///
+/// Each member has a buffer of pending messages, a member that falls behind
+/// and fills up its buffer is removed from the room, and its [`RoomMember`]
+/// stream ends.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::{SinkExt, StreamExt};
+/// use poem::{
+///     get, handler,
+///     middleware::AddData,
+///     web::{
+///         websocket::{Message, Rooms, WebSocket},
+///         Data, Path,
+///     },
+///     EndpointExt, IntoResponse, Route,
+/// };
+///
+/// #[handler]
+/// fn chat(
+///     Path(room): Path<String>,
+///     ws: WebSocket,
+///     rooms: Data<&Rooms<String>>,
+/// ) -> impl IntoResponse {
+///     let rooms = rooms.clone();
+///     ws.on_upgrade(move |socket| async move {
+///         let mut member = match rooms.join(room) {
+///             Some(member) => member,
+///             None => return,
+///         };
+///         let (mut sink, mut stream) = socket.split();
+///
+///         loop {
+///             tokio::select! {
+///                 msg = member.next() => match msg {
+///                     Some(msg) => {
+///                         if sink.send(msg).await.is_err() {
+///                             break;
+///                         }
+///                     }
+///                     None => break,
+///                 },
+///                 msg = stream.next() => match msg {
+///                     Some(Ok(Message::Text(text))) => {
+///                         member.broadcast(Message::text(text));
+///                     }
+///                     Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+///                     _ => {}
+///                 },
+///             }
+///         }
+///     })
+/// }
+///
+/// let app = Route::new()
+///     .at("/chat/:room", get(chat))
+///     .with(AddData::new(Rooms::<String>::new().capacity(100)));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub struct Rooms<K> {
+    inner: Arc<Inner<K>>,
+}
+
+impl<K> Clone for Rooms<K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static> Default for Rooms<K> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                rooms: Default::default(),
+                next_id: AtomicU64::new(0),
+                capacity: usize::MAX,
+                buffer: 32,
+            }),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static> Rooms<K> {
+    /// Create a `Rooms`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of the members in each room, default is
+    /// unlimited.
+    #[must_use]
+    pub fn capacity(self, capacity: usize) -> Self {
+        self.with_config(|inner| inner.capacity = capacity)
+    }
+
+    /// Sets the maximum number of pending messages for each member, default
+    /// is `32`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `size` is `0`.
+    #[must_use]
+    pub fn buffer(self, size: usize) -> Self {
+        assert!(size > 0, "the buffer size must be greater than 0");
+        self.with_config(|inner| inner.buffer = size)
+    }
+
+    fn with_config(self, f: impl FnOnce(&mut Inner<K>)) -> Self {
+        let mut inner = Arc::try_unwrap(self.inner).unwrap_or_else(|_| {
+            panic!("the `Rooms` must be configured before it is cloned");
+        });
+        f(&mut inner);
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Joins a room, returns `None` if the room is full.
+    ///
+    /// The member leaves the room when the returned [`RoomMember`] is dropped.
+    pub fn join(&self, room: K) -> Option<RoomMember<K>> {
+        let mut rooms = self.inner.rooms.lock();
+        let members = rooms.entry(room.clone()).or_default();
+        members.retain(|_, sender| !sender.is_closed());
+        if members.len() >= self.inner.capacity {
+            if members.is_empty() {
+                rooms.remove(&room);
+            }
+            return None;
+        }
+
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(self.inner.buffer);
+        members.insert(id, tx);
+
+        Some(RoomMember {
+            rooms: self.clone(),
+            room,
+            id,
+            receiver: rx,
+        })
+    }
+
+    /// Sends a message to all the members of the room, and returns the number
+    /// of the members that receive it.
+    pub fn broadcast<Q>(&self, room: &Q, msg: Message) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.broadcast(room, msg, None)
+    }
+
+    /// Serializes the value to JSON and sends it as a text message to all the
+    /// members of the room.
+    pub fn broadcast_json<Q, T>(&self, room: &Q, value: &T) -> serde_json::Result<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        T: Serialize,
+    {
+        Ok(self.broadcast(room, Message::text(serde_json::to_string(value)?)))
+    }
+
+    /// Returns the number of the members in the room.
+    pub fn member_count<Q>(&self, room: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner
+            .rooms
+            .lock()
+            .get(room)
+            .map(|members| {
+                members
+                    .values()
+                    .filter(|sender| !sender.is_closed())
+                    .count()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the rooms that have at least one member.
+    pub fn rooms(&self) -> Vec<K> {
+        self.inner.rooms.lock().keys().cloned().collect()
+    }
+}
+
+/// A member of a room, it is a stream of the messages sent to the room.
+///
+/// The member leaves the room when it is dropped.
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub struct RoomMember<K: Eq + Hash> {
+    rooms: Rooms<K>,
+    room: K,
+    id: u64,
+    receiver: mpsc::Receiver<Message>,
+}
+
+impl<K: Eq + Hash> RoomMember<K> {
+    /// Returns the room that this member joined.
+    pub fn room(&self) -> &K {
+        &self.room
+    }
+
+    /// Sends a message to the other members of the room, and returns the
+    /// number of the members that receive it.
+    pub fn broadcast(&self, msg: Message) -> usize {
+        self.rooms.inner.broadcast(&self.room, msg, Some(self.id))
+    }
+
+    /// Serializes the value to JSON and sends it as a text message to the
+    /// other members of the room.
+    pub fn broadcast_json<T: Serialize>(&self, value: &T) -> serde_json::Result<usize> {
+        Ok(self.broadcast(Message::text(serde_json::to_string(value)?)))
+    }
+}
+
+impl<K: Eq + Hash> Unpin for RoomMember<K> {}
+
+impl<K: Eq + Hash> Stream for RoomMember<K> {
+    type Item = Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl<K: Eq + Hash> Drop for RoomMember<K> {
+    fn drop(&mut self) {
+        let mut rooms = self.rooms.inner.rooms.lock();
+        if let Some(members) = rooms.get_mut(&self.room) {
+            members.remove(&self.id);
+            if members.is_empty() {
+                rooms.remove(&self.room);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn join_and_broadcast() {
+        let rooms = Rooms::new();
+        let mut a = rooms.join("a").unwrap();
+        let mut b = rooms.join("a").unwrap();
+        let mut c = rooms.join("b").unwrap();
+        assert_eq!(rooms.member_count("a"), 2);
+
+        assert_eq!(rooms.broadcast("a", Message::text("hello")), 2);
+        assert_eq!(a.next().await, Some(Message::text("hello")));
+        assert_eq!(b.next().await, Some(Message::text("hello")));
+
+        assert_eq!(a.broadcast(Message::text("from a")), 1);
+        assert_eq!(b.next().await, Some(Message::text("from a")));
+
+        assert_eq!(rooms.broadcast_json("b", &[1, 2]).unwrap(), 1);
+        assert_eq!(c.next().await, Some(Message::text("[1,2]")));
+
+        drop(a);
+        assert_eq!(rooms.member_count("a"), 1);
+        drop(b);
+        assert_eq!(rooms.member_count("a"), 0);
+        assert_eq!(rooms.rooms(), vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn capacity_and_lagging() {
+        let rooms = Rooms::new().capacity(1).buffer(1);
+        let mut a = rooms.join("a").unwrap();
+        assert!(rooms.join("a").is_none());
+
+        assert_eq!(rooms.broadcast("a", Message::text("1")), 1);
+        assert_eq!(rooms.broadcast("a", Message::text("2")), 0);
+        assert_eq!(a.next().await, Some(Message::text("1")));
+        assert_eq!(a.next().await, None);
+
+        assert!(rooms.rooms().is_empty());
+        assert!(rooms.join("a").is_some());
+    }
+}